@@ -0,0 +1,66 @@
+//! Gateway fan-out throughput: one event broadcast to 1,000 connected clients.
+//!
+//! Run with `cargo bench -p flux-server --bench broadcast_fanout`.
+
+use flux_server::ws::events::ServerEvent;
+use flux_server::ws::gateway::{client_channel, ClientReceiver, GatewayState, CLIENT_QUEUE_CAPACITY};
+use std::time::{Duration, Instant};
+
+const CLIENTS: usize = 1_000;
+const ROUNDS: usize = 200;
+
+async fn setup() -> (GatewayState, Vec<ClientReceiver>) {
+    let gw = GatewayState::new();
+    let mut receivers = Vec::with_capacity(CLIENTS);
+    for i in 0..CLIENTS {
+        let (tx, rx) = client_channel(CLIENT_QUEUE_CAPACITY);
+        let cid = gw.next_client_id().await;
+        gw.register(cid, format!("u{}", i), format!("user{}", i), tx, "online".into()).await;
+        gw.subscribe_channel(cid, "general").await;
+        receivers.push(rx);
+    }
+    (gw, receivers)
+}
+
+fn drain(receivers: &mut [ClientReceiver]) -> usize {
+    receivers.iter_mut().map(|rx| std::iter::from_fn(|| rx.try_recv().ok()).count()).sum()
+}
+
+fn report(name: &str, elapsed: Duration, delivered: usize) {
+    let per_broadcast = elapsed / ROUNDS as u32;
+    let per_second = delivered as f64 / elapsed.as_secs_f64();
+    println!(
+        "{:<16} {:>6} rounds x {} clients: {:>10.2?}/broadcast, {:>12.0} deliveries/s",
+        name, ROUNDS, CLIENTS, per_broadcast, per_second
+    );
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let (gw, mut receivers) = setup().await;
+
+    // A typical chat edit: a few hundred bytes of JSON
+    let edit = ServerEvent::MessageEdit {
+        message_id: "m".repeat(36),
+        content: "lorem ipsum ".repeat(40),
+        edited_at: "2030-01-01T00:00:00+00:00".into(),
+        metadata: Default::default(),
+    };
+
+    for (name, scope) in [("broadcast_channel", true), ("broadcast_all", false)] {
+        let mut elapsed = Duration::ZERO;
+        let mut delivered = 0;
+        for _ in 0..ROUNDS {
+            let start = Instant::now();
+            if scope {
+                gw.broadcast_channel("general", &edit, None).await;
+            } else {
+                gw.broadcast_all(&edit, None).await;
+            }
+            elapsed += start.elapsed();
+            delivered += drain(&mut receivers);
+        }
+        assert_eq!(delivered, ROUNDS * CLIENTS);
+        report(name, elapsed, delivered);
+    }
+}
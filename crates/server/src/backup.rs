@@ -0,0 +1,339 @@
+use futures::TryStreamExt;
+use object_store::aws::AmazonS3Builder;
+use object_store::buffered::BufWriter;
+use object_store::path::Path as ObjectPath;
+use object_store::ObjectStore;
+use serde::Serialize;
+use sqlx::{Connection, SqlitePool};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::config::Config;
+use crate::AppState;
+
+/// Backups and restores take turns; two at once would race on the files.
+static BACKUP_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub name: String,
+    pub size: u64,
+    pub created_at: String,
+}
+
+/// Whether `name` looks like a file `create_backup` wrote. Anything else
+/// (including path separators) is refused before it touches the filesystem.
+pub fn is_backup_name(name: &str) -> bool {
+    name.starts_with("flux-")
+        && name.ends_with(".db")
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+}
+
+fn s3_store(config: &Config) -> Result<Option<Arc<dyn ObjectStore>>, String> {
+    if config.backup_s3_bucket.is_empty() {
+        return Ok(None);
+    }
+    let store = AmazonS3Builder::from_env()
+        .with_bucket_name(&config.backup_s3_bucket)
+        .build()
+        .map_err(|e| format!("S3 backup store: {}", e))?;
+    Ok(Some(Arc::new(store)))
+}
+
+fn s3_path(config: &Config, name: &str) -> ObjectPath {
+    ObjectPath::from(format!("{}{}", config.backup_s3_prefix, name))
+}
+
+/// Snapshot the database into `backup_dir` (and S3, if configured), then
+/// prune old backups. `label` ends up in the file name, e.g. "pre-restore".
+pub async fn create_backup(db: &SqlitePool, config: &Config, label: &str) -> Result<BackupInfo, String> {
+    let _guard = BACKUP_LOCK.lock().await;
+    create_backup_locked(db, config, label).await
+}
+
+async fn create_backup_locked(db: &SqlitePool, config: &Config, label: &str) -> Result<BackupInfo, String> {
+    tokio::fs::create_dir_all(&config.backup_dir)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", config.backup_dir, e))?;
+
+    let now = chrono::Utc::now();
+    let name = format!("flux-{}-{}.db", now.format("%Y%m%dT%H%M%S%.3fZ"), label);
+    let path = Path::new(&config.backup_dir).join(&name);
+
+    // VACUUM INTO gives a consistent, compacted copy without blocking writers
+    // for longer than the copy takes.
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().as_ref())
+        .execute(db)
+        .await
+        .map_err(|e| format!("Backup failed: {}", e))?;
+
+    // An in-memory database "succeeds" without writing anything
+    let size = tokio::fs::metadata(&path)
+        .await
+        .map(|m| m.len())
+        .map_err(|e| format!("Backup failed: {}: {}", path.display(), e))?;
+
+    if let Some(store) = s3_store(config)? {
+        upload(store, &path, s3_path(config, &name)).await?;
+    }
+
+    prune(config).await;
+    tracing::info!("Database backed up to {} ({} bytes)", path.display(), size);
+    Ok(BackupInfo { name, size, created_at: now.to_rfc3339() })
+}
+
+async fn upload(store: Arc<dyn ObjectStore>, path: &Path, dest: ObjectPath) -> Result<(), String> {
+    let mut file = tokio::fs::File::open(path).await.map_err(|e| e.to_string())?;
+    let mut writer = BufWriter::new(store, dest.clone());
+    tokio::io::copy(&mut file, &mut writer)
+        .await
+        .map_err(|e| format!("Upload to S3 failed: {}", e))?;
+    writer.shutdown().await.map_err(|e| format!("Upload to S3 failed: {}", e))?;
+    Ok(())
+}
+
+/// Backups in `backup_dir`, newest first.
+pub async fn list_backups(config: &Config) -> Vec<BackupInfo> {
+    let mut backups = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(&config.backup_dir).await else {
+        return backups;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !is_backup_name(&name) {
+            continue;
+        }
+        let Ok(meta) = entry.metadata().await else { continue };
+        let created_at = meta
+            .modified()
+            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
+            .unwrap_or_default();
+        backups.push(BackupInfo { name, size: meta.len(), created_at });
+    }
+    // Names start with the timestamp, so they sort by age
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    backups
+}
+
+/// Keep the newest `backup_keep` backups, locally and in S3.
+async fn prune(config: &Config) {
+    for old in list_backups(config).await.into_iter().skip(config.backup_keep.max(1)) {
+        let path = Path::new(&config.backup_dir).join(&old.name);
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            tracing::warn!("Failed to prune backup {}: {}", path.display(), e);
+        }
+    }
+
+    let store = match s3_store(config) {
+        Ok(Some(store)) => store,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!("{}", e);
+            return;
+        }
+    };
+    let prefix = ObjectPath::from(config.backup_s3_prefix.trim_end_matches('/'));
+    let mut names: Vec<ObjectPath> = match store.list(Some(&prefix)).try_collect::<Vec<_>>().await {
+        Ok(objects) => objects
+            .into_iter()
+            .map(|o| o.location)
+            .filter(|p| p.filename().is_some_and(is_backup_name))
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Failed to list S3 backups: {}", e);
+            return;
+        }
+    };
+    names.sort_by(|a, b| b.filename().cmp(&a.filename()));
+    for old in names.into_iter().skip(config.backup_keep.max(1)) {
+        if let Err(e) = store.delete(&old).await {
+            tracing::warn!("Failed to prune S3 backup {}: {}", old, e);
+        }
+    }
+}
+
+/// Background task: back up every `backup_interval_hours`.
+pub async fn run_scheduled_backups(state: Arc<AppState>) {
+    if state.config.backup_interval_hours == 0 {
+        return;
+    }
+    let mut interval = tokio::time::interval(Duration::from_secs(state.config.backup_interval_hours * 3600));
+    // The first tick is immediate; the database was just opened, so skip it
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        if let Err(e) = create_backup(&state.db, &state.config, "scheduled").await {
+            tracing::error!("{}", e);
+        }
+    }
+}
+
+/// Find the backup `from` refers to: "latest", a backup name, or a path.
+/// Backups only in S3 are downloaded into `backup_dir` first.
+async fn resolve(config: &Config, from: &str) -> Result<PathBuf, String> {
+    if from == "latest" {
+        if let Some(newest) = list_backups(config).await.into_iter().next() {
+            return Ok(Path::new(&config.backup_dir).join(newest.name));
+        }
+        let Some(store) = s3_store(config)? else {
+            return Err(format!("No backups in {}", config.backup_dir));
+        };
+        let prefix = ObjectPath::from(config.backup_s3_prefix.trim_end_matches('/'));
+        let objects: Vec<_> = store.list(Some(&prefix)).try_collect().await.map_err(|e| e.to_string())?;
+        let newest = objects
+            .into_iter()
+            .filter_map(|o| o.location.filename().filter(|n| is_backup_name(n)).map(String::from))
+            .max()
+            .ok_or_else(|| "No backups locally or in S3".to_string())?;
+        return download(config, store, &newest).await;
+    }
+
+    if is_backup_name(from) {
+        let local = Path::new(&config.backup_dir).join(from);
+        if local.exists() {
+            return Ok(local);
+        }
+        if let Some(store) = s3_store(config)? {
+            return download(config, store, from).await;
+        }
+    }
+
+    let path = PathBuf::from(from);
+    if path.exists() {
+        Ok(path)
+    } else {
+        Err(format!("Backup {} not found", from))
+    }
+}
+
+async fn download(config: &Config, store: Arc<dyn ObjectStore>, name: &str) -> Result<PathBuf, String> {
+    let bytes = store
+        .get(&s3_path(config, name))
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", name, e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", name, e))?;
+    tokio::fs::create_dir_all(&config.backup_dir).await.map_err(|e| e.to_string())?;
+    let path = Path::new(&config.backup_dir).join(name);
+    tokio::fs::write(&path, bytes).await.map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Refuse anything that isn't an intact SQLite database.
+async fn check_integrity(path: &Path) -> Result<(), String> {
+    let options = sqlx::sqlite::SqliteConnectOptions::new().filename(path).read_only(true);
+    let mut conn = sqlx::SqliteConnection::connect_with(&options)
+        .await
+        .map_err(|e| format!("{} is not a usable database: {}", path.display(), e))?;
+    let result: String = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_one(&mut conn)
+        .await
+        .map_err(|e| format!("{} is not a usable database: {}", path.display(), e))?;
+    conn.close().await.ok();
+    if result == "ok" {
+        Ok(())
+    } else {
+        Err(format!("{} failed its integrity check: {}", path.display(), result))
+    }
+}
+
+/// Replace the database file with a backup before the pool opens it. The
+/// database being replaced is kept next to it as `<name>.pre-restore`.
+pub async fn restore_at_startup(config: &Config, from: &str) -> Result<PathBuf, String> {
+    let source = resolve(config, from).await?;
+    check_integrity(&source).await?;
+
+    let target = PathBuf::from(&config.database_path);
+    if target.exists() {
+        let kept = target.with_extension("db.pre-restore");
+        tokio::fs::copy(&target, &kept)
+            .await
+            .map_err(|e| format!("Failed to keep the current database: {}", e))?;
+    }
+    // Copy next to the target, then rename, so a crash never leaves half a file
+    let staging = target.with_extension("db.restoring");
+    tokio::fs::copy(&source, &staging).await.map_err(|e| e.to_string())?;
+    for suffix in ["-wal", "-shm"] {
+        let mut side = target.clone().into_os_string();
+        side.push(suffix);
+        tokio::fs::remove_file(PathBuf::from(side)).await.ok();
+    }
+    tokio::fs::rename(&staging, &target).await.map_err(|e| e.to_string())?;
+    Ok(source)
+}
+
+/// Restore a backup into the live database: every table's rows are replaced
+/// with the backup's in one transaction, so other connections see either the
+/// old data or the restored data. A "pre-restore" backup is taken first.
+pub async fn restore_online(db: &SqlitePool, config: &Config, name: &str) -> Result<BackupInfo, String> {
+    let _guard = BACKUP_LOCK.lock().await;
+    let source = resolve(config, name).await?;
+    check_integrity(&source).await?;
+    let safety = create_backup_locked(db, config, "pre-restore").await?;
+
+    let mut conn = db.acquire().await.map_err(|e| e.to_string())?;
+    sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut *conn).await.map_err(|e| e.to_string())?;
+    sqlx::query("ATTACH DATABASE ? AS restore_src")
+        .bind(source.to_string_lossy().as_ref())
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let copied = copy_tables(&mut conn).await;
+
+    sqlx::query("DETACH DATABASE restore_src").execute(&mut *conn).await.ok();
+    sqlx::query("PRAGMA foreign_keys = ON").execute(&mut *conn).await.ok();
+    copied.map_err(|e| format!("Restore failed, nothing was changed: {}", e))?;
+
+    tracing::warn!("Database restored from {} (previous state saved as {})", source.display(), safety.name);
+    Ok(safety)
+}
+
+async fn copy_tables(conn: &mut sqlx::SqliteConnection) -> sqlx::Result<()> {
+    let tables: Vec<(String, String)> = sqlx::query_as(
+        "SELECT name, sql FROM main.sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    // FTS5 keeps its index in shadow tables named after it; those follow
+    // from the rows put into the virtual table itself.
+    let virtual_tables: Vec<&str> = tables
+        .iter()
+        .filter(|(_, sql)| sql.starts_with("CREATE VIRTUAL TABLE"))
+        .map(|(name, _)| name.as_str())
+        .collect();
+
+    let mut tx = conn.begin().await?;
+    for (table, _) in &tables {
+        if virtual_tables.iter().any(|vt| table.starts_with(&format!("{}_", vt))) {
+            continue;
+        }
+        sqlx::query(&format!(r#"DELETE FROM main."{}""#, table)).execute(&mut *tx).await?;
+
+        // Only columns both sides have, so a backup from before a migration
+        // still restores (new columns take their defaults)
+        let columns: Vec<String> = sqlx::query_scalar(
+            "SELECT m.name FROM pragma_table_info(?1, 'main') m
+             JOIN pragma_table_info(?1, 'restore_src') b ON b.name = m.name",
+        )
+        .bind(table)
+        .fetch_all(&mut *tx)
+        .await?;
+        if columns.is_empty() {
+            continue;
+        }
+        let columns = columns.iter().map(|c| format!(r#""{}""#, c)).collect::<Vec<_>>().join(", ");
+        sqlx::query(&format!(
+            r#"INSERT INTO main."{table}" ({columns}) SELECT {columns} FROM restore_src."{table}""#
+        ))
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await
+}
@@ -0,0 +1,89 @@
+//! First-run setup from the environment, for containers and other unattended
+//! installs. With `admin_email` and `admin_password` set, the admin account
+//! (and with it the default server, named `server_name`) exists before the
+//! first request, instead of going to whoever signs up first.
+
+use flux_shared::constants::MAX_USERNAME_LENGTH;
+use flux_shared::validation::validate_username;
+
+use crate::config::Config;
+use crate::routes::auth::{add_password, check_password, create_user, hash_password};
+use crate::AppState;
+
+/// `admin_username`, or failing that a username made from the email.
+fn admin_username(config: &Config, email: &str) -> String {
+    if !config.admin_username.is_empty() {
+        return config.admin_username.clone();
+    }
+    let local: String = email
+        .split('@')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .take(MAX_USERNAME_LENGTH)
+        .collect();
+    if validate_username(&local).is_ok() {
+        local
+    } else {
+        "admin".into()
+    }
+}
+
+/// Create the configured admin account if there's no account with its email,
+/// or make sure it's still an instance admin if there is. Existing accounts
+/// keep their password, so restarting with the same environment is safe.
+pub async fn run(state: &AppState) -> Result<(), String> {
+    let config = &state.config;
+    if config.admin_email.is_empty() {
+        return Ok(());
+    }
+    let email = config.admin_email.trim().to_lowercase();
+
+    let existing = sqlx::query_scalar::<_, String>(r#"SELECT id FROM "user" WHERE email = ?"#)
+        .bind(&email)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(user_id) = existing {
+        let promoted = sqlx::query(r#"UPDATE "user" SET is_instance_admin = 1 WHERE id = ? AND is_instance_admin = 0"#)
+            .bind(&user_id)
+            .execute(&state.db)
+            .await
+            .map_err(|e| e.to_string())?
+            .rows_affected();
+        if promoted > 0 {
+            tracing::info!("Made {} an instance admin", email);
+        }
+        return Ok(());
+    }
+
+    let username = admin_username(config, &email);
+    let taken = sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "user" WHERE username = ?"#)
+        .bind(&username)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| e.to_string())?;
+    if taken > 0 {
+        return Err(format!("The username {} is taken; set admin_username to another", username));
+    }
+    check_password(config, &config.admin_password, &[&email, &username])
+        .map_err(|e| format!("admin_password: {}", e))?;
+    let password_hash = hash_password(config, &config.admin_password).ok_or("Failed to hash admin_password")?;
+
+    let created = async {
+        let mut tx = state.db.begin().await?;
+        let user = create_user(&mut tx, &email, &username, &username, None, true, &config.server_name).await?;
+        sqlx::query(r#"UPDATE "user" SET is_instance_admin = 1 WHERE id = ?"#)
+            .bind(&user.id)
+            .execute(&mut *tx)
+            .await?;
+        add_password(&mut tx, &user.id, &password_hash).await?;
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(user)
+    };
+    created.await.map_err(|e| e.to_string())?;
+
+    tracing::info!("Created admin account {} ({})", username, email);
+    Ok(())
+}
@@ -1,9 +1,21 @@
+use serde::Serialize;
 use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
-#[derive(Clone)]
+/// Server settings. Each comes from, in rising precedence: the default here,
+/// `config.toml` (keys are the field names), then its environment variable.
+#[derive(Clone, Serialize)]
 pub struct Config {
     pub host: String,
     pub port: u16,
+    /// Listen on this Unix socket instead of host:port, for a reverse proxy
+    /// on the same machine. A socket passed by systemd takes precedence.
+    pub unix_socket: String,
+    /// Octal permissions for `unix_socket`, e.g. "660" to let the proxy's
+    /// group connect.
+    pub unix_socket_mode: String,
     pub database_path: String,
     pub auth_secret: String,
     pub livekit_api_key: String,
@@ -11,33 +23,454 @@ pub struct Config {
     pub livekit_url: String,
     pub upload_dir: String,
     pub max_upload_bytes: u64,
+    /// Avatars over this are scaled down, or stored without their animation.
+    pub avatar_max_bytes: usize,
     pub room_cleanup_delay_secs: u64,
+    pub youtube_cache_dir: String,
+    /// On-disk YouTube audio cache budget; 0 disables the cache.
+    pub youtube_cache_max_bytes: u64,
+    /// "tenor" (default) or "giphy"
+    pub gif_provider: String,
+    pub gif_api_key: String,
+    /// LRCLIB-compatible lyrics API base; empty disables lyrics.
+    pub lyrics_api_url: String,
+    /// Where noise suppression model files are cached and served from.
+    pub noise_model_dir: String,
+    /// Base URL model files are downloaded from at startup; empty serves
+    /// only what's already in `noise_model_dir`.
+    pub noise_model_url: String,
+    /// Comma separated model file paths under that URL, each optionally
+    /// pinned to a checksum as `path=<sha256>`.
+    pub noise_model_files: String,
+    /// OpenID Connect issuer URL; empty disables OIDC sign-in.
+    pub oidc_issuer: String,
+    pub oidc_client_id: String,
+    pub oidc_client_secret: String,
+    pub oidc_redirect_uri: String,
+    /// SMTP relay for account emails; empty host disables sending.
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_username: String,
+    pub smtp_password: String,
+    /// Sender address, e.g. `Flux <noreply@example.com>`.
+    pub smtp_from: String,
+    /// "starttls" (default), "tls" for implicit TLS, or "none".
+    pub smtp_security: String,
+    /// Log emails instead of sending them, for development. Email features
+    /// stay available without an SMTP relay.
+    pub mail_log_only: bool,
+    /// PEM certificate chain and private key to serve HTTPS with; empty
+    /// serves plain HTTP. Both are reloaded on SIGHUP or when they change.
+    pub tls_cert_path: String,
+    pub tls_key_path: String,
+    /// With TLS on, also listen here and redirect plain HTTP to HTTPS; 0
+    /// turns the redirect off.
+    pub tls_redirect_port: u16,
+    /// Domain to get a certificate for from an ACME CA, instead of the
+    /// files above; empty turns ACME off.
+    pub acme_domain: String,
+    /// Contact address the CA sends expiry and policy notices to.
+    pub acme_email: String,
+    /// Directory URL of the CA; Let's Encrypt by default.
+    pub acme_directory_url: String,
+    /// "tls-alpn-01" (answered on the HTTPS port, which must be 443 to the
+    /// outside) or "http-01" (answered on the redirect port, which must be 80).
+    pub acme_challenge: String,
+    /// Where the ACME account key and issued certificates are kept.
+    pub acme_dir: String,
+    /// Base URL this server is reachable at, for links in emails.
+    pub public_url: String,
+    /// Refuse gateway connections until the user has verified their email.
+    pub require_email_verification: bool,
+    /// Argon2id cost for new password hashes. Existing hashes are upgraded
+    /// when their owner next signs in.
+    pub argon2_memory_kib: u32,
+    pub argon2_iterations: u32,
+    /// Lowest strength score (0-4) a new password may have.
+    pub password_min_score: u8,
+    /// Minimum time between a user's username changes.
+    pub username_change_cooldown_secs: u64,
+    /// Mark session cookies `Secure` (and `SameSite=None`). Needs HTTPS.
+    pub cookie_secure: bool,
+    /// `Domain` for session cookies; empty means the host that set them.
+    pub cookie_domain: String,
+    /// Comma separated addresses or CIDR ranges of reverse proxies whose
+    /// `X-Forwarded-For`/`X-Real-IP` headers are believed. Anyone else is
+    /// taken to be the client, whatever headers they send.
+    pub trusted_proxies: String,
+    /// Paths only some networks may reach, as `;` separated
+    /// `<path prefix>=<networks>` rules, e.g. `/api/admin=192.168.0.0/16`.
+    pub ip_allowlists: String,
+    /// Bearer token `/metrics` requires; empty leaves it open.
+    pub metrics_token: String,
+    /// OTLP/HTTP collector to export traces to; empty turns export off.
+    pub otlp_endpoint: String,
+    /// `service.name` on exported traces.
+    pub otel_service_name: String,
+    /// Connections in the main pool, and in the read-only pool list
+    /// endpoints use. Message writes always go through one connection.
+    pub db_max_connections: u32,
+    pub db_read_connections: u32,
+    /// How long a query waits for SQLite's write lock before failing with
+    /// "database is locked".
+    pub db_busy_timeout_ms: u64,
+    /// Where database backups are written.
+    pub backup_dir: String,
+    /// Hours between scheduled backups; 0 turns the schedule off.
+    pub backup_interval_hours: u64,
+    /// How many backups to keep; older ones are deleted.
+    pub backup_keep: usize,
+    /// S3 bucket to also upload backups to; empty keeps them local only.
+    /// Credentials and region come from the usual `AWS_*` variables.
+    pub backup_s3_bucket: String,
+    pub backup_s3_prefix: String,
+    /// Backup to restore before opening the database: a file path, a backup
+    /// name, or "latest". Empty starts from the database as it is.
+    pub restore_from: String,
+    /// Admin account to create on startup if there isn't one with this
+    /// email, so unattended installs don't leave the instance to whoever
+    /// signs up first. Existing accounts keep their password.
+    pub admin_email: String,
+    pub admin_password: String,
+    /// Defaults to the part of `admin_email` before the @.
+    pub admin_username: String,
+    /// Name of the server created along with the first account.
+    pub server_name: String,
+    /// Seconds between channel feed polls; 0 stops feeds from posting.
+    pub feed_poll_interval_secs: u64,
+    /// Minutes before an event starts to remind members who RSVP'd; 0 turns
+    /// reminders off.
+    pub event_reminder_minutes: u64,
+    /// Days before direct messages are deleted; 0 keeps them forever.
+    /// Channel retention is set per server and channel instead.
+    pub dm_retention_days: u64,
+    /// Captcha verification endpoint (hCaptcha, Turnstile or reCAPTCHA
+    /// `siteverify`) for servers that require new members to pass a
+    /// challenge. Empty disables challenges.
+    pub challenge_verify_url: String,
+    /// Public site key clients render the challenge widget with.
+    pub challenge_site_key: String,
+    pub challenge_secret: String,
 }
 
+/// Settings printed as `<redacted>` by `--print-config`.
+const SECRET_KEYS: &[&str] = &[
+    "auth_secret",
+    "livekit_api_secret",
+    "gif_api_key",
+    "oidc_client_secret",
+    "smtp_password",
+    "metrics_token",
+    "challenge_secret",
+    "admin_password",
+];
+
+/// A setting that's missing, malformed or not recognised. The message names
+/// the key and where its value came from.
+#[derive(Debug)]
+pub struct ConfigError(String);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
 impl Config {
-    pub fn from_env() -> Self {
-        Self {
-            host: env::var("HOST").unwrap_or_else(|_| "0.0.0.0".into()),
-            port: env::var("PORT")
-                .ok()
-                .and_then(|p| p.parse().ok())
-                .unwrap_or(3001),
-            database_path: env::var("DATABASE_PATH").unwrap_or_else(|_| "./flux.db".into()),
-            auth_secret: env::var("BETTER_AUTH_SECRET")
-                .expect("BETTER_AUTH_SECRET must be set"),
-            livekit_api_key: env::var("LIVEKIT_API_KEY").unwrap_or_default(),
-            livekit_api_secret: env::var("LIVEKIT_API_SECRET").unwrap_or_default(),
-            livekit_url: env::var("LIVEKIT_URL")
-                .unwrap_or_else(|_| "ws://localhost:7880".into()),
-            upload_dir: env::var("UPLOAD_DIR").unwrap_or_else(|_| "./uploads".into()),
-            max_upload_bytes: env::var("MAX_UPLOAD_BYTES")
-                .ok()
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(1_073_741_824), // 1GB
-            room_cleanup_delay_secs: env::var("ROOM_CLEANUP_DELAY_SECS")
-                .ok()
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(120),
+    /// Load settings from `file` (when given, it must exist), else from
+    /// `FLUX_CONFIG` or `./config.toml` if either is there, then apply
+    /// environment overrides.
+    pub fn load(file: Option<&Path>) -> Result<Self, ConfigError> {
+        let path = match file {
+            Some(path) => Some(path.to_path_buf()),
+            None => match env::var("FLUX_CONFIG") {
+                Ok(path) if !path.is_empty() => Some(PathBuf::from(path)),
+                _ => Some(PathBuf::from("config.toml")).filter(|p| p.exists()),
+            },
+        };
+
+        let mut layers = match path {
+            Some(path) => {
+                let text = std::fs::read_to_string(&path)
+                    .map_err(|e| ConfigError(format!("{}: {}", path.display(), e)))?;
+                Layers::from_toml(&text, &path.display().to_string())?
+            }
+            None => Layers::default(),
+        };
+        Self::from_layers(&mut layers)
+    }
+
+    /// Load from `config_toml` (the contents of a config file) plus the
+    /// environment. `file_name` is only used in error messages.
+    pub fn from_toml(config_toml: &str, file_name: &str) -> Result<Self, ConfigError> {
+        Self::from_layers(&mut Layers::from_toml(config_toml, file_name)?)
+    }
+
+    fn from_layers(l: &mut Layers) -> Result<Self, ConfigError> {
+        let config = Self {
+            host: l.string("host", "HOST", "0.0.0.0")?,
+            port: l.number("port", "PORT", 3001)?,
+            unix_socket: l.string("unix_socket", "UNIX_SOCKET", "")?,
+            unix_socket_mode: l.string("unix_socket_mode", "UNIX_SOCKET_MODE", "660")?,
+            database_path: l.string("database_path", "DATABASE_PATH", "./flux.db")?,
+            auth_secret: l.string("auth_secret", "BETTER_AUTH_SECRET", "")?,
+            livekit_api_key: l.string("livekit_api_key", "LIVEKIT_API_KEY", "")?,
+            livekit_api_secret: l.string("livekit_api_secret", "LIVEKIT_API_SECRET", "")?,
+            livekit_url: l.string("livekit_url", "LIVEKIT_URL", "ws://localhost:7880")?,
+            upload_dir: l.string("upload_dir", "UPLOAD_DIR", "./uploads")?,
+            max_upload_bytes: l.number("max_upload_bytes", "MAX_UPLOAD_BYTES", 1_073_741_824)?, // 1GB
+            avatar_max_bytes: l.number("avatar_max_bytes", "AVATAR_MAX_BYTES", 2_097_152)?, // 2MB
+            room_cleanup_delay_secs: l.number("room_cleanup_delay_secs", "ROOM_CLEANUP_DELAY_SECS", 120)?,
+            youtube_cache_dir: l.string("youtube_cache_dir", "YOUTUBE_CACHE_DIR", "./youtube-cache")?,
+            youtube_cache_max_bytes: l.number("youtube_cache_max_bytes", "YOUTUBE_CACHE_MAX_BYTES", 2_147_483_648)?, // 2GB
+            gif_provider: l.string("gif_provider", "GIF_PROVIDER", "tenor")?,
+            gif_api_key: l.string("gif_api_key", "GIF_API_KEY", "")?,
+            lyrics_api_url: l.string("lyrics_api_url", "LYRICS_API_URL", "https://lrclib.net/api")?,
+            noise_model_dir: l.string("noise_model_dir", "NOISE_MODEL_DIR", "./noise-models")?,
+            noise_model_url: l.string(
+                "noise_model_url",
+                "NOISE_MODEL_URL",
+                "https://cdn.mezon.ai/AI/models/datas/noise_suppression/deepfilternet3",
+            )?,
+            noise_model_files: l.string(
+                "noise_model_files",
+                "NOISE_MODEL_FILES",
+                "v2/pkg/df_bg.wasm,v2/models/DeepFilterNet3_onnx.tar.gz",
+            )?,
+            oidc_issuer: l.string("oidc_issuer", "OIDC_ISSUER", "")?,
+            oidc_client_id: l.string("oidc_client_id", "OIDC_CLIENT_ID", "")?,
+            oidc_client_secret: l.string("oidc_client_secret", "OIDC_CLIENT_SECRET", "")?,
+            oidc_redirect_uri: l.string(
+                "oidc_redirect_uri",
+                "OIDC_REDIRECT_URI",
+                "http://127.0.0.1:3001/api/auth/oidc/callback",
+            )?,
+            smtp_host: l.string("smtp_host", "SMTP_HOST", "")?,
+            smtp_port: l.number("smtp_port", "SMTP_PORT", 587)?,
+            smtp_username: l.string("smtp_username", "SMTP_USERNAME", "")?,
+            smtp_password: l.string("smtp_password", "SMTP_PASSWORD", "")?,
+            smtp_from: l.string("smtp_from", "SMTP_FROM", "Flux <noreply@localhost>")?,
+            smtp_security: l.string("smtp_security", "SMTP_SECURITY", "starttls")?,
+            mail_log_only: l.flag("mail_log_only", "MAIL_LOG_ONLY", false)?,
+            tls_cert_path: l.string("tls_cert_path", "TLS_CERT_PATH", "")?,
+            tls_key_path: l.string("tls_key_path", "TLS_KEY_PATH", "")?,
+            tls_redirect_port: l.number("tls_redirect_port", "TLS_REDIRECT_PORT", 0)?,
+            acme_domain: l.string("acme_domain", "ACME_DOMAIN", "")?,
+            acme_email: l.string("acme_email", "ACME_EMAIL", "")?,
+            acme_directory_url: l.string(
+                "acme_directory_url",
+                "ACME_DIRECTORY_URL",
+                "https://acme-v02.api.letsencrypt.org/directory",
+            )?,
+            acme_challenge: l.string("acme_challenge", "ACME_CHALLENGE", "tls-alpn-01")?,
+            acme_dir: l.string("acme_dir", "ACME_DIR", "./acme")?,
+            public_url: l.string("public_url", "PUBLIC_URL", "http://127.0.0.1:3001")?,
+            require_email_verification: l.flag("require_email_verification", "REQUIRE_EMAIL_VERIFICATION", false)?,
+            argon2_memory_kib: l.number("argon2_memory_kib", "ARGON2_MEMORY_KIB", 19_456)?,
+            argon2_iterations: l.number("argon2_iterations", "ARGON2_ITERATIONS", 2)?,
+            password_min_score: l.number("password_min_score", "PASSWORD_MIN_SCORE", 3)?,
+            username_change_cooldown_secs: l.number("username_change_cooldown_secs", "USERNAME_CHANGE_COOLDOWN_SECS", 604_800)?, // 7 days
+            cookie_secure: l.flag("cookie_secure", "COOKIE_SECURE", false)?,
+            cookie_domain: l.string("cookie_domain", "COOKIE_DOMAIN", "")?,
+            trusted_proxies: l.string("trusted_proxies", "TRUSTED_PROXIES", "127.0.0.0/8,::1,10.0.0.0/8,172.16.0.0/12,192.168.0.0/16,fc00::/7")?,
+            ip_allowlists: l.string("ip_allowlists", "IP_ALLOWLISTS", "")?,
+            metrics_token: l.string("metrics_token", "METRICS_TOKEN", "")?,
+            otlp_endpoint: l.string("otlp_endpoint", "OTEL_EXPORTER_OTLP_ENDPOINT", "")?,
+            otel_service_name: l.string("otel_service_name", "OTEL_SERVICE_NAME", "flux-server")?,
+            db_max_connections: l.number("db_max_connections", "DB_MAX_CONNECTIONS", 8)?,
+            db_read_connections: l.number("db_read_connections", "DB_READ_CONNECTIONS", 8)?,
+            db_busy_timeout_ms: l.number("db_busy_timeout_ms", "DB_BUSY_TIMEOUT_MS", 5000)?,
+            backup_dir: l.string("backup_dir", "BACKUP_DIR", "./backups")?,
+            backup_interval_hours: l.number("backup_interval_hours", "BACKUP_INTERVAL_HOURS", 24)?,
+            backup_keep: l.number("backup_keep", "BACKUP_KEEP", 7)?,
+            backup_s3_bucket: l.string("backup_s3_bucket", "BACKUP_S3_BUCKET", "")?,
+            backup_s3_prefix: l.string("backup_s3_prefix", "BACKUP_S3_PREFIX", "flux-backups/")?,
+            restore_from: l.string("restore_from", "RESTORE_FROM", "")?,
+            admin_email: l.string("admin_email", "FLUX_ADMIN_EMAIL", "")?,
+            admin_password: l.string("admin_password", "FLUX_ADMIN_PASSWORD", "")?,
+            admin_username: l.string("admin_username", "FLUX_ADMIN_USERNAME", "")?,
+            server_name: l.string("server_name", "FLUX_SERVER_NAME", "flux")?,
+            feed_poll_interval_secs: l.number("feed_poll_interval_secs", "FEED_POLL_INTERVAL_SECS", 900)?,
+            event_reminder_minutes: l.number("event_reminder_minutes", "EVENT_REMINDER_MINUTES", 15)?,
+            dm_retention_days: l.number("dm_retention_days", "DM_RETENTION_DAYS", 0)?,
+            challenge_verify_url: l.string("challenge_verify_url", "CHALLENGE_VERIFY_URL", "")?,
+            challenge_site_key: l.string("challenge_site_key", "CHALLENGE_SITE_KEY", "")?,
+            challenge_secret: l.string("challenge_secret", "CHALLENGE_SECRET", "")?,
+        };
+        l.finish()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.auth_secret.is_empty() {
+            return Err(ConfigError("auth_secret (BETTER_AUTH_SECRET) must be set".into()));
+        }
+        one_of("gif_provider", &self.gif_provider, &["tenor", "giphy"])?;
+        one_of("smtp_security", &self.smtp_security, &["starttls", "tls", "none"])?;
+        if self.tls_cert_path.is_empty() != self.tls_key_path.is_empty() {
+            return Err(ConfigError("tls_cert_path and tls_key_path must be set together".into()));
+        }
+        one_of("acme_challenge", &self.acme_challenge, &["tls-alpn-01", "http-01"])?;
+        if self.admin_email.is_empty() != self.admin_password.is_empty() {
+            return Err(ConfigError("admin_email and admin_password must be set together".into()));
+        }
+        if !self.admin_email.is_empty() && !self.admin_email.contains('@') {
+            return Err(ConfigError(format!("admin_email \"{}\" isn't an email address", self.admin_email)));
+        }
+        if !self.admin_username.is_empty() {
+            flux_shared::validation::validate_username(&self.admin_username)
+                .map_err(|e| ConfigError(format!("admin_username: {}", e)))?;
+        }
+        flux_shared::validation::validate_server_name(&self.server_name)
+            .map_err(|e| ConfigError(format!("server_name: {}", e)))?;
+        if !matches!(u32::from_str_radix(&self.unix_socket_mode, 8), Ok(mode) if mode <= 0o777) {
+            return Err(ConfigError(format!(
+                "unix_socket_mode must be octal permissions like 660, got \"{}\"",
+                self.unix_socket_mode
+            )));
+        }
+        if !self.unix_socket.is_empty() {
+            if !cfg!(unix) {
+                return Err(ConfigError("unix_socket is only supported on Unix".into()));
+            }
+            if !self.tls_cert_path.is_empty() || !self.acme_domain.is_empty() {
+                return Err(ConfigError("unix_socket can't serve TLS; leave that to the proxy".into()));
+            }
+        }
+        if !self.acme_domain.is_empty() {
+            if !self.tls_cert_path.is_empty() {
+                return Err(ConfigError("acme_domain and tls_cert_path can't both be set".into()));
+            }
+            if self.acme_challenge == "http-01" && self.tls_redirect_port == 0 {
+                return Err(ConfigError("acme_challenge http-01 needs tls_redirect_port".into()));
+            }
+        }
+        crate::routes::noise_models::parse_model_files(&self.noise_model_files)
+            .map_err(|e| ConfigError(format!("noise_model_files: {}", e)))?;
+        crate::middleware::ip_filter::parse_networks(&self.trusted_proxies)
+            .map_err(|e| ConfigError(format!("trusted_proxies: {}", e)))?;
+        crate::middleware::ip_filter::parse_allowlists(&self.ip_allowlists)
+            .map_err(|e| ConfigError(format!("ip_allowlists: {}", e)))?;
+        if self.db_max_connections == 0 || self.db_read_connections == 0 {
+            return Err(ConfigError("db_max_connections and db_read_connections must be at least 1".into()));
+        }
+        if self.password_min_score > 4 {
+            return Err(ConfigError(format!(
+                "password_min_score must be 0-4, got {}",
+                self.password_min_score
+            )));
+        }
+        Ok(())
+    }
+
+    /// The effective settings as TOML, with secrets replaced so it can be
+    /// pasted into a bug report.
+    pub fn to_redacted_toml(&self) -> String {
+        let mut table = toml::Table::try_from(self).expect("Config serializes to a table");
+        for key in SECRET_KEYS {
+            if let Some(toml::Value::String(value)) = table.get_mut(*key) {
+                if !value.is_empty() {
+                    *value = "<redacted>".into();
+                }
+            }
+        }
+        toml::to_string(&table).expect("a TOML table serializes")
+    }
+}
+
+fn one_of(key: &str, value: &str, allowed: &[&str]) -> Result<(), ConfigError> {
+    if allowed.contains(&value) {
+        Ok(())
+    } else {
+        Err(ConfigError(format!(
+            "{} must be one of {}, got {:?}",
+            key,
+            allowed.join(", "),
+            value
+        )))
+    }
+}
+
+/// The config file's values, taken out key by key as each setting is
+/// resolved so that whatever is left over is a key we don't know.
+#[derive(Default)]
+struct Layers {
+    file: toml::Table,
+    file_name: String,
+}
+
+impl Layers {
+    fn from_toml(text: &str, file_name: &str) -> Result<Self, ConfigError> {
+        let file = text
+            .parse::<toml::Table>()
+            .map_err(|e| ConfigError(format!("{}: {}", file_name, e)))?;
+        Ok(Self { file, file_name: file_name.to_string() })
+    }
+
+    /// The environment value if set, else the file's.
+    fn take(&mut self, key: &str, env_var: &str) -> Option<Source> {
+        let from_file = self.file.remove(key);
+        if let Ok(value) = env::var(env_var) {
+            return Some(Source::Env(value));
+        }
+        from_file.map(Source::File)
+    }
+
+    fn string(&mut self, key: &str, env_var: &str, default: &str) -> Result<String, ConfigError> {
+        match self.take(key, env_var) {
+            None => Ok(default.to_string()),
+            Some(Source::Env(value)) => Ok(value),
+            Some(Source::File(toml::Value::String(value))) => Ok(value),
+            Some(Source::File(other)) => Err(self.wrong_type(key, "a string", &other)),
         }
     }
+
+    fn number<T: FromStr>(&mut self, key: &str, env_var: &str, default: T) -> Result<T, ConfigError> {
+        match self.take(key, env_var) {
+            // An empty variable (as in .env.example) means "not set"
+            None => Ok(default),
+            Some(Source::Env(value)) if value.trim().is_empty() => Ok(default),
+            Some(Source::Env(value)) => value.trim().parse().map_err(|_| {
+                ConfigError(format!("{} ({}): {:?} is not a valid number for this setting", key, env_var, value))
+            }),
+            Some(Source::File(toml::Value::Integer(n))) => n.to_string().parse().map_err(|_| {
+                ConfigError(format!("{} in {}: {} is out of range", key, self.file_name, n))
+            }),
+            Some(Source::File(other)) => Err(self.wrong_type(key, "a whole number", &other)),
+        }
+    }
+
+    fn flag(&mut self, key: &str, env_var: &str, default: bool) -> Result<bool, ConfigError> {
+        match self.take(key, env_var) {
+            None => Ok(default),
+            Some(Source::Env(value)) => match value.trim() {
+                "" => Ok(default),
+                "true" | "1" => Ok(true),
+                "false" | "0" => Ok(false),
+                _ => Err(ConfigError(format!("{} ({}): expected true or false, got {:?}", key, env_var, value))),
+            },
+            Some(Source::File(toml::Value::Boolean(value))) => Ok(value),
+            Some(Source::File(other)) => Err(self.wrong_type(key, "true or false", &other)),
+        }
+    }
+
+    fn wrong_type(&self, key: &str, expected: &str, got: &toml::Value) -> ConfigError {
+        ConfigError(format!("{} in {}: expected {}, got {}", key, self.file_name, expected, got.type_str()))
+    }
+
+    /// Fail on keys no setting claimed, which are usually typos.
+    fn finish(&self) -> Result<(), ConfigError> {
+        match self.file.keys().next() {
+            Some(key) => Err(ConfigError(format!("{}: unknown key {:?}", self.file_name, key))),
+            None => Ok(()),
+        }
+    }
+}
+
+enum Source {
+    Env(String),
+    File(toml::Value),
 }
@@ -1,25 +1,91 @@
-use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions};
 use std::path::Path;
+use std::time::Duration;
 
+use crate::config::Config;
+
+/// Connection pool sizes and lock handling.
+#[derive(Debug, Clone, Copy)]
+pub struct PoolSettings {
+    pub max_connections: u32,
+    pub read_connections: u32,
+    /// How long a connection waits on a locked database before giving up.
+    pub busy_timeout: Duration,
+}
+
+impl Default for PoolSettings {
+    fn default() -> Self {
+        Self {
+            max_connections: 8,
+            read_connections: 8,
+            busy_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+impl PoolSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_connections: config.db_max_connections,
+            read_connections: config.db_read_connections,
+            busy_timeout: Duration::from_millis(config.db_busy_timeout_ms),
+        }
+    }
+}
+
+/// The pools the server runs on. SQLite allows one writer at a time, so
+/// `write` has a single connection and hot write paths queue on it instead
+/// of retrying on "database is locked". `read` is opened read-only for list
+/// and search endpoints; WAL lets those run alongside the writer.
+pub struct Pools {
+    pub db: SqlitePool,
+    pub read: SqlitePool,
+    pub write: SqlitePool,
+}
+
+fn connect_options(database_path: &str, settings: &PoolSettings) -> SqliteConnectOptions {
+    SqliteConnectOptions::new()
+        .filename(database_path)
+        .foreign_keys(true)
+        .busy_timeout(settings.busy_timeout)
+}
+
+/// Open the database with default pool settings and bring the schema up to date.
 pub async fn init_pool(database_path: &str) -> Result<SqlitePool, sqlx::Error> {
+    open_and_migrate(database_path, &PoolSettings::default()).await
+}
+
+/// Open the main, read-only and writer pools. Migrations run on the main
+/// pool before the others connect.
+pub async fn init_pools(database_path: &str, settings: PoolSettings) -> Result<Pools, sqlx::Error> {
+    let db = open_and_migrate(database_path, &settings).await?;
+
+    let read = SqlitePoolOptions::new()
+        .max_connections(settings.read_connections)
+        .connect_with(connect_options(database_path, &settings).read_only(true))
+        .await?;
+    let write = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(connect_options(database_path, &settings).journal_mode(SqliteJournalMode::Wal))
+        .await?;
+
+    Ok(Pools { db, read, write })
+}
+
+async fn open_and_migrate(database_path: &str, settings: &PoolSettings) -> Result<SqlitePool, sqlx::Error> {
     // Ensure parent directory exists
     if let Some(parent) = Path::new(database_path).parent() {
         std::fs::create_dir_all(parent).ok();
     }
 
-    let database_url = format!("sqlite:{}?mode=rwc", database_path);
-
+    // WAL so readers don't block the writer; foreign keys and busy_timeout
+    // are per connection, so they're set on every one the pool opens
+    let options = connect_options(database_path, settings)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal);
     let pool = SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect(&database_url)
-        .await?;
-
-    // Enable WAL mode and foreign keys
-    sqlx::query("PRAGMA journal_mode = WAL")
-        .execute(&pool)
-        .await?;
-    sqlx::query("PRAGMA foreign_keys = ON")
-        .execute(&pool)
+        .max_connections(settings.max_connections)
+        .connect_with(options)
         .await?;
 
     // Run schema
@@ -64,6 +130,48 @@ pub async fn init_pool(database_path: &str) -> Result<SqlitePool, sqlx::Error> {
         .await
         .ok();
 
+    // Profile details shown on the profile card
+    sqlx::query(r#"ALTER TABLE "user" ADD COLUMN bio TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query(r#"ALTER TABLE "user" ADD COLUMN pronouns TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query(r#"ALTER TABLE "user" ADD COLUMN accent_color TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query(r#"ALTER TABLE "user" ADD COLUMN timezone TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Still first frame of an animated avatar
+    sqlx::query(r#"ALTER TABLE "user" ADD COLUMN image_static TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Per-server nicknames and avatars
+    sqlx::query(r#"ALTER TABLE "memberships" ADD COLUMN nickname TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query(r#"ALTER TABLE "memberships" ADD COLUMN avatar TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query(r#"ALTER TABLE "memberships" ADD COLUMN avatar_static TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query(r#"ALTER TABLE "memberships" ADD COLUMN profile_updated_at TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+
     // Unique index for account upsert (userId + providerId)
     sqlx::query(r#"CREATE UNIQUE INDEX IF NOT EXISTS idx_account_user_provider ON "account"(userId, providerId)"#)
         .execute(&pool)
@@ -80,7 +188,8 @@ pub async fn init_pool(database_path: &str) -> Result<SqlitePool, sqlx::Error> {
             current_track_position_ms INTEGER DEFAULT 0,
             is_playing INTEGER DEFAULT 0,
             created_at TEXT NOT NULL,
-            updated_at TEXT NOT NULL
+            updated_at TEXT NOT NULL,
+            session_type TEXT NOT NULL DEFAULT 'audio'
         )"#,
     )
     .execute(&pool)
@@ -107,6 +216,75 @@ pub async fn init_pool(database_path: &str) -> Result<SqlitePool, sqlx::Error> {
     .await
     .ok();
 
+    // Spotify: tracks played per session
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "session_history" (
+            id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL REFERENCES "listening_sessions"(id) ON DELETE CASCADE,
+            track_uri TEXT NOT NULL,
+            track_name TEXT NOT NULL,
+            track_artist TEXT NOT NULL,
+            track_album TEXT,
+            track_image_url TEXT,
+            track_duration_ms INTEGER NOT NULL DEFAULT 0,
+            source TEXT NOT NULL DEFAULT 'spotify',
+            played_by_user_id TEXT NOT NULL,
+            played_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Spotify: co-hosts allowed to control a session's playback
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "listening_session_cohosts" (
+            session_id TEXT NOT NULL REFERENCES "listening_sessions"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (session_id, user_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Last.fm: linked accounts and scrobbles waiting to be submitted
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "lastfm_links" (
+            user_id TEXT PRIMARY KEY REFERENCES "user"(id) ON DELETE CASCADE,
+            username TEXT NOT NULL,
+            session_key TEXT NOT NULL,
+            scrobbling_enabled INTEGER NOT NULL DEFAULT 1,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "scrobble_queue" (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            artist TEXT NOT NULL,
+            track TEXT NOT NULL,
+            album TEXT,
+            duration_ms INTEGER NOT NULL DEFAULT 0,
+            played_at INTEGER NOT NULL,
+            ready_at INTEGER NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_scrobble_queue_ready ON "scrobble_queue"(ready_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
     // Migration: add source column to session_queue
     sqlx::query(
         r#"ALTER TABLE "session_queue" ADD COLUMN source TEXT NOT NULL DEFAULT 'spotify'"#,
@@ -115,6 +293,14 @@ pub async fn init_pool(database_path: &str) -> Result<SqlitePool, sqlx::Error> {
     .await
     .ok();
 
+    // Migration: session type, 'audio' (listening) or 'video' (watch-together)
+    sqlx::query(
+        r#"ALTER TABLE "listening_sessions" ADD COLUMN session_type TEXT NOT NULL DEFAULT 'audio'"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
     // Migration: add role_updated_at to memberships
     sqlx::query(r#"ALTER TABLE "memberships" ADD COLUMN role_updated_at TEXT"#)
         .execute(&pool)
@@ -140,6 +326,37 @@ pub async fn init_pool(database_path: &str) -> Result<SqlitePool, sqlx::Error> {
     .await
     .ok();
 
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "signup_invites" (
+            id TEXT PRIMARY KEY,
+            token_hash TEXT UNIQUE NOT NULL,
+            note TEXT,
+            created_by TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL,
+            expires_at TEXT NOT NULL,
+            used_by TEXT REFERENCES "user"(id) ON DELETE SET NULL,
+            used_at TEXT
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "access_requests" (
+            id TEXT PRIMARY KEY,
+            email TEXT UNIQUE NOT NULL,
+            reason TEXT NOT NULL DEFAULT '',
+            status TEXT NOT NULL DEFAULT 'pending',
+            created_at TEXT NOT NULL,
+            resolved_by TEXT REFERENCES "user"(id) ON DELETE SET NULL,
+            resolved_at TEXT
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
     // Migration: add parent_id and position to channels (tree hierarchy)
     sqlx::query(r#"ALTER TABLE "channels" ADD COLUMN parent_id TEXT REFERENCES "channels"(id) ON DELETE CASCADE"#)
         .execute(&pool)
@@ -224,6 +441,34 @@ pub async fn init_pool(database_path: &str) -> Result<SqlitePool, sqlx::Error> {
         .execute(&pool)
         .await?;
 
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "roadmap_votes" (
+            item_id TEXT NOT NULL REFERENCES "roadmap_items"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (item_id, user_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "roadmap_comments" (
+            id TEXT PRIMARY KEY,
+            item_id TEXT NOT NULL REFERENCES "roadmap_items"(id) ON DELETE CASCADE,
+            parent_id TEXT REFERENCES "roadmap_comments"(id) ON DELETE CASCADE,
+            author_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            content TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_roadmap_comments_item ON roadmap_comments(item_id, created_at)"#)
+        .execute(&pool)
+        .await?;
+
     // Gallery sets (global, any user can create)
     sqlx::query(
         r#"CREATE TABLE IF NOT EXISTS "gallery_sets" (
@@ -276,6 +521,942 @@ pub async fn init_pool(database_path: &str) -> Result<SqlitePool, sqlx::Error> {
     .await
     .ok();
 
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "gallery_collaborators" (
+            set_id TEXT NOT NULL REFERENCES "gallery_sets"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (set_id, user_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_gallery_collaborators_user ON gallery_collaborators(user_id)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Economy: coin balances, reward payouts and the rules that drive them
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "wallets" (
+            user_id TEXT PRIMARY KEY REFERENCES "user"(id) ON DELETE CASCADE,
+            balance INTEGER NOT NULL DEFAULT 0,
+            updated_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "coin_rewards_log" (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            amount INTEGER NOT NULL,
+            reason TEXT NOT NULL,
+            server_id TEXT,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_coin_rewards_log_user ON "coin_rewards_log"(user_id, reason, created_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "daily_streaks" (
+            user_id TEXT PRIMARY KEY REFERENCES "user"(id) ON DELETE CASCADE,
+            streak INTEGER NOT NULL DEFAULT 0,
+            last_claim_date TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "reward_rules" (
+            rule TEXT PRIMARY KEY,
+            amount INTEGER NOT NULL,
+            daily_cap INTEGER NOT NULL DEFAULT 0,
+            cooldown_secs INTEGER NOT NULL DEFAULT 0,
+            enabled INTEGER NOT NULL DEFAULT 1
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+    sqlx::query(
+        r#"INSERT OR IGNORE INTO "reward_rules" (rule, amount, daily_cap, cooldown_secs) VALUES
+           ('daily_login', 100, 0, 0),
+           ('message', 2, 100, 60),
+           ('voice_minute', 1, 120, 50)"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Migration: admin notes on manual coin adjustments
+    sqlx::query(r#"ALTER TABLE "coin_rewards_log" ADD COLUMN note TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query(r#"ALTER TABLE "coin_rewards_log" ADD COLUMN actor_id TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Economy pauses: servers where an admin has switched earning and games off
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "economy_pauses" (
+            server_id TEXT PRIMARY KEY REFERENCES "servers"(id) ON DELETE CASCADE,
+            paused_until TEXT,
+            reason TEXT,
+            paused_by TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Games: per-user provably-fair seed pairs, game records and per-server limits
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "game_seeds" (
+            user_id TEXT PRIMARY KEY REFERENCES "user"(id) ON DELETE CASCADE,
+            server_seed TEXT NOT NULL,
+            client_seed TEXT NOT NULL,
+            nonce INTEGER NOT NULL DEFAULT 0
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "games" (
+            id TEXT PRIMARY KEY,
+            kind TEXT NOT NULL,
+            server_id TEXT NOT NULL REFERENCES "servers"(id) ON DELETE CASCADE,
+            channel_id TEXT NOT NULL,
+            creator_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            opponent_id TEXT,
+            wager INTEGER NOT NULL,
+            target INTEGER,
+            status TEXT NOT NULL,
+            seed_user_id TEXT,
+            server_seed TEXT,
+            server_seed_hash TEXT,
+            client_seed TEXT,
+            nonce INTEGER,
+            roll INTEGER,
+            winner_id TEXT,
+            payout INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            resolved_at TEXT
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "game_settings" (
+            server_id TEXT PRIMARY KEY REFERENCES "servers"(id) ON DELETE CASCADE,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            house_edge_bps INTEGER NOT NULL DEFAULT 200,
+            daily_loss_limit INTEGER NOT NULL DEFAULT 1000
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // E2EE key backups: one passphrase-wrapped identity key per user
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "key_backups" (
+            user_id TEXT PRIMARY KEY REFERENCES "user"(id) ON DELETE CASCADE,
+            version INTEGER NOT NULL,
+            wrapped_key TEXT NOT NULL,
+            salt TEXT NOT NULL,
+            kdf TEXT NOT NULL,
+            kdf_params TEXT NOT NULL,
+            public_key TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // E2EE device keys: one identity key per (user, device)
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "device_keys" (
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            device_id TEXT NOT NULL,
+            public_key TEXT NOT NULL,
+            name TEXT,
+            created_at TEXT NOT NULL,
+            last_seen_at TEXT NOT NULL,
+            PRIMARY KEY (user_id, device_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // E2EE key verifications: which peer key (account or device) a user confirmed
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "key_verifications" (
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            peer_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            device_id TEXT NOT NULL DEFAULT '',
+            public_key TEXT NOT NULL,
+            verified_at TEXT NOT NULL,
+            PRIMARY KEY (user_id, peer_id, device_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Password reset tokens: only an HMAC of the token is stored
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "password_resets" (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            token_hash TEXT UNIQUE NOT NULL,
+            expires_at TEXT NOT NULL,
+            used_at TEXT,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Emailed verification tokens (HMAC stored). purpose: verify, change_old, change_new
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "email_tokens" (
+            token_hash TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            email TEXT NOT NULL,
+            purpose TEXT NOT NULL,
+            expires_at TEXT NOT NULL,
+            used_at TEXT,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Pending email changes; applied once both addresses confirm
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "email_changes" (
+            user_id TEXT PRIMARY KEY REFERENCES "user"(id) ON DELETE CASCADE,
+            new_email TEXT NOT NULL,
+            old_confirmed_at TEXT,
+            new_confirmed_at TEXT,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Sign-in attempts, kept as an audit trail and for throttling
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "login_attempts" (
+            id TEXT PRIMARY KEY,
+            email TEXT NOT NULL,
+            user_id TEXT REFERENCES "user"(id) ON DELETE CASCADE,
+            ip TEXT,
+            success INTEGER NOT NULL,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Failed sign-in counters per account (`account:<email>`) and per IP (`ip:<addr>`)
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "login_lockouts" (
+            key TEXT PRIMARY KEY,
+            failures INTEGER NOT NULL DEFAULT 0,
+            lockouts INTEGER NOT NULL DEFAULT 0,
+            locked_until TEXT,
+            updated_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Index for listing an account's sign-in attempts
+    sqlx::query(
+        r#"CREATE INDEX IF NOT EXISTS idx_login_attempts_email ON "login_attempts"(email, created_at)"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Migration: per-device server keys. device_id joins the primary key, so
+    // older databases get the table rebuilt.
+    let has_device_id = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM pragma_table_info('server_keys') WHERE name = 'device_id'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(1);
+    if has_device_id == 0 {
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            r#"CREATE TABLE "server_keys_new" (
+                server_id TEXT NOT NULL REFERENCES "servers"(id) ON DELETE CASCADE,
+                user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+                device_id TEXT NOT NULL DEFAULT '',
+                encrypted_key TEXT NOT NULL,
+                sender_id TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (server_id, user_id, device_id)
+            )"#,
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"INSERT INTO "server_keys_new" (server_id, user_id, device_id, encrypted_key, sender_id, created_at)
+               SELECT server_id, user_id, '', encrypted_key, sender_id, created_at FROM "server_keys"
+               WHERE server_id IN (SELECT id FROM "servers") AND user_id IN (SELECT id FROM "user")"#,
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(r#"DROP TABLE "server_keys""#).execute(&mut *tx).await?;
+        sqlx::query(r#"ALTER TABLE "server_keys_new" RENAME TO "server_keys""#)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+    }
+
+    // Migration: parsed markup metadata on channel messages
+    sqlx::query(r#"ALTER TABLE "messages" ADD COLUMN metadata TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Migration: messages posted by a channel feed
+    sqlx::query(r#"ALTER TABLE "messages" ADD COLUMN feed_id TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Migration: messages posted by a webhook integration
+    sqlx::query(r#"ALTER TABLE "messages" ADD COLUMN integration_id TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Migration: instance admins, separate from server roles
+    sqlx::query(r#"ALTER TABLE "user" ADD COLUMN is_instance_admin INTEGER NOT NULL DEFAULT 0"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Existing instances: the first server's owner becomes the instance admin
+    sqlx::query(
+        r#"UPDATE "user" SET is_instance_admin = 1
+           WHERE id = (SELECT owner_id FROM servers ORDER BY created_at ASC LIMIT 1)
+             AND NOT EXISTS (SELECT 1 FROM "user" WHERE is_instance_admin = 1)"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Instance-wide settings (registration, whitelist mode), one row per key
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "instance_settings" (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_by TEXT REFERENCES "user"(id) ON DELETE SET NULL,
+            updated_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Online user counts over time, for the admin dashboard
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "online_samples" (
+            sampled_at TEXT PRIMARY KEY,
+            online_users INTEGER NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Former usernames, so old mentions and searches still find their owner
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "username_history" (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            old_username TEXT NOT NULL,
+            changed_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_username_history_old ON "username_history"(old_username, changed_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // RSS/Atom feeds that post into channels, and the entries each has seen
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "channel_feeds" (
+            id TEXT PRIMARY KEY,
+            channel_id TEXT NOT NULL REFERENCES "channels"(id) ON DELETE CASCADE,
+            url TEXT NOT NULL,
+            title TEXT NOT NULL,
+            created_by TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL,
+            last_polled_at TEXT,
+            last_error TEXT,
+            UNIQUE(channel_id, url)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "feed_entries" (
+            feed_id TEXT NOT NULL REFERENCES "channel_feeds"(id) ON DELETE CASCADE,
+            guid TEXT NOT NULL,
+            seen_at TEXT NOT NULL,
+            PRIMARY KEY (feed_id, guid)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Webhook integrations (GitHub/GitLab) that post into channels
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "channel_integrations" (
+            id TEXT PRIMARY KEY,
+            channel_id TEXT NOT NULL REFERENCES "channels"(id) ON DELETE CASCADE,
+            provider TEXT NOT NULL,
+            name TEXT NOT NULL,
+            token_hash TEXT NOT NULL UNIQUE,
+            secret TEXT NOT NULL,
+            created_by TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL,
+            last_delivery_at TEXT
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Revocable tokens that let feed readers follow a channel as Atom, RSS
+    // or JSON Feed on a member's behalf
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "channel_feed_tokens" (
+            id TEXT PRIMARY KEY,
+            channel_id TEXT NOT NULL REFERENCES "channels"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            name TEXT NOT NULL,
+            token_hash TEXT NOT NULL UNIQUE,
+            created_at TEXT NOT NULL,
+            last_used_at TEXT
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Scheduled server events, their RSVPs, and which occurrence was last reminded
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "events" (
+            id TEXT PRIMARY KEY,
+            server_id TEXT NOT NULL REFERENCES "servers"(id) ON DELETE CASCADE,
+            channel_id TEXT REFERENCES "channels"(id) ON DELETE SET NULL,
+            title TEXT NOT NULL,
+            description TEXT NOT NULL DEFAULT '',
+            start_at TEXT NOT NULL,
+            end_at TEXT,
+            recurrence TEXT,
+            created_by TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            reminded_for TEXT
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_events_server ON "events"(server_id, start_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "event_rsvps" (
+            event_id TEXT NOT NULL REFERENCES "events"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            status TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (event_id, user_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // "Remind me about this message"; exactly one of message_id / dm_message_id is set
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "message_reminders" (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            message_id TEXT REFERENCES "messages"(id) ON DELETE CASCADE,
+            dm_message_id TEXT REFERENCES "dm_messages"(id) ON DELETE CASCADE,
+            note TEXT,
+            remind_at TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            fired_at TEXT
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_message_reminders_due ON "message_reminders"(fired_at, remind_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_message_reminders_user ON "message_reminders"(user_id, remind_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Saved (bookmarked) messages; exactly one of message_id / dm_message_id is set
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "saved_messages" (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            message_id TEXT REFERENCES "messages"(id) ON DELETE CASCADE,
+            dm_message_id TEXT REFERENCES "dm_messages"(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE UNIQUE INDEX IF NOT EXISTS idx_saved_messages_message ON "saved_messages"(user_id, message_id)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(r#"CREATE UNIQUE INDEX IF NOT EXISTS idx_saved_messages_dm ON "saved_messages"(user_id, dm_message_id)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_saved_messages_user ON "saved_messages"(user_id, created_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Daily custom emoji usage counters, for per-server emoji stats
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "emoji_usage_daily" (
+            server_id TEXT NOT NULL REFERENCES "servers"(id) ON DELETE CASCADE,
+            emoji_id TEXT NOT NULL REFERENCES "custom_emojis"(id) ON DELETE CASCADE,
+            day TEXT NOT NULL,
+            reactions INTEGER NOT NULL DEFAULT 0,
+            messages INTEGER NOT NULL DEFAULT 0,
+            last_used_at TEXT NOT NULL,
+            PRIMARY KEY (emoji_id, day)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_emoji_usage_daily_server ON "emoji_usage_daily"(server_id, day)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Voice call history: one row per stretch a user spent in a voice channel.
+    // channel_id has no foreign key so history outlives deleted rooms.
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "voice_sessions" (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            channel_id TEXT NOT NULL,
+            channel_name TEXT NOT NULL,
+            server_id TEXT REFERENCES "servers"(id) ON DELETE CASCADE,
+            joined_at TEXT NOT NULL,
+            last_seen_at TEXT NOT NULL,
+            left_at TEXT,
+            duration_secs INTEGER,
+            coins_earned INTEGER NOT NULL DEFAULT 0
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_voice_sessions_user ON "voice_sessions"(user_id, joined_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_voice_sessions_server ON "voice_sessions"(server_id, joined_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Preferred language for server-generated text; NULL follows Accept-Language
+    sqlx::query(r#"ALTER TABLE "user" ADD COLUMN locale TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Message retention: days before messages are purged. On channels, NULL
+    // follows the server setting and 0 keeps messages forever.
+    sqlx::query(r#"ALTER TABLE "servers" ADD COLUMN retention_days INTEGER"#)
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query(r#"ALTER TABLE "channels" ADD COLUMN retention_days INTEGER"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Custom roles members can hold alongside owner/admin/member
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "server_roles" (
+            id TEXT PRIMARY KEY,
+            server_id TEXT NOT NULL REFERENCES "servers"(id) ON DELETE CASCADE,
+            name TEXT NOT NULL,
+            color TEXT,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE UNIQUE INDEX IF NOT EXISTS idx_server_roles_name ON "server_roles"(server_id, name)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "member_roles" (
+            server_id TEXT NOT NULL REFERENCES "servers"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            role_id TEXT NOT NULL REFERENCES "server_roles"(id) ON DELETE CASCADE,
+            assigned_at TEXT NOT NULL,
+            PRIMARY KEY (user_id, role_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_member_roles_server ON "member_roles"(server_id, user_id)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // "React to get a role": one binding per emoji on a message
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "reaction_roles" (
+            id TEXT PRIMARY KEY,
+            server_id TEXT NOT NULL REFERENCES "servers"(id) ON DELETE CASCADE,
+            message_id TEXT NOT NULL REFERENCES "messages"(id) ON DELETE CASCADE,
+            emoji TEXT NOT NULL,
+            role_id TEXT NOT NULL REFERENCES "server_roles"(id) ON DELETE CASCADE,
+            created_by TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE UNIQUE INDEX IF NOT EXISTS idx_reaction_roles_message ON "reaction_roles"(message_id, emoji)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(r#"ALTER TABLE "memberships" ADD COLUMN rules_accepted_at TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Welcome screen shown to new members, and the rules they accept
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "server_welcome" (
+            server_id TEXT PRIMARY KEY REFERENCES "servers"(id) ON DELETE CASCADE,
+            message TEXT,
+            rules TEXT,
+            suggested_channel_ids TEXT NOT NULL DEFAULT '[]',
+            rules_updated_at TEXT,
+            updated_by TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // New-member gating: what members must meet before they can post
+    sqlx::query(r#"ALTER TABLE "servers" ADD COLUMN min_account_age_minutes INTEGER NOT NULL DEFAULT 0"#)
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query(r#"ALTER TABLE "servers" ADD COLUMN require_verified_email INTEGER NOT NULL DEFAULT 0"#)
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query(r#"ALTER TABLE "servers" ADD COLUMN require_challenge INTEGER NOT NULL DEFAULT 0"#)
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query(r#"ALTER TABLE "memberships" ADD COLUMN challenge_passed_at TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // DM requests: a new conversation stays 'pending' until the recipient
+    // accepts (or 'declined'). Conversations from before requests existed
+    // count as accepted.
+    sqlx::query(r#"ALTER TABLE "dm_channels" ADD COLUMN status TEXT NOT NULL DEFAULT 'accepted'"#)
+        .execute(&pool)
+        .await
+        .ok();
+    sqlx::query(r#"ALTER TABLE "dm_channels" ADD COLUMN requested_by TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Users someone has blocked. Either side of a block is hidden from the
+    // other: no DMs, reactions, mentions, invites, games or typing.
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "blocked_users" (
+            blocker_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            blocked_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (blocker_id, blocked_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_blocked_users_blocked ON "blocked_users"(blocked_id)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Preferred voice region; NULL picks the node closest to whoever joins first
+    sqlx::query(r#"ALTER TABLE "channels" ADD COLUMN voice_region TEXT"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // LiveKit nodes voice can be routed to. With none, everything goes to
+    // the configured livekit_url. Empty credentials fall back to the
+    // configured API key and secret.
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "voice_nodes" (
+            id TEXT PRIMARY KEY,
+            region TEXT NOT NULL,
+            url TEXT NOT NULL,
+            api_key TEXT NOT NULL DEFAULT '',
+            api_secret TEXT NOT NULL DEFAULT '',
+            enabled INTEGER NOT NULL DEFAULT 1,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Each user's last measured round trip to each node
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "voice_latency" (
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            node_id TEXT NOT NULL REFERENCES "voice_nodes"(id) ON DELETE CASCADE,
+            rtt_ms INTEGER NOT NULL,
+            measured_at TEXT NOT NULL,
+            PRIMARY KEY (user_id, node_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // The node a voice channel's call is running on, so everyone in it
+    // lands on the same one
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "voice_room_nodes" (
+            channel_id TEXT PRIMARY KEY REFERENCES "channels"(id) ON DELETE CASCADE,
+            node_id TEXT NOT NULL REFERENCES "voice_nodes"(id) ON DELETE CASCADE,
+            assigned_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Stage channels: who may speak, and listeners waiting to be brought up
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "stage_speakers" (
+            channel_id TEXT NOT NULL REFERENCES "channels"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            added_by TEXT NOT NULL,
+            added_at TEXT NOT NULL,
+            PRIMARY KEY (channel_id, user_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "stage_hands" (
+            channel_id TEXT NOT NULL REFERENCES "channels"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            raised_at TEXT NOT NULL,
+            PRIMARY KEY (channel_id, user_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Call quality samples clients report while in voice
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "voice_quality_samples" (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            server_id TEXT NOT NULL,
+            channel_id TEXT NOT NULL REFERENCES "channels"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL,
+            rtt_ms REAL NOT NULL,
+            jitter_ms REAL NOT NULL,
+            packet_loss REAL NOT NULL,
+            sampled_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_voice_quality_server ON "voice_quality_samples"(server_id, sampled_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_voice_quality_channel ON "voice_quality_samples"(channel_id, sampled_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Client settings synced between a user's devices (key bindings etc.)
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "user_settings" (
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (user_id, key)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // How far each user has read in each channel or DM, for email digests
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "read_states" (
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            channel_id TEXT NOT NULL,
+            last_read_at TEXT NOT NULL,
+            PRIMARY KEY (user_id, channel_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Opt-in email digests; last_sent_at is where the next one starts
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "email_digests" (
+            user_id TEXT PRIMARY KEY REFERENCES "user"(id) ON DELETE CASCADE,
+            frequency TEXT NOT NULL,
+            last_sent_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Outgoing emails waiting to be (re)sent
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "mail_queue" (
+            id TEXT PRIMARY KEY,
+            recipient TEXT NOT NULL,
+            subject TEXT NOT NULL,
+            text_body TEXT NOT NULL,
+            html_body TEXT,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            next_attempt_at TEXT NOT NULL,
+            last_error TEXT,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_mail_queue_next ON "mail_queue"(next_attempt_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Addresses and ranges instance admins have banned
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "ip_bans" (
+            id TEXT PRIMARY KEY,
+            network TEXT NOT NULL UNIQUE,
+            reason TEXT,
+            created_by TEXT REFERENCES "user"(id) ON DELETE SET NULL,
+            created_at TEXT NOT NULL,
+            expires_at TEXT
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
     tracing::info!("Database initialized at {}", database_path);
     Ok(pool)
 }
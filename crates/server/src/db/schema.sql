@@ -83,6 +83,29 @@ CREATE TABLE IF NOT EXISTS "email_whitelist" (
     added_at TEXT NOT NULL
 );
 
+-- One-time signup invites (only a hash of the token is kept)
+CREATE TABLE IF NOT EXISTS "signup_invites" (
+    id TEXT PRIMARY KEY,
+    token_hash TEXT UNIQUE NOT NULL,
+    note TEXT,
+    created_by TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    used_by TEXT REFERENCES "user"(id) ON DELETE SET NULL,
+    used_at TEXT
+);
+
+-- Emails asking to be whitelisted
+CREATE TABLE IF NOT EXISTS "access_requests" (
+    id TEXT PRIMARY KEY,
+    email TEXT UNIQUE NOT NULL,
+    reason TEXT NOT NULL DEFAULT '',
+    status TEXT NOT NULL DEFAULT 'pending',
+    created_at TEXT NOT NULL,
+    resolved_by TEXT REFERENCES "user"(id) ON DELETE SET NULL,
+    resolved_at TEXT
+);
+
 CREATE TABLE IF NOT EXISTS "reactions" (
     id TEXT PRIMARY KEY,
     message_id TEXT NOT NULL REFERENCES "messages"(id) ON DELETE CASCADE,
@@ -187,14 +210,16 @@ CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
     tokenize='porter unicode61'
 );
 
--- E2EE: server encryption keys (group key wrapped per-member)
+-- E2EE: server encryption keys (group key wrapped per-member, per-device;
+-- device_id '' is the account-level key from before multi-device)
 CREATE TABLE IF NOT EXISTS "server_keys" (
     server_id TEXT NOT NULL REFERENCES "servers"(id) ON DELETE CASCADE,
     user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+    device_id TEXT NOT NULL DEFAULT '',
     encrypted_key TEXT NOT NULL,
     sender_id TEXT NOT NULL,
     created_at TEXT NOT NULL,
-    PRIMARY KEY (server_id, user_id)
+    PRIMARY KEY (server_id, user_id, device_id)
 );
 
 -- Roadmap items
@@ -211,6 +236,25 @@ CREATE TABLE IF NOT EXISTS "roadmap_items" (
 );
 CREATE INDEX IF NOT EXISTS idx_roadmap_server ON roadmap_items(server_id);
 
+-- One upvote per member per roadmap item
+CREATE TABLE IF NOT EXISTS "roadmap_votes" (
+    item_id TEXT NOT NULL REFERENCES "roadmap_items"(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (item_id, user_id)
+);
+
+-- Threaded discussion on roadmap items
+CREATE TABLE IF NOT EXISTS "roadmap_comments" (
+    id TEXT PRIMARY KEY,
+    item_id TEXT NOT NULL REFERENCES "roadmap_items"(id) ON DELETE CASCADE,
+    parent_id TEXT REFERENCES "roadmap_comments"(id) ON DELETE CASCADE,
+    author_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_roadmap_comments_item ON roadmap_comments(item_id, created_at);
+
 -- Gallery sets (global, any user can create)
 CREATE TABLE IF NOT EXISTS "gallery_sets" (
     id TEXT PRIMARY KEY,
@@ -241,3 +285,12 @@ CREATE TABLE IF NOT EXISTS "gallery_subscriptions" (
     created_at TEXT NOT NULL,
     PRIMARY KEY (user_id, set_id)
 );
+
+-- Co-editors of a gallery set, added by its creator
+CREATE TABLE IF NOT EXISTS "gallery_collaborators" (
+    set_id TEXT NOT NULL REFERENCES "gallery_sets"(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (set_id, user_id)
+);
+CREATE INDEX IF NOT EXISTS idx_gallery_collaborators_user ON gallery_collaborators(user_id);
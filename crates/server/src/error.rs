@@ -0,0 +1,197 @@
+use axum::{
+    http::{
+        header::{CONTENT_LANGUAGE, RETRY_AFTER},
+        HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Response},
+    Json,
+};
+
+/// An error from a route handler. Renders as `{"error": message, "code": code}`
+/// with the matching status; `code` is stable for clients to branch on, the
+/// message is for people and is translated into the request's language when
+/// the catalog in `flux_shared::i18n` has it.
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Unauthorized(String),
+    Forbidden(String),
+    NotFound(String),
+    Conflict(String),
+    PayloadTooLarge(String),
+    RateLimited { message: String, retry_after: i64 },
+    /// A feature that isn't configured on this server.
+    Unavailable(String),
+    /// An upstream service failed or sent something unusable.
+    Upstream(String),
+    UpstreamTimeout(String),
+    Internal(String),
+    /// A failed query. The details are logged, not sent.
+    Database(sqlx::Error),
+    /// Another error with extra fields merged into its body.
+    WithDetails(Box<ApiError>, serde_json::Value),
+}
+
+impl ApiError {
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::BadRequest(message.into())
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::Unauthorized(message.into())
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::Forbidden(message.into())
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::NotFound(message.into())
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::Conflict(message.into())
+    }
+
+    pub fn payload_too_large(message: impl Into<String>) -> Self {
+        Self::PayloadTooLarge(message.into())
+    }
+
+    pub fn rate_limited(message: impl Into<String>, retry_after: i64) -> Self {
+        Self::RateLimited { message: message.into(), retry_after }
+    }
+
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self::Unavailable(message.into())
+    }
+
+    pub fn upstream(message: impl Into<String>) -> Self {
+        Self::Upstream(message.into())
+    }
+
+    pub fn upstream_timeout(message: impl Into<String>) -> Self {
+        Self::UpstreamTimeout(message.into())
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::Internal(message.into())
+    }
+
+    /// Add fields to the response body, e.g. what to fix in a rejected value.
+    pub fn with_details(self, details: serde_json::Value) -> Self {
+        Self::WithDetails(Box::new(self), details)
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Upstream(_) => StatusCode::BAD_GATEWAY,
+            Self::UpstreamTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            Self::Internal(_) | Self::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::WithDetails(inner, _) => inner.status(),
+        }
+    }
+
+    /// Machine-readable error code.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::BadRequest(_) => "bad_request",
+            Self::Unauthorized(_) => "unauthorized",
+            Self::Forbidden(_) => "forbidden",
+            Self::NotFound(_) => "not_found",
+            Self::Conflict(_) => "conflict",
+            Self::PayloadTooLarge(_) => "payload_too_large",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::Unavailable(_) => "unavailable",
+            Self::Upstream(_) => "upstream_error",
+            Self::UpstreamTimeout(_) => "upstream_timeout",
+            Self::Internal(_) => "internal",
+            Self::Database(_) => "database",
+            Self::WithDetails(inner, _) => inner.code(),
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            Self::BadRequest(m)
+            | Self::Unauthorized(m)
+            | Self::Forbidden(m)
+            | Self::NotFound(m)
+            | Self::Conflict(m)
+            | Self::PayloadTooLarge(m)
+            | Self::RateLimited { message: m, .. }
+            | Self::Unavailable(m)
+            | Self::Upstream(m)
+            | Self::UpstreamTimeout(m)
+            | Self::Internal(m) => m,
+            Self::Database(_) => "Database error",
+            Self::WithDetails(inner, _) => inner.message(),
+        }
+    }
+
+    fn retry_after(&self) -> Option<i64> {
+        match self {
+            Self::RateLimited { retry_after, .. } => Some(*retry_after),
+            Self::WithDetails(inner, _) => inner.retry_after(),
+            _ => None,
+        }
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(e: sqlx::Error) -> Self {
+        Self::Database(e)
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Database(e) => write!(f, "database error: {}", e),
+            _ => write!(f, "{}", self.message()),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        if status.is_server_error() {
+            tracing::error!("{} ({})", self, self.code());
+        }
+
+        let locale = crate::middleware::locale::current();
+        let mut body = serde_json::json!({
+            "error": flux_shared::i18n::translate(locale, self.message()),
+            "code": self.code(),
+        });
+        if let Some(retry_after) = self.retry_after() {
+            body["retryAfter"] = retry_after.into();
+        }
+        if let Self::WithDetails(_, serde_json::Value::Object(details)) = &self {
+            for (key, value) in details {
+                body[key] = value.clone();
+            }
+        }
+
+        let mut response = (status, Json(body)).into_response();
+        response
+            .headers_mut()
+            .insert(CONTENT_LANGUAGE, HeaderValue::from_static(locale.tag()));
+        if let Some(retry_after) = self.retry_after() {
+            if let Ok(value) = retry_after.to_string().parse() {
+                response.headers_mut().insert(RETRY_AFTER, value);
+            }
+        }
+        response
+    }
+}
@@ -1,8 +1,16 @@
+pub mod backup;
+pub mod bootstrap;
 pub mod config;
 pub mod db;
+pub mod error;
+pub mod listen;
+pub mod mailer;
+pub mod metrics;
 pub mod middleware;
 pub mod models;
 pub mod routes;
+pub mod telemetry;
+pub mod tls;
 pub mod ws;
 
 use config::Config;
@@ -10,8 +18,29 @@ use std::sync::Arc;
 
 pub struct AppState {
     pub db: sqlx::SqlitePool,
+    /// Read-only connections for list and search endpoints, so long reads
+    /// don't tie up connections writers are waiting for.
+    pub db_read: sqlx::SqlitePool,
+    /// A single connection for hot write paths (message sends, edits,
+    /// reactions). They queue here instead of fighting over SQLite's lock.
+    pub db_write: sqlx::SqlitePool,
     pub config: Config,
     pub gateway: Arc<ws::gateway::GatewayState>,
     pub spotify_auth_pending: tokio::sync::RwLock<std::collections::HashMap<String, (String, String)>>,
+    /// Last.fm auth nonce -> user id
+    pub lastfm_auth_pending: tokio::sync::RwLock<std::collections::HashMap<String, String>>,
+    /// OIDC sign-ins in progress, by `state`.
+    pub oidc_pending: tokio::sync::RwLock<std::collections::HashMap<String, routes::auth::OidcPending>>,
     pub youtube_url_cache: tokio::sync::RwLock<std::collections::HashMap<String, (String, std::time::Instant)>>,
+    /// Video ids with a cache download in flight.
+    pub youtube_downloads: tokio::sync::RwLock<std::collections::HashSet<String>>,
+    pub gif_search_cache: tokio::sync::RwLock<std::collections::HashMap<String, (Vec<routes::gifs::GifResult>, std::time::Instant)>>,
+    /// Server insights reports keyed by "serverId:days"
+    pub insights_cache: tokio::sync::RwLock<std::collections::HashMap<String, (serde_json::Value, std::time::Instant)>>,
+    pub lyrics_cache: tokio::sync::RwLock<routes::spotify::LyricsCache>,
+    /// Noise suppression model files cached on disk.
+    pub noise_models: tokio::sync::RwLock<routes::noise_models::NoiseModels>,
+    pub gif_rate_limits: tokio::sync::RwLock<std::collections::HashMap<String, (std::time::Instant, u32)>>,
+    /// Trusted proxies, path allowlists and banned addresses.
+    pub ip_filter: middleware::ip_filter::IpFilter,
 }
@@ -0,0 +1,114 @@
+//! Where the server takes connections from: `host:port`, a Unix socket path
+//! for a reverse proxy on the same machine, or a socket systemd opened for
+//! us (socket activation).
+
+use std::io;
+use tokio::net::TcpListener;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+
+use crate::config::Config;
+
+/// The first file descriptor systemd passes, after stdin, stdout and stderr.
+#[cfg(unix)]
+const SD_LISTEN_FDS_START: i32 = 3;
+
+pub enum ServerListener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl ServerListener {
+    /// Where clients connect, for the startup log.
+    pub fn describe(&self) -> String {
+        match self {
+            ServerListener::Tcp(tcp) => tcp
+                .local_addr()
+                .map(|a| a.to_string())
+                .unwrap_or_else(|_| "a TCP socket".into()),
+            #[cfg(unix)]
+            ServerListener::Unix(unix) => {
+                let path = unix.local_addr().ok().and_then(|a| a.as_pathname().map(|p| p.to_owned()));
+                match path {
+                    Some(path) => format!("unix:{}", path.display()),
+                    None => "an unnamed Unix socket".into(),
+                }
+            }
+        }
+    }
+}
+
+/// The socket to serve on: one passed by systemd if there is one, otherwise
+/// `unix_socket` if set, otherwise `host:port`.
+pub async fn bind(config: &Config) -> io::Result<ServerListener> {
+    #[cfg(unix)]
+    {
+        if let Some(fd) = systemd_socket() {
+            return adopt(fd);
+        }
+        if !config.unix_socket.is_empty() {
+            return bind_unix(config);
+        }
+    }
+    let addr = format!("{}:{}", config.host, config.port);
+    Ok(ServerListener::Tcp(TcpListener::bind(&addr).await?))
+}
+
+/// The listening socket systemd passed to this process, per sd_listen_fds(3).
+/// Only the first is used; a unit with several `Listen*=` lines would need
+/// to say which is which.
+#[cfg(unix)]
+fn systemd_socket() -> Option<i32> {
+    let pid = std::env::var("LISTEN_PID").ok()?.parse::<u32>().ok()?;
+    if pid != std::process::id() {
+        return None;
+    }
+    let fds = std::env::var("LISTEN_FDS").ok()?.parse::<i32>().ok()?;
+    if fds > 1 {
+        tracing::warn!("systemd passed {} sockets; serving only the first", fds);
+    }
+    (fds >= 1).then_some(SD_LISTEN_FDS_START)
+}
+
+/// Take over an already listening socket, TCP or Unix.
+#[cfg(unix)]
+pub fn adopt(fd: i32) -> io::Result<ServerListener> {
+    use std::os::fd::FromRawFd;
+
+    // SAFETY: the fd was handed to us to own, and nothing else uses it
+    let socket = unsafe { socket2::Socket::from_raw_fd(fd) };
+    socket.set_nonblocking(true)?;
+    if socket.local_addr()?.is_unix() {
+        let std = std::os::unix::net::UnixListener::from(socket);
+        Ok(ServerListener::Unix(UnixListener::from_std(std)?))
+    } else {
+        let std = std::net::TcpListener::from(socket);
+        Ok(ServerListener::Tcp(TcpListener::from_std(std)?))
+    }
+}
+
+/// Bind `unix_socket`, clearing out a socket left behind by an earlier run.
+/// Anything at the path that isn't a socket is left alone.
+#[cfg(unix)]
+fn bind_unix(config: &Config) -> io::Result<ServerListener> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    let path = &config.unix_socket;
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_socket() => std::fs::remove_file(path)?,
+        Ok(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} exists and isn't a socket", path),
+            ))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+
+    let listener = UnixListener::bind(path)?;
+    let mode = u32::from_str_radix(&config.unix_socket_mode, 8).unwrap_or(0o660);
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    Ok(ServerListener::Unix(listener))
+}
@@ -0,0 +1,39 @@
+//! Outgoing email. Messages are rendered from the templates in `templates/`,
+//! queued so a flaky relay gets retried, and sent over SMTP, or only logged
+//! when `mail_log_only` is set for development.
+
+mod queue;
+mod smtp;
+mod templates;
+
+pub use queue::{queue, retry_queued, run_mail_queue};
+pub use templates::render;
+
+use crate::config::Config;
+
+/// A rendered message, ready to send.
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+    /// Sent alongside `text` as an alternative when present.
+    pub html: Option<String>,
+}
+
+/// Whether outgoing email is configured.
+pub fn enabled(config: &Config) -> bool {
+    config.mail_log_only || !config.smtp_host.is_empty()
+}
+
+/// Send right away, without retries. Most callers want [`queue`].
+pub async fn send(config: &Config, email: &Email) -> Result<(), String> {
+    if config.mail_log_only {
+        tracing::info!(to = %email.to, subject = %email.subject, "Email not sent (mail_log_only):\n{}", email.text);
+        return Ok(());
+    }
+    if config.smtp_host.is_empty() {
+        return Err("SMTP is not configured".into());
+    }
+    smtp::send(config, email).await
+}
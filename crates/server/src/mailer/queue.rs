@@ -0,0 +1,130 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+use super::Email;
+use crate::config::Config;
+use crate::AppState;
+
+/// How often the background task looks for emails to retry.
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+/// How long the retry task leaves a message alone while it's being sent, so
+/// it isn't sent twice. One that's still queued after this was interrupted.
+const SENDING_LEASE_SECS: i64 = 120;
+const MAX_ATTEMPTS: i64 = 8;
+
+#[derive(sqlx::FromRow)]
+struct QueuedEmail {
+    id: String,
+    recipient: String,
+    subject: String,
+    text_body: String,
+    html_body: Option<String>,
+    attempts: i64,
+}
+
+/// Store an email and send it in the background. If the relay fails it's
+/// retried with backoff by [`run_mail_queue`], across restarts too.
+pub async fn queue(state: &AppState, email: Email) {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now();
+    let stored = sqlx::query(
+        r#"INSERT INTO "mail_queue" (id, recipient, subject, text_body, html_body, attempts, next_attempt_at, created_at)
+           VALUES (?, ?, ?, ?, ?, 0, ?, ?)"#,
+    )
+    .bind(&id)
+    .bind(&email.to)
+    .bind(&email.subject)
+    .bind(&email.text)
+    .bind(&email.html)
+    .bind((now + chrono::Duration::seconds(SENDING_LEASE_SECS)).to_rfc3339())
+    .bind(now.to_rfc3339())
+    .execute(&state.db)
+    .await;
+    // Still worth one try without the queue
+    if let Err(e) = stored {
+        tracing::error!("Failed to queue email: {}", e);
+    }
+
+    let (db, config) = (state.db.clone(), state.config.clone());
+    tokio::spawn(async move {
+        attempt(&db, &config, &id, 0, &email).await;
+    });
+}
+
+/// Try once, then drop the message on success or schedule the next try.
+async fn attempt(db: &SqlitePool, config: &Config, id: &str, attempts: i64, email: &Email) -> bool {
+    let err = match super::send(config, email).await {
+        Ok(()) => {
+            let _ = sqlx::query(r#"DELETE FROM "mail_queue" WHERE id = ?"#).bind(id).execute(db).await;
+            return true;
+        }
+        Err(e) => e,
+    };
+
+    let attempts = attempts + 1;
+    if attempts >= MAX_ATTEMPTS {
+        tracing::error!("Giving up on \"{}\" email to {} after {} attempts: {}", email.subject, email.to, attempts, err);
+        let _ = sqlx::query(r#"DELETE FROM "mail_queue" WHERE id = ?"#).bind(id).execute(db).await;
+        return false;
+    }
+
+    // 30s, doubling up to an hour
+    let backoff = (30i64 << (attempts - 1).min(7)).min(3600);
+    tracing::warn!("Failed to send \"{}\" email to {}, retrying in {}s: {}", email.subject, email.to, backoff, err);
+    let _ = sqlx::query(
+        r#"UPDATE "mail_queue" SET attempts = ?, next_attempt_at = ?, last_error = ? WHERE id = ?"#,
+    )
+    .bind(attempts)
+    .bind((Utc::now() + chrono::Duration::seconds(backoff)).to_rfc3339())
+    .bind(&err)
+    .bind(id)
+    .execute(db)
+    .await;
+    false
+}
+
+/// Send every queued email whose next attempt is due. Returns how many
+/// went out.
+pub async fn retry_queued(state: &AppState) -> usize {
+    let now = Utc::now();
+    // Claim them first, so a slow relay doesn't get the same ones next round
+    let due = sqlx::query_as::<_, QueuedEmail>(
+        r#"UPDATE "mail_queue" SET next_attempt_at = ?
+           WHERE next_attempt_at <= ?
+           RETURNING id, recipient, subject, text_body, html_body, attempts"#,
+    )
+    .bind((now + chrono::Duration::seconds(SENDING_LEASE_SECS)).to_rfc3339())
+    .bind(now.to_rfc3339())
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let mut sent = 0;
+    for row in due {
+        let email = Email {
+            to: row.recipient,
+            subject: row.subject,
+            text: row.text_body,
+            html: row.html_body,
+        };
+        if attempt(&state.db, &state.config, &row.id, row.attempts, &email).await {
+            sent += 1;
+        }
+    }
+    sent
+}
+
+/// Background task: retry failed emails every 30 seconds, forever.
+pub async fn run_mail_queue(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(RETRY_INTERVAL);
+    loop {
+        interval.tick().await;
+        let sent = retry_queued(&state).await;
+        if sent > 0 {
+            tracing::info!("Sent {} queued emails", sent);
+        }
+    }
+}
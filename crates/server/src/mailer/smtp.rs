@@ -0,0 +1,44 @@
+use lettre::message::{MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use super::Email;
+use crate::config::Config;
+
+fn transport(config: &Config) -> Result<AsyncSmtpTransport<Tokio1Executor>, String> {
+    let builder = match config.smtp_security.as_str() {
+        "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host).map_err(|e| e.to_string())?,
+        "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host),
+        _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host).map_err(|e| e.to_string())?,
+    };
+    let builder = builder.port(config.smtp_port);
+    let builder = if config.smtp_username.is_empty() {
+        builder
+    } else {
+        builder.credentials(Credentials::new(
+            config.smtp_username.clone(),
+            config.smtp_password.clone(),
+        ))
+    };
+    Ok(builder.build())
+}
+
+/// Deliver through the configured relay: plain text, plus the HTML version
+/// as an alternative when there is one.
+pub(super) async fn send(config: &Config, email: &Email) -> Result<(), String> {
+    let builder = Message::builder()
+        .from(config.smtp_from.parse().map_err(|e| format!("bad SMTP_FROM: {}", e))?)
+        .to(email.to.parse().map_err(|e| format!("bad recipient: {}", e))?)
+        .subject(&email.subject);
+    let message = match &email.html {
+        Some(html) => builder.multipart(MultiPart::alternative_plain_html(email.text.clone(), html.clone())),
+        None => builder.singlepart(SinglePart::plain(email.text.clone())),
+    }
+    .map_err(|e| e.to_string())?;
+
+    transport(config)?
+        .send(message)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,51 @@
+use std::sync::LazyLock;
+
+use minijinja::{context, Environment, Value};
+
+use super::Email;
+
+/// Every email, as `<name>.txt` and optionally `<name>.html`. HTML ones
+/// extend `layout.html` and are autoescaped.
+const TEMPLATES: &[(&str, &str)] = &[
+    ("layout.html", include_str!("templates/layout.html")),
+    ("verify_email.txt", include_str!("templates/verify_email.txt")),
+    ("verify_email.html", include_str!("templates/verify_email.html")),
+    ("password_reset.txt", include_str!("templates/password_reset.txt")),
+    ("password_reset.html", include_str!("templates/password_reset.html")),
+    ("login_locked.txt", include_str!("templates/login_locked.txt")),
+    ("login_locked.html", include_str!("templates/login_locked.html")),
+    ("access_approved.txt", include_str!("templates/access_approved.txt")),
+    ("access_approved.html", include_str!("templates/access_approved.html")),
+    ("digest.txt", include_str!("templates/digest.txt")),
+    ("digest.html", include_str!("templates/digest.html")),
+];
+
+static ENV: LazyLock<Environment<'static>> = LazyLock::new(|| {
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env.set_keep_trailing_newline(true);
+    for (name, source) in TEMPLATES {
+        env.add_template(name, source).expect("email templates are valid");
+    }
+    env
+});
+
+/// Render the `name` email for `to`. `subject` is also available to the
+/// templates.
+pub fn render(name: &str, to: &str, subject: &str, ctx: Value) -> Result<Email, String> {
+    let ctx = context! { subject, ..ctx };
+    let render = |file: String| ENV.get_template(&file).and_then(|t| t.render(&ctx)).map_err(|e| e.to_string());
+
+    let text = render(format!("{}.txt", name))?;
+    let html = match ENV.get_template(&format!("{}.html", name)) {
+        Ok(_) => Some(render(format!("{}.html", name))?),
+        Err(_) => None,
+    };
+    Ok(Email {
+        to: to.to_string(),
+        subject: subject.to_string(),
+        text,
+        html,
+    })
+}
@@ -1,6 +1,7 @@
-use flux_server::{config::Config, db, routes, ws, AppState};
+use flux_server::middleware::ip_filter::{IpFilter, PeerAddr};
+use flux_server::listen::{self, ServerListener};
+use flux_server::{backup, bootstrap, config::Config, db, mailer, routes, telemetry, tls, ws, AppState};
 use std::sync::Arc;
-use tokio::net::TcpListener;
 use axum::http::{HeaderName, Method};
 use tower_http::cors::CorsLayer;
 
@@ -9,34 +10,87 @@ async fn main() {
     // Load .env if present
     dotenvy::dotenv().ok();
 
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "flux_server=info".into()),
-        )
-        .init();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let config_file = args
+        .iter()
+        .position(|a| a == "--config")
+        .and_then(|i| args.get(i + 1))
+        .map(std::path::PathBuf::from);
+    let config = match Config::load(config_file.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if args.iter().any(|a| a == "--print-config") {
+        print!("{}", config.to_redacted_toml());
+        return;
+    }
 
-    let config = Config::from_env();
+    // Initialize tracing (and OTLP export, if configured)
+    let tracer_provider = telemetry::init(&config);
 
     // Create upload directory
     tokio::fs::create_dir_all(&config.upload_dir)
         .await
         .expect("Failed to create upload directory");
 
+    // Restore a backup over the database before anything opens it
+    let restore_from = args
+        .iter()
+        .position(|a| a == "--restore-from")
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+        .unwrap_or_else(|| config.restore_from.clone());
+    if !restore_from.is_empty() {
+        match backup::restore_at_startup(&config, &restore_from).await {
+            Ok(source) => tracing::warn!("Restored {} from {}", config.database_path, source.display()),
+            Err(e) => {
+                tracing::error!("Restore failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     // Initialize database
-    let pool = db::init_pool(&config.database_path)
+    let pools = db::init_pools(&config.database_path, db::PoolSettings::from_config(&config))
         .await
         .expect("Failed to initialize database");
 
     let state = Arc::new(AppState {
-        db: pool,
+        db: pools.db,
+        db_read: pools.read,
+        db_write: pools.write,
         config: config.clone(),
         gateway: Arc::new(ws::gateway::GatewayState::new()),
         spotify_auth_pending: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        lastfm_auth_pending: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        oidc_pending: tokio::sync::RwLock::new(std::collections::HashMap::new()),
         youtube_url_cache: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        youtube_downloads: tokio::sync::RwLock::new(std::collections::HashSet::new()),
+        gif_search_cache: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        insights_cache: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        gif_rate_limits: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        lyrics_cache: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        noise_models: tokio::sync::RwLock::new(Default::default()),
+        ip_filter: IpFilter::new(&config),
     });
 
+    if let Err(e) = state.ip_filter.reload_bans(&state.db).await {
+        tracing::error!("Failed to load IP bans: {}", e);
+    }
+
+    // The admin account from the environment, before anyone can sign up
+    if let Err(e) = bootstrap::run(&state).await {
+        tracing::error!("Bootstrap failed: {}", e);
+        std::process::exit(1);
+    }
+    if args.iter().any(|a| a == "--bootstrap-only") {
+        tracing::info!("Bootstrap finished");
+        return;
+    }
+
     // Clean up stale rooms from previous server sessions
     // (in-memory cleanup timers are lost on restart, so empty temp rooms linger in the DB)
     let cleaned = sqlx::query("DELETE FROM channels WHERE is_room = 1")
@@ -48,6 +102,55 @@ async fn main() {
         tracing::info!("Cleaned up {} stale room(s)", cleaned);
     }
 
+    // Voice sessions left open by a restart end when they were last seen
+    let orphaned = routes::voice::close_orphaned_voice_sessions(&state).await;
+    if orphaned > 0 {
+        tracing::info!("Closed {} voice session(s) left open by the last run", orphaned);
+    }
+
+    // Cache the noise suppression models clients load
+    tokio::spawn(routes::noise_models::run_noise_model_sync(state.clone()));
+
+    // Scheduled database backups
+    tokio::spawn(backup::run_scheduled_backups(state.clone()));
+
+    // Sample online users for the admin dashboard
+    tokio::spawn(routes::admin::run_online_sampler(state.clone()));
+
+    // Pay voice-minute rewards
+    tokio::spawn(routes::economy::run_voice_rewards(state.clone()));
+
+    // Post new RSS/Atom entries into their channels
+    if state.config.feed_poll_interval_secs > 0 {
+        tokio::spawn(routes::feeds::run_feed_poller(state.clone()));
+    }
+
+    // Fire "remind me about this message" reminders
+    tokio::spawn(routes::messages::run_reminder_dispatcher(state.clone()));
+
+    // Delete messages past their server, channel or DM retention
+    tokio::spawn(routes::messages::run_retention_purge(state.clone()));
+
+    // Retry emails the relay failed to take
+    if mailer::enabled(&state.config) {
+        tokio::spawn(mailer::run_mail_queue(state.clone()));
+    }
+
+    // Email opted-in users a digest of the mentions and DMs they missed
+    if mailer::enabled(&state.config) {
+        tokio::spawn(routes::users::run_email_digests(state.clone()));
+    }
+
+    // Remind RSVP'd members shortly before events start
+    if state.config.event_reminder_minutes > 0 {
+        tokio::spawn(routes::events::run_event_reminders(state.clone()));
+    }
+
+    // Submit Last.fm scrobbles in the background (retries when Last.fm is down)
+    if routes::lastfm::credentials().is_some() {
+        tokio::spawn(routes::lastfm::run_scrobbler(state.clone()));
+    }
+
     // Check for yt-dlp
     match tokio::process::Command::new("yt-dlp").arg("--version").output().await {
         Ok(output) if output.status.success() => {
@@ -76,16 +179,79 @@ async fn main() {
                     HeaderName::from_static("content-type"),
                     HeaderName::from_static("cookie"),
                     HeaderName::from_static("authorization"),
+                    HeaderName::from_static("x-csrf-token"),
+                    HeaderName::from_static("traceparent"),
+                    HeaderName::from_static("tracestate"),
+                    HeaderName::from_static("if-none-match"),
                 ])
+                .expose_headers([HeaderName::from_static("etag")])
                 .allow_credentials(true),
         );
 
-    let addr = format!("{}:{}", config.host, config.port);
-    let listener = TcpListener::bind(&addr).await.expect("Failed to bind");
+    let listener = match listen::bind(&config).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!("Failed to bind: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let addr = listener.describe();
 
-    tracing::info!("Flux server running on {}", addr);
+    // Peer addresses, for clients that don't come through a trusted proxy
+    let app = app.into_make_service_with_connect_info::<PeerAddr>();
+    let shutdown = async {
+        tokio::signal::ctrl_c().await.ok();
+    };
 
-    axum::serve(listener, app)
-        .await
-        .expect("Server error");
+    match listener {
+        ServerListener::Tcp(listener) if tls::enabled(&config) => {
+            let (acceptor, certs) = match tls::acceptor(&config) {
+                Ok(tls) => tls,
+                Err(e) => {
+                    tracing::error!("Failed to load TLS certificate: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            if config.tls_redirect_port > 0 {
+                tokio::spawn(tls::run_https_redirect(config.clone(), certs.challenges()));
+            }
+            if tls::acme::enabled(&config) {
+                tokio::spawn(tls::acme::run_acme(config.clone(), certs.clone()));
+            }
+            tokio::spawn(tls::run_cert_reloader(certs));
+
+            tracing::info!("Flux server running on https://{}", addr);
+            let listener = tls::TlsListener::new(listener, acceptor).expect("Failed to bind");
+            axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown)
+                .await
+                .expect("Server error");
+        }
+        ServerListener::Tcp(listener) => {
+            tracing::info!("Flux server running on {}", addr);
+            axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown)
+                .await
+                .expect("Server error");
+        }
+        #[cfg(unix)]
+        ServerListener::Unix(listener) => {
+            if tls::enabled(&config) {
+                tracing::error!("TLS can't be served on a Unix socket; leave it to the proxy");
+                std::process::exit(1);
+            }
+            tracing::info!("Flux server running on {}", addr);
+            axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown)
+                .await
+                .expect("Server error");
+        }
+    }
+
+    // Flush spans still waiting in the batch
+    if let Some(provider) = tracer_provider {
+        if let Err(e) = provider.shutdown() {
+            tracing::warn!("Failed to flush traces: {}", e);
+        }
+    }
 }
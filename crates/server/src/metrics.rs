@@ -0,0 +1,131 @@
+use ::metrics::{counter, gauge, histogram};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+
+use crate::AppState;
+
+static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Servers that had voice participants at the last scrape, so their gauge
+/// can drop back to zero when the last person leaves.
+static VOICE_SERVERS: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Voice channels whose recent call quality is below the warning thresholds.
+static DEGRADED_VOICE_CHANNELS: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+const LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+const FANOUT_BUCKETS: &[f64] = &[0.0, 1.0, 2.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0];
+
+/// Install the Prometheus recorder. The recorder is process-wide, so this only
+/// does anything the first time; later calls return the same handle.
+pub fn install() -> &'static PrometheusHandle {
+    HANDLE.get_or_init(|| {
+        let recorder = PrometheusBuilder::new()
+            .set_buckets_for_metric(Matcher::Full("http_request_duration_seconds".into()), LATENCY_BUCKETS)
+            .and_then(|b| b.set_buckets_for_metric(Matcher::Full("gateway_broadcast_recipients".into()), FANOUT_BUCKETS))
+            .expect("bucket lists are not empty")
+            .build_recorder();
+        let handle = recorder.handle();
+        if ::metrics::set_global_recorder(recorder).is_err() {
+            tracing::warn!("A metrics recorder is already installed; /metrics will be empty");
+        }
+        handle
+    })
+}
+
+/// Count an event the gateway sent and how many clients it reached. `scope`
+/// is who it was addressed to: a channel, a DM, one user, everyone.
+pub fn record_broadcast(scope: &'static str, msg: &str, recipients: usize) {
+    counter!("gateway_events_sent_total", "type" => event_type(msg).to_string()).increment(1);
+    histogram!("gateway_broadcast_recipients", "scope" => scope).record(recipients as f64);
+}
+
+/// Count an event a client sent. Only called for events that parsed, so the
+/// label can't be made to grow by sending junk.
+pub fn record_client_event(kind: &str) {
+    counter!("gateway_events_received_total", "type" => kind.to_string()).increment(1);
+}
+
+/// Record one client's call quality sample.
+pub fn record_voice_sample(rtt_ms: f64, jitter_ms: f64, packet_loss: f64) {
+    histogram!("voice_rtt_ms").record(rtt_ms);
+    histogram!("voice_jitter_ms").record(jitter_ms);
+    histogram!("voice_packet_loss_percent").record(packet_loss);
+}
+
+/// Mark whether a voice channel's call quality is degraded. Returns true when
+/// that changed, so the caller logs transitions rather than every sample.
+pub fn set_voice_channel_degraded(channel_id: &str, degraded: bool) -> bool {
+    let mut channels = DEGRADED_VOICE_CHANNELS.lock().unwrap();
+    let channels = channels.get_or_insert_with(HashSet::new);
+    let changed = if degraded {
+        channels.insert(channel_id.to_string())
+    } else {
+        channels.remove(channel_id)
+    };
+    if changed && degraded {
+        counter!("voice_quality_degradations_total").increment(1);
+    }
+    gauge!("voice_channels_degraded").set(channels.len() as f64);
+    changed
+}
+
+/// The `type` tag of a serialized server event. Serde writes the tag first.
+fn event_type(msg: &str) -> &str {
+    msg.strip_prefix(r#"{"type":""#)
+        .and_then(|rest| rest.split('"').next())
+        .unwrap_or("unknown")
+}
+
+/// Update the gauges that are cheaper to read at scrape time than to keep
+/// current, then render everything.
+pub async fn render(state: &AppState) -> String {
+    let handle = install();
+
+    {
+        let clients = state.gateway.clients.read().await;
+        let depths: Vec<usize> = clients.values().map(|c| c.tx.depth()).collect();
+        gauge!("gateway_connected_clients").set(clients.len() as f64);
+        gauge!("gateway_queued_events").set(depths.iter().sum::<usize>() as f64);
+        gauge!("gateway_queue_depth_max").set(depths.iter().copied().max().unwrap_or(0) as f64);
+    }
+
+    for (name, pool) in [("main", &state.db), ("read", &state.db_read), ("write", &state.db_write)] {
+        gauge!("db_pool_connections", "pool" => name).set(pool.size() as f64);
+        gauge!("db_pool_idle_connections", "pool" => name).set(pool.num_idle() as f64);
+        gauge!("db_pool_max_connections", "pool" => name).set(pool.options().get_max_connections() as f64);
+    }
+
+    let voice = state.gateway.all_voice_states().await;
+    let mut per_server: HashMap<String, usize> = HashMap::new();
+    if !voice.is_empty() {
+        let placeholders = vec!["?"; voice.len()].join(",");
+        let sql = format!("SELECT id, server_id FROM channels WHERE id IN ({})", placeholders);
+        let mut query = sqlx::query_as::<_, (String, String)>(&sql);
+        for (channel_id, _) in &voice {
+            query = query.bind(channel_id);
+        }
+        let servers: HashMap<String, String> = query.fetch_all(&state.db).await.unwrap_or_default().into_iter().collect();
+        for (channel_id, participants) in &voice {
+            if let Some(server_id) = servers.get(channel_id) {
+                *per_server.entry(server_id.clone()).or_default() += participants.len();
+            }
+        }
+    }
+    {
+        let mut reported = VOICE_SERVERS.lock().unwrap();
+        for server_id in reported.get_or_insert_with(HashSet::new).iter() {
+            if !per_server.contains_key(server_id) {
+                gauge!("voice_participants", "server_id" => server_id.clone()).set(0.0);
+            }
+        }
+        for (server_id, count) in &per_server {
+            gauge!("voice_participants", "server_id" => server_id.clone()).set(*count as f64);
+        }
+        *reported = Some(per_server.into_keys().collect());
+    }
+
+    handle.run_upkeep();
+    handle.render()
+}
@@ -1,16 +1,15 @@
-use axum::{
-    extract::FromRequestParts,
-    http::{request::Parts, StatusCode},
-    response::{IntoResponse, Response},
-    Json,
-};
+use axum::{extract::FromRequestParts, http::request::Parts};
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::AuthUser;
 use crate::AppState;
 
+/// How stale a session's last-seen time may get before a request updates it.
+const SESSION_TOUCH_INTERVAL_SECS: i64 = 300;
+
 impl FromRequestParts<Arc<AppState>> for AuthUser {
-    type Rejection = Response;
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         parts: &mut Parts,
@@ -44,50 +43,37 @@ impl FromRequestParts<Arc<AppState>> for AuthUser {
 
         let token = match token {
             Some(ref t) if !t.is_empty() => t.as_str(),
-            _ => {
-                return Err((
-                    StatusCode::UNAUTHORIZED,
-                    Json(serde_json::json!({"error": "Not authenticated"})),
-                )
-                    .into_response())
-            }
+            _ => return Err(ApiError::unauthorized("Not authenticated")),
         };
 
-        let row = sqlx::query_as::<_, (String, String, String)>(
-            r#"SELECT u.id, u.username, s.expiresAt
+        let row = sqlx::query_as::<_, (String, String, String, String, String, Option<String>)>(
+            r#"SELECT u.id, u.username, s.expiresAt, s.id, s.updatedAt, u.locale
                FROM "session" s
                JOIN "user" u ON u.id = s.userId
                WHERE s.token = ?"#,
         )
         .bind(token)
         .fetch_optional(&state.db)
-        .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "Database error"})),
-            )
-                .into_response()
-        })?;
+        .await?;
 
-        let (user_id, username, expires_at) = match row {
+        let (user_id, username, expires_at, session_id, last_seen, locale) = match row {
             Some(r) => r,
-            None => {
-                return Err((
-                    StatusCode::UNAUTHORIZED,
-                    Json(serde_json::json!({"error": "Invalid session"})),
-                )
-                    .into_response())
-            }
+            None => return Err(ApiError::unauthorized("Invalid session")),
         };
 
         let now = chrono::Utc::now().to_rfc3339();
         if expires_at < now {
-            return Err((
-                StatusCode::UNAUTHORIZED,
-                Json(serde_json::json!({"error": "Session expired"})),
-            )
-                .into_response());
+            return Err(ApiError::unauthorized("Session expired"));
+        }
+
+        // Last-seen only needs to be roughly right; skip the write most of the time
+        let stale = (chrono::Utc::now() - chrono::Duration::seconds(SESSION_TOUCH_INTERVAL_SECS)).to_rfc3339();
+        if last_seen < stale {
+            crate::routes::auth::touch_session(state, &session_id).await;
+        }
+
+        if let Some(locale) = locale.as_deref().and_then(flux_shared::i18n::Locale::from_tag) {
+            crate::middleware::locale::prefer(locale);
         }
 
         Ok(AuthUser {
@@ -0,0 +1,59 @@
+use axum::{
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// A weak ETag for a collection, from whatever identifies its current
+/// version (row count, latest update time, the page asked for).
+pub fn weak_etag(parts: &[&str]) -> HeaderValue {
+    let digest = md5::compute(parts.join("\u{1f}"));
+    HeaderValue::from_str(&format!("W/\"{:x}\"", digest)).expect("hex digest is a valid header value")
+}
+
+/// Headers for a collection response that clients should revalidate rather
+/// than reuse blindly.
+pub fn etag_headers(etag: HeaderValue) -> [(header::HeaderName, HeaderValue); 2] {
+    [
+        (header::ETAG, etag),
+        (header::CACHE_CONTROL, HeaderValue::from_static("private, no-cache")),
+    ]
+}
+
+/// Whether an If-None-Match header matches `etag`, using the weak comparison
+/// RFC 9110 specifies for it.
+fn matches(if_none_match: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = opaque(etag);
+    if_none_match.split(',').any(|tag| tag.trim() == "*" || opaque(tag) == etag)
+}
+
+fn not_modified(headers: &HeaderMap) -> Response {
+    let mut response = StatusCode::NOT_MODIFIED.into_response();
+    for name in [header::ETAG, header::CACHE_CONTROL] {
+        if let Some(value) = headers.get(&name) {
+            response.headers_mut().insert(name, value.clone());
+        }
+    }
+    response
+}
+
+/// Turn a GET whose response carries an ETag the client already has into a
+/// 304 with no body. Handlers opt in by setting the ETag.
+pub async fn conditional_get(req: Request, next: Next) -> Response {
+    let if_none_match = (req.method() == Method::GET)
+        .then(|| req.headers().get(header::IF_NONE_MATCH)?.to_str().ok().map(str::to_string))
+        .flatten();
+
+    let response = next.run(req).await;
+
+    let Some(if_none_match) = if_none_match else { return response };
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+    match response.headers().get(header::ETAG).and_then(|v| v.to_str().ok()) {
+        Some(etag) if matches(&if_none_match, etag) => not_modified(response.headers()),
+        _ => response,
+    }
+}
@@ -0,0 +1,220 @@
+use axum::{
+    extract::{connect_info::Connected, ConnectInfo, FromRequestParts, Request, State},
+    http::{request::Parts, HeaderMap},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    serve::IncomingStream,
+};
+use ipnet::IpNet;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::AppState;
+
+/// The address a request really came from, once trusted proxies are
+/// accounted for. None when it can't be told.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts.extensions.get::<ClientIp>().copied().unwrap_or(ClientIp(None)))
+    }
+}
+
+/// The socket address a connection came from, as connect info for every
+/// listener the server runs. None for transports without one.
+#[derive(Debug, Clone, Copy)]
+pub struct PeerAddr(pub Option<SocketAddr>);
+
+impl Connected<IncomingStream<'_, TcpListener>> for PeerAddr {
+    fn connect_info(stream: IncomingStream<'_, TcpListener>) -> Self {
+        PeerAddr(Some(*stream.remote_addr()))
+    }
+}
+
+#[cfg(unix)]
+impl Connected<IncomingStream<'_, tokio::net::UnixListener>> for PeerAddr {
+    fn connect_info(_stream: IncomingStream<'_, tokio::net::UnixListener>) -> Self {
+        PeerAddr(None)
+    }
+}
+
+/// A banned network, as cached from `ip_bans`.
+#[derive(Debug, Clone)]
+pub struct Ban {
+    pub network: IpNet,
+    pub expires_at: Option<String>,
+}
+
+/// Which addresses may reach the server: trusted proxies from the config,
+/// per-path allowlists from the config, and bans admins manage at runtime.
+pub struct IpFilter {
+    trusted_proxies: Vec<IpNet>,
+    allowlists: Vec<(String, Vec<IpNet>)>,
+    bans: tokio::sync::RwLock<Vec<Ban>>,
+}
+
+/// A comma separated list of addresses and CIDR ranges.
+pub fn parse_networks(list: &str) -> Result<Vec<IpNet>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(parse_network)
+        .collect()
+}
+
+/// One address or CIDR range; a bare address is a network of one.
+pub fn parse_network(s: &str) -> Result<IpNet, String> {
+    s.parse::<IpNet>()
+        .or_else(|_| s.parse::<IpAddr>().map(IpNet::from))
+        .map(|n| n.trunc())
+        .map_err(|_| format!("\"{}\" is not an IP address or CIDR range", s))
+}
+
+/// `;` separated `<path prefix>=<networks>` rules, e.g.
+/// `/api/admin=192.168.0.0/16,10.0.0.0/8;/metrics=127.0.0.1`.
+pub fn parse_allowlists(rules: &str) -> Result<Vec<(String, Vec<IpNet>)>, String> {
+    rules
+        .split(';')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|rule| {
+            let (prefix, networks) = rule
+                .split_once('=')
+                .ok_or_else(|| format!("\"{}\" should be <path prefix>=<networks>", rule))?;
+            let prefix = prefix.trim().trim_end_matches('/');
+            if !prefix.starts_with('/') {
+                return Err(format!("path prefix \"{}\" must start with /", prefix));
+            }
+            let networks = parse_networks(networks)?;
+            if networks.is_empty() {
+                return Err(format!("{} allows no addresses", prefix));
+            }
+            Ok((prefix.to_string(), networks))
+        })
+        .collect()
+}
+
+/// An address from a forwarding header, which may carry a port.
+fn parse_ip(s: &str) -> Option<IpAddr> {
+    let s = s.trim();
+    s.parse::<IpAddr>().ok().or_else(|| s.parse::<SocketAddr>().ok().map(|a| a.ip()))
+}
+
+fn path_matches(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+impl IpFilter {
+    /// Rules from the config, which `Config::load` has already validated.
+    pub fn new(config: &Config) -> Self {
+        Self {
+            trusted_proxies: parse_networks(&config.trusted_proxies).unwrap_or_default(),
+            allowlists: parse_allowlists(&config.ip_allowlists).unwrap_or_default(),
+            bans: tokio::sync::RwLock::new(Vec::new()),
+        }
+    }
+
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|n| n.contains(&ip))
+    }
+
+    /// The client behind `peer`. Forwarding headers only count when the peer
+    /// is a trusted proxy; then `X-Forwarded-For` is read right to left,
+    /// since each proxy appends what it saw, and the first hop that isn't
+    /// one of ours is the client. A missing peer means a local transport
+    /// only a proxy on this host can reach, so it's trusted too.
+    pub fn resolve(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        if let Some(peer) = peer.filter(|p| !self.is_trusted(*p)) {
+            return Some(peer);
+        }
+
+        let forwarded: Vec<IpAddr> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(parse_ip)
+            .collect();
+        if let Some(ip) = forwarded.iter().rev().find(|ip| !self.is_trusted(**ip)) {
+            return Some(*ip);
+        }
+        // Every hop is ours; the first is as far back as we can see
+        if let Some(first) = forwarded.first() {
+            return Some(*first);
+        }
+
+        headers
+            .get("x-real-ip")
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_ip)
+            .or(peer)
+    }
+
+    /// Whether `ip` may use `path` under the configured allowlists. Unknown
+    /// addresses can't be on a list, so they're kept out of listed paths.
+    pub fn allows(&self, path: &str, ip: Option<IpAddr>) -> bool {
+        self.allowlists
+            .iter()
+            .filter(|(prefix, _)| path_matches(path, prefix))
+            .all(|(_, networks)| ip.is_some_and(|ip| networks.iter().any(|n| n.contains(&ip))))
+    }
+
+    /// The ban covering `ip`, if any is in effect.
+    pub async fn banned(&self, ip: IpAddr) -> Option<Ban> {
+        let now = chrono::Utc::now().to_rfc3339();
+        self.bans
+            .read()
+            .await
+            .iter()
+            .find(|b| b.network.contains(&ip) && b.expires_at.as_ref().is_none_or(|e| *e > now))
+            .cloned()
+    }
+
+    /// Reload bans from the database after they change.
+    pub async fn reload_bans(&self, db: &sqlx::SqlitePool) -> sqlx::Result<()> {
+        let rows = sqlx::query_as::<_, (String, Option<String>)>(r#"SELECT network, expires_at FROM "ip_bans""#)
+            .fetch_all(db)
+            .await?;
+        let bans = rows
+            .into_iter()
+            .filter_map(|(network, expires_at)| {
+                let network = parse_network(&network).ok()?;
+                Some(Ban { network, expires_at })
+            })
+            .collect();
+        *self.bans.write().await = bans;
+        Ok(())
+    }
+}
+
+/// Work out the client's address for handlers (see [`ClientIp`]), and turn
+/// away banned addresses and ones a path's allowlist doesn't include.
+pub async fn filter_requests(State(state): State<Arc<AppState>>, mut req: Request, next: Next) -> Response {
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<PeerAddr>>()
+        .and_then(|c| c.0 .0)
+        .map(|a| a.ip());
+    let filter = &state.ip_filter;
+    let ip = filter.resolve(peer, req.headers());
+
+    if let Some(ip) = ip {
+        if filter.banned(ip).await.is_some() {
+            return ApiError::forbidden("Your address has been blocked").into_response();
+        }
+    }
+    if !filter.allows(req.uri().path(), ip) {
+        return ApiError::forbidden("Not available from your network").into_response();
+    }
+
+    req.extensions_mut().insert(ClientIp(ip));
+    next.run(req).await
+}
@@ -0,0 +1,35 @@
+use axum::{
+    extract::Request,
+    http::header,
+    middleware::Next,
+    response::Response,
+};
+use flux_shared::i18n::{self, Locale};
+use std::cell::Cell;
+
+tokio::task_local! {
+    static LOCALE: Cell<Locale>;
+}
+
+/// The language to answer the current request in. English outside a request.
+pub fn current() -> Locale {
+    LOCALE.try_with(Cell::get).unwrap_or_default()
+}
+
+/// Switch the current request to a signed-in user's saved language, which
+/// takes priority over what their browser asks for.
+pub fn prefer(locale: Locale) {
+    let _ = LOCALE.try_with(|l| l.set(locale));
+}
+
+/// Pick the request's language from `Accept-Language`, for error messages
+/// and other server-generated text.
+pub async fn negotiate_locale(req: Request, next: Next) -> Response {
+    let locale = req
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok())
+        .map(i18n::negotiate)
+        .unwrap_or_default();
+    LOCALE.scope(Cell::new(locale), next.run(req)).await
+}
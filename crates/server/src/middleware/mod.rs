@@ -1 +1,7 @@
 pub mod auth;
+pub mod csrf;
+pub mod etag;
+pub mod ip_filter;
+pub mod locale;
+pub mod metrics;
+pub mod trace;
@@ -1,4 +1,6 @@
+use flux_shared::markdown::MessageMetadata;
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 #[serde(rename_all = "camelCase")]
@@ -9,6 +11,15 @@ pub struct Message {
     pub content: String,
     pub created_at: String,
     pub edited_at: Option<String>,
+    /// Parsed markup; NULL for messages stored before parsing was added.
+    pub metadata: Option<Json<MessageMetadata>>,
+    /// Set on messages a channel feed posted; `sender_id` is then whoever
+    /// added the feed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed_id: Option<String>,
+    /// Set on messages a webhook integration posted, like `feed_id`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integration_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -21,6 +32,29 @@ pub struct Reaction {
     pub created_at: String,
 }
 
+/// One emoji's reactions on a message, counted in SQL so popular messages
+/// don't ship every row. `sample_user_ids` holds the first few reactors.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactionSummary {
+    pub message_id: String,
+    pub emoji: String,
+    pub count: i64,
+    /// Whether the caller is among the reactors
+    pub me: bool,
+    pub sample_user_ids: Vec<String>,
+}
+
+/// Custom emoji resolved from a `:name:id` reaction.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomEmojiRef {
+    pub id: String,
+    pub name: String,
+    pub attachment_id: String,
+    pub filename: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct DmMessage {
@@ -43,3 +77,41 @@ pub struct Attachment {
     pub size: i64,
     pub created_at: String,
 }
+
+/// A "remind me about this message" reminder. The location fields say where
+/// to jump to: server and channel for channel messages, the DM channel for DMs.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageReminder {
+    pub id: String,
+    pub message_id: String,
+    pub server_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub dm_channel_id: Option<String>,
+    pub note: Option<String>,
+    pub remind_at: String,
+    pub created_at: String,
+    /// When the reminder went off; None while it's pending
+    pub fired_at: Option<String>,
+}
+
+/// A message the user saved, with enough context to show and jump to it.
+/// Channel messages carry their content; DMs carry the ciphertext, which
+/// only the participants can decrypt.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedMessage {
+    pub id: String,
+    pub message_id: String,
+    pub sender_id: String,
+    pub server_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub channel_name: Option<String>,
+    pub content: Option<String>,
+    pub dm_channel_id: Option<String>,
+    pub ciphertext: Option<String>,
+    pub mls_epoch: Option<i64>,
+    /// When the message itself was sent
+    pub message_created_at: String,
+    pub saved_at: String,
+}
@@ -49,6 +49,8 @@ pub struct ListeningSession {
     pub is_playing: i64,
     pub created_at: String,
     pub updated_at: String,
+    /// "audio" for a listening session, "video" for watch-together.
+    pub session_type: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -68,6 +70,31 @@ pub struct QueueItem {
     pub source: String,
 }
 
+/// A track that started playing in a listening session.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryItem {
+    pub id: String,
+    pub session_id: String,
+    pub track_uri: String,
+    pub track_name: String,
+    pub track_artist: String,
+    pub track_album: Option<String>,
+    pub track_image_url: Option<String>,
+    pub track_duration_ms: i64,
+    pub source: String,
+    pub played_by_user_id: String,
+    pub played_at: String,
+}
+
+/// One line of time-synced lyrics, `time_ms` from the start of the track.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LyricLine {
+    pub time_ms: i64,
+    pub text: String,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AddToQueueRequest {
@@ -108,3 +135,113 @@ pub struct WhitelistEntry {
 pub struct AddWhitelistRequest {
     pub emails: Vec<String>,
 }
+
+/// A signup invite as admins see it; the token itself is only shown once.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SignupInvite {
+    pub id: String,
+    pub note: Option<String>,
+    pub created_by: String,
+    pub created_at: String,
+    pub expires_at: String,
+    pub used_by: Option<String>,
+    pub used_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateInviteRequest {
+    pub note: Option<String>,
+    pub expires_in_hours: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessRequest {
+    pub id: String,
+    pub email: String,
+    pub reason: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RequestAccessRequest {
+    pub email: String,
+    pub reason: Option<String>,
+}
+
+/// How many coins an activity earns, and the limits on earning it.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RewardRule {
+    pub rule: String,
+    pub amount: i64,
+    /// Most coins per UTC day from this rule; 0 means no cap.
+    pub daily_cap: i64,
+    /// Minimum seconds between two rewards from this rule.
+    pub cooldown_secs: i64,
+    pub enabled: i64,
+}
+
+/// A coinflip or dice game. `server_seed` stays hidden until the player whose
+/// seed pair rolled it rotates to a new pair.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Game {
+    pub id: String,
+    /// "coinflip" or "dice"
+    pub kind: String,
+    pub server_id: String,
+    pub channel_id: String,
+    pub creator_id: String,
+    pub opponent_id: Option<String>,
+    pub wager: i64,
+    /// Dice only: win chance in percent.
+    pub target: Option<i64>,
+    /// "open", "resolved" or "cancelled"
+    pub status: String,
+    pub seed_user_id: Option<String>,
+    pub server_seed: Option<String>,
+    pub server_seed_hash: Option<String>,
+    pub client_seed: Option<String>,
+    pub nonce: Option<i64>,
+    /// 0..10000, from the seed pair and nonce.
+    pub roll: Option<i64>,
+    pub winner_id: Option<String>,
+    pub payout: i64,
+    pub created_at: String,
+    pub resolved_at: Option<String>,
+}
+
+/// Per-server game limits, set by the server owner.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct GameSettings {
+    pub server_id: String,
+    pub enabled: i64,
+    /// House edge in basis points (200 = 2%).
+    pub house_edge_bps: i64,
+    /// Most coins a member can lose in games per UTC day; 0 means no limit.
+    pub daily_loss_limit: i64,
+}
+
+/// One quick switcher result. `kind` is "channel", "dm" or "user"; `id` is
+/// the channel, DM channel or user to open.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickSwitcherItem {
+    pub kind: &'static str,
+    pub id: String,
+    pub name: String,
+    pub image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_name: Option<String>,
+    /// The other person, for DMs and users.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::user::nullable_value;
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct Server {
@@ -8,6 +10,8 @@ pub struct Server {
     pub owner_id: String,
     pub invite_code: String,
     pub created_at: String,
+    /// Days before channel messages are deleted; None keeps them forever
+    pub retention_days: Option<i64>,
 }
 
 #[derive(Debug, Serialize, sqlx::FromRow)]
@@ -18,6 +22,7 @@ pub struct ServerWithRole {
     pub owner_id: String,
     pub invite_code: String,
     pub created_at: String,
+    pub retention_days: Option<i64>,
     pub role: String,
 }
 
@@ -37,6 +42,12 @@ pub struct Channel {
     pub creator_id: Option<String>,
     pub is_locked: i64,
     pub created_at: String,
+    /// Overrides the server's retention: None follows it, 0 keeps messages
+    /// forever
+    pub retention_days: Option<i64>,
+    /// Voice channels only: the region calls run in. None picks the node
+    /// closest to whoever joins first
+    pub voice_region: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -57,6 +68,10 @@ pub struct UpdateChannelRequest {
     pub name: Option<String>,
     pub bitrate: Option<i64>,
     pub is_locked: Option<bool>,
+    #[serde(default, deserialize_with = "nullable_value")]
+    pub retention_days: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "nullable_value")]
+    pub voice_region: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -92,11 +107,209 @@ pub struct ReorderChannelsRequest {
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct UpdateServerRequest {
     pub name: Option<String>,
+    #[serde(default, deserialize_with = "nullable_value")]
+    pub retention_days: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateMemberRoleRequest {
     pub role: String,
 }
+
+/// A member's own per-server profile; null clears a field.
+#[derive(Debug, Deserialize)]
+pub struct UpdateMemberProfileRequest {
+    #[serde(default, deserialize_with = "nullable_value")]
+    pub nickname: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "nullable_value")]
+    pub avatar: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetNicknameRequest {
+    pub nickname: Option<String>,
+}
+
+/// An RSS/Atom feed posted into a channel.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelFeed {
+    pub id: String,
+    pub channel_id: String,
+    pub url: String,
+    pub title: String,
+    pub created_by: String,
+    pub created_at: String,
+    pub last_polled_at: Option<String>,
+    /// Why the last poll failed; cleared by the next one that succeeds.
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateFeedRequest {
+    pub url: String,
+}
+
+/// A webhook integration (GitHub or GitLab) that posts into a channel. The
+/// URL token and signing secret are only shown when created or rotated.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelIntegration {
+    pub id: String,
+    pub channel_id: String,
+    pub provider: String,
+    pub name: String,
+    pub created_by: String,
+    pub created_at: String,
+    pub last_delivery_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateIntegrationRequest {
+    pub provider: String,
+    pub name: Option<String>,
+}
+
+/// A member's token for following a channel from a feed reader. The token
+/// itself is only shown when it's issued.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelFeedToken {
+    pub id: String,
+    pub channel_id: String,
+    pub user_id: String,
+    pub name: String,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateFeedTokenRequest {
+    pub name: Option<String>,
+}
+
+/// A custom role. Unlike owner/admin/member these grant no permissions;
+/// they label members and can be handed out by reaction roles.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerRole {
+    pub id: String,
+    pub server_id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub created_at: String,
+    #[sqlx(skip)]
+    pub member_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRoleRequest {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+/// Reacting to `message_id` with `emoji` grants `role_id`; removing the
+/// reaction takes it away again.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactionRole {
+    pub id: String,
+    pub message_id: String,
+    pub channel_id: String,
+    pub emoji: String,
+    pub role_id: String,
+    pub role_name: String,
+    pub created_by: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateReactionRoleRequest {
+    pub message_id: String,
+    pub emoji: String,
+    pub role_id: String,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedChannel {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub channel_type: String,
+}
+
+/// What a member sees on first joining a server. `requires_acceptance` is
+/// set while the member still has to accept the current rules before they
+/// can post.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WelcomeScreen {
+    pub server_id: String,
+    pub message: Option<String>,
+    pub rules: Option<String>,
+    pub suggested_channels: Vec<SuggestedChannel>,
+    pub rules_updated_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub rules_accepted_at: Option<String>,
+    pub requires_acceptance: bool,
+}
+
+/// Replaces the whole welcome screen; blank text clears that part.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWelcomeRequest {
+    pub message: Option<String>,
+    pub rules: Option<String>,
+    #[serde(default)]
+    pub suggested_channel_ids: Vec<String>,
+}
+
+/// What a server asks of members before they can post. Owners and admins
+/// are exempt.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationSettings {
+    pub min_account_age_minutes: i64,
+    pub require_verified_email: bool,
+    pub require_challenge: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateVerificationRequest {
+    pub min_account_age_minutes: Option<i64>,
+    pub require_verified_email: Option<bool>,
+    pub require_challenge: Option<bool>,
+}
+
+/// A requirement the member hasn't met yet. `code` is one of
+/// `account_too_new` (with `available_at`), `email_unverified` or
+/// `challenge_required`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmetRequirement {
+    pub code: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationStatus {
+    #[serde(flatten)]
+    pub settings: VerificationSettings,
+    /// For rendering the challenge widget, when challenges are configured
+    pub challenge_site_key: Option<String>,
+    pub unmet: Vec<UnmetRequirement>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChallengeRequest {
+    pub token: String,
+}
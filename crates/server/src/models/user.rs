@@ -30,6 +30,11 @@ pub struct MemberWithUser {
     pub ring_pattern_seed: Option<i64>,
     pub banner_css: Option<String>,
     pub banner_pattern_seed: Option<i64>,
+    /// Shown instead of the username in this server.
+    pub nickname: Option<String>,
+    /// Shown instead of the account avatar in this server.
+    #[sqlx(rename = "avatar")]
+    pub server_avatar: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,15 +46,19 @@ pub struct VoiceParticipant {
     pub drink_count: i32,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DmChannelResponse {
     pub id: String,
     pub other_user: DmOtherUser,
     pub created_at: String,
+    /// `accepted`, or `pending`/`declined` for a message request
+    pub status: String,
+    /// Who opened the conversation, for message requests
+    pub requested_by: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DmOtherUser {
     pub id: String,
     pub username: String,
@@ -69,8 +78,58 @@ pub struct VoiceTokenRequest {
     pub viewer: Option<bool>,
 }
 
+/// A LiveKit node voice can be routed to. Credentials never leave the server.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceNode {
+    pub id: String,
+    pub region: String,
+    pub url: String,
+    #[serde(skip)]
+    pub api_key: String,
+    #[serde(skip)]
+    pub api_secret: String,
+    pub enabled: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateVoiceNodeRequest {
+    pub region: String,
+    pub url: String,
+    /// Empty or missing uses the configured LiveKit key and secret.
+    #[serde(default)]
+    pub api_key: String,
+    #[serde(default)]
+    pub api_secret: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateVoiceNodeRequest {
+    pub region: Option<String>,
+    pub url: Option<String>,
+    pub api_key: Option<String>,
+    pub api_secret: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+/// One latency probe result from the client.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencySample {
+    pub node_id: String,
+    pub rtt_ms: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LatencyReport {
+    pub results: Vec<LatencySample>,
+}
+
 /// Deserializer that keeps JSON null as Some(Value::Null) instead of None.
-fn nullable_value<'de, D>(deserializer: D) -> Result<Option<serde_json::Value>, D::Error>
+pub(super) fn nullable_value<'de, D>(deserializer: D) -> Result<Option<serde_json::Value>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
@@ -87,6 +146,85 @@ pub struct UpdateUserRequest {
     pub ring_spin: Option<bool>,
     #[serde(default, deserialize_with = "nullable_value")]
     pub steam_id: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "nullable_value")]
+    pub bio: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "nullable_value")]
+    pub pronouns: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "nullable_value")]
+    pub accent_color: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "nullable_value")]
+    pub timezone: Option<serde_json::Value>,
+    /// Language tag for server messages, e.g. "es"; null follows the browser
+    #[serde(default, deserialize_with = "nullable_value")]
+    pub locale: Option<serde_json::Value>,
+}
+
+/// The signed-in user's own profile, as returned by /users/me.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnProfile {
+    pub id: String,
+    pub username: String,
+    pub email: String,
+    pub image: Option<String>,
+    /// Still version of an animated `image`.
+    pub image_static: Option<String>,
+    pub ring_style: String,
+    pub ring_spin: bool,
+    pub steam_id: Option<String>,
+    pub ring_pattern_seed: Option<i64>,
+    pub banner_css: Option<String>,
+    pub banner_pattern_seed: Option<i64>,
+    pub status: String,
+    pub bio: Option<String>,
+    pub pronouns: Option<String>,
+    pub accent_color: Option<String>,
+    pub timezone: Option<String>,
+    pub locale: Option<String>,
+}
+
+/// Equipped cosmetics shown on a profile card.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileCosmetics {
+    pub ring_style: String,
+    pub ring_spin: bool,
+    pub ring_pattern_seed: Option<i64>,
+    pub banner_css: Option<String>,
+    pub banner_pattern_seed: Option<i64>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct MutualServer {
+    pub id: String,
+    pub name: String,
+}
+
+/// Someone the user has blocked.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockedUser {
+    pub user_id: String,
+    pub username: String,
+    pub image: Option<String>,
+    pub created_at: String,
+}
+
+/// Another user's profile as seen by the viewer.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserProfile {
+    pub id: String,
+    pub username: String,
+    pub image: Option<String>,
+    pub status: String,
+    pub bio: Option<String>,
+    pub pronouns: Option<String>,
+    pub accent_color: Option<String>,
+    pub timezone: Option<String>,
+    pub cosmetics: ProfileCosmetics,
+    pub mutual_servers: Vec<MutualServer>,
+    pub activity: Option<crate::ws::events::ActivityInfo>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -95,6 +233,9 @@ pub struct SignUpRequest {
     pub password: String,
     pub name: String,
     pub username: String,
+    /// A one-time invite that lets this email past the whitelist.
+    #[serde(rename = "inviteToken")]
+    pub invite_token: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -103,6 +244,26 @@ pub struct SignInRequest {
     pub password: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeEmailRequest {
+    pub new_email: String,
+    /// Required for accounts that have a password.
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SetPublicKeyRequest {
@@ -114,4 +275,148 @@ pub struct SetPublicKeyRequest {
 pub struct StoreServerKeyRequest {
     pub encrypted_key: String,
     pub sender_id: String,
+    /// The device the key is wrapped for; omitted for the account-level key.
+    #[serde(default)]
+    pub device_id: Option<String>,
+}
+
+/// One of a user's devices and the identity key it encrypts with.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceKey {
+    pub device_id: String,
+    pub public_key: String,
+    pub name: Option<String>,
+    pub created_at: String,
+    pub last_seen_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterDeviceRequest {
+    pub public_key: String,
+    pub name: Option<String>,
+}
+
+/// A passphrase-wrapped copy of a user's E2EE identity key. The server only
+/// ever sees the wrapped blob; `kdf`/`kdf_params`/`salt` tell a new device how
+/// to derive the unwrapping key from the passphrase.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyBackup {
+    pub version: i64,
+    pub wrapped_key: String,
+    pub salt: String,
+    pub kdf: String,
+    pub kdf_params: String,
+    /// The public half of the backed-up identity, so a restore can be checked.
+    pub public_key: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PutKeyBackupRequest {
+    pub wrapped_key: String,
+    pub salt: String,
+    pub kdf: String,
+    pub kdf_params: String,
+    pub public_key: String,
+    /// The version this replaces (0 or omitted for the first backup).
+    pub expected_version: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRestoredRequest {
+    pub version: i64,
+    pub public_key: String,
+}
+
+/// One of the user's signed-in sessions, for the session manager.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInfo {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: String,
+    pub last_seen_at: String,
+    pub expires_at: String,
+    /// The session making this request.
+    pub current: bool,
+}
+
+/// A user as the instance admin's user list shows them.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminUserEntry {
+    pub id: String,
+    pub name: String,
+    pub username: String,
+    pub email: String,
+    #[sqlx(rename = "emailVerified")]
+    pub email_verified: bool,
+    pub image: Option<String>,
+    #[sqlx(rename = "createdAt")]
+    pub created_at: String,
+    pub is_instance_admin: bool,
+    pub message_count: i64,
+    /// Bytes of attachments they've uploaded.
+    pub storage_bytes: i64,
+    #[sqlx(skip)]
+    pub online: bool,
+}
+
+/// An address or CIDR range turned away from the whole instance.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct IpBan {
+    pub id: String,
+    pub network: String,
+    pub reason: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: String,
+    /// None bans until it's lifted.
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateIpBanRequest {
+    /// An address, or a range like `203.0.113.0/24`.
+    pub network: String,
+    pub reason: Option<String>,
+    /// Missing bans until it's lifted.
+    pub duration_minutes: Option<i64>,
+}
+
+/// Instance-wide switches an instance admin can flip.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceSettings {
+    /// When off, nobody new can sign up.
+    pub registration_open: bool,
+    /// When on, only whitelisted emails can sign up.
+    pub whitelist_enabled: bool,
+}
+
+impl Default for InstanceSettings {
+    fn default() -> Self {
+        Self { registration_open: true, whitelist_enabled: true }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInstanceSettingsRequest {
+    pub registration_open: Option<bool>,
+    pub whitelist_enabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetInstanceAdminRequest {
+    pub is_admin: bool,
 }
@@ -0,0 +1,61 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use super::require_instance_admin;
+use crate::backup;
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::AppState;
+
+/// GET /api/admin/backups
+/// Backups in the backup directory, newest first.
+pub async fn list_backups(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+    Ok(Json(backup::list_backups(&state.config).await))
+}
+
+/// POST /api/admin/backup
+/// Back up now, outside the schedule.
+pub async fn create_backup(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+    let info = backup::create_backup(&state.db, &state.config, "manual")
+        .await
+        .map_err(ApiError::internal)?;
+    tracing::info!("{} took a manual backup: {}", user.username, info.name);
+    Ok(Json(info))
+}
+
+/// POST /api/admin/backups/{name}/restore
+/// Replace the live data with a backup's. The current data is backed up
+/// first; its name is returned so the restore can itself be undone.
+pub async fn restore_backup(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+    if !backup::is_backup_name(&name) {
+        return Err(ApiError::bad_request("Invalid backup name"));
+    }
+    if !backup::list_backups(&state.config).await.iter().any(|b| b.name == name)
+        && state.config.backup_s3_bucket.is_empty()
+    {
+        return Err(ApiError::not_found("Backup not found"));
+    }
+
+    let safety = backup::restore_online(&state.db, &state.config, &name)
+        .await
+        .map_err(ApiError::internal)?;
+    tracing::warn!("{} restored backup {}", user.username, name);
+    Ok(Json(serde_json::json!({ "restored": name, "previous": safety })))
+}
@@ -0,0 +1,122 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use super::require_instance_admin;
+use crate::error::ApiError;
+use crate::middleware::ip_filter::{parse_network, ClientIp};
+use crate::models::{AuthUser, CreateIpBanRequest, IpBan};
+use crate::AppState;
+
+const MAX_REASON_LEN: usize = 200;
+
+async fn clear_expired(state: &AppState) -> sqlx::Result<()> {
+    sqlx::query(r#"DELETE FROM "ip_bans" WHERE expires_at IS NOT NULL AND expires_at <= ?"#)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&state.db)
+        .await?;
+    Ok(())
+}
+
+/// GET /api/admin/ip-bans
+/// Expired bans are cleared out first.
+pub async fn list_ip_bans(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+    clear_expired(&state).await?;
+    let bans = sqlx::query_as::<_, IpBan>(r#"SELECT * FROM "ip_bans" ORDER BY created_at DESC"#)
+        .fetch_all(&state.db)
+        .await?;
+    Ok(Json(bans))
+}
+
+/// POST /api/admin/ip-bans
+/// Bans take effect on the next request. A ban covering the admin's own
+/// address is refused, so nobody locks themselves out by accident.
+pub async fn create_ip_ban(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    ClientIp(ip): ClientIp,
+    Json(body): Json<CreateIpBanRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+    let network = parse_network(body.network.trim()).map_err(ApiError::bad_request)?;
+    if ip.is_some_and(|ip| network.contains(&ip)) {
+        return Err(ApiError::bad_request("That would block your own address"));
+    }
+    let reason = body.reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+    if reason.as_ref().is_some_and(|r| r.chars().count() > MAX_REASON_LEN) {
+        return Err(ApiError::bad_request(format!("Reason must be at most {} characters", MAX_REASON_LEN)));
+    }
+    let now = chrono::Utc::now();
+    let expires_at = match body.duration_minutes {
+        Some(minutes) if minutes <= 0 => return Err(ApiError::bad_request("Duration must be positive")),
+        Some(minutes) => Some((now + chrono::Duration::minutes(minutes)).to_rfc3339()),
+        None => None,
+    };
+
+    // Single addresses read better without the /32
+    let network = if network.prefix_len() == network.max_prefix_len() {
+        network.addr().to_string()
+    } else {
+        network.to_string()
+    };
+    let ban = IpBan {
+        id: uuid::Uuid::new_v4().to_string(),
+        network,
+        reason,
+        created_by: Some(user.id.clone()),
+        created_at: now.to_rfc3339(),
+        expires_at,
+    };
+
+    clear_expired(&state).await?;
+    let taken = sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "ip_bans" WHERE network = ?"#)
+        .bind(&ban.network)
+        .fetch_one(&state.db)
+        .await?;
+    if taken > 0 {
+        return Err(ApiError::conflict("That address is already banned"));
+    }
+
+    sqlx::query(
+        r#"INSERT INTO "ip_bans" (id, network, reason, created_by, created_at, expires_at)
+           VALUES (?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(&ban.id)
+    .bind(&ban.network)
+    .bind(&ban.reason)
+    .bind(&ban.created_by)
+    .bind(&ban.created_at)
+    .bind(&ban.expires_at)
+    .execute(&state.db)
+    .await?;
+    state.ip_filter.reload_bans(&state.db).await?;
+
+    tracing::info!("{} banned {}", user.username, ban.network);
+    Ok((StatusCode::CREATED, Json(ban)))
+}
+
+/// DELETE /api/admin/ip-bans/:banId
+pub async fn delete_ip_ban(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(ban_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+    let network = sqlx::query_scalar::<_, String>(r#"DELETE FROM "ip_bans" WHERE id = ? RETURNING network"#)
+        .bind(&ban_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Ban not found"))?;
+    state.ip_filter.reload_bans(&state.db).await?;
+
+    tracing::info!("{} lifted the ban on {}", user.username, network);
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,203 @@
+mod backups;
+mod ip_bans;
+mod stats;
+mod voice_nodes;
+
+pub use backups::*;
+pub use ip_bans::*;
+pub use stats::*;
+pub use voice_nodes::*;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{
+    AdminUserEntry, AuthUser, InstanceSettings, SetInstanceAdminRequest, UpdateInstanceSettingsRequest,
+};
+use crate::AppState;
+
+/// Instance admins run the whole deployment, which is separate from being
+/// owner or admin of a server on it.
+pub(crate) async fn require_instance_admin(state: &AppState, user_id: &str) -> Result<(), ApiError> {
+    let is_admin = sqlx::query_scalar::<_, bool>(r#"SELECT is_instance_admin FROM "user" WHERE id = ?"#)
+        .bind(user_id)
+        .fetch_optional(&state.db)
+        .await?
+        .unwrap_or(false);
+
+    if is_admin {
+        Ok(())
+    } else {
+        Err(ApiError::forbidden("Instance admin only"))
+    }
+}
+
+/// Current instance settings, with defaults for anything never set.
+pub(crate) async fn instance_settings(db: &sqlx::SqlitePool) -> sqlx::Result<InstanceSettings> {
+    let rows = sqlx::query_as::<_, (String, String)>(r#"SELECT key, value FROM "instance_settings""#)
+        .fetch_all(db)
+        .await?;
+
+    let mut settings = InstanceSettings::default();
+    for (key, value) in rows {
+        let on = value == "true";
+        match key.as_str() {
+            "registration_open" => settings.registration_open = on,
+            "whitelist_enabled" => settings.whitelist_enabled = on,
+            _ => {}
+        }
+    }
+    Ok(settings)
+}
+
+/// GET /api/admin/settings
+pub async fn get_settings(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+    Ok(Json(instance_settings(&state.db).await?))
+}
+
+/// PATCH /api/admin/settings
+pub async fn update_settings(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<UpdateInstanceSettingsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let changes = [
+        ("registration_open", body.registration_open),
+        ("whitelist_enabled", body.whitelist_enabled),
+    ];
+    let mut tx = state.db.begin().await?;
+    for (key, value) in changes {
+        let Some(value) = value else { continue };
+        sqlx::query(
+            r#"INSERT INTO "instance_settings" (key, value, updated_by, updated_at) VALUES (?, ?, ?, ?)
+               ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_by = excluded.updated_by, updated_at = excluded.updated_at"#,
+        )
+        .bind(key)
+        .bind(value.to_string())
+        .bind(&user.id)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    let settings = instance_settings(&state.db).await?;
+    tracing::info!(
+        "Instance settings changed by {}: registration_open={} whitelist_enabled={}",
+        user.username,
+        settings.registration_open,
+        settings.whitelist_enabled
+    );
+    Ok(Json(settings))
+}
+
+#[derive(Deserialize)]
+pub struct AdminUserQuery {
+    pub q: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// GET /api/admin/users?q=&limit=&offset=
+/// Every account on the instance, newest first. `q` matches username, name
+/// or email.
+pub async fn list_users(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(query): Query<AdminUserQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let offset = query.offset.unwrap_or(0).max(0);
+    let pattern = format!("%{}%", query.q.as_deref().unwrap_or("").trim());
+
+    let total = sqlx::query_scalar::<_, i64>(
+        r#"SELECT COUNT(*) FROM "user" WHERE username LIKE ?1 OR name LIKE ?1 OR email LIKE ?1"#,
+    )
+    .bind(&pattern)
+    .fetch_one(&state.db)
+    .await?;
+
+    let mut users = sqlx::query_as::<_, AdminUserEntry>(
+        r#"SELECT u.id, u.name, u.username, u.email, u.emailVerified, u.image, u.createdAt, u.is_instance_admin,
+                  (SELECT COUNT(*) FROM messages m WHERE m.sender_id = u.id)
+                    + (SELECT COUNT(*) FROM dm_messages d WHERE d.sender_id = u.id) AS message_count,
+                  (SELECT COALESCE(SUM(a.size), 0) FROM attachments a WHERE a.uploader_id = u.id) AS storage_bytes
+           FROM "user" u
+           WHERE u.username LIKE ?1 OR u.name LIKE ?1 OR u.email LIKE ?1
+           ORDER BY u.createdAt DESC
+           LIMIT ?2 OFFSET ?3"#,
+    )
+    .bind(&pattern)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await?;
+
+    let online: HashSet<String> = state
+        .gateway
+        .clients
+        .read()
+        .await
+        .values()
+        .map(|c| c.user_id.clone())
+        .collect();
+    for entry in &mut users {
+        entry.online = online.contains(&entry.id);
+    }
+
+    Ok(Json(serde_json::json!({ "items": users, "total": total })))
+}
+
+/// PUT /api/admin/users/{userId}/admin
+/// Grant or revoke instance admin. The last admin can't be demoted, so the
+/// instance is never left without one.
+pub async fn set_instance_admin(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(user_id): Path<String>,
+    Json(body): Json<SetInstanceAdminRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+
+    let mut tx = state.db.begin().await?;
+    let current = sqlx::query_scalar::<_, bool>(r#"SELECT is_instance_admin FROM "user" WHERE id = ?"#)
+        .bind(&user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ApiError::not_found("User not found"))?;
+
+    if current && !body.is_admin {
+        let admins = sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "user" WHERE is_instance_admin = 1"#)
+            .fetch_one(&mut *tx)
+            .await?;
+        if admins <= 1 {
+            return Err(ApiError::conflict("Can't remove the last instance admin"));
+        }
+    }
+
+    sqlx::query(r#"UPDATE "user" SET is_instance_admin = ? WHERE id = ?"#)
+        .bind(body.is_admin)
+        .bind(&user_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    tracing::info!("{} set instance admin = {} for user {}", user.username, body.is_admin, user_id);
+    Ok(Json(serde_json::json!({ "id": user_id, "isInstanceAdmin": body.is_admin })))
+}
@@ -0,0 +1,178 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::require_instance_admin;
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::AppState;
+
+/// How often the online count is sampled for the history graph.
+const ONLINE_SAMPLE_SECS: u64 = 5 * 60;
+/// Samples older than this are dropped.
+const ONLINE_HISTORY_DAYS: i64 = 30;
+
+/// GET /api/admin/stats
+/// Instance totals: accounts, servers, messages, storage, and who's online now.
+pub async fn get_stats(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+
+    let count = |sql: &'static str| sqlx::query_scalar::<_, i64>(sql).fetch_one(&state.db);
+    let users = count(r#"SELECT COUNT(*) FROM "user""#).await?;
+    let servers = count("SELECT COUNT(*) FROM servers").await?;
+    let channels = count("SELECT COUNT(*) FROM channels WHERE is_room = 0").await?;
+    let messages = count("SELECT COUNT(*) FROM messages").await?;
+    let dm_messages = count("SELECT COUNT(*) FROM dm_messages").await?;
+    let attachments = count("SELECT COUNT(*) FROM attachments").await?;
+    let attachment_bytes = count("SELECT COALESCE(SUM(size), 0) FROM attachments").await?;
+    let database_bytes = count("SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()").await?;
+
+    let online_users = online_user_count(&state).await;
+
+    Ok(Json(serde_json::json!({
+        "users": users,
+        "servers": servers,
+        "channels": channels,
+        "messages": messages,
+        "dmMessages": dm_messages,
+        "onlineUsers": online_users,
+        "storage": {
+            "attachments": attachments,
+            "attachmentBytes": attachment_bytes,
+            "databaseBytes": database_bytes,
+        },
+    })))
+}
+
+#[derive(Deserialize)]
+pub struct MessageStatsQuery {
+    pub days: Option<i64>,
+}
+
+/// GET /api/admin/stats/messages?days=
+/// Messages sent per UTC day, channel and DM counted separately, oldest
+/// first. Days with no messages are included as zeros.
+pub async fn get_message_stats(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(query): Query<MessageStatsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+
+    let days = query.days.unwrap_or(30).clamp(1, 365);
+    let today = chrono::Utc::now().date_naive();
+    let first = today - chrono::Duration::days(days - 1);
+    let since = first.to_string();
+
+    let mut per_day: BTreeMap<String, (i64, i64)> = (0..days)
+        .map(|i| ((first + chrono::Duration::days(i)).to_string(), (0, 0)))
+        .collect();
+
+    let channel = sqlx::query_as::<_, (String, i64)>(
+        "SELECT substr(created_at, 1, 10) AS day, COUNT(*) FROM messages WHERE created_at >= ? GROUP BY day",
+    )
+    .bind(&since)
+    .fetch_all(&state.db)
+    .await?;
+    for (day, n) in channel {
+        if let Some(entry) = per_day.get_mut(&day) {
+            entry.0 = n;
+        }
+    }
+
+    let dm = sqlx::query_as::<_, (String, i64)>(
+        "SELECT substr(created_at, 1, 10) AS day, COUNT(*) FROM dm_messages WHERE created_at >= ? GROUP BY day",
+    )
+    .bind(&since)
+    .fetch_all(&state.db)
+    .await?;
+    for (day, n) in dm {
+        if let Some(entry) = per_day.get_mut(&day) {
+            entry.1 = n;
+        }
+    }
+
+    let items: Vec<serde_json::Value> = per_day
+        .into_iter()
+        .map(|(day, (channel, dm))| serde_json::json!({ "day": day, "channelMessages": channel, "dmMessages": dm }))
+        .collect();
+    Ok(Json(items))
+}
+
+#[derive(Deserialize)]
+pub struct OnlineHistoryQuery {
+    pub hours: Option<i64>,
+}
+
+/// GET /api/admin/stats/online?hours=
+/// How many users were connected, sampled every five minutes, oldest first.
+pub async fn get_online_history(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(query): Query<OnlineHistoryQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+
+    let hours = query.hours.unwrap_or(24).clamp(1, ONLINE_HISTORY_DAYS * 24);
+    let since = (chrono::Utc::now() - chrono::Duration::hours(hours)).to_rfc3339();
+    let samples = sqlx::query_as::<_, (String, i64)>(
+        r#"SELECT sampled_at, online_users FROM "online_samples" WHERE sampled_at >= ? ORDER BY sampled_at ASC"#,
+    )
+    .bind(&since)
+    .fetch_all(&state.db)
+    .await?;
+
+    let items: Vec<serde_json::Value> = samples
+        .into_iter()
+        .map(|(at, n)| serde_json::json!({ "at": at, "onlineUsers": n }))
+        .collect();
+    Ok(Json(items))
+}
+
+/// Distinct users with at least one gateway connection, invisible included.
+async fn online_user_count(state: &AppState) -> usize {
+    state
+        .gateway
+        .clients
+        .read()
+        .await
+        .values()
+        .map(|c| c.user_id.as_str())
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Record how many users are online, and drop samples past the retention window.
+pub async fn record_online_sample(state: &AppState) -> sqlx::Result<()> {
+    let now = chrono::Utc::now();
+    sqlx::query(r#"INSERT OR REPLACE INTO "online_samples" (sampled_at, online_users) VALUES (?, ?)"#)
+        .bind(now.to_rfc3339())
+        .bind(online_user_count(state).await as i64)
+        .execute(&state.db)
+        .await?;
+    sqlx::query(r#"DELETE FROM "online_samples" WHERE sampled_at < ?"#)
+        .bind((now - chrono::Duration::days(ONLINE_HISTORY_DAYS)).to_rfc3339())
+        .execute(&state.db)
+        .await?;
+    Ok(())
+}
+
+/// Background task: sample the online count for the admin history graph.
+pub async fn run_online_sampler(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(ONLINE_SAMPLE_SECS));
+    loop {
+        interval.tick().await;
+        if let Err(e) = record_online_sample(&state).await {
+            tracing::warn!("Failed to record online sample: {}", e);
+        }
+    }
+}
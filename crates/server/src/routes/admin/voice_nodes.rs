@@ -0,0 +1,162 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use super::require_instance_admin;
+use crate::error::ApiError;
+use crate::models::{AuthUser, CreateVoiceNodeRequest, UpdateVoiceNodeRequest, VoiceNode};
+use crate::AppState;
+
+const MAX_REGION_LEN: usize = 32;
+
+fn validate_region(region: &str) -> Result<String, ApiError> {
+    let region = region.trim().to_lowercase();
+    let valid = !region.is_empty()
+        && region.len() <= MAX_REGION_LEN
+        && region.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(region)
+    } else {
+        Err(ApiError::bad_request(format!(
+            "Region must be 1-{} letters, digits, dashes or underscores",
+            MAX_REGION_LEN
+        )))
+    }
+}
+
+fn validate_url(url: &str) -> Result<String, ApiError> {
+    let url = url.trim();
+    if url.starts_with("ws://") || url.starts_with("wss://") {
+        Ok(url.to_string())
+    } else {
+        Err(ApiError::bad_request("Node URL must start with ws:// or wss://"))
+    }
+}
+
+async fn fetch_node(state: &AppState, node_id: &str) -> Result<VoiceNode, ApiError> {
+    sqlx::query_as::<_, VoiceNode>(r#"SELECT * FROM "voice_nodes" WHERE id = ?"#)
+        .bind(node_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Voice node not found"))
+}
+
+/// GET /api/admin/voice-nodes
+pub async fn list_voice_nodes_admin(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+    let nodes = sqlx::query_as::<_, VoiceNode>(r#"SELECT * FROM "voice_nodes" ORDER BY region, created_at"#)
+        .fetch_all(&state.db)
+        .await?;
+    Ok(Json(nodes))
+}
+
+/// POST /api/admin/voice-nodes
+pub async fn create_voice_node(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<CreateVoiceNodeRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+    let node = VoiceNode {
+        id: uuid::Uuid::new_v4().to_string(),
+        region: validate_region(&body.region)?,
+        url: validate_url(&body.url)?,
+        api_key: body.api_key.trim().to_string(),
+        api_secret: body.api_secret.trim().to_string(),
+        enabled: true,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    sqlx::query(
+        r#"INSERT INTO "voice_nodes" (id, region, url, api_key, api_secret, enabled, created_at)
+           VALUES (?, ?, ?, ?, ?, 1, ?)"#,
+    )
+    .bind(&node.id)
+    .bind(&node.region)
+    .bind(&node.url)
+    .bind(&node.api_key)
+    .bind(&node.api_secret)
+    .bind(&node.created_at)
+    .execute(&state.db)
+    .await?;
+
+    tracing::info!("{} added voice node {} ({}) in {}", user.username, node.id, node.url, node.region);
+    Ok((StatusCode::CREATED, Json(node)))
+}
+
+/// PATCH /api/admin/voice-nodes/:nodeId
+/// Disabling a node keeps calls already on it running but sends no one new.
+pub async fn update_voice_node(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(node_id): Path<String>,
+    Json(body): Json<UpdateVoiceNodeRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+    let mut node = fetch_node(&state, &node_id).await?;
+    if let Some(ref region) = body.region {
+        node.region = validate_region(region)?;
+    }
+    if let Some(ref url) = body.url {
+        node.url = validate_url(url)?;
+    }
+    if let Some(ref key) = body.api_key {
+        node.api_key = key.trim().to_string();
+    }
+    if let Some(ref secret) = body.api_secret {
+        node.api_secret = secret.trim().to_string();
+    }
+    if let Some(enabled) = body.enabled {
+        node.enabled = enabled;
+    }
+
+    sqlx::query(
+        r#"UPDATE "voice_nodes" SET region = ?, url = ?, api_key = ?, api_secret = ?, enabled = ? WHERE id = ?"#,
+    )
+    .bind(&node.region)
+    .bind(&node.url)
+    .bind(&node.api_key)
+    .bind(&node.api_secret)
+    .bind(node.enabled)
+    .bind(&node.id)
+    .execute(&state.db)
+    .await?;
+
+    Ok(Json(node))
+}
+
+/// DELETE /api/admin/voice-nodes/:nodeId
+/// Channels pinned to a region no node serves any more go back to automatic.
+pub async fn delete_voice_node(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(node_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_instance_admin(&state, &user.id).await?;
+    let node = fetch_node(&state, &node_id).await?;
+
+    let mut tx = state.db.begin().await?;
+    sqlx::query(r#"DELETE FROM "voice_nodes" WHERE id = ?"#)
+        .bind(&node.id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        r#"UPDATE channels SET voice_region = NULL
+           WHERE voice_region = ? AND NOT EXISTS (SELECT 1 FROM "voice_nodes" WHERE region = ?)"#,
+    )
+    .bind(&node.region)
+    .bind(&node.region)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    tracing::info!("{} removed voice node {} ({})", user.username, node.id, node.url);
+    Ok(StatusCode::NO_CONTENT)
+}
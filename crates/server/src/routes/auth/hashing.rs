@@ -0,0 +1,50 @@
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+
+use crate::config::Config;
+
+/// Argon2id with the configured cost. Falls back to the library defaults if
+/// the configured values are out of range.
+fn hasher(config: &Config) -> Argon2<'static> {
+    let params = argon2::Params::new(config.argon2_memory_kib, config.argon2_iterations, 1, None)
+        .unwrap_or_default();
+    Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+}
+
+/// A PHC string for the password, or None if hashing failed.
+pub(crate) fn hash_password(config: &Config, password: &str) -> Option<String> {
+    let salt = argon2::password_hash::SaltString::generate(&mut rand::rngs::OsRng);
+    hasher(config)
+        .hash_password(password.as_bytes(), &salt)
+        .ok()
+        .map(|h| h.to_string())
+}
+
+/// Check a password against a stored hash. The hash carries its own
+/// parameters, so this works whatever the current configuration.
+pub(crate) fn verify_password(stored: &str, password: &str) -> bool {
+    PasswordHash::new(stored).is_ok_and(|parsed| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok()
+    })
+}
+
+/// Whether a stored hash was made with different parameters than the
+/// configured ones and should be replaced.
+pub(crate) fn needs_rehash(config: &Config, stored: &str) -> bool {
+    let Ok(parsed) = PasswordHash::new(stored) else {
+        return true;
+    };
+    if parsed.algorithm != argon2::Algorithm::Argon2id.ident() {
+        return true;
+    }
+    let current = hasher(config);
+    match argon2::Params::try_from(&parsed) {
+        Ok(params) => {
+            params.m_cost() != current.params().m_cost()
+                || params.t_cost() != current.params().t_cost()
+                || params.p_cost() != current.params().p_cost()
+        }
+        Err(_) => true,
+    }
+}
@@ -1,62 +1,74 @@
+mod oidc;
+mod password;
+mod hashing;
 mod session;
+mod sessions;
+mod strength;
+mod throttle;
+mod tokens;
+mod verification;
 
+pub use oidc::*;
+pub use password::*;
 pub use session::*;
+pub use sessions::*;
+pub(crate) use hashing::*;
+pub(crate) use strength::*;
+pub use throttle::*;
+pub(crate) use tokens::*;
+pub use verification::*;
 
 use axum::{
     extract::State,
     http::{HeaderMap, StatusCode},
-    response::IntoResponse,
+    response::{Html, IntoResponse},
     Json,
 };
 use std::sync::Arc;
 
-use argon2::PasswordHasher;
+use crate::error::ApiError;
+use crate::middleware::ip_filter::ClientIp;
 use crate::models::{SessionResponse, SessionUser, SignUpRequest};
 use crate::ws::events::ServerEvent;
 use crate::AppState;
 
+/// A minimal page for links opened in the browser (provider callbacks,
+/// emailed links).
+pub(crate) fn page(title: &str, detail: &str) -> Html<String> {
+    Html(format!(
+        r#"<html><body style="background:#1a1a2e;color:#fff;font-family:system-ui;display:flex;align-items:center;justify-content:center;height:100vh;margin:0">
+        <div style="text-align:center"><h2>{}</h2><p>{}</p></div></body></html>"#,
+        title, detail
+    ))
+}
+
 /// POST /api/auth/sign-up/email
 pub async fn sign_up(
     State(state): State<Arc<AppState>>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
     Json(body): Json<SignUpRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let email = body.email.trim().to_lowercase();
     let username = body.username.trim().to_string();
     let name = body.name.trim().to_string();
 
-    // Whitelist gate: only whitelisted emails can register
-    // Bypass: allow the first user to register without being whitelisted (bootstrapping)
-    let user_count = sqlx::query_scalar::<_, i64>(
-        r#"SELECT COUNT(*) FROM "user""#,
-    )
-    .fetch_one(&state.db)
-    .await
-    .unwrap_or(1); // default to 1 so whitelist is enforced on error
-
-    if user_count > 0 {
-        let whitelisted = sqlx::query_scalar::<_, i64>(
-            r#"SELECT COUNT(*) FROM email_whitelist WHERE email = ?"#,
-        )
-        .bind(&email)
-        .fetch_one(&state.db)
-        .await
-        .unwrap_or(0);
-
-        if whitelisted == 0 {
-            return (
-                StatusCode::FORBIDDEN,
-                Json(serde_json::json!({"error": "Email not authorized"})),
-            )
-                .into_response();
+    let invite_hash = body
+        .invite_token
+        .as_deref()
+        .map(|token| token_hash(&state.config.auth_secret, token));
+    if let Some(hash) = &invite_hash {
+        if !crate::routes::whitelist::invite_is_open(&state, hash).await {
+            return Err(ApiError::forbidden("Invite is invalid or has expired"));
         }
     }
 
+    if !email_allowed(&state, &email, invite_hash.is_some()).await {
+        return Err(ApiError::forbidden("Email not authorized"));
+    }
+
     if username.len() < 2 || username.len() > 32 {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Username must be 2-32 characters"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("Username must be 2-32 characters"));
     }
 
     // Check if email already exists
@@ -67,11 +79,7 @@ pub async fn sign_up(
         .unwrap_or(0);
 
     if exists > 0 {
-        return (
-            StatusCode::CONFLICT,
-            Json(serde_json::json!({"error": "Email already registered"})),
-        )
-            .into_response();
+        return Err(ApiError::conflict("Email already registered"));
     }
 
     // Check if username already exists
@@ -83,92 +91,148 @@ pub async fn sign_up(
             .unwrap_or(0);
 
     if exists > 0 {
-        return (
-            StatusCode::CONFLICT,
-            Json(serde_json::json!({"error": "Username already taken"})),
-        )
-            .into_response();
+        return Err(ApiError::conflict("Username already taken"));
     }
 
-    // Hash password
-    let salt = argon2::password_hash::SaltString::generate(&mut rand::rngs::OsRng);
-    let password_hash = match argon2::Argon2::default().hash_password(body.password.as_bytes(), &salt) {
-        Ok(h) => h.to_string(),
-        Err(_) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "Failed to hash password"})),
-            )
-                .into_response()
+    check_password(&state.config, &body.password, &[&email, &username, &name])?;
+
+    let Some(password_hash) = hash_password(&state.config, &body.password) else {
+        return Err(ApiError::internal("Failed to hash password"));
+    };
+
+    // The user, their membership, credential and first session go in together
+    let mut tx = state.db.begin().await?;
+    let user = create_user(&mut tx, &email, &name, &username, None, false, &state.config.server_name).await?;
+    let user_id = user.id.clone();
+
+    // Lost a race for the same invite: nothing above is kept
+    if let Some(hash) = &invite_hash {
+        if !crate::routes::whitelist::redeem_invite(&mut tx, hash, &user_id).await? {
+            return Err(ApiError::forbidden("Invite is invalid or has expired"));
         }
+    }
+
+    add_password(&mut tx, &user_id, &password_hash).await?;
+
+    let session_token = create_session(&mut *tx, &user_id, client_ip, &headers).await?;
+    tx.commit().await?;
+    user.announce(&state).await;
+
+    if crate::mailer::enabled(&state.config) {
+        send_verification_email(&state, &user_id, &email).await;
+    }
+
+    let resp_headers = session_cookies(&state.config, Some(&session_token));
+
+    let body = SessionResponse {
+        user: SessionUser {
+            id: user_id,
+            email,
+            username,
+            image: None,
+        },
+        token: Some(session_token),
     };
 
-    let user_id = uuid::Uuid::new_v4().to_string();
-    let now = chrono::Utc::now().to_rfc3339();
+    Ok((StatusCode::OK, resp_headers, Json(body)).into_response())
+}
 
-    // Insert user
-    let result = sqlx::query(
-        r#"INSERT INTO "user" (id, name, username, email, emailVerified, createdAt, updatedAt)
-           VALUES (?, ?, ?, ?, 0, ?, ?)"#,
+/// Whether this email may register, going by the instance settings: nobody
+/// while registration is closed, only whitelisted (or invited) emails in
+/// whitelist mode. The very first user always can (bootstrapping).
+pub(crate) async fn email_allowed(state: &AppState, email: &str, invited: bool) -> bool {
+    let user_count = sqlx::query_scalar::<_, i64>(
+        r#"SELECT COUNT(*) FROM "user""#,
     )
-    .bind(&user_id)
-    .bind(&name)
-    .bind(&username)
-    .bind(&email)
-    .bind(&now)
-    .bind(&now)
-    .execute(&state.db)
-    .await;
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(1); // default to 1 so whitelist is enforced on error
 
-    if result.is_err() {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": "Failed to create user"})),
-        )
-            .into_response();
+    if user_count == 0 {
+        return true;
     }
 
-    // Insert account
-    let account_id = uuid::Uuid::new_v4().to_string();
-    let _ = sqlx::query(
-        r#"INSERT INTO "account" (id, userId, accountId, providerId, password, createdAt, updatedAt)
-           VALUES (?, ?, ?, 'credential', ?, ?, ?)"#,
-    )
-    .bind(&account_id)
-    .bind(&user_id)
-    .bind(&user_id)
-    .bind(&password_hash)
-    .bind(&now)
-    .bind(&now)
-    .execute(&state.db)
-    .await;
+    // Fail closed if the settings can't be read
+    let Ok(settings) = crate::routes::admin::instance_settings(&state.db).await else {
+        return false;
+    };
+    if !settings.registration_open {
+        return false;
+    }
+    if !settings.whitelist_enabled || invited {
+        return true;
+    }
 
-    // Create session
-    let session_token = uuid::Uuid::new_v4().to_string();
-    let session_id = uuid::Uuid::new_v4().to_string();
-    let expires_at = (chrono::Utc::now() + chrono::Duration::days(30)).to_rfc3339();
+    crate::routes::whitelist::is_whitelisted(state, email).await
+}
 
-    let _ = sqlx::query(
-        r#"INSERT INTO "session" (id, userId, token, expiresAt, createdAt, updatedAt)
-           VALUES (?, ?, ?, ?, ?, ?)"#,
+/// A user `create_user` inserted, and the membership they were given.
+pub(crate) struct NewUser {
+    pub id: String,
+    server_id: String,
+    role: &'static str,
+    username: String,
+    image: Option<String>,
+}
+
+impl NewUser {
+    /// Tell connected clients about the new member. Call once the user is
+    /// committed.
+    pub(crate) async fn announce(&self, state: &AppState) {
+        state.gateway.broadcast_all(
+            &ServerEvent::MemberJoined {
+                server_id: self.server_id.clone(),
+                user_id: self.id.clone(),
+                username: self.username.clone(),
+                image: self.image.clone(),
+                role: self.role.to_string(),
+                ring_style: "default".to_string(),
+                ring_spin: false,
+                ring_pattern_seed: None,
+                banner_css: None,
+                banner_pattern_seed: None,
+            },
+            None,
+        ).await;
+    }
+}
+
+/// Insert a new user and join them to the server (creating it, named
+/// `server_name`, for the first user). Run it in a transaction: a failure
+/// part way leaves the user without a membership.
+pub(crate) async fn create_user(
+    conn: &mut sqlx::SqliteConnection,
+    email: &str,
+    name: &str,
+    username: &str,
+    image: Option<&str>,
+    email_verified: bool,
+    server_name: &str,
+) -> sqlx::Result<NewUser> {
+    let user_id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"INSERT INTO "user" (id, name, username, email, emailVerified, image, createdAt, updatedAt)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
     )
-    .bind(&session_id)
     .bind(&user_id)
-    .bind(&session_token)
-    .bind(&expires_at)
+    .bind(name)
+    .bind(username)
+    .bind(email)
+    .bind(email_verified)
+    .bind(image)
     .bind(&now)
     .bind(&now)
-    .execute(&state.db)
-    .await;
+    .execute(&mut *conn)
+    .await?;
 
     // Auto-create server on first registration, or join existing server
     let existing_server = sqlx::query_scalar::<_, String>(
         "SELECT id FROM servers ORDER BY created_at ASC LIMIT 1",
     )
-    .fetch_optional(&state.db)
-    .await
-    .ok()
-    .flatten();
+    .fetch_optional(&mut *conn)
+    .await?;
 
     let (server_id, role) = if let Some(id) = existing_server {
         (id, "member")
@@ -176,14 +240,14 @@ pub async fn sign_up(
         // First user: create the default server + channels
         let sid = uuid::Uuid::new_v4().to_string();
         sqlx::query(
-            "INSERT INTO servers (id, name, owner_id, invite_code, created_at) VALUES (?, 'flux', ?, 'none', ?)",
+            "INSERT INTO servers (id, name, owner_id, invite_code, created_at) VALUES (?, ?, ?, 'none', ?)",
         )
         .bind(&sid)
+        .bind(server_name)
         .bind(&user_id)
         .bind(&now)
-        .execute(&state.db)
-        .await
-        .ok();
+        .execute(&mut *conn)
+        .await?;
 
         sqlx::query(
             "INSERT INTO channels (id, server_id, name, type, parent_id, position, created_at) VALUES (?, ?, 'general', 'text', NULL, 0, ?)",
@@ -191,9 +255,8 @@ pub async fn sign_up(
         .bind(uuid::Uuid::new_v4().to_string())
         .bind(&sid)
         .bind(&now)
-        .execute(&state.db)
-        .await
-        .ok();
+        .execute(&mut *conn)
+        .await?;
 
         sqlx::query(
             "INSERT INTO channels (id, server_id, name, type, parent_id, position, is_room, created_at) VALUES (?, ?, 'Lobby', 'voice', NULL, 1, 1, ?)",
@@ -201,9 +264,14 @@ pub async fn sign_up(
         .bind(uuid::Uuid::new_v4().to_string())
         .bind(&sid)
         .bind(&now)
-        .execute(&state.db)
-        .await
-        .ok();
+        .execute(&mut *conn)
+        .await?;
+
+        // The first user runs the instance
+        sqlx::query(r#"UPDATE "user" SET is_instance_admin = 1 WHERE id = ?"#)
+            .bind(&user_id)
+            .execute(&mut *conn)
+            .await?;
 
         (sid, "owner")
     };
@@ -216,45 +284,36 @@ pub async fn sign_up(
     .bind(role)
     .bind(&now)
     .bind(&now)
-    .execute(&state.db)
-    .await
-    .ok();
-
-    // Broadcast member_joined to all connected clients
-    state.gateway.broadcast_all(
-        &ServerEvent::MemberJoined {
-            server_id: server_id.clone(),
-            user_id: user_id.clone(),
-            username: username.clone(),
-            image: None,
-            role: role.to_string(),
-            ring_style: "default".to_string(),
-            ring_spin: false,
-            ring_pattern_seed: None,
-            banner_css: None,
-            banner_pattern_seed: None,
-        },
-        None,
-    ).await;
-
-    // Set cookie header
-    let cookie = format!(
-        "better-auth.session_token={}; HttpOnly; SameSite=None; Path=/; Max-Age=2592000",
-        session_token
-    );
+    .execute(&mut *conn)
+    .await?;
 
-    let mut headers = HeaderMap::new();
-    headers.insert("set-cookie", cookie.parse().unwrap());
-
-    let body = SessionResponse {
-        user: SessionUser {
-            id: user_id,
-            email,
-            username,
-            image: None,
-        },
-        token: Some(session_token),
-    };
+    Ok(NewUser {
+        id: user_id,
+        server_id,
+        role,
+        username: username.to_string(),
+        image: image.map(str::to_string),
+    })
+}
 
-    (StatusCode::OK, headers, Json(body)).into_response()
+/// Let a user sign in with a password, given its hash.
+pub(crate) async fn add_password(
+    conn: &mut sqlx::SqliteConnection,
+    user_id: &str,
+    password_hash: &str,
+) -> sqlx::Result<()> {
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"INSERT INTO "account" (id, userId, accountId, providerId, password, createdAt, updatedAt)
+           VALUES (?, ?, ?, 'credential', ?, ?, ?)"#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(user_id)
+    .bind(user_id)
+    .bind(password_hash)
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
 }
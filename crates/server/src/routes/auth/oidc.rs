@@ -0,0 +1,479 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use base64::Engine;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::{create_session, create_user, email_allowed, extract_token, page, random_token, session_cookies};
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::middleware::ip_filter::ClientIp;
+use crate::models::{AuthUser, SessionResponse, SessionUser};
+use crate::AppState;
+
+/// `providerId` of linked identities in the `account` table.
+const PROVIDER_ID: &str = "oidc";
+/// How long the user has to finish signing in at the provider.
+const PENDING_TTL: Duration = Duration::from_secs(600);
+
+/// A sign-in started with /oidc/start, waiting for the provider callback and
+/// then for the app to collect the result.
+pub struct OidcPending {
+    /// Handed to the app only; the app polls /oidc/complete with it.
+    pub poll_token: String,
+    pub code_verifier: String,
+    /// Set when a signed-in user is linking the provider to their account.
+    pub link_user_id: Option<String>,
+    pub started: Instant,
+    pub outcome: Option<OidcOutcome>,
+}
+
+pub enum OidcOutcome {
+    SignedIn(String),
+    Linked,
+    Failed(String),
+}
+
+#[derive(Deserialize)]
+struct Discovery {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct UserInfo {
+    sub: String,
+    email: Option<String>,
+    email_verified: Option<bool>,
+    preferred_username: Option<String>,
+    name: Option<String>,
+    picture: Option<String>,
+}
+
+fn configured(config: &Config) -> bool {
+    !config.oidc_issuer.is_empty() && !config.oidc_client_id.is_empty()
+}
+
+/// The provider's endpoints, from its discovery document.
+async fn discover(issuer: &str) -> Result<Discovery, String> {
+    let url = format!("{}/.well-known/openid-configuration", issuer.trim_end_matches('/'));
+    reqwest::Client::new()
+        .get(&url)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// PKCE S256: base64url of the verifier's sha256.
+fn code_challenge(verifier: &str) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// GET /api/auth/oidc/start
+/// Returns the provider page to open and a `pollToken` for /oidc/complete.
+/// Called with a session, the identity is linked to that account instead of
+/// signing in.
+pub async fn oidc_start(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    if !configured(&state.config) {
+        return Err(ApiError::unavailable("OIDC sign-in is not configured"));
+    }
+
+    let discovery = match discover(&state.config.oidc_issuer).await {
+        Ok(d) => d,
+        Err(e) => {
+            tracing::error!("OIDC discovery failed: {}", e);
+            return Err(ApiError::upstream("Identity provider unavailable"));
+        }
+    };
+
+    let link_user_id = match extract_token(&headers) {
+        Some(token) => sqlx::query_scalar::<_, String>(
+            r#"SELECT userId FROM "session" WHERE token = ? AND expiresAt > ?"#,
+        )
+        .bind(&token)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten(),
+        None => None,
+    };
+
+    let nonce = random_token();
+    let poll_token = random_token();
+    let code_verifier = random_token();
+
+    let mut url = match url::Url::parse(&discovery.authorization_endpoint) {
+        Ok(u) => u,
+        Err(_) => return Err(ApiError::upstream("Identity provider unavailable")),
+    };
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &state.config.oidc_client_id)
+        .append_pair("redirect_uri", &state.config.oidc_redirect_uri)
+        .append_pair("scope", "openid email profile")
+        .append_pair("state", &nonce)
+        .append_pair("code_challenge", &code_challenge(&code_verifier))
+        .append_pair("code_challenge_method", "S256");
+
+    let linking = link_user_id.is_some();
+    let mut pending = state.oidc_pending.write().await;
+    pending.retain(|_, p| p.started.elapsed() < PENDING_TTL);
+    pending.insert(
+        nonce,
+        OidcPending {
+            poll_token: poll_token.clone(),
+            code_verifier,
+            link_user_id,
+            started: Instant::now(),
+            outcome: None,
+        },
+    );
+    drop(pending);
+
+    Ok(Json(serde_json::json!({
+        "url": url.to_string(),
+        "pollToken": poll_token,
+        "linking": linking,
+    }))
+    .into_response())
+}
+
+/// Link `sub` to a user, replacing any identity they had linked before.
+async fn link_identity(db: impl sqlx::SqliteExecutor<'_>, user_id: &str, sub: &str) -> sqlx::Result<()> {
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"INSERT INTO "account" (id, userId, accountId, providerId, createdAt, updatedAt)
+           VALUES (?, ?, ?, ?, ?, ?)
+           ON CONFLICT(userId, providerId) DO UPDATE SET accountId = excluded.accountId, updatedAt = excluded.updatedAt"#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(user_id)
+    .bind(sub)
+    .bind(PROVIDER_ID)
+    .bind(&now)
+    .bind(&now)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// A username nobody has yet, based on what the provider calls the user.
+async fn free_username(state: &AppState, info: &UserInfo, email: &str) -> String {
+    let base: String = info
+        .preferred_username
+        .as_deref()
+        .or(info.name.as_deref())
+        .unwrap_or_else(|| email.split('@').next().unwrap_or(""))
+        .trim()
+        .chars()
+        .take(27)
+        .collect();
+    let base = if base.chars().count() < 2 { "user".to_string() } else { base };
+
+    let mut candidate = base.clone();
+    for _ in 0..10 {
+        let taken = sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "user" WHERE username = ?"#)
+            .bind(&candidate)
+            .fetch_one(&state.db)
+            .await
+            .unwrap_or(1);
+        if taken == 0 {
+            break;
+        }
+        candidate = format!("{}{}", base, rand::random::<u16>() % 10_000);
+    }
+    candidate
+}
+
+/// Match the provider identity to a user: an already linked account, the
+/// account being linked, an existing account with the same (verified)
+/// email, or a new account if the email is whitelisted.
+async fn resolve(state: &AppState, link_user_id: Option<&str>, info: UserInfo) -> OidcOutcome {
+    let linked = sqlx::query_scalar::<_, String>(
+        r#"SELECT userId FROM "account" WHERE providerId = ? AND accountId = ?"#,
+    )
+    .bind(PROVIDER_ID)
+    .bind(&info.sub)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    match (linked, link_user_id) {
+        (Some(owner), Some(user_id)) if owner != user_id => {
+            return OidcOutcome::Failed("This identity is already linked to another account.".into())
+        }
+        (Some(_), Some(_)) => return OidcOutcome::Linked,
+        (Some(owner), None) => return OidcOutcome::SignedIn(owner),
+        (None, Some(user_id)) => {
+            return match link_identity(&state.db, user_id, &info.sub).await {
+                Ok(()) => OidcOutcome::Linked,
+                Err(_) => OidcOutcome::Failed("Failed to link account".into()),
+            };
+        }
+        (None, None) => {}
+    }
+
+    let Some(email) = info.email.as_deref().map(|e| e.trim().to_lowercase()) else {
+        return OidcOutcome::Failed("The identity provider did not share an email address.".into());
+    };
+
+    let existing = sqlx::query_scalar::<_, String>(r#"SELECT id FROM "user" WHERE email = ?"#)
+        .bind(&email)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten();
+    if let Some(user_id) = existing {
+        // Only trust the email for an existing account if the provider verified it
+        if info.email_verified != Some(true) {
+            return OidcOutcome::Failed(
+                "An account with this email already exists. Sign in with your password and link the provider from settings.".into(),
+            );
+        }
+        if link_identity(&state.db, &user_id, &info.sub).await.is_err() {
+            return OidcOutcome::Failed("Failed to link account".into());
+        }
+        return OidcOutcome::SignedIn(user_id);
+    }
+
+    if !email_allowed(state, &email, false).await {
+        return OidcOutcome::Failed("Email not authorized".into());
+    }
+
+    let username = free_username(state, &info, &email).await;
+    let name = info.name.clone().unwrap_or_else(|| username.clone());
+    let verified = info.email_verified == Some(true);
+    let created = async {
+        let mut tx = state.db.begin().await?;
+        let user = create_user(
+            &mut tx,
+            &email,
+            &name,
+            &username,
+            info.picture.as_deref(),
+            verified,
+            &state.config.server_name,
+        )
+        .await?;
+        link_identity(&mut *tx, &user.id, &info.sub).await?;
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(user)
+    };
+    match created.await {
+        Ok(user) => {
+            user.announce(state).await;
+            OidcOutcome::SignedIn(user.id)
+        }
+        Err(e) => {
+            tracing::error!("Failed to create user from identity provider: {}", e);
+            OidcOutcome::Failed("Failed to create user".into())
+        }
+    }
+}
+
+/// Exchange the code and fetch the user's claims from the userinfo endpoint.
+async fn fetch_identity(config: &Config, code: &str, code_verifier: &str) -> Result<UserInfo, String> {
+    let discovery = discover(&config.oidc_issuer).await?;
+    let client = reqwest::Client::new();
+
+    let token: TokenResponse = client
+        .post(&discovery.token_endpoint)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", config.oidc_redirect_uri.as_str()),
+            ("client_id", config.oidc_client_id.as_str()),
+            ("client_secret", config.oidc_client_secret.as_str()),
+            ("code_verifier", code_verifier),
+        ])
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    client
+        .get(&discovery.userinfo_endpoint)
+        .bearer_auth(&token.access_token)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// GET /api/auth/oidc/callback
+#[derive(Deserialize)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
+
+pub async fn oidc_callback(
+    Query(query): Query<OidcCallbackQuery>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let Some(nonce) = query.state else {
+        return page("Sign-in Failed", "Missing state parameter.").into_response();
+    };
+    let (code_verifier, link_user_id) = {
+        let pending = state.oidc_pending.read().await;
+        match pending.get(&nonce) {
+            Some(p) if p.outcome.is_none() && p.started.elapsed() < PENDING_TTL => {
+                (p.code_verifier.clone(), p.link_user_id.clone())
+            }
+            _ => {
+                return page("Sign-in session expired", "Please try again from the app.").into_response()
+            }
+        }
+    };
+
+    let outcome = match (query.error, query.code) {
+        (Some(error), _) => {
+            tracing::warn!("OIDC provider returned error: {}", error);
+            OidcOutcome::Failed("The identity provider did not authorize the sign-in.".into())
+        }
+        (None, None) => OidcOutcome::Failed("Missing authorization code.".into()),
+        (None, Some(code)) => match fetch_identity(&state.config, &code, &code_verifier).await {
+            Ok(info) => resolve(&state, link_user_id.as_deref(), info).await,
+            Err(e) => {
+                tracing::error!("OIDC code exchange failed: {}", e);
+                OidcOutcome::Failed("Could not verify your identity with the provider.".into())
+            }
+        },
+    };
+
+    let response = match &outcome {
+        OidcOutcome::SignedIn(_) => page("Signed In", "You can close this tab and return to Flux."),
+        OidcOutcome::Linked => page("Account Linked", "You can close this tab and return to Flux."),
+        OidcOutcome::Failed(reason) => page("Sign-in Failed", reason),
+    };
+    if let Some(p) = state.oidc_pending.write().await.get_mut(&nonce) {
+        p.outcome = Some(outcome);
+    }
+    response.into_response()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcCompleteRequest {
+    pub poll_token: String,
+}
+
+/// POST /api/auth/oidc/complete
+/// Polled by the app after opening the provider page: 202 while the user is
+/// still at the provider, then the new session (or `linked`) once.
+pub async fn oidc_complete(
+    State(state): State<Arc<AppState>>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
+    Json(body): Json<OidcCompleteRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let outcome = {
+        let mut pending = state.oidc_pending.write().await;
+        let Some(nonce) = pending
+            .iter()
+            .find(|(_, p)| p.poll_token == body.poll_token && p.started.elapsed() < PENDING_TTL)
+            .map(|(nonce, _)| nonce.clone())
+        else {
+            return Err(ApiError::not_found("Sign-in expired"));
+        };
+        if pending.get(&nonce).is_some_and(|p| p.outcome.is_none()) {
+            return Ok((StatusCode::ACCEPTED, Json(serde_json::json!({"status": "pending"}))).into_response());
+        }
+        pending.remove(&nonce).and_then(|p| p.outcome)
+    };
+
+    let user_id = match outcome {
+        Some(OidcOutcome::SignedIn(user_id)) => user_id,
+        Some(OidcOutcome::Linked) => return Ok(Json(serde_json::json!({"linked": true})).into_response()),
+        Some(OidcOutcome::Failed(reason)) => return Err(ApiError::forbidden(reason)),
+        None => return Err(ApiError::not_found("Sign-in expired")),
+    };
+
+    let user = sqlx::query_as::<_, (String, String, Option<String>)>(
+        r#"SELECT email, username, image FROM "user" WHERE id = ?"#,
+    )
+    .bind(&user_id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+    let Some((email, username, image)) = user else {
+        return Err(ApiError::not_found("User not found"));
+    };
+
+    let session_token = create_session(&state.db, &user_id, client_ip, &headers).await?;
+    let resp_headers = session_cookies(&state.config, Some(&session_token));
+
+    let body = SessionResponse {
+        user: SessionUser {
+            id: user_id,
+            email,
+            username,
+            image,
+        },
+        token: Some(session_token),
+    };
+
+    Ok((StatusCode::OK, resp_headers, Json(body)).into_response())
+}
+
+/// DELETE /api/auth/oidc
+/// Unlink the provider. Refused if it's the only way to sign in.
+pub async fn oidc_unlink(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    let has_password = sqlx::query_scalar::<_, i64>(
+        r#"SELECT COUNT(*) FROM "account" WHERE userId = ? AND providerId = 'credential'"#,
+    )
+    .bind(&user.id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0);
+
+    if has_password == 0 {
+        return Err(ApiError::bad_request("Set a password before unlinking your only sign-in method"));
+    }
+
+    sqlx::query(r#"DELETE FROM "account" WHERE userId = ? AND providerId = ?"#)
+        .bind(&user.id)
+        .bind(PROVIDER_ID)
+        .execute(&state.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
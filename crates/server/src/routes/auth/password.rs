@@ -0,0 +1,155 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use minijinja::context;
+use std::sync::Arc;
+
+use super::{check_password, hash_password, random_token, revoke_user_sessions, token_hash};
+use crate::error::ApiError;
+use crate::models::{ForgotPasswordRequest, ResetPasswordRequest};
+use crate::AppState;
+
+/// How long a reset link stays valid.
+const RESET_TTL_MINUTES: i64 = 60;
+/// At most this many reset emails per account per `RESET_TTL_MINUTES`.
+const MAX_RESETS_PER_WINDOW: i64 = 3;
+
+/// POST /api/auth/forgot-password
+/// Emails a single-use reset token. Always answers 202 so the response
+/// doesn't reveal which emails have accounts.
+pub async fn forgot_password(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<ForgotPasswordRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    if !crate::mailer::enabled(&state.config) {
+        return Err(ApiError::unavailable("Password reset is not available on this server"));
+    }
+
+    let email = body.email.trim().to_lowercase();
+    let accepted = (StatusCode::ACCEPTED, Json(serde_json::json!({}))).into_response();
+
+    let user_id = sqlx::query_scalar::<_, String>(r#"SELECT id FROM "user" WHERE email = ?"#)
+        .bind(&email)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten();
+    let Some(user_id) = user_id else {
+        return Ok(accepted);
+    };
+
+    let now = chrono::Utc::now();
+    let window_start = (now - chrono::Duration::minutes(RESET_TTL_MINUTES)).to_rfc3339();
+    let recent = sqlx::query_scalar::<_, i64>(
+        r#"SELECT COUNT(*) FROM "password_resets" WHERE user_id = ? AND created_at > ?"#,
+    )
+    .bind(&user_id)
+    .bind(&window_start)
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(MAX_RESETS_PER_WINDOW);
+    if recent >= MAX_RESETS_PER_WINDOW {
+        return Ok(accepted);
+    }
+
+    let token = random_token();
+    let inserted = sqlx::query(
+        r#"INSERT INTO "password_resets" (id, user_id, token_hash, expires_at, created_at)
+           VALUES (?, ?, ?, ?, ?)"#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(&user_id)
+    .bind(token_hash(&state.config.auth_secret, &token))
+    .bind((now + chrono::Duration::minutes(RESET_TTL_MINUTES)).to_rfc3339())
+    .bind(now.to_rfc3339())
+    .execute(&state.db)
+    .await;
+    // Still 202: an error here would give away that the account exists
+    if let Err(e) = inserted {
+        tracing::error!("Failed to store password reset: {}", e);
+        return Ok(accepted);
+    }
+
+    // Queued and sent in the background, so response timing doesn't give
+    // accounts away
+    let ctx = context! { code => token, minutes => RESET_TTL_MINUTES };
+    match crate::mailer::render("password_reset", &email, "Reset your Flux password", ctx) {
+        Ok(email) => crate::mailer::queue(&state, email).await,
+        Err(e) => tracing::error!("Failed to render password reset email: {}", e),
+    }
+
+    Ok(accepted)
+}
+
+/// POST /api/auth/reset-password
+/// Sets a new password with a reset token. The token is used up, and every
+/// session on the account is signed out.
+pub async fn reset_password(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<ResetPasswordRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let hash = token_hash(&state.config.auth_secret, body.token.trim());
+
+    // Check the new password before the token is spent, so a weak one can be
+    // retried with the same link
+    let owner = sqlx::query_as::<_, (String, String, String)>(
+        r#"SELECT u.email, u.username, u.name FROM "password_resets" r
+           JOIN "user" u ON u.id = r.user_id
+           WHERE r.token_hash = ? AND r.used_at IS NULL AND r.expires_at > ?"#,
+    )
+    .bind(&hash)
+    .bind(&now)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+    if let Some((email, username, name)) = &owner {
+        check_password(&state.config, &body.new_password, &[email, username, name])?;
+    }
+
+    let user_id = sqlx::query_scalar::<_, String>(
+        r#"UPDATE "password_resets" SET used_at = ?
+           WHERE token_hash = ? AND used_at IS NULL AND expires_at > ?
+           RETURNING user_id"#,
+    )
+    .bind(&now)
+    .bind(&hash)
+    .bind(&now)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    let Some(user_id) = user_id else {
+        return Err(ApiError::bad_request("Invalid or expired reset token"));
+    };
+
+    let Some(password_hash) = hash_password(&state.config, &body.new_password) else {
+        return Err(ApiError::internal("Failed to hash password"));
+    };
+
+    // Accounts that only used OIDC get a password here too
+    sqlx::query(
+        r#"INSERT INTO "account" (id, userId, accountId, providerId, password, createdAt, updatedAt)
+           VALUES (?, ?, ?, 'credential', ?, ?, ?)
+           ON CONFLICT(userId, providerId) DO UPDATE SET password = excluded.password, updatedAt = excluded.updatedAt"#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(&user_id)
+    .bind(&user_id)
+    .bind(&password_hash)
+    .bind(&now)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    // Any other outstanding tokens are void now
+    sqlx::query(r#"UPDATE "password_resets" SET used_at = ? WHERE user_id = ? AND used_at IS NULL"#)
+        .bind(&now)
+        .bind(&user_id)
+        .execute(&state.db)
+        .await?;
+
+    revoke_user_sessions(&state, &user_id, None).await;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
@@ -1,22 +1,35 @@
 use axum::{
     extract::State,
-    http::{HeaderMap, StatusCode},
+    http::{header::SET_COOKIE, HeaderMap, StatusCode},
     response::IntoResponse,
     Json,
 };
 use std::sync::Arc;
 
+use super::{hash_password, needs_rehash, record_failure, record_success, sign_in_blocked, verify_password};
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::middleware::csrf::{cookie_value, csrf_token, CSRF_COOKIE, SESSION_COOKIE};
+use crate::middleware::ip_filter::ClientIp;
 use crate::models::{SessionResponse, SessionUser, SignInRequest};
 use crate::AppState;
 
 /// POST /api/auth/sign-in/email
 pub async fn sign_in(
     State(state): State<Arc<AppState>>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
     Json(body): Json<SignInRequest>,
-) -> impl IntoResponse {
-    use argon2::PasswordVerifier;
-
+) -> Result<impl IntoResponse, ApiError> {
     let email = body.email.trim().to_lowercase();
+    let (ip, _) = client_info(client_ip, &headers);
+
+    if let Some(retry_after) = sign_in_blocked(&state, &email, ip.as_deref()).await {
+        return Err(ApiError::rate_limited(
+            "Too many failed sign-in attempts. Try again later.",
+            retry_after,
+        ));
+    }
 
     // Look up user
     let user = sqlx::query_as::<_, (String, String, String, Option<String>, String, bool)>(
@@ -31,11 +44,8 @@ pub async fn sign_in(
     let (user_id, user_email, username, image, _ring_style, _ring_spin) = match user {
         Some(u) => u,
         None => {
-            return (
-                StatusCode::UNAUTHORIZED,
-                Json(serde_json::json!({"error": "Invalid credentials"})),
-            )
-                .into_response()
+            record_failure(&state, &email, None, ip.as_deref()).await;
+            return Err(ApiError::unauthorized("Invalid credentials"))
         }
     };
 
@@ -52,79 +62,158 @@ pub async fn sign_in(
     let stored_hash = match stored_hash {
         Some(h) => h,
         None => {
-            return (
-                StatusCode::UNAUTHORIZED,
-                Json(serde_json::json!({"error": "Invalid credentials"})),
-            )
-                .into_response()
+            record_failure(&state, &email, Some(&user_id), ip.as_deref()).await;
+            return Err(ApiError::unauthorized("Invalid credentials"))
         }
     };
 
-    // Verify password
-    let parsed_hash = match argon2::PasswordHash::new(&stored_hash) {
-        Ok(h) => h,
-        Err(_) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "Internal error"})),
+    if !verify_password(&stored_hash, &body.password) {
+        record_failure(&state, &email, Some(&user_id), ip.as_deref()).await;
+        return Err(ApiError::unauthorized("Invalid credentials"));
+    }
+
+    // Upgrade hashes made with older parameters while we have the password
+    if needs_rehash(&state.config, &stored_hash) {
+        if let Some(new_hash) = hash_password(&state.config, &body.password) {
+            sqlx::query(
+                r#"UPDATE "account" SET password = ?, updatedAt = ? WHERE userId = ? AND providerId = 'credential'"#,
             )
-                .into_response()
+            .bind(&new_hash)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(&user_id)
+            .execute(&state.db)
+            .await?;
         }
+    }
+
+    record_success(&state, &email, &user_id, ip.as_deref()).await;
+    let session_token = create_session(&state.db, &user_id, client_ip, &headers).await?;
+
+    let resp_headers = session_cookies(&state.config, Some(&session_token));
+
+    let body = SessionResponse {
+        user: SessionUser {
+            id: user_id,
+            email: user_email,
+            username,
+            image,
+        },
+        token: Some(session_token),
     };
 
-    if argon2::Argon2::default()
-        .verify_password(body.password.as_bytes(), &parsed_hash)
-        .is_err()
-    {
-        return (
-            StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({"error": "Invalid credentials"})),
-        )
-            .into_response();
-    }
+    Ok((StatusCode::OK, resp_headers, Json(body)).into_response())
+}
 
-    // Create session
+/// The client's address and user agent, as recorded on its session.
+pub(crate) fn client_info(ip: ClientIp, headers: &HeaderMap) -> (Option<String>, Option<String>) {
+    let user_agent = headers
+        .get("user-agent")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.chars().take(512).collect());
+    (ip.0.map(|ip| ip.to_string()), user_agent)
+}
+
+/// Start a 30-day session for the user. Returns its token.
+pub(crate) async fn create_session(
+    db: impl sqlx::SqliteExecutor<'_>,
+    user_id: &str,
+    ip: ClientIp,
+    headers: &HeaderMap,
+) -> sqlx::Result<String> {
     let session_token = uuid::Uuid::new_v4().to_string();
-    let session_id = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
     let expires_at = (chrono::Utc::now() + chrono::Duration::days(30)).to_rfc3339();
+    let (ip, user_agent) = client_info(ip, headers);
 
-    let _ = sqlx::query(
-        r#"INSERT INTO "session" (id, userId, token, expiresAt, createdAt, updatedAt)
-           VALUES (?, ?, ?, ?, ?, ?)"#,
+    sqlx::query(
+        r#"INSERT INTO "session" (id, userId, token, expiresAt, ipAddress, userAgent, createdAt, updatedAt)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
     )
-    .bind(&session_id)
-    .bind(&user_id)
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(user_id)
     .bind(&session_token)
     .bind(&expires_at)
+    .bind(ip)
+    .bind(user_agent)
     .bind(&now)
     .bind(&now)
-    .execute(&state.db)
-    .await;
+    .execute(db)
+    .await?;
 
-    let cookie = format!(
-        "better-auth.session_token={}; HttpOnly; SameSite=None; Path=/; Max-Age=2592000",
-        session_token
-    );
+    Ok(session_token)
+}
+
+/// Record that a session was just used. `updatedAt` doubles as last-seen.
+pub(crate) async fn touch_session(state: &AppState, session_id: &str) {
+    let _ = sqlx::query(r#"UPDATE "session" SET updatedAt = ? WHERE id = ?"#)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(session_id)
+        .execute(&state.db)
+        .await;
+}
+
+/// How long session cookies last, matching the session itself.
+const COOKIE_MAX_AGE_SECS: i64 = 2_592_000;
+
+/// Attributes shared by the session and CSRF cookies.
+fn cookie_attributes(config: &Config, max_age: i64) -> String {
+    let mut attributes = format!("Path=/; Max-Age={}", max_age);
+    if config.cookie_secure {
+        attributes.push_str("; SameSite=None; Secure");
+    } else {
+        attributes.push_str("; SameSite=Lax");
+    }
+    if !config.cookie_domain.is_empty() {
+        attributes.push_str(&format!("; Domain={}", config.cookie_domain));
+    }
+    attributes
+}
+
+/// Set-Cookie headers for a session: the HttpOnly session cookie and the
+/// CSRF cookie scripts read to echo back. `None` clears both.
+pub(crate) fn session_cookies(config: &Config, session_token: Option<&str>) -> HeaderMap {
+    let (session, csrf, max_age) = match session_token {
+        Some(token) => (token.to_string(), csrf_token(&config.auth_secret, token), COOKIE_MAX_AGE_SECS),
+        None => (String::new(), String::new(), 0),
+    };
+    let attributes = cookie_attributes(config, max_age);
 
     let mut headers = HeaderMap::new();
-    headers.insert("set-cookie", cookie.parse().unwrap());
+    for cookie in [
+        format!("{}={}; HttpOnly; {}", SESSION_COOKIE, session, attributes),
+        format!("{}={}; {}", CSRF_COOKIE, csrf, attributes),
+    ] {
+        if let Ok(value) = cookie.parse() {
+            headers.append(SET_COOKIE, value);
+        }
+    }
+    headers
+}
 
-    let body = SessionResponse {
-        user: SessionUser {
-            id: user_id,
-            email: user_email,
-            username,
-            image,
-        },
-        token: Some(session_token),
+/// GET /api/auth/csrf
+/// The CSRF token for the cookie session, re-issuing its cookie. For
+/// sessions that started before the token existed.
+pub async fn get_csrf_token(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    let Some(session) = cookie_value(&headers, SESSION_COOKIE).filter(|t| !t.is_empty()) else {
+        return Err(ApiError::unauthorized("Not authenticated"));
     };
 
-    (StatusCode::OK, headers, Json(body)).into_response()
+    let token = csrf_token(&state.config.auth_secret, &session);
+    let cookie = format!(
+        "{}={}; {}",
+        CSRF_COOKIE,
+        token,
+        cookie_attributes(&state.config, COOKIE_MAX_AGE_SECS)
+    );
+
+    Ok(([(SET_COOKIE, cookie)], Json(serde_json::json!({"csrfToken": token}))).into_response())
 }
 
 /// Extract session token from headers (Authorization or cookie).
-fn extract_token(headers: &HeaderMap) -> Option<String> {
+pub(crate) fn extract_token(headers: &HeaderMap) -> Option<String> {
     let token_from_header = headers
         .get("authorization")
         .and_then(|v| v.to_str().ok())
@@ -153,22 +242,17 @@ fn extract_token(headers: &HeaderMap) -> Option<String> {
 pub async fn sign_out(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     if let Some(token) = extract_token(&headers) {
-        let _ = sqlx::query(r#"DELETE FROM "session" WHERE token = ?"#)
+        sqlx::query(r#"DELETE FROM "session" WHERE token = ?"#)
             .bind(&token)
             .execute(&state.db)
-            .await;
+            .await?;
     }
 
-    // Clear cookie
-    let cookie =
-        "better-auth.session_token=; HttpOnly; SameSite=None; Path=/; Max-Age=0".to_string();
-
-    let mut resp_headers = HeaderMap::new();
-    resp_headers.insert("set-cookie", cookie.parse().unwrap());
+    let resp_headers = session_cookies(&state.config, None);
 
-    (StatusCode::OK, resp_headers, Json(serde_json::json!({}))).into_response()
+    Ok((StatusCode::OK, resp_headers, Json(serde_json::json!({}))).into_response())
 }
 
 /// GET /api/auth/get-session
@@ -181,10 +265,12 @@ pub async fn get_session(
         _ => return Json(serde_json::json!(null)).into_response(),
     };
 
-    let row = sqlx::query_as::<_, (String, String, String, Option<String>, String, String, bool, String)>(
-        r#"SELECT u.id, u.email, u.username, u.image, s.expiresAt, u.ring_style, u.ring_spin, u.status
+    let row = sqlx::query_as::<_, (String, String, String, Option<String>, String, String, bool, String, bool, Option<String>)>(
+        r#"SELECT u.id, u.email, u.username, u.image, s.expiresAt, u.ring_style, u.ring_spin, u.status,
+                  u.emailVerified, c.new_email
            FROM "session" s
            JOIN "user" u ON u.id = s.userId
+           LEFT JOIN "email_changes" c ON c.user_id = u.id
            WHERE s.token = ?"#,
     )
     .bind(&token)
@@ -194,7 +280,7 @@ pub async fn get_session(
     .flatten();
 
     match row {
-        Some((id, email, username, image, expires_at, ring_style, ring_spin, status)) => {
+        Some((id, email, username, image, expires_at, ring_style, ring_spin, status, email_verified, pending_email)) => {
             let now = chrono::Utc::now().to_rfc3339();
             if expires_at < now {
                 return Json(serde_json::json!(null)).into_response();
@@ -208,6 +294,8 @@ pub async fn get_session(
                     "ringStyle": ring_style,
                     "ringSpin": ring_spin,
                     "status": status,
+                    "emailVerified": email_verified,
+                    "pendingEmail": pending_email,
                 }
             }))
             .into_response()
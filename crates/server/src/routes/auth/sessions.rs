@@ -0,0 +1,132 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use super::extract_token;
+use crate::error::ApiError;
+use crate::models::{AuthUser, SessionInfo};
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+/// Id of the session this request was made with.
+async fn current_session_id(state: &AppState, headers: &HeaderMap) -> Option<String> {
+    let token = extract_token(headers)?;
+    sqlx::query_scalar::<_, String>(r#"SELECT id FROM "session" WHERE token = ?"#)
+        .bind(&token)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Delete a session and drop any gateway connections made with it.
+async fn revoke(state: &AppState, session_id: &str) {
+    let _ = sqlx::query(r#"DELETE FROM "session" WHERE id = ?"#)
+        .bind(session_id)
+        .execute(&state.db)
+        .await;
+    state
+        .gateway
+        .close_session(
+            session_id,
+            &ServerEvent::SessionRevoked {
+                session_id: session_id.to_string(),
+            },
+        )
+        .await;
+}
+
+/// Revoke all of a user's sessions, optionally keeping one. Returns how many
+/// were revoked.
+pub(crate) async fn revoke_user_sessions(state: &AppState, user_id: &str, keep: Option<&str>) -> usize {
+    let sessions = sqlx::query_scalar::<_, String>(
+        r#"SELECT id FROM "session" WHERE userId = ? AND id != ?"#,
+    )
+    .bind(user_id)
+    .bind(keep.unwrap_or(""))
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    for session_id in &sessions {
+        revoke(state, session_id).await;
+    }
+    sessions.len()
+}
+
+/// GET /api/auth/sessions
+/// The user's unexpired sessions, most recently used first.
+pub async fn list_sessions(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let current = current_session_id(&state, &headers).await;
+
+    let rows = sqlx::query_as::<_, (String, Option<String>, Option<String>, String, String, String)>(
+        r#"SELECT id, userAgent, ipAddress, createdAt, updatedAt, expiresAt FROM "session"
+           WHERE userId = ? AND expiresAt > ?
+           ORDER BY updatedAt DESC"#,
+    )
+    .bind(&user.id)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let sessions: Vec<SessionInfo> = rows
+        .into_iter()
+        .map(|(id, user_agent, ip_address, created_at, last_seen_at, expires_at)| SessionInfo {
+            current: current.as_deref() == Some(id.as_str()),
+            id,
+            user_agent,
+            ip_address,
+            created_at,
+            last_seen_at,
+            expires_at,
+        })
+        .collect();
+
+    Json(sessions)
+}
+
+/// DELETE /api/auth/sessions/:id
+/// Sign a session out. Its open connections are closed immediately.
+pub async fn revoke_session(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(session_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let owned = sqlx::query_scalar::<_, i64>(
+        r#"SELECT COUNT(*) FROM "session" WHERE id = ? AND userId = ?"#,
+    )
+    .bind(&session_id)
+    .bind(&user.id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0);
+
+    if owned == 0 {
+        return Err(ApiError::not_found("Session not found"));
+    }
+
+    revoke(&state, &session_id).await;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// POST /api/auth/sessions/revoke-others
+/// Sign out everywhere except the session making the request.
+pub async fn revoke_other_sessions(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let current = current_session_id(&state, &headers).await;
+    let revoked = revoke_user_sessions(&state, &user.id, current.as_deref()).await;
+
+    Json(serde_json::json!({"revoked": revoked}))
+}
@@ -0,0 +1,287 @@
+use crate::config::Config;
+use crate::error::ApiError;
+
+const MIN_LENGTH: usize = 8;
+const MAX_LENGTH: usize = 256;
+
+/// Most common passwords and words first; a match costs about log2(rank) bits.
+const COMMON_PASSWORDS: &[&str] = &[
+    "password", "123456", "12345678", "qwerty", "abc123", "monkey", "letmein", "dragon",
+    "111111", "baseball", "iloveyou", "trustno1", "1234567", "sunshine", "master", "welcome",
+    "shadow", "ashley", "football", "jesus", "michael", "ninja", "mustang", "password1",
+    "admin", "login", "princess", "starwars", "solo", "qwertyuiop", "passw0rd", "charlie",
+    "donald", "freedom", "whatever", "hello", "secret", "flower", "loveme", "superman",
+    "batman", "access", "654321", "computer", "thomas", "hunter", "ranger", "buster",
+    "soccer", "hockey", "killer", "george", "jordan", "harley", "robert", "matthew",
+    "daniel", "andrew", "pepper", "summer", "winter", "spring", "autumn", "cookie",
+    "cheese", "chocolate", "banana", "orange", "purple", "silver", "golden", "diamond",
+    "flux", "discord", "pokemon", "minecraft", "gaming", "google", "internet", "changeme",
+    "default", "test", "guest", "root", "love", "family", "friends", "happy",
+    "lucky", "money", "angel", "baby", "tigger", "maggie", "ginger", "jennifer",
+    "jessica", "amanda", "nicole", "samsung", "apple", "pass", "qwer", "asdf",
+];
+
+const KEYBOARD_ROWS: &[&str] = &[
+    "`1234567890-=", "qwertyuiop[]\\", "asdfghjkl;'", "zxcvbnm,./", "~!@#$%^&*()_+",
+    "1qaz2wsx3edc4rfv5tgb6yhn7ujm8ik,9ol.0p;/",
+];
+
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+enum Kind {
+    Common,
+    UserInput,
+    Keyboard,
+    Sequence,
+    Repeat,
+    Year,
+}
+
+struct Match {
+    start: usize,
+    end: usize,
+    bits: f64,
+    kind: Kind,
+    leet: bool,
+}
+
+/// How guessable a password is, in the spirit of zxcvbn: score 0 (trivial)
+/// to 4 (strong), with feedback for the weakest part.
+pub(crate) struct Strength {
+    pub score: u8,
+    pub warning: Option<&'static str>,
+    pub suggestions: Vec<&'static str>,
+}
+
+fn unleet(c: char) -> char {
+    match c {
+        '0' => 'o',
+        '1' | '!' => 'i',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' => 't',
+        _ => c,
+    }
+}
+
+/// Bits to brute-force one character of its class.
+fn char_bits(c: char) -> f64 {
+    if c.is_ascii_digit() {
+        10f64.log2()
+    } else if c.is_ascii_alphabetic() {
+        26f64.log2()
+    } else {
+        33f64.log2()
+    }
+}
+
+fn dictionary_matches(lower: &[char], words: &[(usize, String, Kind)], out: &mut Vec<Match>) {
+    let plain: Vec<char> = lower.iter().map(|c| unleet(*c)).collect();
+    for (rank, word, kind) in words {
+        let word: Vec<char> = word.chars().collect();
+        let word_plain: Vec<char> = word.iter().map(|c| unleet(*c)).collect();
+        if word.len() > lower.len() {
+            continue;
+        }
+        for start in 0..=lower.len() - word.len() {
+            let end = start + word.len();
+            if plain[start..end] == word_plain[..] {
+                let leet = lower[start..end] != word[..];
+                out.push(Match {
+                    start,
+                    end,
+                    bits: ((rank + 2) as f64).log2() + if leet { 1.0 } else { 0.0 },
+                    kind: *kind,
+                    leet,
+                });
+            }
+        }
+    }
+}
+
+/// Runs of at least three characters stepping by one (abc, 987) or
+/// repeating one character (aaa).
+fn run_matches(lower: &[char], out: &mut Vec<Match>) {
+    let mut start = 0;
+    while start < lower.len() {
+        let step = lower.get(start + 1).map(|n| *n as i32 - lower[start] as i32);
+        let mut end = start + 1;
+        if let Some(step) = step.filter(|s| (-1..=1).contains(s)) {
+            while end < lower.len() && lower[end] as i32 - lower[end - 1] as i32 == step {
+                end += 1;
+            }
+            let len = end - start;
+            if len >= 3 && (step == 0 || lower[start].is_ascii_alphanumeric()) {
+                let base = if step == 0 { char_bits(lower[start]) } else if lower[start].is_ascii_digit() { 10f64.log2() } else { 26f64.log2() };
+                out.push(Match {
+                    start,
+                    end,
+                    bits: base + (len as f64).log2() + if step < 0 { 1.0 } else { 0.0 },
+                    kind: if step == 0 { Kind::Repeat } else { Kind::Sequence },
+                    leet: false,
+                });
+            }
+        }
+        start = end.max(start + 1);
+    }
+}
+
+/// Four or more neighbouring keys, either direction.
+fn keyboard_matches(lower: &[char], out: &mut Vec<Match>) {
+    for row in KEYBOARD_ROWS {
+        let forward: String = row.to_string();
+        let backward: String = row.chars().rev().collect();
+        for start in 0..lower.len() {
+            let mut best = 0;
+            for end in start + 4..=lower.len() {
+                let chunk: String = lower[start..end].iter().collect();
+                if forward.contains(&chunk) || backward.contains(&chunk) {
+                    best = end;
+                } else {
+                    break;
+                }
+            }
+            if best > 0 {
+                out.push(Match {
+                    start,
+                    end: best,
+                    bits: (KEYBOARD_ROWS.len() as f64 * 2.0).log2() + ((best - start) as f64).log2() + 3.0,
+                    kind: Kind::Keyboard,
+                    leet: false,
+                });
+            }
+        }
+    }
+}
+
+fn year_matches(lower: &[char], out: &mut Vec<Match>) {
+    for start in 0..lower.len().saturating_sub(3) {
+        let chunk: String = lower[start..start + 4].iter().collect();
+        if chunk.parse::<u32>().is_ok_and(|y| (1900..=2039).contains(&y)) {
+            out.push(Match { start, end: start + 4, bits: 140f64.log2(), kind: Kind::Year, leet: false });
+        }
+    }
+}
+
+/// Rate a password. `user_inputs` (email, username, name) count as words an
+/// attacker would try first.
+pub(crate) fn estimate(password: &str, user_inputs: &[&str]) -> Strength {
+    let chars: Vec<char> = password.chars().collect();
+    let lower: Vec<char> = chars.iter().map(|c| c.to_ascii_lowercase()).collect();
+
+    let mut words: Vec<(usize, String, Kind)> = COMMON_PASSWORDS
+        .iter()
+        .enumerate()
+        .map(|(rank, w)| (rank, w.to_string(), Kind::Common))
+        .collect();
+    for input in user_inputs {
+        for part in input.to_lowercase().split(|c: char| !c.is_alphanumeric()) {
+            if part.chars().count() >= 3 {
+                words.push((0, part.to_string(), Kind::UserInput));
+            }
+        }
+    }
+
+    let mut matches = Vec::new();
+    dictionary_matches(&lower, &words, &mut matches);
+    run_matches(&lower, &mut matches);
+    keyboard_matches(&lower, &mut matches);
+    year_matches(&lower, &mut matches);
+    for m in &mut matches {
+        // Capitals somewhere in a known word only add a little
+        if chars[m.start..m.end].iter().any(|c| c.is_ascii_uppercase()) {
+            m.bits += 1.0;
+        }
+    }
+
+    // Cheapest way to cover the password: each character is either
+    // brute-forced or part of a pattern, and every pattern costs a bit extra.
+    let n = chars.len();
+    let mut best = vec![0.0f64; n + 1];
+    let mut via: Vec<Option<usize>> = vec![None; n + 1];
+    for i in 1..=n {
+        best[i] = best[i - 1] + char_bits(chars[i - 1]);
+        via[i] = None;
+        for (idx, m) in matches.iter().enumerate().filter(|(_, m)| m.end == i) {
+            let cost = best[m.start] + m.bits + 1.0;
+            if cost < best[i] {
+                best[i] = cost;
+                via[i] = Some(idx);
+            }
+        }
+    }
+
+    let mut used = Vec::new();
+    let mut i = n;
+    while i > 0 {
+        match via[i] {
+            Some(idx) => {
+                used.push(&matches[idx]);
+                i = matches[idx].start;
+            }
+            None => i -= 1,
+        }
+    }
+
+    let bits = best[n];
+    let score = match bits {
+        b if b < 10.0 => 0,
+        b if b < 20.0 => 1,
+        b if b < 27.0 => 2,
+        b if b < 34.0 => 3,
+        _ => 4,
+    };
+
+    let warning = used
+        .iter()
+        .map(|m| m.kind)
+        .fold(None, |acc: Option<Kind>, k| Some(acc.map_or(k, |a| if k < a { k } else { a })))
+        .map(|kind| match kind {
+            Kind::Common => "This is a commonly used password",
+            Kind::UserInput => "Passwords containing your name, username or email are easy to guess",
+            Kind::Keyboard => "Keyboard patterns like qwerty are easy to guess",
+            Kind::Sequence => "Sequences like abc or 123 are easy to guess",
+            Kind::Repeat => "Repeated characters like aaa are easy to guess",
+            Kind::Year => "Dates and years are easy to guess",
+        });
+
+    let mut suggestions = Vec::new();
+    if score < 4 {
+        suggestions.push("Add another word or two. Uncommon words are better.");
+        if n < 12 {
+            suggestions.push("Use a longer password");
+        }
+        if used.iter().any(|m| m.leet) {
+            suggestions.push("Predictable substitutions like '@' instead of 'a' don't help much");
+        }
+    }
+
+    Strength { score, warning, suggestions }
+}
+
+/// Reject a password that's too short, too long or too guessable, with
+/// details the client can show next to the field.
+pub(crate) fn check_password(
+    config: &Config,
+    password: &str,
+    user_inputs: &[&str],
+) -> Result<(), ApiError> {
+    let len = password.chars().count();
+    if len < MIN_LENGTH {
+        return Err(ApiError::bad_request(format!("Password must be at least {} characters", MIN_LENGTH)));
+    }
+    if len > MAX_LENGTH {
+        return Err(ApiError::bad_request(format!("Password must be at most {} characters", MAX_LENGTH)));
+    }
+
+    let strength = estimate(password, user_inputs);
+    if strength.score < config.password_min_score {
+        return Err(ApiError::bad_request("Password is too weak").with_details(serde_json::json!({
+            "score": strength.score,
+            "minScore": config.password_min_score,
+            "warning": strength.warning,
+            "suggestions": strength.suggestions,
+        })));
+    }
+    Ok(())
+}
@@ -0,0 +1,231 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use minijinja::context;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::routes::whitelist::require_admin;
+use crate::AppState;
+
+/// Failed sign-ins before an account is locked.
+const ACCOUNT_MAX_FAILURES: i64 = 5;
+/// Failed sign-ins before an address is locked, across all accounts.
+const IP_MAX_FAILURES: i64 = 20;
+/// The first lockout lasts this long; each one after doubles it.
+const LOCKOUT_BASE_SECS: i64 = 60;
+const LOCKOUT_MAX_SECS: i64 = 3600;
+/// A key quiet for this long starts its backoff over.
+const LOCKOUT_RESET_HOURS: i64 = 24;
+
+fn account_key(email: &str) -> String {
+    format!("account:{}", email)
+}
+
+fn ip_key(ip: &str) -> String {
+    format!("ip:{}", ip)
+}
+
+/// Seconds until `key` may try again, if it's locked.
+async fn locked_for(state: &AppState, key: &str) -> Option<i64> {
+    let until = sqlx::query_scalar::<_, Option<String>>(
+        r#"SELECT locked_until FROM "login_lockouts" WHERE key = ?"#,
+    )
+    .bind(key)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten()
+    .flatten()?;
+    let until = chrono::DateTime::parse_from_rfc3339(&until).ok()?;
+    let secs = until.signed_duration_since(chrono::Utc::now()).num_seconds();
+    (secs > 0).then_some(secs)
+}
+
+/// Count a failure against `key`. Returns the lockout length if this failure
+/// started one.
+async fn add_failure(state: &AppState, key: &str, max_failures: i64) -> Option<i64> {
+    let now = chrono::Utc::now();
+    let row = sqlx::query_as::<_, (i64, i64, String)>(
+        r#"SELECT failures, lockouts, updated_at FROM "login_lockouts" WHERE key = ?"#,
+    )
+    .bind(key)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    let (mut failures, mut lockouts) = match row {
+        Some((failures, lockouts, updated_at))
+            if chrono::DateTime::parse_from_rfc3339(&updated_at)
+                .is_ok_and(|t| now.signed_duration_since(t) < chrono::Duration::hours(LOCKOUT_RESET_HOURS)) =>
+        {
+            (failures, lockouts)
+        }
+        _ => (0, 0),
+    };
+
+    failures += 1;
+    let mut locked = None;
+    if failures >= max_failures {
+        lockouts += 1;
+        failures = 0;
+        let secs = LOCKOUT_BASE_SECS
+            .saturating_mul(1 << (lockouts - 1).min(16))
+            .min(LOCKOUT_MAX_SECS);
+        locked = Some(secs);
+    }
+    let locked_until = locked.map(|secs| (now + chrono::Duration::seconds(secs)).to_rfc3339());
+
+    let _ = sqlx::query(
+        r#"INSERT INTO "login_lockouts" (key, failures, lockouts, locked_until, updated_at) VALUES (?, ?, ?, ?, ?)
+           ON CONFLICT(key) DO UPDATE SET failures = excluded.failures, lockouts = excluded.lockouts,
+             locked_until = COALESCE(excluded.locked_until, locked_until), updated_at = excluded.updated_at"#,
+    )
+    .bind(key)
+    .bind(failures)
+    .bind(lockouts)
+    .bind(&locked_until)
+    .bind(now.to_rfc3339())
+    .execute(&state.db)
+    .await;
+
+    locked
+}
+
+async fn record_attempt(state: &AppState, email: &str, user_id: Option<&str>, ip: Option<&str>, success: bool) {
+    let _ = sqlx::query(
+        r#"INSERT INTO "login_attempts" (id, email, user_id, ip, success, created_at) VALUES (?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(email)
+    .bind(user_id)
+    .bind(ip)
+    .bind(success)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&state.db)
+    .await;
+}
+
+/// Seconds until a sign-in for this email from this address may be tried,
+/// if either is locked out.
+pub(crate) async fn sign_in_blocked(state: &AppState, email: &str, ip: Option<&str>) -> Option<i64> {
+    let account = locked_for(state, &account_key(email)).await;
+    let address = match ip {
+        Some(ip) => locked_for(state, &ip_key(ip)).await,
+        None => None,
+    };
+    account.max(address)
+}
+
+/// Log a failed sign-in and count it against the account and address. The
+/// owner gets an email when their account locks.
+pub(crate) async fn record_failure(state: &AppState, email: &str, user_id: Option<&str>, ip: Option<&str>) {
+    record_attempt(state, email, user_id, ip, false).await;
+    if let Some(ip) = ip {
+        add_failure(state, &ip_key(ip), IP_MAX_FAILURES).await;
+    }
+
+    let Some(secs) = add_failure(state, &account_key(email), ACCOUNT_MAX_FAILURES).await else {
+        return;
+    };
+    tracing::warn!("Sign-in locked for {} for {}s after repeated failures", email, secs);
+    if user_id.is_none() || !crate::mailer::enabled(&state.config) {
+        return;
+    }
+
+    let ctx = context! {
+        attempts => ACCOUNT_MAX_FAILURES,
+        address => ip.unwrap_or("unknown"),
+        minutes => (secs + 59) / 60,
+    };
+    match crate::mailer::render("login_locked", email, "Failed sign-in attempts on your Flux account", ctx) {
+        Ok(email) => crate::mailer::queue(state, email).await,
+        Err(e) => tracing::error!("Failed to render lockout notice: {}", e),
+    }
+}
+
+/// Log a successful sign-in and clear the account's failures. The address
+/// keeps its backoff, so one good password doesn't unlock guessing others.
+pub(crate) async fn record_success(state: &AppState, email: &str, user_id: &str, ip: Option<&str>) {
+    record_attempt(state, email, Some(user_id), ip, true).await;
+    let _ = sqlx::query(r#"DELETE FROM "login_lockouts" WHERE key = ?"#)
+        .bind(account_key(email))
+        .execute(&state.db)
+        .await;
+}
+
+async fn user_email(state: &AppState, user_id: &str) -> Option<String> {
+    sqlx::query_scalar::<_, String>(r#"SELECT email FROM "user" WHERE id = ?"#)
+        .bind(user_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten()
+}
+
+/// GET /api/users/:id/login-attempts
+/// Recent sign-in attempts on an account. Users see their own; admins see
+/// anyone's.
+pub async fn list_login_attempts(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(user_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user_id = if user_id == "me" { user.id.clone() } else { user_id };
+    if user_id != user.id {
+        require_admin(&state, &user.id).await?;
+    }
+    let Some(email) = user_email(&state, &user_id).await else {
+        return Err(ApiError::not_found("User not found"));
+    };
+
+    let rows = sqlx::query_as::<_, (Option<String>, bool, String)>(
+        r#"SELECT ip, success, created_at FROM "login_attempts"
+           WHERE email = ? OR user_id = ?
+           ORDER BY created_at DESC LIMIT 50"#,
+    )
+    .bind(&email)
+    .bind(&user_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let attempts: Vec<serde_json::Value> = rows
+        .into_iter()
+        .map(|(ip, success, created_at)| {
+            serde_json::json!({"ip": ip, "success": success, "createdAt": created_at})
+        })
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "lockedFor": locked_for(&state, &account_key(&email)).await,
+        "attempts": attempts,
+    }))
+    .into_response())
+}
+
+/// DELETE /api/users/:id/lockout
+/// Lift an account's lockout and reset its backoff.
+pub async fn unlock_account(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(user_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_admin(&state, &user.id).await?;
+    let Some(email) = user_email(&state, &user_id).await else {
+        return Err(ApiError::not_found("User not found"));
+    };
+
+    sqlx::query(r#"DELETE FROM "login_lockouts" WHERE key = ?"#)
+        .bind(account_key(&email))
+        .execute(&state.db)
+        .await?;
+    tracing::info!("{} unlocked sign-in for {}", user.username, email);
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
@@ -0,0 +1,328 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use minijinja::context;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use super::{page, random_token, token_hash, verify_password};
+use crate::error::ApiError;
+use crate::models::{AuthUser, ChangeEmailRequest};
+use crate::AppState;
+
+/// How long an emailed verification link stays valid.
+const VERIFY_TTL_HOURS: i64 = 24;
+/// Minimum gap between verification emails to one account.
+const RESEND_COOLDOWN_SECS: i64 = 60;
+
+const PURPOSE_VERIFY: &str = "verify";
+const PURPOSE_CHANGE_OLD: &str = "change_old";
+const PURPOSE_CHANGE_NEW: &str = "change_new";
+
+/// Store a token and email its link to `to`. For email changes `email` is the
+/// new address whichever side the token goes to, so confirming ties back to
+/// that change.
+async fn send_email_token(
+    state: &AppState,
+    user_id: &str,
+    to: &str,
+    email: &str,
+    purpose: &str,
+    subject: &str,
+    intro: &str,
+) {
+    let token = random_token();
+    let now = chrono::Utc::now();
+    let inserted = sqlx::query(
+        r#"INSERT INTO "email_tokens" (token_hash, user_id, email, purpose, expires_at, created_at)
+           VALUES (?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(token_hash(&state.config.auth_secret, &token))
+    .bind(user_id)
+    .bind(email)
+    .bind(purpose)
+    .bind((now + chrono::Duration::hours(VERIFY_TTL_HOURS)).to_rfc3339())
+    .bind(now.to_rfc3339())
+    .execute(&state.db)
+    .await;
+    if inserted.is_err() {
+        return;
+    }
+
+    let link = format!(
+        "{}/api/auth/verify?token={}",
+        state.config.public_url.trim_end_matches('/'),
+        token
+    );
+    let ctx = context! { intro, hours => VERIFY_TTL_HOURS, link };
+    match crate::mailer::render("verify_email", to, subject, ctx) {
+        Ok(email) => crate::mailer::queue(state, email).await,
+        Err(e) => tracing::error!("Failed to render {} email: {}", subject, e),
+    }
+}
+
+/// Email a link that verifies the user's current address.
+pub(crate) async fn send_verification_email(state: &AppState, user_id: &str, email: &str) {
+    send_email_token(
+        state,
+        user_id,
+        email,
+        email,
+        PURPOSE_VERIFY,
+        "Verify your Flux email",
+        "Welcome to Flux! Please confirm this is your email address.",
+    )
+    .await;
+}
+
+#[derive(Deserialize)]
+pub struct VerifyEmailQuery {
+    pub token: Option<String>,
+}
+
+/// GET /api/auth/verify?token=
+/// Opened from an emailed link. Verifies the address, or confirms one side of
+/// an email change; the change applies once both sides have confirmed.
+pub async fn verify_email(
+    Query(query): Query<VerifyEmailQuery>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let invalid = || page("Link invalid or expired", "Request a new email from Flux and try again.");
+    let failed = || page("Something went wrong", "Please open the link again.");
+    let Some(token) = query.token else {
+        return invalid();
+    };
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let row = sqlx::query_as::<_, (String, String, String)>(
+        r#"UPDATE "email_tokens" SET used_at = ?
+           WHERE token_hash = ? AND used_at IS NULL AND expires_at > ?
+           RETURNING user_id, email, purpose"#,
+    )
+    .bind(&now)
+    .bind(token_hash(&state.config.auth_secret, token.trim()))
+    .bind(&now)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+    let Some((user_id, email, purpose)) = row else {
+        return invalid();
+    };
+
+    if purpose == PURPOSE_VERIFY {
+        // Only counts if it's still the account's address
+        let verified = sqlx::query(r#"UPDATE "user" SET emailVerified = 1, updatedAt = ? WHERE id = ? AND email = ?"#)
+            .bind(&now)
+            .bind(&user_id)
+            .bind(&email)
+            .execute(&state.db)
+            .await;
+        if verified.is_err() {
+            return failed();
+        }
+        return page("Email verified", "You can close this tab and return to Flux.");
+    }
+
+    let column = if purpose == PURPOSE_CHANGE_OLD { "old_confirmed_at" } else { "new_confirmed_at" };
+    let change = sqlx::query_as::<_, (Option<String>, Option<String>)>(&format!(
+        r#"UPDATE "email_changes" SET {} = ? WHERE user_id = ? AND new_email = ?
+           RETURNING old_confirmed_at, new_confirmed_at"#,
+        column
+    ))
+    .bind(&now)
+    .bind(&user_id)
+    .bind(&email)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    match change {
+        Some((Some(_), Some(_))) => {
+            let _ = sqlx::query(r#"DELETE FROM "email_changes" WHERE user_id = ?"#)
+                .bind(&user_id)
+                .execute(&state.db)
+                .await;
+            let applied = sqlx::query(
+                r#"UPDATE "user" SET email = ?, emailVerified = 1, updatedAt = ? WHERE id = ?"#,
+            )
+            .bind(&email)
+            .bind(&now)
+            .bind(&user_id)
+            .execute(&state.db)
+            .await;
+            if applied.is_err() {
+                return page("Email not changed", "That address is now used by another account.");
+            }
+            page("Email changed", &format!("Your Flux account now uses {}.", email))
+        }
+        Some(_) => page(
+            "Confirmed",
+            "Now open the link sent to your other address to finish changing your email.",
+        ),
+        None => invalid(),
+    }
+}
+
+/// POST /api/auth/verify/resend
+pub async fn resend_verification(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    if !crate::mailer::enabled(&state.config) {
+        return Err(ApiError::unavailable("Email is not configured on this server"));
+    }
+
+    let (email, verified) = match sqlx::query_as::<_, (String, bool)>(
+        r#"SELECT email, emailVerified FROM "user" WHERE id = ?"#,
+    )
+    .bind(&user.id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten()
+    {
+        Some(u) => u,
+        None => return Err(ApiError::not_found("User not found")),
+    };
+    if verified {
+        return Err(ApiError::bad_request("Email already verified"));
+    }
+
+    let last_sent = sqlx::query_scalar::<_, Option<String>>(
+        r#"SELECT MAX(created_at) FROM "email_tokens" WHERE user_id = ? AND purpose = ?"#,
+    )
+    .bind(&user.id)
+    .bind(PURPOSE_VERIFY)
+    .fetch_one(&state.db)
+    .await
+    .ok()
+    .flatten();
+    if let Some(last) = last_sent.and_then(|t| chrono::DateTime::parse_from_rfc3339(&t).ok()) {
+        let since = chrono::Utc::now().signed_duration_since(last).num_seconds();
+        if since < RESEND_COOLDOWN_SECS {
+            return Err(ApiError::rate_limited(
+                "Please wait before requesting another email",
+                RESEND_COOLDOWN_SECS - since,
+            ));
+        }
+    }
+
+    send_verification_email(&state, &user.id, &email).await;
+    Ok((StatusCode::ACCEPTED, Json(serde_json::json!({}))).into_response())
+}
+
+/// POST /api/auth/change-email
+/// Starts a change: both the current and the new address get a link, and the
+/// email only changes once both are opened.
+pub async fn change_email(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<ChangeEmailRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    if !crate::mailer::enabled(&state.config) {
+        return Err(ApiError::unavailable("Email is not configured on this server"));
+    }
+
+    let new_email = body.new_email.trim().to_lowercase();
+    if !new_email.contains('@') || new_email.len() > 254 {
+        return Err(ApiError::bad_request("Invalid email address"));
+    }
+
+    let current = sqlx::query_scalar::<_, String>(r#"SELECT email FROM "user" WHERE id = ?"#)
+        .bind(&user.id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    if current == new_email {
+        return Err(ApiError::bad_request("That is already your email"));
+    }
+
+    let taken = sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "user" WHERE email = ?"#)
+        .bind(&new_email)
+        .fetch_one(&state.db)
+        .await
+        .unwrap_or(1);
+    if taken > 0 {
+        return Err(ApiError::conflict("Email already registered"));
+    }
+
+    // Accounts with a password have to re-enter it
+    let stored_hash = sqlx::query_scalar::<_, String>(
+        r#"SELECT password FROM "account" WHERE userId = ? AND providerId = 'credential'"#,
+    )
+    .bind(&user.id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+    if let Some(stored_hash) = stored_hash {
+        if !verify_password(&stored_hash, body.password.as_deref().unwrap_or("")) {
+            return Err(ApiError::unauthorized("Invalid password"));
+        }
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"INSERT INTO "email_changes" (user_id, new_email, created_at) VALUES (?, ?, ?)
+           ON CONFLICT(user_id) DO UPDATE SET new_email = excluded.new_email,
+             old_confirmed_at = NULL, new_confirmed_at = NULL, created_at = excluded.created_at"#,
+    )
+    .bind(&user.id)
+    .bind(&new_email)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+    // Links from an earlier change request no longer apply
+    sqlx::query(
+        r#"UPDATE "email_tokens" SET used_at = ? WHERE user_id = ? AND purpose IN (?, ?) AND used_at IS NULL"#,
+    )
+    .bind(&now)
+    .bind(&user.id)
+    .bind(PURPOSE_CHANGE_OLD)
+    .bind(PURPOSE_CHANGE_NEW)
+    .execute(&state.db)
+    .await?;
+
+    send_email_token(
+        &state,
+        &user.id,
+        &current,
+        &new_email,
+        PURPOSE_CHANGE_OLD,
+        "Confirm your Flux email change",
+        &format!("Someone asked to change your Flux account's email to {}.", new_email),
+    )
+    .await;
+    send_email_token(
+        &state,
+        &user.id,
+        &new_email,
+        &new_email,
+        PURPOSE_CHANGE_NEW,
+        "Confirm your new Flux email",
+        "Please confirm this address for your Flux account.",
+    )
+    .await;
+
+    Ok((StatusCode::ACCEPTED, Json(serde_json::json!({"pendingEmail": new_email}))).into_response())
+}
+
+/// DELETE /api/auth/change-email
+pub async fn cancel_email_change(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    sqlx::query(r#"DELETE FROM "email_changes" WHERE user_id = ?"#)
+        .bind(&user.id)
+        .execute(&state.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
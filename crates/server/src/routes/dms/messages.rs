@@ -1,11 +1,11 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::{AuthUser, DmMessage, PaginatedResponse};
 use crate::AppState;
 
@@ -17,26 +17,20 @@ pub async fn list_dm_messages(
     user: AuthUser,
     Path(dm_channel_id): Path<String>,
     Query(query): Query<DmMessageQuery>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     // Verify user is participant
     let channel = sqlx::query_as::<_, (String, String)>(
         "SELECT user1_id, user2_id FROM dm_channels WHERE id = ?",
     )
     .bind(&dm_channel_id)
-    .fetch_optional(&state.db)
+    .fetch_optional(&state.db_read)
     .await
     .ok()
     .flatten();
 
     match channel {
         Some((u1, u2)) if u1 == user.id || u2 == user.id => {}
-        _ => {
-            return (
-                StatusCode::FORBIDDEN,
-                Json(serde_json::json!({"error": "Not a participant"})),
-            )
-                .into_response()
-        }
+        _ => return Err(ApiError::forbidden("Not a participant")),
     }
 
     let limit: i64 = 50;
@@ -48,7 +42,7 @@ pub async fn list_dm_messages(
         .bind(&dm_channel_id)
         .bind(cursor)
         .bind(limit + 1)
-        .fetch_all(&state.db)
+        .fetch_all(&state.db_read)
         .await
         .unwrap_or_default()
     } else {
@@ -57,7 +51,7 @@ pub async fn list_dm_messages(
         )
         .bind(&dm_channel_id)
         .bind(limit + 1)
-        .fetch_all(&state.db)
+        .fetch_all(&state.db_read)
         .await
         .unwrap_or_default()
     };
@@ -71,12 +65,12 @@ pub async fn list_dm_messages(
 
     let cursor = items.first().map(|m| m.created_at.clone());
 
-    Json(PaginatedResponse {
+    Ok(Json(PaginatedResponse {
         items,
         cursor,
         has_more,
     })
-    .into_response()
+    .into_response())
 }
 
 /// GET /api/dms/:dmChannelId/messages/search
@@ -85,16 +79,10 @@ pub async fn search_dm_messages(
     user: AuthUser,
     Path(dm_channel_id): Path<String>,
     Query(query): Query<UserSearchQuery>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     match query.q.as_deref() {
         Some(q) if !q.trim().is_empty() => {},
-        _ => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": "Missing query"})),
-            )
-                .into_response()
-        }
+        _ => return Err(ApiError::bad_request("Missing query")),
     };
 
     // Verify user is participant
@@ -102,20 +90,14 @@ pub async fn search_dm_messages(
         "SELECT user1_id, user2_id FROM dm_channels WHERE id = ?",
     )
     .bind(&dm_channel_id)
-    .fetch_optional(&state.db)
+    .fetch_optional(&state.db_read)
     .await
     .ok()
     .flatten();
 
     match channel {
         Some((u1, u2)) if u1 == user.id || u2 == user.id => {}
-        _ => {
-            return (
-                StatusCode::FORBIDDEN,
-                Json(serde_json::json!({"error": "Not a participant"})),
-            )
-                .into_response()
-        }
+        _ => return Err(ApiError::forbidden("Not a participant")),
     }
 
     // Return raw messages for client-side decryption and filtering (E2EE)
@@ -123,13 +105,13 @@ pub async fn search_dm_messages(
         "SELECT * FROM dm_messages WHERE dm_channel_id = ? ORDER BY created_at DESC LIMIT 500",
     )
     .bind(&dm_channel_id)
-    .fetch_all(&state.db)
+    .fetch_all(&state.db_read)
     .await
     .unwrap_or_default();
 
     items.reverse();
 
-    Json(serde_json::json!({"items": items})).into_response()
+    Ok(Json(serde_json::json!({"items": items})).into_response())
 }
 
 /// GET /api/users/search
@@ -145,10 +127,13 @@ pub async fn search_users(
 
     let pattern = format!("%{}%", q);
     let results = sqlx::query_as::<_, (String, String)>(
-        r#"SELECT id, username FROM "user" WHERE username LIKE ? LIMIT 10"#,
+        r#"SELECT id, username FROM "user" WHERE username LIKE ?
+           OR id IN (SELECT user_id FROM "username_history" WHERE old_username LIKE ?)
+           LIMIT 10"#,
     )
     .bind(&pattern)
-    .fetch_all(&state.db)
+    .bind(&pattern)
+    .fetch_all(&state.db_read)
     .await
     .unwrap_or_default();
 
@@ -1,16 +1,18 @@
 mod messages;
+mod requests;
 
 pub use messages::*;
+pub use requests::*;
 
 use axum::{
     extract::State,
-    http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::{AuthUser, CreateDmRequest, DmChannelResponse, DmOtherUser};
 use crate::AppState;
 
@@ -25,58 +27,53 @@ pub struct UserSearchQuery {
 }
 
 /// GET /api/dms
+/// Conversations the user is part of. Message requests sent to them are
+/// left out; those are listed by `GET /api/dms/requests`.
 pub async fn list_dms(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
 ) -> impl IntoResponse {
-    let channels = sqlx::query_as::<_, (String, String, String, String)>(
-        "SELECT id, user1_id, user2_id, created_at FROM dm_channels WHERE user1_id = ? OR user2_id = ?",
+    // The other participant comes back in the same row; DMs whose partner
+    // no longer exists drop out of the join
+    let rows = sqlx::query_as::<_, (String, String, String, Option<String>, String, String, Option<String>)>(
+        r#"SELECT dc.id, dc.created_at, dc.status, dc.requested_by, u.id, u.username, u.image
+           FROM dm_channels dc
+           JOIN "user" u ON u.id = CASE WHEN dc.user1_id = ?1 THEN dc.user2_id ELSE dc.user1_id END
+           WHERE (dc.user1_id = ?1 OR dc.user2_id = ?1)
+             AND (dc.status = 'accepted' OR dc.requested_by = ?1)"#,
     )
     .bind(&user.id)
-    .bind(&user.id)
-    .fetch_all(&state.db)
+    .fetch_all(&state.db_read)
     .await
     .unwrap_or_default();
 
-    let mut result = Vec::new();
-    for (id, user1_id, user2_id, created_at) in channels {
-        let other_user_id = if user1_id == user.id {
-            &user2_id
-        } else {
-            &user1_id
-        };
-
-        let other = sqlx::query_as::<_, (String, String, Option<String>)>(
-            r#"SELECT id, username, image FROM "user" WHERE id = ?"#,
-        )
-        .bind(other_user_id)
-        .fetch_optional(&state.db)
-        .await
-        .ok()
-        .flatten();
-
-        if let Some((oid, ousername, oimage)) = other {
-            result.push(DmChannelResponse {
-                id,
-                other_user: DmOtherUser {
-                    id: oid,
-                    username: ousername,
-                    image: oimage,
-                },
-                created_at,
-            });
-        }
-    }
+    let result: Vec<DmChannelResponse> = rows
+        .into_iter()
+        .map(|(id, created_at, status, requested_by, oid, ousername, oimage)| DmChannelResponse {
+            id,
+            other_user: DmOtherUser {
+                id: oid,
+                username: ousername,
+                image: oimage,
+            },
+            created_at,
+            status,
+            requested_by,
+        })
+        .collect();
 
     Json(result).into_response()
 }
 
 /// POST /api/dms
+/// Open (or return) the conversation with a user. A new conversation is a
+/// message request until the other user accepts it; opening one you were
+/// sent, or had declined, accepts it.
 pub async fn create_dm(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Json(body): Json<CreateDmRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     // Check target exists
     let target = sqlx::query_as::<_, (String, String, Option<String>)>(
         r#"SELECT id, username, image FROM "user" WHERE id = ?"#,
@@ -89,14 +86,13 @@ pub async fn create_dm(
 
     let (target_id, target_username, target_image) = match target {
         Some(t) => t,
-        None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({"error": "User not found"})),
-            )
-                .into_response()
-        }
+        None => return Err(ApiError::not_found("User not found")),
     };
+    if crate::routes::users::is_blocked(&state, &user.id, &target_id).await {
+        return Err(ApiError::forbidden("You can't message this user"));
+    }
+    // Notes to self need nobody's approval
+    let initial_status = if target_id == user.id { "accepted" } else { "pending" };
 
     // Sort IDs for consistent storage
     let (id1, id2) = if user.id < body.user_id {
@@ -104,10 +100,15 @@ pub async fn create_dm(
     } else {
         (&body.user_id, &user.id)
     };
+    let other_user = DmOtherUser {
+        id: target_id,
+        username: target_username,
+        image: target_image,
+    };
 
     // Check for existing channel
-    let existing = sqlx::query_as::<_, (String, String)>(
-        "SELECT id, created_at FROM dm_channels WHERE user1_id = ? AND user2_id = ?",
+    let existing = sqlx::query_as::<_, (String, String, String, Option<String>)>(
+        "SELECT id, created_at, status, requested_by FROM dm_channels WHERE user1_id = ? AND user2_id = ?",
     )
     .bind(id1)
     .bind(id2)
@@ -116,41 +117,45 @@ pub async fn create_dm(
     .ok()
     .flatten();
 
-    if let Some((channel_id, created_at)) = existing {
-        return Json(DmChannelResponse {
+    if let Some((channel_id, created_at, status, requested_by)) = existing {
+        let status = if status != "accepted" && requested_by.as_deref() != Some(user.id.as_str()) {
+            accept_request(&state, &channel_id, &user.id).await?;
+            "accepted".to_string()
+        } else {
+            status
+        };
+        return Ok(Json(DmChannelResponse {
             id: channel_id,
-            other_user: DmOtherUser {
-                id: target_id,
-                username: target_username,
-                image: target_image,
-            },
+            other_user,
             created_at,
+            status,
+            requested_by,
         })
-        .into_response();
+        .into_response());
     }
 
     // Create new channel
     let channel_id = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
 
-    let _ = sqlx::query(
-        "INSERT INTO dm_channels (id, user1_id, user2_id, created_at) VALUES (?, ?, ?, ?)",
+    sqlx::query(
+        "INSERT INTO dm_channels (id, user1_id, user2_id, created_at, status, requested_by) VALUES (?, ?, ?, ?, ?, ?)",
     )
     .bind(&channel_id)
     .bind(id1)
     .bind(id2)
     .bind(&now)
+    .bind(initial_status)
+    .bind(&user.id)
     .execute(&state.db)
-    .await;
+    .await?;
 
-    Json(DmChannelResponse {
+    Ok(Json(DmChannelResponse {
         id: channel_id,
-        other_user: DmOtherUser {
-            id: target_id,
-            username: target_username,
-            image: target_image,
-        },
+        other_user,
         created_at: now,
+        status: initial_status.to_string(),
+        requested_by: Some(user.id.clone()),
     })
-    .into_response()
+    .into_response())
 }
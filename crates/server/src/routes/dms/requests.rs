@@ -0,0 +1,134 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, DmChannelResponse, DmMessage, DmOtherUser};
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+/// A request addressed to `user_id`: the channel id and who sent it.
+async fn incoming_request(state: &AppState, dm_channel_id: &str, user_id: &str) -> Result<String, ApiError> {
+    let row = sqlx::query_as::<_, (String, String, String, Option<String>)>(
+        "SELECT user1_id, user2_id, status, requested_by FROM dm_channels WHERE id = ?",
+    )
+    .bind(dm_channel_id)
+    .fetch_optional(&state.db)
+    .await?;
+    match row {
+        Some((u1, u2, status, Some(requester)))
+            if (u1 == user_id || u2 == user_id) && requester != user_id && status == "pending" =>
+        {
+            Ok(requester)
+        }
+        _ => Err(ApiError::not_found("Message request not found")),
+    }
+}
+
+/// Accept the conversation on behalf of `user_id` and tell whoever opened it.
+pub(crate) async fn accept_request(state: &AppState, dm_channel_id: &str, user_id: &str) -> Result<(), ApiError> {
+    let requester = sqlx::query_scalar::<_, Option<String>>("SELECT requested_by FROM dm_channels WHERE id = ?")
+        .bind(dm_channel_id)
+        .fetch_optional(&state.db)
+        .await?
+        .flatten();
+    sqlx::query("UPDATE dm_channels SET status = 'accepted' WHERE id = ?")
+        .bind(dm_channel_id)
+        .execute(&state.db)
+        .await?;
+
+    if let Some(requester) = requester.filter(|r| r != user_id) {
+        state
+            .gateway
+            .send_to_user(
+                &requester,
+                &ServerEvent::DmRequestAccepted {
+                    dm_channel_id: dm_channel_id.to_string(),
+                    user_id: user_id.to_string(),
+                },
+            )
+            .await;
+    }
+    Ok(())
+}
+
+/// GET /api/dms/requests
+/// Message requests waiting on the user, newest first, each with the
+/// message that came with it (if one has been sent yet).
+pub async fn list_dm_requests(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    let rows = sqlx::query_as::<_, (String, String, Option<String>, String, String, Option<String>)>(
+        r#"SELECT dc.id, dc.created_at, dc.requested_by, u.id, u.username, u.image
+           FROM dm_channels dc
+           JOIN "user" u ON u.id = dc.requested_by
+           WHERE (dc.user1_id = ?1 OR dc.user2_id = ?1)
+             AND dc.status = 'pending' AND dc.requested_by != ?1
+           ORDER BY dc.created_at DESC"#,
+    )
+    .bind(&user.id)
+    .fetch_all(&state.db_read)
+    .await?;
+
+    let mut requests = Vec::with_capacity(rows.len());
+    for (id, created_at, requested_by, oid, ousername, oimage) in rows {
+        let message = sqlx::query_as::<_, DmMessage>(
+            "SELECT * FROM dm_messages WHERE dm_channel_id = ? ORDER BY created_at ASC LIMIT 1",
+        )
+        .bind(&id)
+        .fetch_optional(&state.db_read)
+        .await?;
+        requests.push(serde_json::json!({
+            "channel": DmChannelResponse {
+                id,
+                other_user: DmOtherUser { id: oid, username: ousername, image: oimage },
+                created_at,
+                status: "pending".to_string(),
+                requested_by,
+            },
+            "message": message,
+        }));
+    }
+
+    Ok(Json(requests))
+}
+
+/// POST /api/dms/:dmChannelId/accept
+pub async fn accept_dm_request(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(dm_channel_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    incoming_request(&state, &dm_channel_id, &user.id).await?;
+    accept_request(&state, &dm_channel_id, &user.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/dms/:dmChannelId/decline
+/// Decline a request and drop what was sent with it. The sender isn't told,
+/// and can't message again unless the user opens the conversation later.
+pub async fn decline_dm_request(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(dm_channel_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    incoming_request(&state, &dm_channel_id, &user.id).await?;
+
+    let mut tx = state.db.begin().await?;
+    sqlx::query("DELETE FROM dm_messages WHERE dm_channel_id = ?")
+        .bind(&dm_channel_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("UPDATE dm_channels SET status = 'declined' WHERE id = ?")
+        .bind(&dm_channel_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,320 @@
+mod rewards;
+
+pub use rewards::*;
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, RewardRule};
+use crate::AppState;
+
+/// GET /api/economy/wallet
+/// Balance, daily streak, and what was earned today broken down by reason.
+pub async fn get_wallet(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let balance = sqlx::query_scalar::<_, i64>(r#"SELECT balance FROM "wallets" WHERE user_id = ?"#)
+        .bind(&user.id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(0);
+
+    let streak = sqlx::query_as::<_, (i64, String)>(
+        r#"SELECT streak, last_claim_date FROM "daily_streaks" WHERE user_id = ?"#,
+    )
+    .bind(&user.id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    let breakdown = sqlx::query_as::<_, (String, i64)>(
+        r#"SELECT reason, SUM(amount) FROM "coin_rewards_log"
+           WHERE user_id = ? AND created_at >= ? GROUP BY reason ORDER BY reason"#,
+    )
+    .bind(&user.id)
+    .bind(today_start())
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let today = chrono::Utc::now().date_naive().to_string();
+    let (streak, daily_claimed) = match streak {
+        Some((streak, last)) => (streak, last == today),
+        None => (0, false),
+    };
+    let total: i64 = breakdown.iter().map(|(_, amount)| amount).sum();
+    let by_reason: serde_json::Map<String, serde_json::Value> = breakdown
+        .into_iter()
+        .map(|(reason, amount)| (reason, amount.into()))
+        .collect();
+
+    Json(serde_json::json!({
+        "balance": balance,
+        "streak": streak,
+        "dailyClaimed": daily_claimed,
+        "today": {"total": total, "byReason": by_reason},
+    }))
+    .into_response()
+}
+
+/// POST /api/economy/daily
+pub async fn claim_daily_reward(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    match claim_daily(&state, &user.id).await {
+        Ok((amount, streak, balance)) => {
+            Ok(Json(serde_json::json!({"amount": amount, "streak": streak, "balance": balance})).into_response())
+        }
+        Err(DailyClaimError::AlreadyClaimed) => Err(ApiError::conflict("Daily reward already claimed")),
+        Err(DailyClaimError::Disabled) => Err(ApiError::forbidden("Daily rewards are disabled")),
+        Err(DailyClaimError::Database) => Err(ApiError::internal("Failed to claim daily reward")),
+    }
+}
+
+/// GET /api/economy/rules
+pub async fn list_rules(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let rules = sqlx::query_as::<_, RewardRule>(r#"SELECT * FROM "reward_rules" ORDER BY rule"#)
+        .fetch_all(&state.db)
+        .await
+        .unwrap_or_default();
+
+    Json(rules).into_response()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRewardRuleRequest {
+    pub amount: Option<i64>,
+    pub daily_cap: Option<i64>,
+    pub cooldown_secs: Option<i64>,
+    pub enabled: Option<bool>,
+}
+
+/// PATCH /api/economy/rules/:rule
+/// Admin only.
+pub async fn update_rule(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(rule): Path<String>,
+    Json(body): Json<UpdateRewardRuleRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    crate::routes::whitelist::require_admin(&state, &user.id).await?;
+
+    if [body.amount, body.daily_cap, body.cooldown_secs].iter().flatten().any(|v| *v < 0) {
+        return Err(ApiError::bad_request("Values cannot be negative"));
+    }
+
+    let updated = sqlx::query_as::<_, RewardRule>(
+        r#"UPDATE "reward_rules" SET
+             amount = COALESCE(?, amount),
+             daily_cap = COALESCE(?, daily_cap),
+             cooldown_secs = COALESCE(?, cooldown_secs),
+             enabled = COALESCE(?, enabled)
+           WHERE rule = ?
+           RETURNING *"#,
+    )
+    .bind(body.amount)
+    .bind(body.daily_cap)
+    .bind(body.cooldown_secs)
+    .bind(body.enabled.map(|e| e as i64))
+    .bind(&rule)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    match updated {
+        Some(rule) => Ok(Json(rule).into_response()),
+        None => Err(ApiError::not_found("Rule not found")),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdjustCoinsRequest {
+    pub user_id: String,
+    /// Positive grants, negative revokes.
+    pub amount: i64,
+    pub reason: String,
+    pub server_id: Option<String>,
+}
+
+/// POST /api/economy/admin/coins
+/// Admin only. Grant or revoke coins; the reason is kept in the rewards log.
+pub async fn adjust_coins(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<AdjustCoinsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    crate::routes::whitelist::require_admin(&state, &user.id).await?;
+
+    let reason = body.reason.trim();
+    if body.amount == 0 || reason.is_empty() || reason.len() > 200 {
+        return Err(ApiError::bad_request("A non-zero amount and a reason (max 200 chars) are required"));
+    }
+
+    let exists = sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "user" WHERE id = ?"#)
+        .bind(&body.user_id)
+        .fetch_one(&state.db)
+        .await
+        .unwrap_or(0);
+    if exists == 0 {
+        return Err(ApiError::not_found("User not found"));
+    }
+
+    match admin_adjust(&state, &user.id, &body.user_id, body.amount, reason, body.server_id.as_deref()).await {
+        Ok(balance) => {
+            tracing::info!("Admin {} adjusted {} by {} coins: {}", user.id, body.user_id, body.amount, reason);
+            Ok(Json(serde_json::json!({"userId": body.user_id, "balance": balance})).into_response())
+        }
+        Err(sqlx::Error::RowNotFound) => Err(ApiError::bad_request("Cannot revoke more than the user's balance")),
+        Err(_) => Err(ApiError::internal("Failed to adjust balance")),
+    }
+}
+
+/// GET /api/economy/admin/log/:userId
+/// Admin only. The user's last 100 coin movements, newest first.
+pub async fn coin_log(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    crate::routes::whitelist::require_admin(&state, &user.id).await?;
+
+    let rows = sqlx::query_as::<_, (String, i64, String, Option<String>, Option<String>, Option<String>, String)>(
+        r#"SELECT id, amount, reason, server_id, note, actor_id, created_at FROM "coin_rewards_log"
+           WHERE user_id = ? ORDER BY created_at DESC LIMIT 100"#,
+    )
+    .bind(&user_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let entries: Vec<serde_json::Value> = rows
+        .into_iter()
+        .map(|(id, amount, reason, server_id, note, actor_id, created_at)| {
+            serde_json::json!({
+                "id": id,
+                "amount": amount,
+                "reason": reason,
+                "serverId": server_id,
+                "note": note,
+                "actorId": actor_id,
+                "createdAt": created_at,
+            })
+        })
+        .collect();
+
+    Ok(Json(entries).into_response())
+}
+
+/// GET /api/economy/servers/:serverId
+/// Whether earning and games are paused on the server.
+pub async fn get_server_economy(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<String>,
+) -> impl IntoResponse {
+    let pause = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+        r#"SELECT paused_until, reason FROM "economy_pauses" WHERE server_id = ?"#,
+    )
+    .bind(&server_id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+    let paused = economy_paused(&state, Some(&server_id)).await;
+    let (paused_until, reason) = match pause {
+        Some(p) if paused => p,
+        _ => (None, None),
+    };
+
+    Json(serde_json::json!({
+        "serverId": server_id,
+        "paused": paused,
+        "pausedUntil": paused_until,
+        "reason": reason,
+    }))
+    .into_response()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PauseEconomyRequest {
+    /// How long to pause for; omitted pauses until resumed.
+    pub minutes: Option<i64>,
+    pub reason: Option<String>,
+}
+
+/// PUT /api/economy/servers/:serverId/pause
+/// Admin only. Stops activity rewards and games on the server.
+pub async fn pause_server_economy(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<String>,
+    Json(body): Json<PauseEconomyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    crate::routes::whitelist::require_admin(&state, &user.id).await?;
+    if body.minutes.is_some_and(|m| m <= 0) {
+        return Err(ApiError::bad_request("Minutes must be positive"));
+    }
+
+    let now = chrono::Utc::now();
+    let paused_until = body.minutes.map(|m| (now + chrono::Duration::minutes(m)).to_rfc3339());
+    let result = sqlx::query(
+        r#"INSERT INTO "economy_pauses" (server_id, paused_until, reason, paused_by, created_at)
+           SELECT id, ?, ?, ?, ? FROM "servers" WHERE id = ?
+           ON CONFLICT(server_id) DO UPDATE SET paused_until = excluded.paused_until, reason = excluded.reason,
+               paused_by = excluded.paused_by, created_at = excluded.created_at"#,
+    )
+    .bind(&paused_until)
+    .bind(&body.reason)
+    .bind(&user.id)
+    .bind(now.to_rfc3339())
+    .bind(&server_id)
+    .execute(&state.db)
+    .await;
+
+    match result {
+        Ok(r) if r.rows_affected() > 0 => Ok(Json(serde_json::json!({
+            "serverId": server_id,
+            "paused": true,
+            "pausedUntil": paused_until,
+            "reason": body.reason,
+        }))
+        .into_response()),
+        Ok(_) => Err(ApiError::not_found("Server not found")),
+        Err(_) => Err(ApiError::internal("Failed to pause economy")),
+    }
+}
+
+/// DELETE /api/economy/servers/:serverId/pause
+/// Admin only.
+pub async fn resume_server_economy(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    crate::routes::whitelist::require_admin(&state, &user.id).await?;
+
+    sqlx::query(r#"DELETE FROM "economy_pauses" WHERE server_id = ?"#)
+        .bind(&server_id)
+        .execute(&state.db)
+        .await?;
+
+    Ok(Json(serde_json::json!({"serverId": server_id, "paused": false})).into_response())
+}
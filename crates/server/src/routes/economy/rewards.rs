@@ -0,0 +1,282 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::models::RewardRule;
+use crate::AppState;
+
+pub const RULE_DAILY_LOGIN: &str = "daily_login";
+pub const RULE_MESSAGE: &str = "message";
+pub const RULE_VOICE_MINUTE: &str = "voice_minute";
+
+pub const REASON_ADMIN_GRANT: &str = "admin_grant";
+pub const REASON_ADMIN_REVOKE: &str = "admin_revoke";
+
+/// Extra daily bonus per consecutive day, in percent of the base amount.
+pub const STREAK_BONUS_PCT: i64 = 10;
+/// The streak bonus stops growing after this many extra days (so 2x at most).
+pub const MAX_STREAK_BONUS_DAYS: i64 = 10;
+
+/// Start of the current UTC day, in the same RFC 3339 form as `created_at`
+/// columns so the two compare as strings.
+pub(crate) fn today_start() -> String {
+    chrono::Utc::now()
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc()
+        .to_rfc3339()
+}
+
+async fn enabled_rule(state: &AppState, rule: &str) -> Option<RewardRule> {
+    sqlx::query_as::<_, RewardRule>(r#"SELECT * FROM "reward_rules" WHERE rule = ? AND enabled = 1"#)
+        .bind(rule)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Change a wallet balance and log why, on an open connection or transaction.
+/// A negative amount only applies if the balance covers it; otherwise this
+/// fails with `RowNotFound` and nothing changes. Returns the new balance.
+pub(crate) async fn adjust_balance(
+    conn: &mut sqlx::SqliteConnection,
+    user_id: &str,
+    amount: i64,
+    reason: &str,
+    server_id: Option<&str>,
+) -> Result<i64, sqlx::Error> {
+    let now = chrono::Utc::now().to_rfc3339();
+
+    if amount < 0 {
+        let debited = sqlx::query(
+            r#"UPDATE "wallets" SET balance = balance + ?, updated_at = ? WHERE user_id = ? AND balance >= ?"#,
+        )
+        .bind(amount)
+        .bind(&now)
+        .bind(user_id)
+        .bind(-amount)
+        .execute(&mut *conn)
+        .await?;
+        if debited.rows_affected() == 0 {
+            return Err(sqlx::Error::RowNotFound);
+        }
+    } else {
+        sqlx::query(
+            r#"INSERT INTO "wallets" (user_id, balance, updated_at) VALUES (?, ?, ?)
+               ON CONFLICT(user_id) DO UPDATE SET balance = balance + excluded.balance, updated_at = excluded.updated_at"#,
+        )
+        .bind(user_id)
+        .bind(amount)
+        .bind(&now)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    sqlx::query(
+        r#"INSERT INTO "coin_rewards_log" (id, user_id, amount, reason, server_id, created_at)
+           VALUES (?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(user_id)
+    .bind(amount)
+    .bind(reason)
+    .bind(server_id)
+    .bind(&now)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query_scalar::<_, i64>(r#"SELECT balance FROM "wallets" WHERE user_id = ?"#)
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await
+}
+
+/// Add coins and log why, in one transaction. Returns the new balance.
+pub(crate) async fn credit(
+    state: &AppState,
+    user_id: &str,
+    amount: i64,
+    reason: &str,
+    server_id: Option<&str>,
+) -> Result<i64, sqlx::Error> {
+    let mut tx = state.db.begin().await?;
+    let balance = adjust_balance(&mut tx, user_id, amount, reason, server_id).await?;
+    tx.commit().await?;
+    Ok(balance)
+}
+
+/// Grant (positive) or revoke (negative) coins by hand. The admin's note and
+/// id are kept on the log entry. Fails with `RowNotFound` if a revoke is more
+/// than the balance.
+pub(crate) async fn admin_adjust(
+    state: &AppState,
+    admin_id: &str,
+    user_id: &str,
+    amount: i64,
+    note: &str,
+    server_id: Option<&str>,
+) -> Result<i64, sqlx::Error> {
+    let reason = if amount < 0 { REASON_ADMIN_REVOKE } else { REASON_ADMIN_GRANT };
+    let mut tx = state.db.begin().await?;
+    let balance = adjust_balance(&mut tx, user_id, amount, reason, server_id).await?;
+    // The log row is the last insert on this connection
+    sqlx::query(r#"UPDATE "coin_rewards_log" SET note = ?, actor_id = ? WHERE rowid = last_insert_rowid()"#)
+        .bind(note)
+        .bind(admin_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(balance)
+}
+
+/// Whether an admin has paused the economy on this server. Pauses with a
+/// `paused_until` in the past have lapsed.
+pub async fn economy_paused(state: &AppState, server_id: Option<&str>) -> bool {
+    let Some(server_id) = server_id else { return false };
+    let pause = sqlx::query_scalar::<_, Option<String>>(
+        r#"SELECT paused_until FROM "economy_pauses" WHERE server_id = ?"#,
+    )
+    .bind(server_id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    match pause {
+        None => false,
+        Some(None) => true,
+        Some(Some(until)) => until > chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+/// Pay out an activity reward (a message, a minute in voice) if the rule is
+/// enabled, its cooldown has passed and today's cap isn't reached. Returns
+/// the coins credited.
+pub async fn accrue(state: &AppState, user_id: &str, rule: &str, server_id: Option<&str>) -> Option<i64> {
+    if economy_paused(state, server_id).await {
+        return None;
+    }
+    let rule = enabled_rule(state, rule).await?;
+
+    let (earned_today, last_at) = sqlx::query_as::<_, (i64, Option<String>)>(
+        r#"SELECT COALESCE(SUM(amount), 0), MAX(created_at) FROM "coin_rewards_log"
+           WHERE user_id = ? AND reason = ? AND created_at >= ?"#,
+    )
+    .bind(user_id)
+    .bind(&rule.rule)
+    .bind(today_start())
+    .fetch_one(&state.db)
+    .await
+    .ok()?;
+
+    let amount = if rule.daily_cap > 0 {
+        rule.amount.min(rule.daily_cap - earned_today)
+    } else {
+        rule.amount
+    };
+    if amount <= 0 {
+        return None;
+    }
+
+    if let Some(last) = last_at.and_then(|t| chrono::DateTime::parse_from_rfc3339(&t).ok()) {
+        let since = chrono::Utc::now().signed_duration_since(last).num_seconds();
+        if since < rule.cooldown_secs {
+            return None;
+        }
+    }
+
+    credit(state, user_id, amount, &rule.rule, server_id).await.ok()?;
+    Some(amount)
+}
+
+/// Why a daily claim didn't pay out.
+pub enum DailyClaimError {
+    AlreadyClaimed,
+    Disabled,
+    Database,
+}
+
+/// Claim today's login bonus. Claiming on consecutive UTC days grows the
+/// streak, and each extra day adds `STREAK_BONUS_PCT` to the payout.
+/// Returns (coins, streak, new balance).
+pub async fn claim_daily(state: &AppState, user_id: &str) -> Result<(i64, i64, i64), DailyClaimError> {
+    let rule = enabled_rule(state, RULE_DAILY_LOGIN).await.ok_or(DailyClaimError::Disabled)?;
+
+    let today = chrono::Utc::now().date_naive();
+    let yesterday = today.pred_opt().unwrap_or(today);
+
+    let previous = sqlx::query_as::<_, (i64, String)>(
+        r#"SELECT streak, last_claim_date FROM "daily_streaks" WHERE user_id = ?"#,
+    )
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|_| DailyClaimError::Database)?;
+
+    let streak = match previous {
+        Some((_, last)) if last == today.to_string() => return Err(DailyClaimError::AlreadyClaimed),
+        Some((streak, last)) if last == yesterday.to_string() => streak + 1,
+        _ => 1,
+    };
+
+    let bonus_days = (streak - 1).min(MAX_STREAK_BONUS_DAYS);
+    let amount = rule.amount * (100 + STREAK_BONUS_PCT * bonus_days) / 100;
+
+    // The upsert only matches a row not already claimed today, so two
+    // concurrent claims can't both pay out.
+    let claimed = sqlx::query(
+        r#"INSERT INTO "daily_streaks" (user_id, streak, last_claim_date) VALUES (?, ?, ?)
+           ON CONFLICT(user_id) DO UPDATE SET streak = excluded.streak, last_claim_date = excluded.last_claim_date
+           WHERE last_claim_date != excluded.last_claim_date"#,
+    )
+    .bind(user_id)
+    .bind(streak)
+    .bind(today.to_string())
+    .execute(&state.db)
+    .await
+    .map_err(|_| DailyClaimError::Database)?;
+    if claimed.rows_affected() == 0 {
+        return Err(DailyClaimError::AlreadyClaimed);
+    }
+
+    let balance = credit(state, user_id, amount, RULE_DAILY_LOGIN, None)
+        .await
+        .map_err(|_| DailyClaimError::Database)?;
+    Ok((amount, streak, balance))
+}
+
+/// Pay one voice minute to everyone in a voice channel with at least one
+/// other person in it (sitting alone doesn't earn), and record the minute
+/// and its coins on each user's voice session.
+pub async fn accrue_voice_minutes(state: &AppState) {
+    for (channel_id, participants) in state.gateway.all_voice_states().await {
+        let server_id = if participants.len() < 2 {
+            None
+        } else {
+            sqlx::query_scalar::<_, String>("SELECT server_id FROM channels WHERE id = ?")
+                .bind(&channel_id)
+                .fetch_optional(&state.db)
+                .await
+                .ok()
+                .flatten()
+        };
+        for p in participants {
+            let coins = match &server_id {
+                Some(server_id) => accrue(state, &p.user_id, RULE_VOICE_MINUTE, Some(server_id)).await,
+                None => None,
+            };
+            crate::routes::voice::touch_voice_session(state, &p.user_id, coins.unwrap_or(0)).await;
+        }
+    }
+}
+
+/// Background task: voice-minute rewards, once a minute.
+pub async fn run_voice_rewards(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(60));
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        accrue_voice_minutes(&state).await;
+    }
+}
@@ -6,6 +6,7 @@ use axum::{
 };
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::AuthUser;
 use crate::AppState;
 
@@ -44,7 +45,7 @@ pub async fn add_standard_favorite(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Json(body): Json<FavoriteStandardRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let now = chrono::Utc::now().to_rfc3339();
     sqlx::query(
         "INSERT OR IGNORE INTO standard_emoji_favorites (user_id, emoji, created_at) VALUES (?, ?, ?)",
@@ -53,10 +54,9 @@ pub async fn add_standard_favorite(
     .bind(&body.emoji)
     .bind(&now)
     .execute(&state.db)
-    .await
-    .ok();
+    .await?;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
 
 /// DELETE /api/me/emoji-favorites/standard
@@ -64,17 +64,16 @@ pub async fn remove_standard_favorite(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Json(body): Json<FavoriteStandardRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     sqlx::query(
         "DELETE FROM standard_emoji_favorites WHERE user_id = ? AND emoji = ?",
     )
     .bind(&user.id)
     .bind(&body.emoji)
     .execute(&state.db)
-    .await
-    .ok();
+    .await?;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
 
 /// POST /api/me/emoji-favorites/custom/:emojiId
@@ -82,7 +81,7 @@ pub async fn add_custom_favorite(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path(emoji_id): Path<String>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     // Verify emoji exists (any server)
     let exists = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM custom_emojis WHERE id = ?",
@@ -94,11 +93,7 @@ pub async fn add_custom_favorite(
         > 0;
 
     if !exists {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({"error": "Emoji not found"})),
-        )
-            .into_response();
+        return Err(ApiError::not_found("Emoji not found"));
     }
 
     let now = chrono::Utc::now().to_rfc3339();
@@ -109,10 +104,9 @@ pub async fn add_custom_favorite(
     .bind(&emoji_id)
     .bind(&now)
     .execute(&state.db)
-    .await
-    .ok();
+    .await?;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
 
 /// DELETE /api/me/emoji-favorites/custom/:emojiId
@@ -120,15 +114,14 @@ pub async fn remove_custom_favorite(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path(emoji_id): Path<String>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     sqlx::query(
         "DELETE FROM custom_emoji_favorites WHERE user_id = ? AND emoji_id = ?",
     )
     .bind(&user.id)
     .bind(&emoji_id)
     .execute(&state.db)
-    .await
-    .ok();
+    .await?;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
@@ -1,9 +1,13 @@
 mod favorites;
+mod pack;
+mod stats;
 
 pub use favorites::*;
+pub use pack::*;
+pub use stats::*;
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
@@ -11,7 +15,10 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+use crate::error::ApiError;
+use crate::middleware::etag::{etag_headers, weak_etag};
 use crate::models::AuthUser;
+use crate::routes::pagination::{self, Cursor, PageQuery};
 use crate::AppState;
 
 // ── Request / response types ──────────────────────────────────────────────
@@ -51,13 +58,21 @@ pub struct FavoriteStandardRequest {
     pub emoji: String,
 }
 
+/// Largest custom emoji image accepted (256KB).
+const MAX_EMOJI_BYTES: i64 = 262144;
+
+/// Emoji names: alphanumeric + underscore, 1-32 chars.
+fn is_valid_emoji_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') && name.len() <= 32
+}
+
 // ── Per-server admin check ────────────────────────────────────────────────
 
 async fn require_server_admin(
     state: &AppState,
     user_id: &str,
     server_id: &str,
-) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+) -> Result<(), ApiError> {
     let role = sqlx::query_scalar::<_, String>(
         "SELECT role FROM memberships WHERE user_id = ? AND server_id = ?",
     )
@@ -70,22 +85,21 @@ async fn require_server_admin(
 
     match role.as_deref() {
         Some("owner") | Some("admin") => Ok(()),
-        _ => Err((
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({"error": "Insufficient permissions"})),
-        )),
+        _ => Err(ApiError::forbidden("Insufficient permissions")),
     }
 }
 
 // ── Handlers ──────────────────────────────────────────────────────────────
 
-/// GET /api/servers/:serverId/emojis
-/// Any server member can list custom emojis.
+/// GET /api/servers/:serverId/emojis?cursor=&limit=
+/// Any server member can list custom emojis, oldest first. Emojis are only
+/// ever added or deleted, so the count and newest one make the ETag.
 pub async fn list_emojis(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path(server_id): Path<String>,
-) -> impl IntoResponse {
+    Query(query): Query<PageQuery>,
+) -> Result<impl IntoResponse, ApiError> {
     let is_member = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
     )
@@ -97,14 +111,28 @@ pub async fn list_emojis(
         > 0;
 
     if !is_member {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({"error": "Not a member of this server"})),
-        )
-            .into_response();
+        return Err(ApiError::forbidden("Not a member of this server"));
     }
 
-    let emojis = sqlx::query_as::<_, CustomEmojiRow>(
+    let limit = query.limit();
+    let after = query.after()?;
+
+    let (count, newest) = sqlx::query_as::<_, (i64, Option<String>)>(
+        "SELECT COUNT(*), MAX(created_at) FROM custom_emojis WHERE server_id = ?",
+    )
+    .bind(&server_id)
+    .fetch_one(&state.db)
+    .await?;
+    let etag = weak_etag(&[
+        "emojis",
+        &server_id,
+        &count.to_string(),
+        newest.as_deref().unwrap_or(""),
+        query.cursor.as_deref().unwrap_or(""),
+        &limit.to_string(),
+    ]);
+
+    let mut sql = String::from(
         r#"SELECT
             ce.id,
             ce.server_id,
@@ -117,15 +145,25 @@ pub async fn list_emojis(
             ce.created_at
            FROM custom_emojis ce
            JOIN "user" u ON u.id = ce.uploader_id
-           WHERE ce.server_id = ?
-           ORDER BY ce.created_at ASC"#,
-    )
-    .bind(&server_id)
-    .fetch_all(&state.db)
-    .await
-    .unwrap_or_default();
+           WHERE ce.server_id = ?"#,
+    );
+    if after.is_some() {
+        sql.push_str(" AND ");
+        sql.push_str(&pagination::after_condition("ce.created_at", "ce.id", false));
+    }
+    sql.push_str(" ORDER BY ce.created_at ASC, ce.id ASC LIMIT ?");
+
+    let mut emojis = sqlx::query_as::<_, CustomEmojiRow>(&sql).bind(&server_id);
+    if let Some(after) = &after {
+        emojis = emojis.bind(&after.key).bind(&after.id);
+    }
+    let emojis = emojis.bind(limit + 1).fetch_all(&state.db).await?;
 
-    Json(emojis).into_response()
+    Ok((
+        etag_headers(etag),
+        Json(pagination::page(emojis, limit, |e| Cursor::new(&e.created_at, &e.id))),
+    )
+        .into_response())
 }
 
 /// POST /api/servers/:serverId/emojis
@@ -135,19 +173,12 @@ pub async fn create_emoji(
     user: AuthUser,
     Path(server_id): Path<String>,
     Json(body): Json<CreateEmojiRequest>,
-) -> impl IntoResponse {
-    if let Err(resp) = require_server_admin(&state, &user.id, &server_id).await {
-        return resp.into_response();
-    }
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
 
-    // Validate name: alphanumeric + underscore, 1-32 chars
     let name = body.name.trim().to_string();
-    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') || name.len() > 32 {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Name must be 1-32 alphanumeric/underscore characters"})),
-        )
-            .into_response();
+    if !is_valid_emoji_name(&name) {
+        return Err(ApiError::bad_request("Name must be 1-32 alphanumeric/underscore characters"));
     }
 
     // Verify attachment belongs to uploader, is an image, and is within size limit (256KB)
@@ -164,28 +195,16 @@ pub async fn create_emoji(
     let attachment = match attachment {
         Some(a) => a,
         None => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": "Invalid attachment"})),
-            )
-                .into_response();
+            return Err(ApiError::bad_request("Invalid attachment"));
         }
     };
 
     if !attachment.content_type.starts_with("image/") {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Attachment must be an image"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("Attachment must be an image"));
     }
 
-    if attachment.size > 262144 {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Image must be 256KB or smaller"})),
-        )
-            .into_response();
+    if attachment.size > MAX_EMOJI_BYTES {
+        return Err(ApiError::bad_request("Image must be 256KB or smaller"));
     }
 
     let id = uuid::Uuid::new_v4().to_string();
@@ -211,11 +230,7 @@ pub async fn create_emoji(
         } else {
             "Failed to create emoji"
         };
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": msg})),
-        )
-            .into_response();
+        return Err(ApiError::internal(msg));
     }
 
     // Re-fetch with JOINs
@@ -241,8 +256,8 @@ pub async fn create_emoji(
     .flatten();
 
     match emoji {
-        Some(e) => (StatusCode::CREATED, Json(e)).into_response(),
-        None => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        Some(e) => Ok((StatusCode::CREATED, Json(e)).into_response()),
+        None => Err(ApiError::internal("Failed to load emoji")),
     }
 }
 
@@ -252,10 +267,8 @@ pub async fn delete_emoji(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path((server_id, emoji_id)): Path<(String, String)>,
-) -> impl IntoResponse {
-    if let Err(resp) = require_server_admin(&state, &user.id, &server_id).await {
-        return resp.into_response();
-    }
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
 
     sqlx::query(
         "DELETE FROM custom_emojis WHERE id = ? AND server_id = ?",
@@ -263,8 +276,7 @@ pub async fn delete_emoji(
     .bind(&emoji_id)
     .bind(&server_id)
     .execute(&state.db)
-    .await
-    .ok();
+    .await?;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
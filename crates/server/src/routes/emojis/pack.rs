@@ -0,0 +1,330 @@
+use axum::{
+    extract::{Multipart, Path, Query, State},
+    http::header,
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{Cursor, Read, Write};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::routes::files::stored_file_path;
+use crate::AppState;
+
+use super::{is_valid_emoji_name, require_server_admin, MAX_EMOJI_BYTES};
+
+const MANIFEST_NAME: &str = "manifest.json";
+const PACK_VERSION: u32 = 1;
+/// Emojis accepted from one archive.
+const MAX_PACK_EMOJIS: usize = 500;
+
+/// `manifest.json` at the root of a pack archive.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackManifest {
+    pub version: u32,
+    pub emojis: Vec<PackEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackEntry {
+    pub name: String,
+    /// Path of the image inside the archive.
+    pub file: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportQuery {
+    /// What to do when a name is already taken: "rename" (default), "skip" or "replace".
+    pub on_conflict: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct ExportRow {
+    name: String,
+    attachment_id: String,
+    filename: String,
+}
+
+fn image_content_type(filename: &str) -> Option<&'static str> {
+    let ext = filename.rsplit('.').next()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => Some("image/png"),
+        "gif" => Some("image/gif"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// First free `name`, `name_2`, `name_3`, … kept within the 32 char limit.
+fn unique_name(name: &str, taken: &HashSet<String>) -> String {
+    if !taken.contains(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| {
+            let suffix = format!("_{}", n);
+            let mut base = name.to_string();
+            while base.len() + suffix.len() > 32 {
+                base.pop();
+            }
+            base + &suffix
+        })
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_default()
+}
+
+fn build_archive(entries: Vec<(String, String, Vec<u8>)>) -> zip::result::ZipResult<Vec<u8>> {
+    use zip::write::SimpleFileOptions;
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let mut manifest = PackManifest { version: PACK_VERSION, emojis: Vec::new() };
+    // Images are already compressed
+    let stored = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+    for (name, file, data) in entries {
+        zip.start_file(file.as_str(), stored)?;
+        zip.write_all(&data)?;
+        manifest.emojis.push(PackEntry { name, file });
+    }
+
+    zip.start_file(MANIFEST_NAME, SimpleFileOptions::default())?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest).unwrap_or_default())?;
+    Ok(zip.finish()?.into_inner())
+}
+
+/// GET /api/servers/:serverId/emojis/export
+/// Any server member can export. Returns a zip of the images plus manifest.json.
+pub async fn export_emojis(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let is_member = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
+    )
+    .bind(&user.id)
+    .bind(&server_id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0)
+        > 0;
+
+    if !is_member {
+        return Err(ApiError::forbidden("Not a member of this server"));
+    }
+
+    let rows = sqlx::query_as::<_, ExportRow>(
+        "SELECT name, attachment_id, filename FROM custom_emojis WHERE server_id = ? ORDER BY created_at ASC",
+    )
+    .bind(&server_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let mut entries = Vec::with_capacity(rows.len());
+    for row in rows {
+        let path = stored_file_path(&state.config.upload_dir, &row.attachment_id, &row.filename);
+        // Emojis whose file went missing are left out rather than failing the export
+        let Ok(data) = tokio::fs::read(&path).await else {
+            tracing::warn!("Emoji export: missing file for {}", row.attachment_id);
+            continue;
+        };
+        let ext = row.filename.rsplit('.').next().unwrap_or("png").to_ascii_lowercase();
+        entries.push((row.name.clone(), format!("emojis/{}.{}", row.name, ext), data));
+    }
+
+    let archive = match tokio::task::spawn_blocking(move || build_archive(entries)).await {
+        Ok(Ok(a)) => a,
+        _ => return Err(ApiError::internal("Failed to build archive")),
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"emojis-{}.zip\"", server_id),
+            ),
+        ],
+        archive,
+    )
+        .into_response())
+}
+
+/// Read the manifest and every listed image, rejecting anything that would
+/// fail `create_emoji`'s checks.
+fn read_archive(data: Vec<u8>) -> Result<Vec<(String, String, Vec<u8>)>, String> {
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(data)).map_err(|_| "Not a valid zip archive".to_string())?;
+
+    let manifest: PackManifest = {
+        let file = archive
+            .by_name(MANIFEST_NAME)
+            .map_err(|_| "Archive has no manifest.json".to_string())?;
+        let mut buf = Vec::new();
+        file.take(1024 * 1024)
+            .read_to_end(&mut buf)
+            .map_err(|_| "Failed to read manifest.json".to_string())?;
+        serde_json::from_slice(&buf).map_err(|_| "Invalid manifest.json".to_string())?
+    };
+
+    if manifest.version != PACK_VERSION {
+        return Err(format!("Unsupported pack version {}", manifest.version));
+    }
+    if manifest.emojis.len() > MAX_PACK_EMOJIS {
+        return Err(format!("Packs are limited to {} emojis", MAX_PACK_EMOJIS));
+    }
+
+    let mut images = Vec::with_capacity(manifest.emojis.len());
+    for entry in manifest.emojis {
+        let name = entry.name.trim().to_string();
+        if !is_valid_emoji_name(&name) {
+            return Err(format!("Invalid emoji name '{}'", name));
+        }
+        if image_content_type(&entry.file).is_none() {
+            return Err(format!("'{}' is not a supported image", entry.file));
+        }
+        let file = archive
+            .by_name(&entry.file)
+            .map_err(|_| format!("'{}' is missing from the archive", entry.file))?;
+        // Don't trust the header size alone; cap what we actually inflate
+        let mut buf = Vec::new();
+        file.take(MAX_EMOJI_BYTES as u64 + 1)
+            .read_to_end(&mut buf)
+            .map_err(|_| format!("Failed to read '{}'", entry.file))?;
+        if buf.len() as i64 > MAX_EMOJI_BYTES {
+            return Err(format!("'{}' is larger than 256KB", entry.file));
+        }
+        images.push((name, entry.file, buf));
+    }
+    Ok(images)
+}
+
+/// POST /api/servers/:serverId/emojis/import?onConflict=rename|skip|replace
+/// Owner or admin only. Body is multipart with the pack archive as its first field.
+pub async fn import_emojis(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+    Query(query): Query<ImportQuery>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let on_conflict = query.on_conflict.as_deref().unwrap_or("rename");
+    if !matches!(on_conflict, "rename" | "skip" | "replace") {
+        return Err(ApiError::bad_request("onConflict must be rename, skip or replace"));
+    }
+
+    let data = match multipart.next_field().await {
+        Ok(Some(field)) => field.bytes().await.ok(),
+        _ => None,
+    };
+    let Some(data) = data else {
+        return Err(ApiError::bad_request("No file provided"));
+    };
+
+    let images = match tokio::task::spawn_blocking(move || read_archive(data.to_vec())).await {
+        Ok(Ok(images)) => images,
+        Ok(Err(e)) => return Err(ApiError::bad_request(e)),
+        Err(_) => return Err(ApiError::internal("Failed to read archive")),
+    };
+
+    let mut taken: HashSet<String> =
+        sqlx::query_scalar::<_, String>("SELECT name FROM custom_emojis WHERE server_id = ?")
+            .bind(&server_id)
+            .fetch_all(&state.db)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+    // Names replaced during this import can't be replaced again by a later duplicate
+    let mut imported_names: HashSet<String> = HashSet::new();
+
+    let mut imported = Vec::new();
+    let mut renamed = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (name, file, bytes) in images {
+        let final_name = if !taken.contains(&name) {
+            name.clone()
+        } else if on_conflict == "replace" && !imported_names.contains(&name) {
+            sqlx::query("DELETE FROM custom_emojis WHERE server_id = ? AND name = ?")
+                .bind(&server_id)
+                .bind(&name)
+                .execute(&state.db)
+                .await?;
+            name.clone()
+        } else if on_conflict == "skip" {
+            skipped.push(serde_json::json!({"name": name, "reason": "Name already in use"}));
+            continue;
+        } else {
+            let new_name = unique_name(&name, &taken);
+            renamed.push(serde_json::json!({"from": name, "to": new_name}));
+            new_name
+        };
+
+        let attachment_id = uuid::Uuid::new_v4().to_string();
+        let emoji_id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+        let filename = file.rsplit('/').next().unwrap_or(&file).to_string();
+        let content_type = image_content_type(&filename).unwrap_or("image/png");
+
+        let path = stored_file_path(&state.config.upload_dir, &attachment_id, &filename);
+        if tokio::fs::write(&path, &bytes).await.is_err() {
+            skipped.push(serde_json::json!({"name": final_name, "reason": "Failed to save file"}));
+            continue;
+        }
+
+        let result = async {
+            sqlx::query(
+                r#"INSERT INTO attachments (id, message_id, uploader_id, filename, content_type, size, created_at)
+                   VALUES (?, NULL, ?, ?, ?, ?, ?)"#,
+            )
+            .bind(&attachment_id)
+            .bind(&user.id)
+            .bind(&filename)
+            .bind(content_type)
+            .bind(bytes.len() as i64)
+            .bind(&now)
+            .execute(&state.db)
+            .await?;
+            sqlx::query(
+                "INSERT INTO custom_emojis (id, server_id, name, attachment_id, filename, uploader_id, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&emoji_id)
+            .bind(&server_id)
+            .bind(&final_name)
+            .bind(&attachment_id)
+            .bind(&filename)
+            .bind(&user.id)
+            .bind(&now)
+            .execute(&state.db)
+            .await
+        }
+        .await;
+
+        if let Err(e) = result {
+            tracing::error!("Emoji import failed for {}: {:?}", final_name, e);
+            let _ = tokio::fs::remove_file(&path).await;
+            skipped.push(serde_json::json!({"name": final_name, "reason": "Failed to create emoji"}));
+            continue;
+        }
+
+        taken.insert(final_name.clone());
+        imported_names.insert(final_name.clone());
+        imported.push(serde_json::json!({"id": emoji_id, "name": final_name}));
+    }
+
+    Ok(Json(serde_json::json!({
+        "imported": imported,
+        "renamed": renamed,
+        "skipped": skipped,
+    }))
+    .into_response())
+}
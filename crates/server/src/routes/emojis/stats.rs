@@ -0,0 +1,147 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::AppState;
+
+use super::require_server_admin;
+
+#[derive(Deserialize)]
+pub struct EmojiStatsQuery {
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmojiDayUsage {
+    pub day: String,
+    pub reactions: i64,
+    pub messages: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmojiUsageStats {
+    pub id: String,
+    pub name: String,
+    pub filename: String,
+    pub created_at: String,
+    /// Last use ever, or None if the emoji has never been used
+    pub last_used_at: Option<String>,
+    /// Totals over the requested window
+    pub reactions: i64,
+    pub messages: i64,
+    /// Days in the window the emoji was used on, oldest first
+    pub usage: Vec<EmojiDayUsage>,
+}
+
+/// Bump today's counter for a custom emoji, in whichever server owns it.
+async fn record_usage(state: &AppState, emoji_id: &str, reactions: i64, messages: i64) {
+    let now = chrono::Utc::now();
+    let result = sqlx::query(
+        r#"INSERT INTO emoji_usage_daily (server_id, emoji_id, day, reactions, messages, last_used_at)
+           SELECT server_id, id, ?, ?, ?, ? FROM custom_emojis WHERE id = ?
+           ON CONFLICT(emoji_id, day) DO UPDATE SET
+               reactions = reactions + excluded.reactions,
+               messages = messages + excluded.messages,
+               last_used_at = excluded.last_used_at"#,
+    )
+    .bind(now.date_naive().to_string())
+    .bind(reactions)
+    .bind(messages)
+    .bind(now.to_rfc3339())
+    .bind(emoji_id)
+    .execute(&state.db_write)
+    .await;
+    if let Err(e) = result {
+        tracing::warn!("Failed to record emoji usage: {}", e);
+    }
+}
+
+/// Count a reaction with a custom emoji.
+pub(crate) async fn record_reaction_usage(state: &AppState, emoji_id: &str) {
+    record_usage(state, emoji_id, 1, 0).await;
+}
+
+/// Count the `:name:` shortcodes in a message that name custom emojis of the
+/// channel's server. Each emoji counts once per message.
+pub(crate) async fn record_message_usage(state: &AppState, server_id: &str, names: &[String]) {
+    if names.is_empty() {
+        return;
+    }
+    let placeholders = vec!["?"; names.len()].join(",");
+    let sql = format!("SELECT id FROM custom_emojis WHERE server_id = ? AND name IN ({})", placeholders);
+    let mut query = sqlx::query_scalar::<_, String>(&sql).bind(server_id);
+    for name in names {
+        query = query.bind(name);
+    }
+    let emoji_ids = query.fetch_all(&state.db).await.unwrap_or_default();
+    for emoji_id in emoji_ids {
+        record_usage(state, &emoji_id, 0, 1).await;
+    }
+}
+
+/// GET /api/servers/:serverId/emojis/stats?days=
+/// Per-emoji usage over the last `days` UTC days, least recently used
+/// first, so admins can find emojis nobody uses. Server admins only.
+pub async fn get_emoji_stats(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+    Query(query): Query<EmojiStatsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let days = query.days.unwrap_or(30).clamp(1, 365);
+    let since = (chrono::Utc::now().date_naive() - chrono::Duration::days(days - 1)).to_string();
+
+    let emojis = sqlx::query_as::<_, (String, String, String, String, Option<String>)>(
+        r#"SELECT ce.id, ce.name, ce.filename, ce.created_at,
+               (SELECT MAX(u.last_used_at) FROM emoji_usage_daily u WHERE u.emoji_id = ce.id) AS last_used_at
+           FROM custom_emojis ce
+           WHERE ce.server_id = ?
+           ORDER BY last_used_at IS NOT NULL, last_used_at ASC, ce.name ASC"#,
+    )
+    .bind(&server_id)
+    .fetch_all(&state.db_read)
+    .await?;
+
+    let rows = sqlx::query_as::<_, (String, String, i64, i64)>(
+        r#"SELECT emoji_id, day, reactions, messages FROM emoji_usage_daily
+           WHERE server_id = ? AND day >= ? ORDER BY day ASC"#,
+    )
+    .bind(&server_id)
+    .bind(&since)
+    .fetch_all(&state.db_read)
+    .await?;
+    let mut usage: HashMap<String, Vec<EmojiDayUsage>> = HashMap::new();
+    for (emoji_id, day, reactions, messages) in rows {
+        usage.entry(emoji_id).or_default().push(EmojiDayUsage { day, reactions, messages });
+    }
+
+    let stats: Vec<EmojiUsageStats> = emojis
+        .into_iter()
+        .map(|(id, name, filename, created_at, last_used_at)| {
+            let usage = usage.remove(&id).unwrap_or_default();
+            EmojiUsageStats {
+                reactions: usage.iter().map(|u| u.reactions).sum(),
+                messages: usage.iter().map(|u| u.messages).sum(),
+                id,
+                name,
+                filename,
+                created_at,
+                last_used_at,
+                usage,
+            }
+        })
+        .collect();
+
+    Ok(Json(serde_json::json!({ "days": days, "emojis": stats })))
+}
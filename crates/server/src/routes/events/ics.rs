@@ -0,0 +1,130 @@
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::AppState;
+
+use super::require_server_member;
+
+#[derive(sqlx::FromRow)]
+struct IcsEvent {
+    id: String,
+    title: String,
+    description: String,
+    start_at: String,
+    end_at: Option<String>,
+    recurrence: Option<String>,
+    updated_at: String,
+    channel_name: Option<String>,
+}
+
+/// RFC 5545 TEXT escaping.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// `20261020T180000Z`
+fn ics_time(value: &str) -> Option<String> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string())
+}
+
+/// Append a content line, folded so no line is longer than 75 octets.
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn render_calendar(server_name: &str, events: &[IcsEvent]) -> String {
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//Flux//Server Events//EN");
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(&mut out, &format!("X-WR-CALNAME:{}", escape(server_name)));
+
+    for event in events {
+        let Some(start) = ics_time(&event.start_at) else { continue };
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}@flux", event.id));
+        if let Some(stamp) = ics_time(&event.updated_at) {
+            push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        }
+        push_line(&mut out, &format!("DTSTART:{}", start));
+        if let Some(end) = event.end_at.as_deref().and_then(ics_time) {
+            push_line(&mut out, &format!("DTEND:{}", end));
+        }
+        if let Some(ref recurrence) = event.recurrence {
+            push_line(&mut out, &format!("RRULE:FREQ={}", recurrence.to_uppercase()));
+        }
+        push_line(&mut out, &format!("SUMMARY:{}", escape(&event.title)));
+        if !event.description.is_empty() {
+            push_line(&mut out, &format!("DESCRIPTION:{}", escape(&event.description)));
+        }
+        if let Some(ref channel) = event.channel_name {
+            push_line(&mut out, &format!("LOCATION:{}", escape(&format!("#{}", channel))));
+        }
+        push_line(&mut out, "END:VEVENT");
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+/// GET /api/servers/:serverId/events.ics
+/// The server's events as an iCalendar file, for importing into a calendar app.
+pub async fn export_events_ics(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_member(&state, &user.id, &server_id).await?;
+
+    let server_name = sqlx::query_scalar::<_, String>("SELECT name FROM servers WHERE id = ?")
+        .bind(&server_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Server not found"))?;
+
+    let events = sqlx::query_as::<_, IcsEvent>(
+        r#"SELECT e.id, e.title, e.description, e.start_at, e.end_at, e.recurrence, e.updated_at,
+                  c.name AS channel_name
+           FROM events e
+           LEFT JOIN channels c ON c.id = e.channel_id
+           WHERE e.server_id = ?
+           ORDER BY e.start_at ASC"#,
+    )
+    .bind(&server_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"events-{}.ics\"", server_id),
+            ),
+        ],
+        render_calendar(&server_name, &events),
+    ))
+}
@@ -0,0 +1,209 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::AppState;
+
+use super::{
+    broadcast_event_updated, fetch_event, normalize_time, require_server_admin, CreateEventRequest,
+    UpdateEventRequest, MAX_DESCRIPTION_CHARS, MAX_TITLE_CHARS, RECURRENCES,
+};
+
+/// The fields of an event after validation.
+struct EventFields {
+    title: String,
+    description: String,
+    start_at: String,
+    end_at: Option<String>,
+    channel_id: Option<String>,
+    recurrence: Option<String>,
+}
+
+async fn validate(state: &AppState, server_id: &str, fields: &EventFields) -> Result<(), ApiError> {
+    if fields.title.is_empty() {
+        return Err(ApiError::bad_request("Title is required"));
+    }
+    if fields.title.chars().count() > MAX_TITLE_CHARS {
+        return Err(ApiError::bad_request(format!("Title must be at most {} characters", MAX_TITLE_CHARS)));
+    }
+    if fields.description.chars().count() > MAX_DESCRIPTION_CHARS {
+        return Err(ApiError::bad_request(format!(
+            "Description must be at most {} characters",
+            MAX_DESCRIPTION_CHARS
+        )));
+    }
+    if fields.end_at.as_ref().is_some_and(|end| *end <= fields.start_at) {
+        return Err(ApiError::bad_request("An event must end after it starts"));
+    }
+    if fields.recurrence.as_deref().is_some_and(|r| !RECURRENCES.contains(&r)) {
+        return Err(ApiError::bad_request("Recurrence must be daily, weekly or monthly"));
+    }
+    if let Some(ref channel_id) = fields.channel_id {
+        let in_server = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM channels WHERE id = ? AND server_id = ?")
+            .bind(channel_id)
+            .bind(server_id)
+            .fetch_one(&state.db)
+            .await?
+            > 0;
+        if !in_server {
+            return Err(ApiError::bad_request("Channel is not in this server"));
+        }
+    }
+    Ok(())
+}
+
+/// `Some("")` clears an optional field.
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value.map(str::trim).filter(|v| !v.is_empty()).map(str::to_string)
+}
+
+/// POST /api/servers/:serverId/events
+/// Owner or admin only.
+pub async fn create_event(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+    Json(body): Json<CreateEventRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let fields = EventFields {
+        title: body.title.trim().to_string(),
+        description: body.description.as_deref().unwrap_or("").trim().to_string(),
+        start_at: normalize_time(&body.start_at, "startAt")?,
+        end_at: non_empty(body.end_at.as_deref())
+            .map(|end| normalize_time(&end, "endAt"))
+            .transpose()?,
+        channel_id: non_empty(body.channel_id.as_deref()),
+        recurrence: non_empty(body.recurrence.as_deref()),
+    };
+    validate(&state, &server_id, &fields).await?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"INSERT INTO events
+           (id, server_id, channel_id, title, description, start_at, end_at, recurrence, created_by, created_at, updated_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(&id)
+    .bind(&server_id)
+    .bind(&fields.channel_id)
+    .bind(&fields.title)
+    .bind(&fields.description)
+    .bind(&fields.start_at)
+    .bind(&fields.end_at)
+    .bind(&fields.recurrence)
+    .bind(&user.id)
+    .bind(&now)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    broadcast_event_updated(&state, &server_id, &id, false).await;
+
+    match fetch_event(&state, &user.id, &server_id, &id).await? {
+        Some(event) => Ok((StatusCode::CREATED, Json(event))),
+        None => Err(ApiError::internal("Failed to load created event")),
+    }
+}
+
+/// PATCH /api/servers/:serverId/events/:eventId
+/// Owner or admin only. Moving the start re-arms the reminder.
+pub async fn update_event(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, event_id)): Path<(String, String)>,
+    Json(body): Json<UpdateEventRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let existing = fetch_event(&state, &user.id, &server_id, &event_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Event not found"))?;
+
+    let start_at = match body.start_at.as_deref() {
+        Some(start) => normalize_time(start, "startAt")?,
+        None => existing.start_at.clone(),
+    };
+    let end_at = match body.end_at.as_deref() {
+        Some(end) => non_empty(Some(end)).map(|end| normalize_time(&end, "endAt")).transpose()?,
+        None => existing.end_at.clone(),
+    };
+    let fields = EventFields {
+        title: body.title.as_deref().map(str::trim).unwrap_or(&existing.title).to_string(),
+        description: body
+            .description
+            .as_deref()
+            .map(str::trim)
+            .unwrap_or(&existing.description)
+            .to_string(),
+        start_at,
+        end_at,
+        channel_id: match body.channel_id.as_deref() {
+            Some(channel_id) => non_empty(Some(channel_id)),
+            None => existing.channel_id.clone(),
+        },
+        recurrence: match body.recurrence.as_deref() {
+            Some(recurrence) => non_empty(Some(recurrence)),
+            None => existing.recurrence.clone(),
+        },
+    };
+    validate(&state, &server_id, &fields).await?;
+
+    let rescheduled = fields.start_at != existing.start_at || fields.recurrence != existing.recurrence;
+    sqlx::query(
+        r#"UPDATE events
+           SET title = ?, description = ?, start_at = ?, end_at = ?, channel_id = ?, recurrence = ?, updated_at = ?,
+               reminded_for = CASE WHEN ? THEN NULL ELSE reminded_for END
+           WHERE id = ? AND server_id = ?"#,
+    )
+    .bind(&fields.title)
+    .bind(&fields.description)
+    .bind(&fields.start_at)
+    .bind(&fields.end_at)
+    .bind(&fields.channel_id)
+    .bind(&fields.recurrence)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(rescheduled)
+    .bind(&event_id)
+    .bind(&server_id)
+    .execute(&state.db)
+    .await?;
+
+    broadcast_event_updated(&state, &server_id, &event_id, false).await;
+
+    match fetch_event(&state, &user.id, &server_id, &event_id).await? {
+        Some(event) => Ok(Json(event)),
+        None => Err(ApiError::not_found("Event not found")),
+    }
+}
+
+/// DELETE /api/servers/:serverId/events/:eventId
+/// Owner or admin only.
+pub async fn delete_event(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, event_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let deleted = sqlx::query("DELETE FROM events WHERE id = ? AND server_id = ?")
+        .bind(&event_id)
+        .bind(&server_id)
+        .execute(&state.db)
+        .await?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(ApiError::not_found("Event not found"));
+    }
+
+    broadcast_event_updated(&state, &server_id, &event_id, true).await;
+    Ok(StatusCode::NO_CONTENT)
+}
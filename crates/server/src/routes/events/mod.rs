@@ -0,0 +1,240 @@
+mod ics;
+mod manage;
+mod reminders;
+mod rsvp;
+
+pub use ics::*;
+pub use manage::*;
+pub use reminders::{run_event_reminders, send_due_reminders};
+pub use rsvp::*;
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Datelike, Months, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+// ── Request / response types ──────────────────────────────────────────────
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledEvent {
+    pub id: String,
+    pub server_id: String,
+    pub channel_id: Option<String>,
+    pub title: String,
+    pub description: String,
+    pub start_at: String,
+    pub end_at: Option<String>,
+    /// "daily", "weekly" or "monthly"; None for a one-off event
+    pub recurrence: Option<String>,
+    pub created_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub going_count: i64,
+    pub interested_count: i64,
+    /// The caller's RSVP, if they gave one
+    pub my_rsvp: Option<String>,
+    /// Start of the next occurrence from now; `start_at` once a one-off event
+    /// has begun
+    #[sqlx(skip)]
+    pub next_start_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateEventRequest {
+    pub title: String,
+    pub description: Option<String>,
+    pub start_at: String,
+    pub end_at: Option<String>,
+    pub channel_id: Option<String>,
+    pub recurrence: Option<String>,
+}
+
+/// Omitted fields are left alone; an empty `endAt`, `channelId` or
+/// `recurrence` clears it.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateEventRequest {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub start_at: Option<String>,
+    pub end_at: Option<String>,
+    pub channel_id: Option<String>,
+    pub recurrence: Option<String>,
+}
+
+// ── Shared query fragment ──────────────────────────────────────────────────
+
+/// Binds the caller's user id first, for `my_rsvp`.
+pub(super) const EVENT_SELECT: &str = r#"
+    SELECT
+        e.id, e.server_id, e.channel_id, e.title, e.description, e.start_at, e.end_at,
+        e.recurrence, e.created_by, e.created_at, e.updated_at,
+        (SELECT COUNT(*) FROM event_rsvps r WHERE r.event_id = e.id AND r.status = 'going') AS going_count,
+        (SELECT COUNT(*) FROM event_rsvps r WHERE r.event_id = e.id AND r.status = 'interested') AS interested_count,
+        (SELECT status FROM event_rsvps mine WHERE mine.event_id = e.id AND mine.user_id = ?) AS my_rsvp
+    FROM events e
+"#;
+
+pub(super) const RECURRENCES: &[&str] = &["daily", "weekly", "monthly"];
+pub(super) const MAX_TITLE_CHARS: usize = 100;
+pub(super) const MAX_DESCRIPTION_CHARS: usize = 2000;
+
+/// Parse a client-supplied time and store it as UTC, so times sort as text.
+pub(super) fn normalize_time(value: &str, field: &str) -> Result<String, ApiError> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .map(|t| t.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Secs, true))
+        .map_err(|_| ApiError::bad_request(format!("{} must be an RFC 3339 timestamp", field)))
+}
+
+/// The first occurrence starting at or after `after`. None once a one-off
+/// event has started.
+pub fn next_occurrence(start: DateTime<Utc>, recurrence: Option<&str>, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if start >= after {
+        return Some(start);
+    }
+    match recurrence {
+        Some("daily") | Some("weekly") => {
+            let period = if recurrence == Some("daily") { 86_400 } else { 7 * 86_400 };
+            let periods = ((after - start).num_seconds() + period - 1) / period;
+            Some(start + chrono::Duration::seconds(periods * period))
+        }
+        Some("monthly") => {
+            // Always count from the original start, so a 31st doesn't drift to
+            // the 28th after February
+            let months = (after.year() - start.year()) * 12 + after.month() as i32 - start.month() as i32;
+            let mut n = months.max(0) as u32;
+            loop {
+                let t = start.checked_add_months(Months::new(n))?;
+                if t >= after {
+                    return Some(t);
+                }
+                n += 1;
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Fill in `next_start_at` from the stored schedule.
+pub(super) fn with_next_start(mut event: ScheduledEvent) -> ScheduledEvent {
+    let now = Utc::now();
+    event.next_start_at = DateTime::parse_from_rfc3339(&event.start_at)
+        .ok()
+        .and_then(|start| next_occurrence(start.with_timezone(&Utc), event.recurrence.as_deref(), now))
+        .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_else(|| event.start_at.clone());
+    event
+}
+
+pub(super) async fn fetch_event(
+    state: &AppState,
+    user_id: &str,
+    server_id: &str,
+    event_id: &str,
+) -> Result<Option<ScheduledEvent>, ApiError> {
+    Ok(sqlx::query_as::<_, ScheduledEvent>(&format!("{} WHERE e.id = ? AND e.server_id = ?", EVENT_SELECT))
+        .bind(user_id)
+        .bind(event_id)
+        .bind(server_id)
+        .fetch_optional(&state.db)
+        .await?
+        .map(with_next_start))
+}
+
+/// Tell clients to refetch a server's events
+pub(super) async fn broadcast_event_updated(state: &AppState, server_id: &str, event_id: &str, deleted: bool) {
+    state
+        .gateway
+        .broadcast_all(
+            &ServerEvent::ScheduledEventUpdated {
+                server_id: server_id.to_string(),
+                event_id: event_id.to_string(),
+                deleted,
+            },
+            None,
+        )
+        .await;
+}
+
+// ── Per-server membership checks ──────────────────────────────────────────
+
+pub(super) async fn require_server_member(state: &AppState, user_id: &str, server_id: &str) -> Result<(), ApiError> {
+    let is_member = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
+    )
+    .bind(user_id)
+    .bind(server_id)
+    .fetch_one(&state.db)
+    .await?
+        > 0;
+
+    if !is_member {
+        return Err(ApiError::forbidden("Not a member of this server"));
+    }
+    Ok(())
+}
+
+pub(super) async fn require_server_admin(state: &AppState, user_id: &str, server_id: &str) -> Result<(), ApiError> {
+    let role = sqlx::query_scalar::<_, String>(
+        "SELECT role FROM memberships WHERE user_id = ? AND server_id = ?",
+    )
+    .bind(user_id)
+    .bind(server_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    match role.as_deref() {
+        Some("owner") | Some("admin") => Ok(()),
+        _ => Err(ApiError::forbidden("Insufficient permissions")),
+    }
+}
+
+// ── Handlers ──────────────────────────────────────────────────────────────
+
+/// GET /api/servers/:serverId/events
+/// Any server member can list events, soonest next occurrence first.
+pub async fn list_events(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_member(&state, &user.id, &server_id).await?;
+
+    let mut events: Vec<ScheduledEvent> =
+        sqlx::query_as::<_, ScheduledEvent>(&format!("{} WHERE e.server_id = ?", EVENT_SELECT))
+            .bind(&user.id)
+            .bind(&server_id)
+            .fetch_all(&state.db)
+            .await?
+            .into_iter()
+            .map(with_next_start)
+            .collect();
+    events.sort_by(|a, b| a.next_start_at.cmp(&b.next_start_at));
+
+    Ok(Json(events))
+}
+
+/// GET /api/servers/:serverId/events/:eventId
+pub async fn get_event(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, event_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_member(&state, &user.id, &server_id).await?;
+
+    match fetch_event(&state, &user.id, &server_id, &event_id).await? {
+        Some(event) => Ok(Json(event)),
+        None => Err(ApiError::not_found("Event not found")),
+    }
+}
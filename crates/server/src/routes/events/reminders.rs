@@ -0,0 +1,112 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+use super::next_occurrence;
+
+#[derive(sqlx::FromRow)]
+struct DueEvent {
+    id: String,
+    server_id: String,
+    channel_id: Option<String>,
+    title: String,
+    start_at: String,
+    recurrence: Option<String>,
+    reminded_for: Option<String>,
+}
+
+/// Remind everyone who said they're going or interested about events whose
+/// next occurrence starts within `event_reminder_minutes`. Each occurrence is
+/// reminded once. Returns how many reminders were sent.
+pub async fn send_due_reminders(state: &AppState) -> usize {
+    let now = Utc::now();
+    let horizon = now + chrono::Duration::minutes(state.config.event_reminder_minutes as i64);
+
+    // One-off events can be narrowed in SQL; recurring ones need their next
+    // occurrence worked out
+    let events = match sqlx::query_as::<_, DueEvent>(
+        r#"SELECT id, server_id, channel_id, title, start_at, recurrence, reminded_for FROM events
+           WHERE recurrence IS NOT NULL OR (start_at >= ? AND start_at <= ?)"#,
+    )
+    .bind(now.to_rfc3339_opts(SecondsFormat::Secs, true))
+    .bind(horizon.to_rfc3339_opts(SecondsFormat::Secs, true))
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(events) => events,
+        Err(e) => {
+            tracing::warn!("Failed to load events for reminders: {}", e);
+            return 0;
+        }
+    };
+
+    let mut sent = 0;
+    for event in events {
+        let Ok(start) = DateTime::parse_from_rfc3339(&event.start_at) else { continue };
+        let Some(next) = next_occurrence(start.with_timezone(&Utc), event.recurrence.as_deref(), now) else {
+            continue;
+        };
+        if next > horizon {
+            continue;
+        }
+        let occurrence = next.to_rfc3339_opts(SecondsFormat::Secs, true);
+        if event.reminded_for.as_deref() == Some(occurrence.as_str()) {
+            continue;
+        }
+
+        // Claim the occurrence first so a slow send can't remind twice
+        let claimed = sqlx::query(
+            "UPDATE events SET reminded_for = ? WHERE id = ? AND (reminded_for IS NULL OR reminded_for != ?)",
+        )
+        .bind(&occurrence)
+        .bind(&event.id)
+        .bind(&occurrence)
+        .execute(&state.db)
+        .await
+        .map(|r| r.rows_affected() > 0)
+        .unwrap_or(false);
+        if !claimed {
+            continue;
+        }
+
+        let recipients: Vec<String> = sqlx::query_scalar(
+            r#"SELECT r.user_id FROM event_rsvps r
+               INNER JOIN memberships m ON m.user_id = r.user_id AND m.server_id = ?
+               WHERE r.event_id = ? AND r.status IN ('going', 'interested')"#,
+        )
+        .bind(&event.server_id)
+        .bind(&event.id)
+        .fetch_all(&state.db)
+        .await
+        .unwrap_or_default();
+
+        let reminder = ServerEvent::EventReminder {
+            server_id: event.server_id.clone(),
+            event_id: event.id.clone(),
+            channel_id: event.channel_id.clone(),
+            title: event.title.clone(),
+            start_at: occurrence,
+        };
+        for user_id in &recipients {
+            state.gateway.send_to_user(user_id, &reminder).await;
+        }
+        sent += recipients.len();
+    }
+
+    sent
+}
+
+/// Background task: check for upcoming events once a minute.
+pub async fn run_event_reminders(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(60));
+    loop {
+        interval.tick().await;
+        let sent = send_due_reminders(&state).await;
+        if sent > 0 {
+            tracing::debug!("Sent {} event reminders", sent);
+        }
+    }
+}
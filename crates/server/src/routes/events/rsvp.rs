@@ -0,0 +1,113 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::AppState;
+
+use super::{broadcast_event_updated, fetch_event, require_server_member};
+
+/// "going" and "interested" RSVPs get reminders; "not_going" is recorded so
+/// organisers can see who declined.
+const RSVP_STATUSES: &[&str] = &["going", "interested", "not_going"];
+
+#[derive(Debug, Deserialize)]
+pub struct RsvpRequest {
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct EventRsvp {
+    pub user_id: String,
+    pub username: String,
+    pub image: Option<String>,
+    pub status: String,
+    pub updated_at: String,
+}
+
+/// GET /api/servers/:serverId/events/:eventId/rsvps
+pub async fn list_event_rsvps(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, event_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_member(&state, &user.id, &server_id).await?;
+    if fetch_event(&state, &user.id, &server_id, &event_id).await?.is_none() {
+        return Err(ApiError::not_found("Event not found"));
+    }
+
+    let rsvps = sqlx::query_as::<_, EventRsvp>(
+        r#"SELECT r.user_id, u.username, u.image, r.status, r.updated_at
+           FROM event_rsvps r
+           INNER JOIN "user" u ON u.id = r.user_id
+           WHERE r.event_id = ?
+           ORDER BY r.updated_at ASC"#,
+    )
+    .bind(&event_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(rsvps))
+}
+
+/// PUT /api/servers/:serverId/events/:eventId/rsvp
+pub async fn set_event_rsvp(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, event_id)): Path<(String, String)>,
+    Json(body): Json<RsvpRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_member(&state, &user.id, &server_id).await?;
+    if !RSVP_STATUSES.contains(&body.status.as_str()) {
+        return Err(ApiError::bad_request("Status must be going, interested or not_going"));
+    }
+    if fetch_event(&state, &user.id, &server_id, &event_id).await?.is_none() {
+        return Err(ApiError::not_found("Event not found"));
+    }
+
+    sqlx::query(
+        r#"INSERT INTO event_rsvps (event_id, user_id, status, updated_at) VALUES (?, ?, ?, ?)
+           ON CONFLICT(event_id, user_id) DO UPDATE SET status = excluded.status, updated_at = excluded.updated_at"#,
+    )
+    .bind(&event_id)
+    .bind(&user.id)
+    .bind(&body.status)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&state.db)
+    .await?;
+
+    broadcast_event_updated(&state, &server_id, &event_id, false).await;
+
+    match fetch_event(&state, &user.id, &server_id, &event_id).await? {
+        Some(event) => Ok(Json(event)),
+        None => Err(ApiError::not_found("Event not found")),
+    }
+}
+
+/// DELETE /api/servers/:serverId/events/:eventId/rsvp
+pub async fn clear_event_rsvp(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, event_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_member(&state, &user.id, &server_id).await?;
+
+    sqlx::query("DELETE FROM event_rsvps WHERE event_id = ? AND user_id = ?")
+        .bind(&event_id)
+        .bind(&user.id)
+        .execute(&state.db)
+        .await?;
+
+    broadcast_event_updated(&state, &server_id, &event_id, false).await;
+
+    match fetch_event(&state, &user.id, &server_id, &event_id).await? {
+        Some(event) => Ok(Json(event)),
+        None => Err(ApiError::not_found("Event not found")),
+    }
+}
@@ -0,0 +1,169 @@
+mod poller;
+mod subscriptions;
+
+pub use poller::{parse_feed, poll_feeds, run_feed_poller, FeedEntry, ParsedFeed};
+pub use subscriptions::*;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, ChannelFeed, CreateFeedRequest};
+use crate::AppState;
+
+const MAX_FEEDS_PER_CHANNEL: i64 = 10;
+const MAX_TITLE_CHARS: usize = 100;
+
+/// Feeds and webhook integrations are managed by server owners and admins,
+/// and only post to text channels.
+pub(crate) async fn require_channel_admin(
+    state: &AppState,
+    user_id: &str,
+    server_id: &str,
+    channel_id: &str,
+) -> Result<(), ApiError> {
+    let role = sqlx::query_scalar::<_, String>(
+        "SELECT role FROM memberships WHERE user_id = ? AND server_id = ?",
+    )
+    .bind(user_id)
+    .bind(server_id)
+    .fetch_optional(&state.db)
+    .await?;
+    if !matches!(role.as_deref(), Some("owner") | Some("admin")) {
+        return Err(ApiError::forbidden("Insufficient permissions"));
+    }
+
+    let channel_type = sqlx::query_scalar::<_, String>(
+        "SELECT type FROM channels WHERE id = ? AND server_id = ?",
+    )
+    .bind(channel_id)
+    .bind(server_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Channel not found"))?;
+    if channel_type != "text" {
+        return Err(ApiError::bad_request("Only text channels can have feeds or integrations"));
+    }
+
+    Ok(())
+}
+
+/// GET /api/servers/:serverId/channels/:channelId/feeds
+pub async fn list_feeds(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, channel_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_channel_admin(&state, &user.id, &server_id, &channel_id).await?;
+
+    let feeds = sqlx::query_as::<_, ChannelFeed>(
+        "SELECT * FROM channel_feeds WHERE channel_id = ? ORDER BY created_at ASC",
+    )
+    .bind(&channel_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(feeds))
+}
+
+/// POST /api/servers/:serverId/channels/:channelId/feeds — the feed is fetched
+/// once up front; entries already in it are treated as seen, so only later
+/// ones get posted.
+pub async fn create_feed(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, channel_id)): Path<(String, String)>,
+    Json(body): Json<CreateFeedRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_channel_admin(&state, &user.id, &server_id, &channel_id).await?;
+
+    let url = body.url.trim();
+    match url::Url::parse(url) {
+        Ok(u) if matches!(u.scheme(), "http" | "https") => {}
+        _ => return Err(ApiError::bad_request("Feed URL must be an http(s) URL")),
+    }
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM channel_feeds WHERE channel_id = ?")
+        .bind(&channel_id)
+        .fetch_one(&state.db)
+        .await?;
+    if count >= MAX_FEEDS_PER_CHANNEL {
+        return Err(ApiError::bad_request(format!(
+            "A channel can have at most {} feeds",
+            MAX_FEEDS_PER_CHANNEL
+        )));
+    }
+    let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM channel_feeds WHERE channel_id = ? AND url = ?")
+        .bind(&channel_id)
+        .bind(url)
+        .fetch_one(&state.db)
+        .await?
+        > 0;
+    if exists {
+        return Err(ApiError::conflict("This channel already has that feed"));
+    }
+
+    let parsed = poller::fetch_feed(url).await.map_err(ApiError::bad_request)?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let feed = ChannelFeed {
+        id: uuid::Uuid::new_v4().to_string(),
+        channel_id,
+        url: url.to_string(),
+        title: parsed
+            .title
+            .as_deref()
+            .map(|t| t.chars().take(MAX_TITLE_CHARS).collect())
+            .unwrap_or_else(|| url.to_string()),
+        created_by: user.id,
+        created_at: now.clone(),
+        last_polled_at: Some(now),
+        last_error: None,
+    };
+
+    let mut tx = state.db.begin().await?;
+    sqlx::query(
+        r#"INSERT INTO channel_feeds (id, channel_id, url, title, created_by, created_at, last_polled_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(&feed.id)
+    .bind(&feed.channel_id)
+    .bind(&feed.url)
+    .bind(&feed.title)
+    .bind(&feed.created_by)
+    .bind(&feed.created_at)
+    .bind(&feed.last_polled_at)
+    .execute(&mut *tx)
+    .await?;
+    let guids: Vec<&str> = parsed.entries.iter().map(|e| e.guid.as_str()).collect();
+    poller::mark_seen(&mut tx, &feed.id, &guids).await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(feed)))
+}
+
+/// DELETE /api/servers/:serverId/channels/:channelId/feeds/:feedId
+pub async fn delete_feed(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, channel_id, feed_id)): Path<(String, String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_channel_admin(&state, &user.id, &server_id, &channel_id).await?;
+
+    let deleted = sqlx::query("DELETE FROM channel_feeds WHERE id = ? AND channel_id = ?")
+        .bind(&feed_id)
+        .bind(&channel_id)
+        .execute(&state.db)
+        .await?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(ApiError::not_found("Feed not found"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,295 @@
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::models::ChannelFeed;
+use crate::AppState;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_FEED_BYTES: usize = 2 * 1024 * 1024;
+/// Entries past this many in one document are ignored.
+const MAX_ENTRIES: usize = 200;
+/// A feed that suddenly publishes a lot (or changes all its GUIDs) posts only
+/// the newest few; the rest are marked seen without a message.
+const MAX_POSTS_PER_POLL: usize = 5;
+const MAX_TITLE_CHARS: usize = 300;
+/// Seen GUIDs that have dropped out of the feed are forgotten after this long.
+const ENTRY_RETENTION_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedEntry {
+    /// `<guid>` / `<id>`, falling back to the link, then the title.
+    pub guid: String,
+    pub title: Option<String>,
+    pub link: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedFeed {
+    pub title: Option<String>,
+    /// In document order, which for most feeds is newest first.
+    pub entries: Vec<FeedEntry>,
+}
+
+/// Parse an RSS 2.0, RSS 1.0 (RDF) or Atom document. None if it isn't one.
+pub fn parse_feed(xml: &str) -> Option<ParsedFeed> {
+    let mut reader = Reader::from_str(xml);
+    let mut root_seen = false;
+    let mut feed = ParsedFeed { title: None, entries: Vec::new() };
+    let mut entry: Option<(Option<String>, Option<String>, Option<String>)> = None;
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(e) => {
+                let name = e.local_name();
+                let name = name.as_ref();
+                if !root_seen {
+                    if !matches!(name, "rss" | "feed" | "RDF") {
+                        return None;
+                    }
+                    root_seen = true;
+                }
+                text.clear();
+                match name {
+                    "item" | "entry" => entry = Some((None, None, None)),
+                    "link" => {
+                        if let Some((_, _, link)) = entry.as_mut() {
+                            atom_link(&e, link);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Empty(e) if e.local_name().as_ref() == "link" => {
+                if let Some((_, _, link)) = entry.as_mut() {
+                    atom_link(&e, link);
+                }
+            }
+            Event::Text(e) => text.push_str(&e.xml10_content()),
+            Event::CData(e) => text.push_str(&e.into_inner()),
+            Event::GeneralRef(e) => {
+                if e.is_char_ref() {
+                    if let Ok(Some(c)) = e.resolve_char_ref() {
+                        text.push(c);
+                    }
+                } else if let Some(resolved) = quick_xml::escape::resolve_xml_entity(&e) {
+                    text.push_str(resolved);
+                }
+            }
+            Event::End(e) => {
+                let value = text.trim();
+                let value = (!value.is_empty()).then(|| value.to_string());
+                let name = e.local_name();
+                let name = name.as_ref();
+                if matches!(name, "item" | "entry") {
+                    if let Some((guid, title, link)) = entry.take() {
+                        if let Some(guid) = guid.or_else(|| link.clone()).or_else(|| title.clone()) {
+                            if feed.entries.len() < MAX_ENTRIES {
+                                feed.entries.push(FeedEntry { guid, title, link });
+                            }
+                        }
+                    }
+                    text.clear();
+                    continue;
+                }
+                match (name, entry.as_mut()) {
+                    ("guid" | "id", Some((guid, _, _))) => *guid = value,
+                    ("title", Some((_, title, _))) => *title = value,
+                    ("link", Some((_, _, link))) if link.is_none() => *link = value,
+                    // The first channel-level title; later ones belong to <image> and the like
+                    ("title", None) if feed.title.is_none() => feed.title = value,
+                    _ => {}
+                }
+                text.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    root_seen.then_some(feed)
+}
+
+/// Atom's `<link href="..."/>`; only the alternate (default) link is the post.
+fn atom_link(e: &quick_xml::events::BytesStart, link: &mut Option<String>) {
+    if link.is_some() {
+        return;
+    }
+    let mut href = None;
+    let mut alternate = true;
+    for attr in e.attributes().flatten() {
+        let value = attr.normalized_value(quick_xml::XmlVersion::Implicit1_0).ok();
+        match attr.key.local_name().as_ref() {
+            "href" => href = value.map(|v| v.into_owned()),
+            "rel" => alternate = value.as_deref() == Some("alternate"),
+            _ => {}
+        }
+    }
+    if alternate {
+        *link = href.filter(|h| !h.trim().is_empty());
+    }
+}
+
+/// Download and parse a feed. The error is short enough to show admins.
+pub(crate) async fn fetch_feed(url: &str) -> Result<ParsedFeed, String> {
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .user_agent("Mozilla/5.0 (compatible; FluxBot/1.0)")
+        .build()
+        .unwrap_or_default();
+
+    let response = client.get(url).send().await.map_err(|_| "Could not reach the feed".to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("Feed returned HTTP {}", response.status().as_u16()));
+    }
+    if response.content_length().is_some_and(|len| len as usize > MAX_FEED_BYTES) {
+        return Err("Feed is too large".to_string());
+    }
+    let body = response.bytes().await.map_err(|_| "Could not read the feed".to_string())?;
+    if body.len() > MAX_FEED_BYTES {
+        return Err("Feed is too large".to_string());
+    }
+
+    parse_feed(&String::from_utf8_lossy(&body)).ok_or_else(|| "Not an RSS or Atom feed".to_string())
+}
+
+/// Message text for an entry. The link goes on its own line so clients show
+/// their usual link preview for it.
+fn entry_content(feed_title: &str, entry: &FeedEntry) -> String {
+    let title: String = entry
+        .title
+        .as_deref()
+        .unwrap_or("New post")
+        .chars()
+        .take(MAX_TITLE_CHARS)
+        .collect();
+    let mut content = format!("**{}** · {}", feed_title, title);
+    if let Some(ref link) = entry.link {
+        content.push('\n');
+        content.push_str(link);
+    }
+    content
+}
+
+/// Remember `guids` as seen so they are never posted. Returns the ones that
+/// weren't already known.
+pub(crate) async fn mark_seen(
+    conn: &mut sqlx::SqliteConnection,
+    feed_id: &str,
+    guids: &[&str],
+) -> Result<HashSet<String>, sqlx::Error> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut fresh = HashSet::new();
+    for guid in guids {
+        let inserted = sqlx::query("INSERT OR IGNORE INTO feed_entries (feed_id, guid, seen_at) VALUES (?, ?, ?)")
+            .bind(feed_id)
+            .bind(guid)
+            .bind(&now)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected()
+            > 0;
+        if inserted {
+            fresh.insert(guid.to_string());
+        }
+    }
+    Ok(fresh)
+}
+
+/// Fetch one feed and post what's new. Returns how many messages were posted.
+async fn poll_feed(state: &AppState, feed: &ChannelFeed) -> Result<usize, String> {
+    let parsed = fetch_feed(&feed.url).await?;
+    let guids: Vec<&str> = parsed.entries.iter().map(|e| e.guid.as_str()).collect();
+
+    let fresh = mark_seen(&mut *state.db.acquire().await.map_err(|e| e.to_string())?, &feed.id, &guids)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let cutoff = (chrono::Utc::now() - chrono::Duration::days(ENTRY_RETENTION_DAYS)).to_rfc3339();
+    let _ = sqlx::query(
+        "DELETE FROM feed_entries WHERE feed_id = ? AND seen_at < ? AND guid NOT IN (SELECT value FROM json_each(?))",
+    )
+    .bind(&feed.id)
+    .bind(&cutoff)
+    .bind(serde_json::to_string(&guids).unwrap_or_default())
+    .execute(&state.db)
+    .await;
+
+    // Newest first in the document; post the newest few, oldest of those first
+    let to_post: Vec<&FeedEntry> = parsed
+        .entries
+        .iter()
+        .filter(|e| fresh.contains(&e.guid))
+        .take(MAX_POSTS_PER_POLL)
+        .collect();
+    let feed_title = parsed.title.as_deref().unwrap_or(&feed.title);
+
+    let mut posted = 0;
+    for entry in to_post.into_iter().rev() {
+        let message = crate::models::Message {
+            id: uuid::Uuid::new_v4().to_string(),
+            channel_id: feed.channel_id.clone(),
+            sender_id: feed.created_by.clone(),
+            content: entry_content(feed_title, entry),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            edited_at: None,
+            metadata: None,
+            feed_id: Some(feed.id.clone()),
+            integration_id: None,
+        };
+        crate::ws::handler::post_automated_message(state, message)
+            .await
+            .map_err(|e| format!("Failed to post entry: {}", e))?;
+        posted += 1;
+    }
+
+    Ok(posted)
+}
+
+/// Poll every registered feed once, recording the outcome on each.
+pub async fn poll_feeds(state: &AppState) {
+    let feeds = match sqlx::query_as::<_, ChannelFeed>("SELECT * FROM channel_feeds ORDER BY created_at")
+        .fetch_all(&state.db)
+        .await
+    {
+        Ok(feeds) => feeds,
+        Err(e) => {
+            tracing::warn!("Failed to load channel feeds: {}", e);
+            return;
+        }
+    };
+
+    for feed in feeds {
+        let error = match poll_feed(state, &feed).await {
+            Ok(posted) => {
+                if posted > 0 {
+                    tracing::debug!("Feed {} posted {} entries", feed.id, posted);
+                }
+                None
+            }
+            Err(e) => {
+                tracing::warn!("Polling feed {} failed: {}", feed.url, e);
+                Some(e)
+            }
+        };
+        let _ = sqlx::query("UPDATE channel_feeds SET last_polled_at = ?, last_error = ? WHERE id = ?")
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(&error)
+            .bind(&feed.id)
+            .execute(&state.db)
+            .await;
+    }
+}
+
+/// Background task: poll all feeds every `feed_poll_interval_secs`.
+pub async fn run_feed_poller(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(state.config.feed_poll_interval_secs));
+    loop {
+        interval.tick().await;
+        poll_feeds(&state).await;
+    }
+}
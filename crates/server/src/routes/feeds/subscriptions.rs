@@ -0,0 +1,374 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, ChannelFeedToken, CreateFeedTokenRequest};
+use crate::routes::auth::{random_token, token_hash};
+use crate::AppState;
+
+const MAX_TOKENS_PER_CHANNEL: i64 = 5;
+const MAX_NAME_CHARS: usize = 100;
+/// Newest messages a feed document holds.
+const MAX_ITEMS: i64 = 50;
+/// Item titles are the start of the message's first line.
+const MAX_ITEM_TITLE_CHARS: usize = 80;
+
+const FORMATS: &[(&str, &str)] = &[("atom", "feed.atom"), ("rss", "feed.rss"), ("json", "feed.json")];
+
+#[derive(Deserialize)]
+pub struct FeedQuery {
+    pub token: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct FeedItem {
+    id: String,
+    author: String,
+    content: String,
+    created_at: String,
+    edited_at: Option<String>,
+}
+
+/// Channel name, server id and server name, if the user can subscribe to it.
+async fn subscribable_channel(
+    state: &AppState,
+    user_id: &str,
+    channel_id: &str,
+) -> Result<(String, String, String), ApiError> {
+    let (channel_name, channel_type, server_id, server_name) = sqlx::query_as::<_, (String, String, String, String)>(
+        r#"SELECT c.name, c.type, c.server_id, s.name FROM channels c
+           INNER JOIN servers s ON s.id = c.server_id
+           WHERE c.id = ?"#,
+    )
+    .bind(channel_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Channel not found"))?;
+
+    let is_member = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?")
+        .bind(user_id)
+        .bind(&server_id)
+        .fetch_one(&state.db)
+        .await?
+        > 0;
+    if !is_member {
+        return Err(ApiError::forbidden("Not a member of this server"));
+    }
+    if channel_type != "text" {
+        return Err(ApiError::bad_request("Only text channels have feeds"));
+    }
+
+    Ok((channel_name, server_id, server_name))
+}
+
+/// The token plus the feed URLs built from it; neither can be shown again.
+fn with_urls(state: &AppState, feed_token: &ChannelFeedToken, token: &str) -> serde_json::Value {
+    let mut body = serde_json::to_value(feed_token).unwrap_or_default();
+    body["token"] = token.into();
+    let base = format!(
+        "{}/api/channels/{}",
+        state.config.public_url.trim_end_matches('/'),
+        feed_token.channel_id
+    );
+    body["urls"] = FORMATS
+        .iter()
+        .map(|(format, file)| (format.to_string(), format!("{}/{}?token={}", base, file, token).into()))
+        .collect::<serde_json::Map<_, _>>()
+        .into();
+    body
+}
+
+/// GET /api/channels/:channelId/feed-tokens — the caller's own tokens
+pub async fn list_feed_tokens(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(channel_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    subscribable_channel(&state, &user.id, &channel_id).await?;
+
+    let tokens = sqlx::query_as::<_, ChannelFeedToken>(
+        r#"SELECT id, channel_id, user_id, name, created_at, last_used_at FROM channel_feed_tokens
+           WHERE channel_id = ? AND user_id = ? ORDER BY created_at ASC"#,
+    )
+    .bind(&channel_id)
+    .bind(&user.id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(tokens))
+}
+
+/// POST /api/channels/:channelId/feed-tokens — issue a token for following
+/// the channel from a feed reader. It reads as the member who issued it and
+/// stops working if they leave the server.
+pub async fn create_feed_token(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(channel_id): Path<String>,
+    Json(body): Json<CreateFeedTokenRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (channel_name, _, _) = subscribable_channel(&state, &user.id, &channel_id).await?;
+
+    let name = body.name.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    if name.is_some_and(|n| n.chars().count() > MAX_NAME_CHARS) {
+        return Err(ApiError::bad_request(format!("Name must be at most {} characters", MAX_NAME_CHARS)));
+    }
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM channel_feed_tokens WHERE channel_id = ? AND user_id = ?")
+        .bind(&channel_id)
+        .bind(&user.id)
+        .fetch_one(&state.db)
+        .await?;
+    if count >= MAX_TOKENS_PER_CHANNEL {
+        return Err(ApiError::bad_request(format!(
+            "You can have at most {} feed tokens per channel",
+            MAX_TOKENS_PER_CHANNEL
+        )));
+    }
+
+    let token = random_token();
+    let feed_token = ChannelFeedToken {
+        id: uuid::Uuid::new_v4().to_string(),
+        channel_id,
+        user_id: user.id,
+        name: name.map(str::to_string).unwrap_or_else(|| format!("#{}", channel_name)),
+        created_at: Utc::now().to_rfc3339(),
+        last_used_at: None,
+    };
+
+    sqlx::query(
+        r#"INSERT INTO channel_feed_tokens (id, channel_id, user_id, name, token_hash, created_at)
+           VALUES (?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(&feed_token.id)
+    .bind(&feed_token.channel_id)
+    .bind(&feed_token.user_id)
+    .bind(&feed_token.name)
+    .bind(token_hash(&state.config.auth_secret, &token))
+    .bind(&feed_token.created_at)
+    .execute(&state.db)
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(with_urls(&state, &feed_token, &token))))
+}
+
+/// DELETE /api/channels/:channelId/feed-tokens/:tokenId
+pub async fn revoke_feed_token(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((channel_id, token_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let deleted = sqlx::query("DELETE FROM channel_feed_tokens WHERE id = ? AND channel_id = ? AND user_id = ?")
+        .bind(&token_id)
+        .bind(&channel_id)
+        .bind(&user.id)
+        .execute(&state.db)
+        .await?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(ApiError::not_found("Feed token not found"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Resolve `?token=` for a channel and load what the feed shows.
+async fn load_feed(
+    state: &AppState,
+    channel_id: &str,
+    query: &FeedQuery,
+) -> Result<(String, Vec<FeedItem>), ApiError> {
+    let token = query
+        .token
+        .as_deref()
+        .ok_or_else(|| ApiError::unauthorized("Missing feed token"))?;
+    let (token_id, user_id) = sqlx::query_as::<_, (String, String)>(
+        "SELECT id, user_id FROM channel_feed_tokens WHERE token_hash = ? AND channel_id = ?",
+    )
+    .bind(token_hash(&state.config.auth_secret, token))
+    .bind(channel_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Unknown feed"))?;
+
+    let (channel_name, server_id, server_name) = subscribable_channel(state, &user_id, channel_id).await?;
+
+    sqlx::query("UPDATE channel_feed_tokens SET last_used_at = ? WHERE id = ?")
+        .bind(Utc::now().to_rfc3339())
+        .bind(&token_id)
+        .execute(&state.db)
+        .await?;
+
+    let mut items = sqlx::query_as::<_, FeedItem>(
+        r#"SELECT m.id, COALESCE(ms.nickname, u.username, 'Deleted user') AS author,
+                  m.content, m.created_at, m.edited_at
+           FROM messages m
+           LEFT JOIN "user" u ON u.id = m.sender_id
+           LEFT JOIN memberships ms ON ms.user_id = m.sender_id AND ms.server_id = ?
+           WHERE m.channel_id = ?
+           ORDER BY m.created_at DESC, m.id DESC
+           LIMIT ?"#,
+    )
+    .bind(&server_id)
+    .bind(channel_id)
+    .bind(MAX_ITEMS)
+    .fetch_all(&state.db_read)
+    .await?;
+    items.retain(|item| !item.content.trim().is_empty());
+
+    Ok((format!("#{} - {}", channel_name, server_name), items))
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Control characters aren't allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc))
+}
+
+fn item_title(content: &str) -> String {
+    let line = content.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or_default();
+    if line.chars().count() > MAX_ITEM_TITLE_CHARS {
+        format!("{}…", line.chars().take(MAX_ITEM_TITLE_CHARS - 1).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+fn item_id(message_id: &str) -> String {
+    format!("urn:flux:message:{}", message_id)
+}
+
+fn updated_at(item: &FeedItem) -> &str {
+    item.edited_at.as_deref().unwrap_or(&item.created_at)
+}
+
+fn render_atom(title: &str, link: &str, channel_id: &str, items: &[FeedItem]) -> String {
+    let updated = items
+        .iter()
+        .map(updated_at)
+        .max()
+        .map(str::to_string)
+        .unwrap_or_else(|| Utc::now().to_rfc3339());
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    out.push_str(&format!("<id>urn:flux:channel:{}</id>\n", escape_xml(channel_id)));
+    out.push_str(&format!("<title>{}</title>\n", escape_xml(title)));
+    out.push_str(&format!("<link href=\"{}\"/>\n", escape_xml(link)));
+    out.push_str(&format!("<updated>{}</updated>\n", escape_xml(&updated)));
+    for item in items {
+        out.push_str("<entry>\n");
+        out.push_str(&format!("<id>{}</id>\n", escape_xml(&item_id(&item.id))));
+        out.push_str(&format!("<title>{}</title>\n", escape_xml(&item_title(&item.content))));
+        out.push_str(&format!("<author><name>{}</name></author>\n", escape_xml(&item.author)));
+        out.push_str(&format!("<published>{}</published>\n", escape_xml(&item.created_at)));
+        out.push_str(&format!("<updated>{}</updated>\n", escape_xml(updated_at(item))));
+        out.push_str(&format!("<content type=\"text\">{}</content>\n", escape_xml(&item.content)));
+        out.push_str("</entry>\n");
+    }
+    out.push_str("</feed>\n");
+    out
+}
+
+fn render_rss(title: &str, link: &str, items: &[FeedItem]) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<rss version=\"2.0\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n<channel>\n");
+    out.push_str(&format!("<title>{}</title>\n", escape_xml(title)));
+    out.push_str(&format!("<link>{}</link>\n", escape_xml(link)));
+    out.push_str(&format!("<description>{}</description>\n", escape_xml(title)));
+    for item in items {
+        out.push_str("<item>\n");
+        out.push_str(&format!("<guid isPermaLink=\"false\">{}</guid>\n", escape_xml(&item_id(&item.id))));
+        out.push_str(&format!("<title>{}</title>\n", escape_xml(&item_title(&item.content))));
+        out.push_str(&format!("<dc:creator>{}</dc:creator>\n", escape_xml(&item.author)));
+        if let Some(time) = parse_time(&item.created_at) {
+            out.push_str(&format!("<pubDate>{}</pubDate>\n", time.to_rfc2822()));
+        }
+        out.push_str(&format!("<description>{}</description>\n", escape_xml(&item.content)));
+        out.push_str("</item>\n");
+    }
+    out.push_str("</channel>\n</rss>\n");
+    out
+}
+
+/// JSON Feed 1.1
+fn render_json(title: &str, link: &str, feed_url: &str, items: &[FeedItem]) -> serde_json::Value {
+    serde_json::json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": title,
+        "home_page_url": link,
+        "feed_url": feed_url,
+        "items": items.iter().map(|item| serde_json::json!({
+            "id": item_id(&item.id),
+            "title": item_title(&item.content),
+            "content_text": item.content,
+            "date_published": item.created_at,
+            "date_modified": updated_at(item),
+            "authors": [{ "name": item.author }],
+        })).collect::<Vec<_>>(),
+    })
+}
+
+/// GET /api/channels/:channelId/feed.atom?token=
+pub async fn channel_feed_atom(
+    State(state): State<Arc<AppState>>,
+    Path(channel_id): Path<String>,
+    Query(query): Query<FeedQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (title, items) = load_feed(&state, &channel_id, &query).await?;
+    Ok((
+        [(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")],
+        render_atom(&title, &state.config.public_url, &channel_id, &items),
+    ))
+}
+
+/// GET /api/channels/:channelId/feed.rss?token=
+pub async fn channel_feed_rss(
+    State(state): State<Arc<AppState>>,
+    Path(channel_id): Path<String>,
+    Query(query): Query<FeedQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (title, items) = load_feed(&state, &channel_id, &query).await?;
+    Ok((
+        [(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")],
+        render_rss(&title, &state.config.public_url, &items),
+    ))
+}
+
+/// GET /api/channels/:channelId/feed.json?token=
+pub async fn channel_feed_json(
+    State(state): State<Arc<AppState>>,
+    Path(channel_id): Path<String>,
+    Query(query): Query<FeedQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (title, items) = load_feed(&state, &channel_id, &query).await?;
+    let feed_url = format!(
+        "{}/api/channels/{}/feed.json",
+        state.config.public_url.trim_end_matches('/'),
+        channel_id
+    );
+    Ok((
+        [(header::CONTENT_TYPE, "application/feed+json; charset=utf-8")],
+        render_json(&title, &state.config.public_url, &feed_url, &items).to_string(),
+    ))
+}
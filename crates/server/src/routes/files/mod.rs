@@ -5,31 +5,37 @@ pub use preview::*;
 use axum::{
     body::Body,
     extract::{Multipart, Path, State},
-    http::{header, StatusCode},
+    http::header,
     response::IntoResponse,
     Json,
 };
 use std::sync::Arc;
 use tokio_util::io::ReaderStream;
 
+use crate::error::ApiError;
 use crate::models::{Attachment, AuthUser};
 use crate::AppState;
 
+/// On-disk location of an attachment: `<upload_dir>/<id>.<ext>`, with the
+/// extension taken from the original filename.
+pub fn stored_file_path(upload_dir: &str, id: &str, filename: &str) -> std::path::PathBuf {
+    let ext = filename
+        .rsplit('.')
+        .next()
+        .filter(|e| e.len() <= 10 && e.chars().all(|c| c.is_alphanumeric()))
+        .unwrap_or("bin");
+    std::path::Path::new(upload_dir).join(format!("{}.{}", id, ext))
+}
+
 /// POST /api/upload
 pub async fn upload(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     mut multipart: Multipart,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let field = match multipart.next_field().await {
         Ok(Some(f)) => f,
-        _ => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": "No file provided"})),
-            )
-                .into_response()
-        }
+        _ => return Err(ApiError::bad_request("No file provided")),
     };
 
     let original_filename = field
@@ -44,45 +50,22 @@ pub async fn upload(
     // Read file data
     let data = match field.bytes().await {
         Ok(d) => d,
-        Err(_) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": "Failed to read file"})),
-            )
-                .into_response()
-        }
+        Err(_) => return Err(ApiError::bad_request("Failed to read file")),
     };
 
     let size = data.len() as u64;
     if size > state.config.max_upload_bytes {
-        return (
-            StatusCode::PAYLOAD_TOO_LARGE,
-            Json(serde_json::json!({
-                "error": format!("File too large. Max size: {} MB", state.config.max_upload_bytes / 1_048_576)
-            })),
-        )
-            .into_response();
+        return Err(ApiError::payload_too_large(format!("File too large. Max size: {} MB", state.config.max_upload_bytes / 1_048_576)));
     }
 
     let id = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
 
-    // Determine extension from original filename
-    let ext = original_filename
-        .rsplit('.')
-        .next()
-        .filter(|e| e.len() <= 10 && e.chars().all(|c| c.is_alphanumeric()))
-        .unwrap_or("bin");
-    let stored_filename = format!("{}.{}", id, ext);
-    let file_path = std::path::Path::new(&state.config.upload_dir).join(&stored_filename);
+    let file_path = stored_file_path(&state.config.upload_dir, &id, &original_filename);
 
     // Write file to disk
     if tokio::fs::write(&file_path, &data).await.is_err() {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": "Failed to save file"})),
-        )
-            .into_response();
+        return Err(ApiError::internal("Failed to save file"));
     }
 
     // Insert DB record
@@ -102,27 +85,23 @@ pub async fn upload(
     if result.is_err() {
         // Clean up file on DB error
         let _ = tokio::fs::remove_file(&file_path).await;
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": "Failed to save attachment record"})),
-        )
-            .into_response();
+        return Err(ApiError::internal("Failed to save attachment record"));
     }
 
-    Json(serde_json::json!({
+    Ok(Json(serde_json::json!({
         "id": id,
         "filename": original_filename,
         "contentType": content_type,
         "size": size,
     }))
-    .into_response()
+    .into_response())
 }
 
 /// GET /api/files/:id/:filename
 pub async fn serve_file(
     State(state): State<Arc<AppState>>,
     Path((id, _filename)): Path<(String, String)>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     // Look up attachment
     let attachment = sqlx::query_as::<_, Attachment>(
         "SELECT * FROM attachments WHERE id = ?",
@@ -135,34 +114,14 @@ pub async fn serve_file(
 
     let attachment = match attachment {
         Some(a) => a,
-        None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({"error": "File not found"})),
-            )
-                .into_response()
-        }
+        None => return Err(ApiError::not_found("File not found")),
     };
 
-    // Determine stored filename
-    let ext = attachment
-        .filename
-        .rsplit('.')
-        .next()
-        .filter(|e| e.len() <= 10 && e.chars().all(|c| c.is_alphanumeric()))
-        .unwrap_or("bin");
-    let stored_filename = format!("{}.{}", id, ext);
-    let file_path = std::path::Path::new(&state.config.upload_dir).join(&stored_filename);
+    let file_path = stored_file_path(&state.config.upload_dir, &id, &attachment.filename);
 
     let file = match tokio::fs::File::open(&file_path).await {
         Ok(f) => f,
-        Err(_) => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({"error": "File not found on disk"})),
-            )
-                .into_response()
-        }
+        Err(_) => return Err(ApiError::not_found("File not found on disk")),
     };
 
     let stream = ReaderStream::new(file);
@@ -177,7 +136,7 @@ pub async fn serve_file(
         format!("attachment; filename=\"{}\"", attachment.filename)
     };
 
-    (
+    Ok((
         [
             (header::CONTENT_TYPE, attachment.content_type),
             (header::CONTENT_DISPOSITION, disposition),
@@ -188,5 +147,5 @@ pub async fn serve_file(
         ],
         body,
     )
-        .into_response()
+        .into_response())
 }
@@ -1,12 +1,12 @@
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::{AuthUser, LinkPreview};
 use crate::AppState;
 
@@ -20,16 +20,10 @@ pub async fn link_preview(
     State(state): State<Arc<AppState>>,
     _user: AuthUser,
     Query(query): Query<LinkPreviewQuery>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let url = match query.url.as_deref() {
         Some(u) if !u.is_empty() => u.to_string(),
-        _ => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": "Missing url parameter"})),
-            )
-                .into_response()
-        }
+        _ => return Err(ApiError::bad_request("Missing url parameter")),
     };
 
     // Check cache (24h TTL)
@@ -46,14 +40,14 @@ pub async fn link_preview(
         let now = chrono::Utc::now();
         if let Ok(fetched) = chrono::DateTime::parse_from_rfc3339(&preview.fetched_at) {
             if now.signed_duration_since(fetched).num_hours() < 24 {
-                return Json(serde_json::json!({
+                return Ok(Json(serde_json::json!({
                     "url": preview.url,
                     "title": preview.title,
                     "description": preview.description,
                     "image": preview.image,
                     "domain": preview.domain,
                 }))
-                .into_response();
+                .into_response());
             }
         }
     }
@@ -79,14 +73,14 @@ pub async fn link_preview(
         let response = match client.get(&url).send().await {
             Ok(r) => r,
             Err(_) => {
-                return Json(serde_json::json!({
+                return Ok(Json(serde_json::json!({
                     "url": url,
                     "title": serde_json::Value::Null,
                     "description": serde_json::Value::Null,
                     "image": serde_json::Value::Null,
                     "domain": domain,
                 }))
-                .into_response()
+                .into_response())
             }
         };
 
@@ -114,7 +108,7 @@ pub async fn link_preview(
 
     // Only cache if we got at least some data (avoid caching empty results)
     if title.is_some() || description.is_some() || image.is_some() {
-        let _ = sqlx::query(
+        sqlx::query(
             r#"INSERT OR REPLACE INTO link_previews (url, title, description, image, domain, fetched_at)
                VALUES (?, ?, ?, ?, ?, ?)"#,
         )
@@ -125,17 +119,17 @@ pub async fn link_preview(
         .bind(&domain)
         .bind(&now)
         .execute(&state.db)
-        .await;
+        .await?;
     }
 
-    Json(serde_json::json!({
+    Ok(Json(serde_json::json!({
         "url": url,
         "title": title,
         "description": description,
         "image": image,
         "domain": domain,
     }))
-    .into_response()
+    .into_response())
 }
 
 fn extract_og_tag(html: &str, property: &str) -> Option<String> {
@@ -7,6 +7,7 @@ use axum::{
 use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::AuthUser;
 use crate::ws::events::ServerEvent;
 use crate::AppState;
@@ -25,12 +26,31 @@ pub struct AddImagesRequest {
     pub image_names: Vec<String>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageOrderItem {
+    pub id: String,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorderImagesRequest {
+    pub items: Vec<ImageOrderItem>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddCollaboratorRequest {
+    pub user_id: String,
+}
+
 /// Helper: check ownership, returns Ok(()) or an error response
 async fn check_ownership(
     state: &AppState,
     set_id: &str,
     user_id: &str,
-) -> Result<(), axum::response::Response> {
+) -> Result<(), ApiError> {
     let owner_id = sqlx::query_scalar::<_, String>(
         "SELECT creator_id FROM gallery_sets WHERE id = ?",
     )
@@ -42,43 +62,74 @@ async fn check_ownership(
 
     match owner_id.as_deref() {
         Some(id) if id == user_id => Ok(()),
-        Some(_) => Err((
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({"error": "Not the owner of this set"})),
-        )
-            .into_response()),
-        None => Err(StatusCode::NOT_FOUND.into_response()),
+        Some(_) => Err(ApiError::forbidden("Not the owner of this set")),
+        None => Err(ApiError::not_found("Gallery set not found")),
     }
 }
 
-/// Notify all subscribers of a gallery set that it was updated
-async fn notify_subscribers(state: &AppState, set_id: &str) {
-    let subscriber_ids: Vec<String> = sqlx::query_scalar(
-        "SELECT user_id FROM gallery_subscriptions WHERE set_id = ?",
+/// Helper: the creator or one of the set's collaborators may edit its contents
+async fn check_editor(
+    state: &AppState,
+    set_id: &str,
+    user_id: &str,
+) -> Result<(), ApiError> {
+    let can_edit = sqlx::query_scalar::<_, bool>(
+        r#"SELECT gs.creator_id = ? OR EXISTS(
+               SELECT 1 FROM gallery_collaborators gc WHERE gc.set_id = gs.id AND gc.user_id = ?
+           )
+           FROM gallery_sets gs WHERE gs.id = ?"#,
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .bind(set_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    match can_edit {
+        Some(true) => Ok(()),
+        Some(false) => Err(ApiError::forbidden("Not an editor of this set")),
+        None => Err(ApiError::not_found("Gallery set not found")),
+    }
+}
+
+/// Subscribers and editors of a gallery set
+async fn set_audience(state: &AppState, set_id: &str) -> Vec<String> {
+    sqlx::query_scalar(
+        r#"SELECT user_id FROM gallery_subscriptions WHERE set_id = ?
+           UNION SELECT user_id FROM gallery_collaborators WHERE set_id = ?
+           UNION SELECT creator_id FROM gallery_sets WHERE id = ?"#,
     )
     .bind(set_id)
+    .bind(set_id)
+    .bind(set_id)
     .fetch_all(&state.db)
     .await
-    .unwrap_or_default();
+    .unwrap_or_default()
+}
 
+async fn send_set_updated(state: &AppState, set_id: &str, user_ids: &[String]) {
     let event = ServerEvent::GallerySetUpdated {
         set_id: set_id.to_string(),
     };
-    for uid in &subscriber_ids {
+    for uid in user_ids {
         state.gateway.send_to_user(uid, &event).await;
     }
 }
 
-/// PATCH /api/gallery/:setId — owner only
+/// Notify all subscribers and editors of a gallery set that it was updated
+async fn notify_subscribers(state: &AppState, set_id: &str) {
+    let audience = set_audience(state, set_id).await;
+    send_set_updated(state, set_id, &audience).await;
+}
+
+/// PATCH /api/gallery/:setId — owner or collaborator
 pub async fn update_gallery_set(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path(set_id): Path<String>,
     Json(body): Json<UpdateGallerySetRequest>,
-) -> impl IntoResponse {
-    if let Err(resp) = check_ownership(&state, &set_id, &user.id).await {
-        return resp;
-    }
+) -> Result<impl IntoResponse, ApiError> {
+    check_editor(&state, &set_id, &user.id).await?;
 
     let now = chrono::Utc::now().to_rfc3339();
 
@@ -90,8 +141,7 @@ pub async fn update_gallery_set(
                 .bind(&now)
                 .bind(&set_id)
                 .execute(&state.db)
-                .await
-                .ok();
+                .await?;
         }
     }
 
@@ -101,39 +151,28 @@ pub async fn update_gallery_set(
             .bind(&now)
             .bind(&set_id)
             .execute(&state.db)
-            .await
-            .ok();
+            .await?;
     }
 
     notify_subscribers(&state, &set_id).await;
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
 
-/// POST /api/gallery/:setId/images — add images to an existing set (owner only)
+/// POST /api/gallery/:setId/images — append images to an existing set (owner or collaborator)
 pub async fn add_images(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path(set_id): Path<String>,
     Json(body): Json<AddImagesRequest>,
-) -> impl IntoResponse {
-    if let Err(resp) = check_ownership(&state, &set_id, &user.id).await {
-        return resp;
-    }
+) -> Result<impl IntoResponse, ApiError> {
+    check_editor(&state, &set_id, &user.id).await?;
 
     if body.image_attachment_ids.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "At least one image is required"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("At least one image is required"));
     }
 
     if body.image_attachment_ids.len() != body.image_names.len() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Attachment IDs and names must have the same length"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("Attachment IDs and names must have the same length"));
     }
 
     // Validate attachments belong to the user
@@ -149,11 +188,7 @@ pub async fn add_images(
             > 0;
 
         if !ok {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": format!("Invalid attachment: {}", att_id)})),
-            )
-                .into_response();
+            return Err(ApiError::bad_request(format!("Invalid attachment: {}", att_id)));
         }
     }
 
@@ -186,8 +221,7 @@ pub async fn add_images(
         .bind(max_pos + 1 + i as i64)
         .bind(&now)
         .execute(&state.db)
-        .await
-        .ok();
+        .await?;
     }
 
     // Update cover if not set
@@ -198,29 +232,25 @@ pub async fn add_images(
     .bind(body.image_attachment_ids.first())
     .bind(&set_id)
     .execute(&state.db)
-    .await
-    .ok();
+    .await?;
 
     notify_subscribers(&state, &set_id).await;
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
 
-/// DELETE /api/gallery/:setId/images/:imageId — remove a single image from a set (owner only)
+/// DELETE /api/gallery/:setId/images/:imageId — remove a single image from a set (owner or collaborator)
 pub async fn remove_image(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path((set_id, image_id)): Path<(String, String)>,
-) -> impl IntoResponse {
-    if let Err(resp) = check_ownership(&state, &set_id, &user.id).await {
-        return resp;
-    }
+) -> Result<impl IntoResponse, ApiError> {
+    check_editor(&state, &set_id, &user.id).await?;
 
     sqlx::query("DELETE FROM gallery_set_images WHERE id = ? AND set_id = ?")
         .bind(&image_id)
         .bind(&set_id)
         .execute(&state.db)
-        .await
-        .ok();
+        .await?;
 
     // Update cover to first remaining image (or NULL)
     let now = chrono::Utc::now().to_rfc3339();
@@ -238,11 +268,135 @@ pub async fn remove_image(
         .bind(&now)
         .bind(&set_id)
         .execute(&state.db)
-        .await
-        .ok();
+        .await?;
 
     notify_subscribers(&state, &set_id).await;
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// PUT /api/gallery/:setId/images/order — reorder the whole set, optionally renaming
+/// images along the way (owner or collaborator)
+pub async fn reorder_images(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(set_id): Path<String>,
+    Json(body): Json<ReorderImagesRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    check_editor(&state, &set_id, &user.id).await?;
+
+    let image_ids: Vec<String> = sqlx::query_scalar("SELECT id FROM gallery_set_images WHERE set_id = ?")
+        .bind(&set_id)
+        .fetch_all(&state.db)
+        .await?;
+
+    // The order must name every image in the set exactly once
+    let listed: std::collections::HashSet<&str> = body.items.iter().map(|i| i.id.as_str()).collect();
+    if listed.len() != body.items.len() {
+        return Err(ApiError::bad_request("Images may only be listed once"));
+    }
+    if listed.len() != image_ids.len() || !image_ids.iter().all(|id| listed.contains(id.as_str())) {
+        return Err(ApiError::bad_request("Order must list every image in the set"));
+    }
+    if body.items.iter().any(|i| i.name.as_deref().is_some_and(|n| n.trim().is_empty())) {
+        return Err(ApiError::bad_request("Image names cannot be empty"));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = state.db.begin().await?;
+    for (position, item) in body.items.iter().enumerate() {
+        sqlx::query(
+            "UPDATE gallery_set_images SET position = ?, name = COALESCE(?, name) WHERE id = ? AND set_id = ?",
+        )
+        .bind(position as i64)
+        .bind(item.name.as_deref().map(str::trim))
+        .bind(&item.id)
+        .bind(&set_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // The first image is the cover
+    sqlx::query(
+        r#"UPDATE gallery_sets SET updated_at = ?, cover_attachment_id = COALESCE(
+               (SELECT attachment_id FROM gallery_set_images WHERE set_id = ? ORDER BY position ASC LIMIT 1),
+               cover_attachment_id
+           ) WHERE id = ?"#,
+    )
+    .bind(&now)
+    .bind(&set_id)
+    .bind(&set_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    notify_subscribers(&state, &set_id).await;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// POST /api/gallery/:setId/collaborators — owner only
+pub async fn add_collaborator(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(set_id): Path<String>,
+    Json(body): Json<AddCollaboratorRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    check_ownership(&state, &set_id, &user.id).await?;
+
+    if body.user_id == user.id {
+        return Err(ApiError::bad_request("The creator already edits this set"));
+    }
+
+    let exists = sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "user" WHERE id = ?"#)
+        .bind(&body.user_id)
+        .fetch_one(&state.db)
+        .await?
+        > 0;
+    if !exists {
+        return Err(ApiError::not_found("User not found"));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        "INSERT OR IGNORE INTO gallery_collaborators (set_id, user_id, created_at) VALUES (?, ?, ?)",
+    )
+    .bind(&set_id)
+    .bind(&body.user_id)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    notify_subscribers(&state, &set_id).await;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// DELETE /api/gallery/:setId/collaborators/:userId — owner, or a collaborator leaving
+pub async fn remove_collaborator(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((set_id, collaborator_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    if collaborator_id != user.id {
+        check_ownership(&state, &set_id, &user.id).await?;
+    }
+
+    let removed = sqlx::query("DELETE FROM gallery_collaborators WHERE set_id = ? AND user_id = ?")
+        .bind(&set_id)
+        .bind(&collaborator_id)
+        .execute(&state.db)
+        .await?
+        .rows_affected()
+        > 0;
+
+    if removed {
+        // The departing editor hears about it too
+        let mut audience = set_audience(&state, &set_id).await;
+        if !audience.contains(&collaborator_id) {
+            audience.push(collaborator_id);
+        }
+        send_set_updated(&state, &set_id, &audience).await;
+    }
+
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
 
 /// DELETE /api/gallery/:setId — owner only, cascade deletes images + subscriptions
@@ -250,18 +404,15 @@ pub async fn delete_gallery_set(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path(set_id): Path<String>,
-) -> impl IntoResponse {
-    if let Err(resp) = check_ownership(&state, &set_id, &user.id).await {
-        return resp;
-    }
+) -> Result<impl IntoResponse, ApiError> {
+    check_ownership(&state, &set_id, &user.id).await?;
 
     sqlx::query("DELETE FROM gallery_sets WHERE id = ?")
         .bind(&set_id)
         .execute(&state.db)
-        .await
-        .ok();
+        .await?;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
 
 /// POST /api/gallery/:setId/subscribe
@@ -269,7 +420,7 @@ pub async fn subscribe(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path(set_id): Path<String>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let now = chrono::Utc::now().to_rfc3339();
     sqlx::query(
         "INSERT OR IGNORE INTO gallery_subscriptions (user_id, set_id, created_at) VALUES (?, ?, ?)",
@@ -278,10 +429,9 @@ pub async fn subscribe(
     .bind(&set_id)
     .bind(&now)
     .execute(&state.db)
-    .await
-    .ok();
+    .await?;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
 
 /// DELETE /api/gallery/:setId/subscribe
@@ -289,15 +439,14 @@ pub async fn unsubscribe(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path(set_id): Path<String>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     sqlx::query(
         "DELETE FROM gallery_subscriptions WHERE user_id = ? AND set_id = ?",
     )
     .bind(&user.id)
     .bind(&set_id)
     .execute(&state.db)
-    .await
-    .ok();
+    .await?;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
@@ -9,9 +9,12 @@ use axum::{
     Json,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::AuthUser;
+use crate::routes::pagination::{self, Cursor, PageQuery};
 use crate::AppState;
 
 // ── Row types ──────────────────────────────────────────────────────────────
@@ -45,6 +48,14 @@ pub struct GallerySetImageRow {
     pub created_at: String,
 }
 
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryCollaboratorRow {
+    pub user_id: String,
+    pub username: String,
+    pub created_at: String,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateGallerySetRequest {
@@ -83,45 +94,56 @@ const GALLERY_SET_SELECT: &str = r#"
 
 // ── Handlers ──────────────────────────────────────────────────────────────
 
-/// GET /api/gallery — browse all sets, optional ?q= search
+/// GET /api/gallery — browse all sets newest first, optional ?q= search,
+/// paged with ?cursor=&limit=
 pub async fn list_gallery_sets(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Query(params): Query<GalleryQuery>,
-) -> impl IntoResponse {
-    let sets = if let Some(q) = &params.q {
-        let pattern = format!("%{}%", q);
-        sqlx::query_as::<_, GallerySetRow>(
-            &format!("{} WHERE gs.name LIKE ? ORDER BY gs.created_at DESC", GALLERY_SET_SELECT),
-        )
-        .bind(&user.id)
-        .bind(&pattern)
-        .fetch_all(&state.db)
-        .await
-        .unwrap_or_default()
-    } else {
-        sqlx::query_as::<_, GallerySetRow>(
-            &format!("{} ORDER BY gs.created_at DESC", GALLERY_SET_SELECT),
-        )
-        .bind(&user.id)
-        .fetch_all(&state.db)
-        .await
-        .unwrap_or_default()
-    };
+    Query(page): Query<PageQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = page.limit();
+    let after = page.after()?;
+
+    let mut filters = Vec::new();
+    if params.q.is_some() {
+        filters.push("gs.name LIKE ?".to_string());
+    }
+    if after.is_some() {
+        filters.push(pagination::after_condition("gs.created_at", "gs.id", true));
+    }
+    let mut sql = GALLERY_SET_SELECT.to_string();
+    if !filters.is_empty() {
+        sql.push_str(&format!(" WHERE {}", filters.join(" AND ")));
+    }
+    sql.push_str(" ORDER BY gs.created_at DESC, gs.id DESC LIMIT ?");
 
-    Json(sets).into_response()
+    let mut sets = sqlx::query_as::<_, GallerySetRow>(&sql).bind(&user.id);
+    if let Some(q) = &params.q {
+        sets = sets.bind(format!("%{}%", q));
+    }
+    if let Some(after) = &after {
+        sets = sets.bind(&after.key).bind(&after.id);
+    }
+    let sets = sets.bind(limit + 1).fetch_all(&state.db).await?;
+
+    Ok(Json(pagination::page(sets, limit, |s| Cursor::new(&s.created_at, &s.id))))
 }
 
-/// GET /api/gallery/mine — sets created by caller
+/// GET /api/gallery/mine — sets created or co-edited by caller
 pub async fn list_my_sets(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
 ) -> impl IntoResponse {
     let sets = sqlx::query_as::<_, GallerySetRow>(
-        &format!("{} WHERE gs.creator_id = ? ORDER BY gs.created_at DESC", GALLERY_SET_SELECT),
+        &format!(
+            "{} WHERE gs.creator_id = ? OR EXISTS(SELECT 1 FROM gallery_collaborators gc WHERE gc.set_id = gs.id AND gc.user_id = ?) ORDER BY gs.created_at DESC",
+            GALLERY_SET_SELECT,
+        ),
     )
     .bind(&user.id)
     .bind(&user.id)
+    .bind(&user.id)
     .fetch_all(&state.db)
     .await
     .unwrap_or_default();
@@ -146,21 +168,27 @@ pub async fn list_subscribed(
     .await
     .unwrap_or_default();
 
-    // For each set, fetch images
+    // Images for all of them at once, grouped by set
+    let images = sqlx::query_as::<_, GallerySetImageRow>(
+        r#"SELECT gsi.id, gsi.set_id, gsi.attachment_id, a.filename, gsi.name, gsi.position, gsi.created_at
+           FROM gallery_set_images gsi
+           JOIN attachments a ON a.id = gsi.attachment_id
+           JOIN gallery_subscriptions sub ON sub.set_id = gsi.set_id AND sub.user_id = ?
+           ORDER BY gsi.set_id, gsi.position ASC"#,
+    )
+    .bind(&user.id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let mut images_by_set: HashMap<String, Vec<GallerySetImageRow>> = HashMap::new();
+    for image in images {
+        images_by_set.entry(image.set_id.clone()).or_default().push(image);
+    }
+
     let mut results = Vec::new();
     for set in sets {
-        let images = sqlx::query_as::<_, GallerySetImageRow>(
-            r#"SELECT gsi.id, gsi.set_id, gsi.attachment_id, a.filename, gsi.name, gsi.position, gsi.created_at
-               FROM gallery_set_images gsi
-               JOIN attachments a ON a.id = gsi.attachment_id
-               WHERE gsi.set_id = ?
-               ORDER BY gsi.position ASC"#,
-        )
-        .bind(&set.id)
-        .fetch_all(&state.db)
-        .await
-        .unwrap_or_default();
-
+        let images = images_by_set.remove(&set.id).unwrap_or_default();
         results.push(serde_json::json!({
             "id": set.id,
             "name": set.name,
@@ -186,30 +214,18 @@ pub async fn create_gallery_set(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Json(body): Json<CreateGallerySetRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let name = body.name.trim().to_string();
     if name.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Name is required"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("Name is required"));
     }
 
     if body.image_attachment_ids.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "At least one image is required"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("At least one image is required"));
     }
 
     if body.image_attachment_ids.len() != body.image_names.len() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Attachment IDs and names must have the same length"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("Attachment IDs and names must have the same length"));
     }
 
     // Validate all attachment IDs belong to the caller
@@ -225,11 +241,7 @@ pub async fn create_gallery_set(
             > 0;
 
         if !ok {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": format!("Invalid attachment: {}", att_id)})),
-            )
-                .into_response();
+            return Err(ApiError::bad_request(format!("Invalid attachment: {}", att_id)));
         }
     }
 
@@ -238,8 +250,9 @@ pub async fn create_gallery_set(
     let description = body.description.unwrap_or_default();
     let cover_id = body.image_attachment_ids.first().cloned();
 
-    // Create the set
-    let result = sqlx::query(
+    // The set and its images go in together or not at all
+    let mut tx = state.db.begin().await?;
+    sqlx::query(
         r#"INSERT INTO gallery_sets (id, name, description, creator_id, cover_attachment_id, created_at, updated_at)
            VALUES (?, ?, ?, ?, ?, ?, ?)"#,
     )
@@ -250,19 +263,9 @@ pub async fn create_gallery_set(
     .bind(&cover_id)
     .bind(&now)
     .bind(&now)
-    .execute(&state.db)
-    .await;
-
-    if let Err(e) = result {
-        tracing::error!("Failed to create gallery set: {:?}", e);
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": "Failed to create gallery set"})),
-        )
-            .into_response();
-    }
+    .execute(&mut *tx)
+    .await?;
 
-    // Insert images
     for (i, (att_id, img_name)) in body
         .image_attachment_ids
         .iter()
@@ -280,10 +283,10 @@ pub async fn create_gallery_set(
         .bind(img_name)
         .bind(i as i64)
         .bind(&now)
-        .execute(&state.db)
-        .await
-        .ok();
+        .execute(&mut *tx)
+        .await?;
     }
+    tx.commit().await?;
 
     // Return the created set
     let set = sqlx::query_as::<_, GallerySetRow>(
@@ -297,12 +300,12 @@ pub async fn create_gallery_set(
     .flatten();
 
     match set {
-        Some(s) => (StatusCode::CREATED, Json(s)).into_response(),
-        None => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        Some(s) => Ok((StatusCode::CREATED, Json(s)).into_response()),
+        None => Err(ApiError::internal("Failed to load gallery set")),
     }
 }
 
-/// GET /api/gallery/:setId — single set with images
+/// GET /api/gallery/:setId — single set with images and collaborators
 pub async fn get_gallery_set(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
@@ -335,6 +338,18 @@ pub async fn get_gallery_set(
     .await
     .unwrap_or_default();
 
+    let collaborators = sqlx::query_as::<_, GalleryCollaboratorRow>(
+        r#"SELECT gc.user_id, u.username, gc.created_at
+           FROM gallery_collaborators gc
+           JOIN "user" u ON u.id = gc.user_id
+           WHERE gc.set_id = ?
+           ORDER BY gc.created_at ASC"#,
+    )
+    .bind(&set_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
     Json(serde_json::json!({
         "id": set.id,
         "name": set.name,
@@ -349,6 +364,7 @@ pub async fn get_gallery_set(
         "createdAt": set.created_at,
         "updatedAt": set.updated_at,
         "images": images,
+        "collaborators": collaborators,
     }))
     .into_response()
 }
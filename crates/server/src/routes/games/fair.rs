@@ -0,0 +1,71 @@
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// Rolls fall in 0..ROLL_RANGE, i.e. hundredths of a percent.
+pub const ROLL_RANGE: i64 = 10_000;
+
+fn random_hex(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    rand::rngs::OsRng.fill_bytes(&mut buf);
+    buf.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn new_server_seed() -> String {
+    random_hex(32)
+}
+
+pub fn new_client_seed() -> String {
+    random_hex(8)
+}
+
+/// The commitment shown before a seed is used: hex sha256 of the seed.
+pub fn hash_seed(server_seed: &str) -> String {
+    format!("{:x}", Sha256::digest(server_seed.as_bytes()))
+}
+
+/// sha256 of `server_seed:client_seed:nonce`; the first four bytes as a
+/// big-endian integer, modulo `ROLL_RANGE`. Anyone holding the revealed
+/// server seed can recompute it.
+pub fn roll(server_seed: &str, client_seed: &str, nonce: i64) -> i64 {
+    let digest = Sha256::digest(format!("{}:{}:{}", server_seed, client_seed, nonce).as_bytes());
+    let n = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]);
+    n as i64 % ROLL_RANGE
+}
+
+/// One roll from a user's active seed pair.
+pub struct SeededRoll {
+    pub server_seed: String,
+    pub server_seed_hash: String,
+    pub client_seed: String,
+    pub nonce: i64,
+    pub roll: i64,
+}
+
+/// Roll with the user's seed pair and move its nonce on, creating the pair on
+/// first use.
+pub async fn next_roll(conn: &mut sqlx::SqliteConnection, user_id: &str) -> Result<SeededRoll, sqlx::Error> {
+    sqlx::query(
+        r#"INSERT OR IGNORE INTO "game_seeds" (user_id, server_seed, client_seed, nonce) VALUES (?, ?, ?, 0)"#,
+    )
+    .bind(user_id)
+    .bind(new_server_seed())
+    .bind(new_client_seed())
+    .execute(&mut *conn)
+    .await?;
+
+    let (server_seed, client_seed, nonce) = sqlx::query_as::<_, (String, String, i64)>(
+        r#"UPDATE "game_seeds" SET nonce = nonce + 1 WHERE user_id = ?
+           RETURNING server_seed, client_seed, nonce - 1"#,
+    )
+    .bind(user_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(SeededRoll {
+        server_seed_hash: hash_seed(&server_seed),
+        roll: roll(&server_seed, &client_seed, nonce),
+        server_seed,
+        client_seed,
+        nonce,
+    })
+}
@@ -0,0 +1,562 @@
+mod fair;
+
+pub use fair::{hash_seed, roll, ROLL_RANGE};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, Game, GameSettings};
+use crate::routes::economy::{adjust_balance, economy_paused, today_start};
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+const REASON_STAKE: &str = "game_stake";
+const REASON_PAYOUT: &str = "game_payout";
+const REASON_REFUND: &str = "game_refund";
+
+/// Dice win chance bounds, in percent.
+const MIN_DICE_TARGET: i64 = 1;
+const MAX_DICE_TARGET: i64 = 95;
+/// The owner can't set the house edge above 10%.
+const MAX_HOUSE_EDGE_BPS: i64 = 1000;
+
+/// The server a channel belongs to, if the user is a member of it.
+async fn channel_server(state: &AppState, channel_id: &str, user_id: &str) -> Result<String, ApiError> {
+    let server_id = sqlx::query_scalar::<_, String>("SELECT server_id FROM channels WHERE id = ?")
+        .bind(channel_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten()
+        .ok_or_else(|| ApiError::not_found("Channel not found"))?;
+    require_member(state, &server_id, user_id).await?;
+    Ok(server_id)
+}
+
+async fn require_member(state: &AppState, server_id: &str, user_id: &str) -> Result<(), ApiError> {
+    let is_member = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
+    )
+    .bind(user_id)
+    .bind(server_id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0);
+    if is_member == 0 {
+        return Err(ApiError::forbidden("Not a member of this server"));
+    }
+    Ok(())
+}
+
+/// The server's game settings, or the defaults if the owner never changed them.
+async fn settings_for(state: &AppState, server_id: &str) -> GameSettings {
+    sqlx::query_as::<_, GameSettings>(r#"SELECT * FROM "game_settings" WHERE server_id = ?"#)
+        .bind(server_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| GameSettings {
+            server_id: server_id.to_string(),
+            enabled: 1,
+            house_edge_bps: 200,
+            daily_loss_limit: 1000,
+        })
+}
+
+/// Check a wager against the server's settings and what the user has already
+/// lost in its games today (stakes minus payouts and refunds).
+async fn check_wager(state: &AppState, settings: &GameSettings, user_id: &str, wager: i64) -> Result<(), ApiError> {
+    if settings.enabled == 0 {
+        return Err(ApiError::forbidden("Games are disabled on this server"));
+    }
+    if economy_paused(state, Some(&settings.server_id)).await {
+        return Err(ApiError::forbidden("The economy is paused on this server"));
+    }
+    if wager <= 0 {
+        return Err(ApiError::bad_request("Wager must be positive"));
+    }
+    if settings.daily_loss_limit > 0 {
+        let net = sqlx::query_scalar::<_, i64>(
+            r#"SELECT COALESCE(SUM(amount), 0) FROM "coin_rewards_log"
+               WHERE user_id = ? AND server_id = ? AND reason IN (?, ?, ?) AND created_at >= ?"#,
+        )
+        .bind(user_id)
+        .bind(&settings.server_id)
+        .bind(REASON_STAKE)
+        .bind(REASON_PAYOUT)
+        .bind(REASON_REFUND)
+        .bind(today_start())
+        .fetch_one(&state.db)
+        .await
+        .unwrap_or(0);
+        if (-net).max(0) + wager > settings.daily_loss_limit {
+            return Err(ApiError::forbidden("Daily loss limit reached"));
+        }
+    }
+    Ok(())
+}
+
+/// Take a stake from the user's wallet inside a game transaction.
+async fn take_stake(
+    conn: &mut sqlx::SqliteConnection,
+    user_id: &str,
+    wager: i64,
+    server_id: &str,
+) -> Result<(), ApiError> {
+    match adjust_balance(conn, user_id, -wager, REASON_STAKE, Some(server_id)).await {
+        Ok(_) => Ok(()),
+        Err(sqlx::Error::RowNotFound) => Err(ApiError::bad_request("Insufficient balance")),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn load_game(state: &AppState, game_id: &str) -> Option<Game> {
+    sqlx::query_as::<_, Game>(r#"SELECT * FROM "games" WHERE id = ?"#)
+        .bind(game_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Hide the server seed while it's still the roller's active seed; it can be
+/// shown once they've rotated away from it.
+async fn redact(state: &AppState, mut game: Game) -> Game {
+    if let (Some(seed_user_id), Some(server_seed)) = (&game.seed_user_id, &game.server_seed) {
+        let active = sqlx::query_scalar::<_, String>(r#"SELECT server_seed FROM "game_seeds" WHERE user_id = ?"#)
+            .bind(seed_user_id)
+            .fetch_optional(&state.db)
+            .await
+            .ok()
+            .flatten();
+        if active.as_ref() == Some(server_seed) {
+            game.server_seed = None;
+        }
+    }
+    game
+}
+
+/// Post the game's current state to the channel it was started in.
+async fn announce(state: &AppState, game: &Game) {
+    state
+        .gateway
+        .broadcast_channel(&game.channel_id, &ServerEvent::GameUpdate { game: game.clone() }, None)
+        .await;
+}
+
+async fn finish(state: &AppState, game_id: &str) -> Result<Game, ApiError> {
+    let game = load_game(state, game_id)
+        .await
+        .ok_or_else(|| ApiError::internal("Game failed"))?;
+    let game = redact(state, game).await;
+    announce(state, &game).await;
+    Ok(game)
+}
+
+/// GET /api/games/seed
+/// The caller's active seed pair: the server seed's hash, client seed and nonce.
+pub async fn get_seed(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    sqlx::query(
+        r#"INSERT OR IGNORE INTO "game_seeds" (user_id, server_seed, client_seed, nonce) VALUES (?, ?, ?, 0)"#,
+    )
+    .bind(&user.id)
+    .bind(fair::new_server_seed())
+    .bind(fair::new_client_seed())
+    .execute(&state.db)
+    .await?;
+
+    let seed = sqlx::query_as::<_, (String, String, i64)>(
+        r#"SELECT server_seed, client_seed, nonce FROM "game_seeds" WHERE user_id = ?"#,
+    )
+    .bind(&user.id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    match seed {
+        Some((server_seed, client_seed, nonce)) => Ok(Json(serde_json::json!({
+            "serverSeedHash": hash_seed(&server_seed),
+            "clientSeed": client_seed,
+            "nonce": nonce,
+        }))
+        .into_response()),
+        None => Err(ApiError::internal("Failed to load seed")),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotateSeedRequest {
+    pub client_seed: Option<String>,
+}
+
+/// POST /api/games/seed/rotate
+/// Reveals the current server seed and starts a new pair at nonce 0.
+pub async fn rotate_seed(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<RotateSeedRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let client_seed = match body.client_seed.map(|s| s.trim().to_string()) {
+        Some(s) if s.is_empty() || s.len() > 64 => {
+            return Err(ApiError::bad_request("Client seed must be 1-64 characters"));
+        }
+        Some(s) => s,
+        None => fair::new_client_seed(),
+    };
+
+    let previous = sqlx::query_as::<_, (String, String, i64)>(
+        r#"SELECT server_seed, client_seed, nonce FROM "game_seeds" WHERE user_id = ?"#,
+    )
+    .bind(&user.id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    let server_seed = fair::new_server_seed();
+    sqlx::query(
+        r#"INSERT INTO "game_seeds" (user_id, server_seed, client_seed, nonce) VALUES (?, ?, ?, 0)
+           ON CONFLICT(user_id) DO UPDATE SET server_seed = excluded.server_seed, client_seed = excluded.client_seed, nonce = 0"#,
+    )
+    .bind(&user.id)
+    .bind(&server_seed)
+    .bind(&client_seed)
+    .execute(&state.db)
+    .await?;
+
+    let previous = previous.map(|(seed, client, nonce)| {
+        serde_json::json!({
+            "serverSeed": seed,
+            "serverSeedHash": hash_seed(&seed),
+            "clientSeed": client,
+            "nonce": nonce,
+        })
+    });
+
+    Ok(Json(serde_json::json!({
+        "previous": previous,
+        "serverSeedHash": hash_seed(&server_seed),
+        "clientSeed": client_seed,
+        "nonce": 0,
+    }))
+    .into_response())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiceRequest {
+    pub channel_id: String,
+    pub wager: i64,
+    /// Win chance in percent; a roll below `target * 100` wins.
+    pub target: i64,
+}
+
+/// POST /api/games/dice
+/// Roll against the house. A win pays the wager times 100/target, less the
+/// server's house edge.
+pub async fn play_dice(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<DiceRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let game = dice(&state, &user.id, body).await?;
+    Ok(Json(game))
+}
+
+async fn dice(state: &AppState, user_id: &str, body: DiceRequest) -> Result<Game, ApiError> {
+    if !(MIN_DICE_TARGET..=MAX_DICE_TARGET).contains(&body.target) {
+        return Err(ApiError::bad_request("Target must be between 1 and 95"));
+    }
+    let server_id = channel_server(state, &body.channel_id, user_id).await?;
+    let settings = settings_for(state, &server_id).await;
+    check_wager(state, &settings, user_id, body.wager).await?;
+
+    let mut tx = state.db.begin().await?;
+    take_stake(&mut tx, user_id, body.wager, &server_id).await?;
+    let rolled = fair::next_roll(&mut tx, user_id).await?;
+
+    let won = rolled.roll < body.target * 100;
+    let payout = if won {
+        body.wager * (ROLL_RANGE - settings.house_edge_bps) / (body.target * 100)
+    } else {
+        0
+    };
+    if payout > 0 {
+        adjust_balance(&mut tx, user_id, payout, REASON_PAYOUT, Some(&server_id)).await?;
+    }
+
+    let game_id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"INSERT INTO "games" (id, kind, server_id, channel_id, creator_id, wager, target, status,
+               seed_user_id, server_seed, server_seed_hash, client_seed, nonce, roll, winner_id, payout, created_at, resolved_at)
+           VALUES (?, 'dice', ?, ?, ?, ?, ?, 'resolved', ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(&game_id)
+    .bind(&server_id)
+    .bind(&body.channel_id)
+    .bind(user_id)
+    .bind(body.wager)
+    .bind(body.target)
+    .bind(user_id)
+    .bind(&rolled.server_seed)
+    .bind(&rolled.server_seed_hash)
+    .bind(&rolled.client_seed)
+    .bind(rolled.nonce)
+    .bind(rolled.roll)
+    .bind(won.then_some(user_id))
+    .bind(payout)
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    finish(state, &game_id).await
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinflipRequest {
+    pub channel_id: String,
+    pub wager: i64,
+}
+
+/// POST /api/games/coinflip
+/// Open a coinflip in a channel. The stake is held until someone accepts it
+/// or the creator cancels.
+pub async fn create_coinflip(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<CoinflipRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let game = open_coinflip(&state, &user.id, body).await?;
+    Ok((StatusCode::CREATED, Json(game)))
+}
+
+async fn open_coinflip(state: &AppState, user_id: &str, body: CoinflipRequest) -> Result<Game, ApiError> {
+    let server_id = channel_server(state, &body.channel_id, user_id).await?;
+    let settings = settings_for(state, &server_id).await;
+    check_wager(state, &settings, user_id, body.wager).await?;
+
+    let game_id = uuid::Uuid::new_v4().to_string();
+    let mut tx = state.db.begin().await?;
+    take_stake(&mut tx, user_id, body.wager, &server_id).await?;
+    sqlx::query(
+        r#"INSERT INTO "games" (id, kind, server_id, channel_id, creator_id, wager, status, payout, created_at)
+           VALUES (?, 'coinflip', ?, ?, ?, ?, 'open', 0, ?)"#,
+    )
+    .bind(&game_id)
+    .bind(&server_id)
+    .bind(&body.channel_id)
+    .bind(user_id)
+    .bind(body.wager)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    finish(state, &game_id).await
+}
+
+/// POST /api/games/coinflip/:gameId/accept
+/// Match the stake and flip, using the acceptor's seed pair. The creator wins
+/// on a roll below 5000; the winner takes both stakes less the house edge.
+pub async fn accept_coinflip(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(game_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let game = flip(&state, &user.id, &game_id).await?;
+    Ok(Json(game))
+}
+
+async fn flip(state: &AppState, user_id: &str, game_id: &str) -> Result<Game, ApiError> {
+    let game = load_game(state, game_id)
+        .await
+        .filter(|g| g.kind == "coinflip")
+        .ok_or_else(|| ApiError::not_found("Game not found"))?;
+    if game.status != "open" {
+        return Err(ApiError::conflict("Game is no longer open"));
+    }
+    if game.creator_id == user_id {
+        return Err(ApiError::bad_request("Cannot accept your own coinflip"));
+    }
+    if crate::routes::users::is_blocked(state, &game.creator_id, user_id).await {
+        return Err(ApiError::forbidden("You can't play against this user"));
+    }
+    require_member(state, &game.server_id, user_id).await?;
+    let settings = settings_for(state, &game.server_id).await;
+    check_wager(state, &settings, user_id, game.wager).await?;
+
+    let mut tx = state.db.begin().await?;
+    // Claim the game first so two acceptors can't both match it
+    let claimed = sqlx::query(
+        r#"UPDATE "games" SET status = 'resolved', opponent_id = ? WHERE id = ? AND status = 'open'"#,
+    )
+    .bind(user_id)
+    .bind(game_id)
+    .execute(&mut *tx)
+    .await?;
+    if claimed.rows_affected() == 0 {
+        return Err(ApiError::conflict("Game is no longer open"));
+    }
+
+    take_stake(&mut tx, user_id, game.wager, &game.server_id).await?;
+    let rolled = fair::next_roll(&mut tx, user_id).await?;
+
+    let winner_id = if rolled.roll < ROLL_RANGE / 2 { game.creator_id.as_str() } else { user_id };
+    let pot = game.wager * 2;
+    let payout = pot - pot * settings.house_edge_bps / ROLL_RANGE;
+    adjust_balance(&mut tx, winner_id, payout, REASON_PAYOUT, Some(&game.server_id))
+        .await?;
+
+    sqlx::query(
+        r#"UPDATE "games" SET seed_user_id = ?, server_seed = ?, server_seed_hash = ?, client_seed = ?,
+               nonce = ?, roll = ?, winner_id = ?, payout = ?, resolved_at = ?
+           WHERE id = ?"#,
+    )
+    .bind(user_id)
+    .bind(&rolled.server_seed)
+    .bind(&rolled.server_seed_hash)
+    .bind(&rolled.client_seed)
+    .bind(rolled.nonce)
+    .bind(rolled.roll)
+    .bind(winner_id)
+    .bind(payout)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(game_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    finish(state, game_id).await
+}
+
+/// POST /api/games/coinflip/:gameId/cancel
+/// Creator only; refunds the held stake.
+pub async fn cancel_coinflip(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(game_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let game = cancel(&state, &user.id, &game_id).await?;
+    Ok(Json(game))
+}
+
+async fn cancel(state: &AppState, user_id: &str, game_id: &str) -> Result<Game, ApiError> {
+    let game = load_game(state, game_id)
+        .await
+        .filter(|g| g.kind == "coinflip")
+        .ok_or_else(|| ApiError::not_found("Game not found"))?;
+    if game.creator_id != user_id {
+        return Err(ApiError::forbidden("Only the creator can cancel"));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let cancelled = sqlx::query(
+        r#"UPDATE "games" SET status = 'cancelled', resolved_at = ? WHERE id = ? AND status = 'open'"#,
+    )
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(game_id)
+    .execute(&mut *tx)
+    .await?;
+    if cancelled.rows_affected() == 0 {
+        return Err(ApiError::conflict("Game is no longer open"));
+    }
+    adjust_balance(&mut tx, user_id, game.wager, REASON_REFUND, Some(&game.server_id))
+        .await?;
+    tx.commit().await?;
+
+    finish(state, game_id).await
+}
+
+/// GET /api/games/:gameId
+pub async fn get_game(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(game_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Some(game) = load_game(&state, &game_id).await else {
+        return Err(ApiError::not_found("Game not found"));
+    };
+    require_member(&state, &game.server_id, &user.id).await?;
+    Ok(Json(redact(&state, game).await))
+}
+
+/// GET /api/games/settings/:serverId
+pub async fn get_settings(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_member(&state, &server_id, &user.id).await?;
+    Ok(Json(settings_for(&state, &server_id).await))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateGameSettingsRequest {
+    pub enabled: Option<bool>,
+    pub house_edge_bps: Option<i64>,
+    pub daily_loss_limit: Option<i64>,
+}
+
+/// PATCH /api/games/settings/:serverId
+/// Server owner only.
+pub async fn update_settings(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<String>,
+    Json(body): Json<UpdateGameSettingsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let owner_id = sqlx::query_scalar::<_, String>("SELECT owner_id FROM servers WHERE id = ?")
+        .bind(&server_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten();
+    match owner_id {
+        None => return Err(ApiError::not_found("Server not found")),
+        Some(owner_id) if owner_id != user.id => {
+            return Err(ApiError::forbidden("Only the server owner can change game settings"));
+        }
+        Some(_) => {}
+    }
+
+    if body.house_edge_bps.is_some_and(|e| !(0..=MAX_HOUSE_EDGE_BPS).contains(&e)) {
+        return Err(ApiError::bad_request("House edge must be between 0 and 1000 basis points"));
+    }
+    if body.daily_loss_limit.is_some_and(|l| l < 0) {
+        return Err(ApiError::bad_request("Daily loss limit cannot be negative"));
+    }
+
+    let current = settings_for(&state, &server_id).await;
+    let updated = sqlx::query_as::<_, GameSettings>(
+        r#"INSERT INTO "game_settings" (server_id, enabled, house_edge_bps, daily_loss_limit) VALUES (?, ?, ?, ?)
+           ON CONFLICT(server_id) DO UPDATE SET enabled = excluded.enabled,
+               house_edge_bps = excluded.house_edge_bps, daily_loss_limit = excluded.daily_loss_limit
+           RETURNING *"#,
+    )
+    .bind(&server_id)
+    .bind(body.enabled.map(|e| e as i64).unwrap_or(current.enabled))
+    .bind(body.house_edge_bps.unwrap_or(current.house_edge_bps))
+    .bind(body.daily_loss_limit.unwrap_or(current.daily_loss_limit))
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(Json(updated))
+}
@@ -0,0 +1,200 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::AppState;
+
+const CACHE_TTL_SECS: u64 = 10 * 60; // 10 minutes
+const CACHE_MAX_ENTRIES: usize = 500;
+const RATE_LIMIT_WINDOW_SECS: u64 = 60;
+const RATE_LIMIT_MAX_REQUESTS: u32 = 30;
+const DEFAULT_LIMIT: u32 = 20;
+const MAX_LIMIT: u32 = 50;
+
+#[derive(Deserialize)]
+pub struct GifSearchQuery {
+    pub q: Option<String>,
+    pub limit: Option<u32>,
+}
+
+/// Provider-independent search result.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GifResult {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    pub preview_url: String,
+    pub width: i64,
+    pub height: i64,
+}
+
+fn parse_tenor(body: &serde_json::Value) -> Vec<GifResult> {
+    body["results"]
+        .as_array()
+        .map(|results| {
+            results
+                .iter()
+                .filter_map(|r| {
+                    let gif = &r["media_formats"]["gif"];
+                    let url = gif["url"].as_str()?.to_string();
+                    Some(GifResult {
+                        id: r["id"].as_str()?.to_string(),
+                        title: r["content_description"].as_str().unwrap_or("").to_string(),
+                        preview_url: r["media_formats"]["tinygif"]["url"]
+                            .as_str()
+                            .unwrap_or(&url)
+                            .to_string(),
+                        width: gif["dims"][0].as_i64().unwrap_or(0),
+                        height: gif["dims"][1].as_i64().unwrap_or(0),
+                        url,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_giphy(body: &serde_json::Value) -> Vec<GifResult> {
+    // Giphy reports dimensions as strings
+    let dim = |v: &serde_json::Value| {
+        v.as_str().and_then(|s| s.parse().ok()).or_else(|| v.as_i64()).unwrap_or(0)
+    };
+    body["data"]
+        .as_array()
+        .map(|results| {
+            results
+                .iter()
+                .filter_map(|r| {
+                    let original = &r["images"]["original"];
+                    let url = original["url"].as_str()?.to_string();
+                    Some(GifResult {
+                        id: r["id"].as_str()?.to_string(),
+                        title: r["title"].as_str().unwrap_or("").to_string(),
+                        preview_url: r["images"]["fixed_width_small"]["url"]
+                            .as_str()
+                            .unwrap_or(&url)
+                            .to_string(),
+                        width: dim(&original["width"]),
+                        height: dim(&original["height"]),
+                        url,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Count a request against the user's window, rejecting it once they're over the limit.
+async fn check_rate_limit(state: &AppState, user_id: &str) -> Result<(), ApiError> {
+    let mut limits = state.gif_rate_limits.write().await;
+    let now = Instant::now();
+    let entry = limits.entry(user_id.to_string()).or_insert((now, 0));
+    let elapsed = now.duration_since(entry.0).as_secs();
+    if elapsed >= RATE_LIMIT_WINDOW_SECS {
+        *entry = (now, 0);
+    }
+    entry.1 += 1;
+    if entry.1 > RATE_LIMIT_MAX_REQUESTS {
+        return Err(ApiError::rate_limited(
+            "Too many GIF searches, slow down",
+            RATE_LIMIT_WINDOW_SECS.saturating_sub(elapsed) as i64,
+        ));
+    }
+    Ok(())
+}
+
+async fn fetch_gifs(state: &AppState, q: &str, limit: u32) -> Result<Vec<GifResult>, String> {
+    let key = &state.config.gif_api_key;
+    let limit = limit.to_string();
+    let client = reqwest::Client::new();
+    let (req, is_giphy) = match state.config.gif_provider.as_str() {
+        "giphy" => (
+            client.get("https://api.giphy.com/v1/gifs/search").query(&[
+                ("api_key", key.as_str()),
+                ("q", q),
+                ("limit", &limit),
+                ("rating", "pg-13"),
+            ]),
+            true,
+        ),
+        _ => (
+            client.get("https://tenor.googleapis.com/v2/search").query(&[
+                ("key", key.as_str()),
+                ("q", q),
+                ("limit", &limit),
+                ("media_filter", "gif,tinygif"),
+                ("contentfilter", "medium"),
+            ]),
+            false,
+        ),
+    };
+
+    let resp = req
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("provider returned {}", resp.status()));
+    }
+    let body: serde_json::Value = resp.json().await.map_err(|e| format!("bad response: {}", e))?;
+
+    Ok(if is_giphy { parse_giphy(&body) } else { parse_tenor(&body) })
+}
+
+/// GET /api/gifs/search?q=...&limit=...
+pub async fn search(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(query): Query<GifSearchQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let q = match query.q.as_deref() {
+        Some(q) if !q.trim().is_empty() => q.trim().to_lowercase(),
+        _ => return Ok(Json(serde_json::json!({"results": []})).into_response()),
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    check_rate_limit(&state, &user.id).await?;
+
+    if state.config.gif_api_key.is_empty() {
+        return Err(ApiError::unavailable("GIF search is not configured"));
+    }
+
+    let cache_key = format!("{}:{}:{}", state.config.gif_provider, limit, q);
+    {
+        let cache = state.gif_search_cache.read().await;
+        if let Some((results, fetched_at)) = cache.get(&cache_key) {
+            if fetched_at.elapsed().as_secs() < CACHE_TTL_SECS {
+                return Ok(Json(serde_json::json!({"results": results})).into_response());
+            }
+        }
+    }
+
+    let results = match fetch_gifs(&state, &q, limit).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("GIF search failed for q=\"{}\": {}", q, e);
+            return Err(ApiError::upstream("GIF search failed"));
+        }
+    };
+
+    {
+        let mut cache = state.gif_search_cache.write().await;
+        if cache.len() >= CACHE_MAX_ENTRIES {
+            cache.retain(|_, (_, fetched_at)| fetched_at.elapsed().as_secs() < CACHE_TTL_SECS);
+        }
+        if cache.len() < CACHE_MAX_ENTRIES {
+            cache.insert(cache_key, (results.clone(), Instant::now()));
+        }
+    }
+
+    Ok(Json(serde_json::json!({"results": results})).into_response())
+}
@@ -0,0 +1,161 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::AppState;
+
+/// How long any one dependency gets before it counts as down.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// `yt-dlp --version` starts a Python interpreter, so its result is reused
+/// for this long rather than spawning it on every probe.
+const YT_DLP_RECHECK: Duration = Duration::from_secs(60);
+
+static YT_DLP: Mutex<Option<(Instant, Check)>> = Mutex::new(None);
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    /// Optional dependencies are down; the server works without them.
+    Degraded,
+    Error,
+    /// Not configured, so not checked.
+    Disabled,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct Check {
+    status: Status,
+    /// Whether the server is unusable without it.
+    required: bool,
+    latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+impl Check {
+    fn from_result(required: bool, start: Instant, result: Result<Option<String>, String>) -> Self {
+        let (status, detail) = match result {
+            Ok(detail) => (Status::Ok, detail),
+            Err(e) => (Status::Error, Some(e)),
+        };
+        Self { status, required, latency_ms: start.elapsed().as_millis() as u64, detail }
+    }
+
+    fn disabled(detail: &str) -> Self {
+        Self { status: Status::Disabled, required: false, latency_ms: 0, detail: Some(detail.into()) }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Checks {
+    database: Check,
+    uploads: Check,
+    livekit: Check,
+    yt_dlp: Check,
+}
+
+/// GET /healthz
+/// Liveness: the process is up and serving requests. Touches nothing else,
+/// so a slow dependency never gets the server restarted.
+pub async fn healthz() -> impl IntoResponse {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+/// GET /readyz
+/// Readiness: checks each dependency and reports them individually. 503
+/// when a required one (database, uploads) is down; optional ones only
+/// make the overall status "degraded".
+pub async fn readyz(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let (database, uploads, livekit, yt_dlp) = tokio::join!(
+        check_database(&state),
+        check_uploads(&state),
+        check_livekit(&state),
+        check_yt_dlp(),
+    );
+    let checks = Checks { database, uploads, livekit, yt_dlp };
+
+    let all = [&checks.database, &checks.uploads, &checks.livekit, &checks.yt_dlp];
+    let status = if all.iter().any(|c| c.required && c.status == Status::Error) {
+        Status::Error
+    } else if all.iter().any(|c| c.status == Status::Error) {
+        Status::Degraded
+    } else {
+        Status::Ok
+    };
+    let code = if status == Status::Error { StatusCode::SERVICE_UNAVAILABLE } else { StatusCode::OK };
+
+    (code, Json(serde_json::json!({ "status": status, "checks": checks })))
+}
+
+async fn check_database(state: &AppState) -> Check {
+    let start = Instant::now();
+    let result = match tokio::time::timeout(CHECK_TIMEOUT, sqlx::query("SELECT 1").execute(&state.db)).await {
+        Ok(Ok(_)) => Ok(None),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err("timed out".into()),
+    };
+    Check::from_result(true, start, result)
+}
+
+/// Write and remove a scratch file, which catches a missing directory, a
+/// read-only mount and a full disk alike.
+async fn check_uploads(state: &AppState) -> Check {
+    let start = Instant::now();
+    let probe = std::path::Path::new(&state.config.upload_dir).join(format!(".readyz-{}", uuid::Uuid::new_v4()));
+    let result = match tokio::fs::write(&probe, b"ok").await {
+        Ok(()) => {
+            tokio::fs::remove_file(&probe).await.ok();
+            Ok(None)
+        }
+        Err(e) => Err(format!("{}: {}", state.config.upload_dir, e)),
+    };
+    Check::from_result(true, start, result)
+}
+
+/// Any HTTP response from the LiveKit server counts; we only care that it
+/// can be reached.
+async fn check_livekit(state: &AppState) -> Check {
+    if state.config.livekit_api_key.is_empty() || state.config.livekit_api_secret.is_empty() {
+        return Check::disabled("LiveKit not configured");
+    }
+    let start = Instant::now();
+    let url = state
+        .config
+        .livekit_url
+        .replacen("wss://", "https://", 1)
+        .replacen("ws://", "http://", 1);
+    let result = match reqwest::Client::builder().timeout(CHECK_TIMEOUT).build() {
+        Ok(client) => client.get(&url).send().await.map(|_| None).map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    Check::from_result(false, start, result)
+}
+
+async fn check_yt_dlp() -> Check {
+    if let Some((at, check)) = YT_DLP.lock().unwrap().as_ref() {
+        if at.elapsed() < YT_DLP_RECHECK {
+            return check.clone();
+        }
+    }
+
+    let start = Instant::now();
+    let output = tokio::time::timeout(
+        CHECK_TIMEOUT,
+        tokio::process::Command::new(super::media::yt_dlp_path()).arg("--version").output(),
+    )
+    .await;
+    let result = match output {
+        Ok(Ok(o)) if o.status.success() => Ok(Some(String::from_utf8_lossy(&o.stdout).trim().to_string())),
+        Ok(Ok(o)) => Err(format!("exited with {}", o.status)),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err("timed out".into()),
+    };
+    let check = Check::from_result(false, start, result);
+    *YT_DLP.lock().unwrap() = Some((Instant::now(), check.clone()));
+    check
+}
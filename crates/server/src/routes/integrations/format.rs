@@ -0,0 +1,186 @@
+//! Turn webhook payloads into channel messages. Each returns None for events
+//! (or actions) that aren't worth a message.
+
+use serde_json::Value;
+
+/// Commits listed per push; the rest are summarized as a count.
+const MAX_COMMITS: usize = 5;
+const MAX_TITLE_CHARS: usize = 200;
+const MAX_COMMIT_LINE_CHARS: usize = 100;
+
+fn text<'a>(v: &'a Value, path: &[&str]) -> &'a str {
+    path.iter().fold(v, |v, key| &v[*key]).as_str().unwrap_or("")
+}
+
+fn clip(s: &str, max: usize) -> String {
+    let s = s.trim();
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+    let mut clipped: String = s.chars().take(max - 1).collect();
+    clipped.push('…');
+    clipped
+}
+
+fn plural(n: usize, word: &str) -> String {
+    if n == 1 { format!("1 {}", word) } else { format!("{} {}s", n, word) }
+}
+
+/// "pushed N commits" plus one line per commit: short SHA and first message line.
+fn push_message(repo: &str, who: &str, branch: &str, total: usize, commits: &[Value], link: &str) -> String {
+    let mut out = format!("**{}** · {} pushed {} to `{}`", repo, who, plural(total, "commit"), branch);
+    for commit in commits.iter().take(MAX_COMMITS) {
+        let sha: String = text(commit, &["id"]).chars().take(7).collect();
+        let subject = text(commit, &["message"]).lines().next().unwrap_or("");
+        out.push_str(&format!("\n`{}` {}", sha, clip(subject, MAX_COMMIT_LINE_CHARS)));
+    }
+    if total > MAX_COMMITS {
+        out.push_str(&format!("\n…and {} more", total - MAX_COMMITS));
+    }
+    if !link.is_empty() {
+        out.push('\n');
+        out.push_str(link);
+    }
+    out
+}
+
+fn item_message(repo: &str, who: &str, verb: &str, kind: &str, number: i64, title: &str, link: &str) -> String {
+    format!(
+        "**{}** · {} {} {} #{}: {}\n{}",
+        repo,
+        who,
+        verb,
+        kind,
+        number,
+        clip(title, MAX_TITLE_CHARS),
+        link
+    )
+}
+
+/// GitHub: push, pull_request, issues and release events (`X-GitHub-Event`).
+pub fn github(event: &str, p: &Value) -> Option<String> {
+    let repo = text(p, &["repository", "full_name"]);
+    let sender = text(p, &["sender", "login"]);
+    let action = text(p, &["action"]);
+
+    match event {
+        "push" => {
+            let git_ref = text(p, &["ref"]);
+            let branch = git_ref
+                .strip_prefix("refs/heads/")
+                .or_else(|| git_ref.strip_prefix("refs/tags/"))
+                .unwrap_or(git_ref);
+            let pusher = Some(text(p, &["pusher", "name"])).filter(|n| !n.is_empty()).unwrap_or(sender);
+            if p["deleted"].as_bool() == Some(true) {
+                return Some(format!("**{}** · {} deleted `{}`", repo, pusher, branch));
+            }
+            let commits = p["commits"].as_array()?;
+            if commits.is_empty() {
+                return None;
+            }
+            Some(push_message(repo, pusher, branch, commits.len(), commits, text(p, &["compare"])))
+        }
+        "pull_request" => {
+            let pr = &p["pull_request"];
+            let verb = match action {
+                "opened" => "opened",
+                "reopened" => "reopened",
+                "closed" if pr["merged"].as_bool() == Some(true) => "merged",
+                "closed" => "closed",
+                _ => return None,
+            };
+            Some(item_message(
+                repo,
+                sender,
+                verb,
+                "pull request",
+                pr["number"].as_i64().unwrap_or(0),
+                text(pr, &["title"]),
+                text(pr, &["html_url"]),
+            ))
+        }
+        "issues" => {
+            if !matches!(action, "opened" | "closed" | "reopened") {
+                return None;
+            }
+            let issue = &p["issue"];
+            Some(item_message(
+                repo,
+                sender,
+                action,
+                "issue",
+                issue["number"].as_i64().unwrap_or(0),
+                text(issue, &["title"]),
+                text(issue, &["html_url"]),
+            ))
+        }
+        "release" if action == "published" => {
+            let release = &p["release"];
+            let name = Some(text(release, &["name"])).filter(|n| !n.is_empty()).unwrap_or(text(release, &["tag_name"]));
+            Some(format!(
+                "**{}** · {} published release {}\n{}",
+                repo,
+                sender,
+                clip(name, MAX_TITLE_CHARS),
+                text(release, &["html_url"])
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// GitLab: push, merge request, issue and release hooks (`X-Gitlab-Event`).
+pub fn gitlab(event: &str, p: &Value) -> Option<String> {
+    let repo = text(p, &["project", "path_with_namespace"]);
+    let user = Some(text(p, &["user", "username"])).filter(|n| !n.is_empty()).unwrap_or(text(p, &["user_name"]));
+
+    match event {
+        "Push Hook" | "Tag Push Hook" => {
+            let git_ref = text(p, &["ref"]);
+            let branch = git_ref
+                .strip_prefix("refs/heads/")
+                .or_else(|| git_ref.strip_prefix("refs/tags/"))
+                .unwrap_or(git_ref);
+            let after = text(p, &["after"]);
+            if !after.is_empty() && after.chars().all(|c| c == '0') {
+                return Some(format!("**{}** · {} deleted `{}`", repo, user, branch));
+            }
+            let commits = p["commits"].as_array()?;
+            let total = p["total_commits_count"].as_u64().map(|n| n as usize).unwrap_or(commits.len());
+            if total == 0 {
+                return None;
+            }
+            Some(push_message(repo, user, branch, total, commits, text(p, &["project", "web_url"])))
+        }
+        "Merge Request Hook" | "Issue Hook" => {
+            let attrs = &p["object_attributes"];
+            let verb = match text(attrs, &["action"]) {
+                "open" => "opened",
+                "reopen" => "reopened",
+                "close" => "closed",
+                "merge" => "merged",
+                _ => return None,
+            };
+            let kind = if event == "Issue Hook" { "issue" } else { "merge request" };
+            Some(item_message(
+                repo,
+                user,
+                verb,
+                kind,
+                attrs["iid"].as_i64().unwrap_or(0),
+                text(attrs, &["title"]),
+                text(attrs, &["url"]),
+            ))
+        }
+        "Release Hook" if text(p, &["action"]) == "create" => {
+            let name = Some(text(p, &["name"])).filter(|n| !n.is_empty()).unwrap_or(text(p, &["tag"]));
+            Some(format!(
+                "**{}** · published release {}\n{}",
+                repo,
+                clip(name, MAX_TITLE_CHARS),
+                text(p, &["url"])
+            ))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,285 @@
+mod format;
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, ChannelIntegration, CreateIntegrationRequest};
+use crate::routes::auth::{random_token, token_hash};
+use crate::routes::feeds::require_channel_admin;
+use crate::AppState;
+
+const PROVIDERS: &[&str] = &["github", "gitlab"];
+const MAX_INTEGRATIONS_PER_CHANNEL: i64 = 10;
+const MAX_NAME_CHARS: usize = 100;
+
+const INTEGRATION_SELECT: &str = "SELECT id, channel_id, provider, name, created_by, created_at, last_delivery_at FROM channel_integrations";
+
+/// The integration plus its freshly issued credentials, which aren't stored
+/// in a form that can be shown again.
+fn with_credentials(state: &AppState, integration: &ChannelIntegration, token: &str, secret: &str) -> serde_json::Value {
+    let mut body = serde_json::to_value(integration).unwrap_or_default();
+    body["token"] = token.into();
+    body["secret"] = secret.into();
+    body["webhookUrl"] = format!(
+        "{}/api/integrations/{}/{}",
+        state.config.public_url.trim_end_matches('/'),
+        integration.provider,
+        token
+    )
+    .into();
+    body
+}
+
+/// GET /api/servers/:serverId/channels/:channelId/integrations
+pub async fn list_integrations(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, channel_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_channel_admin(&state, &user.id, &server_id, &channel_id).await?;
+
+    let integrations = sqlx::query_as::<_, ChannelIntegration>(&format!(
+        "{} WHERE channel_id = ? ORDER BY created_at ASC",
+        INTEGRATION_SELECT
+    ))
+    .bind(&channel_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(integrations))
+}
+
+/// POST /api/servers/:serverId/channels/:channelId/integrations
+pub async fn create_integration(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, channel_id)): Path<(String, String)>,
+    Json(body): Json<CreateIntegrationRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_channel_admin(&state, &user.id, &server_id, &channel_id).await?;
+
+    if !PROVIDERS.contains(&body.provider.as_str()) {
+        return Err(ApiError::bad_request("Provider must be github or gitlab"));
+    }
+    let name = body.name.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    if name.is_some_and(|n| n.chars().count() > MAX_NAME_CHARS) {
+        return Err(ApiError::bad_request(format!("Name must be at most {} characters", MAX_NAME_CHARS)));
+    }
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM channel_integrations WHERE channel_id = ?")
+        .bind(&channel_id)
+        .fetch_one(&state.db)
+        .await?;
+    if count >= MAX_INTEGRATIONS_PER_CHANNEL {
+        return Err(ApiError::bad_request(format!(
+            "A channel can have at most {} integrations",
+            MAX_INTEGRATIONS_PER_CHANNEL
+        )));
+    }
+
+    let default_name = if body.provider == "github" { "GitHub" } else { "GitLab" };
+    let (token, secret) = (random_token(), random_token());
+    let integration = ChannelIntegration {
+        id: uuid::Uuid::new_v4().to_string(),
+        channel_id,
+        name: name.unwrap_or(default_name).to_string(),
+        provider: body.provider,
+        created_by: user.id,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        last_delivery_at: None,
+    };
+
+    sqlx::query(
+        r#"INSERT INTO channel_integrations (id, channel_id, provider, name, token_hash, secret, created_by, created_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(&integration.id)
+    .bind(&integration.channel_id)
+    .bind(&integration.provider)
+    .bind(&integration.name)
+    .bind(token_hash(&state.config.auth_secret, &token))
+    .bind(&secret)
+    .bind(&integration.created_by)
+    .bind(&integration.created_at)
+    .execute(&state.db)
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(with_credentials(&state, &integration, &token, &secret))))
+}
+
+/// POST /api/servers/:serverId/channels/:channelId/integrations/:integrationId/rotate —
+/// issue a new URL token and secret; the old ones stop working at once
+pub async fn rotate_integration(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, channel_id, integration_id)): Path<(String, String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_channel_admin(&state, &user.id, &server_id, &channel_id).await?;
+
+    let (token, secret) = (random_token(), random_token());
+    let rotated = sqlx::query("UPDATE channel_integrations SET token_hash = ?, secret = ? WHERE id = ? AND channel_id = ?")
+        .bind(token_hash(&state.config.auth_secret, &token))
+        .bind(&secret)
+        .bind(&integration_id)
+        .bind(&channel_id)
+        .execute(&state.db)
+        .await?
+        .rows_affected();
+    if rotated == 0 {
+        return Err(ApiError::not_found("Integration not found"));
+    }
+
+    let integration = sqlx::query_as::<_, ChannelIntegration>(&format!("{} WHERE id = ?", INTEGRATION_SELECT))
+        .bind(&integration_id)
+        .fetch_one(&state.db)
+        .await?;
+    Ok(Json(with_credentials(&state, &integration, &token, &secret)))
+}
+
+/// DELETE /api/servers/:serverId/channels/:channelId/integrations/:integrationId
+pub async fn revoke_integration(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, channel_id, integration_id)): Path<(String, String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_channel_admin(&state, &user.id, &server_id, &channel_id).await?;
+
+    let deleted = sqlx::query("DELETE FROM channel_integrations WHERE id = ? AND channel_id = ?")
+        .bind(&integration_id)
+        .bind(&channel_id)
+        .execute(&state.db)
+        .await?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(ApiError::not_found("Integration not found"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+        .collect()
+}
+
+/// `X-Hub-Signature-256: sha256=<hex HMAC of the body>`
+fn github_signature_valid(secret: &str, signature: Option<&str>, body: &[u8]) -> bool {
+    let Some(expected) = signature.and_then(|s| s.strip_prefix("sha256=")).and_then(decode_hex) else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// GitLab sends the secret itself in `X-Gitlab-Token`; compare in constant time.
+fn gitlab_token_valid(secret: &str, given: Option<&str>) -> bool {
+    let Some(given) = given else { return false };
+    given.len() == secret.len() && given.bytes().zip(secret.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// POST /api/integrations/github/:channelToken
+pub async fn receive_github(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, ApiError> {
+    receive_webhook(&state, "github", &token, &headers, &body).await
+}
+
+/// POST /api/integrations/gitlab/:channelToken
+pub async fn receive_gitlab(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, ApiError> {
+    receive_webhook(&state, "gitlab", &token, &headers, &body).await
+}
+
+/// Webhook deliveries. Events we don't announce are accepted and dropped, so
+/// the sender doesn't retry them.
+async fn receive_webhook(
+    state: &AppState,
+    provider: &str,
+    token: &str,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<StatusCode, ApiError> {
+    let row = sqlx::query_as::<_, (String, String, String, String)>(
+        "SELECT id, channel_id, secret, created_by FROM channel_integrations WHERE token_hash = ? AND provider = ?",
+    )
+    .bind(token_hash(&state.config.auth_secret, token))
+    .bind(provider)
+    .fetch_optional(&state.db)
+    .await?;
+    let Some((integration_id, channel_id, secret, created_by)) = row else {
+        return Err(ApiError::not_found("Unknown integration"));
+    };
+
+    let (valid, event) = match provider {
+        "github" => (
+            github_signature_valid(&secret, header(headers, "x-hub-signature-256"), body),
+            header(headers, "x-github-event"),
+        ),
+        _ => (
+            gitlab_token_valid(&secret, header(headers, "x-gitlab-token")),
+            header(headers, "x-gitlab-event"),
+        ),
+    };
+    if !valid {
+        return Err(ApiError::unauthorized("Invalid webhook signature"));
+    }
+
+    let payload: serde_json::Value =
+        serde_json::from_slice(body).map_err(|_| ApiError::bad_request("Payload must be JSON"))?;
+    let event = event.unwrap_or_default();
+    let content = if provider == "github" {
+        format::github(event, &payload)
+    } else {
+        format::gitlab(event, &payload)
+    };
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query("UPDATE channel_integrations SET last_delivery_at = ? WHERE id = ?")
+        .bind(&now)
+        .bind(&integration_id)
+        .execute(&state.db)
+        .await?;
+
+    if let Some(content) = content {
+        let message = crate::models::Message {
+            id: uuid::Uuid::new_v4().to_string(),
+            channel_id,
+            sender_id: created_by,
+            content,
+            created_at: now,
+            edited_at: None,
+            metadata: None,
+            feed_id: None,
+            integration_id: Some(integration_id),
+        };
+        crate::ws::handler::post_automated_message(state, message).await?;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
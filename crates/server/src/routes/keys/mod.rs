@@ -0,0 +1,441 @@
+mod verification;
+
+pub use verification::*;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{
+    AuthUser, DeviceKey, KeyBackup, KeyRestoredRequest, PutKeyBackupRequest, RegisterDeviceRequest,
+    SetPublicKeyRequest, StoreServerKeyRequest,
+};
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+/// Wrapped keys are small; this just keeps the table from being used as storage.
+const MAX_BACKUP_FIELD_LEN: usize = 16 * 1024;
+
+/// Publish a new account key, warning the user's contacts if it changed.
+async fn set_account_key(state: &AppState, user_id: &str, public_key: &str) {
+    let previous = sqlx::query_scalar::<_, Option<String>>(r#"SELECT public_key FROM "user" WHERE id = ?"#)
+        .bind(user_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten()
+        .flatten();
+
+    let _ = sqlx::query(r#"UPDATE "user" SET public_key = ? WHERE id = ?"#)
+        .bind(public_key)
+        .bind(user_id)
+        .execute(&state.db)
+        .await;
+
+    if previous.is_some_and(|k| k != public_key) {
+        notify_key_changed(state, user_id, None).await;
+    }
+}
+
+/// PUT /api/users/me/public-key
+pub async fn set_public_key(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<SetPublicKeyRequest>,
+) -> impl IntoResponse {
+    set_account_key(&state, &user.id, &body.public_key).await;
+
+    StatusCode::NO_CONTENT.into_response()
+}
+
+/// GET /api/users/:id/public-key
+pub async fn get_public_key(
+    State(state): State<Arc<AppState>>,
+    _user: AuthUser,
+    Path(user_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let result = sqlx::query_as::<_, (Option<String>,)>(
+        r#"SELECT public_key FROM "user" WHERE id = ?"#,
+    )
+    .bind(&user_id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    match result {
+        Some((public_key,)) => {
+            Ok(Json(serde_json::json!({ "publicKey": public_key })).into_response())
+        }
+        None => Err(ApiError::not_found("User not found")),
+    }
+}
+
+/// PUT /api/users/me/devices/:deviceId
+/// Register a device's identity key (or replace it). It also becomes the
+/// account's public key, for clients that only know one key per user.
+pub async fn register_device(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(device_id): Path<String>,
+    Json(body): Json<RegisterDeviceRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let valid_id = !device_id.is_empty()
+        && device_id.len() <= 64
+        && device_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_id || body.public_key.is_empty() || body.public_key.len() > MAX_BACKUP_FIELD_LEN {
+        return Err(ApiError::bad_request("Invalid device id or public key"));
+    }
+
+    let previous = sqlx::query_scalar::<_, String>(
+        r#"SELECT public_key FROM "device_keys" WHERE user_id = ? AND device_id = ?"#,
+    )
+    .bind(&user.id)
+    .bind(&device_id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let device = sqlx::query_as::<_, DeviceKey>(
+        r#"INSERT INTO "device_keys" (user_id, device_id, public_key, name, created_at, last_seen_at)
+           VALUES (?, ?, ?, ?, ?, ?)
+           ON CONFLICT(user_id, device_id) DO UPDATE SET public_key = excluded.public_key,
+               name = COALESCE(excluded.name, name), last_seen_at = excluded.last_seen_at
+           RETURNING device_id, public_key, name, created_at, last_seen_at"#,
+    )
+    .bind(&user.id)
+    .bind(&device_id)
+    .bind(&body.public_key)
+    .bind(&body.name)
+    .bind(&now)
+    .bind(&now)
+    .fetch_one(&state.db)
+    .await;
+
+    let Ok(device) = device else {
+        return Err(ApiError::internal("Failed to register device"));
+    };
+
+    if previous.is_some_and(|k| k != body.public_key) {
+        notify_key_changed(&state, &user.id, Some(&device_id)).await;
+    }
+    set_account_key(&state, &user.id, &body.public_key).await;
+
+    Ok(Json(device).into_response())
+}
+
+/// DELETE /api/users/me/devices/:deviceId
+/// Also drops the server keys wrapped for that device.
+pub async fn remove_device(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(device_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    sqlx::query("DELETE FROM server_keys WHERE user_id = ? AND device_id = ?")
+        .bind(&user.id)
+        .bind(&device_id)
+        .execute(&state.db)
+        .await?;
+    sqlx::query(r#"DELETE FROM "device_keys" WHERE user_id = ? AND device_id = ?"#)
+        .bind(&user.id)
+        .bind(&device_id)
+        .execute(&state.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// GET /api/users/:id/devices
+pub async fn list_devices(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(user_id): Path<String>,
+) -> impl IntoResponse {
+    let user_id = if user_id == "me" { user.id } else { user_id };
+    let devices = sqlx::query_as::<_, DeviceKey>(
+        r#"SELECT device_id, public_key, name, created_at, last_seen_at FROM "device_keys"
+           WHERE user_id = ? ORDER BY created_at"#,
+    )
+    .bind(&user_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    Json(devices).into_response()
+}
+
+/// POST /api/servers/:id/keys — store my own wrapped group key
+pub async fn store_server_key(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+    Json(body): Json<StoreServerKeyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Verify membership
+    let is_member = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
+    )
+    .bind(&user.id)
+    .bind(&server_id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0);
+
+    if is_member == 0 {
+        return Err(ApiError::forbidden("Not a member"));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        "INSERT INTO server_keys (server_id, user_id, device_id, encrypted_key, sender_id, created_at) VALUES (?, ?, ?, ?, ?, ?)
+         ON CONFLICT(server_id, user_id, device_id) DO UPDATE SET encrypted_key = excluded.encrypted_key, sender_id = excluded.sender_id",
+    )
+    .bind(&server_id)
+    .bind(&user.id)
+    .bind(body.device_id.as_deref().unwrap_or(""))
+    .bind(&body.encrypted_key)
+    .bind(&body.sender_id)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// GET /api/servers/:id/keys/me — get my wrapped group key
+/// With `?deviceId=`, the key wrapped for that device, falling back to the
+/// account-level key.
+pub async fn get_my_server_key(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let device_id = query.get("deviceId").map(|d| d.as_str()).unwrap_or("");
+    let result = sqlx::query_as::<_, (String, String, String)>(
+        "SELECT encrypted_key, sender_id, device_id FROM server_keys
+         WHERE server_id = ? AND user_id = ? AND device_id IN (?, '')
+         ORDER BY device_id = '' LIMIT 1",
+    )
+    .bind(&server_id)
+    .bind(&user.id)
+    .bind(device_id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    match result {
+        Some((encrypted_key, sender_id, device_id)) => {
+            Json(serde_json::json!({
+                "encryptedKey": encrypted_key,
+                "senderId": sender_id,
+                "deviceId": (!device_id.is_empty()).then_some(device_id),
+            }))
+            .into_response()
+        }
+        None => Json(serde_json::json!(null)).into_response(),
+    }
+}
+
+/// POST /api/servers/:id/keys/:userId — share wrapped key with another member
+pub async fn share_server_key(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, target_user_id)): Path<(String, String)>,
+    Json(body): Json<StoreServerKeyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Verify both are members
+    let my_membership = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
+    )
+    .bind(&user.id)
+    .bind(&server_id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0);
+
+    let target_membership = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
+    )
+    .bind(&target_user_id)
+    .bind(&server_id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0);
+
+    if my_membership == 0 || target_membership == 0 {
+        return Err(ApiError::forbidden("Not a member"));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        "INSERT INTO server_keys (server_id, user_id, device_id, encrypted_key, sender_id, created_at) VALUES (?, ?, ?, ?, ?, ?)
+         ON CONFLICT(server_id, user_id, device_id) DO UPDATE SET encrypted_key = excluded.encrypted_key, sender_id = excluded.sender_id",
+    )
+    .bind(&server_id)
+    .bind(&target_user_id)
+    .bind(body.device_id.as_deref().unwrap_or(""))
+    .bind(&body.encrypted_key)
+    .bind(&body.sender_id)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// GET /api/users/me/key-backup
+pub async fn get_key_backup(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    let backup = sqlx::query_as::<_, KeyBackup>(
+        r#"SELECT version, wrapped_key, salt, kdf, kdf_params, public_key, created_at, updated_at
+           FROM "key_backups" WHERE user_id = ?"#,
+    )
+    .bind(&user.id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    match backup {
+        Some(backup) => Ok(Json(backup).into_response()),
+        None => Err(ApiError::not_found("No key backup")),
+    }
+}
+
+/// PUT /api/users/me/key-backup
+/// Store or replace the wrapped key. `expectedVersion` must match the stored
+/// version so two devices can't silently overwrite each other's backup.
+pub async fn put_key_backup(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<PutKeyBackupRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let fields = [&body.wrapped_key, &body.salt, &body.kdf, &body.kdf_params, &body.public_key];
+    if fields.iter().any(|f| f.is_empty() || f.len() > MAX_BACKUP_FIELD_LEN) {
+        return Err(ApiError::bad_request("Backup fields must be non-empty and under 16 KB"));
+    }
+
+    let expected = body.expected_version.unwrap_or(0);
+    let now = chrono::Utc::now().to_rfc3339();
+    let stored = if expected == 0 {
+        sqlx::query_scalar::<_, i64>(
+            r#"INSERT INTO "key_backups" (user_id, version, wrapped_key, salt, kdf, kdf_params, public_key, created_at, updated_at)
+               VALUES (?, 1, ?, ?, ?, ?, ?, ?, ?)
+               ON CONFLICT(user_id) DO NOTHING
+               RETURNING version"#,
+        )
+        .bind(&user.id)
+        .bind(&body.wrapped_key)
+        .bind(&body.salt)
+        .bind(&body.kdf)
+        .bind(&body.kdf_params)
+        .bind(&body.public_key)
+        .bind(&now)
+        .bind(&now)
+        .fetch_optional(&state.db)
+        .await
+    } else {
+        sqlx::query_scalar::<_, i64>(
+            r#"UPDATE "key_backups" SET version = version + 1, wrapped_key = ?, salt = ?, kdf = ?,
+                   kdf_params = ?, public_key = ?, updated_at = ?
+               WHERE user_id = ? AND version = ?
+               RETURNING version"#,
+        )
+        .bind(&body.wrapped_key)
+        .bind(&body.salt)
+        .bind(&body.kdf)
+        .bind(&body.kdf_params)
+        .bind(&body.public_key)
+        .bind(&now)
+        .bind(&user.id)
+        .bind(expected)
+        .fetch_optional(&state.db)
+        .await
+    };
+
+    let version = match stored {
+        Ok(v) => v,
+        Err(_) => {
+            return Err(ApiError::internal("Failed to store key backup"));
+        }
+    };
+
+    let Some(version) = version else {
+        let current = sqlx::query_scalar::<_, i64>(r#"SELECT version FROM "key_backups" WHERE user_id = ?"#)
+            .bind(&user.id)
+            .fetch_optional(&state.db)
+            .await
+            .ok()
+            .flatten();
+        return Err(ApiError::conflict("Key backup version mismatch")
+            .with_details(serde_json::json!({"currentVersion": current})));
+    };
+
+    state
+        .gateway
+        .send_to_user(&user.id, &ServerEvent::KeyBackupUpdated { version })
+        .await;
+
+    Ok(Json(serde_json::json!({"version": version})).into_response())
+}
+
+/// DELETE /api/users/me/key-backup
+pub async fn delete_key_backup(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    sqlx::query(r#"DELETE FROM "key_backups" WHERE user_id = ?"#)
+        .bind(&user.id)
+        .execute(&state.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// POST /api/users/me/key-backup/restored
+/// A new device unwrapped the backup. The identity it restored must be the
+/// backed-up one; it becomes the published public key and the user's other
+/// sessions are told.
+pub async fn key_backup_restored(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<KeyRestoredRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let backup = sqlx::query_as::<_, (i64, String)>(
+        r#"SELECT version, public_key FROM "key_backups" WHERE user_id = ?"#,
+    )
+    .bind(&user.id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    let Some((version, public_key)) = backup else {
+        return Err(ApiError::not_found("No key backup"));
+    };
+    if version != body.version || public_key != body.public_key {
+        return Err(ApiError::conflict("Restored key does not match the current backup")
+            .with_details(serde_json::json!({"currentVersion": version})));
+    }
+
+    set_account_key(&state, &user.id, &public_key).await;
+
+    state
+        .gateway
+        .send_to_user(&user.id, &ServerEvent::KeyBackupRestored { version, public_key })
+        .await;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
@@ -0,0 +1,263 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha512};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+const FINGERPRINT_VERSION: u16 = 0;
+const FINGERPRINT_ITERATIONS: usize = 5200;
+
+/// 30 digits identifying one user's key: iterated SHA-512 over the key and
+/// user id, the first 30 bytes read as six 5-digit groups.
+fn fingerprint(user_id: &str, public_key: &str) -> String {
+    let mut hash = Sha512::new()
+        .chain_update(FINGERPRINT_VERSION.to_be_bytes())
+        .chain_update(public_key.as_bytes())
+        .chain_update(user_id.as_bytes())
+        .finalize();
+    for _ in 0..FINGERPRINT_ITERATIONS {
+        hash = Sha512::new().chain_update(hash).chain_update(public_key.as_bytes()).finalize();
+    }
+    hash[..30]
+        .chunks(5)
+        .map(|c| {
+            let n = c.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+            format!("{:05}", n % 100_000)
+        })
+        .collect()
+}
+
+/// The 60-digit number two users compare out of band. Both sides get the same
+/// digits: the fingerprints are ordered by user id.
+pub fn safety_number(user_a: &str, key_a: &str, user_b: &str, key_b: &str) -> String {
+    let (a, b) = (fingerprint(user_a, key_a), fingerprint(user_b, key_b));
+    let digits = if user_a <= user_b { a + &b } else { b + &a };
+    digits
+        .as_bytes()
+        .chunks(5)
+        .map(|c| String::from_utf8_lossy(c).into_owned())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A user's account key, or the key of one of their devices.
+async fn current_key(state: &AppState, user_id: &str, device_id: &str) -> Option<String> {
+    if device_id.is_empty() {
+        sqlx::query_scalar::<_, Option<String>>(r#"SELECT public_key FROM "user" WHERE id = ?"#)
+            .bind(user_id)
+            .fetch_optional(&state.db)
+            .await
+            .ok()
+            .flatten()
+            .flatten()
+    } else {
+        sqlx::query_scalar::<_, String>(
+            r#"SELECT public_key FROM "device_keys" WHERE user_id = ? AND device_id = ?"#,
+        )
+        .bind(user_id)
+        .bind(device_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten()
+    }
+}
+
+/// Both keys needed for a safety number, or the error to return.
+async fn key_pair(
+    state: &AppState,
+    user_id: &str,
+    peer_id: &str,
+    device_id: &str,
+) -> Result<(String, String), ApiError> {
+    let Some(mine) = current_key(state, user_id, "").await else {
+        return Err(ApiError::bad_request("You have no public key"));
+    };
+    let Some(theirs) = current_key(state, peer_id, device_id).await else {
+        return Err(ApiError::not_found("Peer has no public key"));
+    };
+    Ok((mine, theirs))
+}
+
+/// Tell a user's DM partners, and everyone who verified them, that their key
+/// changed. `wasVerified` means the recipient's verification no longer holds.
+pub async fn notify_key_changed(state: &AppState, user_id: &str, device_id: Option<&str>) {
+    let verifiers: HashSet<String> = sqlx::query_scalar::<_, String>(
+        "SELECT user_id FROM key_verifications WHERE peer_id = ? AND device_id = ?",
+    )
+    .bind(user_id)
+    .bind(device_id.unwrap_or(""))
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default()
+    .into_iter()
+    .collect();
+
+    let partners = sqlx::query_scalar::<_, String>(
+        "SELECT user2_id FROM dm_channels WHERE user1_id = ?
+         UNION SELECT user1_id FROM dm_channels WHERE user2_id = ?",
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let recipients: HashSet<&String> = verifiers.iter().chain(partners.iter()).collect();
+    for recipient in recipients {
+        if recipient == user_id {
+            continue;
+        }
+        state
+            .gateway
+            .send_to_user(
+                recipient,
+                &ServerEvent::KeyChanged {
+                    user_id: user_id.to_string(),
+                    device_id: device_id.map(str::to_string),
+                    was_verified: verifiers.contains(recipient),
+                },
+            )
+            .await;
+    }
+}
+
+/// GET /api/users/:id/safety-number
+/// `?deviceId=` compares against one of the peer's devices instead of their
+/// account key.
+pub async fn get_safety_number(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(peer_id): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let device_id = query.get("deviceId").map(|d| d.as_str()).unwrap_or("");
+    let (mine, theirs) = key_pair(&state, &user.id, &peer_id, device_id).await?;
+
+    let verified_key = sqlx::query_scalar::<_, String>(
+        "SELECT public_key FROM key_verifications WHERE user_id = ? AND peer_id = ? AND device_id = ?",
+    )
+    .bind(&user.id)
+    .bind(&peer_id)
+    .bind(device_id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    Ok(Json(serde_json::json!({
+        "userId": peer_id,
+        "deviceId": (!device_id.is_empty()).then_some(device_id),
+        "safetyNumber": safety_number(&user.id, &mine, &peer_id, &theirs),
+        "verified": verified_key.as_deref() == Some(theirs.as_str()),
+        "stale": verified_key.is_some_and(|k| k != theirs),
+    }))
+    .into_response())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyKeyRequest {
+    pub safety_number: String,
+    pub device_id: Option<String>,
+}
+
+/// PUT /api/users/:id/verification
+/// Mark the peer's current key as verified. The safety number the user
+/// compared must still match, so a key that rotated mid-comparison isn't
+/// marked verified.
+pub async fn verify_key(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(peer_id): Path<String>,
+    Json(body): Json<VerifyKeyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    if peer_id == user.id {
+        return Err(ApiError::bad_request("Cannot verify yourself"));
+    }
+    let device_id = body.device_id.as_deref().unwrap_or("");
+    let (mine, theirs) = key_pair(&state, &user.id, &peer_id, device_id).await?;
+
+    let expected = safety_number(&user.id, &mine, &peer_id, &theirs);
+    let normalize = |s: &str| s.chars().filter(|c| c.is_ascii_digit()).collect::<String>();
+    if normalize(&body.safety_number) != normalize(&expected) {
+        return Err(ApiError::conflict("Safety number does not match the current keys"));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        "INSERT INTO key_verifications (user_id, peer_id, device_id, public_key, verified_at) VALUES (?, ?, ?, ?, ?)
+         ON CONFLICT(user_id, peer_id, device_id) DO UPDATE SET public_key = excluded.public_key, verified_at = excluded.verified_at",
+    )
+    .bind(&user.id)
+    .bind(&peer_id)
+    .bind(device_id)
+    .bind(&theirs)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    Ok(Json(serde_json::json!({"userId": peer_id, "verified": true, "verifiedAt": now})).into_response())
+}
+
+/// DELETE /api/users/:id/verification
+pub async fn unverify_key(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(peer_id): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, ApiError> {
+    sqlx::query("DELETE FROM key_verifications WHERE user_id = ? AND peer_id = ? AND device_id = ?")
+        .bind(&user.id)
+        .bind(&peer_id)
+        .bind(query.get("deviceId").map(|d| d.as_str()).unwrap_or(""))
+        .execute(&state.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// GET /api/users/me/verifications
+/// Everyone the user has verified; `stale` entries need verifying again.
+pub async fn list_verifications(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> impl IntoResponse {
+    let rows = sqlx::query_as::<_, (String, String, String, String, Option<String>)>(
+        r#"SELECT v.peer_id, v.device_id, v.verified_at, v.public_key,
+               CASE WHEN v.device_id = '' THEN u.public_key ELSE d.public_key END
+           FROM key_verifications v
+           LEFT JOIN "user" u ON u.id = v.peer_id
+           LEFT JOIN "device_keys" d ON d.user_id = v.peer_id AND d.device_id = v.device_id
+           WHERE v.user_id = ?
+           ORDER BY v.verified_at DESC"#,
+    )
+    .bind(&user.id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let entries: Vec<serde_json::Value> = rows
+        .into_iter()
+        .map(|(peer_id, device_id, verified_at, verified_key, current_key)| {
+            serde_json::json!({
+                "userId": peer_id,
+                "deviceId": (!device_id.is_empty()).then_some(device_id),
+                "verifiedAt": verified_at,
+                "stale": current_key.as_deref() != Some(verified_key.as_str()),
+            })
+        })
+        .collect();
+
+    Json(entries).into_response()
+}
@@ -0,0 +1,281 @@
+mod scrobbler;
+
+pub use scrobbler::{enqueue_scrobbles, run_scrobbler, submit_due_scrobbles};
+
+use axum::{
+    extract::{Query, State},
+    response::{Html, IntoResponse},
+    Json,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::AppState;
+
+const LASTFM_API_URL: &str = "https://ws.audioscrobbler.com/2.0/";
+const LASTFM_AUTH_URL: &str = "https://www.last.fm/api/auth/";
+
+/// (api key, shared secret), or None when Last.fm isn't configured.
+pub fn credentials() -> Option<(String, String)> {
+    let key = std::env::var("LASTFM_API_KEY").unwrap_or_default();
+    let secret = std::env::var("LASTFM_API_SECRET").unwrap_or_default();
+    (!key.is_empty() && !secret.is_empty()).then_some((key, secret))
+}
+
+/// Sign a Last.fm API call: md5 of the params sorted by name, concatenated as
+/// `namevalue`, followed by the shared secret.
+pub(crate) fn api_signature(params: &BTreeMap<String, String>, secret: &str) -> String {
+    let mut raw = String::new();
+    for (k, v) in params {
+        if k == "format" || k == "callback" {
+            continue;
+        }
+        raw.push_str(k);
+        raw.push_str(v);
+    }
+    raw.push_str(secret);
+    format!("{:x}", md5::compute(raw.as_bytes()))
+}
+
+/// Why a Last.fm call failed. `code` is Last.fm's API error code, or 0 for
+/// network and HTTP failures.
+#[derive(Debug)]
+pub(crate) struct LastfmError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl LastfmError {
+    /// Worth retrying later: network trouble, service offline (11),
+    /// temporarily unavailable (16) or rate limited (29).
+    pub fn is_transient(&self) -> bool {
+        matches!(self.code, 0 | 11 | 16 | 29)
+    }
+}
+
+impl std::fmt::Display for LastfmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Last.fm error {}: {}", self.code, self.message)
+    }
+}
+
+/// Make a signed POST to the Last.fm API. Last.fm reports API errors in the
+/// body as `{"error": code, "message": ..}`.
+pub(crate) async fn signed_call(
+    mut params: BTreeMap<String, String>,
+    api_key: &str,
+    secret: &str,
+) -> Result<serde_json::Value, LastfmError> {
+    params.insert("api_key".into(), api_key.to_string());
+    let sig = api_signature(&params, secret);
+    params.insert("api_sig".into(), sig);
+    params.insert("format".into(), "json".into());
+
+    let res = reqwest::Client::new()
+        .post(LASTFM_API_URL)
+        .form(&params)
+        .timeout(std::time::Duration::from_secs(15))
+        .send()
+        .await
+        .map_err(|e| LastfmError { code: 0, message: e.to_string() })?;
+    let status = res.status();
+    let body: serde_json::Value = res.json().await.unwrap_or_default();
+    if let Some(code) = body["error"].as_i64() {
+        return Err(LastfmError {
+            code,
+            message: body["message"].as_str().unwrap_or("").to_string(),
+        });
+    }
+    if !status.is_success() {
+        return Err(LastfmError { code: 0, message: format!("HTTP {}", status) });
+    }
+    Ok(body)
+}
+
+fn page(title: &str, detail: &str) -> Html<String> {
+    Html(format!(
+        r#"<html><body style="background:#1a1a2e;color:#fff;font-family:system-ui;display:flex;align-items:center;justify-content:center;height:100vh;margin:0">
+        <div style="text-align:center"><h2>{}</h2><p>{}</p></div></body></html>"#,
+        title, detail
+    ))
+}
+
+/// POST /api/lastfm/init-auth
+/// Returns the Last.fm page to open; it redirects back to /api/lastfm/callback.
+pub async fn init_auth(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Some((api_key, _)) = credentials() else {
+        return Err(ApiError::unavailable("Last.fm is not configured"));
+    };
+
+    let nonce = uuid::Uuid::new_v4().to_string();
+    let mut pending = state.lastfm_auth_pending.write().await;
+    pending.retain(|_, uid| uid != &user.id);
+    pending.insert(nonce.clone(), user.id.clone());
+    drop(pending);
+
+    let callback = std::env::var("LASTFM_REDIRECT_URI")
+        .unwrap_or_else(|_| "http://127.0.0.1:3001/api/lastfm/callback".to_string());
+    let callback = format!("{}?state={}", callback, nonce);
+    let url = format!(
+        "{}?api_key={}&cb={}",
+        LASTFM_AUTH_URL,
+        urlencoding::encode(&api_key),
+        urlencoding::encode(&callback)
+    );
+
+    Ok(Json(serde_json::json!({"url": url, "state": nonce})).into_response())
+}
+
+/// GET /api/lastfm/callback
+#[derive(Deserialize)]
+pub struct LastfmCallbackQuery {
+    pub state: Option<String>,
+    pub token: Option<String>,
+}
+
+pub async fn lastfm_callback(
+    Query(query): Query<LastfmCallbackQuery>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let (Some(nonce), Some(token)) = (query.state, query.token) else {
+        return page("Last.fm Authorization Failed", "Missing token or state.").into_response();
+    };
+    let Some(user_id) = state.lastfm_auth_pending.write().await.remove(&nonce) else {
+        return page("Auth session expired", "Please try linking again from the app.").into_response();
+    };
+    let Some((api_key, secret)) = credentials() else {
+        return page("Server misconfigured", "LASTFM_API_KEY / LASTFM_API_SECRET not set.").into_response();
+    };
+
+    let params = BTreeMap::from([
+        ("method".to_string(), "auth.getSession".to_string()),
+        ("token".to_string(), token),
+    ]);
+    let body = match signed_call(params, &api_key, &secret).await {
+        Ok(b) => b,
+        Err(e) => {
+            tracing::error!("Last.fm auth.getSession failed: {}", e);
+            return page("Last.fm Authorization Failed", "Could not get a Last.fm session.").into_response();
+        }
+    };
+
+    let (Some(username), Some(session_key)) =
+        (body["session"]["name"].as_str(), body["session"]["key"].as_str())
+    else {
+        return page("Last.fm Authorization Failed", "Unexpected response from Last.fm.").into_response();
+    };
+
+    let saved = sqlx::query(
+        r#"INSERT INTO "lastfm_links" (user_id, username, session_key, scrobbling_enabled, created_at)
+           VALUES (?, ?, ?, 1, ?)
+           ON CONFLICT(user_id) DO UPDATE SET username = excluded.username, session_key = excluded.session_key"#,
+    )
+    .bind(&user_id)
+    .bind(username)
+    .bind(session_key)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&state.db)
+    .await;
+    if let Err(e) = saved {
+        tracing::error!("Failed to save Last.fm link: {}", e);
+        return page("Last.fm Authorization Failed", "Could not save the link. Please try again.").into_response();
+    }
+
+    page(
+        "Last.fm Linked Successfully!",
+        &format!("Scrobbling as {}. You can close this tab and return to Flux.", username),
+    )
+    .into_response()
+}
+
+/// GET /api/lastfm/status
+pub async fn get_status(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let link = sqlx::query_as::<_, (String, i64)>(
+        r#"SELECT username, scrobbling_enabled FROM "lastfm_links" WHERE user_id = ?"#,
+    )
+    .bind(&user.id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    let pending = sqlx::query_scalar::<_, i64>(
+        r#"SELECT COUNT(*) FROM "scrobble_queue" WHERE user_id = ?"#,
+    )
+    .bind(&user.id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0);
+
+    match link {
+        Some((username, enabled)) => Json(serde_json::json!({
+            "linked": true,
+            "username": username,
+            "scrobbling": enabled != 0,
+            "pendingScrobbles": pending,
+        })),
+        None => Json(serde_json::json!({
+            "linked": false,
+            "username": null,
+            "scrobbling": false,
+            "pendingScrobbles": pending,
+        })),
+    }
+    .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct LastfmSettingsRequest {
+    pub scrobbling: bool,
+}
+
+/// PATCH /api/lastfm/settings
+pub async fn update_settings(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<LastfmSettingsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let result = sqlx::query(r#"UPDATE "lastfm_links" SET scrobbling_enabled = ? WHERE user_id = ?"#)
+        .bind(body.scrobbling as i64)
+        .bind(&user.id)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found("Last.fm is not linked"));
+    }
+    if !body.scrobbling {
+        sqlx::query(r#"DELETE FROM "scrobble_queue" WHERE user_id = ?"#)
+            .bind(&user.id)
+            .execute(&state.db)
+            .await?;
+    }
+
+    Ok(Json(serde_json::json!({"scrobbling": body.scrobbling})))
+}
+
+/// POST /api/lastfm/unlink
+pub async fn unlink_lastfm(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    sqlx::query(r#"DELETE FROM "scrobble_queue" WHERE user_id = ?"#)
+        .bind(&user.id)
+        .execute(&state.db)
+        .await?;
+    sqlx::query(r#"DELETE FROM "lastfm_links" WHERE user_id = ?"#)
+        .bind(&user.id)
+        .execute(&state.db)
+        .await?;
+
+    Ok(Json(serde_json::json!({"success": true})).into_response())
+}
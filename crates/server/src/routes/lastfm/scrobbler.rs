@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::AppState;
+
+/// How often the background task looks for scrobbles that are due.
+const SUBMIT_INTERVAL: Duration = Duration::from_secs(30);
+/// Last.fm accepts at most 50 scrobbles per request.
+const BATCH_SIZE: usize = 50;
+/// Last.fm ignores tracks shorter than 30 seconds.
+const MIN_TRACK_MS: i64 = 30_000;
+/// A track counts once half of it, or 4 minutes, has played.
+const MAX_LISTEN_MS: i64 = 4 * 60 * 1000;
+/// Last.fm rejects scrobbles older than two weeks, so stop retrying before then.
+const MAX_AGE_SECS: i64 = 14 * 24 * 60 * 60;
+const MAX_ATTEMPTS: i64 = 10;
+
+/// Queue a scrobble of a track that just started in a listening session, for
+/// every participant of its voice channel who linked Last.fm with scrobbling
+/// on. It's submitted once enough of the track would have played.
+pub async fn enqueue_scrobbles(
+    state: &AppState,
+    session_id: &str,
+    artist: &str,
+    track: &str,
+    album: Option<&str>,
+    duration_ms: i64,
+) {
+    if artist.is_empty() || track.is_empty() || (duration_ms > 0 && duration_ms < MIN_TRACK_MS) {
+        return;
+    }
+
+    let voice_channel_id = sqlx::query_scalar::<_, String>(
+        r#"SELECT voice_channel_id FROM "listening_sessions" WHERE id = ?"#,
+    )
+    .bind(session_id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+    let Some(voice_channel_id) = voice_channel_id else { return };
+
+    let participants = state.gateway.voice_channel_participants(&voice_channel_id).await;
+    if participants.is_empty() {
+        return;
+    }
+
+    let now = chrono::Utc::now();
+    let listen_ms = if duration_ms > 0 { (duration_ms / 2).min(MAX_LISTEN_MS) } else { MIN_TRACK_MS };
+    let ready_at = now.timestamp() + listen_ms / 1000;
+
+    for p in participants {
+        let _ = sqlx::query(
+            r#"INSERT INTO "scrobble_queue" (id, user_id, artist, track, album, duration_ms, played_at, ready_at, attempts, created_at)
+               SELECT ?, user_id, ?, ?, ?, ?, ?, ?, 0, ?
+               FROM "lastfm_links" WHERE user_id = ? AND scrobbling_enabled = 1"#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(artist)
+        .bind(track)
+        .bind(album)
+        .bind(duration_ms)
+        .bind(now.timestamp())
+        .bind(ready_at)
+        .bind(now.to_rfc3339())
+        .bind(&p.user_id)
+        .execute(&state.db)
+        .await;
+    }
+}
+
+/// Background task: submit due scrobbles every 30 seconds, forever.
+pub async fn run_scrobbler(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(SUBMIT_INTERVAL);
+    loop {
+        interval.tick().await;
+        submit_due_scrobbles(&state).await;
+    }
+}
+
+/// Submit every scrobble whose `ready_at` has passed, batched per user.
+/// Transient Last.fm failures are retried with exponential backoff; anything
+/// else (e.g. a revoked session key) drops that user's batch.
+pub async fn submit_due_scrobbles(state: &AppState) {
+    let Some((api_key, secret)) = super::credentials() else { return };
+    let now = chrono::Utc::now().timestamp();
+
+    let _ = sqlx::query(r#"DELETE FROM "scrobble_queue" WHERE played_at < ? OR attempts >= ?"#)
+        .bind(now - MAX_AGE_SECS)
+        .bind(MAX_ATTEMPTS)
+        .execute(&state.db)
+        .await;
+
+    let due = sqlx::query_as::<_, (String, String, String, String, Option<String>, i64, i64, i64, String)>(
+        r#"SELECT q.id, q.user_id, q.artist, q.track, q.album, q.duration_ms, q.played_at, q.attempts, l.session_key
+           FROM "scrobble_queue" q JOIN "lastfm_links" l ON l.user_id = q.user_id
+           WHERE q.ready_at <= ? AND l.scrobbling_enabled = 1
+           ORDER BY q.user_id, q.played_at"#,
+    )
+    .bind(now)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    for user_rows in due.chunk_by(|a, b| a.1 == b.1) {
+        for batch in user_rows.chunks(BATCH_SIZE) {
+            let mut params = BTreeMap::from([
+                ("method".to_string(), "track.scrobble".to_string()),
+                ("sk".to_string(), batch[0].8.clone()),
+            ]);
+            for (i, (_, _, artist, track, album, duration_ms, played_at, _, _)) in batch.iter().enumerate() {
+                params.insert(format!("artist[{}]", i), artist.clone());
+                params.insert(format!("track[{}]", i), track.clone());
+                params.insert(format!("timestamp[{}]", i), played_at.to_string());
+                if let Some(album) = album {
+                    params.insert(format!("album[{}]", i), album.clone());
+                }
+                if *duration_ms > 0 {
+                    params.insert(format!("duration[{}]", i), (duration_ms / 1000).to_string());
+                }
+            }
+
+            match super::signed_call(params, &api_key, &secret).await {
+                Ok(_) => {
+                    for row in batch {
+                        let _ = sqlx::query(r#"DELETE FROM "scrobble_queue" WHERE id = ?"#)
+                            .bind(&row.0)
+                            .execute(&state.db)
+                            .await;
+                    }
+                }
+                Err(e) if e.is_transient() => {
+                    tracing::warn!("Last.fm unavailable, will retry {} scrobble(s): {}", batch.len(), e);
+                    for row in batch {
+                        let backoff = (60i64 << row.7.min(8)).min(6 * 60 * 60);
+                        let _ = sqlx::query(
+                            r#"UPDATE "scrobble_queue" SET attempts = attempts + 1, ready_at = ? WHERE id = ?"#,
+                        )
+                        .bind(now + backoff)
+                        .bind(&row.0)
+                        .execute(&state.db)
+                        .await;
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Dropping {} scrobble(s) for user {}: {}", batch.len(), batch[0].1, e);
+                    for row in batch {
+                        let _ = sqlx::query(r#"DELETE FROM "scrobble_queue" WHERE id = ?"#)
+                            .bind(&row.0)
+                            .execute(&state.db)
+                            .await;
+                    }
+                }
+            }
+        }
+    }
+}
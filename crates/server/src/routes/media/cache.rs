@@ -0,0 +1,232 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+
+use super::yt_dlp_path;
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::routes::whitelist::require_admin;
+use crate::AppState;
+
+const CACHE_EXT: &str = "webm";
+const DOWNLOAD_TIMEOUT_SECS: u64 = 180;
+
+fn cached_path(dir: &str, video_id: &str) -> PathBuf {
+    FsPath::new(dir).join(format!("{}.{}", video_id, CACHE_EXT))
+}
+
+fn cache_enabled(state: &AppState) -> bool {
+    state.config.youtube_cache_max_bytes > 0
+}
+
+/// Cached files, oldest access first.
+async fn cached_files(dir: &str) -> Vec<(PathBuf, u64, SystemTime)> {
+    let mut files = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return files;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(CACHE_EXT) {
+            continue;
+        }
+        if let Ok(meta) = entry.metadata().await {
+            files.push((path, meta.len(), meta.modified().unwrap_or(SystemTime::UNIX_EPOCH)));
+        }
+    }
+    files.sort_by_key(|(_, _, modified)| *modified);
+    files
+}
+
+/// Drop least recently played files until the cache fits in `max_bytes`.
+async fn evict(dir: &str, max_bytes: u64) {
+    let files = cached_files(dir).await;
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+    for (path, len, _) in files {
+        if total <= max_bytes {
+            break;
+        }
+        if tokio::fs::remove_file(&path).await.is_ok() {
+            total -= len;
+        }
+    }
+}
+
+/// Path of the cached audio if present. Bumps its mtime, which is what LRU
+/// eviction orders by.
+pub(super) async fn lookup(state: &AppState, video_id: &str) -> Option<PathBuf> {
+    if !cache_enabled(state) {
+        return None;
+    }
+    let path = cached_path(&state.config.youtube_cache_dir, video_id);
+    let touch = path.clone();
+    tokio::task::spawn_blocking(move || {
+        std::fs::File::options().write(true).open(&touch)?.set_modified(SystemTime::now())
+    })
+    .await
+    .ok()?
+    .ok()?;
+    Some(path)
+}
+
+/// Download a video's audio into the cache in the background. Returns false
+/// if caching is disabled; a download already in flight is not started twice.
+pub(super) async fn start_download(state: Arc<AppState>, video_id: String) -> bool {
+    if !cache_enabled(&state) {
+        return false;
+    }
+    if !state.youtube_downloads.write().await.insert(video_id.clone()) {
+        return true;
+    }
+
+    tokio::spawn(async move {
+        let dir = state.config.youtube_cache_dir.clone();
+        let dest = cached_path(&dir, &video_id);
+        let part = dest.with_extension("part");
+        let yt_url = format!("https://www.youtube.com/watch?v={}", video_id);
+
+        let result = async {
+            tokio::fs::create_dir_all(&dir).await.map_err(|e| e.to_string())?;
+            let output = tokio::time::timeout(
+                Duration::from_secs(DOWNLOAD_TIMEOUT_SECS),
+                tokio::process::Command::new(yt_dlp_path())
+                    .args(["-f", "bestaudio[ext=webm]", "--no-part", "--no-playlist", "--no-warnings", "-o"])
+                    .arg(&part)
+                    .arg(&yt_url)
+                    .output(),
+            )
+            .await
+            .map_err(|_| "yt-dlp download timed out".to_string())?
+            .map_err(|e| format!("Failed to run yt-dlp: {}", e))?;
+            if !output.status.success() {
+                return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+            }
+            tokio::fs::rename(&part, &dest).await.map_err(|e| e.to_string())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                tracing::info!("YouTube cache: stored {}", video_id);
+                evict(&dir, state.config.youtube_cache_max_bytes).await;
+            }
+            Err(e) => {
+                tracing::warn!("YouTube cache: download failed for {}: {}", video_id, e);
+                let _ = tokio::fs::remove_file(&part).await;
+            }
+        }
+        state.youtube_downloads.write().await.remove(&video_id);
+    });
+    true
+}
+
+/// Parse a single `bytes=` range against a file of `len` bytes into an inclusive (start, end).
+fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    if len == 0 {
+        return None;
+    }
+    let spec = value.strip_prefix("bytes=")?.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = if start.is_empty() {
+        // Suffix range: the last N bytes
+        let n: u64 = end.parse().ok()?;
+        (len - n.min(len), len - 1)
+    } else {
+        let start: u64 = start.parse().ok()?;
+        let end = if end.is_empty() { len - 1 } else { end.parse::<u64>().ok()?.min(len - 1) };
+        (start, end)
+    };
+    (start <= end && end < len).then_some((start, end))
+}
+
+/// Serve a cached file, honouring a Range header.
+pub(super) async fn serve_cached(path: &FsPath, headers: &HeaderMap) -> Response {
+    let Ok(mut file) = tokio::fs::File::open(path).await else {
+        return (StatusCode::NOT_FOUND, "Not cached").into_response();
+    };
+    let len = file.metadata().await.map(|m| m.len()).unwrap_or(0);
+
+    let range = headers.get(header::RANGE).and_then(|v| v.to_str().ok());
+    let Some(range) = range else {
+        return (
+            [
+                (header::CONTENT_TYPE, "audio/webm".to_string()),
+                (header::CONTENT_LENGTH, len.to_string()),
+                (header::ACCEPT_RANGES, "bytes".to_string()),
+            ],
+            Body::from_stream(ReaderStream::new(file)),
+        )
+            .into_response();
+    };
+
+    let Some((start, end)) = parse_range(range, len) else {
+        return (
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            [(header::CONTENT_RANGE, format!("bytes */{}", len))],
+        )
+            .into_response();
+    };
+
+    if file.seek(std::io::SeekFrom::Start(start)).await.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    let body = Body::from_stream(ReaderStream::new(file.take(end - start + 1)));
+    (
+        StatusCode::PARTIAL_CONTENT,
+        [
+            (header::CONTENT_TYPE, "audio/webm".to_string()),
+            (header::CONTENT_LENGTH, (end - start + 1).to_string()),
+            (header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len)),
+            (header::ACCEPT_RANGES, "bytes".to_string()),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// GET /api/youtube/cache
+/// Admin only.
+pub async fn cache_stats(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    require_admin(&state, &user.id).await?;
+
+    let files = cached_files(&state.config.youtube_cache_dir).await;
+    let downloading = state.youtube_downloads.read().await.len();
+    Ok(Json(serde_json::json!({
+        "files": files.len(),
+        "bytes": files.iter().map(|(_, len, _)| len).sum::<u64>(),
+        "maxBytes": state.config.youtube_cache_max_bytes,
+        "downloading": downloading,
+    })))
+}
+
+/// DELETE /api/youtube/cache
+/// Admin only. Downloads in flight are left alone.
+pub async fn purge_cache(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    require_admin(&state, &user.id).await?;
+
+    let mut removed = 0;
+    let mut freed: u64 = 0;
+    for (path, len, _) in cached_files(&state.config.youtube_cache_dir).await {
+        if tokio::fs::remove_file(&path).await.is_ok() {
+            removed += 1;
+            freed += len;
+        }
+    }
+    tracing::info!("YouTube cache purged by {}: {} files, {} bytes", user.id, removed, freed);
+    Ok(Json(serde_json::json!({"removed": removed, "freedBytes": freed})))
+}
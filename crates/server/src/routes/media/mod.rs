@@ -1,3 +1,9 @@
+mod cache;
+mod sources;
+
+pub use cache::{cache_stats, purge_cache};
+pub use sources::{MediaSource, MediaTrack};
+
 use axum::{
     body::Body,
     extract::{Path, Query, State},
@@ -5,10 +11,11 @@ use axum::{
     response::IntoResponse,
     Json,
 };
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use crate::error::ApiError;
 use crate::models::AuthUser;
 use crate::AppState;
 
@@ -16,7 +23,7 @@ const CACHE_TTL_SECS: u64 = 30 * 60; // 30 minutes
 
 /// Resolve the yt-dlp binary path. Checks next to the server executable first,
 /// then falls back to bare "yt-dlp" (relies on PATH).
-fn yt_dlp_path() -> std::path::PathBuf {
+pub(crate) fn yt_dlp_path() -> std::path::PathBuf {
     if let Ok(exe) = std::env::current_exe() {
         if let Some(dir) = exe.parent() {
             // Walk up from e.g. target/debug/ to project root
@@ -38,34 +45,40 @@ fn yt_dlp_path() -> std::path::PathBuf {
 #[derive(Deserialize)]
 pub struct SearchQuery {
     pub q: Option<String>,
+    /// youtube (default), soundcloud or bandcamp
+    pub source: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct YouTubeTrack {
-    pub id: String,
-    pub title: String,
-    pub channel: String,
-    pub thumbnail: String,
-    pub duration_ms: i64,
-}
-
-/// GET /api/youtube/search?q=...
+/// GET /api/media/search?q=...&source=
+/// Also served at the older GET /api/youtube/search, where source defaults to youtube.
+/// A pasted track URL for the source is resolved directly instead of searched.
 pub async fn search(
     _user: AuthUser,
     Query(query): Query<SearchQuery>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
+    let source_name = query.source.as_deref().unwrap_or("youtube");
+    let Some(source) = MediaSource::parse(source_name) else {
+        return Err(ApiError::bad_request("Unknown media source"));
+    };
+
     let q = match query.q.as_deref() {
         Some(q) if !q.trim().is_empty() => q.trim().to_string(),
-        _ => return Json(serde_json::json!({"tracks": []})).into_response(),
+        _ => return Ok(Json(serde_json::json!({"tracks": []})).into_response()),
     };
 
-    let search_query = format!("ytsearch5:{}", q);
-    tracing::info!("YouTube search: q=\"{}\"", q);
+    let target = match (source.id_from_url(&q), source.search_prefix()) {
+        (Some(id), _) => source.page_url(&id),
+        (None, Some(prefix)) => format!("{}{}", prefix, q),
+        (None, None) => {
+            return Err(ApiError::bad_request(format!("{} search is not supported, paste a track URL", source.as_str())));
+        }
+    };
+
+    tracing::info!("Media search: source={} q=\"{}\"", source.as_str(), q);
     let output = match tokio::time::timeout(
         Duration::from_secs(15),
         tokio::process::Command::new(yt_dlp_path())
-            .args(["--dump-json", "--flat-playlist", "--no-warnings", &search_query])
+            .args(["--dump-json", "--flat-playlist", "--no-warnings", &target])
             .output(),
     )
     .await
@@ -74,60 +87,58 @@ pub async fn search(
         Ok(Ok(o)) => {
             let stderr = String::from_utf8_lossy(&o.stderr);
             tracing::error!("yt-dlp search failed (exit {}): {}", o.status, stderr);
-            return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": format!("YouTube search failed: {}", stderr.chars().take(200).collect::<String>())}))).into_response();
+            return Err(ApiError::internal(format!("Search failed: {}", stderr.chars().take(200).collect::<String>())));
         }
         Ok(Err(e)) => {
             tracing::error!("Failed to run yt-dlp: {}", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": format!("yt-dlp not available: {}", e)}))).into_response();
+            return Err(ApiError::internal(format!("yt-dlp not available: {}", e)));
         }
         Err(_) => {
             tracing::error!("yt-dlp search timed out after 15s for q=\"{}\"", q);
-            return (StatusCode::GATEWAY_TIMEOUT, Json(serde_json::json!({"error": "YouTube search timed out"}))).into_response();
+            return Err(ApiError::upstream_timeout("Search timed out"));
         }
     };
 
     let stdout = String::from_utf8_lossy(&output);
-    let tracks: Vec<YouTubeTrack> = stdout
+    let tracks: Vec<MediaTrack> = stdout
         .lines()
         .filter_map(|line| {
             let v: serde_json::Value = serde_json::from_str(line).ok()?;
-            Some(YouTubeTrack {
-                id: v["id"].as_str()?.to_string(),
-                title: v["title"].as_str().unwrap_or("Unknown").to_string(),
-                channel: v["channel"].as_str()
-                    .or_else(|| v["uploader"].as_str())
-                    .unwrap_or("Unknown")
-                    .to_string(),
-                thumbnail: v["thumbnail"].as_str()
-                    .or_else(|| v["thumbnails"].as_array()?.last()?.get("url")?.as_str())
-                    .unwrap_or("")
-                    .to_string(),
-                duration_ms: v["duration"].as_f64().map(|d| (d * 1000.0) as i64).unwrap_or(0),
-            })
+            MediaTrack::from_yt_dlp(source, &v)
         })
         .collect();
 
-    tracing::info!("YouTube search: q=\"{}\" results={}", q, tracks.len());
-    Json(serde_json::json!({"tracks": tracks})).into_response()
+    tracing::info!("Media search: source={} q=\"{}\" results={}", source.as_str(), q, tracks.len());
+    Ok(Json(serde_json::json!({"tracks": tracks})).into_response())
+}
+
+/// Key into `youtube_url_cache`, which holds resolved URLs for every source.
+/// YouTube keeps the bare video id.
+fn url_cache_key(source: MediaSource, id: &str) -> String {
+    match source {
+        MediaSource::YouTube => id.to_string(),
+        _ => format!("{}:{}", source.as_str(), id),
+    }
 }
 
-/// Resolve the direct audio stream URL for a video, using cache.
-async fn resolve_audio_url(state: &AppState, video_id: &str) -> Result<String, String> {
+/// Resolve the direct audio stream URL for a track, using cache.
+async fn resolve_audio_url(state: &AppState, source: MediaSource, id: &str) -> Result<String, String> {
+    let key = url_cache_key(source, id);
     // Check cache
     {
         let cache = state.youtube_url_cache.read().await;
-        if let Some((url, fetched_at)) = cache.get(video_id) {
+        if let Some((url, fetched_at)) = cache.get(&key) {
             if fetched_at.elapsed().as_secs() < CACHE_TTL_SECS {
                 return Ok(url.clone());
             }
         }
     }
 
-    let yt_url = format!("https://www.youtube.com/watch?v={}", video_id);
+    let page_url = source.page_url(id);
     let output = tokio::time::timeout(
         Duration::from_secs(15),
         tokio::process::Command::new(yt_dlp_path())
-            .args(["-f", "bestaudio", "--get-url", "--no-warnings", &yt_url])
+            .args(["-f", source.format(), "--get-url", "--no-warnings", &page_url])
             .output(),
     )
     .await
@@ -139,7 +150,7 @@ async fn resolve_audio_url(state: &AppState, video_id: &str) -> Result<String, S
         return Err(format!("yt-dlp failed: {}", stderr));
     }
 
-    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let url = String::from_utf8_lossy(&output.stdout).lines().next().unwrap_or("").trim().to_string();
     if url.is_empty() {
         return Err("yt-dlp returned empty URL".to_string());
     }
@@ -147,7 +158,7 @@ async fn resolve_audio_url(state: &AppState, video_id: &str) -> Result<String, S
     // Cache it
     {
         let mut cache = state.youtube_url_cache.write().await;
-        cache.insert(video_id.to_string(), (url.clone(), Instant::now()));
+        cache.insert(key, (url.clone(), Instant::now()));
     }
 
     Ok(url)
@@ -166,6 +177,31 @@ pub async fn stream_audio(
     Query(query): Query<AudioQuery>,
     headers: HeaderMap,
 ) -> impl IntoResponse {
+    stream_source(state, MediaSource::YouTube, video_id, query, headers).await
+}
+
+/// GET /api/media/audio/{source}/{*id}
+/// Same as the YouTube endpoint for any source; SoundCloud and Bandcamp ids
+/// are `artist/track` paths.
+pub async fn stream_media_audio(
+    State(state): State<Arc<AppState>>,
+    Path((source, id)): Path<(String, String)>,
+    Query(query): Query<AudioQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let Some(source) = MediaSource::parse(&source) else {
+        return (StatusCode::BAD_REQUEST, "Unknown media source").into_response();
+    };
+    stream_source(state, source, id, query, headers).await
+}
+
+async fn stream_source(
+    state: Arc<AppState>,
+    source: MediaSource,
+    id: String,
+    query: AudioQuery,
+    headers: HeaderMap,
+) -> axum::response::Response {
     // Validate auth — check Authorization header or query token
     let token = headers
         .get(header::AUTHORIZATION)
@@ -177,15 +213,23 @@ pub async fn stream_audio(
         return (StatusCode::UNAUTHORIZED, "Authentication required").into_response();
     }
 
-    // Validate video ID (alphanumeric + dash/underscore, max 20 chars)
-    if !video_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') || video_id.len() > 20 {
-        return (StatusCode::BAD_REQUEST, "Invalid video ID").into_response();
+    if !source.validate_id(&id) {
+        return (StatusCode::BAD_REQUEST, "Invalid track ID").into_response();
+    }
+
+    // Only YouTube audio is kept on disk
+    if source == MediaSource::YouTube {
+        if let Some(path) = cache::lookup(&state, &id).await {
+            return cache::serve_cached(&path, &headers).await;
+        }
+        // Fill the cache for next time; this play still streams from YouTube
+        cache::start_download(state.clone(), id.clone()).await;
     }
 
-    let audio_url = match resolve_audio_url(&state, &video_id).await {
+    let audio_url = match resolve_audio_url(&state, source, &id).await {
         Ok(url) => url,
         Err(e) => {
-            tracing::error!("Failed to resolve audio URL for {}: {}", video_id, e);
+            tracing::error!("Failed to resolve {} audio URL for {}: {}", source.as_str(), id, e);
             return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to get audio stream").into_response();
         }
     };
@@ -203,7 +247,7 @@ pub async fn stream_audio(
             tracing::error!("Failed to fetch audio stream: {}", e);
             // Invalidate cache on failure
             let mut cache = state.youtube_url_cache.write().await;
-            cache.remove(&video_id);
+            cache.remove(&url_cache_key(source, &id));
             return (StatusCode::BAD_GATEWAY, "Failed to fetch audio").into_response();
         }
     };
@@ -215,7 +259,7 @@ pub async fn stream_audio(
     if let Some(ct) = upstream.headers().get(header::CONTENT_TYPE) {
         response_headers.insert(header::CONTENT_TYPE, ct.clone());
     } else {
-        response_headers.insert(header::CONTENT_TYPE, "audio/webm".parse().unwrap());
+        response_headers.insert(header::CONTENT_TYPE, source.fallback_content_type().parse().unwrap());
     }
     if let Some(cl) = upstream.headers().get(header::CONTENT_LENGTH) {
         response_headers.insert(header::CONTENT_LENGTH, cl.clone());
@@ -233,3 +277,21 @@ pub async fn stream_audio(
 
     (axum_status, response_headers, body).into_response()
 }
+
+/// POST /api/youtube/prefetch/{videoId}
+/// Start caching a track's audio ahead of playback (e.g. when it's queued).
+pub async fn prefetch_audio(
+    State(state): State<Arc<AppState>>,
+    _user: AuthUser,
+    Path(video_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    if !MediaSource::YouTube.validate_id(&video_id) {
+        return Err(ApiError::bad_request("Invalid video ID"));
+    }
+
+    if cache::lookup(&state, &video_id).await.is_some() {
+        return Ok(Json(serde_json::json!({"cached": true, "downloading": false})).into_response());
+    }
+    let downloading = cache::start_download(state.clone(), video_id).await;
+    Ok(Json(serde_json::json!({"cached": false, "downloading": downloading})).into_response())
+}
@@ -0,0 +1,159 @@
+use serde::Serialize;
+
+/// Where a queued track's audio comes from. Everything except Spotify (which
+/// plays through the Web Playback SDK) is resolved and proxied via yt-dlp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaSource {
+    YouTube,
+    SoundCloud,
+    Bandcamp,
+}
+
+impl MediaSource {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "youtube" => Some(Self::YouTube),
+            "soundcloud" => Some(Self::SoundCloud),
+            "bandcamp" => Some(Self::Bandcamp),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::YouTube => "youtube",
+            Self::SoundCloud => "soundcloud",
+            Self::Bandcamp => "bandcamp",
+        }
+    }
+
+    /// yt-dlp search prefix. Bandcamp has no search extractor, so it only
+    /// resolves pasted track URLs.
+    pub fn search_prefix(self) -> Option<&'static str> {
+        match self {
+            Self::YouTube => Some("ytsearch5:"),
+            Self::SoundCloud => Some("scsearch5:"),
+            Self::Bandcamp => None,
+        }
+    }
+
+    /// yt-dlp format selector. Progressive HTTP is preferred so the stream can
+    /// be proxied with range requests (SoundCloud also offers HLS).
+    pub fn format(self) -> &'static str {
+        match self {
+            Self::YouTube => "bestaudio",
+            Self::SoundCloud | Self::Bandcamp => "bestaudio[protocol^=http]/bestaudio",
+        }
+    }
+
+    pub fn fallback_content_type(self) -> &'static str {
+        match self {
+            Self::YouTube => "audio/webm",
+            Self::SoundCloud | Self::Bandcamp => "audio/mpeg",
+        }
+    }
+
+    /// Track ids are what goes in a queue item's `trackUri`:
+    /// - YouTube: the video id
+    /// - SoundCloud: `artist/track`, the soundcloud.com path
+    /// - Bandcamp: `artist/track`, for artist.bandcamp.com/track/track
+    pub fn validate_id(self, id: &str) -> bool {
+        let slug = |s: &str| {
+            !s.is_empty() && s.len() <= 100 && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        };
+        match self {
+            Self::YouTube => slug(id) && id.len() <= 20,
+            Self::SoundCloud | Self::Bandcamp => {
+                matches!(id.split_once('/'), Some((artist, track)) if slug(artist) && slug(track))
+            }
+        }
+    }
+
+    /// Page URL handed to yt-dlp. Only call with an id that passed `validate_id`.
+    pub fn page_url(self, id: &str) -> String {
+        match self {
+            Self::YouTube => format!("https://www.youtube.com/watch?v={}", id),
+            Self::SoundCloud => format!("https://soundcloud.com/{}", id),
+            Self::Bandcamp => {
+                let (artist, track) = id.split_once('/').unwrap_or((id, ""));
+                format!("https://{}.bandcamp.com/track/{}", artist, track)
+            }
+        }
+    }
+
+    /// Reverse of `page_url`: recognise a pasted link to this source.
+    pub fn id_from_url(self, url: &str) -> Option<String> {
+        let rest = url
+            .trim()
+            .strip_prefix("https://")
+            .or_else(|| url.trim().strip_prefix("http://"))?;
+        let rest = rest.split(['?', '#']).next()?.trim_end_matches('/');
+        let id = match self {
+            Self::YouTube => {
+                let rest = rest.strip_prefix("www.").unwrap_or(rest);
+                if let Some(id) = rest.strip_prefix("youtu.be/") {
+                    id.to_string()
+                } else {
+                    rest.strip_prefix("youtube.com/watch")?;
+                    let query = url.split_once('?')?.1;
+                    query.split('&').find_map(|kv| kv.strip_prefix("v="))?.to_string()
+                }
+            }
+            Self::SoundCloud => {
+                let rest = rest.strip_prefix("www.").unwrap_or(rest);
+                rest.strip_prefix("soundcloud.com/")?.to_string()
+            }
+            Self::Bandcamp => {
+                let (host, path) = rest.split_once('/')?;
+                let artist = host.strip_suffix(".bandcamp.com")?;
+                let track = path.strip_prefix("track/")?;
+                format!("{}/{}", artist, track)
+            }
+        };
+        self.validate_id(&id).then_some(id)
+    }
+}
+
+/// A search result, the same shape for every source.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaTrack {
+    pub id: String,
+    pub title: String,
+    /// Uploader / artist.
+    pub channel: String,
+    pub thumbnail: String,
+    pub duration_ms: i64,
+    pub source: &'static str,
+}
+
+impl MediaTrack {
+    /// Build from one line of `yt-dlp --dump-json` output.
+    pub fn from_yt_dlp(source: MediaSource, v: &serde_json::Value) -> Option<Self> {
+        let id = match source {
+            MediaSource::YouTube => v["id"].as_str()?.to_string(),
+            // Flat search results only carry the page URL
+            _ => v["webpage_url"]
+                .as_str()
+                .or_else(|| v["url"].as_str())
+                .and_then(|u| source.id_from_url(u))?,
+        };
+        Some(Self {
+            id,
+            title: v["title"].as_str().unwrap_or("Unknown").to_string(),
+            channel: v["channel"]
+                .as_str()
+                .or_else(|| v["uploader"].as_str())
+                .or_else(|| v["artist"].as_str())
+                .unwrap_or("Unknown")
+                .to_string(),
+            thumbnail: v["thumbnail"]
+                .as_str()
+                .or_else(|| v["thumbnails"].as_array()?.last()?.get("url")?.as_str())
+                .unwrap_or("")
+                .to_string(),
+            duration_ms: v["duration"].as_f64().map(|d| (d * 1000.0) as i64).unwrap_or(0),
+            source: source.as_str(),
+        })
+    }
+}
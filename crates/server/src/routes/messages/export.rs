@@ -0,0 +1,269 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::IntoResponse,
+};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{Attachment, AuthUser};
+use crate::routes::files::stored_file_path;
+use crate::AppState;
+
+/// Newest messages a transcript can hold; older ones are left out.
+const MAX_EXPORT_MESSAGES: i64 = 20_000;
+/// Images up to this size are inlined when embedding; bigger ones link to
+/// the file route.
+const MAX_EMBED_IMAGE_BYTES: i64 = 5 * 1024 * 1024;
+const MAX_EMBED_TOTAL_BYTES: i64 = 50 * 1024 * 1024;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportQuery {
+    /// Inline images as data URIs so the file works offline
+    #[serde(default)]
+    pub embed_images: bool,
+}
+
+#[derive(sqlx::FromRow)]
+struct ExportMessage {
+    id: String,
+    author: String,
+    content: String,
+    created_at: String,
+    edited_at: Option<String>,
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc))
+}
+
+const STYLE: &str = r#"
+body { margin: 0; background: #1e1f22; color: #dbdee1; font: 15px/1.4 system-ui, sans-serif; }
+header { padding: 16px 24px; border-bottom: 1px solid #3f4147; }
+header h1 { margin: 0; font-size: 20px; }
+header p, footer { margin: 4px 0 0; color: #949ba4; font-size: 13px; }
+main { padding: 8px 24px 24px; }
+.day { margin: 20px 0 8px; color: #949ba4; font-size: 12px; font-weight: 600; border-bottom: 1px solid #3f4147; }
+.msg { padding: 4px 0; }
+.author { font-weight: 600; color: #f2f3f5; }
+.time { margin-left: 8px; color: #949ba4; font-size: 12px; }
+.content { white-space: pre-wrap; word-wrap: break-word; }
+.attachment img { display: block; max-width: 480px; max-height: 360px; margin-top: 4px; border-radius: 4px; }
+.attachment a { color: #00a8fc; }
+.reactions span { display: inline-block; margin: 4px 4px 0 0; padding: 0 6px; border-radius: 8px; background: #2b2d31; font-size: 13px; }
+footer { padding: 0 24px 24px; }
+"#;
+
+/// POST /api/channels/:channelId/export?embedImages=
+/// Download a text channel as a self-contained HTML transcript, oldest
+/// message first. Images either link to the file routes or, with
+/// `embedImages`, are inlined. Server admins only.
+pub async fn export_channel_transcript(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(channel_id): Path<String>,
+    Query(query): Query<ExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (channel_name, channel_type, server_id, server_name) = sqlx::query_as::<_, (String, String, String, String)>(
+        r#"SELECT c.name, c.type, c.server_id, s.name FROM channels c
+           INNER JOIN servers s ON s.id = c.server_id
+           WHERE c.id = ?"#,
+    )
+    .bind(&channel_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Channel not found"))?;
+
+    let role = sqlx::query_scalar::<_, String>("SELECT role FROM memberships WHERE user_id = ? AND server_id = ?")
+        .bind(&user.id)
+        .bind(&server_id)
+        .fetch_optional(&state.db)
+        .await?;
+    if !matches!(role.as_deref(), Some("owner") | Some("admin")) {
+        return Err(ApiError::forbidden("Insufficient permissions"));
+    }
+    if channel_type != "text" {
+        return Err(ApiError::bad_request("Only text channels can be exported"));
+    }
+
+    let mut messages = sqlx::query_as::<_, ExportMessage>(
+        r#"SELECT m.id, COALESCE(ms.nickname, u.username, 'Deleted user') AS author,
+                  m.content, m.created_at, m.edited_at
+           FROM messages m
+           LEFT JOIN "user" u ON u.id = m.sender_id
+           LEFT JOIN memberships ms ON ms.user_id = m.sender_id AND ms.server_id = ?
+           WHERE m.channel_id = ?
+           ORDER BY m.created_at DESC, m.id DESC
+           LIMIT ?"#,
+    )
+    .bind(&server_id)
+    .bind(&channel_id)
+    .bind(MAX_EXPORT_MESSAGES + 1)
+    .fetch_all(&state.db_read)
+    .await?;
+    let truncated = messages.len() as i64 > MAX_EXPORT_MESSAGES;
+    messages.truncate(MAX_EXPORT_MESSAGES as usize);
+    messages.reverse();
+
+    let mut attachments: HashMap<String, Vec<Attachment>> = HashMap::new();
+    for attachment in sqlx::query_as::<_, Attachment>(
+        r#"SELECT a.* FROM attachments a
+           INNER JOIN messages m ON m.id = a.message_id
+           WHERE m.channel_id = ?
+           ORDER BY a.created_at ASC"#,
+    )
+    .bind(&channel_id)
+    .fetch_all(&state.db_read)
+    .await?
+    {
+        if let Some(message_id) = attachment.message_id.clone() {
+            attachments.entry(message_id).or_default().push(attachment);
+        }
+    }
+
+    let mut reactions: HashMap<String, Vec<(String, i64)>> = HashMap::new();
+    for (message_id, emoji, count) in sqlx::query_as::<_, (String, String, i64)>(
+        r#"SELECT r.message_id, r.emoji, COUNT(*) FROM reactions r
+           INNER JOIN messages m ON m.id = r.message_id
+           WHERE m.channel_id = ?
+           GROUP BY r.message_id, r.emoji
+           ORDER BY MIN(r.created_at) ASC"#,
+    )
+    .bind(&channel_id)
+    .fetch_all(&state.db_read)
+    .await?
+    {
+        reactions.entry(message_id).or_default().push((emoji, count));
+    }
+
+    let file_base = format!("{}/api/files", state.config.public_url.trim_end_matches('/'));
+    let mut embed_budget = if query.embed_images { MAX_EMBED_TOTAL_BYTES } else { 0 };
+    let now = Utc::now();
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!(
+        "<title>#{} - {}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        escape_html(&channel_name),
+        escape_html(&server_name),
+        STYLE
+    ));
+    html.push_str(&format!(
+        "<header><h1>#{}</h1><p>{} &middot; {} message{} &middot; exported {}</p></header>\n<main>\n",
+        escape_html(&channel_name),
+        escape_html(&server_name),
+        messages.len(),
+        if messages.len() == 1 { "" } else { "s" },
+        now.format("%Y-%m-%d %H:%M UTC")
+    ));
+
+    let mut last_day = String::new();
+    for message in &messages {
+        let time = parse_time(&message.created_at);
+        let day = time.map(|t| t.format("%A, %B %-d, %Y").to_string()).unwrap_or_default();
+        if day != last_day {
+            html.push_str(&format!("<div class=\"day\">{}</div>\n", escape_html(&day)));
+            last_day = day;
+        }
+
+        html.push_str(&format!(
+            "<div class=\"msg\" id=\"m-{}\"><span class=\"author\">{}</span><time class=\"time\" datetime=\"{}\">{}{}</time>",
+            escape_html(&message.id),
+            escape_html(&message.author),
+            escape_html(&message.created_at),
+            time.map(|t| t.format("%H:%M UTC").to_string()).unwrap_or_default(),
+            if message.edited_at.is_some() { " (edited)" } else { "" }
+        ));
+        if !message.content.is_empty() {
+            html.push_str(&format!("<div class=\"content\">{}</div>", escape_html(&message.content)));
+        }
+
+        for attachment in attachments.get(&message.id).map(Vec::as_slice).unwrap_or_default() {
+            let url = format!(
+                "{}/{}/{}",
+                file_base,
+                attachment.id,
+                urlencoding::encode(&attachment.filename)
+            );
+            if !attachment.content_type.starts_with("image/") {
+                html.push_str(&format!(
+                    "<div class=\"attachment\"><a href=\"{}\">{}</a></div>",
+                    escape_html(&url),
+                    escape_html(&attachment.filename)
+                ));
+                continue;
+            }
+
+            let mut src = url;
+            if attachment.size <= MAX_EMBED_IMAGE_BYTES && attachment.size <= embed_budget {
+                let path = stored_file_path(&state.config.upload_dir, &attachment.id, &attachment.filename);
+                if let Ok(bytes) = tokio::fs::read(&path).await {
+                    embed_budget -= bytes.len() as i64;
+                    src = format!(
+                        "data:{};base64,{}",
+                        attachment.content_type,
+                        base64::engine::general_purpose::STANDARD.encode(&bytes)
+                    );
+                }
+            }
+            html.push_str(&format!(
+                "<div class=\"attachment\"><img src=\"{}\" alt=\"{}\" loading=\"lazy\"></div>",
+                escape_html(&src),
+                escape_html(&attachment.filename)
+            ));
+        }
+
+        if let Some(reactions) = reactions.get(&message.id) {
+            html.push_str("<div class=\"reactions\">");
+            for (emoji, count) in reactions {
+                html.push_str(&format!("<span>{} {}</span>", escape_html(emoji), count));
+            }
+            html.push_str("</div>");
+        }
+        html.push_str("</div>\n");
+    }
+
+    html.push_str("</main>\n");
+    if truncated {
+        html.push_str(&format!(
+            "<footer>Only the latest {} messages are included.</footer>\n",
+            MAX_EXPORT_MESSAGES
+        ));
+    }
+    html.push_str("</body>\n</html>\n");
+
+    let slug: String = channel_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/html; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}-{}.html\"", slug, now.format("%Y-%m-%d")),
+            ),
+        ],
+        html,
+    ))
+}
@@ -1,17 +1,27 @@
+mod export;
+mod read_state;
+mod reminders;
+mod retention;
+mod saved;
 mod search;
 
+pub use export::*;
+pub use read_state::*;
+pub use reminders::*;
+pub use retention::*;
+pub use saved::*;
 pub use search::*;
 
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use crate::models::{Attachment, AuthUser, Message, Reaction};
+use crate::error::ApiError;
+use crate::models::{Attachment, AuthUser, Message, Reaction, ReactionSummary};
 use crate::AppState;
 
 #[derive(Debug, Serialize)]
@@ -21,14 +31,33 @@ pub(super) struct MessageWithAttachments {
     message: Message,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     attachments: Vec<Attachment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    reactions: Vec<ReactionSummary>,
 }
 
+/// Reactors listed per emoji in a reaction summary.
+const REACTION_SAMPLE_SIZE: i64 = 3;
+/// Messages one reaction request can ask about.
+const MAX_REACTION_IDS: usize = 100;
+
+/// At most one of `cursor` (older than), `after` (newer than), `around`
+/// (a message id) and `date` (YYYY-MM-DD) picks where the page sits; with
+/// none it holds the newest messages.
 #[derive(Deserialize)]
 pub struct MessageQuery {
     pub cursor: Option<String>,
+    pub after: Option<String>,
+    pub around: Option<String>,
+    pub date: Option<String>,
     pub limit: Option<i64>,
 }
 
+#[derive(Deserialize)]
+pub struct MessageContextQuery {
+    /// Messages to include on each side of the requested one
+    pub context: Option<i64>,
+}
+
 #[derive(Deserialize)]
 pub struct ReactionQuery {
     pub ids: Option<String>,
@@ -46,6 +75,7 @@ pub(super) fn attach_to_messages(
             MessageWithAttachments {
                 message: msg,
                 attachments,
+                reactions: Vec::new(),
             }
         })
         .collect()
@@ -80,34 +110,162 @@ pub(super) async fn fetch_attachment_map(
     attachment_map
 }
 
+/// Per-emoji reaction counts for a list of messages, ordered by message and
+/// then by when each emoji was first used. Messages in servers the caller
+/// isn't in are skipped.
+pub(super) async fn fetch_reaction_summaries(
+    db: &sqlx::SqlitePool,
+    user_id: &str,
+    message_ids: &[&str],
+) -> Result<Vec<ReactionSummary>, sqlx::Error> {
+    if message_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders = vec!["?"; message_ids.len()].join(",");
+    let sql = format!(
+        r#"SELECT r.message_id, r.emoji, COUNT(*) AS count, MAX(r.user_id = ?) AS me,
+               (SELECT group_concat(user_id) FROM (
+                   SELECT s.user_id FROM reactions s
+                   WHERE s.message_id = r.message_id AND s.emoji = r.emoji
+                   ORDER BY s.created_at ASC LIMIT ?
+               )) AS sample
+           FROM reactions r
+           INNER JOIN messages m ON m.id = r.message_id
+           INNER JOIN channels c ON c.id = m.channel_id
+           INNER JOIN memberships ms ON ms.server_id = c.server_id AND ms.user_id = ?
+           WHERE r.message_id IN ({})
+           GROUP BY r.message_id, r.emoji
+           ORDER BY r.message_id, MIN(r.created_at) ASC"#,
+        placeholders
+    );
+    let mut query = sqlx::query_as::<_, (String, String, i64, bool, Option<String>)>(&sql)
+        .bind(user_id)
+        .bind(REACTION_SAMPLE_SIZE)
+        .bind(user_id);
+    for id in message_ids {
+        query = query.bind(id);
+    }
+    let rows = query.fetch_all(db).await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(message_id, emoji, count, me, sample)| ReactionSummary {
+            message_id,
+            emoji,
+            count,
+            me,
+            sample_user_ids: sample
+                .map(|s| s.split(',').map(str::to_string).collect())
+                .unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Fill in each message's reaction summaries.
+pub(super) async fn attach_reactions(
+    db: &sqlx::SqlitePool,
+    user_id: &str,
+    items: &mut [MessageWithAttachments],
+) -> Result<(), ApiError> {
+    let ids: Vec<&str> = items.iter().map(|m| m.message.id.as_str()).collect();
+    let mut by_message: std::collections::HashMap<String, Vec<ReactionSummary>> =
+        std::collections::HashMap::new();
+    for summary in fetch_reaction_summaries(db, user_id, &ids).await? {
+        by_message.entry(summary.message_id.clone()).or_default().push(summary);
+    }
+    for item in items {
+        item.reactions = by_message.remove(&item.message.id).unwrap_or_default();
+    }
+    Ok(())
+}
+
+/// Split a comma-separated `ids` query parameter, capped at
+/// `MAX_REACTION_IDS`.
+fn parse_reaction_ids(query: &ReactionQuery) -> Result<Vec<String>, ApiError> {
+    let ids: Vec<String> = query
+        .ids
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect();
+    if ids.len() > MAX_REACTION_IDS {
+        return Err(ApiError::bad_request(format!(
+            "Ask about at most {} messages at a time",
+            MAX_REACTION_IDS
+        )));
+    }
+    Ok(ids)
+}
+
+/// Which kind of message an id belongs to, once the caller is known to be
+/// able to read it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MessageKind {
+    Channel,
+    Dm,
+}
+
+/// Find a channel message or DM by id. Messages the caller can't read are
+/// reported as missing, so ids can't be probed.
+pub(crate) async fn locate_visible_message(
+    state: &AppState,
+    user_id: &str,
+    message_id: &str,
+) -> Result<MessageKind, ApiError> {
+    let in_channel = sqlx::query_scalar::<_, String>(
+        r#"SELECT m.id FROM messages m
+           INNER JOIN channels c ON c.id = m.channel_id
+           INNER JOIN memberships ms ON ms.server_id = c.server_id AND ms.user_id = ?
+           WHERE m.id = ?"#,
+    )
+    .bind(user_id)
+    .bind(message_id)
+    .fetch_optional(&state.db_read)
+    .await?;
+    if in_channel.is_some() {
+        return Ok(MessageKind::Channel);
+    }
+
+    let in_dm = sqlx::query_scalar::<_, String>(
+        r#"SELECT dm.id FROM dm_messages dm
+           INNER JOIN dm_channels c ON c.id = dm.dm_channel_id
+           WHERE dm.id = ? AND (c.user1_id = ? OR c.user2_id = ?)"#,
+    )
+    .bind(message_id)
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_optional(&state.db_read)
+    .await?;
+    match in_dm {
+        Some(_) => Ok(MessageKind::Dm),
+        None => Err(ApiError::not_found("Message not found")),
+    }
+}
+
 /// GET /api/channels/:channelId/messages
 pub async fn list_messages(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path(channel_id): Path<String>,
     Query(query): Query<MessageQuery>,
-) -> impl IntoResponse {
-    let limit = query.limit.unwrap_or(50).min(100);
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
 
     // Verify access: channel exists and user is a member of its server
     let server_id = sqlx::query_scalar::<_, String>(
         "SELECT server_id FROM channels WHERE id = ?",
     )
     .bind(&channel_id)
-    .fetch_optional(&state.db)
+    .fetch_optional(&state.db_read)
     .await
     .ok()
     .flatten();
 
     let server_id = match server_id {
         Some(s) => s,
-        None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({"error": "Channel not found"})),
-            )
-                .into_response()
-        }
+        None => return Err(ApiError::not_found("Channel not found")),
     };
 
     let is_member = sqlx::query_scalar::<_, i64>(
@@ -115,76 +273,213 @@ pub async fn list_messages(
     )
     .bind(&user.id)
     .bind(&server_id)
-    .fetch_one(&state.db)
+    .fetch_one(&state.db_read)
     .await
     .unwrap_or(0);
 
     if is_member == 0 {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({"error": "Not a member of this server"})),
-        )
-            .into_response();
+        return Err(ApiError::forbidden("Not a member of this server"));
     }
 
-    let items = if let Some(cursor) = &query.cursor {
-        sqlx::query_as::<_, Message>(
-            "SELECT * FROM messages WHERE channel_id = ? AND created_at < ? ORDER BY created_at DESC LIMIT ?",
+    let modes = [&query.cursor, &query.after, &query.around, &query.date];
+    if modes.iter().filter(|m| m.is_some()).count() > 1 {
+        return Err(ApiError::bad_request("Use only one of cursor, after, around and date"));
+    }
+
+    // Older messages come back newest first and newer ones oldest first;
+    // each fetch asks for one extra row to learn whether there's more.
+    let (mut older, mut newer) = if let Some(cursor) = &query.cursor {
+        (fetch_older(&state, &channel_id, Some(cursor), limit + 1).await?, Vec::new())
+    } else if let Some(after) = &query.after {
+        (Vec::new(), fetch_newer(&state, &channel_id, after, false, limit + 1).await?)
+    } else if query.around.is_some() || query.date.is_some() {
+        let anchor = match (&query.around, &query.date) {
+            (Some(message_id), _) => sqlx::query_scalar::<_, String>(
+                "SELECT created_at FROM messages WHERE id = ? AND channel_id = ?",
+            )
+            .bind(message_id)
+            .bind(&channel_id)
+            .fetch_optional(&state.db_read)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Message not found"))?,
+            // Timestamps are RFC 3339, so the bare date sorts before any
+            // message sent that day
+            (None, Some(date)) => chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| ApiError::bad_request("date must be YYYY-MM-DD"))?
+                .format("%Y-%m-%d")
+                .to_string(),
+            (None, None) => unreachable!(),
+        };
+        (
+            fetch_older(&state, &channel_id, Some(&anchor), limit + 1).await?,
+            fetch_newer(&state, &channel_id, &anchor, true, limit + 1).await?,
         )
-        .bind(&channel_id)
-        .bind(cursor)
-        .bind(limit + 1)
-        .fetch_all(&state.db)
-        .await
-        .unwrap_or_default()
     } else {
-        sqlx::query_as::<_, Message>(
-            "SELECT * FROM messages WHERE channel_id = ? ORDER BY created_at DESC LIMIT ?",
-        )
-        .bind(&channel_id)
-        .bind(limit + 1)
-        .fetch_all(&state.db)
-        .await
-        .unwrap_or_default()
+        (fetch_older(&state, &channel_id, None, limit + 1).await?, Vec::new())
     };
 
-    let has_more = items.len() as i64 > limit;
-    let mut items = items;
-    if has_more {
-        items.pop();
-    }
-    items.reverse(); // chronological order
+    // Split the page evenly around an anchor, letting either side fill in
+    // when the other runs out
+    let older_len = older.len().min((limit as usize / 2).max((limit as usize).saturating_sub(newer.len())));
+    let newer_len = newer.len().min(limit as usize - older_len);
+    // A cursor page was read from a newer one, and an after page continues
+    // an older one
+    let has_more = older.len() > older_len || query.after.is_some();
+    let has_more_after = newer.len() > newer_len || query.cursor.is_some();
+    older.truncate(older_len);
+    newer.truncate(newer_len);
+
+    older.reverse(); // chronological order
+    let mut items = older;
+    items.append(&mut newer);
 
     let cursor = items.first().map(|m| m.created_at.clone());
+    let after_cursor = items.last().map(|m| m.created_at.clone());
 
-    let attachment_map = fetch_attachment_map(&state.db, &items).await;
-    let items_with_attachments = attach_to_messages(items, attachment_map);
+    let attachment_map = fetch_attachment_map(&state.db_read, &items).await;
+    let mut items_with_attachments = attach_to_messages(items, attachment_map);
+    attach_reactions(&state.db_read, &user.id, &mut items_with_attachments).await?;
 
-    Json(serde_json::json!({
+    Ok(Json(serde_json::json!({
         "items": items_with_attachments,
         "cursor": cursor,
         "hasMore": has_more,
+        "afterCursor": after_cursor,
+        "hasMoreAfter": has_more_after,
     }))
-    .into_response()
+    .into_response())
+}
+
+/// Up to `limit` messages sent before `before` (or the newest ones),
+/// newest first.
+async fn fetch_older(
+    state: &AppState,
+    channel_id: &str,
+    before: Option<&str>,
+    limit: i64,
+) -> Result<Vec<Message>, ApiError> {
+    let items = match before {
+        Some(before) => {
+            sqlx::query_as::<_, Message>(
+                "SELECT * FROM messages WHERE channel_id = ? AND created_at < ? ORDER BY created_at DESC LIMIT ?",
+            )
+            .bind(channel_id)
+            .bind(before)
+            .bind(limit)
+            .fetch_all(&state.db_read)
+            .await?
+        }
+        None => {
+            sqlx::query_as::<_, Message>(
+                "SELECT * FROM messages WHERE channel_id = ? ORDER BY created_at DESC LIMIT ?",
+            )
+            .bind(channel_id)
+            .bind(limit)
+            .fetch_all(&state.db_read)
+            .await?
+        }
+    };
+    Ok(items)
+}
+
+/// Up to `limit` messages sent after `from` (or at it, when `inclusive`),
+/// oldest first.
+async fn fetch_newer(
+    state: &AppState,
+    channel_id: &str,
+    from: &str,
+    inclusive: bool,
+    limit: i64,
+) -> Result<Vec<Message>, ApiError> {
+    let op = if inclusive { ">=" } else { ">" };
+    let sql = format!(
+        "SELECT * FROM messages WHERE channel_id = ? AND created_at {} ? ORDER BY created_at ASC LIMIT ?",
+        op
+    );
+    Ok(sqlx::query_as::<_, Message>(&sql)
+        .bind(channel_id)
+        .bind(from)
+        .bind(limit)
+        .fetch_all(&state.db_read)
+        .await?)
+}
+
+/// GET /api/messages/:messageId?context=N
+/// One channel message with its attachments and reaction counts, plus up to N
+/// messages either side of it, for permalinks and jump-to-message.
+pub async fn get_message(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(message_id): Path<String>,
+    Query(query): Query<MessageContextQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let context = query.context.unwrap_or(25).clamp(0, 50);
+
+    let message = sqlx::query_as::<_, Message>(
+        r#"SELECT m.* FROM messages m
+           INNER JOIN channels c ON c.id = m.channel_id
+           INNER JOIN memberships ms ON ms.server_id = c.server_id AND ms.user_id = ?
+           WHERE m.id = ?"#,
+    )
+    .bind(&user.id)
+    .bind(&message_id)
+    .fetch_optional(&state.db_read)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Message not found"))?;
+
+    let mut before = sqlx::query_as::<_, Message>(
+        r#"SELECT * FROM messages WHERE channel_id = ? AND (created_at, id) < (?, ?)
+           ORDER BY created_at DESC, id DESC LIMIT ?"#,
+    )
+    .bind(&message.channel_id)
+    .bind(&message.created_at)
+    .bind(&message.id)
+    .bind(context + 1)
+    .fetch_all(&state.db_read)
+    .await?;
+    let has_more_before = before.len() as i64 > context;
+    before.truncate(context as usize);
+    before.reverse(); // chronological order
+
+    let mut after = sqlx::query_as::<_, Message>(
+        r#"SELECT * FROM messages WHERE channel_id = ? AND (created_at, id) > (?, ?)
+           ORDER BY created_at ASC, id ASC LIMIT ?"#,
+    )
+    .bind(&message.channel_id)
+    .bind(&message.created_at)
+    .bind(&message.id)
+    .bind(context + 1)
+    .fetch_all(&state.db_read)
+    .await?;
+    let has_more_after = after.len() as i64 > context;
+    after.truncate(context as usize);
+
+    let all: Vec<Message> = before.iter().chain(std::iter::once(&message)).chain(&after).cloned().collect();
+    let attachment_map = fetch_attachment_map(&state.db_read, &all).await;
+    let mut items = attach_to_messages(all, attachment_map);
+    attach_reactions(&state.db_read, &user.id, &mut items).await?;
+    let after_items = items.split_off(before.len() + 1);
+    let message = items.pop();
+
+    Ok(Json(serde_json::json!({
+        "message": message,
+        "before": items,
+        "after": after_items,
+        "hasMoreBefore": has_more_before,
+        "hasMoreAfter": has_more_after,
+    })))
 }
 
-/// GET /api/messages/reactions
+/// GET /api/messages/reactions?ids=
+/// Raw reaction rows; prefer the summary for anything user-facing.
 pub async fn get_reactions(
     State(state): State<Arc<AppState>>,
     _user: AuthUser,
     Query(query): Query<ReactionQuery>,
-) -> impl IntoResponse {
-    let ids: Vec<String> = query
-        .ids
-        .as_deref()
-        .unwrap_or("")
-        .split(',')
-        .filter(|s| !s.is_empty())
-        .map(|s| s.to_string())
-        .collect();
-
+) -> Result<impl IntoResponse, ApiError> {
+    let ids = parse_reaction_ids(&query)?;
     if ids.is_empty() {
-        return Json(Vec::<Reaction>::new()).into_response();
+        return Ok(Json(Vec::<Reaction>::new()));
     }
 
     let placeholders: Vec<String> = ids.iter().map(|_| "?".to_string()).collect();
@@ -201,5 +496,18 @@ pub async fn get_reactions(
 
     let items = query_builder.fetch_all(&state.db).await.unwrap_or_default();
 
-    Json(items).into_response()
+    Ok(Json(items))
+}
+
+/// GET /api/messages/reactions/summary?ids=
+/// Per-emoji counts for each message, with a few sample reactors.
+pub async fn get_reaction_summaries(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(query): Query<ReactionQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let ids = parse_reaction_ids(&query)?;
+    let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+    let summaries = fetch_reaction_summaries(&state.db_read, &user.id, &ids).await?;
+    Ok(Json(summaries))
 }
@@ -0,0 +1,71 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::AppState;
+
+async fn mark_read(state: &AppState, user_id: &str, channel_id: &str) -> Result<(), ApiError> {
+    sqlx::query(
+        r#"INSERT INTO read_states (user_id, channel_id, last_read_at) VALUES (?, ?, ?)
+           ON CONFLICT(user_id, channel_id) DO UPDATE SET last_read_at = excluded.last_read_at"#,
+    )
+    .bind(user_id)
+    .bind(channel_id)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&state.db)
+    .await?;
+    Ok(())
+}
+
+/// PUT /api/channels/:channelId/read — everything in the channel up to now
+/// has been seen. Email digests leave read messages out.
+pub async fn mark_channel_read(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(channel_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let visible = sqlx::query_scalar::<_, i64>(
+        r#"SELECT COUNT(*) FROM channels c
+           INNER JOIN memberships ms ON ms.server_id = c.server_id AND ms.user_id = ?
+           WHERE c.id = ?"#,
+    )
+    .bind(&user.id)
+    .bind(&channel_id)
+    .fetch_one(&state.db)
+    .await?
+        > 0;
+    if !visible {
+        return Err(ApiError::not_found("Channel not found"));
+    }
+
+    mark_read(&state, &user.id, &channel_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// PUT /api/dms/:dmChannelId/read
+pub async fn mark_dm_read(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(dm_channel_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let participant = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM dm_channels WHERE id = ? AND (user1_id = ? OR user2_id = ?)",
+    )
+    .bind(&dm_channel_id)
+    .bind(&user.id)
+    .bind(&user.id)
+    .fetch_one(&state.db)
+    .await?
+        > 0;
+    if !participant {
+        return Err(ApiError::forbidden("Not a participant"));
+    }
+
+    mark_read(&state, &user.id, &dm_channel_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
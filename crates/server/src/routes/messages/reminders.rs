@@ -0,0 +1,208 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, MessageReminder};
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+use super::{locate_visible_message, MessageKind};
+
+/// Pending reminders one user can have at a time.
+const MAX_PENDING_REMINDERS: i64 = 100;
+const MAX_NOTE_CHARS: usize = 200;
+const MIN_DELAY_SECS: i64 = 60;
+const MAX_DELAY_DAYS: i64 = 365;
+
+/// Give either `delaySeconds` or `remindAt`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateReminderRequest {
+    pub delay_seconds: Option<i64>,
+    pub remind_at: Option<String>,
+    pub note: Option<String>,
+}
+
+const REMINDER_SELECT: &str = r#"
+    SELECT
+        r.id,
+        COALESCE(r.message_id, r.dm_message_id) AS message_id,
+        c.server_id,
+        m.channel_id,
+        dm.dm_channel_id,
+        r.note, r.remind_at, r.created_at, r.fired_at
+    FROM message_reminders r
+    LEFT JOIN messages m ON m.id = r.message_id
+    LEFT JOIN channels c ON c.id = m.channel_id
+    LEFT JOIN dm_messages dm ON dm.id = r.dm_message_id
+"#;
+
+/// POST /api/messages/:messageId/remind
+pub async fn create_message_reminder(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(message_id): Path<String>,
+    Json(body): Json<CreateReminderRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let kind = locate_visible_message(&state, &user.id, &message_id).await?;
+
+    let now = Utc::now();
+    let remind_at = match (body.delay_seconds, body.remind_at.as_deref()) {
+        (Some(delay), None) => now + chrono::Duration::seconds(delay),
+        (None, Some(at)) => DateTime::parse_from_rfc3339(at.trim())
+            .map_err(|_| ApiError::bad_request("remindAt must be an RFC 3339 timestamp"))?
+            .with_timezone(&Utc),
+        _ => return Err(ApiError::bad_request("Give either delaySeconds or remindAt")),
+    };
+    if remind_at < now + chrono::Duration::seconds(MIN_DELAY_SECS) {
+        return Err(ApiError::bad_request("A reminder must be at least a minute away"));
+    }
+    if remind_at > now + chrono::Duration::days(MAX_DELAY_DAYS) {
+        return Err(ApiError::bad_request("A reminder can be at most a year away"));
+    }
+
+    let note = body.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    if note.is_some_and(|n| n.chars().count() > MAX_NOTE_CHARS) {
+        return Err(ApiError::bad_request(format!("Note must be at most {} characters", MAX_NOTE_CHARS)));
+    }
+
+    let pending: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM message_reminders WHERE user_id = ? AND fired_at IS NULL")
+            .bind(&user.id)
+            .fetch_one(&state.db)
+            .await?;
+    if pending >= MAX_PENDING_REMINDERS {
+        return Err(ApiError::bad_request(format!(
+            "You can have at most {} pending reminders",
+            MAX_PENDING_REMINDERS
+        )));
+    }
+
+    let (channel_message, dm_message) = match kind {
+        MessageKind::Channel => (Some(&message_id), None),
+        MessageKind::Dm => (None, Some(&message_id)),
+    };
+    let id = uuid::Uuid::new_v4().to_string();
+    sqlx::query(
+        r#"INSERT INTO message_reminders (id, user_id, message_id, dm_message_id, note, remind_at, created_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(&id)
+    .bind(&user.id)
+    .bind(channel_message)
+    .bind(dm_message)
+    .bind(note)
+    .bind(remind_at.to_rfc3339_opts(SecondsFormat::Secs, true))
+    .bind(now.to_rfc3339())
+    .execute(&state.db)
+    .await?;
+
+    let reminder = sqlx::query_as::<_, MessageReminder>(&format!("{} WHERE r.id = ?", REMINDER_SELECT))
+        .bind(&id)
+        .fetch_one(&state.db)
+        .await?;
+    Ok((StatusCode::CREATED, Json(reminder)))
+}
+
+/// GET /api/users/me/reminders
+/// Pending reminders soonest first, then ones that already went off.
+pub async fn list_my_reminders(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    let reminders = sqlx::query_as::<_, MessageReminder>(&format!(
+        "{} WHERE r.user_id = ? ORDER BY r.fired_at IS NOT NULL, r.remind_at ASC",
+        REMINDER_SELECT
+    ))
+    .bind(&user.id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(reminders))
+}
+
+/// DELETE /api/users/me/reminders/:reminderId
+/// Cancels a pending reminder, or dismisses one that already went off.
+pub async fn delete_my_reminder(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(reminder_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let deleted = sqlx::query("DELETE FROM message_reminders WHERE id = ? AND user_id = ?")
+        .bind(&reminder_id)
+        .bind(&user.id)
+        .execute(&state.db)
+        .await?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(ApiError::not_found("Reminder not found"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Fire every reminder that's due: mark it and tell the user's clients. A
+/// user who is offline sees it in their reminder list. Returns how many fired.
+pub async fn dispatch_due_reminders(state: &AppState) -> usize {
+    let now = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+    let due = match sqlx::query_as::<_, (String, String)>(
+        "SELECT id, user_id FROM message_reminders WHERE fired_at IS NULL AND remind_at <= ?",
+    )
+    .bind(&now)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::warn!("Failed to load due reminders: {}", e);
+            return 0;
+        }
+    };
+
+    let mut fired = 0;
+    for (id, user_id) in due {
+        let claimed = sqlx::query("UPDATE message_reminders SET fired_at = ? WHERE id = ? AND fired_at IS NULL")
+            .bind(&now)
+            .bind(&id)
+            .execute(&state.db)
+            .await
+            .map(|r| r.rows_affected() > 0)
+            .unwrap_or(false);
+        if !claimed {
+            continue;
+        }
+
+        let reminder = sqlx::query_as::<_, MessageReminder>(&format!("{} WHERE r.id = ?", REMINDER_SELECT))
+            .bind(&id)
+            .fetch_optional(&state.db)
+            .await
+            .ok()
+            .flatten();
+        if let Some(reminder) = reminder {
+            state
+                .gateway
+                .send_to_user(&user_id, &ServerEvent::ReminderDue { reminder })
+                .await;
+            fired += 1;
+        }
+    }
+
+    fired
+}
+
+/// Background task: fire due message reminders.
+pub async fn run_reminder_dispatcher(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(15));
+    loop {
+        interval.tick().await;
+        dispatch_due_reminders(&state).await;
+    }
+}
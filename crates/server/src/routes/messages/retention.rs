@@ -0,0 +1,149 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::ApiError;
+use crate::routes::files::stored_file_path;
+use crate::AppState;
+
+/// Longest retention a server or channel can be set to (about ten years).
+pub const MAX_RETENTION_DAYS: i64 = 3650;
+/// Messages deleted per statement, so a first purge of a large backlog
+/// doesn't hold the write lock for long.
+const PURGE_BATCH: i64 = 500;
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Read a `retentionDays` value from a PATCH body: null clears it, otherwise
+/// it must be a whole number of days. Channels may also use 0 to keep their
+/// messages forever whatever the server says.
+pub(crate) fn parse_retention_days(value: &serde_json::Value, allow_zero: bool) -> Result<Option<i64>, ApiError> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    let min = if allow_zero { 0 } else { 1 };
+    match value.as_i64() {
+        Some(days) if (min..=MAX_RETENTION_DAYS).contains(&days) => Ok(Some(days)),
+        _ => Err(ApiError::bad_request(format!(
+            "retentionDays must be null or between {} and {}",
+            min, MAX_RETENTION_DAYS
+        ))),
+    }
+}
+
+/// Channel messages past their channel's retention (or the server's, when
+/// the channel doesn't override it).
+const EXPIRED_MESSAGES: &str = r#"
+    SELECT m.id FROM messages m
+    INNER JOIN channels c ON c.id = m.channel_id
+    INNER JOIN servers s ON s.id = c.server_id
+    WHERE COALESCE(c.retention_days, s.retention_days, 0) > 0
+      AND julianday(m.created_at) < julianday('now', '-' || COALESCE(c.retention_days, s.retention_days) || ' days')
+    LIMIT ?
+"#;
+
+/// Delete one batch of expired channel messages with their search rows,
+/// reactions and attachments. Returns how many messages went.
+async fn purge_message_batch(state: &AppState) -> Result<u64, sqlx::Error> {
+    let ids = sqlx::query_scalar::<_, String>(EXPIRED_MESSAGES)
+        .bind(PURGE_BATCH)
+        .fetch_all(&state.db_write)
+        .await?;
+    if ids.is_empty() {
+        return Ok(0);
+    }
+    let placeholders = vec!["?"; ids.len()].join(",");
+
+    let sql = format!("SELECT id, filename FROM attachments WHERE message_id IN ({})", placeholders);
+    let mut files = sqlx::query_as::<_, (String, String)>(&sql);
+    for id in &ids {
+        files = files.bind(id);
+    }
+    let files = files.fetch_all(&state.db_write).await?;
+
+    let mut tx = state.db_write.begin().await?;
+    let mut deleted = 0;
+    for (table, column) in [
+        ("messages_fts", "message_id"),
+        ("reactions", "message_id"),
+        ("attachments", "message_id"),
+        ("messages", "id"),
+    ] {
+        let sql = format!("DELETE FROM {} WHERE {} IN ({})", table, column, placeholders);
+        let mut query = sqlx::query(&sql);
+        for id in &ids {
+            query = query.bind(id);
+        }
+        deleted = query.execute(&mut *tx).await?.rows_affected();
+    }
+    tx.commit().await?;
+
+    for (id, filename) in files {
+        let path = stored_file_path(&state.config.upload_dir, &id, &filename);
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove purged attachment {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    Ok(deleted)
+}
+
+/// Delete one batch of DMs older than the instance-wide DM retention.
+async fn purge_dm_batch(state: &AppState) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        r#"DELETE FROM dm_messages WHERE id IN (
+               SELECT id FROM dm_messages WHERE julianday(created_at) < julianday('now', '-' || ? || ' days') LIMIT ?
+           )"#,
+    )
+    .bind(state.config.dm_retention_days as i64)
+    .bind(PURGE_BATCH)
+    .execute(&state.db_write)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+/// Delete every message past its retention, batch by batch. Returns how
+/// many channel messages and DMs were deleted.
+pub async fn purge_expired_messages(state: &AppState) -> (u64, u64) {
+    let mut messages = 0;
+    loop {
+        match purge_message_batch(state).await {
+            Ok(0) => break,
+            Ok(n) => messages += n,
+            Err(e) => {
+                tracing::warn!("Message retention purge failed: {}", e);
+                break;
+            }
+        }
+        tokio::task::yield_now().await;
+    }
+
+    let mut dms = 0;
+    if state.config.dm_retention_days > 0 {
+        loop {
+            match purge_dm_batch(state).await {
+                Ok(0) => break,
+                Ok(n) => dms += n,
+                Err(e) => {
+                    tracing::warn!("DM retention purge failed: {}", e);
+                    break;
+                }
+            }
+            tokio::task::yield_now().await;
+        }
+    }
+
+    (messages, dms)
+}
+
+/// Background task: enforce retention policies once an hour.
+pub async fn run_retention_purge(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(PURGE_INTERVAL);
+    loop {
+        interval.tick().await;
+        let (messages, dms) = purge_expired_messages(&state).await;
+        if messages > 0 || dms > 0 {
+            tracing::info!("Retention purge deleted {} message(s) and {} DM(s)", messages, dms);
+        }
+    }
+}
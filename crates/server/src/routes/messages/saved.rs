@@ -0,0 +1,137 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, SavedMessage};
+use crate::routes::pagination::{self, Cursor, PageQuery};
+use crate::AppState;
+
+use super::{locate_visible_message, MessageKind};
+
+/// Messages one user can have saved at a time.
+const MAX_SAVED_MESSAGES: i64 = 1000;
+
+/// Saved messages with their channel or DM context. Channel messages from
+/// servers the user has since left are hidden.
+const SAVED_SELECT: &str = r#"
+    SELECT
+        s.id,
+        COALESCE(s.message_id, s.dm_message_id) AS message_id,
+        COALESCE(m.sender_id, dm.sender_id) AS sender_id,
+        c.server_id,
+        m.channel_id,
+        c.name AS channel_name,
+        m.content,
+        dm.dm_channel_id,
+        dm.ciphertext,
+        dm.mls_epoch,
+        COALESCE(m.created_at, dm.created_at) AS message_created_at,
+        s.created_at AS saved_at
+    FROM saved_messages s
+    LEFT JOIN messages m ON m.id = s.message_id
+    LEFT JOIN channels c ON c.id = m.channel_id
+    LEFT JOIN dm_messages dm ON dm.id = s.dm_message_id
+    WHERE s.user_id = ?
+      AND (s.message_id IS NULL OR EXISTS (
+          SELECT 1 FROM memberships ms WHERE ms.server_id = c.server_id AND ms.user_id = s.user_id
+      ))
+"#;
+
+/// PUT /api/messages/:messageId/save
+/// Saving an already saved message is a no-op.
+pub async fn save_message(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(message_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let kind = locate_visible_message(&state, &user.id, &message_id).await?;
+
+    let saved: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM saved_messages WHERE user_id = ? AND message_id IS NOT ? AND dm_message_id IS NOT ?",
+    )
+    .bind(&user.id)
+    .bind(&message_id)
+    .bind(&message_id)
+    .fetch_one(&state.db)
+    .await?;
+    if saved >= MAX_SAVED_MESSAGES {
+        return Err(ApiError::bad_request(format!(
+            "You can have at most {} saved messages",
+            MAX_SAVED_MESSAGES
+        )));
+    }
+
+    let (channel_message, dm_message) = match kind {
+        MessageKind::Channel => (Some(&message_id), None),
+        MessageKind::Dm => (None, Some(&message_id)),
+    };
+    sqlx::query(
+        r#"INSERT OR IGNORE INTO saved_messages (id, user_id, message_id, dm_message_id, created_at)
+           VALUES (?, ?, ?, ?, ?)"#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(&user.id)
+    .bind(channel_message)
+    .bind(dm_message)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&state.db)
+    .await?;
+
+    let saved = sqlx::query_as::<_, SavedMessage>(&format!(
+        "{} AND (s.message_id = ? OR s.dm_message_id = ?)",
+        SAVED_SELECT
+    ))
+    .bind(&user.id)
+    .bind(&message_id)
+    .bind(&message_id)
+    .fetch_one(&state.db)
+    .await?;
+    Ok(Json(saved))
+}
+
+/// DELETE /api/messages/:messageId/save
+pub async fn unsave_message(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(message_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    sqlx::query("DELETE FROM saved_messages WHERE user_id = ? AND (message_id = ? OR dm_message_id = ?)")
+        .bind(&user.id)
+        .bind(&message_id)
+        .bind(&message_id)
+        .execute(&state.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/users/me/saved?cursor=&limit=
+/// Most recently saved first.
+pub async fn list_saved_messages(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(query): Query<PageQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = query.limit();
+    let after = query.after()?;
+
+    let mut sql = SAVED_SELECT.to_string();
+    if after.is_some() {
+        sql.push_str(" AND ");
+        sql.push_str(&pagination::after_condition("s.created_at", "s.id", true));
+    }
+    sql.push_str(" ORDER BY s.created_at DESC, s.id DESC LIMIT ?");
+
+    let mut saved = sqlx::query_as::<_, SavedMessage>(&sql).bind(&user.id);
+    if let Some(after) = &after {
+        saved = saved.bind(&after.key).bind(&after.id);
+    }
+    let saved = saved.bind(limit + 1).fetch_all(&state.db_read).await?;
+
+    Ok(Json(pagination::page(saved, limit, |s| Cursor::new(&s.saved_at, &s.id))))
+}
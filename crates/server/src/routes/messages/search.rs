@@ -1,12 +1,12 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::{AuthUser, Message};
 use crate::AppState;
 
@@ -30,16 +30,10 @@ pub async fn search_messages(
     user: AuthUser,
     Path(channel_id): Path<String>,
     Query(query): Query<SearchQuery>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let search_query = match query.q.as_deref() {
         Some(q) if !q.trim().is_empty() => q.trim().to_string(),
-        _ => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": "Missing query"})),
-            )
-                .into_response()
-        }
+        _ => return Err(ApiError::bad_request("Missing query")),
     };
 
     // Verify access
@@ -47,20 +41,14 @@ pub async fn search_messages(
         "SELECT server_id FROM channels WHERE id = ?",
     )
     .bind(&channel_id)
-    .fetch_optional(&state.db)
+    .fetch_optional(&state.db_read)
     .await
     .ok()
     .flatten();
 
     let server_id = match server_id {
         Some(s) => s,
-        None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({"error": "Channel not found"})),
-            )
-                .into_response()
-        }
+        None => return Err(ApiError::not_found("Channel not found")),
     };
 
     let is_member = sqlx::query_scalar::<_, i64>(
@@ -68,16 +56,12 @@ pub async fn search_messages(
     )
     .bind(&user.id)
     .bind(&server_id)
-    .fetch_one(&state.db)
+    .fetch_one(&state.db_read)
     .await
     .unwrap_or(0);
 
     if is_member == 0 {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({"error": "Not a member"})),
-        )
-            .into_response();
+        return Err(ApiError::forbidden("Not a member"));
     }
 
     // Sanitize query for FTS5: strip special chars, append * for prefix matching
@@ -95,7 +79,7 @@ pub async fn search_messages(
     )
     .bind(&fts_query)
     .bind(&channel_id)
-    .fetch_all(&state.db)
+    .fetch_all(&state.db_read)
     .await
     {
         Ok(rows) => rows,
@@ -105,7 +89,7 @@ pub async fn search_messages(
         }
     };
 
-    Json(serde_json::json!({"items": items})).into_response()
+    Ok(Json(serde_json::json!({"items": items})).into_response())
 }
 
 /// GET /api/servers/:serverId/messages/search
@@ -114,23 +98,19 @@ pub async fn search_server_messages(
     user: AuthUser,
     Path(server_id): Path<String>,
     Query(query): Query<SearchQuery>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     // Verify membership
     let is_member = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
     )
     .bind(&user.id)
     .bind(&server_id)
-    .fetch_one(&state.db)
+    .fetch_one(&state.db_read)
     .await
     .unwrap_or(0);
 
     if is_member == 0 {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({"error": "Not a member"})),
-        )
-            .into_response();
+        return Err(ApiError::forbidden("Not a member"));
     }
 
     let has_q = query.q.as_deref().map(|s| !s.trim().is_empty()).unwrap_or(false);
@@ -143,11 +123,7 @@ pub async fn search_server_messages(
         || query.after.is_some();
 
     if !has_q && !has_filters {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Provide a search query or at least one filter"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("Provide a search query or at least one filter"));
     }
 
     let fts_query: Option<String> = if has_q {
@@ -159,15 +135,11 @@ pub async fn search_server_messages(
     };
 
     if fts_query.is_none() && !has_filters {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Provide a search query or at least one filter"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("Provide a search query or at least one filter"));
     }
 
     let mut qb: sqlx::QueryBuilder<sqlx::Sqlite> = sqlx::QueryBuilder::new(
-        "SELECT m.id, m.channel_id, m.sender_id, m.content, m.created_at, m.edited_at \
+        "SELECT m.id, m.channel_id, m.sender_id, m.content, m.created_at, m.edited_at, m.metadata, m.feed_id, m.integration_id \
          FROM messages m \
          INNER JOIN channels c ON c.id = m.channel_id \
          WHERE c.server_id = ",
@@ -201,8 +173,20 @@ pub async fn search_server_messages(
     }
 
     if let Some(ref username) = query.mentions_username {
-        qb.push(" AND m.content LIKE ");
-        qb.push_bind(format!("%@{}%", username));
+        // Mentions written before a rename still count
+        let names = match crate::routes::users::resolve_username(&state.db_read, username).await? {
+            Some(user_id) => crate::routes::users::known_usernames(&state.db_read, &user_id).await?,
+            None => vec![username.clone()],
+        };
+        qb.push(" AND (");
+        for (i, name) in names.iter().enumerate() {
+            if i > 0 {
+                qb.push(" OR ");
+            }
+            qb.push("m.content LIKE ");
+            qb.push_bind(format!("%@{}%", name));
+        }
+        qb.push(")");
     }
 
     fn is_valid_date(s: &str) -> bool {
@@ -239,7 +223,7 @@ pub async fn search_server_messages(
 
     qb.push(" ORDER BY m.created_at DESC LIMIT 50");
 
-    let items: Vec<Message> = match qb.build_query_as::<Message>().fetch_all(&state.db).await {
+    let items: Vec<Message> = match qb.build_query_as::<Message>().fetch_all(&state.db_read).await {
         Ok(rows) => rows,
         Err(e) => {
             eprintln!("Search error: {:?}", e);
@@ -247,10 +231,10 @@ pub async fn search_server_messages(
         }
     };
 
-    let attachment_map = fetch_attachment_map(&state.db, &items).await;
+    let attachment_map = fetch_attachment_map(&state.db_read, &items).await;
     let items_with_attachments = attach_to_messages(items, attachment_map);
 
-    Json(serde_json::json!({"items": items_with_attachments})).into_response()
+    Ok(Json(serde_json::json!({"items": items_with_attachments})).into_response())
 }
 
 fn sanitize_fts_query(raw: &str) -> String {
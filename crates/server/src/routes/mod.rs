@@ -1,41 +1,97 @@
+pub mod admin;
 pub mod auth;
 pub mod dms;
+pub mod economy;
 pub mod emojis;
+pub mod events;
+pub mod feeds;
 pub mod files;
 pub mod gallery;
+pub mod games;
+pub mod health;
+pub mod integrations;
+pub mod gifs;
 pub mod keys;
+pub mod lastfm;
+pub mod media;
 pub mod messages;
+pub mod noise_models;
+pub mod pagination;
 pub mod roadmap;
 pub mod servers;
 pub mod soundboard;
 pub mod spotify;
+pub mod switcher;
 pub mod users;
 pub mod voice;
 pub mod whitelist;
-pub mod youtube;
 
+use crate::error::ApiError;
 use crate::ws;
 use crate::AppState;
-use axum::{extract::{DefaultBodyLimit, Path}, response::IntoResponse, routing::{get, post, patch, delete, put}, Router};
+use axum::{extract::{DefaultBodyLimit, State}, http::{header, HeaderMap}, response::IntoResponse, routing::{get, post, patch, delete, put}, Router};
 use std::sync::Arc;
 
 pub fn build_router(state: Arc<AppState>) -> Router {
+    crate::metrics::install();
+
     let auth_routes = Router::new()
         .route("/sign-up/email", post(auth::sign_up))
+        .route("/request-access", post(whitelist::request_access))
         .route("/sign-in/email", post(auth::sign_in))
         .route("/sign-out", post(auth::sign_out))
-        .route("/get-session", get(auth::get_session));
+        .route("/get-session", get(auth::get_session))
+        .route("/csrf", get(auth::get_csrf_token))
+        .route("/forgot-password", post(auth::forgot_password))
+        .route("/reset-password", post(auth::reset_password))
+        .route("/verify", get(auth::verify_email))
+        .route("/verify/resend", post(auth::resend_verification))
+        .route("/change-email", post(auth::change_email).delete(auth::cancel_email_change))
+        .route("/sessions", get(auth::list_sessions))
+        .route("/sessions/revoke-others", post(auth::revoke_other_sessions))
+        .route("/sessions/{id}", delete(auth::revoke_session))
+        .route("/oidc", delete(auth::oidc_unlink))
+        .route("/oidc/start", get(auth::oidc_start))
+        .route("/oidc/callback", get(auth::oidc_callback))
+        .route("/oidc/complete", post(auth::oidc_complete));
 
     let api_routes = Router::new()
         // Servers
         .route("/servers", get(servers::list_servers))
         .route("/servers/{serverId}", get(servers::get_server))
         .route("/servers/{serverId}", patch(servers::update_server))
+        .route("/servers/{serverId}/insights", get(servers::get_server_insights))
+        .route("/servers/{serverId}/roles", get(servers::list_roles))
+        .route("/servers/{serverId}/roles", post(servers::create_role))
+        .route("/servers/{serverId}/roles/{roleId}", delete(servers::delete_role))
+        .route("/servers/{serverId}/reaction-roles", get(servers::list_reaction_roles))
+        .route("/servers/{serverId}/reaction-roles", post(servers::create_reaction_role))
+        .route("/servers/{serverId}/reaction-roles/{bindingId}", delete(servers::delete_reaction_role))
+        .route("/servers/{serverId}/welcome", get(servers::get_welcome).put(servers::update_welcome).delete(servers::delete_welcome))
+        .route("/servers/{serverId}/welcome/accept", post(servers::accept_rules))
+        .route("/servers/{serverId}/verification", get(servers::get_verification).patch(servers::update_verification))
+        .route("/servers/{serverId}/verification/challenge", post(servers::submit_challenge))
         .route("/servers/{serverId}/members/me", delete(servers::leave_server))
+        .route("/servers/{serverId}/members/me/profile", patch(servers::update_my_member_profile))
+        .route("/servers/{serverId}/members/{userId}/nickname", patch(servers::set_member_nickname))
         .route("/servers/{serverId}/channels", get(servers::list_channels))
         .route("/servers/{serverId}/channels", post(servers::create_channel))
         .route("/servers/{serverId}/channels/{channelId}", patch(servers::update_channel))
         .route("/servers/{serverId}/channels/{channelId}", delete(servers::delete_channel))
+        .route("/servers/{serverId}/channels/{channelId}/feeds", get(feeds::list_feeds).post(feeds::create_feed))
+        .route("/servers/{serverId}/channels/{channelId}/feeds/{feedId}", delete(feeds::delete_feed))
+        .route("/servers/{serverId}/channels/{channelId}/integrations", get(integrations::list_integrations).post(integrations::create_integration))
+        .route("/servers/{serverId}/channels/{channelId}/integrations/{integrationId}", delete(integrations::revoke_integration))
+        .route("/servers/{serverId}/channels/{channelId}/integrations/{integrationId}/rotate", post(integrations::rotate_integration))
+        // Webhook deliveries; authenticated by the URL token and signature, not a session
+        .route("/integrations/github/{channelToken}", post(integrations::receive_github))
+        .route("/integrations/gitlab/{channelToken}", post(integrations::receive_gitlab))
+        // Channel feeds for feed readers; the feed documents are authenticated by `?token=`
+        .route("/channels/{channelId}/feed-tokens", get(feeds::list_feed_tokens).post(feeds::create_feed_token))
+        .route("/channels/{channelId}/feed-tokens/{tokenId}", delete(feeds::revoke_feed_token))
+        .route("/channels/{channelId}/feed.atom", get(feeds::channel_feed_atom))
+        .route("/channels/{channelId}/feed.rss", get(feeds::channel_feed_rss))
+        .route("/channels/{channelId}/feed.json", get(feeds::channel_feed_json))
         .route("/servers/{serverId}/channels/reorder", put(servers::reorder_channels))
         .route("/servers/{serverId}/rooms/{channelId}/accept-knock", post(servers::accept_knock))
         .route("/servers/{serverId}/rooms/{channelId}/invite", post(servers::invite_to_room))
@@ -43,36 +99,117 @@ pub fn build_router(state: Arc<AppState>) -> Router {
         .route("/servers/{serverId}/members", get(servers::list_members))
         // Role management
         .route("/members/{userId}/role", patch(servers::update_member_role))
+        // Instance administration
+        .route("/admin/stats", get(admin::get_stats))
+        .route("/admin/stats/messages", get(admin::get_message_stats))
+        .route("/admin/stats/online", get(admin::get_online_history))
+        .route("/admin/users", get(admin::list_users))
+        .route("/admin/users/{userId}/admin", put(admin::set_instance_admin))
+        .route("/admin/settings", get(admin::get_settings).patch(admin::update_settings))
+        .route("/admin/backups", get(admin::list_backups))
+        .route("/admin/backup", post(admin::create_backup))
+        .route("/admin/backups/{name}/restore", post(admin::restore_backup))
+        .route("/admin/voice-nodes", get(admin::list_voice_nodes_admin).post(admin::create_voice_node))
+        .route("/admin/voice-nodes/{nodeId}", patch(admin::update_voice_node).delete(admin::delete_voice_node))
+        .route("/admin/ip-bans", get(admin::list_ip_bans).post(admin::create_ip_ban))
+        .route("/admin/ip-bans/{banId}", delete(admin::delete_ip_ban))
         // Email whitelist
         .route("/whitelist", get(whitelist::list_whitelist))
         .route("/whitelist", post(whitelist::add_to_whitelist))
         .route("/whitelist/{id}", delete(whitelist::remove_from_whitelist))
+        .route("/whitelist/invites", get(whitelist::list_invites).post(whitelist::create_invite))
+        .route("/whitelist/invites/{id}", delete(whitelist::revoke_invite))
+        .route("/whitelist/requests", get(whitelist::list_access_requests))
+        .route("/whitelist/requests/{id}/approve", post(whitelist::approve_access_request))
+        .route("/whitelist/requests/{id}/deny", post(whitelist::deny_access_request))
         // Messages
         .route("/channels/{channelId}/messages", get(messages::list_messages))
         .route("/channels/{channelId}/messages/search", get(messages::search_messages))
+        .route("/channels/{channelId}/read", put(messages::mark_channel_read))
+        .route("/channels/{channelId}/export", post(messages::export_channel_transcript))
         .route("/servers/{serverId}/messages/search", get(messages::search_server_messages))
         .route("/messages/reactions", get(messages::get_reactions))
+        .route("/messages/reactions/summary", get(messages::get_reaction_summaries))
+        .route("/messages/{messageId}", get(messages::get_message))
+        .route("/messages/{messageId}/remind", post(messages::create_message_reminder))
+        .route("/messages/{messageId}/save", put(messages::save_message).delete(messages::unsave_message))
         // DMs
         .route("/dms", get(dms::list_dms))
         .route("/dms", post(dms::create_dm))
+        .route("/dms/requests", get(dms::list_dm_requests))
+        .route("/dms/{dmChannelId}/accept", post(dms::accept_dm_request))
+        .route("/dms/{dmChannelId}/decline", post(dms::decline_dm_request))
         .route("/dms/{dmChannelId}/messages", get(dms::list_dm_messages))
         .route("/dms/{dmChannelId}/messages/search", get(dms::search_dm_messages))
+        .route("/dms/{dmChannelId}/read", put(messages::mark_dm_read))
         .route("/users/search", get(dms::search_users))
+        .route("/quick-switcher", get(switcher::quick_switcher))
         // Users
         .route("/users/me", get(users::get_me))
         .route("/users/me", patch(users::update_me))
+        .route("/users/me/username", patch(users::update_username))
+        .route("/users/me/blocks", get(users::list_blocks))
+        .route("/users/me/blocks/{userId}", put(users::block_user).delete(users::unblock_user))
+        .route("/users/me/settings", get(users::list_settings))
+        .route("/users/me/settings/{key}", put(users::put_setting).delete(users::delete_setting))
+        .route("/users/me/digest", get(users::get_digest_settings).put(users::update_digest_settings))
+        .route("/digest/unsubscribe", get(users::unsubscribe_digest))
+        .route("/users/me/reminders", get(messages::list_my_reminders))
+        .route("/users/me/reminders/{reminderId}", delete(messages::delete_my_reminder))
+        .route("/users/me/saved", get(messages::list_saved_messages))
+        .route("/users/me/voice-history", get(voice::get_voice_history))
+        .route("/users/{userId}/profile", get(users::get_profile))
+        .route("/users/{userId}/login-attempts", get(auth::list_login_attempts))
+        .route("/users/{userId}/lockout", delete(auth::unlock_account))
         // E2EE Keys
         .route("/users/me/public-key", axum::routing::put(keys::set_public_key))
+        .route("/users/me/key-backup", get(keys::get_key_backup).put(keys::put_key_backup).delete(keys::delete_key_backup))
+        .route("/users/me/key-backup/restored", post(keys::key_backup_restored))
         .route("/users/{userId}/public-key", get(keys::get_public_key))
+        .route("/users/me/devices/{deviceId}", put(keys::register_device).delete(keys::remove_device))
+        .route("/users/{userId}/devices", get(keys::list_devices))
+        .route("/users/{userId}/safety-number", get(keys::get_safety_number))
+        .route("/users/{userId}/verification", put(keys::verify_key).delete(keys::unverify_key))
+        .route("/users/me/verifications", get(keys::list_verifications))
         .route("/servers/{serverId}/keys", post(keys::store_server_key))
         .route("/servers/{serverId}/keys/me", get(keys::get_my_server_key))
         .route("/servers/{serverId}/keys/{userId}", post(keys::share_server_key))
         // Voice
+        .route("/noise-models/version", get(noise_models::get_noise_model_version))
         .route("/voice/token", post(voice::get_token))
+        .route("/voice/nodes", get(voice::list_voice_nodes))
+        .route("/voice/latency", post(voice::report_latency))
+        .route("/channels/{channelId}/stage", get(voice::get_stage))
+        .route("/servers/{serverId}/voice/stats", get(voice::get_server_voice_stats))
+        .route("/servers/{serverId}/voice/quality", get(voice::get_server_voice_quality))
         // Files
         .route("/upload", post(files::upload))
         .route("/files/{id}/{filename}", get(files::serve_file))
         .route("/link-preview", get(files::link_preview))
+        // Economy
+        .route("/economy/wallet", get(economy::get_wallet))
+        .route("/economy/daily", post(economy::claim_daily_reward))
+        .route("/economy/rules", get(economy::list_rules))
+        .route("/economy/rules/{rule}", patch(economy::update_rule))
+        .route("/economy/admin/coins", post(economy::adjust_coins))
+        .route("/economy/admin/log/{userId}", get(economy::coin_log))
+        .route("/economy/servers/{serverId}", get(economy::get_server_economy))
+        .route("/economy/servers/{serverId}/pause", put(economy::pause_server_economy).delete(economy::resume_server_economy))
+        // Games
+        .route("/games/seed", get(games::get_seed))
+        .route("/games/seed/rotate", post(games::rotate_seed))
+        .route("/games/dice", post(games::play_dice))
+        .route("/games/coinflip", post(games::create_coinflip))
+        .route("/games/coinflip/{gameId}/accept", post(games::accept_coinflip))
+        .route("/games/coinflip/{gameId}/cancel", post(games::cancel_coinflip))
+        .route("/games/settings/{serverId}", get(games::get_settings).patch(games::update_settings))
+        .route("/games/{gameId}", get(games::get_game))
+        // Last.fm
+        .route("/lastfm/init-auth", post(lastfm::init_auth))
+        .route("/lastfm/callback", get(lastfm::lastfm_callback))
+        .route("/lastfm/status", get(lastfm::get_status))
+        .route("/lastfm/settings", patch(lastfm::update_settings))
+        .route("/lastfm/unlink", post(lastfm::unlink_lastfm))
         // Spotify
         .route("/spotify/auth-info", get(spotify::get_auth_info))
         .route("/spotify/init-auth", post(spotify::init_auth))
@@ -86,12 +223,31 @@ pub fn build_router(state: Arc<AppState>) -> Router {
         .route("/spotify/sessions/{sessionId}/queue", post(spotify::add_to_queue))
         .route("/spotify/sessions/{sessionId}/queue/{itemId}", delete(spotify::remove_from_queue))
         .route("/spotify/sessions/{sessionId}/end", delete(spotify::delete_session))
-        // YouTube
-        .route("/youtube/search", get(youtube::search))
-        .route("/youtube/audio/{videoId}", get(youtube::stream_audio))
+        .route("/spotify/sessions/{sessionId}/history", get(spotify::get_history))
+        .route("/spotify/sessions/{sessionId}/history/{historyId}/requeue", post(spotify::requeue_from_history))
+        .route("/spotify/sessions/{sessionId}/lyrics", get(spotify::get_lyrics))
+        .route("/spotify/sessions/{sessionId}/host", post(spotify::transfer_host))
+        .route("/spotify/sessions/{sessionId}/cohosts/{userId}", put(spotify::add_cohost).delete(spotify::remove_cohost))
+        // Media (YouTube, SoundCloud, Bandcamp via yt-dlp)
+        .route("/media/search", get(media::search))
+        .route("/media/audio/{source}/{*id}", get(media::stream_media_audio))
+        .route("/youtube/search", get(media::search))
+        .route("/youtube/audio/{videoId}", get(media::stream_audio))
+        .route("/youtube/prefetch/{videoId}", post(media::prefetch_audio))
+        .route("/youtube/cache", get(media::cache_stats).delete(media::purge_cache))
+        .route("/gifs/search", get(gifs::search))
+        // Scheduled events
+        .route("/servers/{serverId}/events", get(events::list_events).post(events::create_event))
+        .route("/servers/{serverId}/events.ics", get(events::export_events_ics))
+        .route("/servers/{serverId}/events/{eventId}", get(events::get_event).patch(events::update_event).delete(events::delete_event))
+        .route("/servers/{serverId}/events/{eventId}/rsvp", put(events::set_event_rsvp).delete(events::clear_event_rsvp))
+        .route("/servers/{serverId}/events/{eventId}/rsvps", get(events::list_event_rsvps))
         // Roadmap
         .route("/servers/{serverId}/roadmap", get(roadmap::list_roadmap_items).post(roadmap::create_roadmap_item))
         .route("/servers/{serverId}/roadmap/{itemId}", patch(roadmap::update_roadmap_item).delete(roadmap::delete_roadmap_item))
+        .route("/servers/{serverId}/roadmap/{itemId}/vote", post(roadmap::vote_roadmap_item).delete(roadmap::unvote_roadmap_item))
+        .route("/servers/{serverId}/roadmap/{itemId}/comments", get(roadmap::list_roadmap_comments).post(roadmap::create_roadmap_comment))
+        .route("/servers/{serverId}/roadmap/{itemId}/comments/{commentId}", delete(roadmap::delete_roadmap_comment))
         // Soundboard
         .route("/servers/{serverId}/soundboard", get(soundboard::list_sounds))
         .route("/servers/{serverId}/soundboard", post(soundboard::create_sound))
@@ -104,9 +260,15 @@ pub fn build_router(state: Arc<AppState>) -> Router {
         .route("/gallery/{setId}", get(gallery::get_gallery_set).patch(gallery::manage::update_gallery_set).delete(gallery::manage::delete_gallery_set))
         .route("/gallery/{setId}/subscribe", post(gallery::manage::subscribe).delete(gallery::manage::unsubscribe))
         .route("/gallery/{setId}/images", post(gallery::manage::add_images))
+        .route("/gallery/{setId}/images/order", put(gallery::manage::reorder_images))
         .route("/gallery/{setId}/images/{imageId}", delete(gallery::manage::remove_image))
+        .route("/gallery/{setId}/collaborators", post(gallery::manage::add_collaborator))
+        .route("/gallery/{setId}/collaborators/{userId}", delete(gallery::manage::remove_collaborator))
         // Custom emoji
         .route("/servers/{serverId}/emojis", get(emojis::list_emojis).post(emojis::create_emoji))
+        .route("/servers/{serverId}/emojis/export", get(emojis::export_emojis))
+        .route("/servers/{serverId}/emojis/import", post(emojis::import_emojis))
+        .route("/servers/{serverId}/emojis/stats", get(emojis::get_emoji_stats))
         .route("/servers/{serverId}/emojis/{emojiId}", delete(emojis::delete_emoji))
         .route("/me/emoji-favorites", get(emojis::list_emoji_favorites))
         .route("/me/emoji-favorites/standard", post(emojis::add_standard_favorite).delete(emojis::remove_standard_favorite))
@@ -116,40 +278,35 @@ pub fn build_router(state: Arc<AppState>) -> Router {
         .nest("/api/auth", auth_routes)
         .nest("/api", api_routes)
         .route("/gateway", get(ws::handler::ws_handler))
-        // Proxy DeepFilter model CDN to avoid CORS in Tauri production builds
-        .route("/deepfilter-cdn/{*path}", get(proxy_deepfilter_cdn))
+        .route("/metrics", get(metrics))
+        .route("/healthz", get(health::healthz))
+        .route("/readyz", get(health::readyz))
+        // Noise suppression models, served from the local cache
+        .route("/noise-models/{version}/{*path}", get(noise_models::serve_noise_model))
+        .route("/deepfilter-cdn/{*path}", get(noise_models::serve_noise_model_legacy))
         .layer(DefaultBodyLimit::max(10 * 1024 * 1024)) // 10 MB for GIF avatars
+        .layer(axum::middleware::from_fn(crate::middleware::etag::conditional_get))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), crate::middleware::csrf::csrf_protect))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), crate::middleware::ip_filter::filter_requests))
+        .layer(axum::middleware::from_fn(crate::middleware::metrics::track_requests))
+        .layer(axum::middleware::from_fn(crate::middleware::trace::trace_requests))
+        .layer(axum::middleware::from_fn(crate::middleware::locale::negotiate_locale))
         .with_state(state)
 }
 
-/// Proxy requests to cdn.mezon.ai for DeepFilter model files (avoids CORS in Tauri)
-async fn proxy_deepfilter_cdn(
-    Path(path): Path<String>,
-) -> impl axum::response::IntoResponse {
-    let url = format!(
-        "https://cdn.mezon.ai/AI/models/datas/noise_suppression/deepfilternet3/{}",
-        path
-    );
-    let upstream = match reqwest::get(&url).await {
-        Ok(r) => r,
-        Err(e) => {
-            tracing::error!("DeepFilter CDN proxy failed: {}", e);
-            return (
-                axum::http::StatusCode::BAD_GATEWAY,
-                "Failed to fetch model",
-            )
-                .into_response();
+/// GET /metrics
+/// Prometheus scrape endpoint. Needs `Authorization: Bearer <METRICS_TOKEN>`
+/// when a token is configured.
+async fn metrics(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Result<impl IntoResponse, ApiError> {
+    let token = &state.config.metrics_token;
+    if !token.is_empty() {
+        let given = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        if given != Some(token.as_str()) {
+            return Err(ApiError::unauthorized("Invalid metrics token"));
         }
-    };
-    let status =
-        axum::http::StatusCode::from_u16(upstream.status().as_u16()).unwrap_or(axum::http::StatusCode::OK);
-    let mut headers = axum::http::HeaderMap::new();
-    if let Some(ct) = upstream.headers().get(axum::http::header::CONTENT_TYPE) {
-        headers.insert(axum::http::header::CONTENT_TYPE, ct.clone());
-    }
-    if let Some(cl) = upstream.headers().get(axum::http::header::CONTENT_LENGTH) {
-        headers.insert(axum::http::header::CONTENT_LENGTH, cl.clone());
     }
-    let body = axum::body::Body::from_stream(upstream.bytes_stream());
-    (status, headers, body).into_response()
+    Ok(([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], crate::metrics::render(&state).await))
 }
@@ -0,0 +1,326 @@
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+    Json,
+};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio_util::io::ReaderStream;
+
+use crate::error::ApiError;
+use crate::AppState;
+
+/// Model files are tens of megabytes; anything past this is not a model.
+const MAX_MODEL_BYTES: u64 = 256 * 1024 * 1024;
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// A model file cached on disk.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelFile {
+    pub path: String,
+    pub sha256: String,
+    pub size: u64,
+    /// Upstream ETag, to ask for the file only when it's changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+}
+
+/// The noise suppression model files ready to serve. `version` is set once
+/// every configured file is cached, and changes whenever any of them does.
+#[derive(Default)]
+pub struct NoiseModels {
+    pub version: Option<String>,
+    pub files: Vec<ModelFile>,
+}
+
+impl NoiseModels {
+    fn file(&self, path: &str) -> Option<&ModelFile> {
+        self.files.iter().find(|f| f.path == path)
+    }
+}
+
+/// Parse `noise_model_files`: comma separated relative paths, each
+/// optionally pinned to a checksum as `path=<sha256 hex>`.
+pub fn parse_model_files(spec: &str) -> Result<Vec<(String, Option<String>)>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (path, sha256) = match entry.split_once('=') {
+                Some((path, sha256)) => (path.trim(), Some(sha256.trim().to_lowercase())),
+                None => (entry, None),
+            };
+            let safe_path = !path.is_empty()
+                && !path.starts_with('/')
+                && path.split('/').all(|part| !part.is_empty() && part != "." && part != "..");
+            if !safe_path {
+                return Err(format!("{:?} is not a relative file path", path));
+            }
+            if let Some(ref sha256) = sha256 {
+                if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(format!("{:?} is not a SHA-256 checksum", sha256));
+                }
+            }
+            Ok((path.to_string(), sha256))
+        })
+        .collect()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Short id for a set of files; any file changing changes it.
+fn version_of(files: &[ModelFile]) -> String {
+    let mut hasher = Sha256::new();
+    for file in files {
+        hasher.update(file.path.as_bytes());
+        hasher.update(b":");
+        hasher.update(file.sha256.as_bytes());
+        hasher.update(b"\n");
+    }
+    hex(&hasher.finalize()[..8])
+}
+
+async fn hash_file(path: &FsPath) -> std::io::Result<(String, u64)> {
+    let mut stream = ReaderStream::new(tokio::fs::File::open(path).await?);
+    let mut hasher = Sha256::new();
+    let mut size = 0u64;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        hasher.update(&chunk);
+        size += chunk.len() as u64;
+    }
+    Ok((hex(&hasher.finalize()), size))
+}
+
+/// Download `path` next to where it's cached, then move it into place once
+/// its checksum checks out. `Ok(None)` means upstream says it's unchanged.
+async fn download(
+    client: &reqwest::Client,
+    base_url: &str,
+    dir: &FsPath,
+    path: &str,
+    etag: Option<&str>,
+    pinned: Option<&str>,
+) -> Result<Option<ModelFile>, String> {
+    let url = format!("{}/{}", base_url.trim_end_matches('/'), path);
+    let mut req = client.get(&url);
+    if let Some(etag) = etag {
+        req = req.header(header::IF_NONE_MATCH, etag);
+    }
+    let res = req.send().await.map_err(|e| e.to_string())?;
+    if res.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+    if !res.status().is_success() {
+        return Err(format!("HTTP {}", res.status()));
+    }
+    let etag = res
+        .headers()
+        .get(header::ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let local = dir.join(path);
+    let part = PathBuf::from(format!("{}.part", local.display()));
+    if let Some(parent) = local.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(|e| e.to_string())?;
+    }
+    let mut out = tokio::fs::File::create(&part).await.map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    let mut size = 0u64;
+    let mut stream = res.bytes_stream();
+    let written: Result<(), String> = async {
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| e.to_string())?;
+            size += chunk.len() as u64;
+            if size > MAX_MODEL_BYTES {
+                return Err(format!("larger than {} bytes", MAX_MODEL_BYTES));
+            }
+            hasher.update(&chunk);
+            out.write_all(&chunk).await.map_err(|e| e.to_string())?;
+        }
+        out.flush().await.map_err(|e| e.to_string())
+    }
+    .await;
+    drop(out);
+
+    let sha256 = hex(&hasher.finalize());
+    let result = written.and_then(|_| match pinned {
+        Some(pinned) if pinned != sha256 => Err(format!("checksum mismatch: expected {}, got {}", pinned, sha256)),
+        _ => Ok(()),
+    });
+    if let Err(e) = result {
+        let _ = tokio::fs::remove_file(&part).await;
+        return Err(e);
+    }
+    tokio::fs::rename(&part, &local).await.map_err(|e| e.to_string())?;
+
+    Ok(Some(ModelFile { path: path.to_string(), sha256, size, etag }))
+}
+
+/// Verify the cached model files, fetch any that are missing, corrupt or
+/// changed upstream, and publish the result. Files that fail to download
+/// keep their last good copy. With `noise_model_url` empty nothing is
+/// fetched and whatever is in `noise_model_dir` is served.
+pub async fn sync_noise_models(state: &AppState) {
+    let config = &state.config;
+    let wanted = match parse_model_files(&config.noise_model_files) {
+        Ok(wanted) => wanted,
+        Err(e) => {
+            tracing::error!("noise_model_files: {}", e);
+            return;
+        }
+    };
+    let dir = PathBuf::from(&config.noise_model_dir);
+    if let Err(e) = tokio::fs::create_dir_all(&dir).await {
+        tracing::error!("Couldn't create noise model dir {}: {}", dir.display(), e);
+        return;
+    }
+
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let cached: HashMap<String, ModelFile> = tokio::fs::read(&manifest_path)
+        .await
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<Vec<ModelFile>>(&bytes).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|f| (f.path.clone(), f))
+        .collect();
+
+    let client = reqwest::Client::builder().timeout(DOWNLOAD_TIMEOUT).build().ok();
+    let mut files = Vec::new();
+    for (path, pinned) in &wanted {
+        let mut current = match hash_file(&dir.join(path)).await {
+            Ok((sha256, _)) if pinned.as_ref().is_some_and(|p| *p != sha256) => {
+                tracing::warn!("Cached noise model {} doesn't match its pinned checksum", path);
+                None
+            }
+            Ok((sha256, size)) => {
+                let etag = cached.get(path).filter(|c| c.sha256 == sha256).and_then(|c| c.etag.clone());
+                Some(ModelFile { path: path.clone(), sha256, size, etag })
+            }
+            Err(_) => None,
+        };
+
+        // A file matching its pinned checksum can't have changed
+        let verified = pinned.is_some() && current.is_some();
+        if let (false, false, Some(client)) = (config.noise_model_url.is_empty(), verified, &client) {
+            let etag = current.as_ref().and_then(|f| f.etag.as_deref());
+            match download(client, &config.noise_model_url, &dir, path, etag, pinned.as_deref()).await {
+                Ok(Some(file)) => {
+                    tracing::info!("Downloaded noise model {} ({} bytes, sha256 {})", path, file.size, file.sha256);
+                    current = Some(file);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Couldn't download noise model {}: {}", path, e),
+            }
+        }
+
+        match current {
+            Some(file) => files.push(file),
+            None => tracing::warn!("Noise model {} is unavailable", path),
+        }
+    }
+
+    match serde_json::to_vec_pretty(&files) {
+        Ok(bytes) => {
+            if let Err(e) = tokio::fs::write(&manifest_path, bytes).await {
+                tracing::warn!("Couldn't write {}: {}", manifest_path.display(), e);
+            }
+        }
+        Err(e) => tracing::warn!("Couldn't serialize noise model manifest: {}", e),
+    }
+
+    let version = (!files.is_empty() && files.len() == wanted.len()).then(|| version_of(&files));
+    if let Some(ref version) = version {
+        tracing::info!("Noise models ready (version {})", version);
+    }
+    *state.noise_models.write().await = NoiseModels { version, files };
+}
+
+/// Background task: cache the noise models once at startup.
+pub async fn run_noise_model_sync(state: Arc<AppState>) {
+    sync_noise_models(&state).await;
+}
+
+fn content_type(path: &str) -> &'static str {
+    if path.ends_with(".wasm") {
+        "application/wasm"
+    } else if path.ends_with(".tar.gz") || path.ends_with(".tgz") {
+        "application/gzip"
+    } else if path.ends_with(".json") {
+        "application/json"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+async fn serve_model(state: &AppState, path: &str, cache_control: &'static str) -> Result<impl IntoResponse, ApiError> {
+    let size = {
+        let models = state.noise_models.read().await;
+        models.file(path).map(|f| f.size).ok_or_else(|| ApiError::not_found("Model file not found"))?
+    };
+    let file = tokio::fs::File::open(PathBuf::from(&state.config.noise_model_dir).join(path))
+        .await
+        .map_err(|_| ApiError::not_found("Model file not found"))?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type(path).to_string()),
+            (header::CONTENT_LENGTH, size.to_string()),
+            (header::CACHE_CONTROL, cache_control.to_string()),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    ))
+}
+
+/// GET /api/noise-models/version
+/// The current model version, where its files are served from, and each
+/// file's checksum. Clients reload the model when the version changes.
+pub async fn get_noise_model_version(State(state): State<Arc<AppState>>) -> Result<impl IntoResponse, ApiError> {
+    let models = state.noise_models.read().await;
+    let Some(ref version) = models.version else {
+        return Err(ApiError::unavailable("Noise suppression models aren't available yet"));
+    };
+    Ok(Json(serde_json::json!({
+        "version": version,
+        "baseUrl": format!("/noise-models/{}", version),
+        "files": models
+            .files
+            .iter()
+            .map(|f| serde_json::json!({ "path": f.path, "sha256": f.sha256, "size": f.size }))
+            .collect::<Vec<_>>(),
+    })))
+}
+
+/// GET /noise-models/:version/*path
+/// Versioned URLs never change content, so they're cached for good.
+pub async fn serve_noise_model(
+    State(state): State<Arc<AppState>>,
+    Path((version, path)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    if state.noise_models.read().await.version.as_deref() != Some(version.as_str()) {
+        return Err(ApiError::not_found("Unknown model version"));
+    }
+    serve_model(&state, &path, "public, max-age=31536000, immutable").await
+}
+
+/// GET /deepfilter-cdn/*path
+/// Unversioned path older clients load the model from.
+pub async fn serve_noise_model_legacy(
+    State(state): State<Arc<AppState>>,
+    Path(path): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    serve_model(&state, &path, "public, max-age=3600").await
+}
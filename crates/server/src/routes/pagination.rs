@@ -0,0 +1,83 @@
+//! Keyset pagination for list endpoints.
+//!
+//! Pages are ordered by a sort column with the row id as a tiebreaker, and
+//! the cursor names the last row of the previous page. Responses have the
+//! same shape as message history: `{ items, cursor, hasMore }`, where
+//! `cursor` is passed back as `?cursor=` to get the next page.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ApiError;
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct PageQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// The sort key and id of the last row on a page.
+#[derive(Debug, Clone)]
+pub struct Cursor {
+    pub key: String,
+    pub id: String,
+}
+
+impl Cursor {
+    pub fn new(key: &str, id: &str) -> Self {
+        Self { key: key.to_string(), id: id.to_string() }
+    }
+
+    fn encode(&self) -> String {
+        format!("{}|{}", self.key, self.id)
+    }
+
+    fn decode(raw: &str) -> Option<Self> {
+        // Ids never contain `|`, so split on the last one
+        let (key, id) = raw.rsplit_once('|')?;
+        (!id.is_empty()).then(|| Self::new(key, id))
+    }
+}
+
+impl PageQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+
+    /// Where this page starts, or `None` for the first page.
+    pub fn after(&self) -> Result<Option<Cursor>, ApiError> {
+        match self.cursor.as_deref() {
+            None | Some("") => Ok(None),
+            Some(raw) => Cursor::decode(raw)
+                .map(Some)
+                .ok_or_else(|| ApiError::bad_request("Invalid cursor")),
+        }
+    }
+}
+
+/// SQL condition selecting rows past the cursor, for a list ordered by
+/// `key_col, id_col` (both descending when `desc`). Bind the cursor's key
+/// then its id.
+pub fn after_condition(key_col: &str, id_col: &str, desc: bool) -> String {
+    let op = if desc { "<" } else { ">" };
+    format!("({}, {}) {} (?, ?)", key_col, id_col, op)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub cursor: Option<String>,
+    pub has_more: bool,
+}
+
+/// Build a page from rows fetched with `LIMIT limit + 1`; the extra row only
+/// says whether there's more.
+pub fn page<T>(mut rows: Vec<T>, limit: i64, cursor_of: impl Fn(&T) -> Cursor) -> Page<T> {
+    let has_more = rows.len() as i64 > limit;
+    rows.truncate(limit as usize);
+    let cursor = if has_more { rows.last().map(|row| cursor_of(row).encode()) } else { None };
+    Page { items: rows, cursor, has_more }
+}
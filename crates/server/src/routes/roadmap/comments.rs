@@ -0,0 +1,169 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::AppState;
+
+use super::{broadcast_roadmap_updated, fetch_item, require_server_admin, require_server_member};
+
+const MAX_COMMENT_CHARS: usize = 2000;
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RoadmapCommentRow {
+    pub id: String,
+    pub item_id: String,
+    pub parent_id: Option<String>,
+    pub author_id: String,
+    pub author_username: String,
+    pub author_image: Option<String>,
+    pub content: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRoadmapCommentRequest {
+    pub content: String,
+    pub parent_id: Option<String>,
+}
+
+const COMMENT_SELECT: &str = r#"
+    SELECT
+        rc.id,
+        rc.item_id,
+        rc.parent_id,
+        rc.author_id,
+        COALESCE(u.username, 'Unknown') AS author_username,
+        u.image AS author_image,
+        rc.content,
+        rc.created_at
+    FROM roadmap_comments rc
+    LEFT JOIN "user" u ON u.id = rc.author_id
+"#;
+
+/// Members may only discuss items that exist on their own server
+async fn require_item(state: &AppState, user_id: &str, server_id: &str, item_id: &str) -> Result<(), ApiError> {
+    require_server_member(state, user_id, server_id).await?;
+    match fetch_item(state, user_id, server_id, item_id).await? {
+        Some(_) => Ok(()),
+        None => Err(ApiError::not_found("Roadmap item not found")),
+    }
+}
+
+/// GET /api/servers/:serverId/roadmap/:itemId/comments
+/// Oldest first; replies carry a parentId and clients build the thread.
+pub async fn list_roadmap_comments(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, item_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_item(&state, &user.id, &server_id, &item_id).await?;
+
+    let comments = sqlx::query_as::<_, RoadmapCommentRow>(
+        &format!("{} WHERE rc.item_id = ? ORDER BY rc.created_at ASC, rc.id ASC", COMMENT_SELECT),
+    )
+    .bind(&item_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(comments))
+}
+
+/// POST /api/servers/:serverId/roadmap/:itemId/comments
+/// Any member; `parentId` replies to another comment on the same item.
+pub async fn create_roadmap_comment(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, item_id)): Path<(String, String)>,
+    Json(body): Json<CreateRoadmapCommentRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_item(&state, &user.id, &server_id, &item_id).await?;
+
+    let content = body.content.trim();
+    if content.is_empty() {
+        return Err(ApiError::bad_request("Comment cannot be empty"));
+    }
+    if content.chars().count() > MAX_COMMENT_CHARS {
+        return Err(ApiError::bad_request(format!(
+            "Comment must be at most {} characters",
+            MAX_COMMENT_CHARS
+        )));
+    }
+
+    if let Some(parent_id) = &body.parent_id {
+        let parent_exists = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM roadmap_comments WHERE id = ? AND item_id = ?",
+        )
+        .bind(parent_id)
+        .bind(&item_id)
+        .fetch_one(&state.db)
+        .await?
+            > 0;
+        if !parent_exists {
+            return Err(ApiError::bad_request("Parent comment not found on this item"));
+        }
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"INSERT INTO roadmap_comments (id, item_id, parent_id, author_id, content, created_at)
+           VALUES (?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(&id)
+    .bind(&item_id)
+    .bind(&body.parent_id)
+    .bind(&user.id)
+    .bind(content)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    broadcast_roadmap_updated(&state, &server_id, &item_id).await;
+
+    let comment = sqlx::query_as::<_, RoadmapCommentRow>(&format!("{} WHERE rc.id = ?", COMMENT_SELECT))
+        .bind(&id)
+        .fetch_one(&state.db)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(comment)))
+}
+
+/// DELETE /api/servers/:serverId/roadmap/:itemId/comments/:commentId
+/// The author, or an owner/admin. Replies go with it.
+pub async fn delete_roadmap_comment(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, item_id, comment_id)): Path<(String, String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_item(&state, &user.id, &server_id, &item_id).await?;
+
+    let author_id = sqlx::query_scalar::<_, String>(
+        "SELECT author_id FROM roadmap_comments WHERE id = ? AND item_id = ?",
+    )
+    .bind(&comment_id)
+    .bind(&item_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Comment not found"))?;
+
+    if author_id != user.id {
+        require_server_admin(&state, &user.id, &server_id).await?;
+    }
+
+    sqlx::query("DELETE FROM roadmap_comments WHERE id = ?")
+        .bind(&comment_id)
+        .execute(&state.db)
+        .await?;
+
+    broadcast_roadmap_updated(&state, &server_id, &item_id).await;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -6,10 +6,11 @@ use axum::{
 };
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::AuthUser;
 use crate::AppState;
 
-use super::{require_server_admin, RoadmapItemRow, UpdateRoadmapItemRequest, VALID_STATUSES};
+use super::{broadcast_roadmap_updated, fetch_item, require_server_admin, UpdateRoadmapItemRequest, VALID_STATUSES};
 
 /// PATCH /api/servers/:serverId/roadmap/:itemId
 /// Owner or admin only.
@@ -18,30 +19,14 @@ pub async fn update_roadmap_item(
     user: AuthUser,
     Path((server_id, item_id)): Path<(String, String)>,
     Json(body): Json<UpdateRoadmapItemRequest>,
-) -> impl IntoResponse {
-    if let Err(resp) = require_server_admin(&state, &user.id, &server_id).await {
-        return resp.into_response();
-    }
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
 
     // Fetch existing item
-    let existing = sqlx::query_as::<_, RoadmapItemRow>(
-        "SELECT * FROM roadmap_items WHERE id = ? AND server_id = ?",
-    )
-    .bind(&item_id)
-    .bind(&server_id)
-    .fetch_optional(&state.db)
-    .await
-    .ok()
-    .flatten();
-
-    let existing = match existing {
+    let existing = match fetch_item(&state, &user.id, &server_id, &item_id).await? {
         Some(e) => e,
         None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({"error": "Roadmap item not found"})),
-            )
-                .into_response();
+            return Err(ApiError::not_found("Roadmap item not found"));
         }
     };
 
@@ -52,20 +37,12 @@ pub async fn update_roadmap_item(
         .unwrap_or(&existing.title)
         .to_string();
     if title.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Title cannot be empty"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("Title cannot be empty"));
     }
 
     let status = body.status.as_deref().unwrap_or(&existing.status);
     if !VALID_STATUSES.contains(&status) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Invalid status"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("Invalid status"));
     }
 
     let description = body
@@ -95,25 +72,14 @@ pub async fn update_roadmap_item(
 
     if let Err(e) = result {
         tracing::error!("Failed to update roadmap item: {:?}", e);
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": "Failed to update roadmap item"})),
-        )
-            .into_response();
+        return Err(ApiError::internal("Failed to update roadmap item"));
     }
 
-    let item = sqlx::query_as::<_, RoadmapItemRow>(
-        "SELECT * FROM roadmap_items WHERE id = ?",
-    )
-    .bind(&item_id)
-    .fetch_optional(&state.db)
-    .await
-    .ok()
-    .flatten();
+    broadcast_roadmap_updated(&state, &server_id, &item_id).await;
 
-    match item {
-        Some(i) => Json(i).into_response(),
-        None => StatusCode::NOT_FOUND.into_response(),
+    match fetch_item(&state, &user.id, &server_id, &item_id).await? {
+        Some(i) => Ok(Json(i).into_response()),
+        None => Err(ApiError::not_found("Roadmap item not found")),
     }
 }
 
@@ -123,17 +89,20 @@ pub async fn delete_roadmap_item(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path((server_id, item_id)): Path<(String, String)>,
-) -> impl IntoResponse {
-    if let Err(resp) = require_server_admin(&state, &user.id, &server_id).await {
-        return resp.into_response();
-    }
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
 
-    sqlx::query("DELETE FROM roadmap_items WHERE id = ? AND server_id = ?")
+    let deleted = sqlx::query("DELETE FROM roadmap_items WHERE id = ? AND server_id = ?")
         .bind(&item_id)
         .bind(&server_id)
         .execute(&state.db)
-        .await
-        .ok();
+        .await?
+        .rows_affected()
+        > 0;
+
+    if deleted {
+        broadcast_roadmap_updated(&state, &server_id, &item_id).await;
+    }
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
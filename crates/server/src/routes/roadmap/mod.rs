@@ -1,6 +1,10 @@
+mod comments;
 mod manage;
+mod votes;
 
+pub use comments::*;
 pub use manage::*;
+pub use votes::*;
 
 use axum::{
     extract::{Path, State},
@@ -11,7 +15,9 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::AuthUser;
+use crate::ws::events::ServerEvent;
 use crate::AppState;
 
 // ── Request / response types ──────────────────────────────────────────────
@@ -28,6 +34,10 @@ pub struct RoadmapItemRow {
     pub created_by: String,
     pub created_at: String,
     pub updated_at: String,
+    pub vote_count: i64,
+    pub comment_count: i64,
+    /// Whether the caller has upvoted this item
+    pub voted: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -48,13 +58,76 @@ pub struct UpdateRoadmapItemRequest {
     pub category: Option<String>,
 }
 
-// ── Per-server admin check ────────────────────────────────────────────────
+// ── Shared query fragment ──────────────────────────────────────────────────
+
+/// Binds the caller's user id first, for `voted`.
+pub(super) const ROADMAP_ITEM_SELECT: &str = r#"
+    SELECT
+        ri.*,
+        (SELECT COUNT(*) FROM roadmap_votes rv WHERE rv.item_id = ri.id) AS vote_count,
+        (SELECT COUNT(*) FROM roadmap_comments rc WHERE rc.item_id = ri.id) AS comment_count,
+        EXISTS(SELECT 1 FROM roadmap_votes my_vote WHERE my_vote.item_id = ri.id AND my_vote.user_id = ?) AS voted
+    FROM roadmap_items ri
+"#;
+
+pub(super) async fn fetch_item(
+    state: &AppState,
+    user_id: &str,
+    server_id: &str,
+    item_id: &str,
+) -> Result<Option<RoadmapItemRow>, ApiError> {
+    Ok(sqlx::query_as::<_, RoadmapItemRow>(
+        &format!("{} WHERE ri.id = ? AND ri.server_id = ?", ROADMAP_ITEM_SELECT),
+    )
+    .bind(user_id)
+    .bind(item_id)
+    .bind(server_id)
+    .fetch_optional(&state.db)
+    .await?)
+}
+
+/// Tell everyone watching the board that an item changed
+pub(super) async fn broadcast_roadmap_updated(state: &AppState, server_id: &str, item_id: &str) {
+    state
+        .gateway
+        .broadcast_all(
+            &ServerEvent::RoadmapUpdated {
+                server_id: server_id.to_string(),
+                item_id: item_id.to_string(),
+            },
+            None,
+        )
+        .await;
+}
+
+// ── Per-server membership checks ──────────────────────────────────────────
+
+pub(super) async fn require_server_member(
+    state: &AppState,
+    user_id: &str,
+    server_id: &str,
+) -> Result<(), ApiError> {
+    let is_member = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
+    )
+    .bind(user_id)
+    .bind(server_id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0)
+        > 0;
+
+    if !is_member {
+        return Err(ApiError::forbidden("Not a member of this server"));
+    }
+    Ok(())
+}
 
 pub(super) async fn require_server_admin(
     state: &AppState,
     user_id: &str,
     server_id: &str,
-) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+) -> Result<(), ApiError> {
     let role = sqlx::query_scalar::<_, String>(
         "SELECT role FROM memberships WHERE user_id = ? AND server_id = ?",
     )
@@ -67,10 +140,7 @@ pub(super) async fn require_server_admin(
 
     match role.as_deref() {
         Some("owner") | Some("admin") => Ok(()),
-        _ => Err((
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({"error": "Insufficient permissions"})),
-        )),
+        _ => Err(ApiError::forbidden("Insufficient permissions")),
     }
 }
 
@@ -79,40 +149,25 @@ const VALID_STATUSES: &[&str] = &["planned", "in-progress", "done", "bug"];
 // ── Handlers ──────────────────────────────────────────────────────────────
 
 /// GET /api/servers/:serverId/roadmap
-/// Any server member can list roadmap items.
+/// Any server member can list roadmap items, most upvoted first.
 pub async fn list_roadmap_items(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path(server_id): Path<String>,
-) -> impl IntoResponse {
-    // Verify caller is a member
-    let is_member = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
-    )
-    .bind(&user.id)
-    .bind(&server_id)
-    .fetch_one(&state.db)
-    .await
-    .unwrap_or(0)
-        > 0;
-
-    if !is_member {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({"error": "Not a member of this server"})),
-        )
-            .into_response();
-    }
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_member(&state, &user.id, &server_id).await?;
 
-    let items = sqlx::query_as::<_, RoadmapItemRow>(
-        "SELECT * FROM roadmap_items WHERE server_id = ? ORDER BY created_at ASC",
-    )
+    let items = sqlx::query_as::<_, RoadmapItemRow>(&format!(
+        "{} WHERE ri.server_id = ? ORDER BY vote_count DESC, ri.created_at ASC",
+        ROADMAP_ITEM_SELECT,
+    ))
+    .bind(&user.id)
     .bind(&server_id)
     .fetch_all(&state.db)
     .await
     .unwrap_or_default();
 
-    Json(items).into_response()
+    Ok(Json(items).into_response())
 }
 
 /// POST /api/servers/:serverId/roadmap
@@ -122,27 +177,17 @@ pub async fn create_roadmap_item(
     user: AuthUser,
     Path(server_id): Path<String>,
     Json(body): Json<CreateRoadmapItemRequest>,
-) -> impl IntoResponse {
-    if let Err(resp) = require_server_admin(&state, &user.id, &server_id).await {
-        return resp.into_response();
-    }
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
 
     let title = body.title.trim().to_string();
     if title.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Title is required"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("Title is required"));
     }
 
     let status = body.status.as_deref().unwrap_or("planned");
     if !VALID_STATUSES.contains(&status) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Invalid status"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("Invalid status"));
     }
 
     let id = uuid::Uuid::new_v4().to_string();
@@ -168,24 +213,13 @@ pub async fn create_roadmap_item(
 
     if let Err(e) = result {
         tracing::error!("Failed to create roadmap item: {:?}", e);
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": "Failed to create roadmap item"})),
-        )
-            .into_response();
+        return Err(ApiError::internal("Failed to create roadmap item"));
     }
 
-    let item = sqlx::query_as::<_, RoadmapItemRow>(
-        "SELECT * FROM roadmap_items WHERE id = ?",
-    )
-    .bind(&id)
-    .fetch_optional(&state.db)
-    .await
-    .ok()
-    .flatten();
+    broadcast_roadmap_updated(&state, &server_id, &id).await;
 
-    match item {
-        Some(i) => (StatusCode::CREATED, Json(i)).into_response(),
-        None => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    match fetch_item(&state, &user.id, &server_id, &id).await? {
+        Some(i) => Ok((StatusCode::CREATED, Json(i)).into_response()),
+        None => Err(ApiError::internal("Failed to load roadmap item")),
     }
 }
@@ -0,0 +1,72 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::AppState;
+
+use super::{broadcast_roadmap_updated, fetch_item, require_server_member};
+
+/// Upvote (`true`) or withdraw a vote on an item, then answer with its new tally
+async fn set_vote(
+    state: &AppState,
+    user: &AuthUser,
+    server_id: &str,
+    item_id: &str,
+    voted: bool,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_member(state, &user.id, server_id).await?;
+    if fetch_item(state, &user.id, server_id, item_id).await?.is_none() {
+        return Err(ApiError::not_found("Roadmap item not found"));
+    }
+
+    let result = if voted {
+        let now = chrono::Utc::now().to_rfc3339();
+        sqlx::query("INSERT OR IGNORE INTO roadmap_votes (item_id, user_id, created_at) VALUES (?, ?, ?)")
+            .bind(item_id)
+            .bind(&user.id)
+            .bind(&now)
+            .execute(&state.db)
+            .await?
+    } else {
+        sqlx::query("DELETE FROM roadmap_votes WHERE item_id = ? AND user_id = ?")
+            .bind(item_id)
+            .bind(&user.id)
+            .execute(&state.db)
+            .await?
+    };
+
+    if result.rows_affected() > 0 {
+        broadcast_roadmap_updated(state, server_id, item_id).await;
+    }
+
+    let vote_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM roadmap_votes WHERE item_id = ?")
+        .bind(item_id)
+        .fetch_one(&state.db)
+        .await?;
+
+    Ok(Json(serde_json::json!({ "voteCount": vote_count, "voted": voted })))
+}
+
+/// POST /api/servers/:serverId/roadmap/:itemId/vote
+/// Any member; voting twice is a no-op.
+pub async fn vote_roadmap_item(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, item_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    set_vote(&state, &user, &server_id, &item_id, true).await
+}
+
+/// DELETE /api/servers/:serverId/roadmap/:itemId/vote
+pub async fn unvote_roadmap_item(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, item_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    set_vote(&state, &user, &server_id, &item_id, false).await
+}
@@ -6,6 +6,7 @@ use axum::{
 };
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::{AuthUser, Channel, CreateChannelRequest};
 use crate::AppState;
 
@@ -14,31 +15,27 @@ pub async fn list_channels(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path(server_id): Path<String>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let membership = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
     )
     .bind(&user.id)
     .bind(&server_id)
-    .fetch_one(&state.db)
+    .fetch_one(&state.db_read)
     .await
     .unwrap_or(0);
 
     if membership == 0 {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({"error": "Not a member of this server"})),
-        )
-            .into_response();
+        return Err(ApiError::forbidden("Not a member of this server"));
     }
 
     let channels = sqlx::query_as::<_, Channel>("SELECT * FROM channels WHERE server_id = ? ORDER BY position ASC, created_at ASC")
         .bind(&server_id)
-        .fetch_all(&state.db)
+        .fetch_all(&state.db_read)
         .await
         .unwrap_or_default();
 
-    Json(channels).into_response()
+    Ok(Json(channels).into_response())
 }
 
 /// POST /api/servers/:serverId/channels
@@ -47,7 +44,7 @@ pub async fn create_channel(
     user: AuthUser,
     Path(server_id): Path<String>,
     Json(body): Json<CreateChannelRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let role = sqlx::query_scalar::<_, String>(
         "SELECT role FROM memberships WHERE user_id = ? AND server_id = ?",
     )
@@ -61,22 +58,12 @@ pub async fn create_channel(
     // Rooms: any server member can create; regular channels require admin/owner
     if body.is_room {
         if role.is_none() {
-            return (
-                StatusCode::FORBIDDEN,
-                Json(serde_json::json!({"error": "Not a member of this server"})),
-            )
-                .into_response();
+            return Err(ApiError::forbidden("Not a member of this server"));
         }
     } else {
         match role.as_deref() {
             Some("owner") | Some("admin") => {}
-            _ => {
-                return (
-                    StatusCode::FORBIDDEN,
-                    Json(serde_json::json!({"error": "Insufficient permissions"})),
-                )
-                    .into_response()
-            }
+            _ => return Err(ApiError::forbidden("Insufficient permissions")),
         }
     }
 
@@ -84,18 +71,18 @@ pub async fn create_channel(
     let channel_type = if body.is_room { "voice".to_string() } else { body.channel_type.clone() };
     let parent_id_input = if body.is_room { None } else { body.parent_id.clone() };
 
-    if !["text", "voice", "game", "category"].contains(&channel_type.as_str()) {
-        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid channel type"}))).into_response();
+    if !["text", "voice", "stage", "game", "category"].contains(&channel_type.as_str()) {
+        return Err(ApiError::bad_request("Invalid channel type"));
     }
 
     // Rooms allow free-form names; regular channels use strict validation
     if body.is_room {
         let trimmed = body.name.trim();
         if trimmed.is_empty() || trimmed.len() > 64 {
-            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Room name must be 1-64 characters"}))).into_response();
+            return Err(ApiError::bad_request("Room name must be 1-64 characters"));
         }
     } else if let Err(e) = flux_shared::validation::validate_channel_name(&body.name) {
-        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))).into_response();
+        return Err(ApiError::bad_request(e));
     }
 
     // Validate parent_id if provided
@@ -113,7 +100,7 @@ pub async fn create_channel(
         match parent {
             Some(p) => {
                 if p.channel_type != "category" {
-                    return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Parent must be a category"}))).into_response();
+                    return Err(ApiError::bad_request("Parent must be a category"));
                 }
                 // Check nesting depth (max 3 levels of categories)
                 if body.channel_type == "category" {
@@ -122,7 +109,7 @@ pub async fn create_channel(
                     while let Some(ref cpid) = current_parent {
                         depth += 1;
                         if depth > 3 {
-                            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Maximum category nesting depth is 3"}))).into_response();
+                            return Err(ApiError::bad_request("Maximum category nesting depth is 3"));
                         }
                         let pp = sqlx::query_scalar::<_, Option<String>>(
                             "SELECT parent_id FROM channels WHERE id = ?",
@@ -139,7 +126,7 @@ pub async fn create_channel(
                 Some(pid.clone())
             }
             None => {
-                return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Parent channel not found"}))).into_response();
+                return Err(ApiError::bad_request("Parent channel not found"));
             }
         }
     } else {
@@ -149,7 +136,7 @@ pub async fn create_channel(
     let channel_id = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
     let name = body.name.trim().to_string();
-    let bitrate = if channel_type == "voice" {
+    let bitrate = if channel_type == "voice" || channel_type == "stage" {
         body.bitrate
     } else {
         None
@@ -180,7 +167,7 @@ pub async fn create_channel(
     };
     let position = max_pos.unwrap_or(-1) + 1;
 
-    let _ = sqlx::query(
+    sqlx::query(
         "INSERT INTO channels (id, server_id, name, type, bitrate, parent_id, position, is_room, creator_id, is_locked, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 0, ?)",
     )
     .bind(&channel_id)
@@ -194,7 +181,7 @@ pub async fn create_channel(
     .bind(&creator_id)
     .bind(&now)
     .execute(&state.db)
-    .await;
+    .await?;
 
     let channel = Channel {
         id: channel_id,
@@ -208,6 +195,8 @@ pub async fn create_channel(
         creator_id,
         is_locked: 0,
         created_at: now,
+        retention_days: None,
+        voice_region: None,
     };
 
     state
@@ -220,5 +209,5 @@ pub async fn create_channel(
         )
         .await;
 
-    (StatusCode::CREATED, Json(channel)).into_response()
+    Ok((StatusCode::CREATED, Json(channel)).into_response())
 }
@@ -6,6 +6,7 @@ use axum::{
 };
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::{AuthUser, Channel, ReorderChannelsRequest, UpdateChannelRequest};
 use crate::AppState;
 
@@ -15,7 +16,7 @@ pub async fn update_channel(
     user: AuthUser,
     Path((server_id, channel_id)): Path<(String, String)>,
     Json(body): Json<UpdateChannelRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let role = sqlx::query_scalar::<_, String>(
         "SELECT role FROM memberships WHERE user_id = ? AND server_id = ?",
     )
@@ -38,45 +39,28 @@ pub async fn update_channel(
 
     let channel = match channel {
         Some(c) => c,
-        None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({"error": "Channel not found"})),
-            )
-                .into_response()
-        }
+        None => return Err(ApiError::not_found("Channel not found")),
     };
 
     let is_admin_or_owner = matches!(role.as_deref(), Some("owner") | Some("admin"));
     if channel.is_room == 1 {
         let is_creator = channel.creator_id.as_deref() == Some(&user.id);
         if !is_admin_or_owner && !is_creator {
-            return (
-                StatusCode::FORBIDDEN,
-                Json(serde_json::json!({"error": "Insufficient permissions"})),
-            )
-                .into_response();
+            return Err(ApiError::forbidden("Insufficient permissions"));
         }
     } else if !is_admin_or_owner {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({"error": "Insufficient permissions"})),
-        )
-            .into_response();
+        return Err(ApiError::forbidden("Insufficient permissions"));
     }
 
     if let Some(ref name) = body.name {
         if let Err(e) = flux_shared::validation::validate_channel_name(name) {
-            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))).into_response();
+            return Err(ApiError::bad_request(e));
         }
     }
 
-    if body.bitrate.is_some() && channel.channel_type != "voice" {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Bitrate can only be set on voice channels"})),
-        )
-            .into_response();
+    let is_voice = channel.channel_type == "voice" || channel.channel_type == "stage";
+    if body.bitrate.is_some() && !is_voice {
+        return Err(ApiError::bad_request("Bitrate can only be set on voice channels"));
     }
 
     let new_name = body.name.as_deref().map(|n| n.trim()).unwrap_or(&channel.name);
@@ -91,13 +75,38 @@ pub async fn update_channel(
         channel.is_locked
     };
 
-    let _ = sqlx::query("UPDATE channels SET name = ?, bitrate = ?, is_locked = ? WHERE id = ?")
-        .bind(new_name)
-        .bind(new_bitrate)
-        .bind(new_is_locked)
-        .bind(&channel_id)
-        .execute(&state.db)
-        .await;
+    if body.retention_days.is_some() && !is_admin_or_owner {
+        return Err(ApiError::forbidden("Only server admins can change message retention"));
+    }
+    let new_retention = match body.retention_days {
+        Some(ref value) => crate::routes::messages::parse_retention_days(value, true)?,
+        None => channel.retention_days,
+    };
+
+    let new_voice_region = match body.voice_region {
+        Some(ref value) => {
+            if !is_admin_or_owner {
+                return Err(ApiError::forbidden("Only server admins can change the voice region"));
+            }
+            if !is_voice {
+                return Err(ApiError::bad_request("Voice region can only be set on voice channels"));
+            }
+            crate::routes::voice::parse_voice_region(&state, value).await?
+        }
+        None => channel.voice_region.clone(),
+    };
+
+    sqlx::query(
+        "UPDATE channels SET name = ?, bitrate = ?, is_locked = ?, retention_days = ?, voice_region = ? WHERE id = ?",
+    )
+    .bind(new_name)
+    .bind(new_bitrate)
+    .bind(new_is_locked)
+    .bind(new_retention)
+    .bind(&new_voice_region)
+    .bind(&channel_id)
+    .execute(&state.db)
+    .await?;
 
     let updated = Channel {
         id: channel.id.clone(),
@@ -111,6 +120,8 @@ pub async fn update_channel(
         creator_id: channel.creator_id,
         is_locked: new_is_locked,
         created_at: channel.created_at,
+        retention_days: new_retention,
+        voice_region: new_voice_region,
     };
 
     let ch_id = channel.id.clone();
@@ -141,7 +152,7 @@ pub async fn update_channel(
             .await;
     }
 
-    Json(updated).into_response()
+    Ok(Json(updated).into_response())
 }
 
 /// DELETE /api/servers/:serverId/channels/:channelId
@@ -149,7 +160,7 @@ pub async fn delete_channel(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path((server_id, channel_id)): Path<(String, String)>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let role = sqlx::query_scalar::<_, String>(
         "SELECT role FROM memberships WHERE user_id = ? AND server_id = ?",
     )
@@ -172,7 +183,7 @@ pub async fn delete_channel(
 
     let channel = match channel {
         Some(c) => c,
-        None => return StatusCode::NOT_FOUND.into_response(),
+        None => return Err(ApiError::not_found("Channel not found")),
     };
 
     let is_admin_or_owner = matches!(role.as_deref(), Some("owner") | Some("admin"));
@@ -180,28 +191,24 @@ pub async fn delete_channel(
     if channel.is_room == 1 {
         let participants = state.gateway.voice_channel_participants(&channel_id).await;
         if !participants.is_empty() {
-            return (
-                StatusCode::FORBIDDEN,
-                Json(serde_json::json!({"error": "Cannot delete a room with active participants"})),
-            )
-                .into_response();
+            return Err(ApiError::forbidden("Cannot delete a room with active participants"));
         }
     }
 
     if channel.is_room == 1 {
         let is_creator = channel.creator_id.as_deref() == Some(&user.id);
         if !is_admin_or_owner && !is_creator {
-            return (StatusCode::FORBIDDEN).into_response();
+            return Err(ApiError::forbidden("Only the room creator or an admin can delete this room"));
         }
     } else if !is_admin_or_owner {
-        return (StatusCode::FORBIDDEN).into_response();
+        return Err(ApiError::forbidden("Only admins can delete channels"));
     }
 
-    let _ = sqlx::query("DELETE FROM channels WHERE id = ? AND server_id = ?")
+    sqlx::query("DELETE FROM channels WHERE id = ? AND server_id = ?")
         .bind(&channel_id)
         .bind(&server_id)
         .execute(&state.db)
-        .await;
+        .await?;
 
     state
         .gateway
@@ -214,7 +221,7 @@ pub async fn delete_channel(
         )
         .await;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
 
 /// PUT /api/servers/:serverId/channels/reorder
@@ -223,7 +230,7 @@ pub async fn reorder_channels(
     user: AuthUser,
     Path(server_id): Path<String>,
     Json(body): Json<ReorderChannelsRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let role = sqlx::query_scalar::<_, String>(
         "SELECT role FROM memberships WHERE user_id = ? AND server_id = ?",
     )
@@ -236,13 +243,7 @@ pub async fn reorder_channels(
 
     match role.as_deref() {
         Some("owner") | Some("admin") => {}
-        _ => {
-            return (
-                StatusCode::FORBIDDEN,
-                Json(serde_json::json!({"error": "Insufficient permissions"})),
-            )
-                .into_response()
-        }
+        _ => return Err(ApiError::forbidden("Insufficient permissions")),
     }
 
     let all_channels = sqlx::query_as::<_, Channel>("SELECT * FROM channels WHERE server_id = ?")
@@ -256,18 +257,18 @@ pub async fn reorder_channels(
 
     for item in &body.items {
         if !channel_map.contains_key(item.id.as_str()) {
-            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": format!("Channel {} not found in server", item.id)}))).into_response();
+            return Err(ApiError::bad_request(format!("Channel {} not found in server", item.id)));
         }
 
         if let Some(ref pid) = item.parent_id {
             match channel_map.get(pid.as_str()) {
                 Some(parent) => {
                     if parent.channel_type != "category" {
-                        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Parent must be a category"}))).into_response();
+                        return Err(ApiError::bad_request("Parent must be a category"));
                     }
                 }
                 None => {
-                    return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": format!("Parent {} not found", pid)}))).into_response();
+                    return Err(ApiError::bad_request(format!("Parent {} not found", pid)));
                 }
             }
         }
@@ -276,19 +277,22 @@ pub async fn reorder_channels(
         if ch.channel_type != "category"
             && body.items.iter().any(|other| other.parent_id.as_deref() == Some(item.id.as_str()))
         {
-            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Only categories can have children"}))).into_response();
+            return Err(ApiError::bad_request("Only categories can have children"));
         }
     }
 
+    // A half-applied reorder leaves the sidebar scrambled; apply all or none
+    let mut tx = state.db.begin().await?;
     for item in &body.items {
-        let _ = sqlx::query("UPDATE channels SET parent_id = ?, position = ? WHERE id = ? AND server_id = ?")
+        sqlx::query("UPDATE channels SET parent_id = ?, position = ? WHERE id = ? AND server_id = ?")
             .bind(&item.parent_id)
             .bind(item.position)
             .bind(&item.id)
             .bind(&server_id)
-            .execute(&state.db)
-            .await;
+            .execute(&mut *tx)
+            .await?;
     }
+    tx.commit().await?;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
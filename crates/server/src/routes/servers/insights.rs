@@ -0,0 +1,205 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::AppState;
+
+/// How long a computed insights report is served from memory.
+const INSIGHTS_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Deserialize)]
+pub struct InsightsQuery {
+    pub days: Option<i64>,
+}
+
+#[derive(Default)]
+struct DayInsights {
+    messages: i64,
+    active_users: i64,
+    new_members: i64,
+    voice_minutes: i64,
+}
+
+async fn require_server_admin(state: &AppState, user_id: &str, server_id: &str) -> Result<(), ApiError> {
+    let role = sqlx::query_scalar::<_, String>("SELECT role FROM memberships WHERE user_id = ? AND server_id = ?")
+        .bind(user_id)
+        .bind(server_id)
+        .fetch_optional(&state.db)
+        .await?;
+    match role.as_deref() {
+        Some("owner") | Some("admin") => Ok(()),
+        _ => Err(ApiError::forbidden("Insufficient permissions")),
+    }
+}
+
+/// GET /api/servers/:serverId/insights?days=
+/// Activity over the last `days` UTC days: per-day messages, active users
+/// (anyone who posted or was in voice), new members and voice minutes, plus
+/// the busiest channels. Reports are cached for a few minutes per server
+/// and window. Server admins only.
+pub async fn get_server_insights(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+    Query(query): Query<InsightsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let days = query.days.unwrap_or(30).clamp(1, 365);
+    let key = format!("{}:{}", server_id, days);
+    if let Some((report, at)) = state.insights_cache.read().await.get(&key) {
+        if at.elapsed() < INSIGHTS_TTL {
+            return Ok(Json(report.clone()));
+        }
+    }
+
+    let report = compute_insights(&state, &server_id, days).await?;
+    let mut cache = state.insights_cache.write().await;
+    cache.retain(|_, (_, at)| at.elapsed() < INSIGHTS_TTL);
+    cache.insert(key, (report.clone(), Instant::now()));
+    Ok(Json(report))
+}
+
+async fn compute_insights(state: &AppState, server_id: &str, days: i64) -> Result<serde_json::Value, ApiError> {
+    let now = chrono::Utc::now();
+    let first = now.date_naive() - chrono::Duration::days(days - 1);
+    let since = first.to_string();
+    let now_str = now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+
+    let mut per_day: BTreeMap<String, DayInsights> = (0..days)
+        .map(|i| ((first + chrono::Duration::days(i)).to_string(), DayInsights::default()))
+        .collect();
+
+    let messages = sqlx::query_as::<_, (String, i64)>(
+        r#"SELECT substr(m.created_at, 1, 10) AS day, COUNT(*) FROM messages m
+           INNER JOIN channels c ON c.id = m.channel_id
+           WHERE c.server_id = ? AND m.created_at >= ?
+           GROUP BY day"#,
+    )
+    .bind(server_id)
+    .bind(&since)
+    .fetch_all(&state.db_read)
+    .await?;
+    for (day, n) in messages {
+        if let Some(entry) = per_day.get_mut(&day) {
+            entry.messages = n;
+        }
+    }
+
+    // Posters and voice users, deduplicated per day
+    const ACTIVITY: &str = r#"
+        SELECT substr(m.created_at, 1, 10) AS day, m.sender_id AS user_id FROM messages m
+        INNER JOIN channels c ON c.id = m.channel_id
+        WHERE c.server_id = ?1 AND m.created_at >= ?2
+        UNION
+        SELECT substr(v.joined_at, 1, 10), v.user_id FROM voice_sessions v
+        WHERE v.server_id = ?1 AND v.joined_at >= ?2
+    "#;
+    let active = sqlx::query_as::<_, (String, i64)>(&format!(
+        "SELECT day, COUNT(*) FROM ({}) GROUP BY day",
+        ACTIVITY
+    ))
+    .bind(server_id)
+    .bind(&since)
+    .fetch_all(&state.db_read)
+    .await?;
+    for (day, n) in active {
+        if let Some(entry) = per_day.get_mut(&day) {
+            entry.active_users = n;
+        }
+    }
+    let active_users: i64 = sqlx::query_scalar(&format!("SELECT COUNT(DISTINCT user_id) FROM ({})", ACTIVITY))
+        .bind(server_id)
+        .bind(&since)
+        .fetch_one(&state.db_read)
+        .await?;
+
+    let joins = sqlx::query_as::<_, (String, i64)>(
+        r#"SELECT substr(joined_at, 1, 10) AS day, COUNT(*) FROM memberships
+           WHERE server_id = ? AND joined_at >= ?
+           GROUP BY day"#,
+    )
+    .bind(server_id)
+    .bind(&since)
+    .fetch_all(&state.db_read)
+    .await?;
+    for (day, n) in joins {
+        if let Some(entry) = per_day.get_mut(&day) {
+            entry.new_members = n;
+        }
+    }
+    let total_members: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM memberships WHERE server_id = ?")
+        .bind(server_id)
+        .fetch_one(&state.db_read)
+        .await?;
+
+    // Sessions still in progress count up to now
+    let voice = sqlx::query_as::<_, (String, i64)>(
+        r#"SELECT substr(joined_at, 1, 10) AS day,
+               SUM(COALESCE(duration_secs, MAX(0, CAST(ROUND((julianday(?) - julianday(joined_at)) * 86400) AS INTEGER)))) / 60
+           FROM voice_sessions
+           WHERE server_id = ? AND joined_at >= ?
+           GROUP BY day"#,
+    )
+    .bind(&now_str)
+    .bind(server_id)
+    .bind(&since)
+    .fetch_all(&state.db_read)
+    .await?;
+    for (day, minutes) in voice {
+        if let Some(entry) = per_day.get_mut(&day) {
+            entry.voice_minutes = minutes;
+        }
+    }
+
+    let top_channels = sqlx::query_as::<_, (String, String, i64)>(
+        r#"SELECT c.id, c.name, COUNT(*) AS n FROM messages m
+           INNER JOIN channels c ON c.id = m.channel_id
+           WHERE c.server_id = ? AND m.created_at >= ?
+           GROUP BY c.id
+           ORDER BY n DESC, c.name ASC
+           LIMIT 10"#,
+    )
+    .bind(server_id)
+    .bind(&since)
+    .fetch_all(&state.db_read)
+    .await?;
+
+    let totals = per_day.values().fold((0, 0, 0), |(m, j, v), d| {
+        (m + d.messages, j + d.new_members, v + d.voice_minutes)
+    });
+
+    Ok(serde_json::json!({
+        "days": days,
+        "generatedAt": now_str,
+        "totalMembers": total_members,
+        "totals": {
+            "messages": totals.0,
+            "activeUsers": active_users,
+            "newMembers": totals.1,
+            "voiceMinutes": totals.2,
+        },
+        "daily": per_day
+            .into_iter()
+            .map(|(day, d)| serde_json::json!({
+                "day": day,
+                "messages": d.messages,
+                "activeUsers": d.active_users,
+                "newMembers": d.new_members,
+                "voiceMinutes": d.voice_minutes,
+            }))
+            .collect::<Vec<_>>(),
+        "topChannels": top_channels
+            .into_iter()
+            .map(|(id, name, n)| serde_json::json!({ "channelId": id, "name": name, "messages": n }))
+            .collect::<Vec<_>>(),
+    }))
+}
@@ -1,49 +1,93 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use std::sync::Arc;
 
-use crate::models::{AuthUser, MemberWithUser, UpdateMemberRoleRequest};
+use crate::error::ApiError;
+use crate::middleware::etag::{etag_headers, weak_etag};
+use crate::models::{AuthUser, MemberWithUser, SetNicknameRequest, UpdateMemberProfileRequest, UpdateMemberRoleRequest};
+use crate::routes::pagination::{self, Cursor, PageQuery};
+use crate::routes::users::{process_upload, AvatarQuery};
 use crate::AppState;
 
-/// GET /api/servers/:serverId/members
+/// GET /api/servers/:serverId/members?cursor=&limit=&staticAvatars=
+/// Members in the order they joined. Carries a weak ETag so polling
+/// clients get a 304 when nothing changed.
 pub async fn list_members(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path(server_id): Path<String>,
-) -> impl IntoResponse {
+    Query(query): Query<PageQuery>,
+    Query(avatars): Query<AvatarQuery>,
+) -> Result<impl IntoResponse, ApiError> {
     let membership = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
     )
     .bind(&user.id)
     .bind(&server_id)
-    .fetch_one(&state.db)
+    .fetch_one(&state.db_read)
     .await
     .unwrap_or(0);
 
     if membership == 0 {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({"error": "Not a member of this server"})),
-        )
-            .into_response();
+        return Err(ApiError::forbidden("Not a member of this server"));
     }
 
-    let members = sqlx::query_as::<_, MemberWithUser>(
-        r#"SELECT m.user_id, m.server_id, m.role, m.joined_at, u.username, u.image, u.ring_style, u.ring_spin, u.steam_id, u.ring_pattern_seed, u.banner_css, u.banner_pattern_seed
+    let limit = query.limit();
+    let after = query.after()?;
+
+    // Joins, leaves, role changes and profile edits all move one of these
+    let (count, joined, role_updated, member_updated, profile_updated) =
+        sqlx::query_as::<_, (i64, Option<String>, Option<String>, Option<String>, Option<String>)>(
+            r#"SELECT COUNT(*), MAX(m.joined_at), MAX(m.role_updated_at), MAX(m.profile_updated_at), MAX(u.updatedAt)
+               FROM memberships m
+               INNER JOIN "user" u ON u.id = m.user_id
+               WHERE m.server_id = ?"#,
+        )
+        .bind(&server_id)
+        .fetch_one(&state.db_read)
+        .await?;
+    let etag = weak_etag(&[
+        "members",
+        &server_id,
+        &count.to_string(),
+        joined.as_deref().unwrap_or(""),
+        role_updated.as_deref().unwrap_or(""),
+        member_updated.as_deref().unwrap_or(""),
+        profile_updated.as_deref().unwrap_or(""),
+        query.cursor.as_deref().unwrap_or(""),
+        &limit.to_string(),
+        if avatars.static_avatars { "static" } else { "" },
+    ]);
+
+    let mut sql = format!(
+        r#"SELECT m.user_id, m.server_id, m.role, m.joined_at, u.username, {}, u.ring_style, u.ring_spin, u.steam_id, u.ring_pattern_seed, u.banner_css, u.banner_pattern_seed, m.nickname, {}
            FROM memberships m
            INNER JOIN "user" u ON u.id = m.user_id
            WHERE m.server_id = ?"#,
-    )
-    .bind(&server_id)
-    .fetch_all(&state.db)
-    .await
-    .unwrap_or_default();
+        avatars.image_column("u", "image"),
+        avatars.image_column("m", "avatar"),
+    );
+    if after.is_some() {
+        sql.push_str(" AND ");
+        sql.push_str(&pagination::after_condition("m.joined_at", "m.user_id", false));
+    }
+    sql.push_str(" ORDER BY m.joined_at ASC, m.user_id ASC LIMIT ?");
+
+    let mut members = sqlx::query_as::<_, MemberWithUser>(&sql).bind(&server_id);
+    if let Some(after) = &after {
+        members = members.bind(&after.key).bind(&after.id);
+    }
+    let members = members.bind(limit + 1).fetch_all(&state.db_read).await?;
 
-    Json(members).into_response()
+    Ok((
+        etag_headers(etag),
+        Json(pagination::page(members, limit, |m| Cursor::new(&m.joined_at, &m.user_id))),
+    )
+        .into_response())
 }
 
 /// PATCH /api/members/:userId/role
@@ -52,7 +96,7 @@ pub async fn update_member_role(
     user: AuthUser,
     Path(target_user_id): Path<String>,
     Json(body): Json<UpdateMemberRoleRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let server = sqlx::query_as::<_, (String,)>(
         "SELECT id FROM servers ORDER BY created_at ASC LIMIT 1",
     )
@@ -63,13 +107,7 @@ pub async fn update_member_role(
 
     let server_id = match server {
         Some((id,)) => id,
-        None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({"error": "No server found"})),
-            )
-                .into_response()
-        }
+        None => return Err(ApiError::not_found("No server found")),
     };
 
     let caller_role = sqlx::query_scalar::<_, String>(
@@ -84,13 +122,7 @@ pub async fn update_member_role(
 
     match caller_role.as_deref() {
         Some("owner") | Some("admin") => {}
-        _ => {
-            return (
-                StatusCode::FORBIDDEN,
-                Json(serde_json::json!({"error": "Insufficient permissions"})),
-            )
-                .into_response()
-        }
+        _ => return Err(ApiError::forbidden("Insufficient permissions")),
     }
 
     let target_info = sqlx::query_as::<_, (String, Option<String>)>(
@@ -105,29 +137,15 @@ pub async fn update_member_role(
 
     let (target_role, role_updated_at) = match target_info {
         Some(info) => info,
-        None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({"error": "Member not found"})),
-            )
-                .into_response()
-        }
+        None => return Err(ApiError::not_found("Member not found")),
     };
 
     if target_role == "owner" {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({"error": "Cannot change owner role"})),
-        )
-            .into_response();
+        return Err(ApiError::forbidden("Cannot change owner role"));
     }
 
     if body.role != "admin" && body.role != "member" {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Role must be 'admin' or 'member'"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("Role must be 'admin' or 'member'"));
     }
 
     // Demotion rules: admins can only demote other admins within 72 hours of their promotion
@@ -137,19 +155,11 @@ pub async fn update_member_role(
                 let hours_since = (chrono::Utc::now() - promoted_at.with_timezone(&chrono::Utc))
                     .num_hours();
                 if hours_since > 72 {
-                    return (
-                        StatusCode::FORBIDDEN,
-                        Json(serde_json::json!({"error": "Admins can only demote other admins within 72 hours of their promotion. Only the owner can demote after that."})),
-                    )
-                        .into_response();
+                    return Err(ApiError::forbidden("Admins can only demote other admins within 72 hours of their promotion. Only the owner can demote after that."));
                 }
             }
         } else {
-            return (
-                StatusCode::FORBIDDEN,
-                Json(serde_json::json!({"error": "Only the owner can demote this admin"})),
-            )
-                .into_response();
+            return Err(ApiError::forbidden("Only the owner can demote this admin"));
         }
     }
 
@@ -160,8 +170,7 @@ pub async fn update_member_role(
         .bind(&target_user_id)
         .bind(&server_id)
         .execute(&state.db)
-        .await
-        .ok();
+        .await?;
 
     state
         .gateway
@@ -170,10 +179,153 @@ pub async fn update_member_role(
                 server_id: server_id.clone(),
                 user_id: target_user_id.clone(),
                 role: body.role.clone(),
+                roles: None,
             },
             None,
         )
         .await;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// A nickname from a request body: trimmed, with blank meaning none.
+fn validate_nickname(nickname: Option<&str>) -> Result<Option<String>, ApiError> {
+    let Some(trimmed) = nickname.map(str::trim).filter(|n| !n.is_empty()) else {
+        return Ok(None);
+    };
+    if trimmed.chars().count() > 32 {
+        return Err(ApiError::bad_request("Nickname must be at most 32 characters"));
+    }
+    Ok(Some(trimmed.to_string()))
+}
+
+/// Tell everyone a member's per-server profile changed.
+async fn broadcast_member_profile(state: &AppState, server_id: &str, user_id: &str) -> Result<(), ApiError> {
+    let (nickname, server_avatar) = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+        "SELECT nickname, avatar FROM memberships WHERE user_id = ? AND server_id = ?",
+    )
+    .bind(user_id)
+    .bind(server_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    state
+        .gateway
+        .broadcast_all(
+            &crate::ws::events::ServerEvent::MemberProfileUpdated {
+                server_id: server_id.to_string(),
+                user_id: user_id.to_string(),
+                nickname,
+                server_avatar,
+            },
+            None,
+        )
+        .await;
+    Ok(())
+}
+
+/// PATCH /api/servers/:serverId/members/me/profile
+/// Set or clear your own nickname and avatar in a server.
+pub async fn update_my_member_profile(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+    Json(body): Json<UpdateMemberProfileRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let is_member = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
+    )
+    .bind(&user.id)
+    .bind(&server_id)
+    .fetch_one(&state.db)
+    .await?;
+    if is_member == 0 {
+        return Err(ApiError::forbidden("Not a member of this server"));
+    }
+
+    let nickname = match &body.nickname {
+        None => None,
+        Some(serde_json::Value::Null) => Some(None),
+        Some(serde_json::Value::String(n)) => Some(validate_nickname(Some(n))?),
+        Some(_) => return Err(ApiError::bad_request("Nickname must be a string or null")),
+    };
+    let avatar = match &body.avatar {
+        None => None,
+        Some(serde_json::Value::Null) => Some(None),
+        Some(serde_json::Value::String(img)) => Some(Some(process_upload(img, state.config.avatar_max_bytes).await?)),
+        Some(_) => return Err(ApiError::bad_request("Avatar must be a string or null")),
+    };
+    if nickname.is_none() && avatar.is_none() {
+        return Err(ApiError::bad_request("No fields to update"));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    if let Some(nickname) = nickname {
+        sqlx::query("UPDATE memberships SET nickname = ?, profile_updated_at = ? WHERE user_id = ? AND server_id = ?")
+            .bind(nickname)
+            .bind(&now)
+            .bind(&user.id)
+            .bind(&server_id)
+            .execute(&state.db)
+            .await?;
+    }
+    if let Some(avatar) = avatar {
+        let (image, image_static) = match avatar {
+            Some(avatar) => (Some(avatar.image), avatar.static_image),
+            None => (None, None),
+        };
+        sqlx::query("UPDATE memberships SET avatar = ?, avatar_static = ?, profile_updated_at = ? WHERE user_id = ? AND server_id = ?")
+            .bind(image)
+            .bind(image_static)
+            .bind(&now)
+            .bind(&user.id)
+            .bind(&server_id)
+            .execute(&state.db)
+            .await?;
+    }
+
+    broadcast_member_profile(&state, &server_id, &user.id).await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// PATCH /api/servers/:serverId/members/:userId/nickname
+/// Owners can rename anyone; admins can rename members.
+pub async fn set_member_nickname(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, target_user_id)): Path<(String, String)>,
+    Json(body): Json<SetNicknameRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let roles = sqlx::query_as::<_, (String, String)>(
+        "SELECT user_id, role FROM memberships WHERE server_id = ? AND user_id IN (?, ?)",
+    )
+    .bind(&server_id)
+    .bind(&user.id)
+    .bind(&target_user_id)
+    .fetch_all(&state.db)
+    .await?;
+    let role_of = |id: &str| roles.iter().find(|(uid, _)| uid == id).map(|(_, role)| role.as_str());
+
+    let caller_role = role_of(&user.id);
+    if !matches!(caller_role, Some("owner") | Some("admin")) {
+        return Err(ApiError::forbidden("Insufficient permissions"));
+    }
+    let Some(target_role) = role_of(&target_user_id) else {
+        return Err(ApiError::not_found("Member not found"));
+    };
+    if target_user_id != user.id && caller_role == Some("admin") && target_role != "member" {
+        return Err(ApiError::forbidden("Admins can only change members' nicknames"));
+    }
+
+    let nickname = validate_nickname(body.nickname.as_deref())?;
+    sqlx::query("UPDATE memberships SET nickname = ?, profile_updated_at = ? WHERE user_id = ? AND server_id = ?")
+        .bind(nickname)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(&target_user_id)
+        .bind(&server_id)
+        .execute(&state.db)
+        .await?;
+
+    broadcast_member_profile(&state, &server_id, &target_user_id).await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
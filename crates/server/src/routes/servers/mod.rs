@@ -1,12 +1,20 @@
 mod channels;
 mod channels_manage;
+mod insights;
 mod members;
+mod roles;
 mod rooms;
+mod verification;
+mod welcome;
 
 pub use channels::*;
 pub use channels_manage::*;
+pub use insights::*;
 pub use members::*;
+pub use roles::*;
 pub use rooms::*;
+pub use verification::*;
+pub use welcome::*;
 
 use axum::{
     extract::{Path, State},
@@ -16,6 +24,7 @@ use axum::{
 };
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::{AuthUser, Server, ServerWithRole, UpdateServerRequest};
 use crate::AppState;
 
@@ -25,13 +34,13 @@ pub async fn list_servers(
     user: AuthUser,
 ) -> impl IntoResponse {
     let servers = sqlx::query_as::<_, ServerWithRole>(
-        r#"SELECT s.id, s.name, s.owner_id, s.invite_code, s.created_at, m.role
+        r#"SELECT s.id, s.name, s.owner_id, s.invite_code, s.created_at, s.retention_days, m.role
            FROM memberships m
            INNER JOIN servers s ON s.id = m.server_id
            WHERE m.user_id = ?"#,
     )
     .bind(&user.id)
-    .fetch_all(&state.db)
+    .fetch_all(&state.db_read)
     .await
     .unwrap_or_default();
 
@@ -43,7 +52,7 @@ pub async fn get_server(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path(server_id): Path<String>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let membership = sqlx::query_scalar::<_, String>(
         "SELECT role FROM memberships WHERE user_id = ? AND server_id = ?",
     )
@@ -56,13 +65,7 @@ pub async fn get_server(
 
     let role = match membership {
         Some(r) => r,
-        None => {
-            return (
-                StatusCode::FORBIDDEN,
-                Json(serde_json::json!({"error": "Not a member of this server"})),
-            )
-                .into_response()
-        }
+        None => return Err(ApiError::forbidden("Not a member of this server")),
     };
 
     let server = sqlx::query_as::<_, Server>("SELECT * FROM servers WHERE id = ?")
@@ -73,20 +76,17 @@ pub async fn get_server(
         .flatten();
 
     match server {
-        Some(s) => Json(ServerWithRole {
+        Some(s) => Ok(Json(ServerWithRole {
             id: s.id,
             name: s.name,
             owner_id: s.owner_id,
             invite_code: s.invite_code,
             created_at: s.created_at,
+            retention_days: s.retention_days,
             role,
         })
-        .into_response(),
-        None => (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({"error": "Server not found"})),
-        )
-            .into_response(),
+        .into_response()),
+        None => Err(ApiError::not_found("Server not found")),
     }
 }
 
@@ -96,7 +96,7 @@ pub async fn update_server(
     user: AuthUser,
     Path(server_id): Path<String>,
     Json(body): Json<UpdateServerRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let role = sqlx::query_scalar::<_, String>(
         "SELECT role FROM memberships WHERE user_id = ? AND server_id = ?",
     )
@@ -110,11 +110,7 @@ pub async fn update_server(
     match role.as_deref() {
         Some("owner") | Some("admin") => {}
         _ => {
-            return (
-                StatusCode::FORBIDDEN,
-                Json(serde_json::json!({"error": "Insufficient permissions"})),
-            )
-                .into_response();
+            return Err(ApiError::forbidden("Insufficient permissions"));
         }
     }
 
@@ -127,29 +123,29 @@ pub async fn update_server(
 
     let server = match server {
         Some(s) => s,
-        None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({"error": "Server not found"})),
-            )
-                .into_response()
-        }
+        None => return Err(ApiError::not_found("Server not found")),
     };
 
     let new_name = if let Some(ref name) = body.name {
         if let Err(e) = flux_shared::validation::validate_server_name(name) {
-            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e}))).into_response();
+            return Err(ApiError::bad_request(e));
         }
         name.trim().to_string()
     } else {
         server.name.clone()
     };
 
-    let _ = sqlx::query("UPDATE servers SET name = ? WHERE id = ?")
+    let new_retention = match body.retention_days {
+        Some(ref value) => crate::routes::messages::parse_retention_days(value, false)?,
+        None => server.retention_days,
+    };
+
+    sqlx::query("UPDATE servers SET name = ?, retention_days = ? WHERE id = ?")
         .bind(&new_name)
+        .bind(new_retention)
         .bind(&server_id)
         .execute(&state.db)
-        .await;
+        .await?;
 
     state
         .gateway
@@ -168,9 +164,10 @@ pub async fn update_server(
         owner_id: server.owner_id,
         invite_code: server.invite_code,
         created_at: server.created_at,
+        retention_days: new_retention,
     };
 
-    Json(updated).into_response()
+    Ok(Json(updated).into_response())
 }
 
 /// DELETE /api/servers/:serverId/members/me
@@ -178,7 +175,7 @@ pub async fn leave_server(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path(server_id): Path<String>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let role = sqlx::query_scalar::<_, String>(
         "SELECT role FROM memberships WHERE user_id = ? AND server_id = ?",
     )
@@ -190,28 +187,21 @@ pub async fn leave_server(
     .flatten();
 
     match role.as_deref() {
-        None => {
-            return (
-                StatusCode::FORBIDDEN,
-                Json(serde_json::json!({"error": "Not a member of this server"})),
-            )
-                .into_response()
-        }
-        Some("owner") => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": "Server owner cannot leave. Delete the server instead."})),
-            )
-                .into_response()
-        }
+        None => return Err(ApiError::forbidden("Not a member of this server")),
+        Some("owner") => return Err(ApiError::bad_request("Server owner cannot leave. Delete the server instead.")),
         _ => {}
     }
 
-    let _ = sqlx::query("DELETE FROM memberships WHERE user_id = ? AND server_id = ?")
+    sqlx::query("DELETE FROM memberships WHERE user_id = ? AND server_id = ?")
         .bind(&user.id)
         .bind(&server_id)
         .execute(&state.db)
-        .await;
+        .await?;
+    sqlx::query("DELETE FROM member_roles WHERE user_id = ? AND server_id = ?")
+        .bind(&user.id)
+        .bind(&server_id)
+        .execute(&state.db)
+        .await?;
 
     state
         .gateway
@@ -224,5 +214,5 @@ pub async fn leave_server(
         )
         .await;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
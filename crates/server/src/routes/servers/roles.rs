@@ -0,0 +1,348 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, CreateReactionRoleRequest, CreateRoleRequest, ReactionRole, ServerRole};
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+const MAX_ROLES_PER_SERVER: i64 = 100;
+const MAX_BINDINGS_PER_MESSAGE: i64 = 20;
+const MAX_ROLE_NAME_CHARS: usize = 32;
+
+const REACTION_ROLE_SELECT: &str = r#"
+    SELECT rr.id, rr.message_id, m.channel_id, rr.emoji, rr.role_id, r.name AS role_name,
+           rr.created_by, rr.created_at
+    FROM reaction_roles rr
+    INNER JOIN server_roles r ON r.id = rr.role_id
+    INNER JOIN messages m ON m.id = rr.message_id
+"#;
+
+async fn member_role(state: &AppState, user_id: &str, server_id: &str) -> Result<Option<String>, ApiError> {
+    Ok(
+        sqlx::query_scalar::<_, String>("SELECT role FROM memberships WHERE user_id = ? AND server_id = ?")
+            .bind(user_id)
+            .bind(server_id)
+            .fetch_optional(&state.db)
+            .await?,
+    )
+}
+
+async fn require_server_admin(state: &AppState, user_id: &str, server_id: &str) -> Result<(), ApiError> {
+    match member_role(state, user_id, server_id).await?.as_deref() {
+        Some("owner") | Some("admin") => Ok(()),
+        _ => Err(ApiError::forbidden("Insufficient permissions")),
+    }
+}
+
+/// GET /api/servers/:serverId/roles
+/// Custom roles with the members holding each.
+pub async fn list_roles(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    if member_role(&state, &user.id, &server_id).await?.is_none() {
+        return Err(ApiError::forbidden("Not a member of this server"));
+    }
+
+    let mut roles = sqlx::query_as::<_, ServerRole>(
+        "SELECT id, server_id, name, color, created_at FROM server_roles WHERE server_id = ? ORDER BY name COLLATE NOCASE ASC",
+    )
+    .bind(&server_id)
+    .fetch_all(&state.db_read)
+    .await?;
+
+    let mut holders: HashMap<String, Vec<String>> = HashMap::new();
+    for (role_id, user_id) in sqlx::query_as::<_, (String, String)>(
+        "SELECT role_id, user_id FROM member_roles WHERE server_id = ? ORDER BY assigned_at ASC",
+    )
+    .bind(&server_id)
+    .fetch_all(&state.db_read)
+    .await?
+    {
+        holders.entry(role_id).or_default().push(user_id);
+    }
+    for role in &mut roles {
+        role.member_ids = holders.remove(&role.id).unwrap_or_default();
+    }
+
+    Ok(Json(roles))
+}
+
+/// POST /api/servers/:serverId/roles
+pub async fn create_role(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+    Json(body): Json<CreateRoleRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let name = body.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_ROLE_NAME_CHARS {
+        return Err(ApiError::bad_request(format!(
+            "Role name must be 1-{} characters",
+            MAX_ROLE_NAME_CHARS
+        )));
+    }
+    if ["owner", "admin", "member"].contains(&name.to_lowercase().as_str()) {
+        return Err(ApiError::bad_request("That role name is reserved"));
+    }
+    let color = body.color.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    if let Some(c) = color {
+        let re = regex_lite::Regex::new(r"^#[0-9a-fA-F]{6}$").unwrap();
+        if !re.is_match(c) {
+            return Err(ApiError::bad_request("Color must be a hex color like #5865f2"));
+        }
+    }
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM server_roles WHERE server_id = ?")
+        .bind(&server_id)
+        .fetch_one(&state.db)
+        .await?;
+    if count >= MAX_ROLES_PER_SERVER {
+        return Err(ApiError::bad_request(format!(
+            "A server can have at most {} roles",
+            MAX_ROLES_PER_SERVER
+        )));
+    }
+
+    let role = ServerRole {
+        id: uuid::Uuid::new_v4().to_string(),
+        server_id: server_id.clone(),
+        name: name.to_string(),
+        color: color.map(str::to_lowercase),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        member_ids: Vec::new(),
+    };
+    let inserted = sqlx::query(
+        "INSERT OR IGNORE INTO server_roles (id, server_id, name, color, created_at) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&role.id)
+    .bind(&role.server_id)
+    .bind(&role.name)
+    .bind(&role.color)
+    .bind(&role.created_at)
+    .execute(&state.db)
+    .await?;
+    if inserted.rows_affected() == 0 {
+        return Err(ApiError::conflict("A role with that name already exists"));
+    }
+
+    Ok((StatusCode::CREATED, Json(role)))
+}
+
+/// DELETE /api/servers/:serverId/roles/:roleId
+/// Also removes the role from its members and drops its reaction bindings.
+pub async fn delete_role(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, role_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let holders = sqlx::query_scalar::<_, String>("SELECT user_id FROM member_roles WHERE role_id = ?")
+        .bind(&role_id)
+        .fetch_all(&state.db)
+        .await?;
+    let deleted = sqlx::query("DELETE FROM server_roles WHERE id = ? AND server_id = ?")
+        .bind(&role_id)
+        .bind(&server_id)
+        .execute(&state.db)
+        .await?;
+    if deleted.rows_affected() == 0 {
+        return Err(ApiError::not_found("Role not found"));
+    }
+
+    for user_id in holders {
+        broadcast_member_roles(&state, &server_id, &user_id).await;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/servers/:serverId/reaction-roles
+pub async fn list_reaction_roles(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let bindings = sqlx::query_as::<_, ReactionRole>(&format!(
+        "{} WHERE rr.server_id = ? ORDER BY rr.created_at ASC",
+        REACTION_ROLE_SELECT
+    ))
+    .bind(&server_id)
+    .fetch_all(&state.db_read)
+    .await?;
+
+    Ok(Json(bindings))
+}
+
+/// POST /api/servers/:serverId/reaction-roles
+/// Bind an emoji on one of the server's messages to a role. Members who
+/// already reacted with it aren't granted the role retroactively.
+pub async fn create_reaction_role(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+    Json(body): Json<CreateReactionRoleRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let emoji = body.emoji.trim();
+    if emoji.is_empty() || emoji.chars().count() > 64 {
+        return Err(ApiError::bad_request("Invalid emoji"));
+    }
+
+    let in_server: Option<String> = sqlx::query_scalar(
+        "SELECT c.server_id FROM messages m INNER JOIN channels c ON c.id = m.channel_id WHERE m.id = ?",
+    )
+    .bind(&body.message_id)
+    .fetch_optional(&state.db)
+    .await?;
+    if in_server.as_deref() != Some(server_id.as_str()) {
+        return Err(ApiError::not_found("Message not found"));
+    }
+    let role_exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM server_roles WHERE id = ? AND server_id = ?")
+        .bind(&body.role_id)
+        .bind(&server_id)
+        .fetch_one(&state.db)
+        .await?;
+    if role_exists == 0 {
+        return Err(ApiError::not_found("Role not found"));
+    }
+
+    let bindings: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM reaction_roles WHERE message_id = ?")
+        .bind(&body.message_id)
+        .fetch_one(&state.db)
+        .await?;
+    if bindings >= MAX_BINDINGS_PER_MESSAGE {
+        return Err(ApiError::bad_request(format!(
+            "A message can have at most {} reaction roles",
+            MAX_BINDINGS_PER_MESSAGE
+        )));
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let inserted = sqlx::query(
+        r#"INSERT OR IGNORE INTO reaction_roles (id, server_id, message_id, emoji, role_id, created_by, created_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(&id)
+    .bind(&server_id)
+    .bind(&body.message_id)
+    .bind(emoji)
+    .bind(&body.role_id)
+    .bind(&user.id)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&state.db)
+    .await?;
+    if inserted.rows_affected() == 0 {
+        return Err(ApiError::conflict("That emoji already grants a role on this message"));
+    }
+
+    let binding = sqlx::query_as::<_, ReactionRole>(&format!("{} WHERE rr.id = ?", REACTION_ROLE_SELECT))
+        .bind(&id)
+        .fetch_one(&state.db)
+        .await?;
+    Ok((StatusCode::CREATED, Json(binding)))
+}
+
+/// DELETE /api/servers/:serverId/reaction-roles/:bindingId
+/// Members keep roles they already got through it.
+pub async fn delete_reaction_role(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path((server_id, binding_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let deleted = sqlx::query("DELETE FROM reaction_roles WHERE id = ? AND server_id = ?")
+        .bind(&binding_id)
+        .bind(&server_id)
+        .execute(&state.db)
+        .await?;
+    if deleted.rows_affected() == 0 {
+        return Err(ApiError::not_found("Reaction role not found"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Tell everyone a member's roles changed, with their full custom role list.
+async fn broadcast_member_roles(state: &AppState, server_id: &str, user_id: &str) {
+    let Ok(Some(role)) = member_role(state, user_id, server_id).await else {
+        return;
+    };
+    let roles = sqlx::query_scalar::<_, String>(
+        "SELECT role_id FROM member_roles WHERE server_id = ? AND user_id = ? ORDER BY assigned_at ASC",
+    )
+    .bind(server_id)
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    state
+        .gateway
+        .broadcast_all(
+            &ServerEvent::MemberRoleUpdated {
+                server_id: server_id.to_string(),
+                user_id: user_id.to_string(),
+                role,
+                roles: Some(roles),
+            },
+            None,
+        )
+        .await;
+}
+
+/// Grant (on react) or revoke (on unreact) the role bound to an emoji on a
+/// message, if there is one. Called by the gateway's reaction handlers.
+pub(crate) async fn apply_reaction_role(state: &AppState, user_id: &str, message_id: &str, emoji: &str, add: bool) {
+    let binding = sqlx::query_as::<_, (String, String)>(
+        r#"SELECT rr.server_id, rr.role_id FROM reaction_roles rr
+           INNER JOIN memberships ms ON ms.server_id = rr.server_id AND ms.user_id = ?
+           WHERE rr.message_id = ? AND rr.emoji = ?"#,
+    )
+    .bind(user_id)
+    .bind(message_id)
+    .bind(emoji)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+    let Some((server_id, role_id)) = binding else {
+        return;
+    };
+
+    let result = if add {
+        sqlx::query("INSERT OR IGNORE INTO member_roles (server_id, user_id, role_id, assigned_at) VALUES (?, ?, ?, ?)")
+            .bind(&server_id)
+            .bind(user_id)
+            .bind(&role_id)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .execute(&state.db)
+            .await
+    } else {
+        sqlx::query("DELETE FROM member_roles WHERE user_id = ? AND role_id = ?")
+            .bind(user_id)
+            .bind(&role_id)
+            .execute(&state.db)
+            .await
+    };
+    match result {
+        Ok(r) if r.rows_affected() > 0 => broadcast_member_roles(state, &server_id, user_id).await,
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to apply reaction role: {}", e),
+    }
+}
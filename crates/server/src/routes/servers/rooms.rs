@@ -6,6 +6,7 @@ use axum::{
 };
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::{AcceptKnockRequest, AuthUser, Channel, InviteToRoomRequest, MoveUserRequest};
 use crate::AppState;
 
@@ -66,7 +67,7 @@ pub async fn invite_to_room(
     user: AuthUser,
     Path((server_id, channel_id)): Path<(String, String)>,
     Json(body): Json<InviteToRoomRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let inviter_member = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
     )
@@ -77,7 +78,7 @@ pub async fn invite_to_room(
     .unwrap_or(0);
 
     if inviter_member == 0 {
-        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Not a member"}))).into_response();
+        return Err(ApiError::forbidden("Not a member"));
     }
 
     let channel = sqlx::query_as::<_, Channel>(
@@ -92,7 +93,7 @@ pub async fn invite_to_room(
 
     let channel = match channel {
         Some(c) => c,
-        None => return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Room not found"}))).into_response(),
+        None => return Err(ApiError::not_found("Room not found")),
     };
 
     let target_member = sqlx::query_scalar::<_, i64>(
@@ -105,7 +106,10 @@ pub async fn invite_to_room(
     .unwrap_or(0);
 
     if target_member == 0 {
-        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Target user is not a server member"}))).into_response();
+        return Err(ApiError::bad_request("Target user is not a server member"));
+    }
+    if crate::routes::users::is_blocked(&state, &user.id, &body.user_id).await {
+        return Err(ApiError::forbidden("You can't invite this user"));
     }
 
     state
@@ -122,7 +126,7 @@ pub async fn invite_to_room(
         )
         .await;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
 
 /// POST /api/servers/:serverId/rooms/:channelId/move
@@ -131,7 +135,7 @@ pub async fn move_user(
     user: AuthUser,
     Path((server_id, channel_id)): Path<(String, String)>,
     Json(body): Json<MoveUserRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let role = sqlx::query_scalar::<_, String>(
         "SELECT role FROM memberships WHERE user_id = ? AND server_id = ?",
     )
@@ -143,7 +147,7 @@ pub async fn move_user(
     .flatten();
 
     if !matches!(role.as_deref(), Some("owner") | Some("admin")) {
-        return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "Insufficient permissions"}))).into_response();
+        return Err(ApiError::forbidden("Insufficient permissions"));
     }
 
     let source = sqlx::query_as::<_, Channel>(
@@ -157,7 +161,7 @@ pub async fn move_user(
     .flatten();
 
     if source.is_none() {
-        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Source channel not found"}))).into_response();
+        return Err(ApiError::not_found("Source channel not found"));
     }
 
     let target = sqlx::query_as::<_, Channel>(
@@ -172,13 +176,13 @@ pub async fn move_user(
 
     let target = match target {
         Some(t) => t,
-        None => return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Target channel not found"}))).into_response(),
+        None => return Err(ApiError::not_found("Target channel not found")),
     };
 
     let participants = state.gateway.voice_channel_participants(&channel_id).await;
     let user_in_channel = participants.iter().any(|p| p.user_id == body.user_id);
     if !user_in_channel {
-        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "User is not in the source channel"}))).into_response();
+        return Err(ApiError::bad_request("User is not in the source channel"));
     }
 
     state
@@ -192,5 +196,5 @@ pub async fn move_user(
         )
         .await;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
@@ -0,0 +1,246 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::ApiError;
+use crate::models::{
+    AuthUser, ChallengeRequest, UnmetRequirement, UpdateVerificationRequest, VerificationSettings,
+    VerificationStatus,
+};
+use crate::AppState;
+
+/// Longest minimum account age a server can ask for: a year.
+const MAX_ACCOUNT_AGE_MINUTES: i64 = 525_600;
+
+#[derive(sqlx::FromRow)]
+struct GateRow {
+    min_account_age_minutes: i64,
+    require_verified_email: bool,
+    require_challenge: bool,
+    role: String,
+    challenge_passed_at: Option<String>,
+    account_created_at: String,
+    email_verified: bool,
+}
+
+async fn require_server_admin(state: &AppState, user_id: &str, server_id: &str) -> Result<(), ApiError> {
+    let role = sqlx::query_scalar::<_, String>("SELECT role FROM memberships WHERE user_id = ? AND server_id = ?")
+        .bind(user_id)
+        .bind(server_id)
+        .fetch_optional(&state.db)
+        .await?;
+    match role.as_deref() {
+        Some("owner") | Some("admin") => Ok(()),
+        _ => Err(ApiError::forbidden("Insufficient permissions")),
+    }
+}
+
+fn challenges_configured(state: &AppState) -> bool {
+    !state.config.challenge_verify_url.is_empty() && !state.config.challenge_secret.is_empty()
+}
+
+async fn fetch_settings(state: &AppState, server_id: &str) -> Result<VerificationSettings, ApiError> {
+    sqlx::query_as::<_, VerificationSettings>(
+        "SELECT min_account_age_minutes, require_verified_email, require_challenge FROM servers WHERE id = ?",
+    )
+    .bind(server_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Server not found"))
+}
+
+/// Requirements `user_id` still has to meet in `server_id`, in the order a
+/// client should walk them through. Empty for owners, admins and
+/// non-members.
+pub(crate) async fn unmet_requirements(
+    state: &AppState,
+    user_id: &str,
+    server_id: &str,
+) -> Result<Vec<UnmetRequirement>, ApiError> {
+    let row = sqlx::query_as::<_, GateRow>(
+        r#"SELECT s.min_account_age_minutes, s.require_verified_email, s.require_challenge,
+                  ms.role, ms.challenge_passed_at,
+                  u.createdAt AS account_created_at, u.emailVerified AS email_verified
+           FROM servers s
+           INNER JOIN memberships ms ON ms.server_id = s.id AND ms.user_id = ?
+           INNER JOIN "user" u ON u.id = ms.user_id
+           WHERE s.id = ?"#,
+    )
+    .bind(user_id)
+    .bind(server_id)
+    .fetch_optional(&state.db)
+    .await?;
+    let Some(row) = row else {
+        return Ok(Vec::new());
+    };
+    if row.role != "member" {
+        return Ok(Vec::new());
+    }
+
+    let mut unmet = Vec::new();
+    if row.require_verified_email && !row.email_verified {
+        unmet.push(UnmetRequirement { code: "email_unverified", available_at: None });
+    }
+    if row.min_account_age_minutes > 0 {
+        // An unparseable creation time counts as brand new
+        let created = chrono::DateTime::parse_from_rfc3339(&row.account_created_at)
+            .map(|t| t.with_timezone(&chrono::Utc))
+            .unwrap_or_else(|_| chrono::Utc::now());
+        let available = created + chrono::Duration::minutes(row.min_account_age_minutes);
+        if available > chrono::Utc::now() {
+            unmet.push(UnmetRequirement {
+                code: "account_too_new",
+                available_at: Some(available.to_rfc3339()),
+            });
+        }
+    }
+    if row.require_challenge && row.challenge_passed_at.is_none() {
+        unmet.push(UnmetRequirement { code: "challenge_required", available_at: None });
+    }
+    Ok(unmet)
+}
+
+/// The first requirement holding `user_id` back from posting in
+/// `channel_id`, with the channel's server.
+pub(crate) async fn posting_blocked(
+    state: &AppState,
+    user_id: &str,
+    channel_id: &str,
+) -> Option<(String, UnmetRequirement)> {
+    let server_id = sqlx::query_scalar::<_, String>("SELECT server_id FROM channels WHERE id = ?")
+        .bind(channel_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten()?;
+    let first = unmet_requirements(state, user_id, &server_id).await.ok()?.into_iter().next()?;
+    Some((server_id, first))
+}
+
+/// GET /api/servers/:serverId/verification
+/// The server's requirements and which ones the caller still has to meet.
+pub async fn get_verification(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let is_member: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?")
+        .bind(&user.id)
+        .bind(&server_id)
+        .fetch_one(&state.db)
+        .await?;
+    if is_member == 0 {
+        return Err(ApiError::forbidden("Not a member of this server"));
+    }
+
+    let settings = fetch_settings(&state, &server_id).await?;
+    let unmet = unmet_requirements(&state, &user.id, &server_id).await?;
+    Ok(Json(VerificationStatus {
+        settings,
+        challenge_site_key: challenges_configured(&state).then(|| state.config.challenge_site_key.clone()),
+        unmet,
+    }))
+}
+
+/// PATCH /api/servers/:serverId/verification
+pub async fn update_verification(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+    Json(body): Json<UpdateVerificationRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let mut settings = fetch_settings(&state, &server_id).await?;
+    if let Some(minutes) = body.min_account_age_minutes {
+        if !(0..=MAX_ACCOUNT_AGE_MINUTES).contains(&minutes) {
+            return Err(ApiError::bad_request(format!(
+                "Minimum account age must be 0-{} minutes",
+                MAX_ACCOUNT_AGE_MINUTES
+            )));
+        }
+        settings.min_account_age_minutes = minutes;
+    }
+    if let Some(require) = body.require_verified_email {
+        settings.require_verified_email = require;
+    }
+    if let Some(require) = body.require_challenge {
+        if require && !challenges_configured(&state) {
+            return Err(ApiError::unavailable("Challenges are not configured on this server"));
+        }
+        settings.require_challenge = require;
+    }
+
+    sqlx::query(
+        "UPDATE servers SET min_account_age_minutes = ?, require_verified_email = ?, require_challenge = ? WHERE id = ?",
+    )
+    .bind(settings.min_account_age_minutes)
+    .bind(settings.require_verified_email)
+    .bind(settings.require_challenge)
+    .bind(&server_id)
+    .execute(&state.db)
+    .await?;
+
+    Ok(Json(settings))
+}
+
+/// Ask the captcha provider whether `token` is a solved challenge. Speaks the
+/// `siteverify` protocol hCaptcha, Turnstile and reCAPTCHA share.
+async fn verify_challenge(state: &AppState, token: &str) -> Result<bool, ApiError> {
+    let resp = reqwest::Client::new()
+        .post(&state.config.challenge_verify_url)
+        .form(&[("secret", state.config.challenge_secret.as_str()), ("response", token)])
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| {
+            tracing::warn!("Challenge verification failed: {}", e);
+            ApiError::upstream("Couldn't reach the challenge provider")
+        })?;
+    let body: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|_| ApiError::upstream("The challenge provider sent an invalid response"))?;
+    Ok(body["success"].as_bool().unwrap_or(false))
+}
+
+/// POST /api/servers/:serverId/verification/challenge
+/// Submit a solved challenge token from the provider's widget.
+pub async fn submit_challenge(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+    Json(body): Json<ChallengeRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    if !challenges_configured(&state) {
+        return Err(ApiError::unavailable("Challenges are not configured on this server"));
+    }
+    let is_member: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?")
+        .bind(&user.id)
+        .bind(&server_id)
+        .fetch_one(&state.db)
+        .await?;
+    if is_member == 0 {
+        return Err(ApiError::forbidden("Not a member of this server"));
+    }
+
+    let token = body.token.trim();
+    if token.is_empty() || !verify_challenge(&state, token).await? {
+        return Err(ApiError::bad_request("Challenge failed, try again")
+            .with_details(serde_json::json!({ "requirement": "challenge_required" })));
+    }
+
+    sqlx::query("UPDATE memberships SET challenge_passed_at = ? WHERE user_id = ? AND server_id = ?")
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(&user.id)
+        .bind(&server_id)
+        .execute(&state.db)
+        .await?;
+
+    let unmet = unmet_requirements(&state, &user.id, &server_id).await?;
+    Ok(Json(serde_json::json!({ "unmet": unmet })))
+}
@@ -0,0 +1,250 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, SuggestedChannel, UpdateWelcomeRequest, WelcomeScreen};
+use crate::AppState;
+
+const MAX_WELCOME_MESSAGE_CHARS: usize = 2000;
+const MAX_RULES_CHARS: usize = 6000;
+const MAX_SUGGESTED_CHANNELS: usize = 5;
+
+#[derive(sqlx::FromRow)]
+struct WelcomeRow {
+    message: Option<String>,
+    rules: Option<String>,
+    suggested_channel_ids: String,
+    rules_updated_at: Option<String>,
+    updated_at: String,
+}
+
+async fn fetch_welcome(state: &AppState, server_id: &str) -> Result<Option<WelcomeRow>, ApiError> {
+    Ok(sqlx::query_as::<_, WelcomeRow>(
+        "SELECT message, rules, suggested_channel_ids, rules_updated_at, updated_at FROM server_welcome WHERE server_id = ?",
+    )
+    .bind(server_id)
+    .fetch_optional(&state.db)
+    .await?)
+}
+
+async fn require_server_admin(state: &AppState, user_id: &str, server_id: &str) -> Result<(), ApiError> {
+    let role = sqlx::query_scalar::<_, String>("SELECT role FROM memberships WHERE user_id = ? AND server_id = ?")
+        .bind(user_id)
+        .bind(server_id)
+        .fetch_optional(&state.db)
+        .await?;
+    match role.as_deref() {
+        Some("owner") | Some("admin") => Ok(()),
+        _ => Err(ApiError::forbidden("Insufficient permissions")),
+    }
+}
+
+/// Trimmed text with blank meaning none, capped at `max` characters.
+fn optional_text(value: Option<&str>, max: usize, what: &str) -> Result<Option<String>, ApiError> {
+    let Some(text) = value.map(str::trim).filter(|t| !t.is_empty()) else {
+        return Ok(None);
+    };
+    if text.chars().count() > max {
+        return Err(ApiError::bad_request(format!("{} must be at most {} characters", what, max)));
+    }
+    Ok(Some(text.to_string()))
+}
+
+/// Whether `user_id` must accept the rules of `channel_id`'s server before
+/// posting there: the server has rules, and the member hasn't accepted them
+/// since they last changed. Owners and admins are never held back.
+pub(crate) async fn rules_pending(state: &AppState, user_id: &str, channel_id: &str) -> bool {
+    sqlx::query_scalar::<_, i64>(
+        r#"SELECT COUNT(*) FROM channels c
+           INNER JOIN memberships ms ON ms.server_id = c.server_id AND ms.user_id = ?
+           INNER JOIN server_welcome w ON w.server_id = c.server_id
+           WHERE c.id = ? AND ms.role = 'member' AND w.rules IS NOT NULL
+             AND (ms.rules_accepted_at IS NULL OR ms.rules_accepted_at < w.rules_updated_at)"#,
+    )
+    .bind(user_id)
+    .bind(channel_id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0)
+        > 0
+}
+
+/// GET /api/servers/:serverId/welcome
+/// The welcome screen for the caller, which clients show on first join.
+/// Every field is empty when the server hasn't set one up.
+pub async fn get_welcome(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let membership = sqlx::query_as::<_, (String, Option<String>)>(
+        "SELECT role, rules_accepted_at FROM memberships WHERE user_id = ? AND server_id = ?",
+    )
+    .bind(&user.id)
+    .bind(&server_id)
+    .fetch_optional(&state.db)
+    .await?;
+    let Some((role, rules_accepted_at)) = membership else {
+        return Err(ApiError::forbidden("Not a member of this server"));
+    };
+
+    let Some(row) = fetch_welcome(&state, &server_id).await? else {
+        return Ok(Json(WelcomeScreen {
+            server_id,
+            message: None,
+            rules: None,
+            suggested_channels: Vec::new(),
+            rules_updated_at: None,
+            updated_at: None,
+            rules_accepted_at,
+            requires_acceptance: false,
+        }));
+    };
+
+    let ids: Vec<String> = serde_json::from_str(&row.suggested_channel_ids).unwrap_or_default();
+    let mut channels = sqlx::query_as::<_, SuggestedChannel>(
+        "SELECT id, name, type FROM channels WHERE server_id = ?",
+    )
+    .bind(&server_id)
+    .fetch_all(&state.db_read)
+    .await?;
+    // Keep the admin's order, dropping channels deleted since
+    let suggested_channels = ids
+        .iter()
+        .filter_map(|id| channels.iter().position(|c| &c.id == id).map(|i| channels.swap_remove(i)))
+        .collect();
+
+    let requires_acceptance = role == "member"
+        && row.rules.is_some()
+        && match (&rules_accepted_at, &row.rules_updated_at) {
+            (None, _) => true,
+            (Some(accepted), Some(changed)) => accepted < changed,
+            (Some(_), None) => false,
+        };
+
+    Ok(Json(WelcomeScreen {
+        server_id,
+        message: row.message,
+        rules: row.rules,
+        suggested_channels,
+        rules_updated_at: row.rules_updated_at,
+        updated_at: Some(row.updated_at),
+        rules_accepted_at,
+        requires_acceptance,
+    }))
+}
+
+/// PUT /api/servers/:serverId/welcome
+/// Replace the welcome screen. Changing the rules text asks every member to
+/// accept them again.
+pub async fn update_welcome(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+    Json(body): Json<UpdateWelcomeRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let message = optional_text(body.message.as_deref(), MAX_WELCOME_MESSAGE_CHARS, "Welcome message")?;
+    let rules = optional_text(body.rules.as_deref(), MAX_RULES_CHARS, "Rules")?;
+
+    let mut channel_ids: Vec<String> = Vec::new();
+    for id in body.suggested_channel_ids {
+        if !channel_ids.contains(&id) {
+            channel_ids.push(id);
+        }
+    }
+    if channel_ids.len() > MAX_SUGGESTED_CHANNELS {
+        return Err(ApiError::bad_request(format!(
+            "At most {} suggested channels",
+            MAX_SUGGESTED_CHANNELS
+        )));
+    }
+    for id in &channel_ids {
+        let found: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM channels WHERE id = ? AND server_id = ?")
+            .bind(id)
+            .bind(&server_id)
+            .fetch_one(&state.db)
+            .await?;
+        if found == 0 {
+            return Err(ApiError::not_found("Channel not found"));
+        }
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let previous = fetch_welcome(&state, &server_id).await?;
+    let rules_updated_at = match (&rules, previous) {
+        (None, _) => None,
+        (Some(new), Some(old)) if old.rules.as_ref() == Some(new) => old.rules_updated_at,
+        (Some(_), _) => Some(now.clone()),
+    };
+
+    sqlx::query(
+        r#"INSERT INTO server_welcome (server_id, message, rules, suggested_channel_ids, rules_updated_at, updated_by, updated_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?)
+           ON CONFLICT(server_id) DO UPDATE SET
+             message = excluded.message,
+             rules = excluded.rules,
+             suggested_channel_ids = excluded.suggested_channel_ids,
+             rules_updated_at = excluded.rules_updated_at,
+             updated_by = excluded.updated_by,
+             updated_at = excluded.updated_at"#,
+    )
+    .bind(&server_id)
+    .bind(&message)
+    .bind(&rules)
+    .bind(serde_json::to_string(&channel_ids).unwrap_or_else(|_| "[]".into()))
+    .bind(&rules_updated_at)
+    .bind(&user.id)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    get_welcome(State(state), user, Path(server_id)).await
+}
+
+/// DELETE /api/servers/:serverId/welcome
+/// Remove the welcome screen, and with it the rules gate.
+pub async fn delete_welcome(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let deleted = sqlx::query("DELETE FROM server_welcome WHERE server_id = ?")
+        .bind(&server_id)
+        .execute(&state.db)
+        .await?;
+    if deleted.rows_affected() == 0 {
+        return Err(ApiError::not_found("Welcome screen not found"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/servers/:serverId/welcome/accept
+/// Accept the server's current rules, unlocking posting.
+pub async fn accept_rules(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let updated = sqlx::query("UPDATE memberships SET rules_accepted_at = ? WHERE user_id = ? AND server_id = ?")
+        .bind(&now)
+        .bind(&user.id)
+        .bind(&server_id)
+        .execute(&state.db)
+        .await?;
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::forbidden("Not a member of this server"));
+    }
+
+    Ok(Json(serde_json::json!({ "rulesAcceptedAt": now })))
+}
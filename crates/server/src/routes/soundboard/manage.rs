@@ -6,6 +6,7 @@ use axum::{
 };
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::AuthUser;
 use crate::AppState;
 
@@ -18,18 +19,12 @@ pub async fn update_sound(
     user: AuthUser,
     Path((server_id, sound_id)): Path<(String, String)>,
     Json(body): Json<UpdateSoundRequest>,
-) -> impl IntoResponse {
-    if let Err(resp) = require_server_admin(&state, &user.id, &server_id).await {
-        return resp.into_response();
-    }
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
 
     let name = body.name.trim().to_string();
     if name.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Name is required"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("Name is required"));
     }
 
     let volume = body.volume.clamp(0.0, 1.0);
@@ -47,11 +42,7 @@ pub async fn update_sound(
 
     if let Err(e) = result {
         tracing::error!("Failed to update soundboard sound: {:?}", e);
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": "Failed to update sound"})),
-        )
-            .into_response();
+        return Err(ApiError::internal("Failed to update sound"));
     }
 
     let sound = sqlx::query_as::<_, SoundboardSoundRow>(
@@ -79,8 +70,8 @@ pub async fn update_sound(
     .flatten();
 
     match sound {
-        Some(s) => Json(s).into_response(),
-        None => StatusCode::NOT_FOUND.into_response(),
+        Some(s) => Ok(Json(s).into_response()),
+        None => Err(ApiError::not_found("Sound not found")),
     }
 }
 
@@ -90,7 +81,7 @@ pub async fn favorite_sound(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path((server_id, sound_id)): Path<(String, String)>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let is_member = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
     )
@@ -102,11 +93,7 @@ pub async fn favorite_sound(
         > 0;
 
     if !is_member {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({"error": "Not a member of this server"})),
-        )
-            .into_response();
+        return Err(ApiError::forbidden("Not a member of this server"));
     }
 
     let now = chrono::Utc::now().to_rfc3339();
@@ -117,10 +104,9 @@ pub async fn favorite_sound(
     .bind(&sound_id)
     .bind(&now)
     .execute(&state.db)
-    .await
-    .ok();
+    .await?;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
 
 /// DELETE /api/servers/:serverId/soundboard/:soundId/favorite
@@ -129,17 +115,16 @@ pub async fn unfavorite_sound(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path((_server_id, sound_id)): Path<(String, String)>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     sqlx::query(
         "DELETE FROM soundboard_favorites WHERE user_id = ? AND sound_id = ?",
     )
     .bind(&user.id)
     .bind(&sound_id)
     .execute(&state.db)
-    .await
-    .ok();
+    .await?;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
 
 /// DELETE /api/servers/:serverId/soundboard/:soundId
@@ -148,10 +133,8 @@ pub async fn delete_sound(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path((server_id, sound_id)): Path<(String, String)>,
-) -> impl IntoResponse {
-    if let Err(resp) = require_server_admin(&state, &user.id, &server_id).await {
-        return resp.into_response();
-    }
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
 
     sqlx::query(
         "DELETE FROM soundboard_sounds WHERE id = ? AND server_id = ?",
@@ -159,8 +142,7 @@ pub async fn delete_sound(
     .bind(&sound_id)
     .bind(&server_id)
     .execute(&state.db)
-    .await
-    .ok();
+    .await?;
 
-    StatusCode::NO_CONTENT.into_response()
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
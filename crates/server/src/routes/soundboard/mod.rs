@@ -11,6 +11,7 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::AuthUser;
 use crate::AppState;
 
@@ -55,7 +56,7 @@ pub(super) async fn require_server_admin(
     state: &AppState,
     user_id: &str,
     server_id: &str,
-) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+) -> Result<(), ApiError> {
     let role = sqlx::query_scalar::<_, String>(
         "SELECT role FROM memberships WHERE user_id = ? AND server_id = ?",
     )
@@ -68,10 +69,7 @@ pub(super) async fn require_server_admin(
 
     match role.as_deref() {
         Some("owner") | Some("admin") => Ok(()),
-        _ => Err((
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({"error": "Insufficient permissions"})),
-        )),
+        _ => Err(ApiError::forbidden("Insufficient permissions")),
     }
 }
 
@@ -83,7 +81,7 @@ pub async fn list_sounds(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
     Path(server_id): Path<String>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     // Verify caller is a member
     let is_member = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
@@ -96,11 +94,7 @@ pub async fn list_sounds(
         > 0;
 
     if !is_member {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({"error": "Not a member of this server"})),
-        )
-            .into_response();
+        return Err(ApiError::forbidden("Not a member of this server"));
     }
 
     let sounds = sqlx::query_as::<_, SoundboardSoundRow>(
@@ -129,7 +123,7 @@ pub async fn list_sounds(
     .await
     .unwrap_or_default();
 
-    Json(sounds).into_response()
+    Ok(Json(sounds).into_response())
 }
 
 /// POST /api/servers/:serverId/soundboard
@@ -139,19 +133,13 @@ pub async fn create_sound(
     user: AuthUser,
     Path(server_id): Path<String>,
     Json(body): Json<CreateSoundRequest>,
-) -> impl IntoResponse {
-    if let Err(resp) = require_server_admin(&state, &user.id, &server_id).await {
-        return resp.into_response();
-    }
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
 
     // Validate name
     let name = body.name.trim().to_string();
     if name.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Name is required"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("Name is required"));
     }
 
     // Verify audio attachment belongs to uploader and exists
@@ -166,11 +154,7 @@ pub async fn create_sound(
         > 0;
 
     if !audio_ok {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Invalid audio attachment"})),
-        )
-            .into_response();
+        return Err(ApiError::bad_request("Invalid audio attachment"));
     }
 
     let id = uuid::Uuid::new_v4().to_string();
@@ -195,11 +179,7 @@ pub async fn create_sound(
 
     if let Err(e) = result {
         tracing::error!("Failed to create soundboard sound: {:?}", e);
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": "Failed to create sound"})),
-        )
-            .into_response();
+        return Err(ApiError::internal("Failed to create sound"));
     }
 
     // Fetch and return the created sound with joined filenames
@@ -228,7 +208,7 @@ pub async fn create_sound(
     .flatten();
 
     match sound {
-        Some(s) => (StatusCode::CREATED, Json(s)).into_response(),
-        None => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        Some(s) => Ok((StatusCode::CREATED, Json(s)).into_response()),
+        None => Err(ApiError::internal("Failed to load sound")),
     }
 }
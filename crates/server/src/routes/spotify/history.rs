@@ -0,0 +1,132 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AddToQueueRequest, AuthUser, HistoryItem};
+use crate::AppState;
+
+const DEFAULT_HISTORY_LIMIT: i64 = 50;
+const MAX_HISTORY_LIMIT: i64 = 200;
+
+#[derive(Deserialize)]
+pub struct HistoryQuery {
+    pub limit: Option<i64>,
+}
+
+/// Log a track as played. Called when playback moves to a new track (a skip,
+/// which is also how clients advance at track end, or a play of a specific
+/// track). Metadata comes from the queue entry if it's still there, otherwise
+/// from an earlier play of the same track.
+pub(crate) async fn record_played(
+    state: &AppState,
+    session_id: &str,
+    track_uri: &str,
+    source: &str,
+    user_id: &str,
+) {
+    let known = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, i64)>(
+        r#"SELECT track_name, track_artist, track_album, track_image_url, track_duration_ms
+           FROM "session_queue" WHERE session_id = ? AND track_uri = ?
+           UNION ALL
+           SELECT * FROM (
+               SELECT track_name, track_artist, track_album, track_image_url, track_duration_ms
+               FROM "session_history" WHERE session_id = ? AND track_uri = ? AND track_name != track_uri
+               ORDER BY played_at DESC
+           )
+           LIMIT 1"#,
+    )
+    .bind(session_id)
+    .bind(track_uri)
+    .bind(session_id)
+    .bind(track_uri)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    let (name, artist, album, image, duration) =
+        known.unwrap_or_else(|| (track_uri.to_string(), String::new(), None, None, 0));
+
+    crate::routes::lastfm::enqueue_scrobbles(state, session_id, &artist, &name, album.as_deref(), duration).await;
+
+    let _ = sqlx::query(
+        r#"INSERT INTO "session_history"
+           (id, session_id, track_uri, track_name, track_artist, track_album, track_image_url, track_duration_ms, source, played_by_user_id, played_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(session_id)
+    .bind(track_uri)
+    .bind(&name)
+    .bind(&artist)
+    .bind(&album)
+    .bind(&image)
+    .bind(duration)
+    .bind(source)
+    .bind(user_id)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&state.db)
+    .await;
+}
+
+/// GET /api/spotify/sessions/:sessionId/history?limit=
+/// Most recently played first.
+pub async fn get_history(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(DEFAULT_HISTORY_LIMIT).clamp(1, MAX_HISTORY_LIMIT);
+
+    let history = sqlx::query_as::<_, HistoryItem>(
+        r#"SELECT * FROM "session_history" WHERE session_id = ? ORDER BY played_at DESC LIMIT ?"#,
+    )
+    .bind(&session_id)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    Json(serde_json::json!({"history": history})).into_response()
+}
+
+/// POST /api/spotify/sessions/:sessionId/history/:historyId/requeue
+/// "Play again": put a previously played track back at the end of the queue.
+pub async fn requeue_from_history(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path((session_id, history_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let item = sqlx::query_as::<_, HistoryItem>(
+        r#"SELECT * FROM "session_history" WHERE id = ? AND session_id = ?"#,
+    )
+    .bind(&history_id)
+    .bind(&session_id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    let Some(item) = item else {
+        return Err(ApiError::not_found("History entry not found"));
+    };
+
+    let request = AddToQueueRequest {
+        track_uri: item.track_uri,
+        track_name: item.track_name,
+        track_artist: item.track_artist,
+        track_album: item.track_album,
+        track_image_url: item.track_image_url,
+        track_duration_ms: item.track_duration_ms,
+        source: item.source,
+    };
+    let item_id = super::enqueue(&state, session_id, &user.id, request).await;
+
+    Ok(Json(serde_json::json!({"id": item_id})).into_response())
+}
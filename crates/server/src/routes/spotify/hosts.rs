@@ -0,0 +1,196 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, ListeningSession, VoiceParticipant};
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferHostRequest {
+    pub user_id: String,
+}
+
+pub(crate) async fn session_cohosts(db: &sqlx::SqlitePool, session_id: &str) -> Vec<String> {
+    sqlx::query_scalar::<_, String>(
+        r#"SELECT user_id FROM "listening_session_cohosts" WHERE session_id = ? ORDER BY created_at ASC"#,
+    )
+    .bind(session_id)
+    .fetch_all(db)
+    .await
+    .unwrap_or_default()
+}
+
+async fn broadcast_roles(state: &AppState, session_id: &str, voice_channel_id: &str, host_user_id: &str) {
+    let cohost_ids = session_cohosts(&state.db, session_id).await;
+    state
+        .gateway
+        .broadcast_all(
+            &ServerEvent::SpotifySessionRoles {
+                session_id: session_id.to_string(),
+                voice_channel_id: voice_channel_id.to_string(),
+                host_user_id: host_user_id.to_string(),
+                cohost_ids,
+            },
+            None,
+        )
+        .await;
+}
+
+async fn set_host(state: &AppState, session: &ListeningSession, new_host_id: &str) {
+    let now = chrono::Utc::now().to_rfc3339();
+    let _ = sqlx::query(r#"UPDATE "listening_sessions" SET host_user_id = ?, updated_at = ? WHERE id = ?"#)
+        .bind(new_host_id)
+        .bind(&now)
+        .bind(&session.id)
+        .execute(&state.db)
+        .await;
+    // The host's role supersedes co-host
+    let _ = sqlx::query(r#"DELETE FROM "listening_session_cohosts" WHERE session_id = ? AND user_id = ?"#)
+        .bind(&session.id)
+        .bind(new_host_id)
+        .execute(&state.db)
+        .await;
+    broadcast_roles(state, &session.id, &session.voice_channel_id, new_host_id).await;
+}
+
+/// Called after `user_id` leaves (or disconnects from) a voice channel. If they
+/// hosted its listening session, hand it to a co-host still in the channel,
+/// or failing that to any remaining participant.
+pub(crate) async fn hand_off_host(
+    state: &AppState,
+    user_id: &str,
+    voice_channel_id: &str,
+    participants: &[VoiceParticipant],
+) {
+    // Still there from another client, or nobody left to take over
+    if participants.is_empty() || participants.iter().any(|p| p.user_id == user_id) {
+        return;
+    }
+
+    let session = sqlx::query_as::<_, ListeningSession>(
+        r#"SELECT * FROM "listening_sessions" WHERE voice_channel_id = ? AND host_user_id = ?"#,
+    )
+    .bind(voice_channel_id)
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+    let Some(session) = session else { return };
+
+    let cohosts = session_cohosts(&state.db, &session.id).await;
+    let new_host = cohosts
+        .iter()
+        .find(|id| participants.iter().any(|p| &p.user_id == *id))
+        .cloned()
+        .or_else(|| {
+            participants
+                .iter()
+                .min_by(|a, b| a.username.cmp(&b.username))
+                .map(|p| p.user_id.clone())
+        });
+
+    if let Some(new_host) = new_host {
+        tracing::info!("Listening session {} host left, handing off to {}", session.id, new_host);
+        set_host(state, &session, &new_host).await;
+    }
+}
+
+async fn fetch_session_as_host(
+    state: &AppState,
+    session_id: &str,
+    user_id: &str,
+) -> Result<ListeningSession, ApiError> {
+    let session = sqlx::query_as::<_, ListeningSession>(r#"SELECT * FROM "listening_sessions" WHERE id = ?"#)
+        .bind(session_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten();
+
+    match session {
+        Some(s) if s.host_user_id == user_id => Ok(s),
+        Some(_) => Err(ApiError::forbidden("Not the host")),
+        None => Err(ApiError::not_found("Session not found")),
+    }
+}
+
+/// POST /api/spotify/sessions/:sessionId/host
+/// Host only. The new host must be in the session's voice channel.
+pub async fn transfer_host(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+    Json(body): Json<TransferHostRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let session = fetch_session_as_host(&state, &session_id, &user.id).await?;
+
+    if body.user_id == user.id {
+        return Ok(Json(serde_json::json!({"success": true})).into_response());
+    }
+
+    let participants = state.gateway.voice_channel_participants(&session.voice_channel_id).await;
+    if !participants.iter().any(|p| p.user_id == body.user_id) {
+        return Err(ApiError::bad_request("User is not in the voice channel"));
+    }
+
+    set_host(&state, &session, &body.user_id).await;
+    Ok(Json(serde_json::json!({"success": true})).into_response())
+}
+
+/// PUT /api/spotify/sessions/:sessionId/cohosts/:userId
+/// Host only.
+pub async fn add_cohost(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path((session_id, target_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let session = fetch_session_as_host(&state, &session_id, &user.id).await?;
+
+    if target_id == session.host_user_id {
+        return Err(ApiError::bad_request("The host can't be a co-host"));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let result = sqlx::query(
+        r#"INSERT OR IGNORE INTO "listening_session_cohosts" (session_id, user_id, created_at) VALUES (?, ?, ?)"#,
+    )
+    .bind(&session_id)
+    .bind(&target_id)
+    .bind(&now)
+    .execute(&state.db)
+    .await;
+
+    if result.is_err() {
+        return Err(ApiError::not_found("User not found"));
+    }
+
+    broadcast_roles(&state, &session_id, &session.voice_channel_id, &session.host_user_id).await;
+    Ok(Json(serde_json::json!({"success": true})).into_response())
+}
+
+/// DELETE /api/spotify/sessions/:sessionId/cohosts/:userId
+/// Host only.
+pub async fn remove_cohost(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path((session_id, target_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let session = fetch_session_as_host(&state, &session_id, &user.id).await?;
+
+    sqlx::query(r#"DELETE FROM "listening_session_cohosts" WHERE session_id = ? AND user_id = ?"#)
+        .bind(&session_id)
+        .bind(&target_id)
+        .execute(&state.db)
+        .await?;
+
+    broadcast_roles(&state, &session_id, &session.voice_channel_id, &session.host_user_id).await;
+    Ok(Json(serde_json::json!({"success": true})).into_response())
+}
@@ -0,0 +1,245 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, ListeningSession, LyricLine};
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+const CACHE_TTL_SECS: u64 = 6 * 60 * 60; // 6 hours
+const CACHE_MAX_ENTRIES: usize = 1000;
+
+/// Synced lyrics by track uri; `None` caches "provider has none".
+pub type LyricsCache = HashMap<String, (Option<Vec<LyricLine>>, Instant)>;
+
+/// Parse LRC text (`[mm:ss.xx] line`) into lines sorted by time. Lines with
+/// several timestamps are repeated, and an `[offset:±ms]` tag is applied
+/// (positive offset shows lyrics earlier, per the LRC convention).
+pub fn parse_lrc(lrc: &str) -> Vec<LyricLine> {
+    let mut offset_ms = 0i64;
+    let mut lines = Vec::new();
+
+    for raw in lrc.lines() {
+        let mut rest = raw.trim();
+        let mut times = Vec::new();
+        while let Some(tag) = rest.strip_prefix('[') {
+            let Some((inner, after)) = tag.split_once(']') else { break };
+            rest = after;
+            if let Some(off) = inner.strip_prefix("offset:") {
+                offset_ms = off.trim().parse().unwrap_or(0);
+            } else if let Some(ms) = parse_timestamp(inner) {
+                times.push(ms);
+            }
+        }
+        let text = rest.trim();
+        for ms in times {
+            lines.push(LyricLine { time_ms: ms, text: text.to_string() });
+        }
+    }
+
+    for line in &mut lines {
+        line.time_ms = (line.time_ms - offset_ms).max(0);
+    }
+    lines.sort_by_key(|l| l.time_ms);
+    lines
+}
+
+/// `mm:ss`, `mm:ss.xx` or `mm:ss.xxx` to milliseconds.
+fn parse_timestamp(tag: &str) -> Option<i64> {
+    let (min, sec) = tag.split_once(':')?;
+    let min: i64 = min.parse().ok()?;
+    let (whole, frac) = sec.split_once('.').unwrap_or((sec, ""));
+    let whole: i64 = whole.parse().ok()?;
+    let frac_ms = match frac.len() {
+        0 => 0,
+        1 => frac.parse::<i64>().ok()? * 100,
+        2 => frac.parse::<i64>().ok()? * 10,
+        _ => frac.get(..3)?.parse().ok()?,
+    };
+    Some(min * 60_000 + whole * 1000 + frac_ms)
+}
+
+/// Ask the provider for synced lyrics. `Ok(None)` means it has none for this
+/// track; `Err` is a provider failure and isn't cached.
+async fn fetch_lyrics(
+    base: &str,
+    name: &str,
+    artist: &str,
+    album: Option<&str>,
+    duration_ms: i64,
+) -> Result<Option<Vec<LyricLine>>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut params = vec![("track_name", name.to_string()), ("artist_name", artist.to_string())];
+    if let Some(album) = album {
+        params.push(("album_name", album.to_string()));
+    }
+    if duration_ms > 0 {
+        params.push(("duration", (duration_ms / 1000).to_string()));
+    }
+
+    // Exact match first, then a looser search (YouTube titles rarely match exactly)
+    let res = client
+        .get(format!("{}/get", base))
+        .query(&params)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let candidates = if res.status().is_success() {
+        vec![res.json::<serde_json::Value>().await.map_err(|e| e.to_string())?]
+    } else if res.status() == reqwest::StatusCode::NOT_FOUND {
+        let q = format!("{} {}", artist, name);
+        let res = client
+            .get(format!("{}/search", base))
+            .query(&[("q", q.trim())])
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            return Err(format!("lyrics search returned {}", res.status()));
+        }
+        res.json::<Vec<serde_json::Value>>().await.map_err(|e| e.to_string())?
+    } else {
+        return Err(format!("lyrics lookup returned {}", res.status()));
+    };
+
+    Ok(candidates
+        .iter()
+        .filter_map(|c| c["syncedLyrics"].as_str())
+        .map(parse_lrc)
+        .find(|lines| !lines.is_empty()))
+}
+
+/// Where the session's playback is now: the stored position, plus the time
+/// since it was stored if the track is playing.
+fn current_position(session: &ListeningSession) -> i64 {
+    let elapsed = if session.is_playing != 0 {
+        chrono::DateTime::parse_from_rfc3339(&session.updated_at)
+            .map(|t| (chrono::Utc::now() - t.with_timezone(&chrono::Utc)).num_milliseconds().max(0))
+            .unwrap_or(0)
+    } else {
+        0
+    };
+    session.current_track_position_ms + elapsed
+}
+
+/// GET /api/spotify/sessions/:sessionId/lyrics
+/// Fetches synced lyrics for the current track and broadcasts them, with the
+/// playback position, to everyone in the session's voice channel.
+pub async fn get_lyrics(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let session = sqlx::query_as::<_, ListeningSession>(
+        r#"SELECT * FROM "listening_sessions" WHERE id = ?"#,
+    )
+    .bind(&session_id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    let Some(session) = session else {
+        return Err(ApiError::not_found("Session not found"));
+    };
+    let Some(track_uri) = session.current_track_uri.clone() else {
+        return Err(ApiError::not_found("Nothing is playing"));
+    };
+
+    let cached = {
+        let cache = state.lyrics_cache.read().await;
+        cache
+            .get(&track_uri)
+            .filter(|(_, fetched_at)| fetched_at.elapsed().as_secs() < CACHE_TTL_SECS)
+            .map(|(lines, _)| lines.clone())
+    };
+
+    let lines = match cached {
+        Some(lines) => lines,
+        None => {
+            if state.config.lyrics_api_url.is_empty() {
+                return Err(ApiError::unavailable("Lyrics are not configured"));
+            }
+
+            // Same metadata sources as play history
+            let meta = sqlx::query_as::<_, (String, String, Option<String>, i64)>(
+                r#"SELECT track_name, track_artist, track_album, track_duration_ms
+                   FROM "session_history" WHERE session_id = ? AND track_uri = ? AND track_name != track_uri
+                   UNION ALL
+                   SELECT track_name, track_artist, track_album, track_duration_ms
+                   FROM "session_queue" WHERE session_id = ? AND track_uri = ?
+                   LIMIT 1"#,
+            )
+            .bind(&session_id)
+            .bind(&track_uri)
+            .bind(&session_id)
+            .bind(&track_uri)
+            .fetch_optional(&state.db)
+            .await
+            .ok()
+            .flatten();
+
+            let Some((name, artist, album, duration_ms)) = meta else {
+                return Err(ApiError::not_found("Unknown track"));
+            };
+
+            let fetched = fetch_lyrics(&state.config.lyrics_api_url, &name, &artist, album.as_deref(), duration_ms).await;
+            let lines = match fetched {
+                Ok(lines) => lines,
+                Err(e) => {
+                    tracing::error!("Lyrics fetch failed for {}: {}", track_uri, e);
+                    return Err(ApiError::upstream("Lyrics provider error"));
+                }
+            };
+
+            let mut cache = state.lyrics_cache.write().await;
+            if cache.len() >= CACHE_MAX_ENTRIES {
+                cache.retain(|_, (_, fetched_at)| fetched_at.elapsed().as_secs() < CACHE_TTL_SECS);
+            }
+            if cache.len() < CACHE_MAX_ENTRIES {
+                cache.insert(track_uri.clone(), (lines.clone(), Instant::now()));
+            }
+            lines
+        }
+    };
+
+    let Some(lines) = lines else {
+        return Err(ApiError::not_found("No synced lyrics for this track"));
+    };
+
+    let position_ms = current_position(&session);
+    let is_playing = session.is_playing != 0;
+    state
+        .gateway
+        .broadcast_voice_channel(
+            &session.voice_channel_id,
+            &ServerEvent::LyricsSync {
+                session_id: session_id.clone(),
+                voice_channel_id: session.voice_channel_id.clone(),
+                track_uri: track_uri.clone(),
+                lines: lines.clone(),
+                position_ms,
+                is_playing,
+            },
+            None,
+        )
+        .await;
+
+    Ok(Json(serde_json::json!({
+        "trackUri": track_uri,
+        "lines": lines,
+        "positionMs": position_ms,
+        "isPlaying": is_playing,
+    }))
+    .into_response())
+}
@@ -1,19 +1,25 @@
+mod history;
+mod hosts;
+mod lyrics;
 mod oauth;
 mod sessions;
 mod token;
 
+pub use history::*;
+pub use hosts::*;
+pub use lyrics::*;
 pub use oauth::*;
 pub use sessions::*;
 
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::{AuthUser, SpotifyAccountInfo};
 use crate::AppState;
 
@@ -63,27 +69,23 @@ pub async fn get_auth_info(
 pub async fn unlink_spotify(
     user: AuthUser,
     State(state): State<Arc<AppState>>,
-) -> impl IntoResponse {
-    let _ = sqlx::query(r#"DELETE FROM "account" WHERE userId = ? AND providerId = 'spotify'"#)
+) -> Result<impl IntoResponse, ApiError> {
+    sqlx::query(r#"DELETE FROM "account" WHERE userId = ? AND providerId = 'spotify'"#)
         .bind(&user.id)
         .execute(&state.db)
-        .await;
+        .await?;
 
-    Json(serde_json::json!({"success": true})).into_response()
+    Ok(Json(serde_json::json!({"success": true})).into_response())
 }
 
 /// GET /api/spotify/token
 pub async fn get_token(
     user: AuthUser,
     State(state): State<Arc<AppState>>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     match get_valid_token(&state.db, &user.id).await {
-        Ok(token) => Json(serde_json::json!({"accessToken": token})).into_response(),
-        Err(e) => (
-            StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({"error": e})),
-        )
-            .into_response(),
+        Ok(token) => Ok(Json(serde_json::json!({"accessToken": token})).into_response()),
+        Err(e) => Err(ApiError::unauthorized(e)),
     }
 }
 
@@ -97,21 +99,17 @@ pub async fn search_tracks(
     user: AuthUser,
     State(state): State<Arc<AppState>>,
     Query(query): Query<SearchQuery>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let q = match query.q.as_deref() {
         Some(q) if !q.trim().is_empty() => q.trim().to_string(),
-        _ => return Json(serde_json::json!({"tracks": {"items": []}})).into_response(),
+        _ => return Ok(Json(serde_json::json!({"tracks": {"items": []}})).into_response()),
     };
 
     let token = match get_valid_token(&state.db, &user.id).await {
         Ok(t) => t,
         Err(e) => {
             tracing::error!("Spotify token error for search: {}", e);
-            return (
-                StatusCode::UNAUTHORIZED,
-                Json(serde_json::json!({"error": format!("Spotify token error: {}", e)})),
-            )
-                .into_response()
+            return Err(ApiError::unauthorized(format!("Spotify token error: {}", e)))
         }
     };
 
@@ -126,25 +124,17 @@ pub async fn search_tracks(
     {
         Ok(r) if r.status().is_success() => {
             let data: serde_json::Value = r.json().await.unwrap_or_default();
-            Json(data).into_response()
+            Ok(Json(data).into_response())
         }
         Ok(r) => {
             let status = r.status();
             let body = r.text().await.unwrap_or_default();
             tracing::error!("Spotify search failed ({}): {}", status, body);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": format!("Spotify API error ({})", status)})),
-            )
-                .into_response()
+            Err(ApiError::internal(format!("Spotify API error ({})", status)))
         }
         Err(e) => {
             tracing::error!("Spotify search network error: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "Search failed"})),
-            )
-                .into_response()
+            Err(ApiError::internal("Search failed"))
         }
     }
 }
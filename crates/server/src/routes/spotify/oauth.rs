@@ -1,12 +1,12 @@
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
     response::{Html, IntoResponse},
     Json,
 };
 use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::{AuthUser, SpotifyCallbackRequest};
 use crate::AppState;
 
@@ -177,14 +177,10 @@ pub async fn spotify_callback_post(
     user: AuthUser,
     State(state): State<Arc<AppState>>,
     Json(body): Json<SpotifyCallbackRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let client_id = std::env::var("SPOTIFY_CLIENT_ID").unwrap_or_default();
     if client_id.is_empty() {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": "SPOTIFY_CLIENT_ID not configured"})),
-        )
-            .into_response();
+        return Err(ApiError::internal("SPOTIFY_CLIENT_ID not configured"));
     }
 
     let client = reqwest::Client::new();
@@ -204,36 +200,20 @@ pub async fn spotify_callback_post(
     let token_data: SpotifyTokenResponse = match res {
         Ok(r) if r.status().is_success() => match r.json().await {
             Ok(t) => t,
-            Err(_) => {
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(serde_json::json!({"error": "Failed to parse token response"})),
-                )
-                    .into_response()
-            }
+            Err(_) => return Err(ApiError::bad_request("Failed to parse token response")),
         },
         Ok(r) => {
             let status = r.status();
             let body = r.text().await.unwrap_or_default();
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": format!("Spotify token error ({}): {}", status, body)})),
-            )
-                .into_response();
-        }
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": format!("Network error: {}", e)})),
-            )
-                .into_response()
+            return Err(ApiError::bad_request(format!("Spotify token error ({}): {}", status, body)));
         }
+        Err(e) => return Err(ApiError::internal(format!("Network error: {}", e))),
     };
 
     let display_name = fetch_display_name(&client, &token_data.access_token).await;
     upsert_spotify_account(&state.db, &user.id, &display_name, &token_data).await;
 
-    Json(serde_json::json!({"success": true, "displayName": display_name})).into_response()
+    Ok(Json(serde_json::json!({"success": true, "displayName": display_name})).into_response())
 }
 
 async fn fetch_display_name(client: &reqwest::Client, access_token: &str) -> String {
@@ -1,12 +1,12 @@
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::models::{AddToQueueRequest, AuthUser, ListeningSession, QueueItem};
 use crate::ws::events::ServerEvent;
 use crate::AppState;
@@ -15,14 +15,22 @@ use crate::AppState;
 #[serde(rename_all = "camelCase")]
 pub struct CreateSessionRequest {
     pub voice_channel_id: String,
+    /// "audio" (default) or "video" for watch-together.
+    pub session_type: Option<String>,
 }
 
 /// POST /api/spotify/sessions
+/// A voice channel has at most one session; an existing one is returned as is.
 pub async fn create_session(
     user: AuthUser,
     State(state): State<Arc<AppState>>,
     Json(body): Json<CreateSessionRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
+    let session_type = body.session_type.as_deref().unwrap_or("audio");
+    if !matches!(session_type, "audio" | "video") {
+        return Err(ApiError::bad_request("sessionType must be audio or video"));
+    }
+
     let existing = sqlx::query_scalar::<_, String>(
         r#"SELECT id FROM "listening_sessions" WHERE voice_channel_id = ?"#,
     )
@@ -33,25 +41,26 @@ pub async fn create_session(
     .flatten();
 
     if let Some(id) = existing {
-        return Json(serde_json::json!({"sessionId": id, "existing": true})).into_response();
+        return Ok(Json(serde_json::json!({"sessionId": id, "existing": true})).into_response());
     }
 
     let id = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
 
-    let _ = sqlx::query(
-        r#"INSERT INTO "listening_sessions" (id, voice_channel_id, host_user_id, current_track_position_ms, is_playing, created_at, updated_at)
-           VALUES (?, ?, ?, 0, 0, ?, ?)"#,
+    sqlx::query(
+        r#"INSERT INTO "listening_sessions" (id, voice_channel_id, host_user_id, current_track_position_ms, is_playing, created_at, updated_at, session_type)
+           VALUES (?, ?, ?, 0, 0, ?, ?, ?)"#,
     )
     .bind(&id)
     .bind(&body.voice_channel_id)
     .bind(&user.id)
     .bind(&now)
     .bind(&now)
+    .bind(session_type)
     .execute(&state.db)
-    .await;
+    .await?;
 
-    Json(serde_json::json!({"sessionId": id})).into_response()
+    Ok(Json(serde_json::json!({"sessionId": id})).into_response())
 }
 
 /// GET /api/spotify/sessions/channel/:voiceChannelId
@@ -79,9 +88,11 @@ pub async fn get_session(
             .await
             .unwrap_or_default();
 
-            Json(serde_json::json!({"session": s, "queue": queue})).into_response()
+            let cohost_ids = super::session_cohosts(&state.db, &s.id).await;
+
+            Json(serde_json::json!({"session": s, "queue": queue, "cohostIds": cohost_ids})).into_response()
         }
-        None => Json(serde_json::json!({"session": null, "queue": []})).into_response(),
+        None => Json(serde_json::json!({"session": null, "queue": [], "cohostIds": []})).into_response(),
     }
 }
 
@@ -92,6 +103,17 @@ pub async fn add_to_queue(
     Path(session_id): Path<String>,
     Json(body): Json<AddToQueueRequest>,
 ) -> impl IntoResponse {
+    let item_id = enqueue(&state, session_id, &user.id, body).await;
+    Json(serde_json::json!({"id": item_id})).into_response()
+}
+
+/// Append a track to the end of the session queue and broadcast it. Returns the item id.
+pub(crate) async fn enqueue(
+    state: &AppState,
+    session_id: String,
+    user_id: &str,
+    body: AddToQueueRequest,
+) -> String {
     let max_pos = sqlx::query_scalar::<_, i64>(
         r#"SELECT COALESCE(MAX(position), -1) FROM "session_queue" WHERE session_id = ?"#,
     )
@@ -117,7 +139,7 @@ pub async fn add_to_queue(
     .bind(&body.track_album)
     .bind(&body.track_image_url)
     .bind(body.track_duration_ms)
-    .bind(&user.id)
+    .bind(user_id)
     .bind(position)
     .bind(&now)
     .bind(&body.source)
@@ -133,7 +155,7 @@ pub async fn add_to_queue(
         track_album: body.track_album,
         track_image_url: body.track_image_url,
         track_duration_ms: body.track_duration_ms,
-        added_by_user_id: user.id.clone(),
+        added_by_user_id: user_id.to_string(),
         position,
         created_at: now,
         source: body.source,
@@ -161,7 +183,7 @@ pub async fn add_to_queue(
         )
         .await;
 
-    Json(serde_json::json!({"id": item_id})).into_response()
+    item_id
 }
 
 /// DELETE /api/spotify/sessions/:sessionId/queue/:itemId
@@ -169,7 +191,7 @@ pub async fn remove_from_queue(
     _user: AuthUser,
     State(state): State<Arc<AppState>>,
     Path((session_id, item_id)): Path<(String, String)>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let voice_channel_id = sqlx::query_scalar::<_, String>(
         r#"SELECT voice_channel_id FROM "listening_sessions" WHERE id = ?"#,
     )
@@ -180,11 +202,11 @@ pub async fn remove_from_queue(
     .flatten()
     .unwrap_or_default();
 
-    let _ = sqlx::query(r#"DELETE FROM "session_queue" WHERE id = ? AND session_id = ?"#)
+    sqlx::query(r#"DELETE FROM "session_queue" WHERE id = ? AND session_id = ?"#)
         .bind(&item_id)
         .bind(&session_id)
         .execute(&state.db)
-        .await;
+        .await?;
 
     state
         .gateway
@@ -198,7 +220,7 @@ pub async fn remove_from_queue(
         )
         .await;
 
-    Json(serde_json::json!({"success": true})).into_response()
+    Ok(Json(serde_json::json!({"success": true})).into_response())
 }
 
 /// DELETE /api/spotify/sessions/:sessionId/end
@@ -206,7 +228,7 @@ pub async fn delete_session(
     user: AuthUser,
     State(state): State<Arc<AppState>>,
     Path(session_id): Path<String>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     let session = sqlx::query_as::<_, ListeningSession>(
         r#"SELECT * FROM "listening_sessions" WHERE id = ?"#,
     )
@@ -218,19 +240,13 @@ pub async fn delete_session(
 
     let session = match session {
         Some(s) if s.host_user_id == user.id => s,
-        _ => {
-            return (
-                StatusCode::FORBIDDEN,
-                Json(serde_json::json!({"error": "Not the host"})),
-            )
-                .into_response()
-        }
+        _ => return Err(ApiError::forbidden("Not the host")),
     };
 
-    let _ = sqlx::query(r#"DELETE FROM "listening_sessions" WHERE id = ?"#)
+    sqlx::query(r#"DELETE FROM "listening_sessions" WHERE id = ?"#)
         .bind(&session_id)
         .execute(&state.db)
-        .await;
+        .await?;
 
     state
         .gateway
@@ -243,5 +259,5 @@ pub async fn delete_session(
         )
         .await;
 
-    Json(serde_json::json!({"success": true})).into_response()
+    Ok(Json(serde_json::json!({"success": true})).into_response())
 }
@@ -0,0 +1,187 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, QuickSwitcherItem};
+use crate::AppState;
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 50;
+
+#[derive(Deserialize)]
+pub struct QuickSwitcherQuery {
+    pub q: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// How well `query` (already lowercased) matches `name`, or `None` if it
+/// doesn't. Exact beats prefix beats substring beats a scattered subsequence;
+/// shorter names and earlier, tighter matches rank higher within each tier.
+fn fuzzy_score(query: &str, name: &str) -> Option<i64> {
+    let name = name.to_lowercase();
+    let extra = (name.chars().count() as i64 - query.chars().count() as i64).max(0);
+    if name == query {
+        return Some(1000);
+    }
+    if name.starts_with(query) {
+        return Some(800 - extra.min(100));
+    }
+    if let Some(pos) = name.find(query) {
+        // Matching at a word boundary ("gen" in "off-general") reads as a prefix
+        let boundary = name[..pos].ends_with(['-', '_', ' ', '.']);
+        return Some(if boundary { 700 } else { 600 } - (pos as i64).min(50) - extra.min(50));
+    }
+
+    // Every query character in order, penalised for each one skipped over
+    let mut chars = name.chars();
+    let mut gaps = 0i64;
+    for qc in query.chars() {
+        let mut skipped = 0;
+        loop {
+            match chars.next() {
+                Some(c) if c == qc => break,
+                Some(_) => skipped += 1,
+                None => return None,
+            }
+        }
+        gaps += skipped;
+    }
+    Some((400 - gaps * 10).max(1))
+}
+
+/// Tie-breaker between kinds: conversations you already have come first.
+fn kind_rank(kind: &str) -> u8 {
+    match kind {
+        "dm" => 0,
+        "channel" => 1,
+        _ => 2,
+    }
+}
+
+/// GET /api/quick-switcher?q=&limit=
+/// Channels, DMs and people the caller can reach, fuzzy-matched on name and
+/// ranked together, for a Ctrl+K switcher.
+pub async fn quick_switcher(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(query): Query<QuickSwitcherQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let q = query.q.as_deref().unwrap_or("").trim().to_lowercase();
+    // A leading # or @ only says what the user is looking for
+    let q = q.trim_start_matches(['#', '@']).to_string();
+    if q.is_empty() {
+        return Ok(Json(Vec::<QuickSwitcherItem>::new()));
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let mut items: Vec<(i64, QuickSwitcherItem)> = Vec::new();
+
+    let channels = sqlx::query_as::<_, (String, String, String, String, String)>(
+        r#"SELECT c.id, c.name, c.type, s.id, s.name
+           FROM channels c
+           INNER JOIN servers s ON s.id = c.server_id
+           INNER JOIN memberships ms ON ms.server_id = s.id AND ms.user_id = ?
+           WHERE c.type != 'category'"#,
+    )
+    .bind(&user.id)
+    .fetch_all(&state.db_read)
+    .await?;
+    for (id, name, channel_type, server_id, server_name) in channels {
+        if let Some(score) = fuzzy_score(&q, &name) {
+            items.push((
+                score,
+                QuickSwitcherItem {
+                    kind: "channel",
+                    id,
+                    name,
+                    image: None,
+                    channel_type: Some(channel_type),
+                    server_id: Some(server_id),
+                    server_name: Some(server_name),
+                    user_id: None,
+                },
+            ));
+        }
+    }
+
+    // Same conversations the DM list shows: incoming requests stay out
+    let dms = sqlx::query_as::<_, (String, String, String, Option<String>)>(
+        r#"SELECT dc.id, u.id, u.username, u.image
+           FROM dm_channels dc
+           JOIN "user" u ON u.id = CASE WHEN dc.user1_id = ?1 THEN dc.user2_id ELSE dc.user1_id END
+           WHERE (dc.user1_id = ?1 OR dc.user2_id = ?1)
+             AND (dc.status = 'accepted' OR dc.requested_by = ?1)"#,
+    )
+    .bind(&user.id)
+    .fetch_all(&state.db_read)
+    .await?;
+    let mut dm_partners = HashSet::new();
+    for (id, other_id, username, image) in dms {
+        dm_partners.insert(other_id.clone());
+        if let Some(score) = fuzzy_score(&q, &username) {
+            items.push((
+                score,
+                QuickSwitcherItem {
+                    kind: "dm",
+                    id,
+                    name: username,
+                    image,
+                    channel_type: None,
+                    server_id: None,
+                    server_name: None,
+                    user_id: Some(other_id),
+                },
+            ));
+        }
+    }
+
+    // People sharing a server, unless there's already a DM with them or a
+    // block either way
+    let blocked = crate::routes::users::block_relations(&state, &user.id).await;
+    let people = sqlx::query_as::<_, (String, String, Option<String>)>(
+        r#"SELECT DISTINCT u.id, u.username, u.image
+           FROM memberships mine
+           INNER JOIN memberships theirs ON theirs.server_id = mine.server_id
+           INNER JOIN "user" u ON u.id = theirs.user_id
+           WHERE mine.user_id = ?1 AND u.id != ?1"#,
+    )
+    .bind(&user.id)
+    .fetch_all(&state.db_read)
+    .await?;
+    for (id, username, image) in people {
+        if dm_partners.contains(&id) || blocked.contains(&id) {
+            continue;
+        }
+        if let Some(score) = fuzzy_score(&q, &username) {
+            items.push((
+                score,
+                QuickSwitcherItem {
+                    kind: "user",
+                    id: id.clone(),
+                    name: username,
+                    image,
+                    channel_type: None,
+                    server_id: None,
+                    server_name: None,
+                    user_id: Some(id),
+                },
+            ));
+        }
+    }
+
+    items.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then_with(|| kind_rank(a.kind).cmp(&kind_rank(b.kind)))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    items.truncate(limit);
+
+    Ok(Json(items.into_iter().map(|(_, item)| item).collect()))
+}
@@ -0,0 +1,412 @@
+//! Avatar validation.
+//!
+//! Avatars arrive as data URLs and are stored inline, so every member list
+//! and DM carries them. Uploads are decoded to prove they're images, animated
+//! ones are capped by frame count and size, and a static first frame is kept
+//! alongside them for places that shouldn't animate.
+
+use base64::Engine;
+use image::codecs::png::PngDecoder;
+use image::imageops::FilterType;
+use image::{AnimationDecoder, DynamicImage, ImageDecoder, ImageFormat, Limits, RgbaImage};
+use serde::Deserialize;
+use std::io::Cursor;
+
+use crate::error::ApiError;
+
+/// Avatars are never shown bigger than this; larger static images are
+/// scaled down to it.
+pub const AVATAR_SIZE: u32 = 512;
+/// Uploads claiming to be bigger than this aren't decoded at all.
+const MAX_SOURCE_DIMENSION: u32 = 4096;
+/// Animated avatars keep their frames as uploaded, so they must already fit.
+const MAX_ANIMATED_DIMENSION: u32 = AVATAR_SIZE;
+pub const MAX_FRAMES: usize = 300;
+
+pub struct Avatar {
+    /// What's shown by default, as a data URL.
+    pub image: String,
+    /// The first frame as a PNG data URL, for animated avatars only.
+    pub static_image: Option<String>,
+}
+
+/// `?staticAvatars=true` asks for still avatars, for clients that don't want
+/// a list full of animations.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvatarQuery {
+    #[serde(default)]
+    pub static_avatars: bool,
+}
+
+impl AvatarQuery {
+    /// Select the avatar `column` of `table`, or its `_static` twin when
+    /// stills were asked for.
+    pub fn image_column(&self, table: &str, column: &str) -> String {
+        if self.static_avatars {
+            format!("COALESCE({t}.{c}_static, {t}.{c}) AS {c}", t = table, c = column)
+        } else {
+            format!("{}.{}", table, column)
+        }
+    }
+}
+
+/// Validate an uploaded avatar data URL and produce the variants to store.
+///
+/// Static images over `AVATAR_SIZE` or `max_bytes` are scaled down and
+/// re-encoded as PNG. Animated GIF/APNG avatars over the frame or dimension
+/// caps are rejected; ones over `max_bytes` lose their animation and are
+/// stored as their first frame.
+pub fn process(data_url: &str, max_bytes: usize) -> Result<Avatar, String> {
+    let bytes = decode_data_url(data_url)?;
+
+    let (format, animation) = match image::guess_format(&bytes) {
+        Ok(ImageFormat::Gif) => (ImageFormat::Gif, Some(inspect_gif(&bytes)?)),
+        Ok(ImageFormat::Png) => (ImageFormat::Png, inspect_apng(&bytes)?),
+        Ok(ImageFormat::Jpeg) => (ImageFormat::Jpeg, None),
+        _ => return Err("Avatar must be a PNG, JPEG or GIF image".into()),
+    };
+
+    let animation = match animation {
+        Some(animation) if animation.frames > 1 => animation,
+        single_frame => {
+            let image = match single_frame {
+                Some(frame) => DynamicImage::ImageRgba8(frame.first_frame),
+                None => decode_static(&bytes, format)?,
+            };
+            if image.width() <= AVATAR_SIZE && image.height() <= AVATAR_SIZE && bytes.len() <= max_bytes {
+                return Ok(Avatar { image: data_url.to_string(), static_image: None });
+            }
+            let png = encode_png(&fit(image))?;
+            if png.len() > max_bytes {
+                return Err("Avatar is too large".into());
+            }
+            return Ok(Avatar { image: png_data_url(&png), static_image: None });
+        }
+    };
+
+    if animation.frames > MAX_FRAMES {
+        return Err(format!("Animated avatars can have at most {} frames", MAX_FRAMES));
+    }
+    if animation.width > MAX_ANIMATED_DIMENSION || animation.height > MAX_ANIMATED_DIMENSION {
+        return Err(too_big_to_animate());
+    }
+
+    let first_frame = encode_png(&DynamicImage::ImageRgba8(animation.first_frame))?;
+    if first_frame.len() > max_bytes {
+        return Err("Avatar is too large".into());
+    }
+    if bytes.len() > max_bytes {
+        // Too heavy to animate everywhere; keep the still
+        return Ok(Avatar { image: png_data_url(&first_frame), static_image: None });
+    }
+    Ok(Avatar { image: data_url.to_string(), static_image: Some(png_data_url(&first_frame)) })
+}
+
+/// `process` an avatar from a request body off the async runtime.
+pub(crate) async fn process_upload(data_url: &str, max_bytes: usize) -> Result<Avatar, ApiError> {
+    if data_url.len() > 5_000_000 {
+        return Err(ApiError::bad_request("Image too large (max ~4MB)"));
+    }
+    let data_url = data_url.to_string();
+    match tokio::task::spawn_blocking(move || process(&data_url, max_bytes)).await {
+        Ok(Ok(avatar)) => Ok(avatar),
+        Ok(Err(e)) => Err(ApiError::bad_request(e)),
+        Err(_) => Err(ApiError::internal("Failed to process image")),
+    }
+}
+
+fn too_big_to_animate() -> String {
+    format!("Animated avatars can be at most {}x{} pixels", MAX_ANIMATED_DIMENSION, MAX_ANIMATED_DIMENSION)
+}
+
+fn decode_data_url(data_url: &str) -> Result<Vec<u8>, String> {
+    let invalid = || "Avatar must be a base64 image data URL".to_string();
+    let rest = data_url.strip_prefix("data:").ok_or_else(invalid)?;
+    let (header, payload) = rest.split_once(',').ok_or_else(invalid)?;
+    if !header.starts_with("image/") || !header.ends_with(";base64") {
+        return Err(invalid());
+    }
+    base64::engine::general_purpose::STANDARD
+        .decode(payload.trim())
+        .map_err(|_| invalid())
+}
+
+fn limits() -> Limits {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+    limits
+}
+
+fn decode_static(bytes: &[u8], format: ImageFormat) -> Result<DynamicImage, String> {
+    let mut reader = image::ImageReader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits());
+    reader.decode().map_err(|_| "Avatar image could not be decoded".to_string())
+}
+
+/// Scale down to fit `AVATAR_SIZE`, keeping the aspect ratio.
+fn fit(image: DynamicImage) -> DynamicImage {
+    if image.width() <= AVATAR_SIZE && image.height() <= AVATAR_SIZE {
+        image
+    } else {
+        image.resize(AVATAR_SIZE, AVATAR_SIZE, FilterType::Triangle)
+    }
+}
+
+fn encode_png(image: &DynamicImage) -> Result<Vec<u8>, String> {
+    let mut out = Cursor::new(Vec::new());
+    image
+        .write_to(&mut out, ImageFormat::Png)
+        .map_err(|_| "Failed to encode avatar".to_string())?;
+    Ok(out.into_inner())
+}
+
+fn png_data_url(png: &[u8]) -> String {
+    format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(png))
+}
+
+struct Animation {
+    width: u32,
+    height: u32,
+    frames: usize,
+    first_frame: RgbaImage,
+}
+
+/// Frame count and first frame of an APNG, or `None` for a plain PNG.
+fn inspect_apng(bytes: &[u8]) -> Result<Option<Animation>, String> {
+    let undecodable = |_| "Avatar image could not be decoded".to_string();
+    let decoder = PngDecoder::with_limits(Cursor::new(bytes), limits()).map_err(undecodable)?;
+    if !decoder.is_apng().map_err(undecodable)? {
+        return Ok(None);
+    }
+    let (width, height) = decoder.dimensions();
+    // Checked before decoding every frame of something we'd reject anyway
+    if width > MAX_ANIMATED_DIMENSION || height > MAX_ANIMATED_DIMENSION {
+        return Err(too_big_to_animate());
+    }
+
+    let mut frames = decoder.apng().map_err(undecodable)?.into_frames();
+    let first_frame = frames
+        .next()
+        .ok_or_else(|| "Avatar image has no frames".to_string())?
+        .map_err(undecodable)?
+        .into_buffer();
+    let mut count = 1;
+    for frame in frames {
+        frame.map_err(undecodable)?;
+        count += 1;
+        if count > MAX_FRAMES {
+            break;
+        }
+    }
+    Ok(Some(Animation { width, height, frames: count, first_frame }))
+}
+
+/// Walk a GIF's blocks, counting frames and decoding only the first.
+fn inspect_gif(bytes: &[u8]) -> Result<Animation, String> {
+    parse_gif(bytes).ok_or_else(|| "Avatar image could not be decoded".to_string())
+}
+
+fn parse_gif(bytes: &[u8]) -> Option<Animation> {
+    let mut r = Reader { bytes, pos: 6 };
+    let width = r.u16()? as u32;
+    let height = r.u16()? as u32;
+    if width == 0 || height == 0 || width > MAX_SOURCE_DIMENSION || height > MAX_SOURCE_DIMENSION {
+        return None;
+    }
+    let flags = r.u8()?;
+    r.skip(2)?; // background colour, aspect ratio
+    let global_palette = if flags & 0x80 != 0 { Some(r.take(3usize << ((flags & 7) + 1))?) } else { None };
+
+    let mut canvas = RgbaImage::new(width, height);
+    let mut frames = 0;
+    let mut transparent: Option<u8> = None;
+    loop {
+        match r.u8()? {
+            // Extension: only graphic control (transparency) matters here
+            0x21 => {
+                if r.u8()? == 0xF9 {
+                    let block = r.sub_block()?;
+                    if block.len() >= 4 && block[0] & 1 != 0 {
+                        transparent = Some(block[3]);
+                    }
+                }
+                while !r.sub_block()?.is_empty() {}
+            }
+            // Image
+            0x2C => {
+                let (left, top) = (r.u16()? as u32, r.u16()? as u32);
+                let (w, h) = (r.u16()? as u32, r.u16()? as u32);
+                if w > MAX_SOURCE_DIMENSION || h > MAX_SOURCE_DIMENSION {
+                    return None;
+                }
+                let flags = r.u8()?;
+                let local_palette = if flags & 0x80 != 0 { Some(r.take(3usize << ((flags & 7) + 1))?) } else { None };
+                let min_code_size = r.u8()?;
+
+                let mut data = Vec::new();
+                loop {
+                    let block = r.sub_block()?;
+                    if block.is_empty() {
+                        break;
+                    }
+                    if frames == 0 {
+                        data.extend_from_slice(block);
+                    }
+                }
+
+                if frames == 0 {
+                    let palette = local_palette.or(global_palette)?;
+                    let pixels = lzw_decode(&data, min_code_size, (w * h) as usize)?;
+                    for (i, &index) in pixels.iter().enumerate() {
+                        let row = i as u32 / w.max(1);
+                        let y = top + if flags & 0x40 != 0 { interlaced_row(row, h) } else { row };
+                        let x = left + i as u32 % w.max(1);
+                        let offset = index as usize * 3;
+                        if x >= width || y >= height || transparent == Some(index) || offset + 2 >= palette.len() {
+                            continue;
+                        }
+                        canvas.put_pixel(x, y, image::Rgba([palette[offset], palette[offset + 1], palette[offset + 2], 255]));
+                    }
+                }
+                frames += 1;
+                transparent = None;
+                if frames > MAX_FRAMES {
+                    break;
+                }
+            }
+            0x3B => break,
+            _ => return None,
+        }
+    }
+
+    (frames > 0).then_some(Animation { width, height, frames, first_frame: canvas })
+}
+
+/// The canvas row of the `row`th decoded row of an interlaced image.
+fn interlaced_row(row: u32, height: u32) -> u32 {
+    let mut row = row;
+    for (start, step) in [(0, 8), (4, 8), (2, 4), (1, 2)] {
+        let rows_in_pass = height.saturating_sub(start).div_ceil(step);
+        if row < rows_in_pass {
+            return start + row * step;
+        }
+        row -= rows_in_pass;
+    }
+    height
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let slice = self.bytes.get(self.pos..self.pos + n)?;
+        self.pos += n;
+        Some(slice)
+    }
+
+    fn skip(&mut self, n: usize) -> Option<()> {
+        self.take(n).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let b = self.take(2)?;
+        Some(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    /// A length-prefixed data sub-block; empty at the end of a run.
+    fn sub_block(&mut self) -> Option<&'a [u8]> {
+        let len = self.u8()? as usize;
+        self.take(len)
+    }
+}
+
+/// GIF's variable-width LZW, stopping after `max_len` pixels.
+fn lzw_decode(data: &[u8], min_code_size: u8, max_len: usize) -> Option<Vec<u8>> {
+    if !(1..=11).contains(&min_code_size) {
+        return None;
+    }
+    const MAX_CODES: usize = 4096;
+    let clear = 1usize << min_code_size;
+    let end = clear + 1;
+
+    // Each code is a previous code plus one byte
+    let mut prefix = vec![0u16; MAX_CODES];
+    let mut suffix = vec![0u8; MAX_CODES];
+    let mut first = vec![0u8; MAX_CODES];
+    let mut length = vec![0usize; MAX_CODES];
+    for code in 0..clear {
+        suffix[code] = code as u8;
+        first[code] = code as u8;
+        length[code] = 1;
+    }
+
+    let mut out = Vec::with_capacity(max_len);
+    let mut code_size = min_code_size as u32 + 1;
+    let mut next = end + 1;
+    let mut prev: Option<usize> = None;
+    let (mut acc, mut bits) = (0u32, 0u32);
+    let mut input = data.iter();
+
+    'codes: while out.len() < max_len {
+        while bits < code_size {
+            // Truncated data ends the image rather than failing it
+            let Some(&byte) = input.next() else { break 'codes };
+            acc |= (byte as u32) << bits;
+            bits += 8;
+        }
+        let code = (acc & ((1 << code_size) - 1)) as usize;
+        acc >>= code_size;
+        bits -= code_size;
+
+        if code == clear {
+            code_size = min_code_size as u32 + 1;
+            next = end + 1;
+            prev = None;
+            continue;
+        }
+        if code == end {
+            break;
+        }
+
+        match prev {
+            None if code < clear => {}
+            None => return None,
+            Some(p) if next < MAX_CODES => {
+                // For a code not yet defined, the new entry is prev + its own first byte
+                let tail = if code < next { first[code] } else if code == next { first[p] } else { return None };
+                prefix[next] = p as u16;
+                suffix[next] = tail;
+                first[next] = first[p];
+                length[next] = length[p] + 1;
+                next += 1;
+                if next == 1 << code_size && code_size < 12 {
+                    code_size += 1;
+                }
+            }
+            Some(_) if code >= next => return None,
+            Some(_) => {}
+        }
+
+        // Write the code's bytes back to front
+        let start = out.len();
+        out.resize(start + length[code], 0);
+        let mut c = code;
+        for i in (start..out.len()).rev() {
+            out[i] = suffix[c];
+            c = prefix[c] as usize;
+        }
+        prev = Some(code);
+    }
+
+    out.truncate(max_len);
+    Some(out)
+}
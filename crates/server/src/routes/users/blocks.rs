@@ -0,0 +1,114 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, BlockedUser};
+use crate::AppState;
+
+/// Whether either user has blocked the other.
+pub(crate) async fn is_blocked(state: &AppState, a: &str, b: &str) -> bool {
+    sqlx::query_scalar::<_, i64>(
+        r#"SELECT COUNT(*) FROM blocked_users
+           WHERE (blocker_id = ?1 AND blocked_id = ?2) OR (blocker_id = ?2 AND blocked_id = ?1)"#,
+    )
+    .bind(a)
+    .bind(b)
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0)
+        > 0
+}
+
+/// Everyone `user_id` has blocked or been blocked by.
+pub(crate) async fn block_relations(state: &AppState, user_id: &str) -> HashSet<String> {
+    sqlx::query_scalar::<_, String>(
+        r#"SELECT blocked_id FROM blocked_users WHERE blocker_id = ?1
+           UNION SELECT blocker_id FROM blocked_users WHERE blocked_id = ?1"#,
+    )
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await
+    .map(|ids| ids.into_iter().collect())
+    .unwrap_or_default()
+}
+
+/// GET /api/users/me/blocks
+pub async fn list_blocks(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    let blocks = sqlx::query_as::<_, BlockedUser>(
+        r#"SELECT b.blocked_id AS user_id, u.username, u.image, b.created_at
+           FROM blocked_users b
+           INNER JOIN "user" u ON u.id = b.blocked_id
+           WHERE b.blocker_id = ?
+           ORDER BY b.created_at DESC"#,
+    )
+    .bind(&user.id)
+    .fetch_all(&state.db_read)
+    .await?;
+
+    Ok(Json(blocks))
+}
+
+/// PUT /api/users/me/blocks/:userId
+/// Block a user. Any message request between the two is declined.
+pub async fn block_user(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(target_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    if target_id == user.id {
+        return Err(ApiError::bad_request("You can't block yourself"));
+    }
+    let exists: i64 = sqlx::query_scalar(r#"SELECT COUNT(*) FROM "user" WHERE id = ?"#)
+        .bind(&target_id)
+        .fetch_one(&state.db)
+        .await?;
+    if exists == 0 {
+        return Err(ApiError::not_found("User not found"));
+    }
+
+    sqlx::query("INSERT OR IGNORE INTO blocked_users (blocker_id, blocked_id, created_at) VALUES (?, ?, ?)")
+        .bind(&user.id)
+        .bind(&target_id)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&state.db)
+        .await?;
+
+    sqlx::query(
+        r#"UPDATE dm_channels SET status = 'declined'
+           WHERE status = 'pending'
+             AND ((user1_id = ?1 AND user2_id = ?2) OR (user1_id = ?2 AND user2_id = ?1))"#,
+    )
+    .bind(&user.id)
+    .bind(&target_id)
+    .execute(&state.db)
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// DELETE /api/users/me/blocks/:userId
+pub async fn unblock_user(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(target_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let deleted = sqlx::query("DELETE FROM blocked_users WHERE blocker_id = ? AND blocked_id = ?")
+        .bind(&user.id)
+        .bind(&target_id)
+        .execute(&state.db)
+        .await?;
+    if deleted.rows_affected() == 0 {
+        return Err(ApiError::not_found("User isn't blocked"));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
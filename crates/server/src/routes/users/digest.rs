@@ -0,0 +1,349 @@
+use axum::{
+    extract::{Query, State},
+    response::{Html, IntoResponse},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use minijinja::context;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::ApiError;
+use crate::mailer::Email;
+use crate::models::AuthUser;
+use crate::routes::auth::{page, token_hash};
+use crate::AppState;
+
+const FREQUENCIES: &[&str] = &["off", "daily", "weekly"];
+/// Mentions quoted in one digest; the rest are only counted.
+const MAX_LISTED_MENTIONS: usize = 20;
+const MAX_EXCERPT_CHARS: usize = 140;
+
+#[derive(Deserialize)]
+pub struct UpdateDigestRequest {
+    pub frequency: String,
+}
+
+#[derive(Deserialize)]
+pub struct UnsubscribeQuery {
+    pub user: Option<String>,
+    pub token: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct DigestMention {
+    server_name: String,
+    channel_name: String,
+    author: String,
+    content: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct DigestDm {
+    sender: String,
+    count: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct DueDigest {
+    user_id: String,
+    username: String,
+    email: String,
+    frequency: String,
+    last_sent_at: String,
+}
+
+fn period(frequency: &str) -> chrono::Duration {
+    if frequency == "weekly" {
+        chrono::Duration::days(7)
+    } else {
+        chrono::Duration::days(1)
+    }
+}
+
+/// Unsubscribe links carry an HMAC of the user id, so they work without
+/// signing in and can't be forged for someone else.
+fn unsubscribe_token(state: &AppState, user_id: &str) -> String {
+    token_hash(&state.config.auth_secret, &format!("digest-unsubscribe:{}", user_id))
+}
+
+fn unsubscribe_link(state: &AppState, user_id: &str) -> String {
+    format!(
+        "{}/api/digest/unsubscribe?user={}&token={}",
+        state.config.public_url.trim_end_matches('/'),
+        user_id,
+        unsubscribe_token(state, user_id)
+    )
+}
+
+/// GET /api/users/me/digest
+pub async fn get_digest_settings(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    let row = sqlx::query_as::<_, (String, String)>("SELECT frequency, last_sent_at FROM email_digests WHERE user_id = ?")
+        .bind(&user.id)
+        .fetch_optional(&state.db)
+        .await?;
+    let (frequency, last_sent_at) = match row {
+        Some((frequency, last_sent_at)) => (frequency, Some(last_sent_at)),
+        None => ("off".to_string(), None),
+    };
+    Ok(Json(serde_json::json!({
+        "frequency": frequency,
+        "lastSentAt": last_sent_at,
+        "available": crate::mailer::enabled(&state.config),
+    })))
+}
+
+/// PUT /api/users/me/digest — `frequency` is off, daily or weekly. The first
+/// digest covers what arrives from now on.
+pub async fn update_digest_settings(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<UpdateDigestRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    if !FREQUENCIES.contains(&body.frequency.as_str()) {
+        return Err(ApiError::bad_request("Frequency must be off, daily or weekly"));
+    }
+
+    if body.frequency == "off" {
+        sqlx::query("DELETE FROM email_digests WHERE user_id = ?")
+            .bind(&user.id)
+            .execute(&state.db)
+            .await?;
+        return get_digest_settings(State(state), user).await;
+    }
+
+    if !crate::mailer::enabled(&state.config) {
+        return Err(ApiError::unavailable("Email is not configured on this server"));
+    }
+    let verified = sqlx::query_scalar::<_, i64>(r#"SELECT emailVerified FROM "user" WHERE id = ?"#)
+        .bind(&user.id)
+        .fetch_optional(&state.db)
+        .await?
+        .unwrap_or(0);
+    if verified == 0 {
+        return Err(ApiError::forbidden("Verify your email address first"));
+    }
+
+    sqlx::query(
+        r#"INSERT INTO email_digests (user_id, frequency, last_sent_at) VALUES (?, ?, ?)
+           ON CONFLICT(user_id) DO UPDATE SET frequency = excluded.frequency"#,
+    )
+    .bind(&user.id)
+    .bind(&body.frequency)
+    .bind(Utc::now().to_rfc3339())
+    .execute(&state.db)
+    .await?;
+
+    get_digest_settings(State(state), user).await
+}
+
+/// GET /api/digest/unsubscribe?user=&token=
+/// Opened from the link at the bottom of every digest.
+pub async fn unsubscribe_digest(
+    Query(query): Query<UnsubscribeQuery>,
+    State(state): State<Arc<AppState>>,
+) -> Html<String> {
+    let (Some(user_id), Some(token)) = (query.user, query.token) else {
+        return page("Link invalid", "Turn digests off from Flux settings instead.");
+    };
+    let expected = unsubscribe_token(&state, &user_id);
+    let valid = expected.len() == token.len()
+        && expected.bytes().zip(token.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0;
+    if !valid {
+        return page("Link invalid", "Turn digests off from Flux settings instead.");
+    }
+
+    let removed = sqlx::query("DELETE FROM email_digests WHERE user_id = ?")
+        .bind(&user_id)
+        .execute(&state.db)
+        .await;
+    match removed {
+        Ok(_) => page("Unsubscribed", "You won't get digest emails anymore."),
+        Err(_) => page("Something went wrong", "Please open the link again."),
+    }
+}
+
+fn excerpt(content: &str) -> String {
+    let flat = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() > MAX_EXCERPT_CHARS {
+        format!("{}…", flat.chars().take(MAX_EXCERPT_CHARS - 1).collect::<String>())
+    } else {
+        flat
+    }
+}
+
+/// A user's digest email, or None when nothing unread came in
+/// since the last one. Messages in channels or DMs they've read past are
+/// left out.
+async fn build_digest(state: &AppState, due: &DueDigest) -> Result<Option<Email>, sqlx::Error> {
+    let mentions = sqlx::query_as::<_, DigestMention>(
+        r#"SELECT s.name AS server_name, c.name AS channel_name,
+                  COALESCE(sender.nickname, u.username, 'Deleted user') AS author, m.content
+           FROM messages m
+           INNER JOIN channels c ON c.id = m.channel_id
+           INNER JOIN servers s ON s.id = c.server_id
+           INNER JOIN memberships ms ON ms.server_id = c.server_id AND ms.user_id = ?1
+           LEFT JOIN "user" u ON u.id = m.sender_id
+           LEFT JOIN memberships sender ON sender.user_id = m.sender_id AND sender.server_id = c.server_id
+           LEFT JOIN read_states r ON r.user_id = ?1 AND r.channel_id = m.channel_id
+           WHERE m.created_at > ?2 AND m.sender_id != ?1
+             AND (r.last_read_at IS NULL OR m.created_at > r.last_read_at)
+             AND m.metadata IS NOT NULL
+             AND (json_extract(m.metadata, '$.mentionsEveryone') = 1
+                  OR EXISTS (SELECT 1 FROM json_each(m.metadata, '$.mentions') WHERE value = ?3))
+           ORDER BY m.created_at ASC"#,
+    )
+    .bind(&due.user_id)
+    .bind(&due.last_sent_at)
+    .bind(due.username.to_lowercase())
+    .fetch_all(&state.db_read)
+    .await?;
+
+    // DMs are end-to-end encrypted, so only who wrote and how much is known
+    let dms = sqlx::query_as::<_, DigestDm>(
+        r#"SELECT COALESCE(u.username, 'Deleted user') AS sender, COUNT(*) AS count
+           FROM dm_messages dm
+           INNER JOIN dm_channels c ON c.id = dm.dm_channel_id
+           LEFT JOIN "user" u ON u.id = dm.sender_id
+           LEFT JOIN read_states r ON r.user_id = ?1 AND r.channel_id = dm.dm_channel_id
+           WHERE (c.user1_id = ?1 OR c.user2_id = ?1) AND c.status = 'accepted'
+             AND dm.sender_id != ?1 AND dm.created_at > ?2
+             AND (r.last_read_at IS NULL OR dm.created_at > r.last_read_at)
+           GROUP BY dm.sender_id
+           ORDER BY MAX(dm.created_at) DESC"#,
+    )
+    .bind(&due.user_id)
+    .bind(&due.last_sent_at)
+    .fetch_all(&state.db_read)
+    .await?;
+
+    if mentions.is_empty() && dms.is_empty() {
+        return Ok(None);
+    }
+
+    let dm_total: i64 = dms.iter().map(|d| d.count).sum();
+    let plural = |n: usize, word: &str| format!("{} {}{}", n, word, if n == 1 { "" } else { "s" });
+    let mut summary = Vec::new();
+    if !mentions.is_empty() {
+        summary.push(plural(mentions.len(), "mention"));
+    }
+    if dm_total > 0 {
+        summary.push(plural(dm_total as usize, "direct message"));
+    }
+    let subject = format!("Your Flux digest: {}", summary.join(", "));
+
+    let since = DateTime::parse_from_rfc3339(&due.last_sent_at)
+        .map(|t| t.with_timezone(&Utc).format("%B %-d").to_string())
+        .unwrap_or_default();
+    let listed: Vec<_> = mentions
+        .iter()
+        .take(MAX_LISTED_MENTIONS)
+        .map(|m| {
+            context! {
+                channel => m.channel_name,
+                server => m.server_name,
+                author => m.author,
+                excerpt => excerpt(&m.content),
+            }
+        })
+        .collect();
+    let dm_lines: Vec<_> = dms
+        .iter()
+        .map(|dm| context! { sender => dm.sender, messages => plural(dm.count as usize, "message") })
+        .collect();
+
+    let ctx = context! {
+        username => due.username,
+        since,
+        mentions => listed,
+        mention_count => mentions.len(),
+        more_mentions => mentions.len().saturating_sub(MAX_LISTED_MENTIONS),
+        dms => dm_lines,
+        dm_count => dm_total,
+        url => state.config.public_url,
+        frequency => due.frequency,
+        unsubscribe => unsubscribe_link(state, &due.user_id),
+    };
+    match crate::mailer::render("digest", &due.email, &subject, ctx) {
+        Ok(email) => Ok(Some(email)),
+        Err(e) => {
+            tracing::error!("Failed to render digest email: {}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Email each opted-in user whose digest is due and who isn't connected
+/// right now. Returns how many digests were queued.
+pub async fn send_due_digests(state: &AppState) -> usize {
+    if !crate::mailer::enabled(&state.config) {
+        return 0;
+    }
+
+    let due = match sqlx::query_as::<_, DueDigest>(
+        r#"SELECT d.user_id, u.username, u.email, d.frequency, d.last_sent_at
+           FROM email_digests d
+           INNER JOIN "user" u ON u.id = d.user_id
+           WHERE u.emailVerified = 1"#,
+    )
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::warn!("Failed to load email digests: {}", e);
+            return 0;
+        }
+    };
+
+    let now = Utc::now();
+    let mut sent = 0;
+    for digest in due {
+        let Ok(last) = DateTime::parse_from_rfc3339(&digest.last_sent_at) else { continue };
+        if now - last.with_timezone(&Utc) < period(&digest.frequency) {
+            continue;
+        }
+        // Online users see their mentions live; try again next round
+        if state.gateway.get_user_status(&digest.user_id).await.is_some() {
+            continue;
+        }
+
+        let content = match build_digest(state, &digest).await {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::warn!("Failed to build digest for {}: {}", digest.user_id, e);
+                continue;
+            }
+        };
+        if let Some(email) = content {
+            crate::mailer::queue(state, email).await;
+            sent += 1;
+        }
+
+        // Nothing to report still starts a new window, so old mentions
+        // don't pile into a later digest
+        let _ = sqlx::query("UPDATE email_digests SET last_sent_at = ? WHERE user_id = ?")
+            .bind(now.to_rfc3339())
+            .bind(&digest.user_id)
+            .execute(&state.db)
+            .await;
+    }
+
+    sent
+}
+
+/// Background task: check for due digests once an hour.
+pub async fn run_email_digests(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(3600));
+    loop {
+        interval.tick().await;
+        let sent = send_due_digests(&state).await;
+        if sent > 0 {
+            tracing::info!("Queued {} email digests", sent);
+        }
+    }
+}
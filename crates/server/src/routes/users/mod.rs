@@ -0,0 +1,332 @@
+mod avatar;
+mod blocks;
+mod digest;
+mod settings;
+mod username;
+
+pub use avatar::AvatarQuery;
+pub use blocks::{block_user, list_blocks, unblock_user};
+pub use digest::{get_digest_settings, run_email_digests, send_due_digests, unsubscribe_digest, update_digest_settings};
+pub(crate) use blocks::{block_relations, is_blocked};
+pub use settings::{delete_setting, list_settings, put_setting};
+pub(crate) use avatar::process_upload;
+pub use username::update_username;
+pub(crate) use username::{known_usernames, resolve_username};
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, MutualServer, OwnProfile, ProfileCosmetics, UpdateUserRequest, UserProfile};
+use crate::AppState;
+
+const OWN_PROFILE_SQL: &str = r#"SELECT id, username, email, image, image_static, ring_style, ring_spin, steam_id, ring_pattern_seed, banner_css, banner_pattern_seed, status, bio, pronouns, accent_color, timezone, locale FROM "user" WHERE id = ?"#;
+
+/// GET /api/users/me
+pub async fn get_me(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    let profile = sqlx::query_as::<_, OwnProfile>(OWN_PROFILE_SQL)
+        .bind(&user.id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten();
+
+    match profile {
+        Some(profile) => Ok(Json(profile).into_response()),
+        None => Err(ApiError::not_found("User not found")),
+    }
+}
+
+/// A free-text profile field from a PATCH body: null or blank clears it.
+fn profile_text(value: &serde_json::Value, field: &str, max_chars: usize) -> Result<Option<String>, ApiError> {
+    match value {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(s) => {
+            let trimmed = s.trim();
+            if trimmed.chars().count() > max_chars {
+                return Err(ApiError::bad_request(format!("{} must be at most {} characters", field, max_chars)));
+            }
+            Ok((!trimmed.is_empty()).then(|| trimmed.to_string()))
+        }
+        _ => Err(ApiError::bad_request(format!("{} must be a string or null", field))),
+    }
+}
+
+/// Validate the bio, pronouns, accent colour and timezone in a PATCH body,
+/// returning the columns to set.
+fn profile_details(body: &UpdateUserRequest) -> Result<Vec<(&'static str, Option<String>)>, ApiError> {
+    let mut updates = Vec::new();
+
+    if let Some(ref bio) = body.bio {
+        updates.push(("bio", profile_text(bio, "Bio", 190)?));
+    }
+    if let Some(ref pronouns) = body.pronouns {
+        updates.push(("pronouns", profile_text(pronouns, "Pronouns", 40)?));
+    }
+    if let Some(ref accent_color) = body.accent_color {
+        let color = profile_text(accent_color, "Accent color", 7)?;
+        if let Some(ref c) = color {
+            let re = regex_lite::Regex::new(r"^#[0-9a-fA-F]{6}$").unwrap();
+            if !re.is_match(c) {
+                return Err(ApiError::bad_request("Accent color must be a hex color like #5865f2"));
+            }
+        }
+        updates.push(("accent_color", color.map(|c| c.to_lowercase())));
+    }
+    if let Some(ref timezone) = body.timezone {
+        let tz = profile_text(timezone, "Timezone", 64)?;
+        if let Some(ref t) = tz {
+            // IANA names such as Europe/London or America/Argentina/Buenos_Aires
+            let re = regex_lite::Regex::new(r"^(UTC|[A-Za-z]+(/[A-Za-z0-9_+-]+){1,2})$").unwrap();
+            if !re.is_match(t) {
+                return Err(ApiError::bad_request("Invalid timezone"));
+            }
+        }
+        updates.push(("timezone", tz));
+    }
+    if let Some(ref locale) = body.locale {
+        let locale = match profile_text(locale, "Locale", 16)? {
+            Some(tag) => match flux_shared::i18n::Locale::from_tag(&tag) {
+                Some(l) => Some(l.tag().to_string()),
+                None => return Err(ApiError::bad_request("Unsupported locale")),
+            },
+            None => None,
+        };
+        updates.push(("locale", locale));
+    }
+
+    Ok(updates)
+}
+
+/// GET /api/users/:userId/profile
+pub async fn get_profile(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(user_id): Path<String>,
+    Query(avatars): Query<AvatarQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let sql = format!(
+        r#"SELECT u.id, u.username, {}, u.bio, u.pronouns, u.accent_color, u.timezone, u.ring_style, u.ring_spin, u.ring_pattern_seed, u.banner_css, u.banner_pattern_seed FROM "user" u WHERE u.id = ?"#,
+        avatars.image_column("u", "image"),
+    );
+    let row = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, String, bool, Option<i64>, Option<String>, Option<i64>)>(
+        &sql,
+    )
+    .bind(&user_id)
+    .fetch_optional(&state.db_read)
+    .await?;
+    let Some((id, username, image, bio, pronouns, accent_color, timezone, ring_style, ring_spin, ring_pattern_seed, banner_css, banner_pattern_seed)) = row else {
+        return Err(ApiError::not_found("User not found"));
+    };
+    let cosmetics = ProfileCosmetics { ring_style, ring_spin, ring_pattern_seed, banner_css, banner_pattern_seed };
+
+    let mutual_servers = sqlx::query_as::<_, MutualServer>(
+        r#"SELECT s.id, s.name
+           FROM servers s
+           JOIN memberships theirs ON theirs.server_id = s.id AND theirs.user_id = ?
+           JOIN memberships mine ON mine.server_id = s.id AND mine.user_id = ?
+           ORDER BY s.name COLLATE NOCASE ASC"#,
+    )
+    .bind(&id)
+    .bind(&user.id)
+    .fetch_all(&state.db_read)
+    .await?;
+
+    // Invisible users look offline, activity included
+    let status = match state.gateway.get_user_status(&id).await {
+        Some(status) if status != "invisible" => status,
+        _ => "offline".to_string(),
+    };
+    let activity = if status == "offline" {
+        None
+    } else {
+        state.gateway.get_user_activity(&id).await
+    };
+
+    Ok(Json(UserProfile {
+        id,
+        username,
+        image,
+        status,
+        bio,
+        pronouns,
+        accent_color,
+        timezone,
+        cosmetics,
+        mutual_servers,
+        activity,
+    }))
+}
+
+/// PATCH /api/users/me
+pub async fn update_me(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<UpdateUserRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut has_updates = false;
+    let details = profile_details(&body)?;
+
+    // Decode and check a new avatar before anything is written
+    let avatar = match body.image {
+        Some(serde_json::Value::String(ref img)) => {
+            Some(avatar::process_upload(img, state.config.avatar_max_bytes).await?)
+        }
+        _ => None,
+    };
+
+    if let Some(ref username) = body.username {
+        username::change_username(&state, &user.id, username).await?;
+        has_updates = true;
+    }
+
+    if let Some(ref image_val) = body.image {
+        match image_val {
+            serde_json::Value::Null => {
+                let now = chrono::Utc::now().to_rfc3339();
+                sqlx::query(
+                    r#"UPDATE "user" SET image = NULL, image_static = NULL, updatedAt = ? WHERE id = ?"#,
+                )
+                .bind(&now)
+                .bind(&user.id)
+                .execute(&state.db)
+                .await?;
+                has_updates = true;
+            }
+            serde_json::Value::String(_) => {
+                let avatar = avatar.as_ref().expect("avatar processed above");
+                let now = chrono::Utc::now().to_rfc3339();
+                sqlx::query(
+                    r#"UPDATE "user" SET image = ?, image_static = ?, updatedAt = ? WHERE id = ?"#,
+                )
+                .bind(&avatar.image)
+                .bind(&avatar.static_image)
+                .bind(&now)
+                .bind(&user.id)
+                .execute(&state.db)
+                .await?;
+                has_updates = true;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(ref ring_style) = body.ring_style {
+        let valid = [
+            "default", "chroma", "pulse", "wave", "ember", "frost", "neon", "galaxy", "none",
+        ];
+        if !valid.contains(&ring_style.as_str()) {
+            return Err(ApiError::bad_request("Invalid ring style"));
+        }
+        let now = chrono::Utc::now().to_rfc3339();
+        sqlx::query(
+            r#"UPDATE "user" SET ring_style = ?, updatedAt = ? WHERE id = ?"#,
+        )
+        .bind(ring_style)
+        .bind(&now)
+        .bind(&user.id)
+        .execute(&state.db)
+        .await?;
+        has_updates = true;
+    }
+
+    if let Some(ring_spin) = body.ring_spin {
+        let now = chrono::Utc::now().to_rfc3339();
+        sqlx::query(
+            r#"UPDATE "user" SET ring_spin = ?, updatedAt = ? WHERE id = ?"#,
+        )
+        .bind(ring_spin)
+        .bind(&now)
+        .bind(&user.id)
+        .execute(&state.db)
+        .await?;
+        has_updates = true;
+    }
+
+    if let Some(ref steam_val) = body.steam_id {
+        match steam_val {
+            serde_json::Value::Null => {
+                let now = chrono::Utc::now().to_rfc3339();
+                sqlx::query(
+                    r#"UPDATE "user" SET steam_id = NULL, updatedAt = ? WHERE id = ?"#,
+                )
+                .bind(&now)
+                .bind(&user.id)
+                .execute(&state.db)
+                .await?;
+                has_updates = true;
+            }
+            serde_json::Value::String(sid) => {
+                let trimmed = sid.trim();
+                if !trimmed.is_empty() {
+                    let now = chrono::Utc::now().to_rfc3339();
+                    sqlx::query(
+                        r#"UPDATE "user" SET steam_id = ?, updatedAt = ? WHERE id = ?"#,
+                    )
+                    .bind(trimmed)
+                    .bind(&now)
+                    .bind(&user.id)
+                    .execute(&state.db)
+                    .await?;
+                    has_updates = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (column, value) in details {
+        let now = chrono::Utc::now().to_rfc3339();
+        sqlx::query(&format!(r#"UPDATE "user" SET {} = ?, updatedAt = ? WHERE id = ?"#, column))
+            .bind(value)
+            .bind(&now)
+            .bind(&user.id)
+            .execute(&state.db)
+            .await?;
+        has_updates = true;
+    }
+
+    if !has_updates {
+        return Err(ApiError::bad_request("No fields to update"));
+    }
+
+    // Return updated profile
+    let profile = sqlx::query_as::<_, OwnProfile>(OWN_PROFILE_SQL)
+        .bind(&user.id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten();
+
+    match profile {
+        Some(profile) => {
+            // Broadcast profile update to all connected clients
+            state
+                .gateway
+                .broadcast_all(
+                    &crate::ws::events::ServerEvent::ProfileUpdate {
+                        user_id: profile.id.clone(),
+                        username: body.username.as_ref().map(|u| u.trim().to_string()),
+                        image: body.image.as_ref().map(|_| profile.image.clone()),
+                        ring_style: body.ring_style.clone(),
+                        ring_spin: body.ring_spin,
+                        ring_pattern_seed: None,
+                        banner_css: None,
+                        banner_pattern_seed: None,
+                    },
+                    None,
+                )
+                .await;
+
+            Ok(Json(profile).into_response())
+        }
+        None => Err(ApiError::internal("Failed to fetch updated profile")),
+    }
+}
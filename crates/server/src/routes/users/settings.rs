@@ -0,0 +1,137 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+const MAX_KEY_LEN: usize = 64;
+const MAX_VALUE_BYTES: usize = 16 * 1024;
+const MAX_SETTINGS: i64 = 100;
+
+#[derive(Deserialize)]
+pub struct PutSettingRequest {
+    pub value: serde_json::Value,
+}
+
+fn validate_key(key: &str) -> Result<(), ApiError> {
+    let valid = !key.is_empty()
+        && key.len() <= MAX_KEY_LEN
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(ApiError::bad_request(format!(
+            "Setting keys are 1-{} lowercase letters, digits, dashes, underscores or dots",
+            MAX_KEY_LEN
+        )))
+    }
+}
+
+fn setting_json(key: &str, value: serde_json::Value, updated_at: &str) -> serde_json::Value {
+    serde_json::json!({ "key": key, "value": value, "updatedAt": updated_at })
+}
+
+/// GET /api/users/me/settings
+/// Every client setting the user has synced, e.g. their key bindings.
+pub async fn list_settings(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    let rows = sqlx::query_as::<_, (String, String, String)>(
+        "SELECT key, value, updated_at FROM user_settings WHERE user_id = ? ORDER BY key",
+    )
+    .bind(&user.id)
+    .fetch_all(&state.db_read)
+    .await?;
+
+    let settings: Vec<serde_json::Value> = rows
+        .into_iter()
+        .map(|(key, value, updated_at)| {
+            let value = serde_json::from_str(&value).unwrap_or(serde_json::Value::Null);
+            setting_json(&key, value, &updated_at)
+        })
+        .collect();
+    Ok(Json(settings))
+}
+
+/// PUT /api/users/me/settings/:key
+/// Store a setting and push it to the user's other sessions.
+pub async fn put_setting(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(key): Path<String>,
+    Json(body): Json<PutSettingRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    validate_key(&key)?;
+    let value = body.value.to_string();
+    if value.len() > MAX_VALUE_BYTES {
+        return Err(ApiError::bad_request(format!("Setting values are limited to {} bytes", MAX_VALUE_BYTES)));
+    }
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_settings WHERE user_id = ? AND key != ?")
+        .bind(&user.id)
+        .bind(&key)
+        .fetch_one(&state.db)
+        .await?;
+    if count >= MAX_SETTINGS {
+        return Err(ApiError::bad_request(format!("You can sync at most {} settings", MAX_SETTINGS)));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"INSERT INTO user_settings (user_id, key, value, updated_at) VALUES (?, ?, ?, ?)
+           ON CONFLICT(user_id, key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at"#,
+    )
+    .bind(&user.id)
+    .bind(&key)
+    .bind(&value)
+    .bind(&now)
+    .execute(&state.db)
+    .await?;
+
+    state
+        .gateway
+        .send_to_user(
+            &user.id,
+            &ServerEvent::UserSettingUpdate { key: key.clone(), value: Some(body.value.clone()), updated_at: now.clone() },
+        )
+        .await;
+
+    Ok(Json(setting_json(&key, body.value, &now)))
+}
+
+/// DELETE /api/users/me/settings/:key
+/// Forget a setting so clients fall back to their defaults.
+pub async fn delete_setting(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(key): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let deleted = sqlx::query("DELETE FROM user_settings WHERE user_id = ? AND key = ?")
+        .bind(&user.id)
+        .bind(&key)
+        .execute(&state.db)
+        .await?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(ApiError::not_found("Setting not found"));
+    }
+
+    state
+        .gateway
+        .send_to_user(
+            &user.id,
+            &ServerEvent::UserSettingUpdate { key, value: None, updated_at: chrono::Utc::now().to_rfc3339() },
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
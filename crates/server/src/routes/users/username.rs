@@ -0,0 +1,152 @@
+use axum::{extract::State, response::IntoResponse, Json};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use super::OWN_PROFILE_SQL;
+use crate::error::ApiError;
+use crate::models::{AuthUser, OwnProfile};
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+/// A name someone gave up stays theirs this long, so mentions of it keep
+/// pointing at them rather than at whoever grabs it next.
+const HOLD_DAYS: i64 = 90;
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateUsernameRequest {
+    pub username: String,
+}
+
+/// Rename `user_id`, recording the old name and announcing the change.
+/// Returns the new (trimmed) username; renaming to the current name is a no-op.
+pub(crate) async fn change_username(state: &AppState, user_id: &str, requested: &str) -> Result<String, ApiError> {
+    let username = requested.trim();
+    if username.len() < 2 || username.len() > 32 {
+        return Err(ApiError::bad_request("Username must be 2-32 characters"));
+    }
+
+    let re = regex_lite::Regex::new(r"^[a-zA-Z0-9_-]+$").unwrap();
+    if !re.is_match(username) {
+        return Err(ApiError::bad_request("Username can only contain letters, numbers, hyphens, and underscores"));
+    }
+
+    let current = sqlx::query_scalar::<_, String>(r#"SELECT username FROM "user" WHERE id = ?"#)
+        .bind(user_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("User not found"))?;
+    if current == username {
+        return Ok(current);
+    }
+
+    let now = chrono::Utc::now();
+    let last_change = sqlx::query_scalar::<_, String>(
+        r#"SELECT changed_at FROM "username_history" WHERE user_id = ? ORDER BY changed_at DESC LIMIT 1"#,
+    )
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?
+    .and_then(|at| chrono::DateTime::parse_from_rfc3339(&at).ok());
+    if let Some(last_change) = last_change {
+        let next = last_change + chrono::Duration::seconds(state.config.username_change_cooldown_secs as i64);
+        let wait = next.signed_duration_since(now).num_seconds();
+        if wait > 0 {
+            return Err(ApiError::rate_limited("Username was changed too recently", wait));
+        }
+    }
+
+    let held_since = (now - chrono::Duration::days(HOLD_DAYS)).to_rfc3339();
+    let taken = sqlx::query_scalar::<_, i64>(
+        r#"SELECT (SELECT COUNT(*) FROM "user" WHERE username = ? AND id != ?)
+                + (SELECT COUNT(*) FROM "username_history" WHERE old_username = ? AND user_id != ? AND changed_at > ?)"#,
+    )
+    .bind(username)
+    .bind(user_id)
+    .bind(username)
+    .bind(user_id)
+    .bind(&held_since)
+    .fetch_one(&state.db)
+    .await?
+        > 0;
+    if taken {
+        return Err(ApiError::conflict("Username already taken"));
+    }
+
+    let changed_at = now.to_rfc3339();
+    let mut tx = state.db.begin().await?;
+    sqlx::query(r#"UPDATE "user" SET username = ?, name = ?, updatedAt = ? WHERE id = ?"#)
+        .bind(username)
+        .bind(username)
+        .bind(&changed_at)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(r#"INSERT INTO "username_history" (id, user_id, old_username, changed_at) VALUES (?, ?, ?, ?)"#)
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(user_id)
+        .bind(&current)
+        .bind(&changed_at)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    state
+        .gateway
+        .broadcast_all(
+            &ServerEvent::UserRenamed {
+                user_id: user_id.to_string(),
+                old_username: current,
+                username: username.to_string(),
+            },
+            None,
+        )
+        .await;
+
+    Ok(username.to_string())
+}
+
+/// The user a name belongs to: whoever has it now, else whoever most recently gave it up.
+pub(crate) async fn resolve_username(db: &sqlx::SqlitePool, name: &str) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        r#"SELECT id FROM (
+               SELECT id, 1 AS rank, '' AS changed_at FROM "user" WHERE username = ?
+               UNION ALL
+               SELECT user_id, 2, changed_at FROM "username_history" WHERE old_username = ?
+           ) ORDER BY rank ASC, changed_at DESC LIMIT 1"#,
+    )
+    .bind(name)
+    .bind(name)
+    .fetch_optional(db)
+    .await
+}
+
+/// Every name a user has gone by, current first.
+pub(crate) async fn known_usernames(db: &sqlx::SqlitePool, user_id: &str) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        r#"SELECT username FROM "user" WHERE id = ?
+           UNION ALL
+           SELECT old_username FROM (
+               SELECT old_username, MAX(changed_at) AS changed_at FROM "username_history"
+               WHERE user_id = ? GROUP BY old_username ORDER BY changed_at DESC
+           )"#,
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+}
+
+/// PATCH /api/users/me/username
+pub async fn update_username(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<UpdateUsernameRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    change_username(&state, &user.id, &body.username).await?;
+
+    let profile = sqlx::query_as::<_, OwnProfile>(OWN_PROFILE_SQL)
+        .bind(&user.id)
+        .fetch_one(&state.db)
+        .await?;
+    Ok(Json(profile))
+}
@@ -0,0 +1,107 @@
+mod nodes;
+mod quality;
+mod sessions;
+mod stage;
+
+pub use nodes::{list_voice_nodes, report_latency};
+pub(crate) use nodes::parse_voice_region;
+pub use quality::get_server_voice_quality;
+pub(crate) use quality::record_voice_stats;
+pub use sessions::*;
+pub use stage::get_stage;
+pub(crate) use stage::{can_speak, is_stage_moderator, stage_left};
+
+use axum::{
+    extract::State,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, VoiceTokenRequest};
+use crate::AppState;
+
+/// POST /api/voice/token
+pub async fn get_token(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<VoiceTokenRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Verify channel exists and is a voice channel
+    let channel = sqlx::query_as::<_, (String, String, Option<String>)>(
+        "SELECT server_id, type, voice_region FROM channels WHERE id = ?",
+    )
+    .bind(&body.channel_id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    let (server_id, channel_type, voice_region) = match channel {
+        Some(c) => c,
+        None => return Err(ApiError::not_found("Channel not found")),
+    };
+
+    if channel_type != "voice" && channel_type != "stage" {
+        return Err(ApiError::bad_request("Not a voice channel"));
+    }
+
+    // Verify membership
+    let is_member = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?",
+    )
+    .bind(&user.id)
+    .bind(&server_id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0);
+
+    if is_member == 0 {
+        return Err(ApiError::forbidden("Not a member of this server"));
+    }
+
+    let target = nodes::select_node(&state, &user.id, &body.channel_id, voice_region.as_deref()).await?;
+
+    // Check LiveKit is configured
+    if target.api_key.is_empty() || target.api_secret.is_empty() {
+        return Err(ApiError::unavailable("LiveKit not configured. Set LIVEKIT_API_KEY and LIVEKIT_API_SECRET in .env"));
+    }
+
+    // Generate LiveKit access token. On a stage only speakers publish.
+    let is_viewer = body.viewer.unwrap_or(false);
+    let can_publish = !is_viewer && (channel_type != "stage" || can_speak(&state, &user.id, &body.channel_id, &server_id).await);
+    let identity = if is_viewer {
+        format!("{}-viewer", user.id)
+    } else {
+        user.id.clone()
+    };
+    let name = if is_viewer {
+        format!("{} (viewer)", user.username)
+    } else {
+        user.username.clone()
+    };
+
+    let token = livekit_api::access_token::AccessToken::with_api_key(&target.api_key, &target.api_secret)
+    .with_identity(&identity)
+    .with_name(&name)
+    .with_grants(livekit_api::access_token::VideoGrants {
+        room_join: true,
+        room: body.channel_id.clone(),
+        can_publish,
+        can_subscribe: true,
+        ..Default::default()
+    })
+    .to_jwt();
+
+    match token {
+        Ok(jwt) => Ok(Json(serde_json::json!({
+            "token": jwt,
+            "url": target.url,
+            "nodeId": target.node.as_ref().map(|n| &n.id),
+            "region": target.node.as_ref().map(|n| &n.region),
+        }))
+        .into_response()),
+        Err(_) => Err(ApiError::internal("Failed to generate token")),
+    }
+}
@@ -0,0 +1,188 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AuthUser, LatencyReport, VoiceNode};
+use crate::AppState;
+
+/// Round trips above this are a failed probe, not a measurement.
+const MAX_RTT_MS: i64 = 60_000;
+
+/// Where a voice token is minted and who it sends the client to.
+pub(crate) struct SelectedNode {
+    /// None when no nodes are registered and the configured LiveKit is used.
+    pub node: Option<VoiceNode>,
+    pub url: String,
+    pub api_key: String,
+    pub api_secret: String,
+}
+
+/// Validate a channel's voice region from a PATCH body: null for automatic,
+/// or a region at least one node serves.
+pub(crate) async fn parse_voice_region(state: &AppState, value: &serde_json::Value) -> Result<Option<String>, ApiError> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    let Some(region) = value.as_str().map(str::trim).filter(|r| !r.is_empty()) else {
+        return Err(ApiError::bad_request("voiceRegion must be null or a region name"));
+    };
+    let known: i64 = sqlx::query_scalar(r#"SELECT COUNT(*) FROM "voice_nodes" WHERE region = ?"#)
+        .bind(region)
+        .fetch_one(&state.db)
+        .await?;
+    if known == 0 {
+        return Err(ApiError::bad_request(format!("No voice nodes in region '{}'", region)));
+    }
+    Ok(Some(region.to_string()))
+}
+
+/// Pick the node for `user_id` joining `channel_id`. A call already running
+/// stays on its node so everyone in it shares a room. Otherwise the enabled
+/// node (in the channel's region, if it has one) the user measured the
+/// lowest round trip to wins, with unmeasured nodes after measured ones.
+pub(crate) async fn select_node(
+    state: &AppState,
+    user_id: &str,
+    channel_id: &str,
+    region: Option<&str>,
+) -> Result<SelectedNode, ApiError> {
+    let in_call = !state.gateway.voice_channel_participants(channel_id).await.is_empty();
+    let current = if in_call {
+        sqlx::query_as::<_, VoiceNode>(
+            r#"SELECT n.* FROM "voice_room_nodes" r
+               INNER JOIN "voice_nodes" n ON n.id = r.node_id
+               WHERE r.channel_id = ? AND n.enabled = 1"#,
+        )
+        .bind(channel_id)
+        .fetch_optional(&state.db)
+        .await?
+    } else {
+        None
+    };
+
+    let node = match current {
+        Some(node) => Some(node),
+        None => {
+            // Nodes in the region first; a region with every node disabled
+            // falls back to the rest rather than failing the call
+            let ranked = sqlx::query_as::<_, VoiceNode>(
+                r#"SELECT n.* FROM "voice_nodes" n
+                   LEFT JOIN "voice_latency" l ON l.node_id = n.id AND l.user_id = ?
+                   WHERE n.enabled = 1
+                   ORDER BY (n.region = ?) DESC, l.rtt_ms IS NULL, l.rtt_ms, n.created_at
+                   LIMIT 1"#,
+            )
+            .bind(user_id)
+            .bind(region.unwrap_or(""))
+            .fetch_optional(&state.db)
+            .await?;
+            if let Some(ref node) = ranked {
+                sqlx::query(
+                    r#"INSERT INTO "voice_room_nodes" (channel_id, node_id, assigned_at) VALUES (?, ?, ?)
+                       ON CONFLICT(channel_id) DO UPDATE SET node_id = excluded.node_id, assigned_at = excluded.assigned_at"#,
+                )
+                .bind(channel_id)
+                .bind(&node.id)
+                .bind(chrono::Utc::now().to_rfc3339())
+                .execute(&state.db)
+                .await?;
+            }
+            ranked
+        }
+    };
+
+    let (url, api_key, api_secret) = match &node {
+        Some(n) if !n.api_key.is_empty() && !n.api_secret.is_empty() => {
+            (n.url.clone(), n.api_key.clone(), n.api_secret.clone())
+        }
+        Some(n) => (
+            n.url.clone(),
+            state.config.livekit_api_key.clone(),
+            state.config.livekit_api_secret.clone(),
+        ),
+        None => (
+            state.config.livekit_url.clone(),
+            state.config.livekit_api_key.clone(),
+            state.config.livekit_api_secret.clone(),
+        ),
+    };
+    Ok(SelectedNode { node, url, api_key, api_secret })
+}
+
+/// GET /api/voice/nodes
+/// Enabled nodes for the client to probe, with its last measurement of each.
+pub async fn list_voice_nodes(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    let rows = sqlx::query_as::<_, (String, String, String, Option<i64>, Option<String>)>(
+        r#"SELECT n.id, n.region, n.url, l.rtt_ms, l.measured_at
+           FROM "voice_nodes" n
+           LEFT JOIN "voice_latency" l ON l.node_id = n.id AND l.user_id = ?
+           WHERE n.enabled = 1
+           ORDER BY n.region, n.created_at"#,
+    )
+    .bind(&user.id)
+    .fetch_all(&state.db_read)
+    .await?;
+
+    let nodes: Vec<serde_json::Value> = rows
+        .into_iter()
+        .map(|(id, region, url, rtt_ms, measured_at)| {
+            serde_json::json!({
+                "id": id,
+                "region": region,
+                "url": url,
+                "rttMs": rtt_ms,
+                "measuredAt": measured_at,
+            })
+        })
+        .collect();
+    Ok(Json(nodes))
+}
+
+/// POST /api/voice/latency
+/// Store the client's probe results; later ones replace earlier ones.
+pub async fn report_latency(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<LatencyReport>,
+) -> Result<impl IntoResponse, ApiError> {
+    let known: HashSet<String> = sqlx::query_scalar::<_, String>(r#"SELECT id FROM "voice_nodes""#)
+        .fetch_all(&state.db)
+        .await?
+        .into_iter()
+        .collect();
+    for sample in &body.results {
+        if !known.contains(&sample.node_id) {
+            return Err(ApiError::bad_request(format!("Unknown voice node {}", sample.node_id)));
+        }
+        if !(0..=MAX_RTT_MS).contains(&sample.rtt_ms) {
+            return Err(ApiError::bad_request(format!("rttMs must be 0-{}", MAX_RTT_MS)));
+        }
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = state.db.begin().await?;
+    for sample in &body.results {
+        sqlx::query(
+            r#"INSERT INTO "voice_latency" (user_id, node_id, rtt_ms, measured_at) VALUES (?, ?, ?, ?)
+               ON CONFLICT(user_id, node_id) DO UPDATE SET rtt_ms = excluded.rtt_ms, measured_at = excluded.measured_at"#,
+        )
+        .bind(&user.id)
+        .bind(&sample.node_id)
+        .bind(sample.rtt_ms)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,218 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::AppState;
+
+/// How far back a channel's current quality looks.
+const QUALITY_WINDOW_SECS: i64 = 120;
+/// How long samples are kept.
+const QUALITY_HISTORY_DAYS: i64 = 7;
+/// Averages over the window past any of these mark a channel degraded.
+const DEGRADED_RTT_MS: f64 = 300.0;
+const DEGRADED_JITTER_MS: f64 = 50.0;
+const DEGRADED_PACKET_LOSS: f64 = 5.0;
+
+#[derive(Deserialize)]
+pub struct VoiceQualityQuery {
+    pub hours: Option<i64>,
+}
+
+async fn require_server_admin(state: &AppState, user_id: &str, server_id: &str) -> Result<(), ApiError> {
+    let role = sqlx::query_scalar::<_, String>("SELECT role FROM memberships WHERE user_id = ? AND server_id = ?")
+        .bind(user_id)
+        .bind(server_id)
+        .fetch_optional(&state.db)
+        .await?;
+    match role.as_deref() {
+        Some("owner") | Some("admin") => Ok(()),
+        _ => Err(ApiError::forbidden("Insufficient permissions")),
+    }
+}
+
+fn is_degraded(rtt_ms: f64, jitter_ms: f64, packet_loss: f64) -> bool {
+    rtt_ms > DEGRADED_RTT_MS || jitter_ms > DEGRADED_JITTER_MS || packet_loss > DEGRADED_PACKET_LOSS
+}
+
+/// Store a client's call quality sample for the channel it's in, and warn
+/// when the channel's recent average crosses the degraded thresholds (or
+/// recovers). `packet_loss` is a percentage.
+pub(crate) async fn record_voice_stats(
+    state: &AppState,
+    user_id: &str,
+    channel_id: &str,
+    rtt_ms: f64,
+    jitter_ms: f64,
+    packet_loss: f64,
+) {
+    let valid = [rtt_ms, jitter_ms, packet_loss].iter().all(|v| v.is_finite() && *v >= 0.0)
+        && rtt_ms <= 60_000.0
+        && jitter_ms <= 60_000.0
+        && packet_loss <= 100.0;
+    if !valid {
+        return;
+    }
+    let Some(server_id) = sqlx::query_scalar::<_, String>("SELECT server_id FROM channels WHERE id = ?")
+        .bind(channel_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten()
+    else {
+        return;
+    };
+
+    let now = chrono::Utc::now();
+    let inserted = sqlx::query(
+        r#"INSERT INTO "voice_quality_samples" (server_id, channel_id, user_id, rtt_ms, jitter_ms, packet_loss, sampled_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(&server_id)
+    .bind(channel_id)
+    .bind(user_id)
+    .bind(rtt_ms)
+    .bind(jitter_ms)
+    .bind(packet_loss)
+    .bind(now.to_rfc3339())
+    .execute(&state.db)
+    .await;
+    if let Err(e) = inserted {
+        tracing::warn!("Failed to store voice quality sample: {}", e);
+        return;
+    }
+    crate::metrics::record_voice_sample(rtt_ms, jitter_ms, packet_loss);
+
+    let _ = sqlx::query(r#"DELETE FROM "voice_quality_samples" WHERE channel_id = ? AND sampled_at < ?"#)
+        .bind(channel_id)
+        .bind((now - chrono::Duration::days(QUALITY_HISTORY_DAYS)).to_rfc3339())
+        .execute(&state.db)
+        .await;
+
+    let recent = sqlx::query_as::<_, (f64, f64, f64)>(
+        r#"SELECT AVG(rtt_ms), AVG(jitter_ms), AVG(packet_loss) FROM "voice_quality_samples"
+           WHERE channel_id = ? AND sampled_at >= ?"#,
+    )
+    .bind(channel_id)
+    .bind((now - chrono::Duration::seconds(QUALITY_WINDOW_SECS)).to_rfc3339())
+    .fetch_one(&state.db)
+    .await;
+    let Ok((rtt, jitter, loss)) = recent else { return };
+
+    let degraded = is_degraded(rtt, jitter, loss);
+    if crate::metrics::set_voice_channel_degraded(channel_id, degraded) {
+        if degraded {
+            tracing::warn!(
+                "Voice quality degraded in channel {} (server {}): rtt {:.0}ms, jitter {:.0}ms, loss {:.1}%",
+                channel_id, server_id, rtt, jitter, loss
+            );
+        } else {
+            tracing::info!("Voice quality recovered in channel {} (server {})", channel_id, server_id);
+        }
+    }
+}
+
+/// The 95th percentile of `values`, which must not be empty.
+fn p95(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let idx = ((values.len() as f64) * 0.95).ceil() as usize;
+    values[idx.clamp(1, values.len()) - 1]
+}
+
+#[derive(Default)]
+struct ChannelQuality {
+    name: String,
+    users: HashSet<String>,
+    rtt: Vec<f64>,
+    jitter: Vec<f64>,
+    loss: Vec<f64>,
+    recent: Vec<(f64, f64, f64)>,
+}
+
+/// GET /api/servers/:serverId/voice/quality?hours=
+/// Call quality per voice channel over the last `hours` (default 24, at
+/// most a week): averages, 95th percentile round trip and whether the
+/// channel is degraded right now. Server admins only.
+pub async fn get_server_voice_quality(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+    Query(query): Query<VoiceQualityQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let hours = query.hours.unwrap_or(24).clamp(1, QUALITY_HISTORY_DAYS * 24);
+    let now = chrono::Utc::now();
+    let since = (now - chrono::Duration::hours(hours)).to_rfc3339();
+    let recent_since = (now - chrono::Duration::seconds(QUALITY_WINDOW_SECS)).to_rfc3339();
+
+    let samples = sqlx::query_as::<_, (String, String, String, f64, f64, f64, String)>(
+        r#"SELECT q.channel_id, c.name, q.user_id, q.rtt_ms, q.jitter_ms, q.packet_loss, q.sampled_at
+           FROM "voice_quality_samples" q
+           INNER JOIN channels c ON c.id = q.channel_id
+           WHERE q.server_id = ? AND q.sampled_at >= ?"#,
+    )
+    .bind(&server_id)
+    .bind(&since)
+    .fetch_all(&state.db_read)
+    .await?;
+
+    let mut channels: BTreeMap<String, ChannelQuality> = BTreeMap::new();
+    for (channel_id, name, user_id, rtt, jitter, loss, sampled_at) in samples {
+        let entry = channels.entry(channel_id).or_default();
+        entry.name = name;
+        entry.users.insert(user_id);
+        entry.rtt.push(rtt);
+        entry.jitter.push(jitter);
+        entry.loss.push(loss);
+        if sampled_at >= recent_since {
+            entry.recent.push((rtt, jitter, loss));
+        }
+    }
+
+    let avg = |v: &[f64]| v.iter().sum::<f64>() / v.len() as f64;
+    let mut result: Vec<serde_json::Value> = channels
+        .into_iter()
+        .map(|(channel_id, mut q)| {
+            let degraded = !q.recent.is_empty() && {
+                let n = q.recent.len() as f64;
+                let (r, j, l) = q.recent.iter().fold((0.0, 0.0, 0.0), |acc, s| (acc.0 + s.0, acc.1 + s.1, acc.2 + s.2));
+                is_degraded(r / n, j / n, l / n)
+            };
+            serde_json::json!({
+                "channelId": channel_id,
+                "channelName": q.name,
+                "samples": q.rtt.len(),
+                "users": q.users.len(),
+                "avgRttMs": avg(&q.rtt),
+                "p95RttMs": p95(&mut q.rtt),
+                "avgJitterMs": avg(&q.jitter),
+                "avgPacketLoss": avg(&q.loss),
+                "degraded": degraded,
+            })
+        })
+        .collect();
+    // Worst first
+    result.sort_by(|a, b| {
+        b["degraded"]
+            .as_bool()
+            .cmp(&a["degraded"].as_bool())
+            .then_with(|| b["avgPacketLoss"].as_f64().unwrap_or(0.0).total_cmp(&a["avgPacketLoss"].as_f64().unwrap_or(0.0)))
+    });
+
+    Ok(Json(serde_json::json!({
+        "hours": hours,
+        "thresholds": {
+            "rttMs": DEGRADED_RTT_MS,
+            "jitterMs": DEGRADED_JITTER_MS,
+            "packetLoss": DEGRADED_PACKET_LOSS,
+        },
+        "channels": result,
+    })))
+}
@@ -0,0 +1,270 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::routes::pagination::{self, Cursor, PageQuery};
+use crate::AppState;
+
+/// One stretch of time a user spent in a voice channel. The channel name is
+/// copied in when the session starts, since rooms are deleted once empty.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceSession {
+    pub id: String,
+    pub channel_id: String,
+    pub channel_name: String,
+    pub server_id: Option<String>,
+    pub joined_at: String,
+    /// None while the user is still connected
+    pub left_at: Option<String>,
+    pub duration_secs: Option<i64>,
+    /// Voice-minute rewards paid out during the session
+    pub coins_earned: i64,
+}
+
+#[derive(Deserialize)]
+pub struct VoiceStatsQuery {
+    pub days: Option<i64>,
+}
+
+fn now_rfc3339() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn secs_between(from: &str, to: &str) -> i64 {
+    match (DateTime::parse_from_rfc3339(from), DateTime::parse_from_rfc3339(to)) {
+        (Ok(from), Ok(to)) => to.signed_duration_since(from).num_seconds().max(0),
+        _ => 0,
+    }
+}
+
+/// End the user's open session, timing it up to `ended_at`.
+async fn end_sessions(state: &AppState, user_id: &str, ended_at: &str) {
+    let open = sqlx::query_as::<_, (String, String)>(
+        "SELECT id, joined_at FROM voice_sessions WHERE user_id = ? AND left_at IS NULL",
+    )
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+    for (id, joined_at) in open {
+        let _ = sqlx::query("UPDATE voice_sessions SET left_at = ?, duration_secs = ? WHERE id = ?")
+            .bind(ended_at)
+            .bind(secs_between(&joined_at, ended_at))
+            .bind(&id)
+            .execute(&state.db)
+            .await;
+    }
+}
+
+/// Start a session when a user joins a voice channel, ending the one for
+/// the channel they moved from. Rejoining the same channel keeps the
+/// session going.
+pub(crate) async fn start_voice_session(state: &AppState, user_id: &str, channel_id: &str) {
+    let already_in = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM voice_sessions WHERE user_id = ? AND channel_id = ? AND left_at IS NULL",
+    )
+    .bind(user_id)
+    .bind(channel_id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0);
+    if already_in > 0 {
+        return;
+    }
+
+    let now = now_rfc3339();
+    end_sessions(state, user_id, &now).await;
+    let result = sqlx::query(
+        r#"INSERT INTO voice_sessions (id, user_id, channel_id, channel_name, server_id, joined_at, last_seen_at)
+           SELECT ?, ?, id, name, server_id, ?, ? FROM channels WHERE id = ?"#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(user_id)
+    .bind(&now)
+    .bind(&now)
+    .bind(channel_id)
+    .execute(&state.db)
+    .await;
+    if let Err(e) = result {
+        tracing::warn!("Failed to start voice session: {}", e);
+    }
+}
+
+/// End a user's session when they leave voice or disconnect.
+pub(crate) async fn end_voice_session(state: &AppState, user_id: &str) {
+    end_sessions(state, user_id, &now_rfc3339()).await;
+}
+
+/// Mark a user's open session as still live, crediting any coins a voice
+/// minute just paid. Runs once a minute for everyone in voice.
+pub(crate) async fn touch_voice_session(state: &AppState, user_id: &str, coins: i64) {
+    let _ = sqlx::query(
+        "UPDATE voice_sessions SET last_seen_at = ?, coins_earned = coins_earned + ? WHERE user_id = ? AND left_at IS NULL",
+    )
+    .bind(now_rfc3339())
+    .bind(coins)
+    .bind(user_id)
+    .execute(&state.db)
+    .await;
+}
+
+/// Sessions still open at startup were cut off by a restart; end them at the
+/// last minute they were seen live. Returns how many were closed.
+pub async fn close_orphaned_voice_sessions(state: &AppState) -> u64 {
+    sqlx::query(
+        r#"UPDATE voice_sessions SET
+               left_at = last_seen_at,
+               duration_secs = MAX(0, CAST(ROUND((julianday(last_seen_at) - julianday(joined_at)) * 86400) AS INTEGER))
+           WHERE left_at IS NULL"#,
+    )
+    .execute(&state.db)
+    .await
+    .map(|r| r.rows_affected())
+    .unwrap_or(0)
+}
+
+/// GET /api/users/me/voice-history?cursor=&limit=
+/// The caller's voice sessions, most recent first.
+pub async fn get_voice_history(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(query): Query<PageQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = query.limit();
+    let after = query.after()?;
+
+    let mut sql = String::from(
+        r#"SELECT id, channel_id, channel_name, server_id, joined_at, left_at, duration_secs, coins_earned
+           FROM voice_sessions WHERE user_id = ?"#,
+    );
+    if after.is_some() {
+        sql.push_str(" AND ");
+        sql.push_str(&pagination::after_condition("joined_at", "id", true));
+    }
+    sql.push_str(" ORDER BY joined_at DESC, id DESC LIMIT ?");
+
+    let mut sessions = sqlx::query_as::<_, VoiceSession>(&sql).bind(&user.id);
+    if let Some(after) = &after {
+        sessions = sessions.bind(&after.key).bind(&after.id);
+    }
+    let sessions = sessions.bind(limit + 1).fetch_all(&state.db_read).await?;
+
+    Ok(Json(pagination::page(sessions, limit, |s| Cursor::new(&s.joined_at, &s.id))))
+}
+
+async fn require_server_admin(state: &AppState, user_id: &str, server_id: &str) -> Result<(), ApiError> {
+    let role = sqlx::query_scalar::<_, String>("SELECT role FROM memberships WHERE user_id = ? AND server_id = ?")
+        .bind(user_id)
+        .bind(server_id)
+        .fetch_optional(&state.db)
+        .await?;
+    match role.as_deref() {
+        Some("owner") | Some("admin") => Ok(()),
+        _ => Err(ApiError::forbidden("Insufficient permissions")),
+    }
+}
+
+/// GET /api/servers/:serverId/voice/stats?days=
+/// Voice activity on a server over the last `days` UTC days: totals, a
+/// per-day series and the busiest members and channels. Sessions still in
+/// progress count up to now. Server admins only.
+pub async fn get_server_voice_stats(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(server_id): Path<String>,
+    Query(query): Query<VoiceStatsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_server_admin(&state, &user.id, &server_id).await?;
+
+    let days = query.days.unwrap_or(30).clamp(1, 365);
+    let since = (Utc::now().date_naive() - chrono::Duration::days(days - 1)).to_string();
+    let now = now_rfc3339();
+
+    // Sessions in the window, with open ones timed up to now
+    let sessions = sqlx::query_as::<_, (String, String, String, String, i64)>(
+        r#"SELECT v.user_id, v.channel_id, v.channel_name, v.joined_at,
+               COALESCE(v.duration_secs, MAX(0, CAST(ROUND((julianday(?) - julianday(v.joined_at)) * 86400) AS INTEGER)))
+           FROM voice_sessions v
+           WHERE v.server_id = ? AND v.joined_at >= ?"#,
+    )
+    .bind(&now)
+    .bind(&server_id)
+    .bind(&since)
+    .fetch_all(&state.db_read)
+    .await?;
+
+    let mut per_day: std::collections::BTreeMap<String, (i64, i64)> = std::collections::BTreeMap::new();
+    let mut per_user: std::collections::HashMap<String, (i64, i64)> = std::collections::HashMap::new();
+    let mut per_channel: std::collections::HashMap<String, (String, i64, i64)> = std::collections::HashMap::new();
+    let mut total_secs = 0;
+    for (user_id, channel_id, channel_name, joined_at, secs) in &sessions {
+        total_secs += secs;
+        let day = per_day.entry(joined_at.chars().take(10).collect()).or_default();
+        day.0 += 1;
+        day.1 += secs;
+        let u = per_user.entry(user_id.clone()).or_default();
+        u.0 += 1;
+        u.1 += secs;
+        let c = per_channel.entry(channel_id.clone()).or_insert_with(|| (channel_name.clone(), 0, 0));
+        c.1 += 1;
+        c.2 += secs;
+    }
+
+    let unique_users = per_user.len();
+    let mut top_users: Vec<(String, i64, i64)> = per_user.into_iter().map(|(id, (n, secs))| (id, n, secs)).collect();
+    top_users.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+    top_users.truncate(10);
+    let usernames: std::collections::HashMap<String, String> = if top_users.is_empty() {
+        std::collections::HashMap::new()
+    } else {
+        let placeholders = vec!["?"; top_users.len()].join(",");
+        let sql = format!(r#"SELECT id, username FROM "user" WHERE id IN ({})"#, placeholders);
+        let mut q = sqlx::query_as::<_, (String, String)>(&sql);
+        for (id, _, _) in &top_users {
+            q = q.bind(id);
+        }
+        q.fetch_all(&state.db_read).await?.into_iter().collect()
+    };
+
+    let mut top_channels: Vec<(String, String, i64, i64)> =
+        per_channel.into_iter().map(|(id, (name, n, secs))| (id, name, n, secs)).collect();
+    top_channels.sort_by(|a, b| b.3.cmp(&a.3).then_with(|| a.0.cmp(&b.0)));
+    top_channels.truncate(10);
+
+    Ok(Json(serde_json::json!({
+        "days": days,
+        "sessions": sessions.len(),
+        "totalSecs": total_secs,
+        "uniqueUsers": unique_users,
+        "perDay": per_day
+            .into_iter()
+            .map(|(day, (n, secs))| serde_json::json!({ "day": day, "sessions": n, "secs": secs }))
+            .collect::<Vec<_>>(),
+        "topUsers": top_users
+            .iter()
+            .map(|(id, n, secs)| serde_json::json!({
+                "userId": id,
+                "username": usernames.get(id),
+                "sessions": n,
+                "secs": secs,
+            }))
+            .collect::<Vec<_>>(),
+        "topChannels": top_channels
+            .iter()
+            .map(|(id, name, n, secs)| serde_json::json!({
+                "channelId": id,
+                "channelName": name,
+                "sessions": n,
+                "secs": secs,
+            }))
+            .collect::<Vec<_>>(),
+    })))
+}
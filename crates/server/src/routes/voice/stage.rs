@@ -0,0 +1,123 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::AuthUser;
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+/// Server owners and admins run every stage on the server.
+pub(crate) async fn is_stage_moderator(state: &AppState, user_id: &str, server_id: &str) -> bool {
+    let role = sqlx::query_scalar::<_, String>("SELECT role FROM memberships WHERE user_id = ? AND server_id = ?")
+        .bind(user_id)
+        .bind(server_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten();
+    matches!(role.as_deref(), Some("owner") | Some("admin"))
+}
+
+/// Whether `user_id` may publish audio on the stage: moderators always,
+/// everyone else once brought up as a speaker.
+pub(crate) async fn can_speak(state: &AppState, user_id: &str, channel_id: &str, server_id: &str) -> bool {
+    if is_stage_moderator(state, user_id, server_id).await {
+        return true;
+    }
+    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM stage_speakers WHERE channel_id = ? AND user_id = ?")
+        .bind(channel_id)
+        .bind(user_id)
+        .fetch_one(&state.db)
+        .await
+        .unwrap_or(0)
+        > 0
+}
+
+/// Drop `user_id`'s speaker slot and raised hand once they've left the
+/// stage's call, telling whoever is still in it.
+pub(crate) async fn stage_left(state: &AppState, channel_id: &str, user_id: &str) {
+    let speaker = sqlx::query("DELETE FROM stage_speakers WHERE channel_id = ? AND user_id = ?")
+        .bind(channel_id)
+        .bind(user_id)
+        .execute(&state.db)
+        .await
+        .map(|r| r.rows_affected() > 0)
+        .unwrap_or(false);
+    let hand = sqlx::query("DELETE FROM stage_hands WHERE channel_id = ? AND user_id = ?")
+        .bind(channel_id)
+        .bind(user_id)
+        .execute(&state.db)
+        .await
+        .map(|r| r.rows_affected() > 0)
+        .unwrap_or(false);
+
+    if speaker {
+        let event = ServerEvent::StageSpeakerUpdate {
+            channel_id: channel_id.to_string(),
+            user_id: user_id.to_string(),
+            speaker: false,
+        };
+        state.gateway.broadcast_voice_channel(channel_id, &event, None).await;
+    }
+    if hand {
+        let event = ServerEvent::StageHandUpdate {
+            channel_id: channel_id.to_string(),
+            user_id: user_id.to_string(),
+            raised: false,
+        };
+        state.gateway.broadcast_voice_channel(channel_id, &event, None).await;
+    }
+}
+
+/// GET /api/channels/:channelId/stage
+/// Current speakers and raised hands, oldest first.
+pub async fn get_stage(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(channel_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let channel = sqlx::query_as::<_, (String, String)>("SELECT server_id, type FROM channels WHERE id = ?")
+        .bind(&channel_id)
+        .fetch_optional(&state.db)
+        .await?;
+    let Some((server_id, channel_type)) = channel else {
+        return Err(ApiError::not_found("Channel not found"));
+    };
+    if channel_type != "stage" {
+        return Err(ApiError::bad_request("Not a stage channel"));
+    }
+    let is_member: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM memberships WHERE user_id = ? AND server_id = ?")
+        .bind(&user.id)
+        .bind(&server_id)
+        .fetch_one(&state.db)
+        .await?;
+    if is_member == 0 {
+        return Err(ApiError::forbidden("Not a member of this server"));
+    }
+
+    let speakers = sqlx::query_scalar::<_, String>(
+        "SELECT user_id FROM stage_speakers WHERE channel_id = ? ORDER BY added_at ASC",
+    )
+    .bind(&channel_id)
+    .fetch_all(&state.db_read)
+    .await?;
+    let hands = sqlx::query_as::<_, (String, String)>(
+        "SELECT user_id, raised_at FROM stage_hands WHERE channel_id = ? ORDER BY raised_at ASC",
+    )
+    .bind(&channel_id)
+    .fetch_all(&state.db_read)
+    .await?;
+
+    Ok(Json(serde_json::json!({
+        "speakers": speakers,
+        "raisedHands": hands
+            .into_iter()
+            .map(|(user_id, raised_at)| serde_json::json!({ "userId": user_id, "raisedAt": raised_at }))
+            .collect::<Vec<_>>(),
+        "canModerate": is_stage_moderator(&state, &user.id, &server_id).await,
+    })))
+}
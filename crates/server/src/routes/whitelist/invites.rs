@@ -0,0 +1,134 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use super::require_admin;
+use crate::error::ApiError;
+use crate::models::{AuthUser, CreateInviteRequest, SignupInvite};
+use crate::routes::auth::{random_token, token_hash};
+use crate::AppState;
+
+/// How long an invite stays redeemable unless the admin says otherwise.
+const DEFAULT_INVITE_HOURS: i64 = 7 * 24;
+const MAX_INVITE_HOURS: i64 = 30 * 24;
+const MAX_NOTE_CHARS: usize = 100;
+
+/// Whether an invite with this token hash is unused and unexpired
+pub(crate) async fn invite_is_open(state: &AppState, hash: &str) -> bool {
+    sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM signup_invites WHERE token_hash = ? AND used_at IS NULL AND expires_at > ?",
+    )
+    .bind(hash)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0)
+        > 0
+}
+
+/// Mark an invite used by `user_id`. False if someone else got there first
+/// or it expired in the meantime.
+pub(crate) async fn redeem_invite(
+    conn: &mut sqlx::SqliteConnection,
+    hash: &str,
+    user_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let result = sqlx::query(
+        "UPDATE signup_invites SET used_by = ?, used_at = ? WHERE token_hash = ? AND used_at IS NULL AND expires_at > ?",
+    )
+    .bind(user_id)
+    .bind(&now)
+    .bind(hash)
+    .bind(&now)
+    .execute(conn)
+    .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+/// GET /api/whitelist/invites — newest first, used ones included
+pub async fn list_invites(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    require_admin(&state, &user.id).await?;
+
+    let invites = sqlx::query_as::<_, SignupInvite>(
+        r#"SELECT id, note, created_by, created_at, expires_at, used_by, used_at
+           FROM signup_invites ORDER BY created_at DESC, id DESC"#,
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(invites))
+}
+
+/// POST /api/whitelist/invites — the token is in this response and nowhere else
+pub async fn create_invite(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<CreateInviteRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_admin(&state, &user.id).await?;
+
+    let hours = body.expires_in_hours.unwrap_or(DEFAULT_INVITE_HOURS);
+    if !(1..=MAX_INVITE_HOURS).contains(&hours) {
+        return Err(ApiError::bad_request(format!(
+            "Invites must expire within 1 to {} hours",
+            MAX_INVITE_HOURS
+        )));
+    }
+    let note = body.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    if note.is_some_and(|n| n.chars().count() > MAX_NOTE_CHARS) {
+        return Err(ApiError::bad_request(format!("Note must be at most {} characters", MAX_NOTE_CHARS)));
+    }
+
+    let token = random_token();
+    let now = chrono::Utc::now();
+    let invite = SignupInvite {
+        id: uuid::Uuid::new_v4().to_string(),
+        note: note.map(str::to_string),
+        created_by: user.id.clone(),
+        created_at: now.to_rfc3339(),
+        expires_at: (now + chrono::Duration::hours(hours)).to_rfc3339(),
+        used_by: None,
+        used_at: None,
+    };
+
+    sqlx::query(
+        r#"INSERT INTO signup_invites (id, token_hash, note, created_by, created_at, expires_at)
+           VALUES (?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(&invite.id)
+    .bind(token_hash(&state.config.auth_secret, &token))
+    .bind(&invite.note)
+    .bind(&invite.created_by)
+    .bind(&invite.created_at)
+    .bind(&invite.expires_at)
+    .execute(&state.db)
+    .await?;
+
+    let mut body = serde_json::to_value(&invite).map_err(|_| ApiError::internal("Failed to create invite"))?;
+    body["token"] = token.into();
+    Ok((StatusCode::CREATED, Json(body)))
+}
+
+/// DELETE /api/whitelist/invites/:id — revoke an invite
+pub async fn revoke_invite(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_admin(&state, &user.id).await?;
+
+    sqlx::query("DELETE FROM signup_invites WHERE id = ?")
+        .bind(&id)
+        .execute(&state.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
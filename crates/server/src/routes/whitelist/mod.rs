@@ -0,0 +1,173 @@
+mod invites;
+mod requests;
+
+pub use invites::*;
+pub use requests::*;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::{AddWhitelistRequest, AuthUser, WhitelistEntry};
+use crate::routes::pagination::{self, Cursor, PageQuery};
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+/// Check if the caller is an admin or owner of the default server
+pub(crate) async fn require_admin(state: &AppState, user_id: &str) -> Result<(), ApiError> {
+    let role = sqlx::query_scalar::<_, String>(
+        "SELECT m.role FROM memberships m INNER JOIN servers s ON s.id = m.server_id WHERE m.user_id = ? ORDER BY s.created_at ASC LIMIT 1",
+    )
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    match role.as_deref() {
+        Some("owner") | Some("admin") => Ok(()),
+        _ => Err(ApiError::forbidden("Insufficient permissions")),
+    }
+}
+
+/// Send an event to everyone who can manage the whitelist
+pub(super) async fn notify_admins(state: &AppState, event: &ServerEvent) {
+    let admin_ids: Vec<String> = sqlx::query_scalar(
+        r#"SELECT user_id FROM memberships
+           WHERE role IN ('owner', 'admin')
+             AND server_id = (SELECT id FROM servers ORDER BY created_at ASC LIMIT 1)"#,
+    )
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    for uid in &admin_ids {
+        state.gateway.send_to_user(uid, event).await;
+    }
+}
+
+/// A plain address, or `*@domain` to let in everyone at a domain.
+/// Returns the entry normalized, or None if it's neither.
+fn normalize_entry(entry: &str) -> Option<String> {
+    let entry = entry.trim().to_lowercase();
+    let (local, domain) = entry.split_once('@')?;
+    let valid_domain = domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !domain.contains(['@', '*'])
+        && !domain.chars().any(char::is_whitespace);
+    let valid_local = local == "*" || (!local.is_empty() && !local.contains('*') && !local.chars().any(char::is_whitespace));
+    (valid_domain && valid_local).then_some(entry)
+}
+
+/// Whether an exact entry or a domain wildcard covers this (lowercased) email
+pub(crate) async fn is_whitelisted(state: &AppState, email: &str) -> bool {
+    let Some((_, domain)) = email.split_once('@') else {
+        return false;
+    };
+    sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM email_whitelist WHERE email = ? OR email = ?"#)
+        .bind(email)
+        .bind(format!("*@{}", domain))
+        .fetch_one(&state.db)
+        .await
+        .unwrap_or(0)
+        > 0
+}
+
+/// GET /api/whitelist?cursor=&limit=
+/// Most recently added first.
+pub async fn list_whitelist(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(query): Query<PageQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_admin(&state, &user.id).await?;
+
+    let limit = query.limit();
+    let after = query.after()?;
+
+    let mut sql = String::from("SELECT id, email, added_by, added_at FROM email_whitelist");
+    if after.is_some() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&pagination::after_condition("added_at", "id", true));
+    }
+    sql.push_str(" ORDER BY added_at DESC, id DESC LIMIT ?");
+
+    let mut entries = sqlx::query_as::<_, WhitelistEntry>(&sql);
+    if let Some(after) = &after {
+        entries = entries.bind(&after.key).bind(&after.id);
+    }
+    let entries = entries.bind(limit + 1).fetch_all(&state.db).await?;
+
+    Ok(Json(pagination::page(entries, limit, |e| Cursor::new(&e.added_at, &e.id))))
+}
+
+/// POST /api/whitelist
+/// Entries are addresses or `*@domain` wildcards.
+pub async fn add_to_whitelist(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<AddWhitelistRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_admin(&state, &user.id).await?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut added = Vec::new();
+
+    let mut emails = Vec::new();
+    for email in &body.emails {
+        if email.trim().is_empty() {
+            continue;
+        }
+        match normalize_entry(email) {
+            Some(email) => emails.push(email),
+            None => return Err(ApiError::bad_request(format!("Invalid whitelist entry: {}", email.trim()))),
+        }
+    }
+
+    for email in emails {
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let result = sqlx::query(
+            r#"INSERT OR IGNORE INTO email_whitelist (id, email, added_by, added_at) VALUES (?, ?, ?, ?)"#,
+        )
+        .bind(&id)
+        .bind(&email)
+        .bind(&user.id)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            added.push(WhitelistEntry {
+                id,
+                email,
+                added_by: user.id.clone(),
+                added_at: now.clone(),
+            });
+        }
+    }
+
+    Ok((StatusCode::CREATED, Json(added)))
+}
+
+/// DELETE /api/whitelist/:id
+pub async fn remove_from_whitelist(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_admin(&state, &user.id).await?;
+
+    sqlx::query("DELETE FROM email_whitelist WHERE id = ?")
+        .bind(&id)
+        .execute(&state.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
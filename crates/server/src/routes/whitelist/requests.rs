@@ -0,0 +1,172 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use minijinja::context;
+use std::sync::Arc;
+
+use super::{normalize_entry, notify_admins, require_admin};
+use crate::error::ApiError;
+use crate::models::{AccessRequest, AuthUser, RequestAccessRequest};
+use crate::ws::events::ServerEvent;
+use crate::AppState;
+
+const MAX_REASON_CHARS: usize = 500;
+/// Past this many pending requests new ones are dropped, so an open endpoint
+/// can't grow the queue without bound.
+const MAX_PENDING_REQUESTS: i64 = 500;
+
+/// POST /api/auth/request-access — ask to be whitelisted (no account needed).
+/// Always 202 for a well-formed email, whether or not it was queued, so the
+/// endpoint doesn't reveal who is already known.
+pub async fn request_access(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<RequestAccessRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let email = match normalize_entry(&body.email) {
+        Some(email) if !email.starts_with("*@") => email,
+        _ => return Err(ApiError::bad_request("Invalid email")),
+    };
+    let reason = body.reason.as_deref().unwrap_or("").trim();
+    if reason.chars().count() > MAX_REASON_CHARS {
+        return Err(ApiError::bad_request(format!("Reason must be at most {} characters", MAX_REASON_CHARS)));
+    }
+
+    let pending: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM access_requests WHERE status = 'pending'")
+        .fetch_one(&state.db)
+        .await?;
+    if pending >= MAX_PENDING_REQUESTS {
+        tracing::warn!("Access request queue is full, dropping request");
+        return Ok(StatusCode::ACCEPTED);
+    }
+
+    let known = sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "user" WHERE email = ?"#)
+        .bind(&email)
+        .fetch_one(&state.db)
+        .await?
+        > 0;
+    if known || super::is_whitelisted(&state, &email).await {
+        return Ok(StatusCode::ACCEPTED);
+    }
+
+    // A second request from the same email, pending or denied, is ignored
+    let request = AccessRequest {
+        id: uuid::Uuid::new_v4().to_string(),
+        email,
+        reason: reason.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let inserted = sqlx::query(
+        "INSERT OR IGNORE INTO access_requests (id, email, reason, created_at) VALUES (?, ?, ?, ?)",
+    )
+    .bind(&request.id)
+    .bind(&request.email)
+    .bind(&request.reason)
+    .bind(&request.created_at)
+    .execute(&state.db)
+    .await?
+    .rows_affected()
+        > 0;
+
+    if inserted {
+        notify_admins(&state, &ServerEvent::AccessRequested { request }).await;
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// GET /api/whitelist/requests — pending requests, oldest first
+pub async fn list_access_requests(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Result<impl IntoResponse, ApiError> {
+    require_admin(&state, &user.id).await?;
+
+    let requests = sqlx::query_as::<_, AccessRequest>(
+        r#"SELECT id, email, reason, created_at FROM access_requests
+           WHERE status = 'pending' ORDER BY created_at ASC, id ASC"#,
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(requests))
+}
+
+/// Close a pending request; returns its email
+async fn resolve(
+    conn: &mut sqlx::SqliteConnection,
+    admin_id: &str,
+    id: &str,
+    status: &str,
+) -> Result<String, ApiError> {
+    sqlx::query_scalar::<_, String>(
+        r#"UPDATE access_requests SET status = ?, resolved_by = ?, resolved_at = ?
+           WHERE id = ? AND status = 'pending' RETURNING email"#,
+    )
+    .bind(status)
+    .bind(admin_id)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Access request not found"))
+}
+
+async fn notify_resolved(state: &AppState, id: &str, approved: bool) {
+    notify_admins(
+        state,
+        &ServerEvent::AccessRequestResolved {
+            request_id: id.to_string(),
+            approved,
+        },
+    )
+    .await;
+}
+
+/// POST /api/whitelist/requests/:id/approve — whitelist the email and tell them
+pub async fn approve_access_request(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_admin(&state, &user.id).await?;
+
+    let mut tx = state.db.begin().await?;
+    let email = resolve(&mut tx, &user.id, &id, "approved").await?;
+    sqlx::query(
+        r#"INSERT OR IGNORE INTO email_whitelist (id, email, added_by, added_at) VALUES (?, ?, ?, ?)"#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(&email)
+    .bind(&user.id)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    notify_resolved(&state, &id, true).await;
+
+    if crate::mailer::enabled(&state.config) {
+        let ctx = context! { url => state.config.public_url.trim_end_matches('/') };
+        match crate::mailer::render("access_approved", &email, "Your access request was approved", ctx) {
+            Ok(email) => crate::mailer::queue(&state, email).await,
+            Err(e) => tracing::error!("Failed to render access approval email: {}", e),
+        }
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/whitelist/requests/:id/deny
+pub async fn deny_access_request(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_admin(&state, &user.id).await?;
+
+    resolve(&mut *state.db.acquire().await?, &user.id, &id, "denied").await?;
+    notify_resolved(&state, &id, false).await;
+    Ok(StatusCode::NO_CONTENT)
+}
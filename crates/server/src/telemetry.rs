@@ -0,0 +1,104 @@
+use axum::http::HeaderMap;
+use opentelemetry::propagation::{Extractor, TextMapPropagator};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing::{Level, Subscriber};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use crate::config::Config;
+
+/// Set up logging, plus OTLP trace export when an endpoint is configured.
+/// Keep the returned provider alive and shut it down on exit so the last
+/// batch of spans is flushed.
+pub fn init(config: &Config) -> Option<SdkTracerProvider> {
+    let fmt = tracing_subscriber::fmt::layer().with_filter(
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| "flux_server=info".into()),
+    );
+
+    let provider = if config.otlp_endpoint.is_empty() {
+        None
+    } else {
+        match tracer_provider(&config.otlp_endpoint, &config.otel_service_name) {
+            Ok(provider) => Some(provider),
+            Err(e) => {
+                eprintln!("OTLP export disabled: {}", e);
+                None
+            }
+        }
+    };
+
+    tracing_subscriber::registry()
+        .with(fmt)
+        .with(provider.as_ref().map(layer))
+        .init();
+
+    if provider.is_some() {
+        tracing::info!(endpoint = %config.otlp_endpoint, "Exporting traces over OTLP");
+    }
+    provider
+}
+
+/// A batching OTLP/HTTP exporter. `endpoint` is the collector's base URL,
+/// as in `OTEL_EXPORTER_OTLP_ENDPOINT`; `/v1/traces` is added if missing.
+pub fn tracer_provider(
+    endpoint: &str,
+    service_name: &str,
+) -> Result<SdkTracerProvider, opentelemetry_otlp::ExporterBuildError> {
+    let endpoint = endpoint.trim_end_matches('/');
+    let endpoint = if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{}/v1/traces", endpoint)
+    };
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()?;
+
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(service_name.to_string()).build())
+        .build())
+}
+
+/// The layer that turns our spans into OTLP spans. Alongside the server's
+/// own spans it takes sqlx's per-statement events, which land on the
+/// enclosing request or gateway span with their SQL and timing.
+pub fn layer<S>(provider: &SdkTracerProvider) -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    tracing_opentelemetry::layer()
+        .with_tracer(provider.tracer("flux-server"))
+        .with_filter(
+            Targets::new()
+                .with_target("flux_server", Level::INFO)
+                .with_target("sqlx::query", Level::DEBUG),
+        )
+}
+
+/// The trace context a caller sent in `traceparent`, if any, so our spans
+/// join its trace instead of starting a new one.
+pub fn remote_context(headers: &HeaderMap) -> opentelemetry::Context {
+    TraceContextPropagator::new().extract(&HeaderExtractor(headers))
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|v| v.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|k| k.as_str()).collect()
+    }
+}
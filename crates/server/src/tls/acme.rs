@@ -0,0 +1,412 @@
+//! Certificates from an ACME CA such as Let's Encrypt (RFC 8555). The account
+//! key and the issued certificate are kept in `acme_dir`, and the certificate
+//! is renewed a month before it expires.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ring::rand::SystemRandom;
+use ring::signature::{EcdsaKeyPair, KeyPair as _, ECDSA_P256_SHA256_FIXED_SIGNING};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio_rustls::rustls::{
+    crypto::CryptoProvider,
+    pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer},
+    sign::CertifiedKey,
+};
+
+use super::Certificates;
+use crate::config::Config;
+
+/// ALPN protocol the CA uses to fetch TLS-ALPN-01 challenge certificates.
+pub const ACME_TLS_ALPN: &[u8] = b"acme-tls/1";
+
+/// Renew once the certificate has less than this long left.
+const RENEW_BEFORE: Duration = Duration::from_secs(30 * 24 * 3600);
+/// How often the certificate's expiry is checked.
+const CHECK_INTERVAL: Duration = Duration::from_secs(12 * 3600);
+/// How long to wait after a failed attempt before trying again.
+const RETRY_INTERVAL: Duration = Duration::from_secs(3600);
+/// How long to wait for the CA to validate a challenge or issue the certificate.
+const POLL_ATTEMPTS: u32 = 30;
+const POLL_DELAY: Duration = Duration::from_secs(2);
+
+/// Whether the server should get its certificate from an ACME CA.
+pub fn enabled(config: &Config) -> bool {
+    !config.acme_domain.is_empty()
+}
+
+/// Where the certificate chain and its key for `acme_domain` are kept.
+pub fn cert_paths(config: &Config) -> (String, String) {
+    let dir = Path::new(&config.acme_dir);
+    let path = |ext: &str| {
+        dir.join(format!("{}.{}", config.acme_domain, ext))
+            .to_string_lossy()
+            .into_owned()
+    };
+    (path("crt"), path("key"))
+}
+
+/// Answers to challenges the CA is currently checking.
+#[derive(Debug, Default)]
+pub struct Challenges {
+    /// Self-signed certificates for TLS-ALPN-01, by domain
+    tls_alpn: RwLock<HashMap<String, Arc<CertifiedKey>>>,
+    /// Key authorizations for HTTP-01, by token
+    http: RwLock<HashMap<String, String>>,
+}
+
+impl Challenges {
+    /// The key authorization to answer an HTTP-01 request for `token` with.
+    pub fn http(&self, token: &str) -> Option<String> {
+        self.http.read().unwrap_or_else(|e| e.into_inner()).get(token).cloned()
+    }
+
+    pub(super) fn tls_alpn(&self, domain: &str) -> Option<Arc<CertifiedKey>> {
+        self.tls_alpn.read().unwrap_or_else(|e| e.into_inner()).get(domain).cloned()
+    }
+
+    fn clear(&self) {
+        self.tls_alpn.write().unwrap_or_else(|e| e.into_inner()).clear();
+        self.http.write().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+/// Whether the stored certificate is missing, unreadable or close to expiring.
+pub fn renewal_due(config: &Config) -> bool {
+    let (cert_path, _) = cert_paths(config);
+    let Ok(der) = CertificateDer::from_pem_file(&cert_path) else {
+        return true;
+    };
+    let Ok((_, cert)) = x509_parser::parse_x509_certificate(&der) else {
+        return true;
+    };
+    let renew_at = cert.validity().not_after.timestamp() - RENEW_BEFORE.as_secs() as i64;
+    chrono::Utc::now().timestamp() >= renew_at
+}
+
+/// Background task: get a certificate if there isn't one yet, and renew it
+/// when it's close to expiring, forever.
+pub async fn run_acme(config: Config, certs: Arc<Certificates>) {
+    loop {
+        let wait = if !renewal_due(&config) {
+            CHECK_INTERVAL
+        } else {
+            match provision(&config, &certs).await {
+                Ok(()) => {
+                    tracing::info!("Got a certificate for {} from {}", config.acme_domain, config.acme_directory_url);
+                    CHECK_INTERVAL
+                }
+                Err(e) => {
+                    tracing::error!("Failed to get a certificate for {}: {}", config.acme_domain, e);
+                    RETRY_INTERVAL
+                }
+            }
+        };
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Order a new certificate for `acme_domain`, store it and start serving it.
+pub async fn provision(config: &Config, certs: &Certificates) -> Result<(), String> {
+    std::fs::create_dir_all(&config.acme_dir).map_err(|e| format!("{}: {}", config.acme_dir, e))?;
+    let mut client = Client::new(config).await?;
+    client.register(&config.acme_email).await?;
+
+    let new_order = client.directory.new_order.clone();
+    let identifiers = json!({ "identifiers": [{ "type": "dns", "value": config.acme_domain }] });
+    let res = client.post(&new_order, Some(&identifiers)).await?;
+    let order_url = location(&res).ok_or("The CA didn't say where the order is")?;
+    let order: Order = res.json().await.map_err(|e| e.to_string())?;
+
+    let authorized = authorize(&mut client, config, certs, &order).await;
+    certs.challenges.clear();
+    authorized?;
+
+    let key = rcgen::KeyPair::generate().map_err(|e| e.to_string())?;
+    let csr = rcgen::CertificateParams::new(vec![config.acme_domain.clone()])
+        .and_then(|params| params.serialize_request(&key))
+        .map_err(|e| e.to_string())?;
+    client.post(&order.finalize, Some(&json!({ "csr": b64(csr.der()) }))).await?;
+    let order: Order = client.wait(&order_url).await?;
+    let certificate_url = match (order.status.as_str(), order.certificate) {
+        ("valid", Some(url)) => url,
+        (status, _) => return Err(format!("Order ended up {}", status)),
+    };
+    let chain = client
+        .post(&certificate_url, None)
+        .await?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let (cert_path, key_path) = cert_paths(config);
+    write_private(Path::new(&key_path), &key.serialize_pem())?;
+    std::fs::write(&cert_path, chain).map_err(|e| format!("{}: {}", cert_path, e))?;
+    certs.reload()
+}
+
+/// Set up answers to each of the order's challenges and wait for the CA to
+/// check them.
+async fn authorize(client: &mut Client, config: &Config, certs: &Certificates, order: &Order) -> Result<(), String> {
+    let thumbprint = client.thumbprint();
+    for url in &order.authorizations {
+        let authz: Authorization = client.post_json(url).await?;
+        if authz.status == "valid" {
+            continue;
+        }
+        let domain = authz.identifier.value;
+        let challenge = authz
+            .challenges
+            .into_iter()
+            .find(|c| c.kind == config.acme_challenge)
+            .ok_or_else(|| format!("The CA doesn't offer {} for {}", config.acme_challenge, domain))?;
+
+        let key_authorization = format!("{}.{}", challenge.token, thumbprint);
+        if challenge.kind == "http-01" {
+            let mut http = certs.challenges.http.write().unwrap_or_else(|e| e.into_inner());
+            http.insert(challenge.token, key_authorization);
+        } else {
+            let cert = challenge_cert(&domain, &key_authorization, &certs.provider)?;
+            let mut tls_alpn = certs.challenges.tls_alpn.write().unwrap_or_else(|e| e.into_inner());
+            tls_alpn.insert(domain.clone(), Arc::new(cert));
+        }
+
+        client.post(&challenge.url, Some(&json!({}))).await?;
+        let authz: Authorization = client.wait(url).await?;
+        if authz.status != "valid" {
+            return Err(format!("{} wasn't validated ({})", domain, authz.status));
+        }
+    }
+    Ok(())
+}
+
+/// The self-signed certificate that proves control of `domain` for
+/// TLS-ALPN-01 (RFC 8737).
+fn challenge_cert(domain: &str, key_authorization: &str, provider: &CryptoProvider) -> Result<CertifiedKey, String> {
+    let key = rcgen::KeyPair::generate().map_err(|e| e.to_string())?;
+    let mut params = rcgen::CertificateParams::new(vec![domain.to_string()]).map_err(|e| e.to_string())?;
+    params.custom_extensions = vec![rcgen::CustomExtension::new_acme_identifier(&Sha256::digest(key_authorization))];
+    let cert = params.self_signed(&key).map_err(|e| e.to_string())?;
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.serialize_der()));
+    CertifiedKey::from_der(vec![cert.der().clone()], key, provider).map_err(|e| e.to_string())
+}
+
+/// Write a file only the server's user can read.
+fn write_private(path: &Path, contents: &str) -> Result<(), String> {
+    std::fs::write(path, contents).map_err(|e| format!("{}: {}", path.display(), e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// The account key, created on first use.
+fn account_key(config: &Config) -> Result<EcdsaKeyPair, String> {
+    let path = Path::new(&config.acme_dir).join("account.key");
+    let pem = match std::fs::read_to_string(&path) {
+        Ok(pem) => pem,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let pem = rcgen::KeyPair::generate().map_err(|e| e.to_string())?.serialize_pem();
+            write_private(&path, &pem)?;
+            pem
+        }
+        Err(e) => return Err(format!("{}: {}", path.display(), e)),
+    };
+    let der = PrivatePkcs8KeyDer::from_pem_slice(pem.as_bytes()).map_err(|e| format!("{}: {}", path.display(), e))?;
+    EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, der.secret_pkcs8_der(), &SystemRandom::new())
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn b64(bytes: impl AsRef<[u8]>) -> String {
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn location(res: &reqwest::Response) -> Option<String> {
+    res.headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Directory {
+    new_nonce: String,
+    new_account: String,
+    new_order: String,
+}
+
+#[derive(Deserialize)]
+struct Order {
+    status: String,
+    #[serde(default)]
+    authorizations: Vec<String>,
+    finalize: String,
+    certificate: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Authorization {
+    status: String,
+    identifier: Identifier,
+    #[serde(default)]
+    challenges: Vec<Challenge>,
+}
+
+#[derive(Deserialize)]
+struct Identifier {
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct Challenge {
+    #[serde(rename = "type")]
+    kind: String,
+    url: String,
+    #[serde(default)]
+    token: String,
+}
+
+/// Signed requests to the CA on behalf of one account.
+struct Client {
+    http: reqwest::Client,
+    directory: Directory,
+    key: EcdsaKeyPair,
+    rng: SystemRandom,
+    /// The account URL, once registered; until then requests carry the key
+    kid: Option<String>,
+    nonce: Option<String>,
+}
+
+impl Client {
+    async fn new(config: &Config) -> Result<Self, String> {
+        let key = account_key(config)?;
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| e.to_string())?;
+        let directory = http
+            .get(&config.acme_directory_url)
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| format!("{}: {}", config.acme_directory_url, e))?;
+        Ok(Self { http, directory, key, rng: SystemRandom::new(), kid: None, nonce: None })
+    }
+
+    fn jwk(&self) -> Value {
+        // Uncompressed point: 0x04, then x and y
+        let point = self.key.public_key().as_ref();
+        json!({ "crv": "P-256", "kty": "EC", "x": b64(&point[1..33]), "y": b64(&point[33..]) })
+    }
+
+    /// RFC 7638 thumbprint of the account key, which key authorizations end in.
+    fn thumbprint(&self) -> String {
+        let jwk = self.jwk();
+        // Required members only, in lexicographic order, without whitespace
+        let canonical = format!(
+            r#"{{"crv":"P-256","kty":"EC","x":"{}","y":"{}"}}"#,
+            jwk["x"].as_str().unwrap_or_default(),
+            jwk["y"].as_str().unwrap_or_default()
+        );
+        b64(Sha256::digest(canonical))
+    }
+
+    async fn register(&mut self, email: &str) -> Result<(), String> {
+        let mut account = json!({ "termsOfServiceAgreed": true });
+        if !email.is_empty() {
+            account["contact"] = json!([format!("mailto:{}", email)]);
+        }
+        let new_account = self.directory.new_account.clone();
+        let res = self.post(&new_account, Some(&account)).await?;
+        self.kid = Some(location(&res).ok_or("The CA didn't return an account URL")?);
+        Ok(())
+    }
+
+    async fn nonce(&mut self) -> Result<String, String> {
+        if let Some(nonce) = self.nonce.take() {
+            return Ok(nonce);
+        }
+        let res = self
+            .http
+            .head(&self.directory.new_nonce)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        replay_nonce(&res).ok_or_else(|| "The CA didn't return a nonce".into())
+    }
+
+    /// A signed POST, or POST-as-GET when there's no payload. A stale nonce
+    /// is retried once with the fresh one the CA sent back.
+    async fn post(&mut self, url: &str, payload: Option<&Value>) -> Result<reqwest::Response, String> {
+        let payload = payload.map(|p| b64(p.to_string())).unwrap_or_default();
+        let mut retried = false;
+        loop {
+            let mut protected = json!({ "alg": "ES256", "nonce": self.nonce().await?, "url": url });
+            match &self.kid {
+                Some(kid) => protected["kid"] = json!(kid),
+                None => protected["jwk"] = self.jwk(),
+            }
+            let protected = b64(protected.to_string());
+            let signature = self
+                .key
+                .sign(&self.rng, format!("{}.{}", protected, payload).as_bytes())
+                .map_err(|_| "Failed to sign the request")?;
+            let body = json!({ "protected": protected, "payload": payload, "signature": b64(signature) });
+
+            let res = self
+                .http
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/jose+json")
+                .body(body.to_string())
+                .send()
+                .await
+                .map_err(|e| format!("{}: {}", url, e))?;
+            self.nonce = replay_nonce(&res);
+            if res.status().is_success() {
+                return Ok(res);
+            }
+
+            let status = res.status();
+            let problem: Value = res.json().await.unwrap_or_default();
+            if problem["type"] == "urn:ietf:params:acme:error:badNonce" && !retried {
+                retried = true;
+                continue;
+            }
+            return Err(format!("{} returned {}: {}", url, status, problem["detail"].as_str().unwrap_or("no detail")));
+        }
+    }
+
+    async fn post_json<T: DeserializeOwned>(&mut self, url: &str) -> Result<T, String> {
+        self.post(url, None).await?.json().await.map_err(|e| format!("{}: {}", url, e))
+    }
+
+    /// Fetch an order or authorization until the CA has finished with it.
+    async fn wait<T: DeserializeOwned>(&mut self, url: &str) -> Result<T, String> {
+        for _ in 0..POLL_ATTEMPTS {
+            let value: Value = self.post_json(url).await?;
+            if !matches!(value["status"].as_str(), Some("pending" | "processing")) {
+                return serde_json::from_value(value).map_err(|e| format!("{}: {}", url, e));
+            }
+            tokio::time::sleep(POLL_DELAY).await;
+        }
+        Err(format!("Gave up waiting on {}", url))
+    }
+}
+
+fn replay_nonce(res: &reqwest::Response) -> Option<String> {
+    res.headers()
+        .get("replay-nonce")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from)
+}
@@ -0,0 +1,273 @@
+//! HTTPS without a reverse proxy: rustls in front of the router, certificates
+//! reloaded on SIGHUP or when their files change, and an optional plain HTTP
+//! listener that sends everyone over to HTTPS. The certificate can come from
+//! files or from an ACME CA (see [`acme`]).
+
+pub mod acme;
+
+use axum::{
+    extract::{connect_info::Connected, Path},
+    http::{header, HeaderMap, StatusCode, Uri},
+    response::{IntoResponse, Redirect, Response},
+    serve::{IncomingStream, Listener},
+    routing::get,
+    Router,
+};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::rustls::{
+    self,
+    crypto::CryptoProvider,
+    pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+    server::{ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+};
+use tokio_rustls::{server::TlsStream, TlsAcceptor};
+
+use crate::config::Config;
+use crate::middleware::ip_filter::PeerAddr;
+use acme::{Challenges, ACME_TLS_ALPN};
+
+/// Connections that haven't finished their handshake by now are dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often the certificate files are checked for changes.
+const CERT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Whether the server should terminate TLS itself.
+pub fn enabled(config: &Config) -> bool {
+    !config.tls_cert_path.is_empty() || acme::enabled(config)
+}
+
+/// The certificate being served, swapped out in place on reload. With ACME
+/// there may be none until the first one is issued.
+#[derive(Debug)]
+pub struct Certificates {
+    cert_path: String,
+    key_path: String,
+    provider: Arc<CryptoProvider>,
+    current: RwLock<Option<Arc<CertifiedKey>>>,
+    challenges: Arc<Challenges>,
+}
+
+/// A certificate chain and the private key that goes with it.
+fn load_certificate(cert_path: &str, key_path: &str, provider: &CryptoProvider) -> Result<CertifiedKey, String> {
+    let chain = CertificateDer::pem_file_iter(cert_path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("{}: {}", cert_path, e))?;
+    if chain.is_empty() {
+        return Err(format!("{}: no certificates found", cert_path));
+    }
+    let key = PrivateKeyDer::from_pem_file(key_path).map_err(|e| format!("{}: {}", key_path, e))?;
+    CertifiedKey::from_der(chain, key, provider).map_err(|e| format!("{} doesn't fit {}: {}", key_path, cert_path, e))
+}
+
+impl Certificates {
+    /// Read the files again. On failure the old certificate stays in use.
+    pub fn reload(&self) -> Result<(), String> {
+        let key = load_certificate(&self.cert_path, &self.key_path, &self.provider)?;
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(key));
+        Ok(())
+    }
+
+    /// Pending ACME challenges, for the HTTP redirect listener to answer.
+    pub fn challenges(&self) -> Arc<Challenges> {
+        self.challenges.clone()
+    }
+
+    fn modified(&self) -> Option<(SystemTime, SystemTime)> {
+        let mtime = |path: &str| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        Some((mtime(&self.cert_path)?, mtime(&self.key_path)?))
+    }
+}
+
+impl ResolvesServerCert for Certificates {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        if client_hello.alpn().is_some_and(|mut protocols| protocols.any(|p| p == ACME_TLS_ALPN)) {
+            return self.challenges.tls_alpn(client_hello.server_name()?);
+        }
+        self.current.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// A TLS acceptor for the configured certificate and key, and a handle to
+/// reload them with. With ACME, a certificate that hasn't been issued yet
+/// isn't an error; [`acme::run_acme`] fills it in.
+pub fn acceptor(config: &Config) -> Result<(TlsAcceptor, Arc<Certificates>), String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let (cert_path, key_path) = if acme::enabled(config) {
+        acme::cert_paths(config)
+    } else {
+        (config.tls_cert_path.clone(), config.tls_key_path.clone())
+    };
+    let current = match load_certificate(&cert_path, &key_path, &provider) {
+        Ok(key) => Some(Arc::new(key)),
+        Err(_) if acme::enabled(config) && !std::path::Path::new(&cert_path).exists() => None,
+        Err(e) => return Err(e),
+    };
+    let certs = Arc::new(Certificates {
+        cert_path,
+        key_path,
+        provider: provider.clone(),
+        current: RwLock::new(current),
+        challenges: Arc::default(),
+    });
+
+    let mut server = rustls::ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .with_no_client_auth()
+        .with_cert_resolver(certs.clone());
+    server.alpn_protocols = vec![b"http/1.1".to_vec()];
+    if config.acme_challenge == "tls-alpn-01" && acme::enabled(config) {
+        server.alpn_protocols.push(ACME_TLS_ALPN.to_vec());
+    }
+    Ok((TlsAcceptor::from(Arc::new(server)), certs))
+}
+
+/// Background task: reload the certificate on SIGHUP, or when either file
+/// changes (e.g. after a renewal), forever.
+pub async fn run_cert_reloader(certs: Arc<Certificates>) {
+    let mut interval = tokio::time::interval(CERT_POLL_INTERVAL);
+    let mut seen = certs.modified();
+    #[cfg(unix)]
+    let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()).ok();
+
+    loop {
+        #[cfg(unix)]
+        let signalled = tokio::select! {
+            _ = interval.tick() => false,
+            Some(_) = async { hangup.as_mut()?.recv().await } => true,
+        };
+        #[cfg(not(unix))]
+        let signalled = {
+            interval.tick().await;
+            false
+        };
+
+        let modified = certs.modified();
+        if !signalled && modified == seen {
+            continue;
+        }
+        seen = modified;
+        match certs.reload() {
+            Ok(()) => tracing::info!("Reloaded TLS certificate from {}", certs.cert_path),
+            Err(e) => tracing::error!("Failed to reload TLS certificate, keeping the old one: {}", e),
+        }
+    }
+}
+
+/// Accepts TCP connections and completes their TLS handshakes in the
+/// background, so one slow client doesn't hold up the rest.
+pub struct TlsListener {
+    handshaken: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+    local_addr: SocketAddr,
+}
+
+impl TlsListener {
+    pub fn new(tcp: TcpListener, acceptor: TlsAcceptor) -> io::Result<Self> {
+        let local_addr = tcp.local_addr()?;
+        let (tx, handshaken) = mpsc::channel(64);
+        tokio::spawn(async move {
+            while !tx.is_closed() {
+                let (stream, addr) = match tcp.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        // Usually out of file descriptors; give some a chance to close
+                        tracing::warn!("Failed to accept connection: {}", e);
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        continue;
+                    }
+                };
+                let (acceptor, tx) = (acceptor.clone(), tx.clone());
+                tokio::spawn(async move {
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        // The CA only wanted to see the challenge certificate
+                        Ok(Ok(tls)) if tls.get_ref().1.alpn_protocol() == Some(ACME_TLS_ALPN) => {}
+                        Ok(Ok(tls)) => {
+                            let _ = tx.send((tls, addr)).await;
+                        }
+                        Ok(Err(e)) => tracing::debug!("TLS handshake with {} failed: {}", addr, e),
+                        Err(_) => tracing::debug!("TLS handshake with {} timed out", addr),
+                    }
+                });
+            }
+        });
+        Ok(Self { handshaken, local_addr })
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.handshaken.recv().await {
+            Some(conn) => conn,
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+impl Connected<IncomingStream<'_, TlsListener>> for PeerAddr {
+    fn connect_info(stream: IncomingStream<'_, TlsListener>) -> Self {
+        PeerAddr(Some(*stream.remote_addr()))
+    }
+}
+
+/// Plain HTTP app that permanently redirects every request to the same URL
+/// on HTTPS at `https_port`, apart from answering ACME HTTP-01 challenges.
+pub fn redirect_router(https_port: u16, challenges: Arc<Challenges>) -> Router {
+    Router::new()
+        .route(
+            "/.well-known/acme-challenge/{token}",
+            get(move |Path(token): Path<String>| async move {
+                challenges.http(&token).ok_or(StatusCode::NOT_FOUND)
+            }),
+        )
+        .fallback(move |headers: HeaderMap, uri: Uri| async move {
+            redirect_to_https(&headers, &uri, https_port)
+        })
+}
+
+fn redirect_to_https(headers: &HeaderMap, uri: &Uri, https_port: u16) -> Response {
+    let Some(host) = headers.get(header::HOST).and_then(|h| h.to_str().ok()) else {
+        return (StatusCode::BAD_REQUEST, "Missing Host header").into_response();
+    };
+    // Drop the port the request came in on, keeping IPv6 brackets
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => name,
+        _ => host,
+    };
+    let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    let location = if https_port == 443 {
+        format!("https://{}{}", host, path)
+    } else {
+        format!("https://{}:{}{}", host, https_port, path)
+    };
+    Redirect::permanent(&location).into_response()
+}
+
+/// Serve [`redirect_router`] on the configured redirect port until the
+/// process exits.
+pub async fn run_https_redirect(config: Config, challenges: Arc<Challenges>) {
+    let addr = format!("{}:{}", config.host, config.tls_redirect_port);
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!("Failed to bind HTTPS redirect on {}: {}", addr, e);
+            return;
+        }
+    };
+    tracing::info!("Redirecting http://{} to HTTPS", addr);
+    if let Err(e) = axum::serve(listener, redirect_router(config.port, challenges)).await {
+        tracing::error!("HTTPS redirect stopped: {}", e);
+    }
+}
@@ -96,6 +96,9 @@ pub enum ClientEvent {
         user_id: String,
         #[serde(rename = "encryptedKey")]
         encrypted_key: String,
+        /// Wrap target; omitted for the account-level key.
+        #[serde(rename = "deviceId", default)]
+        device_id: Option<String>,
     },
     RequestServerKey {
         #[serde(rename = "serverId")]
@@ -118,6 +121,19 @@ pub enum ClientEvent {
         #[serde(rename = "drinkCount")]
         drink_count: i32,
     },
+    SpeakingUpdate {
+        #[serde(rename = "channelId")]
+        channel_id: String,
+        speaking: bool,
+    },
+    /// Watch-together: the sender's player started or stopped buffering.
+    MediaBufferingUpdate {
+        #[serde(rename = "sessionId")]
+        session_id: String,
+        buffering: bool,
+        #[serde(rename = "positionMs")]
+        position_ms: Option<i64>,
+    },
     UpdateStatus {
         status: String,
     },
@@ -131,6 +147,29 @@ pub enum ClientEvent {
         #[serde(rename = "channelId")]
         channel_id: String,
     },
+    /// Stage listener raising (or lowering) their hand to speak.
+    StageRaiseHand {
+        #[serde(rename = "channelId")]
+        channel_id: String,
+        raised: bool,
+    },
+    /// Stage moderator bringing a listener up to speak, or moving a speaker
+    /// back to the audience. Speakers can step down themselves.
+    StageSetSpeaker {
+        #[serde(rename = "channelId")]
+        channel_id: String,
+        #[serde(rename = "userId")]
+        user_id: String,
+        speaker: bool,
+    },
+    /// Periodic call quality sample from a client in voice: round trip and
+    /// jitter in milliseconds, packet loss as a percentage.
+    VoiceStats {
+        rtt: f64,
+        jitter: f64,
+        #[serde(rename = "packetLoss")]
+        packet_loss: f64,
+    },
     Ping,
 }
 
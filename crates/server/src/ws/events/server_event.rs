@@ -1,6 +1,10 @@
+use flux_shared::markdown::MessageMetadata;
 use serde::Serialize;
 
-use crate::models::{Attachment, Channel, DmMessage, Message, QueueItem, VoiceParticipant};
+use crate::models::{
+    AccessRequest, Attachment, Channel, CustomEmojiRef, DmChannelResponse, DmMessage, Game, LyricLine, Message, MessageReminder, QueueItem,
+    UnmetRequirement, VoiceParticipant,
+};
 
 use super::ActivityInfo;
 
@@ -18,6 +22,7 @@ pub enum ServerEvent {
         content: String,
         #[serde(rename = "editedAt")]
         edited_at: String,
+        metadata: MessageMetadata,
     },
     Typing {
         #[serde(rename = "channelId")]
@@ -36,12 +41,22 @@ pub enum ServerEvent {
         channel_id: String,
         participants: Vec<VoiceParticipant>,
     },
+    /// Only sent to participants of the voice channel.
+    Speaking {
+        #[serde(rename = "channelId")]
+        channel_id: String,
+        #[serde(rename = "userId")]
+        user_id: String,
+        speaking: bool,
+    },
     ReactionAdd {
         #[serde(rename = "messageId")]
         message_id: String,
         #[serde(rename = "userId")]
         user_id: String,
         emoji: String,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "customEmoji")]
+        custom_emoji: Option<CustomEmojiRef>,
     },
     ReactionRemove {
         #[serde(rename = "messageId")]
@@ -59,6 +74,19 @@ pub enum ServerEvent {
     DmMessage {
         message: DmMessage,
     },
+    /// Someone without an accepted conversation messaged the user; it waits
+    /// in their requests until accepted
+    DmRequest {
+        channel: DmChannelResponse,
+        message: DmMessage,
+    },
+    /// The recipient accepted a message request the user sent
+    DmRequestAccepted {
+        #[serde(rename = "dmChannelId")]
+        dm_channel_id: String,
+        #[serde(rename = "userId")]
+        user_id: String,
+    },
     MemberJoined {
         #[serde(rename = "serverId")]
         server_id: String,
@@ -123,6 +151,29 @@ pub enum ServerEvent {
         #[serde(rename = "userId")]
         user_id: String,
         role: String,
+        /// Custom role ids the member now holds, when those changed.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        roles: Option<Vec<String>>,
+    },
+    /// A user picked a new username. `ProfileUpdate` carries it too; this
+    /// also names the old one so clients can repoint cached mentions.
+    UserRenamed {
+        #[serde(rename = "userId")]
+        user_id: String,
+        #[serde(rename = "oldUsername")]
+        old_username: String,
+        username: String,
+    },
+    /// A member's nickname or server avatar changed; carries both as they
+    /// now stand.
+    MemberProfileUpdated {
+        #[serde(rename = "serverId")]
+        server_id: String,
+        #[serde(rename = "userId")]
+        user_id: String,
+        nickname: Option<String>,
+        #[serde(rename = "serverAvatar")]
+        server_avatar: Option<String>,
     },
     MemberLeft {
         #[serde(rename = "serverId")]
@@ -137,12 +188,43 @@ pub enum ServerEvent {
         encrypted_key: String,
         #[serde(rename = "senderId")]
         sender_id: String,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "deviceId")]
+        device_id: Option<String>,
     },
     ServerKeyRequested {
         #[serde(rename = "serverId")]
         server_id: String,
         #[serde(rename = "userId")]
         user_id: String,
+        /// The requesting device, so members wrap the key for its public key.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "deviceId")]
+        device_id: Option<String>,
+    },
+    /// This connection's session was revoked; the socket closes after this.
+    SessionRevoked {
+        #[serde(rename = "sessionId")]
+        session_id: String,
+    },
+    /// A contact's identity key rotated. `wasVerified` means the recipient had
+    /// verified the old key and should verify again.
+    KeyChanged {
+        #[serde(rename = "userId")]
+        user_id: String,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "deviceId")]
+        device_id: Option<String>,
+        #[serde(rename = "wasVerified")]
+        was_verified: bool,
+    },
+    /// The user's key backup was replaced (e.g. a new passphrase); sent to
+    /// their own sessions.
+    KeyBackupUpdated {
+        version: i64,
+    },
+    /// A new device of this user restored its identity key from backup.
+    KeyBackupRestored {
+        version: i64,
+        #[serde(rename = "publicKey")]
+        public_key: String,
     },
     SpotifyQueueUpdate {
         #[serde(rename = "sessionId")]
@@ -163,6 +245,10 @@ pub enum ServerEvent {
         #[serde(skip_serializing_if = "Option::is_none", rename = "positionMs")]
         position_ms: Option<i64>,
         source: String,
+        /// Server clock (unix ms) when `position_ms` was relayed; followers add
+        /// the time since to get the host's current position and correct drift.
+        #[serde(rename = "serverTimeMs")]
+        server_time_ms: i64,
     },
     SpotifyQueueRemove {
         #[serde(rename = "sessionId")]
@@ -172,6 +258,49 @@ pub enum ServerEvent {
         #[serde(rename = "itemId")]
         item_id: String,
     },
+    SpotifySessionRoles {
+        #[serde(rename = "sessionId")]
+        session_id: String,
+        #[serde(rename = "voiceChannelId")]
+        voice_channel_id: String,
+        #[serde(rename = "hostUserId")]
+        host_user_id: String,
+        #[serde(rename = "cohostIds")]
+        cohost_ids: Vec<String>,
+    },
+    /// Lyrics for the session's current track, with the playback position
+    /// they were sent at so every participant highlights the same line.
+    LyricsSync {
+        #[serde(rename = "sessionId")]
+        session_id: String,
+        #[serde(rename = "voiceChannelId")]
+        voice_channel_id: String,
+        #[serde(rename = "trackUri")]
+        track_uri: String,
+        lines: Vec<LyricLine>,
+        #[serde(rename = "positionMs")]
+        position_ms: i64,
+        #[serde(rename = "isPlaying")]
+        is_playing: bool,
+    },
+    /// A game started, resolved or was cancelled in this channel.
+    GameUpdate {
+        game: Game,
+    },
+    MediaBuffering {
+        #[serde(rename = "sessionId")]
+        session_id: String,
+        #[serde(rename = "voiceChannelId")]
+        voice_channel_id: String,
+        #[serde(rename = "userId")]
+        user_id: String,
+        buffering: bool,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "positionMs")]
+        position_ms: Option<i64>,
+        /// Everyone in the session still buffering after this update.
+        #[serde(rename = "bufferingUserIds")]
+        buffering_user_ids: Vec<String>,
+    },
     SpotifySessionEnded {
         #[serde(rename = "sessionId")]
         session_id: String,
@@ -218,6 +347,23 @@ pub enum ServerEvent {
         #[serde(rename = "channelId")]
         channel_id: String,
     },
+    /// Someone in a stage's audience raised or lowered their hand
+    StageHandUpdate {
+        #[serde(rename = "channelId")]
+        channel_id: String,
+        #[serde(rename = "userId")]
+        user_id: String,
+        raised: bool,
+    },
+    /// A stage speaker was added or removed. The affected user fetches a new
+    /// voice token to pick up the changed publish permission.
+    StageSpeakerUpdate {
+        #[serde(rename = "channelId")]
+        channel_id: String,
+        #[serde(rename = "userId")]
+        user_id: String,
+        speaker: bool,
+    },
     RoomInvite {
         #[serde(rename = "channelId")]
         channel_id: String,
@@ -240,6 +386,61 @@ pub enum ServerEvent {
         #[serde(rename = "setId")]
         set_id: String,
     },
+    AccessRequested {
+        request: AccessRequest,
+    },
+    AccessRequestResolved {
+        #[serde(rename = "requestId")]
+        request_id: String,
+        approved: bool,
+    },
+    RoadmapUpdated {
+        #[serde(rename = "serverId")]
+        server_id: String,
+        #[serde(rename = "itemId")]
+        item_id: String,
+    },
+    ScheduledEventUpdated {
+        #[serde(rename = "serverId")]
+        server_id: String,
+        #[serde(rename = "eventId")]
+        event_id: String,
+        deleted: bool,
+    },
+    /// Sent to members who RSVP'd going or interested, shortly before an
+    /// occurrence starts
+    EventReminder {
+        #[serde(rename = "serverId")]
+        server_id: String,
+        #[serde(rename = "eventId")]
+        event_id: String,
+        #[serde(rename = "channelId")]
+        channel_id: Option<String>,
+        title: String,
+        #[serde(rename = "startAt")]
+        start_at: String,
+    },
+    /// A message reminder went off
+    ReminderDue {
+        reminder: MessageReminder,
+    },
+    /// A message was refused because the sender hasn't met the server's
+    /// new-member requirements yet
+    VerificationRequired {
+        #[serde(rename = "serverId")]
+        server_id: String,
+        #[serde(rename = "channelId")]
+        channel_id: String,
+        requirement: UnmetRequirement,
+    },
+    /// One of the user's synced settings changed on another of their
+    /// devices. `value` is null when it was deleted.
+    UserSettingUpdate {
+        key: String,
+        value: Option<serde_json::Value>,
+        #[serde(rename = "updatedAt")]
+        updated_at: String,
+    },
     Error {
         message: String,
     },
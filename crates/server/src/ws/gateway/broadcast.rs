@@ -1,83 +1,179 @@
-use super::{ClientId, GatewayState};
+use axum::extract::ws::Utf8Bytes;
+use std::collections::HashSet;
+
+use super::{ClientId, Delivery, GatewayState};
 use crate::ws::events::ServerEvent;
 
+/// Serialize an event once. Cloning the result only bumps a reference count,
+/// so every recipient's queue shares the one buffer.
+fn encode(event: &ServerEvent) -> Option<Utf8Bytes> {
+    serde_json::to_string(event).ok().map(Utf8Bytes::from)
+}
+
 impl GatewayState {
     pub async fn broadcast_channel(&self, channel_id: &str, event: &ServerEvent, exclude: Option<ClientId>) {
-        let msg = match serde_json::to_string(event) {
-            Ok(m) => m,
-            Err(_) => return,
-        };
+        let Some(msg) = encode(event) else { return };
+        let delivery = Delivery::of(event);
 
         let subs = self.channel_subs.read().await;
         let clients = self.clients.read().await;
 
+        let mut sent = 0;
         if let Some(subscriber_ids) = subs.get(channel_id) {
             for &cid in subscriber_ids {
                 if Some(cid) == exclude {
                     continue;
                 }
                 if let Some(client) = clients.get(&cid) {
-                    let _ = client.tx.send(msg.clone());
+                    let _ = client.tx.send_with(msg.clone(), &delivery);
+                    sent += 1;
+                }
+            }
+        }
+        crate::metrics::record_broadcast("channel", &msg, sent);
+    }
+
+    /// `broadcast_channel`, except clients signed in as one of `users` get
+    /// `alternate` instead, or nothing when it's `None`. Keeps blocked users
+    /// out of each other's way.
+    pub async fn broadcast_channel_except_users(
+        &self,
+        channel_id: &str,
+        event: &ServerEvent,
+        exclude: Option<ClientId>,
+        users: &HashSet<String>,
+        alternate: Option<&ServerEvent>,
+    ) {
+        let Some(msg) = encode(event) else { return };
+        let delivery = Delivery::of(event);
+        let alternate = alternate.and_then(|alt| Some((encode(alt)?, Delivery::of(alt))));
+
+        let subs = self.channel_subs.read().await;
+        let clients = self.clients.read().await;
+
+        let mut sent = 0;
+        if let Some(subscriber_ids) = subs.get(channel_id) {
+            for &cid in subscriber_ids {
+                if Some(cid) == exclude {
+                    continue;
+                }
+                let Some(client) = clients.get(&cid) else { continue };
+                if !users.contains(&client.user_id) {
+                    let _ = client.tx.send_with(msg.clone(), &delivery);
+                } else if let Some((alt, alt_delivery)) = &alternate {
+                    let _ = client.tx.send_with(alt.clone(), alt_delivery);
+                } else {
+                    continue;
                 }
+                sent += 1;
+            }
+        }
+        crate::metrics::record_broadcast("channel", &msg, sent);
+    }
+
+    /// Send to clients currently in the given voice channel.
+    pub async fn broadcast_voice_channel(&self, channel_id: &str, event: &ServerEvent, exclude: Option<ClientId>) {
+        let Some(msg) = encode(event) else { return };
+        let delivery = Delivery::of(event);
+
+        let clients = self.clients.read().await;
+        let mut sent = 0;
+        for (&cid, client) in clients.iter() {
+            if Some(cid) == exclude || client.voice_channel_id.as_deref() != Some(channel_id) {
+                continue;
             }
+            let _ = client.tx.send_with(msg.clone(), &delivery);
+            sent += 1;
         }
+        crate::metrics::record_broadcast("voice_channel", &msg, sent);
     }
 
     pub async fn broadcast_dm(&self, dm_channel_id: &str, event: &ServerEvent) {
-        let msg = match serde_json::to_string(event) {
-            Ok(m) => m,
-            Err(_) => return,
-        };
+        let Some(msg) = encode(event) else { return };
+        let delivery = Delivery::of(event);
 
         let subs = self.dm_subs.read().await;
         let clients = self.clients.read().await;
 
+        let mut sent = 0;
         if let Some(subscriber_ids) = subs.get(dm_channel_id) {
             for &cid in subscriber_ids {
                 if let Some(client) = clients.get(&cid) {
-                    let _ = client.tx.send(msg.clone());
+                    let _ = client.tx.send_with(msg.clone(), &delivery);
+                    sent += 1;
                 }
             }
         }
+        crate::metrics::record_broadcast("dm", &msg, sent);
     }
 
     pub async fn broadcast_all(&self, event: &ServerEvent, exclude: Option<ClientId>) {
-        let msg = match serde_json::to_string(event) {
-            Ok(m) => m,
-            Err(_) => return,
-        };
+        let Some(msg) = encode(event) else { return };
+        let delivery = Delivery::of(event);
 
         let clients = self.clients.read().await;
+        let mut sent = 0;
         for (&cid, client) in clients.iter() {
             if Some(cid) == exclude {
                 continue;
             }
-            let _ = client.tx.send(msg.clone());
+            let _ = client.tx.send_with(msg.clone(), &delivery);
+            sent += 1;
         }
+        crate::metrics::record_broadcast("all", &msg, sent);
     }
 
     pub async fn send_to(&self, client_id: ClientId, event: &ServerEvent) {
-        let msg = match serde_json::to_string(event) {
-            Ok(m) => m,
-            Err(_) => return,
-        };
+        let Some(msg) = encode(event) else { return };
+        let delivery = Delivery::of(event);
 
         let clients = self.clients.read().await;
         if let Some(client) = clients.get(&client_id) {
-            let _ = client.tx.send(msg);
+            crate::metrics::record_broadcast("client", &msg, 1);
+            let _ = client.tx.send_with(msg, &delivery);
         }
     }
 
     pub async fn send_to_user(&self, user_id: &str, event: &ServerEvent) {
-        let msg = match serde_json::to_string(event) {
-            Ok(m) => m,
-            Err(_) => return,
-        };
+        let Some(msg) = encode(event) else { return };
+        let delivery = Delivery::of(event);
 
         let clients = self.clients.read().await;
+        let mut sent = 0;
         for client in clients.values() {
             if client.user_id == user_id {
+                let _ = client.tx.send_with(msg.clone(), &delivery);
+                sent += 1;
+            }
+        }
+        crate::metrics::record_broadcast("user", &msg, sent);
+    }
+
+    pub async fn send_to_device(&self, user_id: &str, device_id: &str, event: &ServerEvent) {
+        let Some(msg) = encode(event) else { return };
+        let delivery = Delivery::of(event);
+
+        let clients = self.clients.read().await;
+        let mut sent = 0;
+        for client in clients.values() {
+            if client.user_id == user_id && client.device_id.as_deref() == Some(device_id) {
+                let _ = client.tx.send_with(msg.clone(), &delivery);
+                sent += 1;
+            }
+        }
+        crate::metrics::record_broadcast("device", &msg, sent);
+    }
+
+    /// Drop every connection made with this session. Each client gets `event`
+    /// before its socket closes.
+    pub async fn close_session(&self, session_id: &str, event: &ServerEvent) {
+        let Some(msg) = encode(event) else { return };
+
+        let clients = self.clients.read().await;
+        for client in clients.values() {
+            if client.session_id.as_deref() == Some(session_id) {
                 let _ = client.tx.send(msg.clone());
+                client.closed.notify_one();
             }
         }
     }
@@ -0,0 +1,41 @@
+use super::{ClientId, GatewayState};
+
+/// A watch-together participant whose player reported it's buffering.
+pub struct BufferingState {
+    pub session_id: String,
+    pub user_id: String,
+}
+
+impl GatewayState {
+    /// Record whether a client's player is buffering in a media session and
+    /// return the users still buffering there, so the host can hold playback
+    /// until it's empty.
+    pub async fn set_buffering(&self, client_id: ClientId, session_id: &str, user_id: &str, buffering: bool) -> Vec<String> {
+        let mut states = self.buffering.write().await;
+        if buffering {
+            states.insert(
+                client_id,
+                BufferingState {
+                    session_id: session_id.to_string(),
+                    user_id: user_id.to_string(),
+                },
+            );
+        } else {
+            states.remove(&client_id);
+        }
+
+        let mut user_ids: Vec<String> = states
+            .values()
+            .filter(|s| s.session_id == session_id)
+            .map(|s| s.user_id.clone())
+            .collect();
+        user_ids.sort();
+        user_ids.dedup();
+        user_ids
+    }
+
+    /// Forget a client's buffering state (on leaving or switching voice channels).
+    pub async fn clear_buffering(&self, client_id: ClientId) {
+        self.buffering.write().await.remove(&client_id);
+    }
+}
@@ -1,9 +1,16 @@
 mod broadcast;
+mod media;
+mod outbox;
+mod speaking;
 mod voice;
 
+pub use media::BufferingState;
+pub use outbox::{client_channel, ClientReceiver, ClientSender, Delivery, CLIENT_QUEUE_CAPACITY};
+pub use speaking::{SpeakingState, SPEAKING_DEBOUNCE};
+
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{Notify, RwLock};
 
 use crate::ws::events::ActivityInfo;
 
@@ -15,12 +22,18 @@ type VoiceParticipantMap = HashMap<String, HashMap<String, (String, i32)>>;
 pub struct ConnectedClient {
     pub user_id: String,
     pub username: String,
-    pub tx: mpsc::UnboundedSender<String>,
+    pub tx: ClientSender,
     pub subscribed_channels: HashSet<String>,
     pub subscribed_dms: HashSet<String>,
     pub voice_channel_id: Option<String>,
     pub activity: Option<ActivityInfo>,
     pub status: String,
+    /// Set when the client connected with `?deviceId=`, for per-device E2EE keys.
+    pub device_id: Option<String>,
+    /// The auth session the client connected with.
+    pub session_id: Option<String>,
+    /// Notified to drop the connection, e.g. when its session is revoked.
+    pub closed: Arc<Notify>,
 }
 
 pub struct GatewayState {
@@ -30,6 +43,8 @@ pub struct GatewayState {
     pub dm_subs: RwLock<HashMap<String, HashSet<ClientId>>>,
     pub voice_participants: RwLock<VoiceParticipantMap>,
     pub cleanup_timers: RwLock<HashMap<String, tokio::task::JoinHandle<()>>>,
+    pub speaking: RwLock<HashMap<ClientId, SpeakingState>>,
+    pub buffering: RwLock<HashMap<ClientId, BufferingState>>,
 }
 
 impl Default for GatewayState {
@@ -47,6 +62,8 @@ impl GatewayState {
             dm_subs: RwLock::new(HashMap::new()),
             voice_participants: RwLock::new(HashMap::new()),
             cleanup_timers: RwLock::new(HashMap::new()),
+            speaking: RwLock::new(HashMap::new()),
+            buffering: RwLock::new(HashMap::new()),
         }
     }
 
@@ -62,9 +79,10 @@ impl GatewayState {
         client_id: ClientId,
         user_id: String,
         username: String,
-        tx: mpsc::UnboundedSender<String>,
+        tx: ClientSender,
         status: String,
     ) {
+        let closed = tx.closed_signal();
         let client = ConnectedClient {
             user_id,
             username,
@@ -74,6 +92,9 @@ impl GatewayState {
             voice_channel_id: None,
             activity: None,
             status,
+            device_id: None,
+            session_id: None,
+            closed,
         };
         self.clients.write().await.insert(client_id, client);
     }
@@ -110,10 +131,32 @@ impl GatewayState {
                 }
             }
         }
+        self.clear_speaking(client_id).await;
+        self.clear_buffering(client_id).await;
 
         Some(client)
     }
 
+    pub async fn set_device(&self, client_id: ClientId, device_id: String) {
+        if let Some(client) = self.clients.write().await.get_mut(&client_id) {
+            client.device_id = Some(device_id);
+        }
+    }
+
+    pub async fn device_of(&self, client_id: ClientId) -> Option<String> {
+        self.clients.read().await.get(&client_id)?.device_id.clone()
+    }
+
+    pub async fn set_session(&self, client_id: ClientId, session_id: String) {
+        if let Some(client) = self.clients.write().await.get_mut(&client_id) {
+            client.session_id = Some(session_id);
+        }
+    }
+
+    pub async fn close_signal(&self, client_id: ClientId) -> Option<Arc<Notify>> {
+        Some(self.clients.read().await.get(&client_id)?.closed.clone())
+    }
+
     pub async fn subscribe_channel(&self, client_id: ClientId, channel_id: &str) {
         self.channel_subs
             .write()
@@ -203,6 +246,15 @@ impl GatewayState {
         activities
     }
 
+    /// The activity of any of the user's connections that has one.
+    pub async fn get_user_activity(&self, user_id: &str) -> Option<ActivityInfo> {
+        let clients = self.clients.read().await;
+        clients
+            .values()
+            .filter(|c| c.user_id == user_id)
+            .find_map(|c| c.activity.clone())
+    }
+
     pub async fn set_status(&self, client_id: ClientId, status: String) {
         if let Some(client) = self.clients.write().await.get_mut(&client_id) {
             client.status = status;
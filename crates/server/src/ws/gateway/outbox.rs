@@ -0,0 +1,209 @@
+//! Per-client outgoing event queues.
+//!
+//! Each connection gets a bounded queue instead of an unbounded channel, so a
+//! stalled socket can't grow memory without limit. When a client falls behind:
+//!
+//! - presence, activity and voice state updates replace an older queued one
+//!   for the same user or channel, since only the latest matters;
+//! - typing and speaking indicators are coalesced the same way, and dropped
+//!   outright once the queue is full;
+//! - everything else (messages, edits, reactions...) is never dropped. It may
+//!   push the queue past capacity for a while, but a client that stays
+//!   saturated, or falls too far behind, is disconnected. It resyncs on
+//!   reconnect, which is better than silently missing messages.
+
+use axum::extract::ws::Utf8Bytes;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio::sync::Notify;
+
+use crate::ws::events::ServerEvent;
+
+/// Events a client can have queued before it counts as saturated.
+pub const CLIENT_QUEUE_CAPACITY: usize = 512;
+/// How long a client may stay saturated before it's disconnected.
+const SATURATED_GRACE: Duration = Duration::from_secs(10);
+/// Past this many times capacity the client is disconnected straight away.
+const HARD_LIMIT_FACTOR: usize = 4;
+
+/// How an event may be treated when its recipient is behind.
+#[derive(Debug, Clone, Default)]
+pub struct Delivery {
+    /// Queued events with the same key are superseded by this one.
+    key: Option<String>,
+    /// May be dropped when the queue is full.
+    droppable: bool,
+}
+
+impl Delivery {
+    /// Delivered in order and never dropped.
+    pub fn reliable() -> Self {
+        Self::default()
+    }
+
+    pub fn of(event: &ServerEvent) -> Self {
+        let (key, droppable) = match event {
+            ServerEvent::Typing { channel_id, user_id, .. } => (format!("typing:{}:{}", channel_id, user_id), true),
+            ServerEvent::Speaking { channel_id, user_id, .. } => (format!("speaking:{}:{}", channel_id, user_id), true),
+            ServerEvent::Presence { user_id, .. } => (format!("presence:{}", user_id), false),
+            ServerEvent::ActivityUpdate { user_id, .. } => (format!("activity:{}", user_id), false),
+            ServerEvent::VoiceState { channel_id, .. } => (format!("voice_state:{}", channel_id), false),
+            _ => return Self::reliable(),
+        };
+        Self { key: Some(key), droppable }
+    }
+}
+
+struct Queued {
+    msg: Utf8Bytes,
+    key: Option<String>,
+    droppable: bool,
+}
+
+#[derive(Default)]
+struct Queue {
+    items: VecDeque<Queued>,
+    saturated_since: Option<Instant>,
+    /// The client was dropped, or the gateway let go of it.
+    closed: bool,
+}
+
+struct Outbox {
+    queue: Mutex<Queue>,
+    capacity: usize,
+    /// Wakes the receiver when something is queued.
+    ready: Notify,
+    /// Tells the connection to close.
+    closed: Arc<Notify>,
+}
+
+/// The gateway's end of a client's queue.
+pub struct ClientSender {
+    outbox: Arc<Outbox>,
+}
+
+/// The connection's end of its queue.
+pub struct ClientReceiver {
+    outbox: Arc<Outbox>,
+}
+
+/// A queue holding up to `capacity` events before it counts as saturated.
+pub fn client_channel(capacity: usize) -> (ClientSender, ClientReceiver) {
+    let outbox = Arc::new(Outbox {
+        queue: Mutex::new(Queue::default()),
+        capacity: capacity.max(1),
+        ready: Notify::new(),
+        closed: Arc::new(Notify::new()),
+    });
+    (ClientSender { outbox: outbox.clone() }, ClientReceiver { outbox })
+}
+
+impl ClientSender {
+    /// Queue an event that must arrive. Returns false if the client is gone.
+    pub fn send(&self, msg: impl Into<Utf8Bytes>) -> bool {
+        self.send_with(msg.into(), &Delivery::reliable())
+    }
+
+    /// Queue an event under the given delivery policy. Returns false if the
+    /// client is gone or the event was dropped. `msg` is reference counted,
+    /// so a broadcast queues the same buffer for every recipient.
+    pub fn send_with(&self, msg: Utf8Bytes, delivery: &Delivery) -> bool {
+        let outbox = &self.outbox;
+        let mut queue = outbox.queue.lock().unwrap();
+        if queue.closed {
+            return false;
+        }
+
+        // Supersede a queued event for the same thing
+        if let Some(key) = &delivery.key {
+            if let Some(queued) = queue.items.iter_mut().find(|q| q.key.as_ref() == Some(key)) {
+                queued.msg = msg;
+                ::metrics::counter!("gateway_events_coalesced_total").increment(1);
+                return true;
+            }
+        }
+
+        if queue.items.len() >= outbox.capacity {
+            if delivery.droppable {
+                ::metrics::counter!("gateway_events_dropped_total").increment(1);
+                return false;
+            }
+            // Make room by dropping the oldest indicator, if there is one
+            if let Some(pos) = queue.items.iter().position(|q| q.droppable) {
+                queue.items.remove(pos);
+                ::metrics::counter!("gateway_events_dropped_total").increment(1);
+            }
+        }
+
+        queue.items.push_back(Queued {
+            msg,
+            key: delivery.key.clone(),
+            droppable: delivery.droppable,
+        });
+
+        if queue.items.len() >= outbox.capacity {
+            let since = *queue.saturated_since.get_or_insert_with(Instant::now);
+            if since.elapsed() >= SATURATED_GRACE || queue.items.len() > outbox.capacity * HARD_LIMIT_FACTOR {
+                tracing::warn!("Disconnecting slow gateway client with {} queued events", queue.items.len());
+                ::metrics::counter!("gateway_slow_consumers_disconnected_total").increment(1);
+                queue.items.clear();
+                queue.closed = true;
+                drop(queue);
+                outbox.closed.notify_one();
+                outbox.ready.notify_one();
+                return false;
+            }
+        }
+        drop(queue);
+        outbox.ready.notify_one();
+        true
+    }
+
+    /// Events waiting to be written to the socket.
+    pub fn depth(&self) -> usize {
+        self.outbox.queue.lock().unwrap().items.len()
+    }
+
+    /// Notified when the connection should close: the client fell too far
+    /// behind, or its session was revoked.
+    pub fn closed_signal(&self) -> Arc<Notify> {
+        self.outbox.closed.clone()
+    }
+}
+
+impl Drop for ClientSender {
+    fn drop(&mut self) {
+        self.outbox.queue.lock().unwrap().closed = true;
+        self.outbox.ready.notify_one();
+    }
+}
+
+impl ClientReceiver {
+    /// The next event, or `None` once the client is closed and its queue
+    /// is drained.
+    pub async fn recv(&mut self) -> Option<Utf8Bytes> {
+        loop {
+            match self.try_recv() {
+                Ok(msg) => return Some(msg),
+                Err(TryRecvError::Disconnected) => return None,
+                Err(TryRecvError::Empty) => self.outbox.ready.notified().await,
+            }
+        }
+    }
+
+    pub fn try_recv(&mut self) -> Result<Utf8Bytes, TryRecvError> {
+        let mut queue = self.outbox.queue.lock().unwrap();
+        match queue.items.pop_front() {
+            Some(queued) => {
+                if queue.items.len() < self.outbox.capacity {
+                    queue.saturated_since = None;
+                }
+                Ok(queued.msg)
+            }
+            None if queue.closed => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+}
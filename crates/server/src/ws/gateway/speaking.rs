@@ -0,0 +1,103 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::{ClientId, GatewayState};
+use crate::ws::events::ServerEvent;
+
+/// Minimum gap between two speaking events for one client. Toggles inside the
+/// window collapse into a single trailing event carrying the latest state.
+pub const SPEAKING_DEBOUNCE: Duration = Duration::from_millis(150);
+
+pub struct SpeakingState {
+    pub user_id: String,
+    pub channel_id: String,
+    /// Last state relayed to the channel.
+    pub sent: bool,
+    pub last_sent: Option<Instant>,
+    /// Latest state waiting for the debounce window; `Some` while a flush is scheduled.
+    pub pending: Option<bool>,
+}
+
+impl GatewayState {
+    /// Relay a speaking change to the other participants of the client's voice
+    /// channel. Ignored unless the client is actually in `channel_id`.
+    pub async fn update_speaking(self: &Arc<Self>, client_id: ClientId, channel_id: &str, speaking: bool) {
+        let user_id = {
+            let clients = self.clients.read().await;
+            match clients.get(&client_id) {
+                Some(c) if c.voice_channel_id.as_deref() == Some(channel_id) => c.user_id.clone(),
+                _ => return,
+            }
+        };
+
+        let mut states = self.speaking.write().await;
+        let entry = states.entry(client_id).or_insert_with(|| SpeakingState {
+            user_id: user_id.clone(),
+            channel_id: channel_id.to_string(),
+            sent: false,
+            last_sent: None,
+            pending: None,
+        });
+
+        let wait = entry
+            .last_sent
+            .map(|t| SPEAKING_DEBOUNCE.saturating_sub(t.elapsed()))
+            .unwrap_or_default();
+
+        if wait.is_zero() {
+            entry.pending = None;
+            if entry.sent == speaking {
+                return;
+            }
+            entry.sent = speaking;
+            entry.last_sent = Some(Instant::now());
+            drop(states);
+            self.broadcast_speaking(client_id, &user_id, channel_id, speaking).await;
+            return;
+        }
+
+        let flush_scheduled = entry.pending.is_some();
+        entry.pending = Some(speaking);
+        if !flush_scheduled {
+            let gw = Arc::clone(self);
+            tokio::spawn(async move {
+                tokio::time::sleep(wait).await;
+                gw.flush_speaking(client_id).await;
+            });
+        }
+    }
+
+    async fn flush_speaking(&self, client_id: ClientId) {
+        let (user_id, channel_id, speaking) = {
+            let mut states = self.speaking.write().await;
+            let Some(entry) = states.get_mut(&client_id) else { return };
+            let Some(speaking) = entry.pending.take() else { return };
+            if entry.sent == speaking {
+                return;
+            }
+            entry.sent = speaking;
+            entry.last_sent = Some(Instant::now());
+            (entry.user_id.clone(), entry.channel_id.clone(), speaking)
+        };
+        self.broadcast_speaking(client_id, &user_id, &channel_id, speaking).await;
+    }
+
+    async fn broadcast_speaking(&self, client_id: ClientId, user_id: &str, channel_id: &str, speaking: bool) {
+        self.broadcast_voice_channel(
+            channel_id,
+            &ServerEvent::Speaking {
+                channel_id: channel_id.to_string(),
+                user_id: user_id.to_string(),
+                speaking,
+            },
+            Some(client_id),
+        )
+        .await;
+    }
+
+    /// Forget a client's speaking state (on leaving or switching voice channels).
+    /// A pending flush finds nothing and does nothing.
+    pub async fn clear_speaking(&self, client_id: ClientId) {
+        self.speaking.write().await.remove(&client_id);
+    }
+}
@@ -38,9 +38,13 @@ impl GatewayState {
                 .or_default()
                 .insert(client.user_id.clone(), (client.username.clone(), 0));
         }
+        self.clear_speaking(client_id).await;
+        self.clear_buffering(client_id).await;
     }
 
     pub async fn voice_leave(&self, client_id: ClientId) -> Option<String> {
+        self.clear_speaking(client_id).await;
+        self.clear_buffering(client_id).await;
         let mut clients = self.clients.write().await;
         let mut vp = self.voice_participants.write().await;
 
@@ -2,6 +2,7 @@ use crate::AppState;
 use crate::models::AuthUser;
 use crate::ws::events::ServerEvent;
 use crate::ws::gateway::ClientId;
+use sqlx::types::Json;
 
 pub async fn handle_send_message(
     state: &AppState,
@@ -11,6 +12,8 @@ pub async fn handle_send_message(
     content: String,
     attachment_ids: Vec<String>,
 ) {
+    let flux_shared::markdown::ParsedMessage { content, metadata } =
+        flux_shared::markdown::parse(&content);
     if let Err(e) = flux_shared::validation::validate_message_content(&content) {
         state
             .gateway
@@ -18,77 +21,167 @@ pub async fn handle_send_message(
             .await;
         return;
     }
+    if let Some((server_id, requirement)) = crate::routes::servers::posting_blocked(state, &user.id, &channel_id).await {
+        state
+            .gateway
+            .send_to(client_id, &ServerEvent::VerificationRequired { server_id, channel_id, requirement })
+            .await;
+        return;
+    }
+    if crate::routes::servers::rules_pending(state, &user.id, &channel_id).await {
+        state
+            .gateway
+            .send_to(client_id, &ServerEvent::Error { message: "Accept the server rules before sending messages".into() })
+            .await;
+        return;
+    }
 
-    let id = uuid::Uuid::new_v4().to_string();
-    let now = chrono::Utc::now().to_rfc3339();
+    let message = crate::models::Message {
+        id: uuid::Uuid::new_v4().to_string(),
+        channel_id: channel_id.clone(),
+        sender_id: user.id.clone(),
+        content,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        edited_at: None,
+        metadata: Some(Json(metadata)),
+        feed_id: None,
+        integration_id: None,
+    };
+    let attachments = match save_message(state, &message, &attachment_ids).await {
+        Ok(attachments) => attachments,
+        Err(e) => {
+            tracing::error!("Failed to insert message: {:?}", e);
+            state.gateway.send_to(client_id, &ServerEvent::Error { message: format!("Failed to save message: {}", e) }).await;
+            return;
+        }
+    };
 
-    let result = sqlx::query(
-        r#"INSERT INTO messages (id, channel_id, sender_id, content, created_at)
-           VALUES (?, ?, ?, ?, ?)"#,
-    )
-    .bind(&id)
-    .bind(&channel_id)
-    .bind(&user.id)
-    .bind(&content)
-    .bind(&now)
-    .execute(&state.db)
-    .await;
-
-    if let Err(e) = result {
-        tracing::error!("Failed to insert message: {:?}", e);
-        state.gateway.send_to(client_id, &ServerEvent::Error { message: format!("Failed to save message: {}", e) }).await;
+    let server_id = sqlx::query_scalar::<_, String>("SELECT server_id FROM channels WHERE id = ?")
+        .bind(&channel_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten();
+    crate::routes::economy::accrue(state, &user.id, crate::routes::economy::RULE_MESSAGE, server_id.as_deref()).await;
+    if let (Some(server_id), Some(metadata)) = (&server_id, &message.metadata) {
+        crate::routes::emojis::record_message_usage(state, server_id, &metadata.emojis).await;
+    }
+
+    // People on either side of a block with the sender still see the
+    // message, but aren't pinged by it
+    let mentions_anyone = message
+        .metadata
+        .as_ref()
+        .is_some_and(|m| !m.mentions.is_empty() || m.mentions_everyone || m.mentions_here);
+    let blocked = if mentions_anyone {
+        crate::routes::users::block_relations(state, &user.id).await
+    } else {
+        Default::default()
+    };
+    if blocked.is_empty() {
+        state
+            .gateway
+            .broadcast_channel(&channel_id, &ServerEvent::Message { message, attachments }, None)
+            .await;
         return;
     }
+    let mut unmentioned = message.clone();
+    if let Some(Json(metadata)) = unmentioned.metadata.as_mut() {
+        metadata.mentions.clear();
+        metadata.mentions_everyone = false;
+        metadata.mentions_here = false;
+    }
+    let alternate = ServerEvent::Message { message: unmentioned, attachments: attachments.clone() };
+    state
+        .gateway
+        .broadcast_channel_except_users(
+            &channel_id,
+            &ServerEvent::Message { message, attachments },
+            None,
+            &blocked,
+            Some(&alternate),
+        )
+        .await;
+}
+
+/// Store and broadcast a message a feed or integration posts on behalf of
+/// `message.sender_id`. Any `@` in the text is defused first, so outside
+/// content can't ping anyone.
+pub(crate) async fn post_automated_message(
+    state: &AppState,
+    mut message: crate::models::Message,
+) -> sqlx::Result<()> {
+    let flux_shared::markdown::ParsedMessage { content, metadata } =
+        flux_shared::markdown::parse(&message.content.replace('@', "@\u{200b}"));
+    message.content = content;
+    message.metadata = Some(Json(metadata));
 
-    let _ = sqlx::query(
-        "INSERT INTO messages_fts (message_id, plaintext) VALUES (?, ?)",
+    save_message(state, &message, &[]).await?;
+    let channel_id = message.channel_id.clone();
+    state
+        .gateway
+        .broadcast_channel(&channel_id, &ServerEvent::Message { message, attachments: Vec::new() }, None)
+        .await;
+    Ok(())
+}
+
+/// Insert a message, its search entry and its attachment links in one
+/// transaction, so a failure part way leaves no message with missing files.
+/// Returns the attachments that were linked.
+async fn save_message(
+    state: &AppState,
+    message: &crate::models::Message,
+    attachment_ids: &[String],
+) -> sqlx::Result<Vec<crate::models::Attachment>> {
+    let mut tx = state.db_write.begin().await?;
+    sqlx::query(
+        r#"INSERT INTO messages (id, channel_id, sender_id, content, created_at, metadata, feed_id, integration_id)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
     )
-    .bind(&id)
-    .bind(&content)
-    .execute(&state.db)
-    .await;
+    .bind(&message.id)
+    .bind(&message.channel_id)
+    .bind(&message.sender_id)
+    .bind(&message.content)
+    .bind(&message.created_at)
+    .bind(&message.metadata)
+    .bind(&message.feed_id)
+    .bind(&message.integration_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("INSERT INTO messages_fts (message_id, plaintext) VALUES (?, ?)")
+        .bind(&message.id)
+        .bind(&message.content)
+        .execute(&mut *tx)
+        .await?;
 
-    // Link attachments to this message
     let mut attachments = Vec::new();
     if !attachment_ids.is_empty() {
-        for att_id in &attachment_ids {
-            let _ = sqlx::query(
+        for att_id in attachment_ids {
+            sqlx::query(
                 "UPDATE attachments SET message_id = ? WHERE id = ? AND uploader_id = ? AND message_id IS NULL",
             )
-            .bind(&id)
+            .bind(&message.id)
             .bind(att_id)
-            .bind(&user.id)
-            .execute(&state.db)
-            .await;
+            .bind(&message.sender_id)
+            .execute(&mut *tx)
+            .await?;
         }
 
         let placeholders: Vec<String> = attachment_ids.iter().map(|_| "?".to_string()).collect();
-        let in_clause = placeholders.join(",");
         let sql = format!(
             "SELECT * FROM attachments WHERE id IN ({}) AND message_id = ?",
-            in_clause
+            placeholders.join(",")
         );
         let mut query = sqlx::query_as::<_, crate::models::Attachment>(&sql);
-        for att_id in &attachment_ids {
+        for att_id in attachment_ids {
             query = query.bind(att_id);
         }
-        query = query.bind(&id);
-        attachments = query.fetch_all(&state.db).await.unwrap_or_default();
+        attachments = query.bind(&message.id).fetch_all(&mut *tx).await?;
     }
 
-    let message = crate::models::Message {
-        id,
-        channel_id: channel_id.clone(),
-        sender_id: user.id.clone(),
-        content,
-        created_at: now,
-        edited_at: None,
-    };
-
-    state
-        .gateway
-        .broadcast_channel(&channel_id, &ServerEvent::Message { message, attachments }, None)
-        .await;
+    tx.commit().await?;
+    Ok(attachments)
 }
 
 pub async fn handle_edit_message(
@@ -98,6 +191,8 @@ pub async fn handle_edit_message(
     message_id: String,
     content: String,
 ) {
+    let flux_shared::markdown::ParsedMessage { content, metadata } =
+        flux_shared::markdown::parse(&content);
     if let Err(e) = flux_shared::validation::validate_message_content(&content) {
         state
             .gateway
@@ -130,28 +225,29 @@ pub async fn handle_edit_message(
 
     let now = chrono::Utc::now().to_rfc3339();
 
-    let _ = sqlx::query("UPDATE messages SET content = ?, edited_at = ? WHERE id = ?")
+    let _ = sqlx::query("UPDATE messages SET content = ?, edited_at = ?, metadata = ? WHERE id = ?")
         .bind(&content)
         .bind(&now)
+        .bind(Json(&metadata))
         .bind(&message_id)
-        .execute(&state.db)
+        .execute(&state.db_write)
         .await;
 
     let _ = sqlx::query("DELETE FROM messages_fts WHERE message_id = ?")
         .bind(&message_id)
-        .execute(&state.db)
+        .execute(&state.db_write)
         .await;
     let _ = sqlx::query("INSERT INTO messages_fts (message_id, plaintext) VALUES (?, ?)")
         .bind(&message_id)
         .bind(&content)
-        .execute(&state.db)
+        .execute(&state.db_write)
         .await;
 
     state
         .gateway
         .broadcast_channel(
             &channel_id,
-            &ServerEvent::MessageEdit { message_id, content, edited_at: now },
+            &ServerEvent::MessageEdit { message_id, content, edited_at: now, metadata },
             None,
         )
         .await;
@@ -187,12 +283,12 @@ pub async fn handle_delete_message(
 
     let _ = sqlx::query("DELETE FROM messages_fts WHERE message_id = ?")
         .bind(&message_id)
-        .execute(&state.db)
+        .execute(&state.db_write)
         .await;
 
     let _ = sqlx::query("DELETE FROM messages WHERE id = ?")
         .bind(&message_id)
-        .execute(&state.db)
+        .execute(&state.db_write)
         .await;
 
     state
@@ -215,9 +311,10 @@ pub async fn handle_typing(
     channel_id: &str,
     active: bool,
 ) {
+    let blocked = crate::routes::users::block_relations(state, &user.id).await;
     state
         .gateway
-        .broadcast_channel(
+        .broadcast_channel_except_users(
             channel_id,
             &ServerEvent::Typing {
                 channel_id: channel_id.to_string(),
@@ -225,6 +322,8 @@ pub async fn handle_typing(
                 active,
             },
             Some(client_id),
+            &blocked,
+            None,
         )
         .await;
 }
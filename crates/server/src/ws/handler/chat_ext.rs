@@ -1,8 +1,39 @@
 use crate::AppState;
-use crate::models::AuthUser;
+use crate::models::{AuthUser, CustomEmojiRef};
 use crate::ws::events::ServerEvent;
 use crate::ws::gateway::ClientId;
 
+/// Split a `:name:id` custom emoji reaction into (name, id).
+fn parse_custom_emoji(emoji: &str) -> Option<(&str, &str)> {
+    let (name, id) = emoji.strip_prefix(':')?.split_once(':')?;
+    if name.is_empty() || id.is_empty() {
+        return None;
+    }
+    Some((name, id))
+}
+
+/// The custom emoji, if it exists under that name in a server the user belongs to.
+async fn resolve_custom_emoji(
+    state: &AppState,
+    user_id: &str,
+    name: &str,
+    emoji_id: &str,
+) -> Option<CustomEmojiRef> {
+    sqlx::query_as::<_, CustomEmojiRef>(
+        r#"SELECT ce.id, ce.name, ce.attachment_id, ce.filename
+           FROM custom_emojis ce
+           INNER JOIN memberships m ON m.server_id = ce.server_id AND m.user_id = ?
+           WHERE ce.id = ? AND ce.name = ?"#,
+    )
+    .bind(user_id)
+    .bind(emoji_id)
+    .bind(name)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten()
+}
+
 pub async fn handle_add_reaction(
     state: &AppState,
     client_id: ClientId,
@@ -10,6 +41,36 @@ pub async fn handle_add_reaction(
     message_id: String,
     emoji: String,
 ) {
+    let custom_emoji = match parse_custom_emoji(&emoji) {
+        Some((name, emoji_id)) => match resolve_custom_emoji(state, &user.id, name, emoji_id).await {
+            Some(e) => Some(e),
+            None => {
+                state
+                    .gateway
+                    .send_to(client_id, &ServerEvent::Error { message: "Unknown custom emoji".into() })
+                    .await;
+                return;
+            }
+        },
+        None => None,
+    };
+
+    let author = sqlx::query_scalar::<_, String>("SELECT sender_id FROM messages WHERE id = ?")
+        .bind(&message_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten();
+    if let Some(author) = author {
+        if author != user.id && crate::routes::users::is_blocked(state, &author, &user.id).await {
+            state
+                .gateway
+                .send_to(client_id, &ServerEvent::Error { message: "You can't react to this message".into() })
+                .await;
+            return;
+        }
+    }
+
     let exists = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM reactions WHERE message_id = ? AND user_id = ? AND emoji = ?",
     )
@@ -27,7 +88,7 @@ pub async fn handle_add_reaction(
     let id = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
 
-    let _ = sqlx::query(
+    let inserted = sqlx::query(
         "INSERT INTO reactions (id, message_id, user_id, emoji, created_at) VALUES (?, ?, ?, ?, ?)",
     )
     .bind(&id)
@@ -35,8 +96,15 @@ pub async fn handle_add_reaction(
     .bind(&user.id)
     .bind(&emoji)
     .bind(&now)
-    .execute(&state.db)
-    .await;
+    .execute(&state.db_write)
+    .await
+    .is_ok();
+    if let (true, Some(custom)) = (inserted, &custom_emoji) {
+        crate::routes::emojis::record_reaction_usage(state, &custom.id).await;
+    }
+    if inserted {
+        crate::routes::servers::apply_reaction_role(state, &user.id, &message_id, &emoji, true).await;
+    }
 
     let channel_id = sqlx::query_scalar::<_, String>(
         "SELECT channel_id FROM messages WHERE id = ?",
@@ -56,13 +124,12 @@ pub async fn handle_add_reaction(
                     message_id,
                     user_id: user.id.clone(),
                     emoji,
+                    custom_emoji,
                 },
                 None,
             )
             .await;
     }
-    // suppress unused variable warning
-    let _ = client_id;
 }
 
 pub async fn handle_remove_reaction(
@@ -71,14 +138,18 @@ pub async fn handle_remove_reaction(
     message_id: String,
     emoji: String,
 ) {
-    let _ = sqlx::query(
+    let removed = sqlx::query(
         "DELETE FROM reactions WHERE message_id = ? AND user_id = ? AND emoji = ?",
     )
     .bind(&message_id)
     .bind(&user.id)
     .bind(&emoji)
-    .execute(&state.db)
-    .await;
+    .execute(&state.db_write)
+    .await
+    .is_ok_and(|r| r.rows_affected() > 0);
+    if removed {
+        crate::routes::servers::apply_reaction_role(state, &user.id, &message_id, &emoji, false).await;
+    }
 
     let channel_id = sqlx::query_scalar::<_, String>(
         "SELECT channel_id FROM messages WHERE id = ?",
@@ -107,13 +178,14 @@ pub async fn handle_remove_reaction(
 
 pub async fn handle_send_dm(
     state: &AppState,
+    client_id: ClientId,
     user: &AuthUser,
     dm_channel_id: String,
     ciphertext: String,
     mls_epoch: i64,
 ) {
-    let dm = sqlx::query_as::<_, (String, String)>(
-        "SELECT user1_id, user2_id FROM dm_channels WHERE id = ?",
+    let dm = sqlx::query_as::<_, (String, String, String, Option<String>, String)>(
+        "SELECT user1_id, user2_id, status, requested_by, created_at FROM dm_channels WHERE id = ?",
     )
     .bind(&dm_channel_id)
     .fetch_optional(&state.db)
@@ -121,7 +193,7 @@ pub async fn handle_send_dm(
     .ok()
     .flatten();
 
-    let (user1, user2) = match dm {
+    let (user1, user2, status, requested_by, channel_created_at) = match dm {
         Some(d) => d,
         None => return,
     };
@@ -129,6 +201,37 @@ pub async fn handle_send_dm(
     if user.id != user1 && user.id != user2 {
         return;
     }
+    if user1 != user2 && crate::routes::users::is_blocked(state, &user1, &user2).await {
+        state
+            .gateway
+            .send_to(client_id, &ServerEvent::Error { message: "You can't message this user".into() })
+            .await;
+        return;
+    }
+
+    // A message request carries one message until it's accepted
+    let is_request = status != "accepted";
+    if is_request {
+        let refusal = if requested_by.as_deref() != Some(user.id.as_str()) {
+            Some("Accept the message request to reply")
+        } else if status == "declined" {
+            Some("This user isn't accepting messages from you")
+        } else {
+            let sent = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM dm_messages WHERE dm_channel_id = ?")
+                .bind(&dm_channel_id)
+                .fetch_one(&state.db)
+                .await
+                .unwrap_or(1);
+            (sent > 0).then_some("Wait for them to accept your message request")
+        };
+        if let Some(message) = refusal {
+            state
+                .gateway
+                .send_to(client_id, &ServerEvent::Error { message: message.into() })
+                .await;
+            return;
+        }
+    }
 
     let id = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
@@ -143,7 +246,7 @@ pub async fn handle_send_dm(
     .bind(&ciphertext)
     .bind(mls_epoch)
     .bind(&now)
-    .execute(&state.db)
+    .execute(&state.db_write)
     .await;
 
     let message = crate::models::DmMessage {
@@ -155,11 +258,38 @@ pub async fn handle_send_dm(
         created_at: now,
     };
 
+    let other_user_id = if user.id == user1 { &user2 } else { &user1 };
+    if is_request {
+        let image = sqlx::query_scalar::<_, Option<String>>(r#"SELECT image FROM "user" WHERE id = ?"#)
+            .bind(&user.id)
+            .fetch_optional(&state.db)
+            .await
+            .ok()
+            .flatten()
+            .flatten();
+        let request = ServerEvent::DmRequest {
+            channel: crate::models::DmChannelResponse {
+                id: dm_channel_id.clone(),
+                other_user: crate::models::DmOtherUser {
+                    id: user.id.clone(),
+                    username: user.username.clone(),
+                    image,
+                },
+                created_at: channel_created_at,
+                status,
+                requested_by,
+            },
+            message: message.clone(),
+        };
+        state.gateway.send_to_user(&user.id, &ServerEvent::DmMessage { message }).await;
+        state.gateway.send_to_user(other_user_id, &request).await;
+        return;
+    }
+
     let event = ServerEvent::DmMessage { message };
 
     state.gateway.broadcast_dm(&dm_channel_id, &event).await;
 
-    let other_user_id = if user.id == user1 { &user2 } else { &user1 };
     if other_user_id != &user.id
         && !state
             .gateway
@@ -65,6 +65,10 @@ pub async fn handle_disconnect(state: &AppState, client_id: ClientId, user: &Aut
 
     if let Some(channel_id) = old_voice {
         let participants = state.gateway.voice_channel_participants(&channel_id).await;
+        if !participants.iter().any(|p| p.user_id == user.id) {
+            crate::routes::voice::end_voice_session(state, &user.id).await;
+            crate::routes::voice::stage_left(state, &channel_id, &user.id).await;
+        }
 
         if participants.is_empty() {
             let is_room = sqlx::query_scalar::<_, i64>(
@@ -85,6 +89,8 @@ pub async fn handle_disconnect(state: &AppState, client_id: ClientId, user: &Aut
             }
         }
 
+        crate::routes::spotify::hand_off_host(state, &user.id, &channel_id, &participants).await;
+
         state
             .gateway
             .broadcast_all(
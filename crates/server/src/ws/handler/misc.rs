@@ -74,31 +74,33 @@ pub async fn handle_share_server_key(
     server_id: String,
     target_user_id: String,
     encrypted_key: String,
+    device_id: Option<String>,
 ) {
     let now = chrono::Utc::now().to_rfc3339();
     let _ = sqlx::query(
-        "INSERT INTO server_keys (server_id, user_id, encrypted_key, sender_id, created_at) VALUES (?, ?, ?, ?, ?)
-         ON CONFLICT(server_id, user_id) DO UPDATE SET encrypted_key = excluded.encrypted_key, sender_id = excluded.sender_id",
+        "INSERT INTO server_keys (server_id, user_id, device_id, encrypted_key, sender_id, created_at) VALUES (?, ?, ?, ?, ?, ?)
+         ON CONFLICT(server_id, user_id, device_id) DO UPDATE SET encrypted_key = excluded.encrypted_key, sender_id = excluded.sender_id",
     )
     .bind(&server_id)
     .bind(&target_user_id)
+    .bind(device_id.as_deref().unwrap_or(""))
     .bind(&encrypted_key)
     .bind(&user.id)
     .bind(&now)
     .execute(&state.db)
     .await;
 
-    state
-        .gateway
-        .send_to_user(
-            &target_user_id,
-            &ServerEvent::ServerKeyShared {
-                server_id,
-                encrypted_key,
-                sender_id: user.id.clone(),
-            },
-        )
-        .await;
+    let event = ServerEvent::ServerKeyShared {
+        server_id,
+        encrypted_key,
+        sender_id: user.id.clone(),
+        device_id: device_id.clone(),
+    };
+    match &device_id {
+        // A key wrapped for one device is useless to the others
+        Some(device_id) => state.gateway.send_to_device(&target_user_id, device_id, &event).await,
+        None => state.gateway.send_to_user(&target_user_id, &event).await,
+    }
 }
 
 pub async fn handle_request_server_key(
@@ -113,6 +115,7 @@ pub async fn handle_request_server_key(
             &ServerEvent::ServerKeyRequested {
                 server_id,
                 user_id: user.id.clone(),
+                device_id: state.gateway.device_of(client_id).await,
             },
             Some(client_id),
         )
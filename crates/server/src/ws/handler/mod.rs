@@ -4,18 +4,20 @@ mod lifecycle;
 mod misc;
 mod voice;
 
+pub(crate) use chat::post_automated_message;
+
 use axum::{
     extract::{State, WebSocketUpgrade, ws::{Message, WebSocket}},
     response::IntoResponse,
 };
 use futures::{SinkExt, StreamExt};
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tracing::Instrument;
 
 use crate::AppState;
 use crate::models::AuthUser;
 use crate::ws::events::{ClientEvent, ServerEvent};
-use crate::ws::gateway::ClientId;
+use crate::ws::gateway::{client_channel, ClientId, CLIENT_QUEUE_CAPACITY};
 
 /// WebSocket upgrade handler
 pub async fn ws_handler(
@@ -24,15 +26,34 @@ pub async fn ws_handler(
     query: axum::extract::Query<std::collections::HashMap<String, String>>,
     headers: axum::http::HeaderMap,
 ) -> impl IntoResponse {
-    let auth_user = extract_session(&state, &headers, &query).await;
-    ws.on_upgrade(move |socket| handle_socket(socket, state, auth_user))
+    let session = extract_session(&state, &headers, &query).await;
+
+    if state.config.require_email_verification {
+        if let Some((user, _)) = &session {
+            let verified = sqlx::query_scalar::<_, bool>(r#"SELECT emailVerified FROM "user" WHERE id = ?"#)
+                .bind(&user.id)
+                .fetch_optional(&state.db)
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or(false);
+            if !verified {
+                return crate::error::ApiError::forbidden("Verify your email to connect").into_response();
+            }
+        }
+    }
+
+    let device_id = query.get("deviceId").filter(|d| !d.is_empty()).cloned();
+    ws.on_upgrade(move |socket| handle_socket(socket, state, session, device_id))
+        .into_response()
 }
 
+/// The connecting user and their session id.
 async fn extract_session(
     state: &AppState,
     headers: &axum::http::HeaderMap,
     query: &std::collections::HashMap<String, String>,
-) -> Option<AuthUser> {
+) -> Option<(AuthUser, String)> {
     let token_from_query = query.get("token").map(|t| t.as_str());
 
     let auth_header = headers.get("authorization")
@@ -64,8 +85,8 @@ async fn extract_session(
     }
     let token = token.as_str();
 
-    let row = sqlx::query_as::<_, (String, String, String)>(
-        r#"SELECT u.id, u.username, s.expiresAt
+    let row = sqlx::query_as::<_, (String, String, String, String)>(
+        r#"SELECT u.id, u.username, s.expiresAt, s.id
            FROM "session" s
            JOIN "user" u ON u.id = s.userId
            WHERE s.token = ?"#,
@@ -80,22 +101,32 @@ async fn extract_session(
         return None;
     }
 
-    Some(AuthUser {
-        id: row.0,
-        username: row.1,
-    })
+    crate::routes::auth::touch_session(state, &row.3).await;
+
+    Some((
+        AuthUser {
+            id: row.0,
+            username: row.1,
+        },
+        row.3,
+    ))
 }
 
-async fn handle_socket(socket: WebSocket, state: Arc<AppState>, auth_user: Option<AuthUser>) {
-    let user = match auth_user {
-        Some(u) => u,
+async fn handle_socket(
+    socket: WebSocket,
+    state: Arc<AppState>,
+    session: Option<(AuthUser, String)>,
+    device_id: Option<String>,
+) {
+    let (user, session_id) = match session {
+        Some(s) => s,
         None => return,
     };
 
     let client_id = state.gateway.next_client_id().await;
     let (mut ws_tx, mut ws_rx) = socket.split();
 
-    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let (tx, mut rx) = client_channel(CLIENT_QUEUE_CAPACITY);
 
     let user_status = sqlx::query_scalar::<_, String>(
         r#"SELECT status FROM "user" WHERE id = ?"#,
@@ -111,6 +142,18 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>, auth_user: Optio
         .gateway
         .register(client_id, user.id.clone(), user.username.clone(), tx, user_status.clone())
         .await;
+    state.gateway.set_session(client_id, session_id).await;
+    let closed = state.gateway.close_signal(client_id).await.unwrap_or_default();
+
+    if let Some(device_id) = device_id {
+        let _ = sqlx::query(r#"UPDATE "device_keys" SET last_seen_at = ? WHERE user_id = ? AND device_id = ?"#)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(&user.id)
+            .bind(&device_id)
+            .execute(&state.db)
+            .await;
+        state.gateway.set_device(client_id, device_id).await;
+    }
 
     // Broadcast online presence (invisible users don't broadcast)
     if user_status != "invisible" {
@@ -128,30 +171,45 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>, auth_user: Optio
 
     lifecycle::send_initial_state(&state, client_id, &user, &user_status).await;
 
-    // Task to forward messages from mpsc to WebSocket
-    let send_task = tokio::spawn(async move {
+    // Task to forward queued events to the WebSocket
+    let mut send_task = tokio::spawn(async move {
         while let Some(msg) = rx.recv().await {
-            if ws_tx.send(Message::Text(msg.into())).await.is_err() {
-                break;
+            if ws_tx.send(Message::Text(msg)).await.is_err() {
+                return;
             }
         }
+        // The gateway dropped this client (its session was revoked, or it
+        // fell too far behind)
+        let _ = ws_tx.send(Message::Close(None)).await;
     });
 
     // Receive loop
     let state_clone = state.clone();
     let user_clone = user.clone();
-    let recv_task = tokio::spawn(async move {
+    let mut recv_task = tokio::spawn(async move {
         while let Some(Ok(msg)) = ws_rx.next().await {
             match msg {
                 Message::Text(text) => {
                     let text_str: &str = &text;
-                    if let Ok(event) = serde_json::from_str::<ClientEvent>(text_str) {
+                    let Ok(value) = serde_json::from_str::<serde_json::Value>(text_str) else {
+                        continue;
+                    };
+                    let kind = value["type"].as_str().unwrap_or_default().to_string();
+                    if let Ok(event) = serde_json::from_value::<ClientEvent>(value) {
+                        crate::metrics::record_client_event(&kind);
+                        let span = tracing::info_span!(
+                            "gateway_event",
+                            otel.name = %format!("gateway {}", kind),
+                            "event.type" = %kind,
+                            user.id = %user_clone.id,
+                        );
                         handle_client_event(
                             &state_clone,
                             client_id,
                             &user_clone,
                             event,
                         )
+                        .instrument(span)
                         .await;
                     }
                 }
@@ -162,11 +220,19 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>, auth_user: Optio
     });
 
     tokio::select! {
-        _ = send_task => {},
-        _ = recv_task => {},
+        _ = &mut send_task => {},
+        _ = &mut recv_task => {},
+        _ = closed.notified() => {},
     }
+    recv_task.abort();
 
     lifecycle::handle_disconnect(&state, client_id, &user).await;
+
+    // Give the socket a moment to flush what's left and the close frame; a
+    // stalled one doesn't get to hold the task forever
+    if tokio::time::timeout(std::time::Duration::from_secs(5), &mut send_task).await.is_err() {
+        send_task.abort();
+    }
 }
 
 async fn handle_client_event(
@@ -210,17 +276,23 @@ async fn handle_client_event(
             chat_ext::handle_remove_reaction(state, user, message_id, emoji).await;
         }
         ClientEvent::SendDm { dm_channel_id, ciphertext, mls_epoch } => {
-            chat_ext::handle_send_dm(state, user, dm_channel_id, ciphertext, mls_epoch).await;
+            chat_ext::handle_send_dm(state, client_id, user, dm_channel_id, ciphertext, mls_epoch).await;
         }
         ClientEvent::VoiceStateUpdate { channel_id, action } => {
-            voice::handle_voice_state(state, client_id, &channel_id, &action).await;
+            voice::handle_voice_state(state, client_id, user, &channel_id, &action).await;
         }
         ClientEvent::VoiceDrinkUpdate { channel_id, drink_count } => {
             voice::handle_drink_update(state, user, &channel_id, drink_count).await;
         }
+        ClientEvent::SpeakingUpdate { channel_id, speaking } => {
+            state.gateway.update_speaking(client_id, &channel_id, speaking).await;
+        }
         ClientEvent::SpotifyPlaybackControl { session_id, action, track_uri, position_ms, source } => {
             voice::handle_spotify_playback(state, client_id, session_id, action, track_uri, position_ms, source).await;
         }
+        ClientEvent::MediaBufferingUpdate { session_id, buffering, position_ms } => {
+            voice::handle_media_buffering(state, client_id, session_id, buffering, position_ms).await;
+        }
         ClientEvent::PlaySound { channel_id, sound_id } => {
             voice::handle_play_sound(state, client_id, user, &channel_id, &sound_id).await;
         }
@@ -230,8 +302,8 @@ async fn handle_client_event(
         ClientEvent::UpdateStatus { status } => {
             misc::handle_update_status(state, client_id, user, status).await;
         }
-        ClientEvent::ShareServerKey { server_id, user_id: target_user_id, encrypted_key } => {
-            misc::handle_share_server_key(state, user, server_id, target_user_id, encrypted_key).await;
+        ClientEvent::ShareServerKey { server_id, user_id: target_user_id, encrypted_key, device_id } => {
+            misc::handle_share_server_key(state, user, server_id, target_user_id, encrypted_key, device_id).await;
         }
         ClientEvent::RequestServerKey { server_id } => {
             misc::handle_request_server_key(state, client_id, user, server_id).await;
@@ -239,6 +311,15 @@ async fn handle_client_event(
         ClientEvent::RoomKnock { channel_id } => {
             misc::handle_room_knock(state, user, &channel_id).await;
         }
+        ClientEvent::StageRaiseHand { channel_id, raised } => {
+            voice::handle_stage_hand(state, client_id, user, &channel_id, raised).await;
+        }
+        ClientEvent::StageSetSpeaker { channel_id, user_id: target_user_id, speaker } => {
+            voice::handle_stage_speaker(state, client_id, user, &channel_id, &target_user_id, speaker).await;
+        }
+        ClientEvent::VoiceStats { rtt, jitter, packet_loss } => {
+            voice::handle_voice_stats(state, client_id, user, rtt, jitter, packet_loss).await;
+        }
         ClientEvent::Ping => {}
     }
 }
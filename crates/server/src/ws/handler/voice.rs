@@ -1,4 +1,5 @@
 use crate::AppState;
+use crate::routes::spotify;
 use crate::models::AuthUser;
 use crate::ws::events::ServerEvent;
 use crate::ws::gateway::ClientId;
@@ -6,13 +7,26 @@ use crate::ws::gateway::ClientId;
 pub async fn handle_voice_state(
     state: &AppState,
     client_id: ClientId,
+    user: &AuthUser,
     channel_id: &str,
     action: &str,
 ) {
     match action {
         "join" => {
+            let previous = {
+                let clients = state.gateway.clients.read().await;
+                clients.get(&client_id).and_then(|c| c.voice_channel_id.clone())
+            };
             state.gateway.cancel_room_cleanup(channel_id).await;
             state.gateway.voice_join(client_id, channel_id).await;
+            crate::routes::voice::start_voice_session(state, &user.id, channel_id).await;
+            if let Some(prev) = previous.filter(|p| p != channel_id) {
+                let prev_participants = state.gateway.voice_channel_participants(&prev).await;
+                spotify::hand_off_host(state, &user.id, &prev, &prev_participants).await;
+                if !prev_participants.iter().any(|p| p.user_id == user.id) {
+                    crate::routes::voice::stage_left(state, &prev, &user.id).await;
+                }
+            }
             let participants = state.gateway.voice_channel_participants(channel_id).await;
             state
                 .gateway
@@ -29,6 +43,10 @@ pub async fn handle_voice_state(
             if let Some(left_channel) = state.gateway.voice_leave(client_id).await {
                 let participants =
                     state.gateway.voice_channel_participants(&left_channel).await;
+                if !participants.iter().any(|p| p.user_id == user.id) {
+                    crate::routes::voice::end_voice_session(state, &user.id).await;
+                    crate::routes::voice::stage_left(state, &left_channel, &user.id).await;
+                }
 
                 if participants.is_empty() {
                     let _ = sqlx::query(
@@ -56,6 +74,8 @@ pub async fn handle_voice_state(
                     }
                 }
 
+                spotify::hand_off_host(state, &user.id, &left_channel, &participants).await;
+
                 state
                     .gateway
                     .broadcast_all(
@@ -110,14 +130,37 @@ pub async fn handle_spotify_playback(
     .ok()
     .flatten();
 
-    let (_host_id, voice_channel_id) = match session {
+    let (host_id, voice_channel_id) = match session {
         Some(s) => s,
         None => return,
     };
 
+    let user_id = {
+        let clients = state.gateway.clients.read().await;
+        clients.get(&client_id).map(|c| c.user_id.clone()).unwrap_or_default()
+    };
+    let is_controller =
+        host_id == user_id || spotify::session_cohosts(&state.db, &session_id).await.contains(&user_id);
+    if !is_controller {
+        state
+            .gateway
+            .send_to(
+                client_id,
+                &ServerEvent::Error {
+                    message: "Only the host or a co-host can control playback".into(),
+                },
+            )
+            .await;
+        return;
+    }
+
     let now = chrono::Utc::now().to_rfc3339();
     match action.as_str() {
         "play" => {
+            // A specific track from the start, as opposed to resuming
+            if let (Some(uri), 0) = (&track_uri, position_ms.unwrap_or(0)) {
+                spotify::record_played(state, &session_id, uri, &source, &user_id).await;
+            }
             let _ = sqlx::query(
                 r#"UPDATE "listening_sessions" SET is_playing = 1, current_track_uri = COALESCE(?, current_track_uri), current_track_position_ms = COALESCE(?, current_track_position_ms), updated_at = ? WHERE id = ?"#,
             )
@@ -150,6 +193,19 @@ pub async fn handle_spotify_playback(
                 .await;
             }
         }
+        // Periodic host heartbeat in watch-together sessions, for drift correction
+        "sync" => {
+            if let Some(pos) = position_ms {
+                let _ = sqlx::query(
+                    r#"UPDATE "listening_sessions" SET current_track_position_ms = ?, updated_at = ? WHERE id = ?"#,
+                )
+                .bind(pos)
+                .bind(&now)
+                .bind(&session_id)
+                .execute(&state.db)
+                .await;
+            }
+        }
         "skip" => {
             let _ = sqlx::query(
                 r#"UPDATE "listening_sessions" SET current_track_uri = ?, current_track_position_ms = 0, is_playing = 1, updated_at = ? WHERE id = ?"#,
@@ -161,6 +217,7 @@ pub async fn handle_spotify_playback(
             .await;
 
             if let Some(uri) = &track_uri {
+                spotify::record_played(state, &session_id, uri, &source, &user_id).await;
                 let _ = sqlx::query(
                     r#"DELETE FROM "session_queue" WHERE session_id = ? AND track_uri = ?"#,
                 )
@@ -183,6 +240,52 @@ pub async fn handle_spotify_playback(
                 track_uri,
                 position_ms,
                 source,
+                server_time_ms: chrono::Utc::now().timestamp_millis(),
+            },
+            Some(client_id),
+        )
+        .await;
+}
+
+pub async fn handle_media_buffering(
+    state: &AppState,
+    client_id: ClientId,
+    session_id: String,
+    buffering: bool,
+    position_ms: Option<i64>,
+) {
+    let voice_channel_id = sqlx::query_scalar::<_, String>(
+        r#"SELECT voice_channel_id FROM "listening_sessions" WHERE id = ?"#,
+    )
+    .bind(&session_id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()
+    .flatten();
+
+    let Some(voice_channel_id) = voice_channel_id else { return };
+
+    let user_id = {
+        let clients = state.gateway.clients.read().await;
+        match clients.get(&client_id) {
+            Some(c) if c.voice_channel_id.as_deref() == Some(voice_channel_id.as_str()) => c.user_id.clone(),
+            _ => return,
+        }
+    };
+
+    let buffering_user_ids = state.gateway.set_buffering(client_id, &session_id, &user_id, buffering).await;
+
+    state
+        .gateway
+        .broadcast_voice_channel(
+            &voice_channel_id,
+            &ServerEvent::MediaBuffering {
+                session_id,
+                voice_channel_id: voice_channel_id.clone(),
+                user_id,
+                buffering,
+                position_ms,
+                buffering_user_ids,
             },
             Some(client_id),
         )
@@ -239,3 +342,165 @@ pub async fn handle_play_sound(
         )
         .await;
 }
+
+/// The server of `channel_id` if it's a stage.
+async fn stage_server(state: &AppState, channel_id: &str) -> Option<String> {
+    sqlx::query_scalar::<_, String>("SELECT server_id FROM channels WHERE id = ? AND type = 'stage'")
+        .bind(channel_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten()
+}
+
+pub async fn handle_stage_hand(
+    state: &AppState,
+    client_id: ClientId,
+    user: &AuthUser,
+    channel_id: &str,
+    raised: bool,
+) {
+    let Some(server_id) = stage_server(state, channel_id).await else { return };
+    let in_call = {
+        let clients = state.gateway.clients.read().await;
+        clients.get(&client_id).and_then(|c| c.voice_channel_id.clone())
+    };
+    if in_call.as_deref() != Some(channel_id) {
+        return;
+    }
+    if raised && crate::routes::voice::can_speak(state, &user.id, channel_id, &server_id).await {
+        state
+            .gateway
+            .send_to(client_id, &ServerEvent::Error { message: "You can already speak on this stage".into() })
+            .await;
+        return;
+    }
+
+    let changed = if raised {
+        sqlx::query("INSERT OR IGNORE INTO stage_hands (channel_id, user_id, raised_at) VALUES (?, ?, ?)")
+            .bind(channel_id)
+            .bind(&user.id)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .execute(&state.db)
+            .await
+    } else {
+        sqlx::query("DELETE FROM stage_hands WHERE channel_id = ? AND user_id = ?")
+            .bind(channel_id)
+            .bind(&user.id)
+            .execute(&state.db)
+            .await
+    }
+    .map(|r| r.rows_affected() > 0)
+    .unwrap_or(false);
+
+    if changed {
+        let event = ServerEvent::StageHandUpdate {
+            channel_id: channel_id.to_string(),
+            user_id: user.id.clone(),
+            raised,
+        };
+        state.gateway.broadcast_voice_channel(channel_id, &event, None).await;
+    }
+}
+
+pub async fn handle_stage_speaker(
+    state: &AppState,
+    client_id: ClientId,
+    user: &AuthUser,
+    channel_id: &str,
+    target_user_id: &str,
+    speaker: bool,
+) {
+    let Some(server_id) = stage_server(state, channel_id).await else { return };
+    let stepping_down = !speaker && target_user_id == user.id;
+    if !stepping_down && !crate::routes::voice::is_stage_moderator(state, &user.id, &server_id).await {
+        state
+            .gateway
+            .send_to(client_id, &ServerEvent::Error { message: "Only stage moderators can change speakers".into() })
+            .await;
+        return;
+    }
+
+    if !speaker {
+        let removed = sqlx::query("DELETE FROM stage_speakers WHERE channel_id = ? AND user_id = ?")
+            .bind(channel_id)
+            .bind(target_user_id)
+            .execute(&state.db)
+            .await
+            .map(|r| r.rows_affected() > 0)
+            .unwrap_or(false);
+        if removed {
+            let event = ServerEvent::StageSpeakerUpdate {
+                channel_id: channel_id.to_string(),
+                user_id: target_user_id.to_string(),
+                speaker: false,
+            };
+            state.gateway.broadcast_voice_channel(channel_id, &event, None).await;
+        }
+        return;
+    }
+
+    // Only people in the audience right now can be brought up
+    let participants = state.gateway.voice_channel_participants(channel_id).await;
+    if !participants.iter().any(|p| p.user_id == target_user_id) {
+        state
+            .gateway
+            .send_to(client_id, &ServerEvent::Error { message: "That user isn't on the stage".into() })
+            .await;
+        return;
+    }
+
+    let added = sqlx::query(
+        "INSERT OR IGNORE INTO stage_speakers (channel_id, user_id, added_by, added_at) VALUES (?, ?, ?, ?)",
+    )
+    .bind(channel_id)
+    .bind(target_user_id)
+    .bind(&user.id)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&state.db)
+    .await
+    .map(|r| r.rows_affected() > 0)
+    .unwrap_or(false);
+    let lowered = sqlx::query("DELETE FROM stage_hands WHERE channel_id = ? AND user_id = ?")
+        .bind(channel_id)
+        .bind(target_user_id)
+        .execute(&state.db)
+        .await
+        .map(|r| r.rows_affected() > 0)
+        .unwrap_or(false);
+
+    if lowered {
+        let event = ServerEvent::StageHandUpdate {
+            channel_id: channel_id.to_string(),
+            user_id: target_user_id.to_string(),
+            raised: false,
+        };
+        state.gateway.broadcast_voice_channel(channel_id, &event, None).await;
+    }
+    if added {
+        let event = ServerEvent::StageSpeakerUpdate {
+            channel_id: channel_id.to_string(),
+            user_id: target_user_id.to_string(),
+            speaker: true,
+        };
+        state.gateway.broadcast_voice_channel(channel_id, &event, None).await;
+    }
+}
+
+/// Store a call quality sample against the channel the client is in.
+/// Samples sent outside a call are ignored.
+pub async fn handle_voice_stats(
+    state: &AppState,
+    client_id: ClientId,
+    user: &AuthUser,
+    rtt: f64,
+    jitter: f64,
+    packet_loss: f64,
+) {
+    let channel_id = {
+        let clients = state.gateway.clients.read().await;
+        clients.get(&client_id).and_then(|c| c.voice_channel_id.clone())
+    };
+    let Some(channel_id) = channel_id else { return };
+    crate::routes::voice::record_voice_stats(state, &user.id, &channel_id, rtt, jitter, packet_loss).await;
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use flux_server::config::Config;
+use flux_server::middleware::ip_filter::PeerAddr;
+use flux_server::tls;
+use rustls::pki_types::{pem::PemObject, CertificateDer};
+use std::path::PathBuf;
+
+fn acme_config(test: &str) -> (Config, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("flux-acme-{}-{}", test, uuid::Uuid::new_v4()));
+    let mut config = common::test_config();
+    config.acme_domain = "localhost".into();
+    config.acme_email = "admin@test.com".into();
+    config.acme_challenge = "http-01".into();
+    config.acme_dir = dir.to_string_lossy().into_owned();
+    (config, dir)
+}
+
+/// Serve the HTTP redirect listener, which answers HTTP-01 challenges.
+async fn serve_challenges(certs: &tls::Certificates) -> u16 {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let app = tls::redirect_router(443, certs.challenges());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    port
+}
+
+/// The certificate a fresh HTTPS connection is shown.
+async fn served_certificate(port: u16) -> Vec<u8> {
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .tls_info(true)
+        .pool_max_idle_per_host(0)
+        .build()
+        .unwrap();
+    let res = client.get(format!("https://127.0.0.1:{}/", port)).send().await.unwrap();
+    res.extensions()
+        .get::<reqwest::tls::TlsInfo>()
+        .and_then(|info| info.peer_certificate())
+        .unwrap()
+        .to_vec()
+}
+
+#[tokio::test]
+async fn provisions_a_certificate_and_serves_it() {
+    let (mut config, dir) = acme_config("provision");
+
+    // Nothing to serve yet, but the server starts anyway
+    let (acceptor, certs) = tls::acceptor(&config).unwrap();
+    assert!(tls::acme::renewal_due(&config));
+    let tcp = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let https_port = tcp.local_addr().unwrap().port();
+    let listener = tls::TlsListener::new(tcp, acceptor).unwrap();
+    let app = axum::Router::new().route("/", axum::routing::get(|| async { "ok" }));
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<PeerAddr>()).await.unwrap();
+    });
+
+    let http_port = serve_challenges(&certs).await;
+    config.acme_directory_url = common::acme::start_acme(http_port).await;
+    tls::acme::provision(&config, &certs).await.unwrap();
+    assert!(!tls::acme::renewal_due(&config));
+
+    let (cert_path, _) = tls::acme::cert_paths(&config);
+    let issued = CertificateDer::from_pem_file(&cert_path).unwrap();
+    assert_eq!(served_certificate(https_port).await, issued.to_vec());
+    // Answers don't outlive the order
+    assert_eq!(certs.challenges().http("challenge-token"), None);
+
+    // Renewing keeps the same account
+    let account = std::fs::read_to_string(dir.join("account.key")).unwrap();
+    tls::acme::provision(&config, &certs).await.unwrap();
+    assert_eq!(std::fs::read_to_string(dir.join("account.key")).unwrap(), account);
+    assert_ne!(served_certificate(https_port).await, issued.to_vec());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn unanswered_challenges_leave_no_certificate() {
+    let (mut config, dir) = acme_config("unanswered");
+    let (_, certs) = tls::acceptor(&config).unwrap();
+
+    // Nothing listening where the CA looks
+    let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = closed.local_addr().unwrap().port();
+    drop(closed);
+    config.acme_directory_url = common::acme::start_acme(port).await;
+
+    let err = tls::acme::provision(&config, &certs).await.unwrap_err();
+    assert!(err.contains("wasn't validated"), "{}", err);
+    assert!(tls::acme::renewal_due(&config));
+    assert!(!std::path::Path::new(&tls::acme::cert_paths(&config).0).exists());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
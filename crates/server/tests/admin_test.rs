@@ -0,0 +1,221 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn make_instance_admin(pool: &sqlx::SqlitePool, user_id: &str) {
+    sqlx::query(r#"UPDATE "user" SET is_instance_admin = 1 WHERE id = ?"#)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn admin_routes_need_an_instance_admin() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (owner_id, token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    // Owning a server isn't enough
+    common::create_test_server(&pool, &owner_id, "flux").await;
+
+    let (h, v) = auth_header(&token);
+    server.get("/api/admin/stats").add_header(h, v).await.assert_status_forbidden();
+    server.get("/api/admin/users").await.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn first_sign_up_becomes_instance_admin() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+
+    let res = server
+        .post("/api/auth/sign-up/email")
+        .json(&json!({
+            "email": "first@test.com",
+            "password": "Tangerine-Lighthouse-42",
+            "username": "first",
+            "name": "First",
+        }))
+        .await;
+    res.assert_status_ok();
+    let token = res.json::<serde_json::Value>()["token"].as_str().unwrap().to_string();
+
+    let (h, v) = auth_header(&token);
+    server.get("/api/admin/stats").add_header(h, v).await.assert_status_ok();
+}
+
+#[tokio::test]
+async fn stats_and_user_listing() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (admin_id, token) = common::create_test_user(&pool, "admin@test.com", "admin", "password123").await;
+    common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    make_instance_admin(&pool, &admin_id).await;
+    let server_id = common::create_test_server(&pool, &admin_id, "flux").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+    common::create_test_attachment(&pool, &admin_id, "a.png", "image/png").await;
+    sqlx::query("INSERT INTO messages (id, channel_id, sender_id, content, created_at) VALUES ('m1', ?, ?, 'hi', ?)")
+        .bind(&channel_id)
+        .bind(&admin_id)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (h, v) = auth_header(&token);
+    let stats: serde_json::Value = server.get("/api/admin/stats").add_header(h.clone(), v.clone()).await.json();
+    assert_eq!(stats["users"], 2);
+    assert_eq!(stats["servers"], 1);
+    assert_eq!(stats["messages"], 1);
+    assert_eq!(stats["storage"]["attachments"], 1);
+    assert!(stats["storage"]["attachmentBytes"].as_i64().unwrap() > 0);
+    assert!(stats["storage"]["databaseBytes"].as_i64().unwrap() > 0);
+
+    let per_day: serde_json::Value = server
+        .get("/api/admin/stats/messages?days=7")
+        .add_header(h.clone(), v.clone())
+        .await
+        .json();
+    let per_day = per_day.as_array().unwrap();
+    assert_eq!(per_day.len(), 7);
+    assert_eq!(per_day[6]["day"], chrono::Utc::now().date_naive().to_string());
+    assert_eq!(per_day[6]["channelMessages"], 1);
+    assert_eq!(per_day[0]["channelMessages"], 0);
+
+    let users: serde_json::Value = server.get("/api/admin/users?q=bob").add_header(h.clone(), v.clone()).await.json();
+    assert_eq!(users["total"], 1);
+    assert_eq!(users["items"][0]["username"], "bob");
+    assert_eq!(users["items"][0]["isInstanceAdmin"], false);
+
+    let users: serde_json::Value = server.get("/api/admin/users?q=admin").add_header(h, v).await.json();
+    assert_eq!(users["items"][0]["messageCount"], 1);
+    assert!(users["items"][0]["storageBytes"].as_i64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn online_history_comes_from_samples() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (admin_id, token) = common::create_test_user(&pool, "admin@test.com", "admin", "password123").await;
+    make_instance_admin(&pool, &admin_id).await;
+
+    let now = chrono::Utc::now();
+    for (ago_hours, online) in [(48, 9), (2, 3), (1, 5)] {
+        sqlx::query("INSERT INTO online_samples (sampled_at, online_users) VALUES (?, ?)")
+            .bind((now - chrono::Duration::hours(ago_hours)).to_rfc3339())
+            .bind(online)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let (h, v) = auth_header(&token);
+    let history: serde_json::Value = server.get("/api/admin/stats/online").add_header(h, v).await.json();
+    let counts: Vec<i64> = history.as_array().unwrap().iter().map(|s| s["onlineUsers"].as_i64().unwrap()).collect();
+    assert_eq!(counts, vec![3, 5]);
+}
+
+#[tokio::test]
+async fn settings_control_registration() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (admin_id, token) = common::create_test_user(&pool, "admin@test.com", "admin", "password123").await;
+    make_instance_admin(&pool, &admin_id).await;
+    common::create_test_server(&pool, &admin_id, "flux").await;
+
+    let sign_up = |email: &str, username: &str| {
+        json!({ "email": email, "password": "Tangerine-Lighthouse-42", "username": username, "name": username })
+    };
+    let (h, v) = auth_header(&token);
+
+    let settings: serde_json::Value = server.get("/api/admin/settings").add_header(h.clone(), v.clone()).await.json();
+    assert_eq!(settings, json!({ "registrationOpen": true, "whitelistEnabled": true }));
+
+    // Whitelist mode (the default) turns away unknown emails
+    server
+        .post("/api/auth/sign-up/email")
+        .json(&sign_up("bob@test.com", "bob"))
+        .await
+        .assert_status_forbidden();
+
+    // Open registration lets anyone in
+    server
+        .patch("/api/admin/settings")
+        .add_header(h.clone(), v.clone())
+        .json(&json!({ "whitelistEnabled": false }))
+        .await
+        .assert_status_ok();
+    server
+        .post("/api/auth/sign-up/email")
+        .json(&sign_up("bob@test.com", "bob"))
+        .await
+        .assert_status_ok();
+
+    // Closed registration turns away even whitelisted emails
+    sqlx::query("INSERT INTO email_whitelist (id, email, added_by, added_at) VALUES ('w1', 'carol@test.com', ?, ?)")
+        .bind(&admin_id)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&pool)
+        .await
+        .unwrap();
+    let settings: serde_json::Value = server
+        .patch("/api/admin/settings")
+        .add_header(h, v)
+        .json(&json!({ "registrationOpen": false, "whitelistEnabled": true }))
+        .await
+        .json();
+    assert_eq!(settings, json!({ "registrationOpen": false, "whitelistEnabled": true }));
+    server
+        .post("/api/auth/sign-up/email")
+        .json(&sign_up("carol@test.com", "carol"))
+        .await
+        .assert_status_forbidden();
+}
+
+#[tokio::test]
+async fn last_instance_admin_stays() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (admin_id, token) = common::create_test_user(&pool, "admin@test.com", "admin", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    make_instance_admin(&pool, &admin_id).await;
+
+    let (h, v) = auth_header(&token);
+    server
+        .put(&format!("/api/admin/users/{}/admin", admin_id))
+        .add_header(h.clone(), v.clone())
+        .json(&json!({ "isAdmin": false }))
+        .await
+        .assert_status(StatusCode::CONFLICT);
+
+    server
+        .put(&format!("/api/admin/users/{}/admin", bob_id))
+        .add_header(h, v)
+        .json(&json!({ "isAdmin": true }))
+        .await
+        .assert_status_ok();
+
+    // With two admins, either can step down
+    let (h, v) = auth_header(&bob_token);
+    server
+        .put(&format!("/api/admin/users/{}/admin", admin_id))
+        .add_header(h.clone(), v.clone())
+        .json(&json!({ "isAdmin": false }))
+        .await
+        .assert_status_ok();
+    server
+        .put("/api/admin/users/missing/admin")
+        .add_header(h, v)
+        .json(&json!({ "isAdmin": true }))
+        .await
+        .assert_status_not_found();
+}
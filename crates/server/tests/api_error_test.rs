@@ -0,0 +1,80 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn errors_carry_a_code() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (_, token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+
+    let res = server.get("/api/games/missing").await;
+    res.assert_status_unauthorized();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["code"], "unauthorized");
+    assert_eq!(body["error"], "Not authenticated");
+
+    let (h, v) = auth_header(&token);
+    let res = server.get("/api/games/missing").add_header(h, v).await;
+    res.assert_status_not_found();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["code"], "not_found");
+    assert_eq!(body["error"], "Game not found");
+}
+
+#[tokio::test]
+async fn failed_writes_are_reported() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (owner_id, token) = common::create_test_user(&pool, "admin@test.com", "admin", "password123").await;
+    common::create_test_server(&pool, &owner_id, "flux").await;
+
+    sqlx::query("DROP TABLE email_whitelist").execute(&pool).await.unwrap();
+
+    let (h, v) = auth_header(&token);
+    let res = server
+        .post("/api/whitelist")
+        .add_header(h, v)
+        .json(&json!({ "emails": ["bob@test.com"] }))
+        .await;
+    res.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["code"], "database");
+    // The query error stays in the logs
+    assert_eq!(body["error"], "Database error");
+}
+
+#[tokio::test]
+async fn rate_limits_say_when_to_retry() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+
+    for _ in 0..5 {
+        server
+            .post("/api/auth/sign-in/email")
+            .json(&json!({ "email": "alice@test.com", "password": "wrong" }))
+            .await
+            .assert_status_unauthorized();
+    }
+
+    let res = server
+        .post("/api/auth/sign-in/email")
+        .json(&json!({ "email": "alice@test.com", "password": "wrong" }))
+        .await;
+    res.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: i64 = res.header("retry-after").to_str().unwrap().parse().unwrap();
+    assert!(retry_after > 0);
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["code"], "rate_limited");
+    assert_eq!(body["retryAfter"], retry_after);
+}
@@ -0,0 +1,181 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn setup() -> (TestServer, sqlx::SqlitePool, mpsc::UnboundedReceiver<String>) {
+    let (port, mail) = common::smtp::start_smtp().await;
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    config.smtp_host = "127.0.0.1".into();
+    config.smtp_port = port;
+    let server = TestServer::new(common::create_test_app_with_config(pool.clone(), config)).unwrap();
+    (server, pool, mail)
+}
+
+async fn next_email(mail: &mut mpsc::UnboundedReceiver<String>) -> String {
+    tokio::time::timeout(std::time::Duration::from_secs(5), mail.recv())
+        .await
+        .expect("email sent")
+        .unwrap()
+}
+
+/// The token from an emailed link, undoing any quoted-printable line wrapping.
+fn link_token(email: &str) -> String {
+    let body = email.replace("=\n", "").replace("=3D", "=");
+    let start = body.find("token=").expect("verify link in email") + "token=".len();
+    body[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect()
+}
+
+async fn session(server: &TestServer, token: &str) -> serde_json::Value {
+    let (h, v) = auth_header(token);
+    server.get("/api/auth/get-session").add_header(h, v).await.json()
+}
+
+#[tokio::test]
+async fn sign_up_sends_verification_email() {
+    let (server, _pool, mut mail) = setup().await;
+
+    let res = server
+        .post("/api/auth/sign-up/email")
+        .json(&json!({ "email": "alice@test.com", "password": "Tangerine-Lighthouse-42", "name": "Alice", "username": "alice" }))
+        .await;
+    res.assert_status_ok();
+    let token = res.json::<serde_json::Value>()["token"].as_str().unwrap().to_string();
+    assert_eq!(session(&server, &token).await["user"]["emailVerified"], false);
+
+    let email = next_email(&mut mail).await;
+    assert!(email.contains("To: alice@test.com"));
+    let link = link_token(&email);
+
+    server.get("/api/auth/verify?token=bogus").await.assert_text_contains("invalid");
+    server
+        .get(&format!("/api/auth/verify?token={}", link))
+        .await
+        .assert_text_contains("Email verified");
+    assert_eq!(session(&server, &token).await["user"]["emailVerified"], true);
+
+    // Single use, and nothing left to resend
+    server
+        .get(&format!("/api/auth/verify?token={}", link))
+        .await
+        .assert_text_contains("invalid");
+    let (h, v) = auth_header(&token);
+    server
+        .post("/api/auth/verify/resend")
+        .add_header(h, v)
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn resend_has_a_cooldown() {
+    let (server, _pool, mut mail) = setup().await;
+    let res = server
+        .post("/api/auth/sign-up/email")
+        .json(&json!({ "email": "alice@test.com", "password": "Tangerine-Lighthouse-42", "name": "Alice", "username": "alice" }))
+        .await;
+    let token = res.json::<serde_json::Value>()["token"].as_str().unwrap().to_string();
+    next_email(&mut mail).await;
+
+    let (h, v) = auth_header(&token);
+    let res = server.post("/api/auth/verify/resend").add_header(h, v).await;
+    res.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    assert!(res.json::<serde_json::Value>()["retryAfter"].as_i64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn change_email_needs_both_addresses_confirmed() {
+    let (server, pool, mut mail) = setup().await;
+    let (_, token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+
+    let change = |email: &'static str, password: &'static str| {
+        let (h, v) = auth_header(&token);
+        server
+            .post("/api/auth/change-email")
+            .add_header(h, v)
+            .json(&json!({ "newEmail": email, "password": password }))
+    };
+    change("new@test.com", "wrong").await.assert_status_unauthorized();
+    change("bob@test.com", "password123").await.assert_status(StatusCode::CONFLICT);
+    change("alice@test.com", "password123").await.assert_status_bad_request();
+
+    let res = change("New@Test.com", "password123").await;
+    res.assert_status(StatusCode::ACCEPTED);
+    assert_eq!(res.json::<serde_json::Value>()["pendingEmail"], "new@test.com");
+    assert_eq!(session(&server, &token).await["user"]["pendingEmail"], "new@test.com");
+
+    let mut links = std::collections::HashMap::new();
+    for _ in 0..2 {
+        let email = next_email(&mut mail).await;
+        let to = if email.contains("To: alice@test.com") { "old" } else { "new" };
+        links.insert(to, link_token(&email));
+    }
+
+    server
+        .get(&format!("/api/auth/verify?token={}", links["new"]))
+        .await
+        .assert_text_contains("Confirmed");
+    assert_eq!(session(&server, &token).await["user"]["email"], "alice@test.com");
+
+    server
+        .get(&format!("/api/auth/verify?token={}", links["old"]))
+        .await
+        .assert_text_contains("Email changed");
+    let user = session(&server, &token).await["user"].clone();
+    assert_eq!(user["email"], "new@test.com");
+    assert_eq!(user["emailVerified"], true);
+    assert_eq!(user["pendingEmail"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn unverified_users_cannot_connect_when_required() {
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    config.require_email_verification = true;
+    let app = common::create_test_app_with_config(pool.clone(), config);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    // Test users start unverified
+    let (alice_id, token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let url = format!("{}/gateway?token={}", base.replace("http://", "ws://"), token);
+    assert!(tokio_tungstenite::connect_async(&url).await.is_err());
+
+    sqlx::query(r#"UPDATE "user" SET emailVerified = 1 WHERE id = ?"#)
+        .bind(&alice_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(tokio_tungstenite::connect_async(&url).await.is_ok());
+}
+
+#[tokio::test]
+async fn change_email_needs_smtp() {
+    let pool = common::setup_test_db().await;
+    let (_, token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let server = TestServer::new(common::create_test_app(pool)).unwrap();
+    let (h, v) = auth_header(&token);
+    server
+        .post("/api/auth/change-email")
+        .add_header(h, v)
+        .json(&json!({ "newEmail": "new@test.com", "password": "Tangerine-Lighthouse-42" }))
+        .await
+        .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn sign_in(server: &TestServer, email: &str, password: &str) -> axum_test::TestResponse {
+    server
+        .post("/api/auth/sign-in/email")
+        .add_header(HeaderName::from_static("x-forwarded-for"), HeaderValue::from_static("203.0.113.7"))
+        .json(&json!({ "email": email, "password": password }))
+        .await
+}
+
+#[tokio::test]
+async fn repeated_failures_lock_the_account_with_backoff() {
+    let (port, mut mail) = common::smtp::start_smtp().await;
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    config.smtp_host = "127.0.0.1".into();
+    config.smtp_port = port;
+    let server = TestServer::new(common::create_test_app_with_config(pool.clone(), config)).unwrap();
+    let (owner_id, owner_token) = common::create_test_user(&pool, "admin@test.com", "admin", "password123").await;
+    common::create_test_server(&pool, &owner_id, "flux").await;
+    let (alice_id, alice_token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+
+    for _ in 0..5 {
+        sign_in(&server, "alice@test.com", "wrong").await.assert_status_unauthorized();
+    }
+
+    // Locked even with the right password
+    let res = sign_in(&server, "alice@test.com", "password123").await;
+    res.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    let retry = res.json::<serde_json::Value>()["retryAfter"].as_i64().unwrap();
+    assert!(retry > 0 && retry <= 60);
+
+    let notice = tokio::time::timeout(std::time::Duration::from_secs(5), mail.recv())
+        .await
+        .expect("lockout notice sent")
+        .unwrap();
+    assert!(notice.contains("To: alice@test.com"));
+    assert!(notice.contains("203.0.113.7"));
+
+    // The owner can see the attempts
+    let (h, v) = auth_header(&alice_token);
+    let body: serde_json::Value = server.get("/api/users/me/login-attempts").add_header(h, v).await.json();
+    assert_eq!(body["attempts"].as_array().unwrap().len(), 5);
+    assert_eq!(body["attempts"][0]["success"], false);
+    assert_eq!(body["attempts"][0]["ip"], "203.0.113.7");
+    assert!(body["lockedFor"].as_i64().unwrap() > 0);
+
+    // The next lockout lasts twice as long
+    sqlx::query(r#"UPDATE "login_lockouts" SET locked_until = '2000-01-01T00:00:00+00:00'"#)
+        .execute(&pool)
+        .await
+        .unwrap();
+    for _ in 0..5 {
+        sign_in(&server, "alice@test.com", "wrong").await.assert_status_unauthorized();
+    }
+    let res = sign_in(&server, "alice@test.com", "password123").await;
+    res.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    assert!(res.json::<serde_json::Value>()["retryAfter"].as_i64().unwrap() > 60);
+
+    // Only admins can unlock
+    let (h, v) = auth_header(&alice_token);
+    server
+        .delete(&format!("/api/users/{}/lockout", alice_id))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    let (h, v) = auth_header(&owner_token);
+    server
+        .delete(&format!("/api/users/{}/lockout", alice_id))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    sign_in(&server, "alice@test.com", "password123").await.assert_status_ok();
+}
+
+#[tokio::test]
+async fn one_address_guessing_many_accounts_is_locked() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+
+    for i in 0..20 {
+        sign_in(&server, &format!("user{}@test.com", i), "guess").await.assert_status_unauthorized();
+    }
+    sign_in(&server, "alice@test.com", "password123")
+        .await
+        .assert_status(StatusCode::TOO_MANY_REQUESTS);
+
+    // Other addresses are unaffected
+    server
+        .post("/api/auth/sign-in/email")
+        .json(&json!({ "email": "alice@test.com", "password": "password123" }))
+        .await
+        .assert_status_ok();
+
+    let unknown: i64 = sqlx::query_scalar(r#"SELECT COUNT(*) FROM "login_attempts" WHERE user_id IS NULL"#)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(unknown, 20);
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use axum_test::TestServer;
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+/// A stand-in identity provider. Every code exchanges for the same user.
+async fn start_provider(claims: serde_json::Value) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+    let discovery = json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{}/authorize", issuer),
+        "token_endpoint": format!("{}/token", issuer),
+        "userinfo_endpoint": format!("{}/userinfo", issuer),
+    });
+    let app = Router::new()
+        .route("/.well-known/openid-configuration", get(move || async move { Json(discovery) }))
+        .route("/token", post(|| async { Json(json!({"access_token": "at-123", "token_type": "Bearer"})) }))
+        .route("/userinfo", get(move || async move { Json(claims) }));
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    issuer
+}
+
+async fn setup(claims: serde_json::Value) -> (TestServer, sqlx::SqlitePool) {
+    let issuer = start_provider(claims).await;
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    config.oidc_issuer = issuer;
+    config.oidc_client_id = "flux".into();
+    config.oidc_client_secret = "secret".into();
+    config.oidc_redirect_uri = "http://127.0.0.1:3001/api/auth/oidc/callback".into();
+    let server = TestServer::new(common::create_test_app_with_config(pool.clone(), config)).unwrap();
+    (server, pool)
+}
+
+/// Start a sign-in and play the provider redirecting back. Returns the poll token.
+async fn sign_in_at_provider(server: &TestServer, token: Option<&str>) -> String {
+    let mut req = server.get("/api/auth/oidc/start");
+    if let Some(token) = token {
+        let (h, v) = auth_header(token);
+        req = req.add_header(h, v);
+    }
+    let start: serde_json::Value = req.await.json();
+    let url = url::Url::parse(start["url"].as_str().unwrap()).unwrap();
+    let query: std::collections::HashMap<_, _> = url.query_pairs().into_owned().collect();
+    assert_eq!(query["client_id"], "flux");
+    assert_eq!(query["code_challenge_method"], "S256");
+
+    let poll_token = start["pollToken"].as_str().unwrap().to_string();
+    let res = server
+        .post("/api/auth/oidc/complete")
+        .json(&json!({ "pollToken": poll_token }))
+        .await;
+    res.assert_status(StatusCode::ACCEPTED);
+
+    server
+        .get(&format!("/api/auth/oidc/callback?code=abc&state={}", query["state"]))
+        .await
+        .assert_status_ok();
+    poll_token
+}
+
+#[tokio::test]
+async fn oidc_provisions_whitelisted_user() {
+    let (server, pool) = setup(json!({
+        "sub": "idp-1",
+        "email": "Carol@Example.com",
+        "email_verified": true,
+        "preferred_username": "carol",
+    }))
+    .await;
+    let (alice_id, _) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+
+    // Not whitelisted yet
+    let poll = sign_in_at_provider(&server, None).await;
+    let res = server.post("/api/auth/oidc/complete").json(&json!({ "pollToken": poll })).await;
+    res.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(res.json::<serde_json::Value>()["error"], "Email not authorized");
+
+    sqlx::query("INSERT INTO email_whitelist (id, email, added_by, added_at) VALUES ('w1', 'carol@example.com', ?, '2024-01-01')")
+        .bind(&alice_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let poll = sign_in_at_provider(&server, None).await;
+    let res = server.post("/api/auth/oidc/complete").json(&json!({ "pollToken": poll })).await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["user"]["email"], "carol@example.com");
+    assert_eq!(body["user"]["username"], "carol");
+    let token = body["token"].as_str().unwrap();
+
+    let (h, v) = auth_header(token);
+    let session: serde_json::Value = server.get("/api/auth/get-session").add_header(h, v).await.json();
+    assert_eq!(session["user"]["username"], "carol");
+
+    // The poll token is single use
+    server
+        .post("/api/auth/oidc/complete")
+        .json(&json!({ "pollToken": poll }))
+        .await
+        .assert_status_not_found();
+
+    // Signing in again finds the linked account rather than creating another
+    let poll = sign_in_at_provider(&server, None).await;
+    let body: serde_json::Value = server.post("/api/auth/oidc/complete").json(&json!({ "pollToken": poll })).await.json();
+    assert_eq!(body["user"]["id"], session["user"]["id"]);
+    let users: i64 = sqlx::query_scalar(r#"SELECT COUNT(*) FROM "user""#).fetch_one(&pool).await.unwrap();
+    assert_eq!(users, 2);
+}
+
+#[tokio::test]
+async fn oidc_links_existing_accounts() {
+    let (server, pool) = setup(json!({
+        "sub": "idp-2",
+        "email": "alice@test.com",
+        "email_verified": false,
+    }))
+    .await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+
+    // An unverified email doesn't take over the existing account
+    let poll = sign_in_at_provider(&server, None).await;
+    server
+        .post("/api/auth/oidc/complete")
+        .json(&json!({ "pollToken": poll }))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    // Linking from a signed-in session does
+    let poll = sign_in_at_provider(&server, Some(&alice_token)).await;
+    let body: serde_json::Value = server.post("/api/auth/oidc/complete").json(&json!({ "pollToken": poll })).await.json();
+    assert_eq!(body["linked"], true);
+
+    let poll = sign_in_at_provider(&server, None).await;
+    let body: serde_json::Value = server.post("/api/auth/oidc/complete").json(&json!({ "pollToken": poll })).await.json();
+    assert_eq!(body["user"]["id"], alice_id.as_str());
+
+    let (h, v) = auth_header(&alice_token);
+    server.delete("/api/auth/oidc").add_header(h, v).await.assert_status(StatusCode::NO_CONTENT);
+    let linked: i64 = sqlx::query_scalar(r#"SELECT COUNT(*) FROM "account" WHERE providerId = 'oidc'"#)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(linked, 0);
+}
+
+#[tokio::test]
+async fn oidc_start_requires_configuration() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool)).unwrap();
+    server
+        .get("/api/auth/oidc/start")
+        .await
+        .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+}
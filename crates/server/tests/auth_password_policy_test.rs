@@ -0,0 +1,79 @@
+mod common;
+
+use axum_test::TestServer;
+use serde_json::json;
+
+async fn sign_up(server: &TestServer, password: &str) -> axum_test::TestResponse {
+    server
+        .post("/api/auth/sign-up/email")
+        .json(&json!({
+            "email": "alice@test.com",
+            "password": password,
+            "name": "Alice",
+            "username": "alice"
+        }))
+        .await
+}
+
+#[tokio::test]
+async fn weak_passwords_are_rejected_with_feedback() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool)).unwrap();
+
+    let res = sign_up(&server, "short").await;
+    res.assert_status_bad_request();
+    assert_eq!(res.json::<serde_json::Value>()["error"], "Password must be at least 8 characters");
+
+    for (password, warning) in [
+        ("password123", "This is a commonly used password"),
+        ("p@ssw0rd!!", "This is a commonly used password"),
+        ("alice2024alice", "Passwords containing your name, username or email are easy to guess"),
+        ("qwertyuiop[]", "Keyboard patterns like qwerty are easy to guess"),
+        ("abcdefghijkl", "Sequences like abc or 123 are easy to guess"),
+        ("zzzzzzzzzzzz", "Repeated characters like aaa are easy to guess"),
+    ] {
+        let res = sign_up(&server, password).await;
+        res.assert_status_bad_request();
+        let body: serde_json::Value = res.json();
+        assert_eq!(body["error"], "Password is too weak", "{}", password);
+        assert_eq!(body["warning"], warning, "{}", password);
+        assert!(body["score"].as_u64().unwrap() < 3);
+        assert!(!body["suggestions"].as_array().unwrap().is_empty());
+    }
+
+    sign_up(&server, "Tangerine-Lighthouse-42").await.assert_status_ok();
+}
+
+#[tokio::test]
+async fn sign_in_upgrades_old_hashes() {
+    let pool = common::setup_test_db().await;
+    let (alice_id, _) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let mut config = common::test_config();
+    config.argon2_iterations = 3;
+    let server = TestServer::new(common::create_test_app_with_config(pool.clone(), config)).unwrap();
+
+    let stored = || async {
+        sqlx::query_scalar::<_, String>(r#"SELECT password FROM "account" WHERE userId = ?"#)
+            .bind(&alice_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+    };
+    assert!(stored().await.contains("t=2"));
+
+    server
+        .post("/api/auth/sign-in/email")
+        .json(&json!({ "email": "alice@test.com", "password": "password123" }))
+        .await
+        .assert_status_ok();
+    let upgraded = stored().await;
+    assert!(upgraded.starts_with("$argon2id$") && upgraded.contains("t=3"));
+
+    // The old password still works against the new hash
+    server
+        .post("/api/auth/sign-in/email")
+        .json(&json!({ "email": "alice@test.com", "password": "password123" }))
+        .await
+        .assert_status_ok();
+    assert_eq!(stored().await, upgraded);
+}
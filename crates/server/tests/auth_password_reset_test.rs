@@ -0,0 +1,142 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn setup() -> (TestServer, sqlx::SqlitePool, mpsc::UnboundedReceiver<String>) {
+    let (port, mail) = common::smtp::start_smtp().await;
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    config.smtp_host = "127.0.0.1".into();
+    config.smtp_port = port;
+    let server = TestServer::new(common::create_test_app_with_config(pool.clone(), config)).unwrap();
+    (server, pool, mail)
+}
+
+/// The reset code from an email: the indented line on its own.
+fn reset_code(email: &str) -> String {
+    email
+        .lines()
+        .find(|l| l.starts_with("    ") && l.trim().len() > 20)
+        .expect("reset code in email")
+        .trim()
+        .to_string()
+}
+
+#[tokio::test]
+async fn reset_password_with_emailed_token() {
+    let (server, pool, mut mail) = setup().await;
+    let (_, old_token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+
+    // Unknown emails get the same answer and no email
+    server
+        .post("/api/auth/forgot-password")
+        .json(&json!({ "email": "nobody@test.com" }))
+        .await
+        .assert_status(StatusCode::ACCEPTED);
+
+    server
+        .post("/api/auth/forgot-password")
+        .json(&json!({ "email": "Alice@Test.com" }))
+        .await
+        .assert_status(StatusCode::ACCEPTED);
+    let email = tokio::time::timeout(std::time::Duration::from_secs(5), mail.recv())
+        .await
+        .expect("reset email sent")
+        .unwrap();
+    assert!(email.contains("To: alice@test.com"));
+    let code = reset_code(&email);
+
+    // Only the HMAC is stored
+    let stored: String = sqlx::query_scalar(r#"SELECT token_hash FROM "password_resets""#)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_ne!(stored, code);
+
+    server
+        .post("/api/auth/reset-password")
+        .json(&json!({ "token": "not-the-code", "newPassword": "Velvet-Canyon-Oboe-88" }))
+        .await
+        .assert_status_bad_request();
+    // A weak password doesn't spend the token
+    let res = server
+        .post("/api/auth/reset-password")
+        .json(&json!({ "token": code, "newPassword": "alice12345" }))
+        .await;
+    res.assert_status_bad_request();
+    assert_eq!(res.json::<serde_json::Value>()["error"], "Password is too weak");
+    server
+        .post("/api/auth/reset-password")
+        .json(&json!({ "token": code, "newPassword": "Velvet-Canyon-Oboe-88" }))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    // Existing sessions are signed out
+    let (h, v) = auth_header(&old_token);
+    server.get("/api/auth/sessions").add_header(h, v).await.assert_status_unauthorized();
+
+    server
+        .post("/api/auth/sign-in/email")
+        .json(&json!({ "email": "alice@test.com", "password": "password123" }))
+        .await
+        .assert_status_unauthorized();
+    server
+        .post("/api/auth/sign-in/email")
+        .json(&json!({ "email": "alice@test.com", "password": "Velvet-Canyon-Oboe-88" }))
+        .await
+        .assert_status_ok();
+
+    // Single use
+    server
+        .post("/api/auth/reset-password")
+        .json(&json!({ "token": code, "newPassword": "Copper-Meadow-Lantern-5" }))
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn expired_reset_token_is_rejected() {
+    let (server, pool, mut mail) = setup().await;
+    common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+
+    server
+        .post("/api/auth/forgot-password")
+        .json(&json!({ "email": "alice@test.com" }))
+        .await
+        .assert_status(StatusCode::ACCEPTED);
+    let email = tokio::time::timeout(std::time::Duration::from_secs(5), mail.recv())
+        .await
+        .unwrap()
+        .unwrap();
+
+    sqlx::query(r#"UPDATE "password_resets" SET expires_at = '2000-01-01T00:00:00+00:00'"#)
+        .execute(&pool)
+        .await
+        .unwrap();
+    server
+        .post("/api/auth/reset-password")
+        .json(&json!({ "token": reset_code(&email), "newPassword": "Velvet-Canyon-Oboe-88" }))
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn forgot_password_needs_smtp() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool)).unwrap();
+    server
+        .post("/api/auth/forgot-password")
+        .json(&json!({ "email": "alice@test.com" }))
+        .await
+        .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+}
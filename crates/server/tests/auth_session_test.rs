@@ -162,3 +162,71 @@ async fn get_session_with_expired_token_returns_null() {
     let body: serde_json::Value = res.json();
     assert!(body.is_null());
 }
+
+#[tokio::test]
+async fn list_and_revoke_sessions() {
+    let (server, pool) = setup().await;
+
+    let (_, first) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+
+    let res = server
+        .post("/api/auth/sign-in/email")
+        .add_header(HeaderName::from_static("user-agent"), HeaderValue::from_static("FluxTest/1.0"))
+        .add_header(HeaderName::from_static("x-forwarded-for"), HeaderValue::from_static("203.0.113.7, 10.0.0.1"))
+        .json(&json!({ "email": "alice@test.com", "password": "password123" }))
+        .await;
+    let second = res.json::<serde_json::Value>()["token"].as_str().unwrap().to_string();
+    let res = server
+        .post("/api/auth/sign-in/email")
+        .json(&json!({ "email": "alice@test.com", "password": "password123" }))
+        .await;
+    let third = res.json::<serde_json::Value>()["token"].as_str().unwrap().to_string();
+
+    let (h, v) = auth_header(&second);
+    let sessions: Vec<serde_json::Value> = server.get("/api/auth/sessions").add_header(h, v).await.json();
+    assert_eq!(sessions.len(), 3);
+    let current: Vec<_> = sessions.iter().filter(|s| s["current"] == true).collect();
+    assert_eq!(current.len(), 1);
+    assert_eq!(current[0]["userAgent"], "FluxTest/1.0");
+    assert_eq!(current[0]["ipAddress"], "203.0.113.7");
+    assert!(sessions.iter().all(|s| s.get("token").is_none()));
+
+    // Revoke the third session by id
+    let third_id: String = sqlx::query_scalar(r#"SELECT id FROM "session" WHERE token = ?"#)
+        .bind(&third)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let (h, v) = auth_header(&second);
+    server
+        .delete(&format!("/api/auth/sessions/{}", third_id))
+        .add_header(h, v)
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    let (h, v) = auth_header(&third);
+    server.get("/api/auth/sessions").add_header(h, v).await.assert_status_unauthorized();
+
+    // Another user can't revoke alice's sessions
+    let (_, bob) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let first_id: String = sqlx::query_scalar(r#"SELECT id FROM "session" WHERE token = ?"#)
+        .bind(&first)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let (h, v) = auth_header(&bob);
+    server
+        .delete(&format!("/api/auth/sessions/{}", first_id))
+        .add_header(h, v)
+        .await
+        .assert_status_not_found();
+
+    let (h, v) = auth_header(&second);
+    let res: serde_json::Value = server.post("/api/auth/sessions/revoke-others").add_header(h, v).await.json();
+    assert_eq!(res["revoked"], 1);
+
+    let (h, v) = auth_header(&second);
+    let sessions: Vec<serde_json::Value> = server.get("/api/auth/sessions").add_header(h, v).await.json();
+    assert_eq!(sessions.len(), 1);
+    let (h, v) = auth_header(&first);
+    server.get("/api/auth/sessions").add_header(h, v).await.assert_status_unauthorized();
+}
@@ -1,16 +1,8 @@
 mod common;
 
-use axum::http::{HeaderName, HeaderValue};
 use axum_test::TestServer;
 use serde_json::json;
 
-fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
-    (
-        HeaderName::from_static("authorization"),
-        format!("Bearer {}", token).parse().unwrap(),
-    )
-}
-
 async fn setup() -> (TestServer, sqlx::SqlitePool) {
     let pool = common::setup_test_db().await;
     let app = common::create_test_app(pool.clone());
@@ -26,7 +18,7 @@ async fn sign_up_first_user_bypasses_whitelist() {
         .post("/api/auth/sign-up/email")
         .json(&json!({
             "email": "alice@test.com",
-            "password": "password123",
+            "password": "Tangerine-Lighthouse-42",
             "name": "Alice",
             "username": "alice"
         }))
@@ -50,7 +42,7 @@ async fn sign_up_second_user_rejected_without_whitelist() {
         .post("/api/auth/sign-up/email")
         .json(&json!({
             "email": "bob@test.com",
-            "password": "password123",
+            "password": "Tangerine-Lighthouse-42",
             "name": "Bob",
             "username": "bob"
         }))
@@ -82,7 +74,7 @@ async fn sign_up_second_user_succeeds_with_whitelist() {
         .post("/api/auth/sign-up/email")
         .json(&json!({
             "email": "bob@test.com",
-            "password": "password123",
+            "password": "Tangerine-Lighthouse-42",
             "name": "Bob",
             "username": "bob"
         }))
@@ -102,7 +94,7 @@ async fn sign_up_duplicate_email_returns_409() {
         .post("/api/auth/sign-up/email")
         .json(&json!({
             "email": "alice@test.com",
-            "password": "password123",
+            "password": "Tangerine-Lighthouse-42",
             "name": "Alice",
             "username": "alice"
         }))
@@ -128,7 +120,7 @@ async fn sign_up_duplicate_email_returns_409() {
         .post("/api/auth/sign-up/email")
         .json(&json!({
             "email": "alice@test.com",
-            "password": "password456",
+            "password": "Marmalade-Quarry-17",
             "name": "Alice2",
             "username": "alice2"
         }))
@@ -160,7 +152,7 @@ async fn sign_up_duplicate_username_returns_409() {
         .post("/api/auth/sign-up/email")
         .json(&json!({
             "email": "bob@test.com",
-            "password": "password123",
+            "password": "Tangerine-Lighthouse-42",
             "name": "Bob",
             "username": "alice"
         }))
@@ -179,7 +171,7 @@ async fn sign_up_short_username_returns_400() {
         .post("/api/auth/sign-up/email")
         .json(&json!({
             "email": "a@test.com",
-            "password": "password123",
+            "password": "Tangerine-Lighthouse-42",
             "name": "A",
             "username": "a"
         }))
@@ -0,0 +1,159 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use base64::Engine;
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+fn data_url(mime: &str, bytes: &[u8]) -> String {
+    format!("data:{};base64,{}", mime, base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+fn decode_data_url(url: &str) -> image::DynamicImage {
+    let (_, payload) = url.split_once(',').unwrap();
+    let bytes = base64::engine::general_purpose::STANDARD.decode(payload).unwrap();
+    image::load_from_memory(&bytes).unwrap()
+}
+
+/// A `width`x`height` GIF with `frames` frames over a four colour palette.
+/// Each pixel is coded as a clear code plus a literal, so the LZW stream
+/// never needs a dictionary.
+fn gif(width: u16, height: u16, frames: usize) -> Vec<u8> {
+    let mut out = b"GIF89a".to_vec();
+    out.extend(width.to_le_bytes());
+    out.extend(height.to_le_bytes());
+    out.extend([0x81, 0, 0]);
+    out.extend([255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255]);
+    for frame in 0..frames {
+        out.extend([0x2C, 0, 0, 0, 0]);
+        out.extend(width.to_le_bytes());
+        out.extend(height.to_le_bytes());
+        out.extend([0, 2]);
+
+        let mut codes = vec![];
+        for i in 0..width as usize * height as usize {
+            codes.extend([4, ((i + frame) % 4) as u32]);
+        }
+        codes.push(5);
+        let (mut data, mut acc, mut bits) = (Vec::new(), 0u32, 0);
+        for code in codes {
+            acc |= code << bits;
+            bits += 3;
+            while bits >= 8 {
+                data.push(acc as u8);
+                acc >>= 8;
+                bits -= 8;
+            }
+        }
+        if bits > 0 {
+            data.push(acc as u8);
+        }
+        for block in data.chunks(255) {
+            out.push(block.len() as u8);
+            out.extend(block);
+        }
+        out.push(0);
+    }
+    out.push(0x3B);
+    out
+}
+
+async fn set_avatar(server: &TestServer, token: &str, image: &str) -> axum_test::TestResponse {
+    let (h, v) = auth_header(token);
+    server.patch("/api/users/me").add_header(h, v).json(&json!({ "image": image })).await
+}
+
+#[tokio::test]
+async fn animated_avatar_keeps_a_static_first_frame() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (user_id, token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let server_id = common::create_test_server(&pool, &user_id, "flux").await;
+
+    let animated = data_url("image/gif", &gif(4, 3, 5));
+    let res = set_avatar(&server, &token, &animated).await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["image"], animated);
+    let still = body["imageStatic"].as_str().unwrap().to_string();
+    assert!(still.starts_with("data:image/png;base64,"));
+    let frame = decode_data_url(&still).to_rgba8();
+    assert_eq!(frame.dimensions(), (4, 3));
+    assert_eq!(frame.get_pixel(0, 0).0, [255, 0, 0, 255]);
+    assert_eq!(frame.get_pixel(1, 0).0, [0, 255, 0, 255]);
+
+    // Lists animate by default, and serve the still when asked
+    let path = format!("/api/servers/{}/members", server_id);
+    let (h, v) = auth_header(&token);
+    let members: serde_json::Value = server.get(&path).add_header(h, v).await.json();
+    assert_eq!(members["items"][0]["image"], animated);
+    let (h, v) = auth_header(&token);
+    let members: serde_json::Value =
+        server.get(&format!("{}?staticAvatars=true", path)).add_header(h, v).await.json();
+    assert_eq!(members["items"][0]["image"], still);
+
+    let (h, v) = auth_header(&token);
+    let profile: serde_json::Value = server
+        .get(&format!("/api/users/{}/profile?staticAvatars=true", user_id))
+        .add_header(h, v)
+        .await
+        .json();
+    assert_eq!(profile["image"], still);
+
+    // Removing the avatar removes both
+    let (h, v) = auth_header(&token);
+    let body: serde_json::Value =
+        server.patch("/api/users/me").add_header(h, v).json(&json!({ "image": null })).await.json();
+    assert!(body["image"].is_null());
+    assert!(body["imageStatic"].is_null());
+}
+
+#[tokio::test]
+async fn invalid_avatars_are_rejected() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (_, token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+
+    let too_many_frames = data_url("image/gif", &gif(1, 1, 301));
+    let too_big_to_animate = data_url("image/gif", &gif(600, 2, 2));
+    for image in [
+        "https://example.com/me.png".to_string(),
+        data_url("image/png", b"definitely not a png"),
+        data_url("image/svg+xml", b"<svg></svg>"),
+        too_many_frames,
+        too_big_to_animate,
+    ] {
+        set_avatar(&server, &token, &image).await.assert_status(StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn oversized_avatars_are_scaled_or_stilled() {
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    config.avatar_max_bytes = 4_096;
+    let server = TestServer::new(common::create_test_app_with_config(pool.clone(), config)).unwrap();
+    let (_, token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+
+    // A large static image is scaled down to 512px
+    let mut png = std::io::Cursor::new(Vec::new());
+    image::DynamicImage::new_rgb8(1024, 768).write_to(&mut png, image::ImageFormat::Png).unwrap();
+    let body: serde_json::Value = set_avatar(&server, &token, &data_url("image/png", png.get_ref())).await.json();
+    let image = decode_data_url(body["image"].as_str().unwrap());
+    assert_eq!((image.width(), image.height()), (512, 384));
+    assert!(body["imageStatic"].is_null());
+
+    // An animation over the budget keeps only its first frame
+    let heavy = gif(32, 32, 20);
+    assert!(heavy.len() > 4_096);
+    let body: serde_json::Value = set_avatar(&server, &token, &data_url("image/gif", &heavy)).await.json();
+    assert!(body["image"].as_str().unwrap().starts_with("data:image/png;base64,"));
+    assert!(body["imageStatic"].is_null());
+}
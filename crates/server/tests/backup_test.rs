@@ -0,0 +1,154 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue};
+use axum_test::TestServer;
+use flux_server::config::Config;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+/// A config with its own directory for the database and its backups. The
+/// database is a file: `VACUUM INTO` can't copy an in-memory one out.
+fn config_in(dir: &str) -> Config {
+    let dir = format!("/tmp/flux-test-backups-{}-{}", dir, uuid::Uuid::new_v4());
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut config = common::test_config();
+    config.database_path = format!("{}/flux.db", dir);
+    config.backup_dir = dir;
+    config
+}
+
+async fn open_db(config: &Config) -> sqlx::SqlitePool {
+    flux_server::db::init_pool(&config.database_path).await.unwrap()
+}
+
+async fn user_count(pool: &sqlx::SqlitePool) -> i64 {
+    sqlx::query_scalar(r#"SELECT COUNT(*) FROM "user""#).fetch_one(pool).await.unwrap()
+}
+
+#[tokio::test]
+async fn backups_are_created_listed_and_pruned() {
+    let mut config = config_in("prune");
+    config.backup_keep = 2;
+    let pool = open_db(&config).await;
+    let dir = config.backup_dir.clone();
+    let server = TestServer::new(common::create_test_app_with_config(pool.clone(), config)).unwrap();
+    let (admin_id, token) = common::create_test_user(&pool, "admin@test.com", "admin", "password123").await;
+    let (_, member_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    sqlx::query(r#"UPDATE "user" SET is_instance_admin = 1 WHERE id = ?"#)
+        .bind(&admin_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (h, v) = auth_header(&member_token);
+    server.post("/api/admin/backup").add_header(h, v).await.assert_status_forbidden();
+
+    let (h, v) = auth_header(&token);
+    let mut names = Vec::new();
+    for _ in 0..3 {
+        let res = server.post("/api/admin/backup").add_header(h.clone(), v.clone()).await;
+        res.assert_status_ok();
+        let body: serde_json::Value = res.json();
+        assert!(body["size"].as_u64().unwrap() > 0);
+        names.push(body["name"].as_str().unwrap().to_string());
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+
+    // Only the newest two are kept
+    let listed: serde_json::Value = server.get("/api/admin/backups").add_header(h, v).await.json();
+    let listed: Vec<&str> = listed.as_array().unwrap().iter().map(|b| b["name"].as_str().unwrap()).collect();
+    assert_eq!(listed, vec![names[2].as_str(), names[1].as_str()]);
+    assert!(!std::path::Path::new(&dir).join(&names[0]).exists());
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[tokio::test]
+async fn online_restore_replaces_the_data() {
+    let config = config_in("online");
+    let pool = open_db(&config).await;
+    let dir = config.backup_dir.clone();
+    let server = TestServer::new(common::create_test_app_with_config(pool.clone(), config)).unwrap();
+    let (admin_id, token) = common::create_test_user(&pool, "admin@test.com", "admin", "password123").await;
+    sqlx::query(r#"UPDATE "user" SET is_instance_admin = 1 WHERE id = ?"#)
+        .bind(&admin_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO messages_fts (message_id, plaintext) VALUES ('m1', 'hello there')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (h, v) = auth_header(&token);
+    let backup: serde_json::Value = server.post("/api/admin/backup").add_header(h.clone(), v.clone()).await.json();
+    let name = backup["name"].as_str().unwrap();
+
+    // Changes after the backup...
+    common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    sqlx::query("DELETE FROM messages_fts").execute(&pool).await.unwrap();
+    assert_eq!(user_count(&pool).await, 2);
+
+    server
+        .post("/api/admin/backups/..%2Fflux.db/restore")
+        .add_header(h.clone(), v.clone())
+        .await
+        .assert_status_bad_request();
+
+    let res = server
+        .post(&format!("/api/admin/backups/{}/restore", name))
+        .add_header(h.clone(), v.clone())
+        .await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    let previous = body["previous"]["name"].as_str().unwrap().to_string();
+    assert!(previous.contains("pre-restore"));
+
+    // ...are gone, and search works off the restored index
+    assert_eq!(user_count(&pool).await, 1);
+    let hits: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM messages_fts WHERE messages_fts MATCH 'hello'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(hits, 1);
+
+    // The pre-restore backup undoes it
+    server
+        .post(&format!("/api/admin/backups/{}/restore", previous))
+        .add_header(h, v)
+        .await
+        .assert_status_ok();
+    assert_eq!(user_count(&pool).await, 2);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[tokio::test]
+async fn startup_restore_replaces_the_database_file() {
+    let config = config_in("startup");
+    let dir = config.backup_dir.clone();
+
+    let pool = open_db(&config).await;
+    common::create_test_user(&pool, "admin@test.com", "admin", "password123").await;
+    flux_server::backup::create_backup(&pool, &config, "manual").await.unwrap();
+    common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    pool.close().await;
+
+    let err = flux_server::backup::restore_at_startup(&config, "flux-missing.db").await.unwrap_err();
+    assert!(err.contains("not found"), "{}", err);
+
+    flux_server::backup::restore_at_startup(&config, "latest").await.unwrap();
+    let pool = open_db(&config).await;
+    assert_eq!(user_count(&pool).await, 1);
+    pool.close().await;
+
+    // What was there before is kept alongside
+    assert!(std::path::Path::new(&format!("{}.pre-restore", config.database_path)).exists());
+
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,186 @@
+mod common;
+
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use serde_json::json;
+
+#[tokio::test]
+async fn blocking_cuts_off_dms_invites_and_games() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let room_id = common::create_room(&pool, &server_id, "hangout", &bob_id).await;
+    let client = reqwest::Client::new();
+
+    // Bob's request is waiting when Alice blocks him
+    let res = client
+        .post(format!("{}/api/dms", base))
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .json(&json!({"userId": alice_id}))
+        .send()
+        .await
+        .unwrap();
+    let dm: serde_json::Value = res.json().await.unwrap();
+    let dm_id = dm["id"].as_str().unwrap().to_string();
+
+    let res = client
+        .put(format!("{}/api/users/me/blocks/{}", base, bob_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 204);
+
+    let res = client
+        .get(format!("{}/api/users/me/blocks", base))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+    let blocks: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(blocks[0]["userId"], bob_id);
+    assert_eq!(blocks[0]["username"], "bob");
+
+    let res = client
+        .get(format!("{}/api/dms/requests", base))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+    let requests: serde_json::Value = res.json().await.unwrap();
+    assert!(requests.as_array().unwrap().is_empty());
+
+    // Neither side can reach the other
+    let mut bob_ws = ws_connect(&base, &bob_token).await;
+    drain_messages(&mut bob_ws).await;
+    send_json(&mut bob_ws, &json!({"type": "send_dm", "dmChannelId": dm_id, "ciphertext": "aGk=", "mlsEpoch": 0})).await;
+    let events = drain_messages(&mut bob_ws).await;
+    assert!(events.iter().any(|e| e["type"] == "error"));
+
+    let res = client
+        .post(format!("{}/api/dms", base))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"userId": bob_id}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 403);
+
+    let res = client
+        .post(format!("{}/api/servers/{}/rooms/{}/invite", base, server_id, room_id))
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .json(&json!({"userId": alice_id}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 403);
+
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+    for id in [&alice_id, &bob_id] {
+        sqlx::query(r#"INSERT INTO "wallets" (user_id, balance, updated_at) VALUES (?, 100, ?)"#)
+            .bind(id)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    let res = client
+        .post(format!("{}/api/games/coinflip", base))
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .json(&json!({"channelId": channel_id, "wager": 10}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 201);
+    let game: serde_json::Value = res.json().await.unwrap();
+    let res = client
+        .post(format!("{}/api/games/coinflip/{}/accept", base, game["id"].as_str().unwrap()))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 403);
+
+    // Unblocking lets the conversation start again
+    let res = client
+        .delete(format!("{}/api/users/me/blocks/{}", base, bob_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 204);
+    let res = client
+        .post(format!("{}/api/dms", base))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"userId": bob_id}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+}
+
+#[tokio::test]
+async fn blocked_users_lose_reactions_typing_and_mentions() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+    sqlx::query("INSERT INTO messages (id, channel_id, sender_id, content, created_at) VALUES ('m1', ?, ?, 'hello', ?)")
+        .bind(&channel_id)
+        .bind(&alice_id)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .put(format!("{}/api/users/me/blocks/{}", base, bob_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 204);
+
+    let mut alice_ws = ws_connect(&base, &alice_token).await;
+    let mut bob_ws = ws_connect(&base, &bob_token).await;
+    for ws in [&mut alice_ws, &mut bob_ws] {
+        drain_messages(ws).await;
+        send_json(ws, &json!({"type": "join_channel", "channelId": channel_id})).await;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    drain_messages(&mut alice_ws).await;
+    drain_messages(&mut bob_ws).await;
+
+    send_json(&mut bob_ws, &json!({"type": "add_reaction", "messageId": "m1", "emoji": "👎"})).await;
+    let events = drain_messages(&mut bob_ws).await;
+    assert!(events.iter().any(|e| e["type"] == "error"));
+    let reactions: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM reactions WHERE message_id = 'm1'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(reactions, 0);
+
+    send_json(&mut bob_ws, &json!({"type": "typing_start", "channelId": channel_id})).await;
+    send_json(&mut bob_ws, &json!({"type": "send_message", "channelId": channel_id, "content": "hey @alice @everyone"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    // Alice still sees the message, but it doesn't ping her
+    let events = drain_messages(&mut alice_ws).await;
+    assert!(!events.iter().any(|e| e["type"] == "typing"));
+    let message = events.iter().find(|e| e["type"] == "message").expect("message event");
+    assert_eq!(message["message"]["metadata"]["mentions"], json!([]));
+    assert_eq!(message["message"]["metadata"]["mentionsEveryone"], false);
+
+    let events = drain_messages(&mut bob_ws).await;
+    let message = events.iter().find(|e| e["type"] == "message").expect("message event");
+    assert_eq!(message["message"]["metadata"]["mentions"], json!(["alice"]));
+}
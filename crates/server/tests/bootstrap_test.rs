@@ -0,0 +1,93 @@
+mod common;
+
+use axum_test::TestServer;
+use flux_server::bootstrap;
+use serde_json::json;
+
+const PASSWORD: &str = "correct horse battery staple";
+
+fn admin_config() -> flux_server::config::Config {
+    let mut config = common::test_config();
+    config.admin_email = "Owner@Example.com".into();
+    config.admin_password = PASSWORD.into();
+    config.server_name = "Homelab".into();
+    config
+}
+
+#[tokio::test]
+async fn creates_the_admin_before_anyone_signs_up() {
+    let pool = common::setup_test_db().await;
+    let state = common::create_test_state(pool.clone(), admin_config());
+    bootstrap::run(&state).await.unwrap();
+
+    let (username, is_admin, verified) = sqlx::query_as::<_, (String, bool, bool)>(
+        r#"SELECT username, is_instance_admin, emailVerified FROM "user" WHERE email = 'owner@example.com'"#,
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(username, "owner");
+    assert!(is_admin);
+    assert!(verified);
+    let (name, role) = sqlx::query_as::<_, (String, String)>(
+        "SELECT s.name, m.role FROM servers s JOIN memberships m ON m.server_id = s.id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!((name.as_str(), role.as_str()), ("Homelab", "owner"));
+
+    let server = TestServer::new(flux_server::routes::build_router(state.clone())).unwrap();
+    server
+        .post("/api/auth/sign-in/email")
+        .json(&json!({ "email": "owner@example.com", "password": PASSWORD }))
+        .await
+        .assert_status_ok();
+
+    // The instance isn't empty any more, so the first sign-up over HTTP
+    // gets no special treatment
+    server
+        .post("/api/auth/sign-up/email")
+        .json(&json!({
+            "email": "bob@test.com",
+            "password": PASSWORD,
+            "name": "Bob",
+            "username": "bob"
+        }))
+        .await
+        .assert_status(axum::http::StatusCode::FORBIDDEN);
+
+    // Starting again with the same environment changes nothing
+    let mut config = admin_config();
+    config.admin_password = "a different password entirely".into();
+    bootstrap::run(&common::create_test_state(pool.clone(), config)).await.unwrap();
+    let users = sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "user""#).fetch_one(&pool).await.unwrap();
+    assert_eq!(users, 1);
+    server
+        .post("/api/auth/sign-in/email")
+        .json(&json!({ "email": "owner@example.com", "password": PASSWORD }))
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn promotes_an_existing_account_and_refuses_weak_passwords() {
+    let pool = common::setup_test_db().await;
+    common::create_test_user(&pool, "first@test.com", "first", "password123").await;
+    common::create_test_user(&pool, "owner@example.com", "owner", "password123").await;
+
+    bootstrap::run(&common::create_test_state(pool.clone(), admin_config())).await.unwrap();
+    let is_admin = sqlx::query_scalar::<_, bool>(r#"SELECT is_instance_admin FROM "user" WHERE username = 'owner'"#)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(is_admin);
+
+    let mut config = admin_config();
+    config.admin_email = "new@example.com".into();
+    config.admin_password = "password".into();
+    let err = bootstrap::run(&common::create_test_state(pool.clone(), config)).await.unwrap_err();
+    assert!(err.starts_with("admin_password:"), "{}", err);
+    let users = sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "user""#).fetch_one(&pool).await.unwrap();
+    assert_eq!(users, 2);
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn export_renders_html_transcript() {
+    let pool = common::setup_test_db().await;
+    let config = common::test_config();
+    let upload_dir = config.upload_dir.clone();
+    let state = common::create_test_state(pool.clone(), config);
+    let server = TestServer::new(flux_server::routes::build_router(state)).unwrap();
+
+    let (alice_id, alice_token) = common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "launch party").await;
+    let voice_id = common::create_voice_channel(&pool, &server_id, "lounge").await;
+
+    for (id, sender, content, created_at) in [
+        ("m1", &alice_id, "Welcome <everyone> & friends", "2026-03-01T18:00:00Z"),
+        ("m2", &bob_id, "look at this", "2026-03-02T09:30:00Z"),
+    ] {
+        sqlx::query("INSERT INTO messages (id, channel_id, sender_id, content, created_at) VALUES (?, ?, ?, ?, ?)")
+            .bind(id)
+            .bind(&channel_id)
+            .bind(sender)
+            .bind(content)
+            .bind(created_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    sqlx::query("UPDATE memberships SET nickname = 'Bobby' WHERE user_id = ?")
+        .bind(&bob_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO reactions (id, message_id, user_id, emoji, created_at) VALUES ('r1', 'm1', ?, '🎉', '2026-03-01T18:01:00Z')")
+        .bind(&bob_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let image_id = common::create_test_attachment(&pool, &bob_id, "cake.png", "image/png").await;
+    sqlx::query("UPDATE attachments SET message_id = 'm2' WHERE id = ?")
+        .bind(&image_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    tokio::fs::create_dir_all(&upload_dir).await.unwrap();
+    tokio::fs::write(flux_server::routes::files::stored_file_path(&upload_dir, &image_id, "cake.png"), b"png")
+        .await
+        .unwrap();
+
+    let (h, v) = auth_header(&alice_token);
+    let res = server.post(&format!("/api/channels/{}/export", channel_id)).add_header(h, v).await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(res.header("content-type"), "text/html; charset=utf-8");
+    let disposition = res.header("content-disposition");
+    assert!(disposition.to_str().unwrap().starts_with("attachment; filename=\"launch-party-"));
+    let html = res.text();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("#launch party"));
+    assert!(html.contains("Welcome &lt;everyone&gt; &amp; friends"));
+    assert!(html.contains("Bobby"));
+    assert!(html.contains("🎉 1"));
+    assert!(html.contains(&format!("/api/files/{}/cake.png", image_id)));
+    assert!(html.find("Welcome").unwrap() < html.find("look at this").unwrap());
+
+    // Embedded images are inlined as data URIs
+    let (h, v) = auth_header(&alice_token);
+    let res = server
+        .post(&format!("/api/channels/{}/export?embedImages=true", channel_id))
+        .add_header(h, v)
+        .await;
+    let html = res.text();
+    assert!(html.contains("data:image/png;base64,cG5n"));
+
+    let (h, v) = auth_header(&bob_token);
+    let res = server.post(&format!("/api/channels/{}/export", channel_id)).add_header(h, v).await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let (h, v) = auth_header(&alice_token);
+    let res = server.post(&format!("/api/channels/{}/export", voice_id)).add_header(h, v).await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn insert_message(pool: &sqlx::SqlitePool, channel_id: &str, sender_id: &str, content: &str, created_at: &str) {
+    sqlx::query("INSERT INTO messages (id, channel_id, sender_id, content, created_at) VALUES (?, ?, ?, ?, ?)")
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(channel_id)
+        .bind(sender_id)
+        .bind(content)
+        .bind(created_at)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn members_follow_a_channel_with_revocable_tokens() {
+    let pool = common::setup_test_db().await;
+    let app = common::create_test_app(pool.clone());
+    let server = TestServer::new(app).unwrap();
+    let (owner_id, _) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let (_, eve_token) = common::create_test_user(&pool, "eve@test.com", "eve", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "announcements").await;
+    let voice_id = common::create_voice_channel(&pool, &server_id, "Lounge").await;
+
+    insert_message(&pool, &channel_id, &owner_id, "Release 1.0 <is> out\nDetails & notes", "2026-01-01T10:00:00+00:00").await;
+    insert_message(&pool, &channel_id, &owner_id, "Maintenance tonight", "2026-01-02T10:00:00+00:00").await;
+
+    let path = format!("/api/channels/{}/feed-tokens", channel_id);
+
+    let (h, v) = auth_header(&eve_token);
+    server.post(&path).add_header(h, v).json(&json!({})).await.assert_status(StatusCode::FORBIDDEN);
+    let (h, v) = auth_header(&bob_token);
+    server
+        .post(&format!("/api/channels/{}/feed-tokens", voice_id))
+        .add_header(h, v)
+        .json(&json!({}))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let (h, v) = auth_header(&bob_token);
+    let res = server.post(&path).add_header(h, v).json(&json!({ "name": "Reader" })).await;
+    res.assert_status(StatusCode::CREATED);
+    let created: serde_json::Value = res.json();
+    let token = created["token"].as_str().unwrap().to_string();
+    assert_eq!(created["name"], "Reader");
+    assert!(created["urls"]["atom"].as_str().unwrap().ends_with(&format!("/feed.atom?token={}", token)));
+
+    let feed_path = |file: &str| format!("/api/channels/{}/{}", channel_id, file);
+
+    let res = server.get(&feed_path("feed.atom")).add_query_param("token", &token).await;
+    res.assert_status_ok();
+    let atom = res.text();
+    assert!(atom.contains("<title>#announcements - flux</title>"));
+    assert!(atom.contains("<title>Release 1.0 &lt;is&gt; out</title>"));
+    assert!(atom.contains("Details &amp; notes"));
+    // Newest first
+    assert!(atom.find("Maintenance tonight").unwrap() < atom.find("Release 1.0").unwrap());
+
+    let res = server.get(&feed_path("feed.rss")).add_query_param("token", &token).await;
+    res.assert_status_ok();
+    assert!(res.text().contains("<pubDate>Fri, 2 Jan 2026 10:00:00 +0000</pubDate>"));
+
+    let res = server.get(&feed_path("feed.json")).add_query_param("token", &token).await;
+    res.assert_status_ok();
+    let feed: serde_json::Value = serde_json::from_str(&res.text()).unwrap();
+    assert_eq!(feed["version"], "https://jsonfeed.org/version/1.1");
+    assert_eq!(feed["items"][0]["content_text"], "Maintenance tonight");
+    assert_eq!(feed["items"][1]["authors"][0]["name"], "owner");
+
+    server.get(&feed_path("feed.atom")).await.assert_status(StatusCode::UNAUTHORIZED);
+    server
+        .get(&feed_path("feed.atom"))
+        .add_query_param("token", "wrong")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    let (h, v) = auth_header(&bob_token);
+    let tokens: serde_json::Value = server.get(&path).add_header(h, v).await.json();
+    assert!(tokens[0]["lastUsedAt"].is_string());
+    assert!(tokens[0].get("token").is_none());
+
+    let (h, v) = auth_header(&bob_token);
+    server
+        .delete(&format!("{}/{}", path, created["id"].as_str().unwrap()))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    server
+        .get(&feed_path("feed.atom"))
+        .add_query_param("token", &token)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn tokens_stop_working_when_the_member_leaves() {
+    let pool = common::setup_test_db().await;
+    let app = common::create_test_app(pool.clone());
+    let server = TestServer::new(app).unwrap();
+    let (owner_id, _) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "announcements").await;
+
+    let (h, v) = auth_header(&bob_token);
+    let created: serde_json::Value = server
+        .post(&format!("/api/channels/{}/feed-tokens", channel_id))
+        .add_header(h, v)
+        .json(&json!({}))
+        .await
+        .json();
+    assert_eq!(created["name"], "#announcements");
+    let token = created["token"].as_str().unwrap();
+    let feed_path = format!("/api/channels/{}/feed.atom", channel_id);
+    server.get(&feed_path).add_query_param("token", token).await.assert_status_ok();
+
+    sqlx::query("DELETE FROM memberships WHERE user_id = ? AND server_id = ?")
+        .bind(&bob_id)
+        .bind(&server_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    server
+        .get(&feed_path)
+        .add_query_param("token", token)
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+}
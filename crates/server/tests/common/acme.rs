@@ -0,0 +1,186 @@
+#![allow(dead_code)]
+
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, Mutex};
+
+const TOKEN: &str = "challenge-token";
+
+struct Ca {
+    base: String,
+    /// Where HTTP-01 challenges are fetched from
+    http_port: u16,
+    issuer: rcgen::CertifiedIssuer<'static, rcgen::KeyPair>,
+    thumbprint: String,
+    domain: String,
+    authz_status: &'static str,
+    chain: Option<String>,
+}
+
+type Shared = Arc<Mutex<Ca>>;
+
+/// A bare-bones ACME CA for one order at a time. It checks HTTP-01 answers
+/// on `http_port` against the account key and signs whatever CSR it gets,
+/// but doesn't check signatures or nonces. Returns the directory URL.
+pub async fn start_acme(http_port: u16) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+
+    let mut params = rcgen::CertificateParams::new(Vec::new()).unwrap();
+    params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+    let issuer = rcgen::CertifiedIssuer::self_signed(params, rcgen::KeyPair::generate().unwrap()).unwrap();
+    let ca = Arc::new(Mutex::new(Ca {
+        base: base.clone(),
+        http_port,
+        issuer,
+        thumbprint: String::new(),
+        domain: String::new(),
+        authz_status: "pending",
+        chain: None,
+    }));
+
+    let app = Router::new()
+        .route("/directory", get(directory))
+        .route("/nonce", get(|| async { nonce(StatusCode::OK, Json(json!({}))) }))
+        .route("/account", post(new_account))
+        .route("/order", post(new_order))
+        .route("/order/1", post(order))
+        .route("/authz/1", post(authz))
+        .route("/challenge/1", post(challenge))
+        .route("/finalize/1", post(finalize))
+        .route("/cert/1", post(cert))
+        .with_state(ca);
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("{}/directory", base)
+}
+
+fn nonce(status: StatusCode, body: impl IntoResponse) -> Response {
+    (status, [("replay-nonce", uuid::Uuid::new_v4().to_string())], body).into_response()
+}
+
+fn decode(part: &Value) -> Value {
+    let bytes = URL_SAFE_NO_PAD.decode(part.as_str().unwrap()).unwrap();
+    if bytes.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&bytes).unwrap()
+    }
+}
+
+fn order_json(ca: &Ca) -> Value {
+    let status = match (&ca.chain, ca.authz_status) {
+        (Some(_), _) => "valid",
+        (None, "valid") => "ready",
+        (None, status) => status,
+    };
+    json!({
+        "status": status,
+        "identifiers": [{ "type": "dns", "value": ca.domain }],
+        "authorizations": [format!("{}/authz/1", ca.base)],
+        "finalize": format!("{}/finalize/1", ca.base),
+        "certificate": ca.chain.as_ref().map(|_| format!("{}/cert/1", ca.base)),
+    })
+}
+
+async fn directory(State(ca): State<Shared>) -> Json<Value> {
+    let base = ca.lock().unwrap().base.clone();
+    Json(json!({
+        "newNonce": format!("{}/nonce", base),
+        "newAccount": format!("{}/account", base),
+        "newOrder": format!("{}/order", base),
+    }))
+}
+
+async fn new_account(State(ca): State<Shared>, Json(jws): Json<Value>) -> Response {
+    let protected = decode(&jws["protected"]);
+    let jwk = &protected["jwk"];
+    let canonical = format!(
+        r#"{{"crv":"{}","kty":"{}","x":"{}","y":"{}"}}"#,
+        jwk["crv"].as_str().unwrap(),
+        jwk["kty"].as_str().unwrap(),
+        jwk["x"].as_str().unwrap(),
+        jwk["y"].as_str().unwrap()
+    );
+    let mut ca = ca.lock().unwrap();
+    ca.thumbprint = URL_SAFE_NO_PAD.encode(Sha256::digest(canonical));
+    let location = format!("{}/account/1", ca.base);
+    let mut res = nonce(StatusCode::CREATED, Json(json!({ "status": "valid" })));
+    res.headers_mut().insert(header::LOCATION, location.parse().unwrap());
+    res
+}
+
+async fn new_order(State(ca): State<Shared>, Json(jws): Json<Value>) -> Response {
+    let payload = decode(&jws["payload"]);
+    let mut ca = ca.lock().unwrap();
+    ca.domain = payload["identifiers"][0]["value"].as_str().unwrap().to_string();
+    ca.authz_status = "pending";
+    ca.chain = None;
+    let mut res = nonce(StatusCode::CREATED, Json(order_json(&ca)));
+    res.headers_mut().insert(header::LOCATION, format!("{}/order/1", ca.base).parse().unwrap());
+    res
+}
+
+async fn order(State(ca): State<Shared>) -> Response {
+    nonce(StatusCode::OK, Json(order_json(&ca.lock().unwrap())))
+}
+
+async fn authz(State(ca): State<Shared>) -> Response {
+    let ca = ca.lock().unwrap();
+    nonce(
+        StatusCode::OK,
+        Json(json!({
+            "status": ca.authz_status,
+            "identifier": { "type": "dns", "value": ca.domain },
+            "challenges": [{
+                "type": "http-01",
+                "url": format!("{}/challenge/1", ca.base),
+                "token": TOKEN,
+                "status": ca.authz_status,
+            }],
+        })),
+    )
+}
+
+async fn challenge(State(ca): State<Shared>) -> Response {
+    let (url, domain, expected) = {
+        let ca = ca.lock().unwrap();
+        let url = format!("http://127.0.0.1:{}/.well-known/acme-challenge/{}", ca.http_port, TOKEN);
+        (url, ca.domain.clone(), format!("{}.{}", TOKEN, ca.thumbprint))
+    };
+    let answer = match reqwest::Client::new().get(url).header(header::HOST, domain).send().await {
+        Ok(res) if res.status().is_success() => res.text().await.ok(),
+        _ => None,
+    };
+    let status = if answer.as_deref() == Some(expected.trim()) { "valid" } else { "invalid" };
+    ca.lock().unwrap().authz_status = status;
+    nonce(StatusCode::OK, Json(json!({ "status": "processing" })))
+}
+
+async fn finalize(State(ca): State<Shared>, Json(jws): Json<Value>) -> Response {
+    let payload = decode(&jws["payload"]);
+    let csr = URL_SAFE_NO_PAD.decode(payload["csr"].as_str().unwrap()).unwrap();
+    let mut ca = ca.lock().unwrap();
+    if ca.authz_status != "valid" {
+        return nonce(
+            StatusCode::FORBIDDEN,
+            Json(json!({ "type": "urn:ietf:params:acme:error:orderNotReady", "detail": "Not authorized" })),
+        );
+    }
+    let csr = rcgen::CertificateSigningRequestParams::from_der(&csr.into()).unwrap();
+    let cert = csr.signed_by(&ca.issuer).unwrap();
+    ca.chain = Some(format!("{}{}", cert.pem(), ca.issuer.pem()));
+    nonce(StatusCode::OK, Json(order_json(&ca)))
+}
+
+async fn cert(State(ca): State<Shared>) -> Response {
+    let chain = ca.lock().unwrap().chain.clone().unwrap_or_default();
+    nonce(StatusCode::OK, ([(header::CONTENT_TYPE, "application/pem-certificate-chain")], chain))
+}
@@ -1,9 +1,11 @@
 #![allow(dead_code)]
 
+pub mod acme;
+pub mod smtp;
 pub mod ws_helpers;
 
 use axum::Router;
-use flux_server::{config::Config, routes, ws, AppState};
+use flux_server::{config::Config, middleware::ip_filter::IpFilter, routes, ws, AppState};
 use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
 use std::sync::Arc;
 
@@ -44,6 +46,30 @@ pub async fn setup_test_db() -> SqlitePool {
         r#"ALTER TABLE "channels" ADD COLUMN is_room INTEGER NOT NULL DEFAULT 0"#,
         r#"ALTER TABLE "channels" ADD COLUMN creator_id TEXT"#,
         r#"ALTER TABLE "channels" ADD COLUMN is_locked INTEGER NOT NULL DEFAULT 0"#,
+        r#"ALTER TABLE "messages" ADD COLUMN metadata TEXT"#,
+        r#"ALTER TABLE "user" ADD COLUMN is_instance_admin INTEGER NOT NULL DEFAULT 0"#,
+        r#"ALTER TABLE "user" ADD COLUMN bio TEXT"#,
+        r#"ALTER TABLE "user" ADD COLUMN pronouns TEXT"#,
+        r#"ALTER TABLE "user" ADD COLUMN accent_color TEXT"#,
+        r#"ALTER TABLE "user" ADD COLUMN timezone TEXT"#,
+        r#"ALTER TABLE "user" ADD COLUMN image_static TEXT"#,
+        r#"ALTER TABLE "memberships" ADD COLUMN nickname TEXT"#,
+        r#"ALTER TABLE "memberships" ADD COLUMN avatar TEXT"#,
+        r#"ALTER TABLE "memberships" ADD COLUMN avatar_static TEXT"#,
+        r#"ALTER TABLE "memberships" ADD COLUMN profile_updated_at TEXT"#,
+        r#"ALTER TABLE "messages" ADD COLUMN feed_id TEXT"#,
+        r#"ALTER TABLE "messages" ADD COLUMN integration_id TEXT"#,
+        r#"ALTER TABLE "servers" ADD COLUMN retention_days INTEGER"#,
+        r#"ALTER TABLE "channels" ADD COLUMN retention_days INTEGER"#,
+        r#"ALTER TABLE "user" ADD COLUMN locale TEXT"#,
+        r#"ALTER TABLE "memberships" ADD COLUMN rules_accepted_at TEXT"#,
+        r#"ALTER TABLE "servers" ADD COLUMN min_account_age_minutes INTEGER NOT NULL DEFAULT 0"#,
+        r#"ALTER TABLE "servers" ADD COLUMN require_verified_email INTEGER NOT NULL DEFAULT 0"#,
+        r#"ALTER TABLE "servers" ADD COLUMN require_challenge INTEGER NOT NULL DEFAULT 0"#,
+        r#"ALTER TABLE "memberships" ADD COLUMN challenge_passed_at TEXT"#,
+        r#"ALTER TABLE "dm_channels" ADD COLUMN status TEXT NOT NULL DEFAULT 'accepted'"#,
+        r#"ALTER TABLE "dm_channels" ADD COLUMN requested_by TEXT"#,
+        r#"ALTER TABLE "channels" ADD COLUMN voice_region TEXT"#,
     ];
 
     for migration in &migrations {
@@ -60,7 +86,8 @@ pub async fn setup_test_db() -> SqlitePool {
             current_track_position_ms INTEGER DEFAULT 0,
             is_playing INTEGER DEFAULT 0,
             created_at TEXT NOT NULL,
-            updated_at TEXT NOT NULL
+            updated_at TEXT NOT NULL,
+            session_type TEXT NOT NULL DEFAULT 'audio'
         )"#,
     )
     .execute(&pool)
@@ -87,6 +114,852 @@ pub async fn setup_test_db() -> SqlitePool {
     .await
     .ok();
 
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "listening_session_cohosts" (
+            session_id TEXT NOT NULL REFERENCES "listening_sessions"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (session_id, user_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "session_history" (
+            id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL REFERENCES "listening_sessions"(id) ON DELETE CASCADE,
+            track_uri TEXT NOT NULL,
+            track_name TEXT NOT NULL,
+            track_artist TEXT NOT NULL,
+            track_album TEXT,
+            track_image_url TEXT,
+            track_duration_ms INTEGER NOT NULL DEFAULT 0,
+            source TEXT NOT NULL DEFAULT 'spotify',
+            played_by_user_id TEXT NOT NULL,
+            played_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "lastfm_links" (
+            user_id TEXT PRIMARY KEY REFERENCES "user"(id) ON DELETE CASCADE,
+            username TEXT NOT NULL,
+            session_key TEXT NOT NULL,
+            scrobbling_enabled INTEGER NOT NULL DEFAULT 1,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "scrobble_queue" (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            artist TEXT NOT NULL,
+            track TEXT NOT NULL,
+            album TEXT,
+            duration_ms INTEGER NOT NULL DEFAULT 0,
+            played_at INTEGER NOT NULL,
+            ready_at INTEGER NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_scrobble_queue_ready ON "scrobble_queue"(ready_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "wallets" (
+            user_id TEXT PRIMARY KEY REFERENCES "user"(id) ON DELETE CASCADE,
+            balance INTEGER NOT NULL DEFAULT 0,
+            updated_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "coin_rewards_log" (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            amount INTEGER NOT NULL,
+            reason TEXT NOT NULL,
+            server_id TEXT,
+            created_at TEXT NOT NULL,
+            note TEXT,
+            actor_id TEXT
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_coin_rewards_log_user ON "coin_rewards_log"(user_id, reason, created_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "daily_streaks" (
+            user_id TEXT PRIMARY KEY REFERENCES "user"(id) ON DELETE CASCADE,
+            streak INTEGER NOT NULL DEFAULT 0,
+            last_claim_date TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "reward_rules" (
+            rule TEXT PRIMARY KEY,
+            amount INTEGER NOT NULL,
+            daily_cap INTEGER NOT NULL DEFAULT 0,
+            cooldown_secs INTEGER NOT NULL DEFAULT 0,
+            enabled INTEGER NOT NULL DEFAULT 1
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+    sqlx::query(
+        r#"INSERT OR IGNORE INTO "reward_rules" (rule, amount, daily_cap, cooldown_secs) VALUES
+           ('daily_login', 100, 0, 0),
+           ('message', 2, 100, 60),
+           ('voice_minute', 1, 120, 50)"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "economy_pauses" (
+            server_id TEXT PRIMARY KEY REFERENCES "servers"(id) ON DELETE CASCADE,
+            paused_until TEXT,
+            reason TEXT,
+            paused_by TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Games: per-user provably-fair seed pairs, game records and per-server limits
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "game_seeds" (
+            user_id TEXT PRIMARY KEY REFERENCES "user"(id) ON DELETE CASCADE,
+            server_seed TEXT NOT NULL,
+            client_seed TEXT NOT NULL,
+            nonce INTEGER NOT NULL DEFAULT 0
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "games" (
+            id TEXT PRIMARY KEY,
+            kind TEXT NOT NULL,
+            server_id TEXT NOT NULL REFERENCES "servers"(id) ON DELETE CASCADE,
+            channel_id TEXT NOT NULL,
+            creator_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            opponent_id TEXT,
+            wager INTEGER NOT NULL,
+            target INTEGER,
+            status TEXT NOT NULL,
+            seed_user_id TEXT,
+            server_seed TEXT,
+            server_seed_hash TEXT,
+            client_seed TEXT,
+            nonce INTEGER,
+            roll INTEGER,
+            winner_id TEXT,
+            payout INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            resolved_at TEXT
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "game_settings" (
+            server_id TEXT PRIMARY KEY REFERENCES "servers"(id) ON DELETE CASCADE,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            house_edge_bps INTEGER NOT NULL DEFAULT 200,
+            daily_loss_limit INTEGER NOT NULL DEFAULT 1000
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // E2EE key backups: one passphrase-wrapped identity key per user
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "key_backups" (
+            user_id TEXT PRIMARY KEY REFERENCES "user"(id) ON DELETE CASCADE,
+            version INTEGER NOT NULL,
+            wrapped_key TEXT NOT NULL,
+            salt TEXT NOT NULL,
+            kdf TEXT NOT NULL,
+            kdf_params TEXT NOT NULL,
+            public_key TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // E2EE device keys: one identity key per (user, device)
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "device_keys" (
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            device_id TEXT NOT NULL,
+            public_key TEXT NOT NULL,
+            name TEXT,
+            created_at TEXT NOT NULL,
+            last_seen_at TEXT NOT NULL,
+            PRIMARY KEY (user_id, device_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // E2EE key verifications: which peer key (account or device) a user confirmed
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "key_verifications" (
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            peer_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            device_id TEXT NOT NULL DEFAULT '',
+            public_key TEXT NOT NULL,
+            verified_at TEXT NOT NULL,
+            PRIMARY KEY (user_id, peer_id, device_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Password reset tokens: only an HMAC of the token is stored
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "password_resets" (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            token_hash TEXT UNIQUE NOT NULL,
+            expires_at TEXT NOT NULL,
+            used_at TEXT,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Emailed verification tokens (HMAC stored). purpose: verify, change_old, change_new
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "email_tokens" (
+            token_hash TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            email TEXT NOT NULL,
+            purpose TEXT NOT NULL,
+            expires_at TEXT NOT NULL,
+            used_at TEXT,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Pending email changes; applied once both addresses confirm
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "email_changes" (
+            user_id TEXT PRIMARY KEY REFERENCES "user"(id) ON DELETE CASCADE,
+            new_email TEXT NOT NULL,
+            old_confirmed_at TEXT,
+            new_confirmed_at TEXT,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Sign-in attempts, kept as an audit trail and for throttling
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "login_attempts" (
+            id TEXT PRIMARY KEY,
+            email TEXT NOT NULL,
+            user_id TEXT REFERENCES "user"(id) ON DELETE CASCADE,
+            ip TEXT,
+            success INTEGER NOT NULL,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Failed sign-in counters per account (`account:<email>`) and per IP (`ip:<addr>`)
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "login_lockouts" (
+            key TEXT PRIMARY KEY,
+            failures INTEGER NOT NULL DEFAULT 0,
+            lockouts INTEGER NOT NULL DEFAULT 0,
+            locked_until TEXT,
+            updated_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Index for listing an account's sign-in attempts
+    sqlx::query(
+        r#"CREATE INDEX IF NOT EXISTS idx_login_attempts_email ON "login_attempts"(email, created_at)"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Instance-wide settings (registration, whitelist mode), one row per key
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "instance_settings" (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_by TEXT REFERENCES "user"(id) ON DELETE SET NULL,
+            updated_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Online user counts over time, for the admin dashboard
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "online_samples" (
+            sampled_at TEXT PRIMARY KEY,
+            online_users INTEGER NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Former usernames, so old mentions and searches still find their owner
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "username_history" (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            old_username TEXT NOT NULL,
+            changed_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_username_history_old ON "username_history"(old_username, changed_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // RSS/Atom feeds that post into channels, and the entries each has seen
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "channel_feeds" (
+            id TEXT PRIMARY KEY,
+            channel_id TEXT NOT NULL REFERENCES "channels"(id) ON DELETE CASCADE,
+            url TEXT NOT NULL,
+            title TEXT NOT NULL,
+            created_by TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL,
+            last_polled_at TEXT,
+            last_error TEXT,
+            UNIQUE(channel_id, url)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "feed_entries" (
+            feed_id TEXT NOT NULL REFERENCES "channel_feeds"(id) ON DELETE CASCADE,
+            guid TEXT NOT NULL,
+            seen_at TEXT NOT NULL,
+            PRIMARY KEY (feed_id, guid)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Webhook integrations (GitHub/GitLab) that post into channels
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "channel_integrations" (
+            id TEXT PRIMARY KEY,
+            channel_id TEXT NOT NULL REFERENCES "channels"(id) ON DELETE CASCADE,
+            provider TEXT NOT NULL,
+            name TEXT NOT NULL,
+            token_hash TEXT NOT NULL UNIQUE,
+            secret TEXT NOT NULL,
+            created_by TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL,
+            last_delivery_at TEXT
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Revocable tokens that let feed readers follow a channel as Atom, RSS
+    // or JSON Feed on a member's behalf
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "channel_feed_tokens" (
+            id TEXT PRIMARY KEY,
+            channel_id TEXT NOT NULL REFERENCES "channels"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            name TEXT NOT NULL,
+            token_hash TEXT NOT NULL UNIQUE,
+            created_at TEXT NOT NULL,
+            last_used_at TEXT
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Scheduled server events, their RSVPs, and which occurrence was last reminded
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "events" (
+            id TEXT PRIMARY KEY,
+            server_id TEXT NOT NULL REFERENCES "servers"(id) ON DELETE CASCADE,
+            channel_id TEXT REFERENCES "channels"(id) ON DELETE SET NULL,
+            title TEXT NOT NULL,
+            description TEXT NOT NULL DEFAULT '',
+            start_at TEXT NOT NULL,
+            end_at TEXT,
+            recurrence TEXT,
+            created_by TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            reminded_for TEXT
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_events_server ON "events"(server_id, start_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "event_rsvps" (
+            event_id TEXT NOT NULL REFERENCES "events"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            status TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (event_id, user_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // "Remind me about this message"; exactly one of message_id / dm_message_id is set
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "message_reminders" (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            message_id TEXT REFERENCES "messages"(id) ON DELETE CASCADE,
+            dm_message_id TEXT REFERENCES "dm_messages"(id) ON DELETE CASCADE,
+            note TEXT,
+            remind_at TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            fired_at TEXT
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_message_reminders_due ON "message_reminders"(fired_at, remind_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_message_reminders_user ON "message_reminders"(user_id, remind_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Saved (bookmarked) messages; exactly one of message_id / dm_message_id is set
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "saved_messages" (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            message_id TEXT REFERENCES "messages"(id) ON DELETE CASCADE,
+            dm_message_id TEXT REFERENCES "dm_messages"(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE UNIQUE INDEX IF NOT EXISTS idx_saved_messages_message ON "saved_messages"(user_id, message_id)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(r#"CREATE UNIQUE INDEX IF NOT EXISTS idx_saved_messages_dm ON "saved_messages"(user_id, dm_message_id)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_saved_messages_user ON "saved_messages"(user_id, created_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Daily custom emoji usage counters, for per-server emoji stats
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "emoji_usage_daily" (
+            server_id TEXT NOT NULL REFERENCES "servers"(id) ON DELETE CASCADE,
+            emoji_id TEXT NOT NULL REFERENCES "custom_emojis"(id) ON DELETE CASCADE,
+            day TEXT NOT NULL,
+            reactions INTEGER NOT NULL DEFAULT 0,
+            messages INTEGER NOT NULL DEFAULT 0,
+            last_used_at TEXT NOT NULL,
+            PRIMARY KEY (emoji_id, day)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_emoji_usage_daily_server ON "emoji_usage_daily"(server_id, day)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Voice call history: one row per stretch a user spent in a voice channel.
+    // channel_id has no foreign key so history outlives deleted rooms.
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "voice_sessions" (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            channel_id TEXT NOT NULL,
+            channel_name TEXT NOT NULL,
+            server_id TEXT REFERENCES "servers"(id) ON DELETE CASCADE,
+            joined_at TEXT NOT NULL,
+            last_seen_at TEXT NOT NULL,
+            left_at TEXT,
+            duration_secs INTEGER,
+            coins_earned INTEGER NOT NULL DEFAULT 0
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_voice_sessions_user ON "voice_sessions"(user_id, joined_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_voice_sessions_server ON "voice_sessions"(server_id, joined_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Custom roles members can hold alongside owner/admin/member
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "server_roles" (
+            id TEXT PRIMARY KEY,
+            server_id TEXT NOT NULL REFERENCES "servers"(id) ON DELETE CASCADE,
+            name TEXT NOT NULL,
+            color TEXT,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE UNIQUE INDEX IF NOT EXISTS idx_server_roles_name ON "server_roles"(server_id, name)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "member_roles" (
+            server_id TEXT NOT NULL REFERENCES "servers"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            role_id TEXT NOT NULL REFERENCES "server_roles"(id) ON DELETE CASCADE,
+            assigned_at TEXT NOT NULL,
+            PRIMARY KEY (user_id, role_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_member_roles_server ON "member_roles"(server_id, user_id)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // "React to get a role": one binding per emoji on a message
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "reaction_roles" (
+            id TEXT PRIMARY KEY,
+            server_id TEXT NOT NULL REFERENCES "servers"(id) ON DELETE CASCADE,
+            message_id TEXT NOT NULL REFERENCES "messages"(id) ON DELETE CASCADE,
+            emoji TEXT NOT NULL,
+            role_id TEXT NOT NULL REFERENCES "server_roles"(id) ON DELETE CASCADE,
+            created_by TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE UNIQUE INDEX IF NOT EXISTS idx_reaction_roles_message ON "reaction_roles"(message_id, emoji)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Welcome screen shown to new members, and the rules they accept
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "server_welcome" (
+            server_id TEXT PRIMARY KEY REFERENCES "servers"(id) ON DELETE CASCADE,
+            message TEXT,
+            rules TEXT,
+            suggested_channel_ids TEXT NOT NULL DEFAULT '[]',
+            rules_updated_at TEXT,
+            updated_by TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Users someone has blocked. Either side of a block is hidden from the
+    // other: no DMs, reactions, mentions, invites, games or typing.
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "blocked_users" (
+            blocker_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            blocked_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (blocker_id, blocked_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_blocked_users_blocked ON "blocked_users"(blocked_id)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // LiveKit nodes voice can be routed to. With none, everything goes to
+    // the configured livekit_url. Empty credentials fall back to the
+    // configured API key and secret.
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "voice_nodes" (
+            id TEXT PRIMARY KEY,
+            region TEXT NOT NULL,
+            url TEXT NOT NULL,
+            api_key TEXT NOT NULL DEFAULT '',
+            api_secret TEXT NOT NULL DEFAULT '',
+            enabled INTEGER NOT NULL DEFAULT 1,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Each user's last measured round trip to each node
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "voice_latency" (
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            node_id TEXT NOT NULL REFERENCES "voice_nodes"(id) ON DELETE CASCADE,
+            rtt_ms INTEGER NOT NULL,
+            measured_at TEXT NOT NULL,
+            PRIMARY KEY (user_id, node_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // The node a voice channel's call is running on, so everyone in it
+    // lands on the same one
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "voice_room_nodes" (
+            channel_id TEXT PRIMARY KEY REFERENCES "channels"(id) ON DELETE CASCADE,
+            node_id TEXT NOT NULL REFERENCES "voice_nodes"(id) ON DELETE CASCADE,
+            assigned_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Stage channels: who may speak, and listeners waiting to be brought up
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "stage_speakers" (
+            channel_id TEXT NOT NULL REFERENCES "channels"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            added_by TEXT NOT NULL,
+            added_at TEXT NOT NULL,
+            PRIMARY KEY (channel_id, user_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "stage_hands" (
+            channel_id TEXT NOT NULL REFERENCES "channels"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            raised_at TEXT NOT NULL,
+            PRIMARY KEY (channel_id, user_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Call quality samples clients report while in voice
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "voice_quality_samples" (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            server_id TEXT NOT NULL,
+            channel_id TEXT NOT NULL REFERENCES "channels"(id) ON DELETE CASCADE,
+            user_id TEXT NOT NULL,
+            rtt_ms REAL NOT NULL,
+            jitter_ms REAL NOT NULL,
+            packet_loss REAL NOT NULL,
+            sampled_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_voice_quality_server ON "voice_quality_samples"(server_id, sampled_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_voice_quality_channel ON "voice_quality_samples"(channel_id, sampled_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Client settings synced between a user's devices (key bindings etc.)
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "user_settings" (
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (user_id, key)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // How far each user has read in each channel or DM, for email digests
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "read_states" (
+            user_id TEXT NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+            channel_id TEXT NOT NULL,
+            last_read_at TEXT NOT NULL,
+            PRIMARY KEY (user_id, channel_id)
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Opt-in email digests; last_sent_at is where the next one starts
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "email_digests" (
+            user_id TEXT PRIMARY KEY REFERENCES "user"(id) ON DELETE CASCADE,
+            frequency TEXT NOT NULL,
+            last_sent_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
+    // Outgoing emails waiting to be (re)sent
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "mail_queue" (
+            id TEXT PRIMARY KEY,
+            recipient TEXT NOT NULL,
+            subject TEXT NOT NULL,
+            text_body TEXT NOT NULL,
+            html_body TEXT,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            next_attempt_at TEXT NOT NULL,
+            last_error TEXT,
+            created_at TEXT NOT NULL
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+    sqlx::query(r#"CREATE INDEX IF NOT EXISTS idx_mail_queue_next ON "mail_queue"(next_attempt_at)"#)
+        .execute(&pool)
+        .await
+        .ok();
+
+    // Addresses and ranges instance admins have banned
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS "ip_bans" (
+            id TEXT PRIMARY KEY,
+            network TEXT NOT NULL UNIQUE,
+            reason TEXT,
+            created_by TEXT REFERENCES "user"(id) ON DELETE SET NULL,
+            created_at TEXT NOT NULL,
+            expires_at TEXT
+        )"#,
+    )
+    .execute(&pool)
+    .await
+    .ok();
+
     // Create unique index for account upsert
     sqlx::query(r#"CREATE UNIQUE INDEX IF NOT EXISTS idx_account_user_provider ON "account"(userId, providerId)"#)
         .execute(&pool)
@@ -96,28 +969,115 @@ pub async fn setup_test_db() -> SqlitePool {
     pool
 }
 
+/// Config for test apps: local paths, external services off.
+pub fn test_config() -> Config {
+    Config {
+        host: "127.0.0.1".into(),
+        port: 0,
+        unix_socket: "".into(),
+        unix_socket_mode: "660".into(),
+        database_path: ":memory:".into(),
+        auth_secret: "test-secret".into(),
+        livekit_api_key: "".into(),
+        livekit_api_secret: "".into(),
+        livekit_url: "ws://localhost:7880".into(),
+        upload_dir: "/tmp/flux-test-uploads".into(),
+        max_upload_bytes: 10_485_760,
+        avatar_max_bytes: 2_097_152,
+        room_cleanup_delay_secs: 2,
+        youtube_cache_dir: "/tmp/flux-test-youtube-cache".into(),
+        youtube_cache_max_bytes: 10_485_760,
+        gif_provider: "tenor".into(),
+        gif_api_key: "".into(),
+        lyrics_api_url: "".into(),
+        noise_model_dir: "/tmp/flux-test-noise-models".into(),
+        noise_model_url: "".into(),
+        noise_model_files: "".into(),
+        oidc_issuer: "".into(),
+        oidc_client_id: "".into(),
+        oidc_client_secret: "".into(),
+        oidc_redirect_uri: "".into(),
+        smtp_host: "".into(),
+        smtp_port: 587,
+        smtp_username: "".into(),
+        smtp_password: "".into(),
+        smtp_from: "Flux <noreply@localhost>".into(),
+        smtp_security: "none".into(),
+        mail_log_only: false,
+        tls_cert_path: "".into(),
+        tls_key_path: "".into(),
+        tls_redirect_port: 0,
+        acme_domain: "".into(),
+        acme_email: "".into(),
+        acme_directory_url: "".into(),
+        acme_challenge: "tls-alpn-01".into(),
+        acme_dir: "./acme".into(),
+        public_url: "http://127.0.0.1:3001".into(),
+        require_email_verification: false,
+        argon2_memory_kib: 19_456,
+        argon2_iterations: 2,
+        password_min_score: 3,
+        username_change_cooldown_secs: 604_800,
+        cookie_secure: false,
+        cookie_domain: "".into(),
+        trusted_proxies: "127.0.0.0/8,::1,10.0.0.0/8,172.16.0.0/12,192.168.0.0/16,fc00::/7".into(),
+        ip_allowlists: "".into(),
+        metrics_token: "".into(),
+        otlp_endpoint: "".into(),
+        otel_service_name: "flux-server".into(),
+        db_max_connections: 8,
+        db_read_connections: 8,
+        db_busy_timeout_ms: 5000,
+        backup_dir: "/tmp/flux-test-backups".into(),
+        backup_interval_hours: 0,
+        backup_keep: 7,
+        backup_s3_bucket: "".into(),
+        backup_s3_prefix: "flux-backups/".into(),
+        restore_from: "".into(),
+        admin_email: "".into(),
+        admin_password: "".into(),
+        admin_username: "".into(),
+        server_name: "flux".into(),
+        feed_poll_interval_secs: 900,
+        event_reminder_minutes: 15,
+        dm_retention_days: 0,
+        challenge_verify_url: "".into(),
+        challenge_site_key: "".into(),
+        challenge_secret: "".into(),
+    }
+}
+
 /// Build a test Axum app with the given pool.
 pub fn create_test_app(pool: SqlitePool) -> Router {
-    let state = Arc::new(AppState {
-        db: pool,
-        config: Config {
-            host: "127.0.0.1".into(),
-            port: 0,
-            database_path: ":memory:".into(),
-            auth_secret: "test-secret".into(),
-            livekit_api_key: "".into(),
-            livekit_api_secret: "".into(),
-            livekit_url: "ws://localhost:7880".into(),
-            upload_dir: "/tmp/flux-test-uploads".into(),
-            max_upload_bytes: 10_485_760,
-            room_cleanup_delay_secs: 2,
-        },
+    create_test_app_with_config(pool, test_config())
+}
+
+/// Build a test Axum app with a custom config.
+pub fn create_test_app_with_config(pool: SqlitePool, config: Config) -> Router {
+    routes::build_router(create_test_state(pool, config))
+}
+
+/// App state for tests that call background jobs directly.
+pub fn create_test_state(pool: SqlitePool, config: Config) -> Arc<AppState> {
+    let ip_filter = IpFilter::new(&config);
+    Arc::new(AppState {
+        db: pool.clone(),
+        db_read: pool.clone(),
+        db_write: pool,
+        config,
         gateway: Arc::new(ws::gateway::GatewayState::new()),
         spotify_auth_pending: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        lastfm_auth_pending: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        oidc_pending: tokio::sync::RwLock::new(std::collections::HashMap::new()),
         youtube_url_cache: tokio::sync::RwLock::new(std::collections::HashMap::new()),
-    });
-
-    routes::build_router(state)
+        youtube_downloads: tokio::sync::RwLock::new(std::collections::HashSet::new()),
+        gif_search_cache: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        insights_cache: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        gif_rate_limits: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        lyrics_cache: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        noise_models: tokio::sync::RwLock::new(Default::default()),
+        ip_filter,
+    })
 }
 
 /// Create a test user directly in the database. Returns (user_id, session_token).
@@ -38,6 +38,23 @@ pub async fn ws_connect(
     ws
 }
 
+/// Connect as a specific E2EE device (`?deviceId=`).
+pub async fn ws_connect_device(
+    base: &str,
+    token: &str,
+    device_id: &str,
+) -> tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>
+{
+    let ws_url = format!(
+        "{}/gateway?token={}&deviceId={}",
+        base.replace("http://", "ws://"),
+        token,
+        device_id
+    );
+    let (ws, _) = tokio_tungstenite::connect_async(&ws_url).await.unwrap();
+    ws
+}
+
 /// Read next text message parsed as JSON, with timeout.
 pub async fn recv_json(
     ws: &mut tokio_tungstenite::WebSocketStream<
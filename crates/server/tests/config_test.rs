@@ -0,0 +1,82 @@
+use flux_server::config::Config;
+
+const MINIMAL: &str = r#"auth_secret = "from-file""#;
+
+#[test]
+fn example_config_loads() {
+    let example = include_str!("../../../config.example.toml");
+    let config = Config::from_toml(example, "config.example.toml").unwrap();
+    assert_eq!(config.gif_provider, "tenor");
+    assert_eq!(config.room_cleanup_delay_secs, 120);
+}
+
+#[test]
+fn file_values_override_defaults() {
+    let config = Config::from_toml(
+        &format!("{}\nyoutube_cache_max_bytes = 0\ngif_provider = \"giphy\"\ncookie_secure = true", MINIMAL),
+        "config.toml",
+    )
+    .unwrap();
+    assert_eq!(config.auth_secret, "from-file");
+    assert_eq!(config.youtube_cache_max_bytes, 0);
+    assert_eq!(config.gif_provider, "giphy");
+    assert!(config.cookie_secure);
+    // Untouched keys keep their defaults
+    assert_eq!(config.youtube_cache_dir, "./youtube-cache");
+}
+
+#[test]
+fn errors_name_the_key() {
+    let err = |toml: &str| Config::from_toml(&format!("{}\n{}", MINIMAL, toml), "config.toml").err().unwrap().to_string();
+
+    assert_eq!(err("argon2_iterations = \"two\""), "argon2_iterations in config.toml: expected a whole number, got string");
+    assert_eq!(err("room_cleanup_delay_secs = -1"), "room_cleanup_delay_secs in config.toml: -1 is out of range");
+    assert_eq!(err("require_email_verification = 1"), "require_email_verification in config.toml: expected true or false, got integer");
+    assert_eq!(err("gif_provder = \"giphy\""), "config.toml: unknown key \"gif_provder\"");
+    assert_eq!(err("smtp_security = \"ssl\""), "smtp_security must be one of starttls, tls, none, got \"ssl\"");
+    assert!(err("youtube_cache_dir = ").starts_with("config.toml: "));
+
+    let missing = Config::from_toml("", "config.toml").err().unwrap().to_string();
+    assert_eq!(missing, "auth_secret (BETTER_AUTH_SECRET) must be set");
+}
+
+#[test]
+fn environment_wins_over_file() {
+    // The only test that touches the environment, so nothing races it
+    std::env::set_var("ARGON2_MEMORY_KIB", "65536");
+    std::env::set_var("OTEL_SERVICE_NAME", "from-env");
+    let config = Config::from_toml(
+        &format!("{}\nargon2_memory_kib = 1024\notel_service_name = \"from-file\"", MINIMAL),
+        "config.toml",
+    )
+    .unwrap();
+    assert_eq!(config.argon2_memory_kib, 65536);
+    assert_eq!(config.otel_service_name, "from-env");
+
+    std::env::set_var("ARGON2_MEMORY_KIB", "lots");
+    let err = Config::from_toml(MINIMAL, "config.toml").err().unwrap().to_string();
+    assert_eq!(err, "argon2_memory_kib (ARGON2_MEMORY_KIB): \"lots\" is not a valid number for this setting");
+
+    std::env::remove_var("ARGON2_MEMORY_KIB");
+    std::env::remove_var("OTEL_SERVICE_NAME");
+}
+
+#[test]
+fn print_config_redacts_secrets() {
+    let config = Config::from_toml(
+        &format!("{}\nsmtp_password = \"hunter2\"\nsmtp_host = \"mail.example.com\"", MINIMAL),
+        "config.toml",
+    )
+    .unwrap();
+    let printed = config.to_redacted_toml();
+    assert!(!printed.contains("hunter2"));
+    assert!(!printed.contains("from-file"));
+    assert!(printed.contains(r#"smtp_password = "<redacted>""#));
+    assert!(printed.contains(r#"smtp_host = "mail.example.com""#));
+    // Unset secrets stay visibly empty
+    assert!(printed.contains(r#"metrics_token = """#));
+
+    // The output is itself a loadable config (once the secrets are filled in)
+    let reloaded = Config::from_toml(&printed.replace("<redacted>", "x"), "printed.toml").unwrap();
+    assert_eq!(reloaded.smtp_host, "mail.example.com");
+}
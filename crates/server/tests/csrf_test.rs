@@ -0,0 +1,125 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use serde_json::json;
+
+fn header(name: &'static str, value: &str) -> (HeaderName, HeaderValue) {
+    (HeaderName::from_static(name), value.parse().unwrap())
+}
+
+/// Sign in and return the Set-Cookie values.
+async fn sign_in_cookies(server: &TestServer) -> Vec<String> {
+    let res = server
+        .post("/api/auth/sign-in/email")
+        .json(&json!({ "email": "alice@test.com", "password": "password123" }))
+        .await;
+    res.assert_status_ok();
+    res.headers()
+        .get_all("set-cookie")
+        .iter()
+        .map(|v| v.to_str().unwrap().to_string())
+        .collect()
+}
+
+fn cookie_value(cookies: &[String], name: &str) -> String {
+    cookies
+        .iter()
+        .find_map(|c| c.strip_prefix(&format!("{}=", name)))
+        .and_then(|c| c.split(';').next())
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn cookie_sessions_need_a_csrf_token() {
+    let pool = common::setup_test_db().await;
+    common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let (_, bearer) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let server = TestServer::new(common::create_test_app(pool)).unwrap();
+
+    let cookies = sign_in_cookies(&server).await;
+    let session = cookie_value(&cookies, "better-auth.session_token");
+    let csrf = cookie_value(&cookies, "flux.csrf_token");
+    assert!(cookies.iter().any(|c| c.starts_with("better-auth.session_token=") && c.contains("HttpOnly")));
+    assert!(cookies.iter().any(|c| c.starts_with("flux.csrf_token=") && !c.contains("HttpOnly")));
+    let cookie_header = format!("better-auth.session_token={}; flux.csrf_token={}", session, csrf);
+
+    // Reads don't need it
+    let (h, v) = header("cookie", &cookie_header);
+    server.get("/api/auth/sessions").add_header(h, v).await.assert_status_ok();
+
+    let (h, v) = header("cookie", &cookie_header);
+    let res = server.post("/api/auth/sessions/revoke-others").add_header(h, v).await;
+    res.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(res.json::<serde_json::Value>()["error"], "CSRF token missing or invalid");
+
+    let (h, v) = header("cookie", &cookie_header);
+    let (ch, cv) = header("x-csrf-token", "forged");
+    server
+        .post("/api/auth/sessions/revoke-others")
+        .add_header(h, v)
+        .add_header(ch, cv)
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    // A matching cookie and header from another site's session don't pass either
+    let (h, v) = header("cookie", &format!("better-auth.session_token={}; flux.csrf_token=forged", session));
+    let (ch, cv) = header("x-csrf-token", "forged");
+    server
+        .post("/api/auth/sessions/revoke-others")
+        .add_header(h, v)
+        .add_header(ch, cv)
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let (h, v) = header("cookie", &cookie_header);
+    let (ch, cv) = header("x-csrf-token", &csrf);
+    server
+        .post("/api/auth/sessions/revoke-others")
+        .add_header(h, v)
+        .add_header(ch, cv)
+        .await
+        .assert_status_ok();
+
+    // Bearer clients are exempt
+    let (h, v) = header("authorization", &format!("Bearer {}", bearer));
+    server.post("/api/auth/sessions/revoke-others").add_header(h, v).await.assert_status_ok();
+}
+
+#[tokio::test]
+async fn csrf_token_can_be_fetched_for_a_cookie_session() {
+    let pool = common::setup_test_db().await;
+    common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let server = TestServer::new(common::create_test_app(pool)).unwrap();
+
+    let cookies = sign_in_cookies(&server).await;
+    let session = cookie_value(&cookies, "better-auth.session_token");
+
+    server.get("/api/auth/csrf").await.assert_status_unauthorized();
+    let (h, v) = header("cookie", &format!("better-auth.session_token={}", session));
+    let res = server.get("/api/auth/csrf").add_header(h, v).await;
+    res.assert_status_ok();
+    let token = res.json::<serde_json::Value>()["csrfToken"].as_str().unwrap().to_string();
+    assert_eq!(token, cookie_value(&cookies, "flux.csrf_token"));
+}
+
+#[tokio::test]
+async fn cookie_attributes_follow_config() {
+    let pool = common::setup_test_db().await;
+    common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    for cookie in sign_in_cookies(&server).await {
+        assert!(cookie.contains("SameSite=Lax") && !cookie.contains("Secure"), "{}", cookie);
+    }
+
+    let mut config = common::test_config();
+    config.cookie_secure = true;
+    config.cookie_domain = "flux.example.com".into();
+    let server = TestServer::new(common::create_test_app_with_config(pool, config)).unwrap();
+    for cookie in sign_in_cookies(&server).await {
+        assert!(cookie.contains("SameSite=None; Secure"), "{}", cookie);
+        assert!(cookie.contains("Domain=flux.example.com"), "{}", cookie);
+    }
+}
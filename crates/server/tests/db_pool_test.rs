@@ -0,0 +1,75 @@
+use flux_server::db::{init_pools, PoolSettings};
+use std::time::Duration;
+
+fn db_path(name: &str) -> (String, String) {
+    let dir = format!("/tmp/flux-test-pools-{}-{}", name, uuid::Uuid::new_v4());
+    (format!("{}/flux.db", dir), dir)
+}
+
+#[tokio::test]
+async fn pools_follow_the_settings() {
+    let (path, dir) = db_path("settings");
+    let settings = PoolSettings {
+        max_connections: 3,
+        read_connections: 2,
+        busy_timeout: Duration::from_millis(1234),
+    };
+    let pools = init_pools(&path, settings).await.unwrap();
+
+    assert_eq!(pools.db.options().get_max_connections(), 3);
+    assert_eq!(pools.read.options().get_max_connections(), 2);
+    assert_eq!(pools.write.options().get_max_connections(), 1);
+
+    for pool in [&pools.db, &pools.read, &pools.write] {
+        let timeout: i64 = sqlx::query_scalar("PRAGMA busy_timeout").fetch_one(pool).await.unwrap();
+        assert_eq!(timeout, 1234);
+    }
+    let mode: String = sqlx::query_scalar("PRAGMA journal_mode").fetch_one(&pools.db).await.unwrap();
+    assert_eq!(mode, "wal");
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[tokio::test]
+async fn read_pool_is_read_only() {
+    let (path, dir) = db_path("readonly");
+    let pools = init_pools(&path, PoolSettings::default()).await.unwrap();
+
+    let count: i64 = sqlx::query_scalar(r#"SELECT COUNT(*) FROM "user""#)
+        .fetch_one(&pools.read)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+
+    let write = sqlx::query(r#"DELETE FROM "user""#).execute(&pools.read).await;
+    assert!(write.is_err());
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[tokio::test]
+async fn concurrent_writes_dont_lock() {
+    let (path, dir) = db_path("concurrent");
+    let pools = init_pools(&path, PoolSettings::default()).await.unwrap();
+    sqlx::query("CREATE TABLE hits (n INTEGER NOT NULL)").execute(&pools.db).await.unwrap();
+
+    // Transactions on the writer and the main pool at once: the writer
+    // queues its own, and busy_timeout covers the rest
+    let mut tasks = Vec::new();
+    for i in 0..40 {
+        let pool = if i % 2 == 0 { pools.write.clone() } else { pools.db.clone() };
+        tasks.push(tokio::spawn(async move {
+            let mut tx = pool.begin().await?;
+            sqlx::query("INSERT INTO hits (n) VALUES (?)").bind(i).execute(&mut *tx).await?;
+            tx.commit().await
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM hits").fetch_one(&pools.read).await.unwrap();
+    assert_eq!(count, 40);
+
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use serde_json::json;
+
+async fn dm_message_count(pool: &sqlx::SqlitePool, dm_channel_id: &str) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM dm_messages WHERE dm_channel_id = ?")
+        .bind(dm_channel_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn first_dm_is_a_request_until_accepted() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let client = reqwest::Client::new();
+
+    let res = client
+        .post(format!("{}/api/dms", base))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"userId": bob_id}))
+        .send()
+        .await
+        .unwrap();
+    let dm: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(dm["status"], "pending");
+    assert_eq!(dm["requestedBy"], alice_id);
+    let dm_id = dm["id"].as_str().unwrap().to_string();
+
+    // Bob's inbox doesn't list it; his requests do
+    let res = client
+        .get(format!("{}/api/dms", base))
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .send()
+        .await
+        .unwrap();
+    let dms: serde_json::Value = res.json().await.unwrap();
+    assert!(dms.as_array().unwrap().is_empty());
+
+    let mut alice_ws = ws_connect(&base, &alice_token).await;
+    drain_messages(&mut alice_ws).await;
+    let mut bob_ws = ws_connect(&base, &bob_token).await;
+    drain_messages(&mut bob_ws).await;
+
+    send_json(&mut alice_ws, &json!({"type": "send_dm", "dmChannelId": dm_id, "ciphertext": "aGk=", "mlsEpoch": 0})).await;
+    let events = drain_messages(&mut bob_ws).await;
+    let request = events.iter().find(|e| e["type"] == "dm_request").expect("request event");
+    assert_eq!(request["channel"]["otherUser"]["username"], "alice");
+    assert_eq!(request["message"]["ciphertext"], "aGk=");
+    assert!(!events.iter().any(|e| e["type"] == "dm_message"));
+
+    // One message until accepted, and no replies before accepting
+    send_json(&mut alice_ws, &json!({"type": "send_dm", "dmChannelId": dm_id, "ciphertext": "b25lIG1vcmU=", "mlsEpoch": 0})).await;
+    send_json(&mut bob_ws, &json!({"type": "send_dm", "dmChannelId": dm_id, "ciphertext": "eW8=", "mlsEpoch": 0})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    assert_eq!(dm_message_count(&pool, &dm_id).await, 1);
+    drain_messages(&mut alice_ws).await;
+
+    let res = client
+        .get(format!("{}/api/dms/requests", base))
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .send()
+        .await
+        .unwrap();
+    let requests: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(requests[0]["channel"]["id"], dm_id);
+    assert_eq!(requests[0]["message"]["ciphertext"], "aGk=");
+
+    // Only the recipient can accept
+    let res = client
+        .post(format!("{}/api/dms/{}/accept", base, dm_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 404);
+    let res = client
+        .post(format!("{}/api/dms/{}/accept", base, dm_id))
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 204);
+
+    let events = drain_messages(&mut alice_ws).await;
+    let accepted = events.iter().find(|e| e["type"] == "dm_request_accepted").expect("accepted event");
+    assert_eq!(accepted["userId"], bob_id);
+
+    send_json(&mut bob_ws, &json!({"type": "send_dm", "dmChannelId": dm_id, "ciphertext": "eW8=", "mlsEpoch": 0})).await;
+    send_json(&mut alice_ws, &json!({"type": "send_dm", "dmChannelId": dm_id, "ciphertext": "b2s=", "mlsEpoch": 0})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    assert_eq!(dm_message_count(&pool, &dm_id).await, 3);
+}
+
+#[tokio::test]
+async fn declined_requests_are_dropped_until_reopened() {
+    let (base, pool) = start_server().await;
+    let (_alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let client = reqwest::Client::new();
+
+    let res = client
+        .post(format!("{}/api/dms", base))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"userId": bob_id}))
+        .send()
+        .await
+        .unwrap();
+    let dm: serde_json::Value = res.json().await.unwrap();
+    let dm_id = dm["id"].as_str().unwrap().to_string();
+    let alice_id = dm["requestedBy"].as_str().unwrap().to_string();
+
+    let mut alice_ws = ws_connect(&base, &alice_token).await;
+    drain_messages(&mut alice_ws).await;
+    send_json(&mut alice_ws, &json!({"type": "send_dm", "dmChannelId": dm_id, "ciphertext": "c3BhbQ==", "mlsEpoch": 0})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let res = client
+        .post(format!("{}/api/dms/{}/decline", base, dm_id))
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 204);
+    assert_eq!(dm_message_count(&pool, &dm_id).await, 0);
+
+    drain_messages(&mut alice_ws).await;
+    send_json(&mut alice_ws, &json!({"type": "send_dm", "dmChannelId": dm_id, "ciphertext": "YWdhaW4=", "mlsEpoch": 0})).await;
+    let events = drain_messages(&mut alice_ws).await;
+    assert!(events.iter().any(|e| e["type"] == "error"));
+    assert_eq!(dm_message_count(&pool, &dm_id).await, 0);
+
+    // Bob reaching out himself accepts the conversation
+    let res = client
+        .post(format!("{}/api/dms", base))
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .json(&json!({"userId": alice_id}))
+        .send()
+        .await
+        .unwrap();
+    let dm: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(dm["id"], dm_id);
+    assert_eq!(dm["status"], "accepted");
+}
@@ -0,0 +1,191 @@
+mod common;
+
+use common::ws_helpers::{send_json, start_server, ws_connect};
+use serde_json::json;
+
+async fn get_json(base: &str, token: &str, path: &str) -> serde_json::Value {
+    reqwest::Client::new()
+        .get(format!("{}{}", base, path))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn daily_reward_builds_a_streak() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let client = reqwest::Client::new();
+    let auth = format!("Bearer {}", alice_token);
+
+    let wallet = get_json(&base, &alice_token, "/api/economy/wallet").await;
+    assert_eq!(wallet["balance"], 0);
+    assert_eq!(wallet["dailyClaimed"], false);
+
+    let res = client.post(format!("{}/api/economy/daily", base)).header("Authorization", &auth).send().await.unwrap();
+    assert_eq!(res.status(), 200);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["amount"], 100);
+    assert_eq!(body["streak"], 1);
+
+    let res = client.post(format!("{}/api/economy/daily", base)).header("Authorization", &auth).send().await.unwrap();
+    assert_eq!(res.status(), 409);
+
+    // Pretend the last claim was yesterday, on a 3-day streak
+    let yesterday = (chrono::Utc::now().date_naive() - chrono::Duration::days(1)).to_string();
+    sqlx::query(r#"UPDATE "daily_streaks" SET streak = 3, last_claim_date = ? WHERE user_id = ?"#)
+        .bind(&yesterday)
+        .bind(&alice_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let res = client.post(format!("{}/api/economy/daily", base)).header("Authorization", &auth).send().await.unwrap();
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["streak"], 4);
+    assert_eq!(body["amount"], 130);
+    assert_eq!(body["balance"], 230);
+
+    let wallet = get_json(&base, &alice_token, "/api/economy/wallet").await;
+    assert_eq!(wallet["balance"], 230);
+    assert_eq!(wallet["streak"], 4);
+    assert_eq!(wallet["dailyClaimed"], true);
+    assert_eq!(wallet["today"]["byReason"]["daily_login"], 230);
+}
+
+#[tokio::test]
+async fn messages_earn_within_cooldown_and_cap() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+
+    let mut ws = ws_connect(&base, &bob_token).await;
+    for i in 0..3 {
+        send_json(&mut ws, &json!({"type": "send_message", "channelId": channel_id, "content": format!("hello {}", i)})).await;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    // 60s cooldown: only the first message pays
+    let wallet = get_json(&base, &bob_token, "/api/economy/wallet").await;
+    assert_eq!(wallet["today"]["byReason"]["message"], 2);
+
+    let client = reqwest::Client::new();
+    let patch = json!({"cooldownSecs": 0, "dailyCap": 5});
+    let res = client
+        .patch(format!("{}/api/economy/rules/message", base))
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .json(&patch)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 403);
+    let res = client
+        .patch(format!("{}/api/economy/rules/message", base))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&patch)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+
+    for i in 0..5 {
+        send_json(&mut ws, &json!({"type": "send_message", "channelId": channel_id, "content": format!("again {}", i)})).await;
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    // Capped at 5 for the day, the last payout trimmed to fit
+    let wallet = get_json(&base, &bob_token, "/api/economy/wallet").await;
+    assert_eq!(wallet["today"]["byReason"]["message"], 5);
+    assert_eq!(wallet["balance"], 5);
+}
+
+#[tokio::test]
+async fn admin_grants_and_revokes_with_reason() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let client = reqwest::Client::new();
+
+    let grant = |token: &str, amount: i64| {
+        client
+            .post(format!("{}/api/economy/admin/coins", base))
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&json!({"userId": bob_id, "amount": amount, "reason": "event prize"}))
+            .send()
+    };
+
+    assert_eq!(grant(&bob_token, 500).await.unwrap().status(), 403);
+    let res = grant(&alice_token, 500).await.unwrap();
+    assert_eq!(res.status(), 200);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["balance"], 500);
+
+    let res = grant(&alice_token, -800).await.unwrap();
+    assert_eq!(res.status(), 400);
+    let res = grant(&alice_token, -200).await.unwrap();
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["balance"], 300);
+
+    let log = get_json(&base, &alice_token, &format!("/api/economy/admin/log/{}", bob_id)).await;
+    let log = log.as_array().unwrap();
+    assert_eq!(log.len(), 2);
+    let revoke = log.iter().find(|e| e["reason"] == "admin_revoke").unwrap();
+    assert_eq!(revoke["amount"], -200);
+    assert_eq!(revoke["note"], "event prize");
+    assert_eq!(revoke["actorId"], alice_id.as_str());
+}
+
+#[tokio::test]
+async fn paused_server_stops_earning() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+    let client = reqwest::Client::new();
+    let pause_url = format!("{}/api/economy/servers/{}/pause", base, server_id);
+
+    let res = client
+        .put(&pause_url)
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"minutes": 30, "reason": "exploit"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    let status = get_json(&base, &alice_token, &format!("/api/economy/servers/{}", server_id)).await;
+    assert_eq!(status["paused"], true);
+    assert_eq!(status["reason"], "exploit");
+
+    let mut ws = ws_connect(&base, &alice_token).await;
+    send_json(&mut ws, &json!({"type": "send_message", "channelId": channel_id, "content": "hello"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let wallet = get_json(&base, &alice_token, "/api/economy/wallet").await;
+    assert_eq!(wallet["balance"], 0);
+
+    let res = client
+        .delete(&pause_url)
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    send_json(&mut ws, &json!({"type": "send_message", "channelId": channel_id, "content": "hello again"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let wallet = get_json(&base, &alice_token, "/api/economy/wallet").await;
+    assert_eq!(wallet["balance"], 2);
+}
@@ -0,0 +1,175 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn insert_message(pool: &sqlx::SqlitePool, channel_id: &str, sender_id: &str, content: &str, mentions: &[&str]) {
+    let metadata = json!({ "mentions": mentions, "mentionsEveryone": false, "mentionsHere": false });
+    sqlx::query("INSERT INTO messages (id, channel_id, sender_id, content, created_at, metadata) VALUES (?, ?, ?, ?, ?, ?)")
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(channel_id)
+        .bind(sender_id)
+        .bind(content)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(metadata.to_string())
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+async fn insert_dm(pool: &sqlx::SqlitePool, dm_channel_id: &str, sender_id: &str) {
+    sqlx::query("INSERT INTO dm_messages (id, dm_channel_id, sender_id, ciphertext, created_at) VALUES (?, ?, ?, 'x', ?)")
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(dm_channel_id)
+        .bind(sender_id)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+/// Pretend the last digest went out two days ago.
+async fn backdate_digest(pool: &sqlx::SqlitePool, user_id: &str) {
+    sqlx::query("UPDATE email_digests SET last_sent_at = ? WHERE user_id = ?")
+        .bind((chrono::Utc::now() - chrono::Duration::days(2)).to_rfc3339())
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+async fn next_email(mail: &mut mpsc::UnboundedReceiver<String>) -> String {
+    let email = tokio::time::timeout(std::time::Duration::from_secs(5), mail.recv())
+        .await
+        .expect("email sent")
+        .unwrap();
+    // Undo quoted-printable line wrapping
+    email.replace("=\n", "").replace("=3D", "=")
+}
+
+#[tokio::test]
+async fn digest_settings_need_smtp_and_a_verified_email() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (_, token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+
+    let (h, v) = auth_header(&token);
+    let settings: serde_json::Value = server.get("/api/users/me/digest").add_header(h, v).await.json();
+    assert_eq!(settings["frequency"], "off");
+    assert_eq!(settings["available"], false);
+
+    let (h, v) = auth_header(&token);
+    server
+        .put("/api/users/me/digest")
+        .add_header(h, v)
+        .json(&json!({ "frequency": "daily" }))
+        .await
+        .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+
+    let (port, _mail) = common::smtp::start_smtp().await;
+    let mut config = common::test_config();
+    config.smtp_host = "127.0.0.1".into();
+    config.smtp_port = port;
+    let server = TestServer::new(common::create_test_app_with_config(pool.clone(), config)).unwrap();
+
+    let (h, v) = auth_header(&token);
+    server
+        .put("/api/users/me/digest")
+        .add_header(h, v)
+        .json(&json!({ "frequency": "hourly" }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    let (h, v) = auth_header(&token);
+    server
+        .put("/api/users/me/digest")
+        .add_header(h, v)
+        .json(&json!({ "frequency": "daily" }))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn digest_lists_unread_mentions_and_dms_and_can_be_unsubscribed() {
+    let (port, mut mail) = common::smtp::start_smtp().await;
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    config.smtp_host = "127.0.0.1".into();
+    config.smtp_port = port;
+    let state = common::create_test_state(pool.clone(), config);
+    let server = TestServer::new(flux_server::routes::build_router(state.clone())).unwrap();
+
+    let (alice_id, _) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    sqlx::query(r#"UPDATE "user" SET emailVerified = 1 WHERE id = ?"#)
+        .bind(&bob_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let server_id = common::create_test_server(&pool, &alice_id, "flux").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let general = common::create_text_channel(&pool, &server_id, "general").await;
+    let random = common::create_text_channel(&pool, &server_id, "random").await;
+    let dm_id = uuid::Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO dm_channels (id, user1_id, user2_id, created_at) VALUES (?, ?, ?, ?)")
+        .bind(&dm_id)
+        .bind(&alice_id)
+        .bind(&bob_id)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (h, v) = auth_header(&bob_token);
+    let res = server.put("/api/users/me/digest").add_header(h, v).json(&json!({ "frequency": "daily" })).await;
+    res.assert_status_ok();
+    assert_eq!(res.json::<serde_json::Value>()["frequency"], "daily");
+
+    insert_message(&pool, &general, &alice_id, "hey @bob, can you review the release notes?", &["bob"]).await;
+    insert_message(&pool, &general, &alice_id, "nothing for bob here", &[]).await;
+    insert_message(&pool, &random, &alice_id, "@bob already read this one", &["bob"]).await;
+    insert_dm(&pool, &dm_id, &alice_id).await;
+    insert_dm(&pool, &dm_id, &alice_id).await;
+
+    let (h, v) = auth_header(&bob_token);
+    server
+        .put(&format!("/api/channels/{}/read", random))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    // Not due yet
+    assert_eq!(flux_server::routes::users::send_due_digests(&state).await, 0);
+
+    backdate_digest(&pool, &bob_id).await;
+    assert_eq!(flux_server::routes::users::send_due_digests(&state).await, 1);
+    let email = next_email(&mut mail).await;
+    assert!(email.contains("To: bob@test.com"));
+    assert!(email.contains("Your Flux digest: 1 mention, 2 direct messages"));
+    assert!(email.contains("#general in flux: alice: hey @bob, can you review the release notes?"));
+    assert!(!email.contains("already read this one"));
+    assert!(email.contains("alice sent you 2 messages"));
+
+    // The next one starts from here
+    assert_eq!(flux_server::routes::users::send_due_digests(&state).await, 0);
+
+    let start = email.find("/api/digest/unsubscribe?").expect("unsubscribe link");
+    let link: String = email[start..].chars().take_while(|c| !c.is_whitespace()).collect();
+    server
+        .get(&link.replace(&format!("user={}", bob_id), &format!("user={}", alice_id)))
+        .await
+        .assert_text_contains("Link invalid");
+    server.get(&link).await.assert_text_contains("Unsubscribed");
+
+    let (h, v) = auth_header(&bob_token);
+    let settings: serde_json::Value = server.get("/api/users/me/digest").add_header(h, v).await.json();
+    assert_eq!(settings["frequency"], "off");
+}
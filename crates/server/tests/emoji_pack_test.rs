@@ -0,0 +1,166 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::multipart::{MultipartForm, Part};
+use axum_test::TestServer;
+use std::io::{Cursor, Read, Write};
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn setup() -> (TestServer, sqlx::SqlitePool) {
+    let pool = common::setup_test_db().await;
+    let app = common::create_test_app(pool.clone());
+    let server = TestServer::new(app).unwrap();
+    std::fs::create_dir_all("/tmp/flux-test-uploads").ok();
+    (server, pool)
+}
+
+/// Build a pack archive from (name, file, bytes) entries.
+fn make_pack(entries: &[(&str, &str, &[u8])]) -> Vec<u8> {
+    use zip::write::SimpleFileOptions;
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let manifest: Vec<_> = entries
+        .iter()
+        .map(|(name, file, _)| serde_json::json!({"name": name, "file": file}))
+        .collect();
+    zip.start_file("manifest.json", SimpleFileOptions::default()).unwrap();
+    zip.write_all(serde_json::json!({"version": 1, "emojis": manifest}).to_string().as_bytes())
+        .unwrap();
+    for (_, file, data) in entries {
+        zip.start_file(*file, SimpleFileOptions::default()).unwrap();
+        zip.write_all(data).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+fn pack_form(data: Vec<u8>) -> MultipartForm {
+    MultipartForm::new().add_part(
+        "file",
+        Part::bytes(data).file_name("pack.zip").mime_type("application/zip"),
+    )
+}
+
+#[tokio::test]
+async fn import_then_export_round_trips() {
+    let (server, pool) = setup().await;
+    let (user_id, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let server_id = common::create_test_server(&pool, &user_id, "TestServer").await;
+
+    let pack = make_pack(&[
+        ("party", "emojis/party.png", b"png-bytes"),
+        ("wave", "emojis/wave.gif", b"gif-bytes"),
+    ]);
+    let (h, v) = auth_header(&token);
+    let res = server
+        .post(&format!("/api/servers/{}/emojis/import", server_id))
+        .add_header(h, v)
+        .multipart(pack_form(pack))
+        .await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["imported"].as_array().unwrap().len(), 2);
+
+    let (h, v) = auth_header(&token);
+    let res = server
+        .get(&format!("/api/servers/{}/emojis/export", server_id))
+        .add_header(h, v)
+        .await;
+    res.assert_status_ok();
+    assert_eq!(res.header("content-type"), "application/zip");
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(res.as_bytes().to_vec())).unwrap();
+    let mut manifest = String::new();
+    archive.by_name("manifest.json").unwrap().read_to_string(&mut manifest).unwrap();
+    let manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+    assert_eq!(manifest["emojis"].as_array().unwrap().len(), 2);
+
+    let mut data = Vec::new();
+    archive.by_name("emojis/wave.gif").unwrap().read_to_end(&mut data).unwrap();
+    assert_eq!(data, b"gif-bytes");
+}
+
+#[tokio::test]
+async fn import_handles_name_collisions() {
+    let (server, pool) = setup().await;
+    let (user_id, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let server_id = common::create_test_server(&pool, &user_id, "TestServer").await;
+
+    let import = |on_conflict: &'static str| {
+        let (h, v) = auth_header(&token);
+        server
+            .post(&format!("/api/servers/{}/emojis/import?onConflict={}", server_id, on_conflict))
+            .add_header(h, v)
+            .multipart(pack_form(make_pack(&[("party", "emojis/party.png", b"x")])))
+    };
+
+    import("rename").await.assert_status_ok();
+
+    let body: serde_json::Value = import("rename").await.json();
+    assert_eq!(body["renamed"][0]["to"], "party_2");
+
+    let body: serde_json::Value = import("skip").await.json();
+    assert!(body["imported"].as_array().unwrap().is_empty());
+    assert_eq!(body["skipped"][0]["name"], "party");
+
+    let body: serde_json::Value = import("replace").await.json();
+    assert_eq!(body["imported"][0]["name"], "party");
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM custom_emojis WHERE server_id = ?")
+        .bind(&server_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+}
+
+#[tokio::test]
+async fn import_rejects_invalid_packs_and_non_admins() {
+    let (server, pool) = setup().await;
+    let (owner_id, owner_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (member_id, member_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "TestServer").await;
+    common::add_member(&pool, &member_id, &server_id, "member").await;
+
+    let (h, v) = auth_header(&member_token);
+    let res = server
+        .post(&format!("/api/servers/{}/emojis/import", server_id))
+        .add_header(h, v)
+        .multipart(pack_form(make_pack(&[("party", "emojis/party.png", b"x")])))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let (h, v) = auth_header(&owner_token);
+    let res = server
+        .post(&format!("/api/servers/{}/emojis/import", server_id))
+        .add_header(h, v)
+        .multipart(pack_form(make_pack(&[("bad name", "emojis/bad.png", b"x")])))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    let oversized = vec![0u8; 262145];
+    let (h, v) = auth_header(&owner_token);
+    let res = server
+        .post(&format!("/api/servers/{}/emojis/import", server_id))
+        .add_header(h, v)
+        .multipart(pack_form(make_pack(&[("big", "emojis/big.png", &oversized)])))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    let (h, v) = auth_header(&owner_token);
+    let res = server
+        .post(&format!("/api/servers/{}/emojis/import", server_id))
+        .add_header(h, v)
+        .multipart(pack_form(b"not a zip".to_vec()))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+}
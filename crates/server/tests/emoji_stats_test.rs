@@ -0,0 +1,89 @@
+mod common;
+
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use serde_json::json;
+
+async fn insert_emoji(pool: &sqlx::SqlitePool, server_id: &str, user_id: &str, name: &str) -> String {
+    let attachment_id = common::create_test_attachment(pool, user_id, &format!("{}.png", name), "image/png").await;
+    let emoji_id = uuid::Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO custom_emojis (id, server_id, name, attachment_id, filename, uploader_id, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)")
+        .bind(&emoji_id)
+        .bind(server_id)
+        .bind(name)
+        .bind(&attachment_id)
+        .bind(format!("{}.png", name))
+        .bind(user_id)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(pool)
+        .await
+        .unwrap();
+    emoji_id
+}
+
+#[tokio::test]
+async fn emoji_stats_count_messages_and_reactions() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+    let party_id = insert_emoji(&pool, &server_id, &alice_id, "party").await;
+    let dusty_id = insert_emoji(&pool, &server_id, &alice_id, "dusty").await;
+
+    let mut ws = ws_connect(&base, &alice_token).await;
+    drain_messages(&mut ws).await;
+    send_json(&mut ws, &json!({"type": "join_channel", "channelId": channel_id})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    // Repeats in one message count once; unknown names are ignored
+    send_json(
+        &mut ws,
+        &json!({"type": "send_message", "channelId": channel_id, "content": ":party: :party: :nope:", "attachmentIds": []}),
+    )
+    .await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let msg_id = sqlx::query_scalar::<_, String>("SELECT id FROM messages WHERE channel_id = ?")
+        .bind(&channel_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    send_json(&mut ws, &json!({"type": "add_reaction", "messageId": msg_id, "emoji": format!(":party:{party_id}")})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let client = reqwest::Client::new();
+    let res = client
+        .get(format!("{}/api/servers/{}/emojis/stats?days=7", base, server_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["days"], 7);
+    let emojis = body["emojis"].as_array().unwrap();
+
+    // Never-used emojis sort first
+    assert_eq!(emojis[0]["id"], dusty_id);
+    assert!(emojis[0]["lastUsedAt"].is_null());
+    assert!(emojis[0]["usage"].as_array().unwrap().is_empty());
+
+    assert_eq!(emojis[1]["id"], party_id);
+    assert_eq!(emojis[1]["messages"], 1);
+    assert_eq!(emojis[1]["reactions"], 1);
+    assert!(emojis[1]["lastUsedAt"].is_string());
+    let usage = emojis[1]["usage"].as_array().unwrap();
+    assert_eq!(usage.len(), 1);
+    assert_eq!(usage[0]["day"], chrono::Utc::now().date_naive().to_string());
+
+    // Stats are for server admins
+    let res = client
+        .get(format!("{}/api/servers/{}/emojis/stats", base, server_id))
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 403);
+}
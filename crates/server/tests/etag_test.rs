@@ -0,0 +1,110 @@
+mod common;
+
+use axum::http::{header, HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn get_with_etag(server: &TestServer, path: &str, token: &str, etag: Option<&HeaderValue>) -> axum_test::TestResponse {
+    let (h, v) = auth_header(token);
+    let mut req = server.get(path).add_header(h, v);
+    if let Some(etag) = etag {
+        req = req.add_header(header::IF_NONE_MATCH, etag.clone());
+    }
+    req.await
+}
+
+#[tokio::test]
+async fn member_list_revalidates_until_it_changes() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (owner_id, token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    let path = format!("/api/servers/{}/members", server_id);
+
+    let res = get_with_etag(&server, &path, &token, None).await;
+    res.assert_status_ok();
+    let etag = res.header(header::ETAG);
+    assert!(etag.to_str().unwrap().starts_with("W/\""));
+
+    let res = get_with_etag(&server, &path, &token, Some(&etag)).await;
+    res.assert_status(StatusCode::NOT_MODIFIED);
+    assert!(res.as_bytes().is_empty());
+    assert_eq!(res.header(header::ETAG), etag);
+
+    // A strong form of the same tag still matches under weak comparison
+    let strong = HeaderValue::from_str(etag.to_str().unwrap().trim_start_matches("W/")).unwrap();
+    get_with_etag(&server, &path, &token, Some(&strong))
+        .await
+        .assert_status(StatusCode::NOT_MODIFIED);
+
+    // A new member changes it
+    let (member_id, _) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    common::add_member(&pool, &member_id, &server_id, "member").await;
+    let res = get_with_etag(&server, &path, &token, Some(&etag)).await;
+    res.assert_status_ok();
+    let etag = res.header(header::ETAG);
+
+    // So does a profile edit
+    sqlx::query(r#"UPDATE "user" SET updatedAt = ? WHERE id = ?"#)
+        .bind("2099-01-01T00:00:00+00:00")
+        .bind(&member_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let res = get_with_etag(&server, &path, &token, Some(&etag)).await;
+    res.assert_status_ok();
+    assert_ne!(res.header(header::ETAG), etag);
+
+    // Each page has its own tag
+    let first_page = get_with_etag(&server, &format!("{}?limit=1", path), &token, None).await;
+    assert_ne!(first_page.header(header::ETAG), res.header(header::ETAG));
+}
+
+#[tokio::test]
+async fn emoji_list_etag_tracks_additions() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (owner_id, token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    let path = format!("/api/servers/{}/emojis", server_id);
+
+    let etag = get_with_etag(&server, &path, &token, None).await.header(header::ETAG);
+    get_with_etag(&server, &path, &token, Some(&etag))
+        .await
+        .assert_status(StatusCode::NOT_MODIFIED);
+
+    let attachment_id = common::create_test_attachment(&pool, &owner_id, "party.png", "image/png").await;
+    sqlx::query(
+        "INSERT INTO custom_emojis (id, server_id, name, attachment_id, filename, uploader_id, created_at) VALUES ('e1', ?, 'party', ?, 'party.png', ?, ?)",
+    )
+    .bind(&server_id)
+    .bind(&attachment_id)
+    .bind(&owner_id)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let res = get_with_etag(&server, &path, &token, Some(&etag)).await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["items"][0]["name"], "party");
+}
+
+#[tokio::test]
+async fn endpoints_without_an_etag_ignore_if_none_match() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (owner_id, token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    common::create_test_server(&pool, &owner_id, "flux").await;
+
+    get_with_etag(&server, "/api/servers", &token, Some(&HeaderValue::from_static("*")))
+        .await
+        .assert_status_ok();
+}
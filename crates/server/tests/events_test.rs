@@ -0,0 +1,231 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use flux_server::routes::events::{next_occurrence, send_due_reminders};
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+fn at(value: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(value).unwrap().with_timezone(&Utc)
+}
+
+fn from_now(minutes: i64) -> String {
+    (Utc::now() + Duration::minutes(minutes)).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[test]
+fn next_occurrence_follows_recurrence() {
+    let start = at("2026-01-31T18:00:00Z");
+    let after = at("2026-03-10T12:00:00Z");
+
+    assert_eq!(next_occurrence(start, None, at("2026-01-01T00:00:00Z")), Some(start));
+    assert_eq!(next_occurrence(start, None, after), None);
+    assert_eq!(next_occurrence(start, Some("daily"), after), Some(at("2026-03-10T18:00:00Z")));
+    assert_eq!(next_occurrence(start, Some("weekly"), after), Some(at("2026-03-14T18:00:00Z")));
+    // Counted from the original start, so March gets the 31st back
+    assert_eq!(next_occurrence(start, Some("monthly"), after), Some(at("2026-03-31T18:00:00Z")));
+    assert_eq!(
+        next_occurrence(start, Some("monthly"), at("2026-02-15T00:00:00Z")),
+        Some(at("2026-02-28T18:00:00Z"))
+    );
+}
+
+#[tokio::test]
+async fn events_crud_and_rsvps() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (owner_id, owner_token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let (_, outsider_token) = common::create_test_user(&pool, "out@test.com", "outsider", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+    let other_server = common::create_test_server(&pool, &owner_id, "other").await;
+    let other_channel = common::create_text_channel(&pool, &other_server, "elsewhere").await;
+    let path = format!("/api/servers/{}/events", server_id);
+
+    let body = json!({ "title": "Game night", "startAt": "2030-05-01T20:00:00+02:00", "channelId": channel_id });
+    let (h, v) = auth_header(&bob_token);
+    server.post(&path).add_header(h, v).json(&body).await.assert_status(StatusCode::FORBIDDEN);
+
+    for bad in [
+        json!({ "title": "x", "startAt": "tomorrow" }),
+        json!({ "title": "x", "startAt": "2030-05-01T20:00:00Z", "endAt": "2030-05-01T19:00:00Z" }),
+        json!({ "title": "x", "startAt": "2030-05-01T20:00:00Z", "recurrence": "hourly" }),
+        json!({ "title": "x", "startAt": "2030-05-01T20:00:00Z", "channelId": other_channel }),
+        json!({ "title": "  ", "startAt": "2030-05-01T20:00:00Z" }),
+    ] {
+        let (h, v) = auth_header(&owner_token);
+        server.post(&path).add_header(h, v).json(&bad).await.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    let (h, v) = auth_header(&owner_token);
+    let res = server.post(&path).add_header(h, v).json(&body).await;
+    res.assert_status(StatusCode::CREATED);
+    let event: serde_json::Value = res.json();
+    // Stored in UTC
+    assert_eq!(event["startAt"], "2030-05-01T18:00:00Z");
+    assert_eq!(event["nextStartAt"], "2030-05-01T18:00:00Z");
+    let event_path = format!("{}/{}", path, event["id"].as_str().unwrap());
+
+    let (h, v) = auth_header(&outsider_token);
+    server.get(&path).add_header(h, v).await.assert_status(StatusCode::FORBIDDEN);
+
+    let (h, v) = auth_header(&bob_token);
+    let res = server.put(&format!("{}/rsvp", event_path)).add_header(h, v).json(&json!({ "status": "going" })).await;
+    res.assert_status_ok();
+    let updated: serde_json::Value = res.json();
+    assert_eq!(updated["goingCount"], 1);
+    assert_eq!(updated["myRsvp"], "going");
+
+    let (h, v) = auth_header(&bob_token);
+    server
+        .put(&format!("{}/rsvp", event_path))
+        .add_header(h, v)
+        .json(&json!({ "status": "maybe" }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    let (h, v) = auth_header(&bob_token);
+    let updated: serde_json::Value = server
+        .put(&format!("{}/rsvp", event_path))
+        .add_header(h, v)
+        .json(&json!({ "status": "interested" }))
+        .await
+        .json();
+    assert_eq!(updated["goingCount"], 0);
+    assert_eq!(updated["interestedCount"], 1);
+
+    let (h, v) = auth_header(&owner_token);
+    let rsvps: serde_json::Value = server.get(&format!("{}/rsvps", event_path)).add_header(h, v).await.json();
+    assert_eq!(rsvps.as_array().unwrap().len(), 1);
+    assert_eq!(rsvps[0]["username"], "bob");
+    assert_eq!(rsvps[0]["status"], "interested");
+
+    let (h, v) = auth_header(&owner_token);
+    let listed: serde_json::Value = server.get(&path).add_header(h, v).await.json();
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+    assert!(listed[0]["myRsvp"].is_null());
+
+    let (h, v) = auth_header(&owner_token);
+    let res = server
+        .patch(&event_path)
+        .add_header(h, v)
+        .json(&json!({ "title": "Board game night", "recurrence": "weekly", "channelId": "" }))
+        .await;
+    res.assert_status_ok();
+    let updated: serde_json::Value = res.json();
+    assert_eq!(updated["title"], "Board game night");
+    assert_eq!(updated["recurrence"], "weekly");
+    assert!(updated["channelId"].is_null());
+    assert_eq!(updated["startAt"], "2030-05-01T18:00:00Z");
+
+    let (h, v) = auth_header(&bob_token);
+    let updated: serde_json::Value = server.delete(&format!("{}/rsvp", event_path)).add_header(h, v).await.json();
+    assert_eq!(updated["interestedCount"], 0);
+
+    let (h, v) = auth_header(&bob_token);
+    server.delete(&event_path).add_header(h, v).await.assert_status(StatusCode::FORBIDDEN);
+    let (h, v) = auth_header(&owner_token);
+    server.delete(&event_path).add_header(h, v).await.assert_status(StatusCode::NO_CONTENT);
+    let (h, v) = auth_header(&owner_token);
+    server.get(&event_path).add_header(h, v).await.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn reminders_go_to_interested_members_once() {
+    let pool = common::setup_test_db().await;
+    let state = common::create_test_state(pool.clone(), common::test_config());
+    let server = TestServer::new(flux_server::routes::build_router(state.clone())).unwrap();
+    let (owner_id, owner_token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    let mut tokens = Vec::new();
+    for name in ["bob", "carol", "dave"] {
+        let (id, token) = common::create_test_user(&pool, &format!("{}@test.com", name), name, "password123").await;
+        common::add_member(&pool, &id, &server_id, "member").await;
+        tokens.push(token);
+    }
+    let path = format!("/api/servers/{}/events", server_id);
+
+    let mut event_ids = Vec::new();
+    for minutes in [5, 120] {
+        let (h, v) = auth_header(&owner_token);
+        let event: serde_json::Value = server
+            .post(&path)
+            .add_header(h, v)
+            .json(&json!({ "title": format!("In {} minutes", minutes), "startAt": from_now(minutes) }))
+            .await
+            .json();
+        event_ids.push(event["id"].as_str().unwrap().to_string());
+    }
+    for (token, status) in tokens.iter().zip(["going", "interested", "not_going"]) {
+        for event_id in &event_ids {
+            let (h, v) = auth_header(token);
+            server
+                .put(&format!("{}/{}/rsvp", path, event_id))
+                .add_header(h, v)
+                .json(&json!({ "status": status }))
+                .await
+                .assert_status_ok();
+        }
+    }
+
+    // Only the event inside the reminder window, and only going + interested
+    assert_eq!(send_due_reminders(&state).await, 2);
+    assert_eq!(send_due_reminders(&state).await, 0);
+
+    // Moving the later event into the window reminds for it too
+    let (h, v) = auth_header(&owner_token);
+    server
+        .patch(&format!("{}/{}", path, event_ids[1]))
+        .add_header(h, v)
+        .json(&json!({ "startAt": from_now(10) }))
+        .await
+        .assert_status_ok();
+    assert_eq!(send_due_reminders(&state).await, 2);
+}
+
+#[tokio::test]
+async fn ics_export_lists_server_events() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (owner_id, owner_token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+
+    let (h, v) = auth_header(&owner_token);
+    server
+        .post(&format!("/api/servers/{}/events", server_id))
+        .add_header(h, v)
+        .json(&json!({
+            "title": "Standup, daily; short",
+            "description": "Bring updates\nand coffee",
+            "startAt": "2030-01-07T09:00:00Z",
+            "endAt": "2030-01-07T09:15:00Z",
+            "recurrence": "weekly",
+            "channelId": channel_id,
+        }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let (h, v) = auth_header(&owner_token);
+    let res = server.get(&format!("/api/servers/{}/events.ics", server_id)).add_header(h, v).await;
+    res.assert_status_ok();
+    assert!(res.header("content-type").to_str().unwrap().starts_with("text/calendar"));
+    let ics = res.text();
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(ics.contains("DTSTART:20300107T090000Z\r\n"));
+    assert!(ics.contains("DTEND:20300107T091500Z\r\n"));
+    assert!(ics.contains("RRULE:FREQ=WEEKLY\r\n"));
+    assert!(ics.contains("SUMMARY:Standup\\, daily\\; short\r\n"));
+    assert!(ics.contains("DESCRIPTION:Bring updates\\nand coffee\r\n"));
+    assert!(ics.contains("LOCATION:#general\r\n"));
+    assert!(ics.trim_end().ends_with("END:VCALENDAR"));
+}
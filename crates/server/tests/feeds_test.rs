@@ -0,0 +1,152 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use flux_server::routes::feeds::{parse_feed, poll_feeds};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+fn rss(items: &[(&str, &str)]) -> String {
+    let items: String = items
+        .iter()
+        .map(|(guid, title)| {
+            format!(
+                "<item><title>{}</title><link>https://blog.test/{}</link><guid>{}</guid></item>",
+                title, guid, guid
+            )
+        })
+        .collect();
+    format!(
+        r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Dev Blog</title>{}</channel></rss>"#,
+        items
+    )
+}
+
+/// Serve whatever `body` holds at `/feed.xml`; returns the feed URL.
+async fn serve_feed(body: Arc<Mutex<String>>) -> String {
+    let app = axum::Router::new().route(
+        "/feed.xml",
+        axum::routing::get(move || {
+            let body = body.clone();
+            async move { body.lock().unwrap().clone() }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}/feed.xml", addr)
+}
+
+#[test]
+fn parses_rss_and_atom() {
+    let feed = parse_feed(
+        r#"<rss><channel><title>News &amp; Notes</title>
+             <image><title>logo</title></image>
+             <item><title><![CDATA[Hello <world>]]></title><link>https://a.test/1</link><guid isPermaLink="false">one</guid></item>
+             <item><title>No guid</title><link>https://a.test/2</link></item>
+           </channel></rss>"#,
+    )
+    .unwrap();
+    assert_eq!(feed.title.as_deref(), Some("News & Notes"));
+    assert_eq!(feed.entries.len(), 2);
+    assert_eq!(feed.entries[0].guid, "one");
+    assert_eq!(feed.entries[0].title.as_deref(), Some("Hello <world>"));
+    assert_eq!(feed.entries[1].guid, "https://a.test/2");
+
+    let feed = parse_feed(
+        r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>Atom</title>
+             <entry><id>urn:1</id><title>First</title>
+               <link rel="self" href="https://a.test/self"/><link href="https://a.test/first"/></entry>
+           </feed>"#,
+    )
+    .unwrap();
+    assert_eq!(feed.title.as_deref(), Some("Atom"));
+    assert_eq!(feed.entries[0].guid, "urn:1");
+    assert_eq!(feed.entries[0].link.as_deref(), Some("https://a.test/first"));
+
+    assert!(parse_feed("<html><body>nope</body></html>").is_none());
+    assert!(parse_feed("not xml at all <<").is_none());
+}
+
+#[tokio::test]
+async fn new_entries_are_posted_once() {
+    let pool = common::setup_test_db().await;
+    let state = common::create_test_state(pool.clone(), common::test_config());
+    let server = TestServer::new(flux_server::routes::build_router(state.clone())).unwrap();
+    let (owner_id, owner_token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "news").await;
+
+    let body = Arc::new(Mutex::new(rss(&[("a", "Old post")])));
+    let url = serve_feed(body.clone()).await;
+    let path = format!("/api/servers/{}/channels/{}/feeds", server_id, channel_id);
+
+    let (h, v) = auth_header(&bob_token);
+    server.post(&path).add_header(h, v).json(&json!({ "url": url })).await.assert_status(StatusCode::FORBIDDEN);
+
+    let (h, v) = auth_header(&owner_token);
+    let res = server.post(&path).add_header(h, v).json(&json!({ "url": url })).await;
+    res.assert_status(StatusCode::CREATED);
+    let feed: serde_json::Value = res.json();
+    assert_eq!(feed["title"], "Dev Blog");
+
+    let (h, v) = auth_header(&owner_token);
+    server.post(&path).add_header(h, v).json(&json!({ "url": url })).await.assert_status(StatusCode::CONFLICT);
+    let (h, v) = auth_header(&owner_token);
+    server
+        .post(&path)
+        .add_header(h, v)
+        .json(&json!({ "url": url.replace("feed.xml", "missing") }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    // What was in the feed when it was added is not posted
+    poll_feeds(&state).await;
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM messages WHERE channel_id = ?")
+        .bind(&channel_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+
+    *body.lock().unwrap() = rss(&[("b", "New @everyone post"), ("a", "Old post")]);
+    poll_feeds(&state).await;
+    poll_feeds(&state).await;
+
+    let posted: Vec<(String, String, Option<String>)> =
+        sqlx::query_as("SELECT content, sender_id, feed_id FROM messages WHERE channel_id = ?")
+            .bind(&channel_id)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(posted.len(), 1);
+    assert!(posted[0].0.contains("https://blog.test/b"));
+    assert!(!posted[0].0.contains("@everyone"));
+    assert_eq!(posted[0].1, owner_id);
+    assert_eq!(posted[0].2.as_deref(), feed["id"].as_str());
+
+    let (h, v) = auth_header(&owner_token);
+    let feeds: serde_json::Value = server.get(&path).add_header(h, v).await.json();
+    assert!(feeds[0]["lastError"].is_null());
+
+    let (h, v) = auth_header(&owner_token);
+    server
+        .delete(&format!("{}/{}", path, feed["id"].as_str().unwrap()))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let (h, v) = auth_header(&owner_token);
+    let feeds: serde_json::Value = server.get(&path).add_header(h, v).await.json();
+    assert!(feeds.as_array().unwrap().is_empty());
+}
@@ -84,9 +84,13 @@ async fn upload_file_too_large() {
     // Create a custom app with a very small max upload size
     let state = Arc::new(AppState {
         db: pool.clone(),
+        db_read: pool.clone(),
+        db_write: pool.clone(),
         config: Config {
             host: "127.0.0.1".into(),
             port: 0,
+            unix_socket: "".into(),
+            unix_socket_mode: "660".into(),
             database_path: ":memory:".into(),
             auth_secret: "test-secret".into(),
             livekit_api_key: "".into(),
@@ -94,11 +98,80 @@ async fn upload_file_too_large() {
             livekit_url: "ws://localhost:7880".into(),
             upload_dir: "/tmp/flux-test-uploads".into(),
             max_upload_bytes: 100, // Very small limit
+            avatar_max_bytes: 2_097_152,
             room_cleanup_delay_secs: 2,
+            youtube_cache_dir: "/tmp/flux-test-youtube-cache".into(),
+            youtube_cache_max_bytes: 10_485_760,
+            gif_provider: "tenor".into(),
+            gif_api_key: "".into(),
+            lyrics_api_url: "".into(),
+            noise_model_dir: "/tmp/flux-test-noise-models".into(),
+            noise_model_url: "".into(),
+            noise_model_files: "".into(),
+            oidc_issuer: "".into(),
+            oidc_client_id: "".into(),
+            oidc_client_secret: "".into(),
+            oidc_redirect_uri: "".into(),
+            smtp_host: "".into(),
+            smtp_port: 587,
+            smtp_username: "".into(),
+            smtp_password: "".into(),
+            smtp_from: "Flux <noreply@localhost>".into(),
+            smtp_security: "none".into(),
+            mail_log_only: false,
+            tls_cert_path: "".into(),
+            tls_key_path: "".into(),
+            tls_redirect_port: 0,
+            acme_domain: "".into(),
+            acme_email: "".into(),
+            acme_directory_url: "".into(),
+            acme_challenge: "tls-alpn-01".into(),
+            acme_dir: "./acme".into(),
+            public_url: "http://127.0.0.1:3001".into(),
+            require_email_verification: false,
+            argon2_memory_kib: 19_456,
+            argon2_iterations: 2,
+            password_min_score: 3,
+            username_change_cooldown_secs: 604_800,
+            cookie_secure: false,
+            cookie_domain: "".into(),
+            trusted_proxies: "127.0.0.0/8,::1,10.0.0.0/8,172.16.0.0/12,192.168.0.0/16,fc00::/7".into(),
+            ip_allowlists: "".into(),
+            metrics_token: "".into(),
+            otlp_endpoint: "".into(),
+            otel_service_name: "flux-server".into(),
+            db_max_connections: 8,
+            db_read_connections: 8,
+            db_busy_timeout_ms: 5000,
+            backup_dir: "/tmp/flux-test-backups".into(),
+            backup_interval_hours: 0,
+            backup_keep: 7,
+            backup_s3_bucket: "".into(),
+            backup_s3_prefix: "flux-backups/".into(),
+            restore_from: "".into(),
+            admin_email: "".into(),
+            admin_password: "".into(),
+            admin_username: "".into(),
+            server_name: "flux".into(),
+            feed_poll_interval_secs: 900,
+            event_reminder_minutes: 15,
+            dm_retention_days: 0,
+            challenge_verify_url: "".into(),
+            challenge_site_key: "".into(),
+            challenge_secret: "".into(),
         },
         gateway: Arc::new(ws::gateway::GatewayState::new()),
         spotify_auth_pending: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        lastfm_auth_pending: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        oidc_pending: tokio::sync::RwLock::new(std::collections::HashMap::new()),
         youtube_url_cache: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        youtube_downloads: tokio::sync::RwLock::new(std::collections::HashSet::new()),
+        gif_search_cache: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        insights_cache: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        gif_rate_limits: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        lyrics_cache: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        noise_models: tokio::sync::RwLock::new(Default::default()),
+        ip_filter: flux_server::middleware::ip_filter::IpFilter::new(&common::test_config()),
     });
     let server = TestServer::new(routes::build_router(state)).unwrap();
 
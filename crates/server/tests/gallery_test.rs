@@ -0,0 +1,182 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use common::ws_helpers::{drain_messages, start_server, ws_connect};
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn create_set(server: &TestServer, token: &str, attachments: &[String]) -> String {
+    let names: Vec<String> = (0..attachments.len()).map(|i| format!("image {}", i)).collect();
+    let (h, v) = auth_header(token);
+    let res = server
+        .post("/api/gallery")
+        .add_header(h, v)
+        .json(&json!({ "name": "Holiday", "imageAttachmentIds": attachments, "imageNames": names }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    res.json::<serde_json::Value>()["id"].as_str().unwrap().to_string()
+}
+
+async fn detail(server: &TestServer, set_id: &str, token: &str) -> serde_json::Value {
+    let (h, v) = auth_header(token);
+    server.get(&format!("/api/gallery/{}", set_id)).add_header(h, v).await.json()
+}
+
+#[tokio::test]
+async fn collaborators_edit_but_only_the_creator_manages_them() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (alice_id, alice_token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let (carol_id, carol_token) = common::create_test_user(&pool, "carol@test.com", "carol", "password123").await;
+    let first = common::create_test_attachment(&pool, &alice_id, "a.png", "image/png").await;
+    let set_id = create_set(&server, &alice_token, &[first]).await;
+    let bobs = common::create_test_attachment(&pool, &bob_id, "b.png", "image/png").await;
+
+    let add_images = |token: &str| {
+        let (h, v) = auth_header(token);
+        server
+            .post(&format!("/api/gallery/{}/images", set_id))
+            .add_header(h, v)
+            .json(&json!({ "imageAttachmentIds": [bobs], "imageNames": ["bob's"] }))
+    };
+    add_images(&bob_token).await.assert_status(StatusCode::FORBIDDEN);
+
+    let (h, v) = auth_header(&alice_token);
+    server
+        .post(&format!("/api/gallery/{}/collaborators", set_id))
+        .add_header(h, v)
+        .json(&json!({ "userId": bob_id }))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    add_images(&bob_token).await.assert_status(StatusCode::NO_CONTENT);
+    let set = detail(&server, &set_id, &carol_token).await;
+    assert_eq!(set["images"].as_array().unwrap().len(), 2);
+    assert_eq!(set["collaborators"][0]["username"], "bob");
+
+    // Collaborators show up in the editor's own list
+    let (h, v) = auth_header(&bob_token);
+    let mine: serde_json::Value = server.get("/api/gallery/mine").add_header(h, v).await.json();
+    assert_eq!(mine[0]["id"], set_id);
+
+    // Bob cannot bring in more editors or delete the set
+    let (h, v) = auth_header(&bob_token);
+    server
+        .post(&format!("/api/gallery/{}/collaborators", set_id))
+        .add_header(h, v)
+        .json(&json!({ "userId": carol_id }))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    let (h, v) = auth_header(&bob_token);
+    server.delete(&format!("/api/gallery/{}", set_id)).add_header(h, v).await.assert_status(StatusCode::FORBIDDEN);
+
+    // But can step down, after which he is locked out again
+    let (h, v) = auth_header(&bob_token);
+    server
+        .delete(&format!("/api/gallery/{}/collaborators/{}", set_id, bob_id))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    add_images(&bob_token).await.assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn images_are_reordered_and_renamed_together() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (alice_id, alice_token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let mut attachments = vec![];
+    for name in ["a.png", "b.png", "c.png"] {
+        attachments.push(common::create_test_attachment(&pool, &alice_id, name, "image/png").await);
+    }
+    let set_id = create_set(&server, &alice_token, &attachments).await;
+    let set = detail(&server, &set_id, &alice_token).await;
+    let ids: Vec<String> = set["images"].as_array().unwrap().iter().map(|i| i["id"].as_str().unwrap().to_string()).collect();
+
+    let reorder = |items: serde_json::Value| {
+        let (h, v) = auth_header(&alice_token);
+        server
+            .put(&format!("/api/gallery/{}/images/order", set_id))
+            .add_header(h, v)
+            .json(&json!({ "items": items }))
+    };
+
+    // Every image exactly once, and no blank names
+    for items in [
+        json!([{ "id": ids[0] }, { "id": ids[1] }]),
+        json!([{ "id": ids[0] }, { "id": ids[0] }, { "id": ids[1] }]),
+        json!([{ "id": ids[0] }, { "id": ids[1] }, { "id": "elsewhere" }]),
+        json!([{ "id": ids[2], "name": "  " }, { "id": ids[0] }, { "id": ids[1] }]),
+    ] {
+        reorder(items).await.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    reorder(json!([{ "id": ids[2], "name": " Sunset " }, { "id": ids[0] }, { "id": ids[1] }]))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let set = detail(&server, &set_id, &alice_token).await;
+    let images = set["images"].as_array().unwrap();
+    let order: Vec<&str> = images.iter().map(|i| i["id"].as_str().unwrap()).collect();
+    assert_eq!(order, [ids[2].as_str(), ids[0].as_str(), ids[1].as_str()]);
+    assert_eq!(images[0]["name"], "Sunset");
+    assert_eq!(images[1]["name"], "image 0");
+    assert_eq!(set["coverAttachmentId"], attachments[2]);
+}
+
+#[tokio::test]
+async fn subscribers_and_editors_hear_about_changes() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let (_, carol_token) = common::create_test_user(&pool, "carol@test.com", "carol", "password123").await;
+    let attachment = common::create_test_attachment(&pool, &alice_id, "a.png", "image/png").await;
+
+    let client = reqwest::Client::new();
+    let set: serde_json::Value = client
+        .post(format!("{}/api/gallery", base))
+        .bearer_auth(&alice_token)
+        .json(&json!({ "name": "Holiday", "imageAttachmentIds": [attachment], "imageNames": ["a"] }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let set_id = set["id"].as_str().unwrap();
+    client
+        .post(format!("{}/api/gallery/{}/subscribe", base, set_id))
+        .bearer_auth(&carol_token)
+        .send()
+        .await
+        .unwrap();
+
+    let mut bob_ws = ws_connect(&base, &bob_token).await;
+    let mut carol_ws = ws_connect(&base, &carol_token).await;
+    drain_messages(&mut bob_ws).await;
+    drain_messages(&mut carol_ws).await;
+
+    let res = client
+        .post(format!("{}/api/gallery/{}/collaborators", base, set_id))
+        .bearer_auth(&alice_token)
+        .json(&json!({ "userId": bob_id }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::NO_CONTENT);
+
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    for ws in [&mut bob_ws, &mut carol_ws] {
+        let events = drain_messages(ws).await;
+        let event = events.iter().find(|m| m["type"] == "gallery_set_updated").unwrap();
+        assert_eq!(event["setId"], set_id);
+    }
+}
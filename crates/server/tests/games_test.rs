@@ -0,0 +1,203 @@
+mod common;
+
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use serde_json::json;
+
+async fn fund(pool: &sqlx::SqlitePool, user_id: &str, balance: i64) {
+    sqlx::query(r#"INSERT INTO "wallets" (user_id, balance, updated_at) VALUES (?, ?, ?)"#)
+        .bind(user_id)
+        .bind(balance)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+async fn balance(pool: &sqlx::SqlitePool, user_id: &str) -> i64 {
+    sqlx::query_scalar(r#"SELECT balance FROM "wallets" WHERE user_id = ?"#)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+async fn post(base: &str, token: &str, path: &str, body: serde_json::Value) -> (u16, serde_json::Value) {
+    let res = reqwest::Client::new()
+        .post(format!("{}{}", base, path))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    let status = res.status().as_u16();
+    (status, res.json().await.unwrap_or_default())
+}
+
+async fn get_json(base: &str, token: &str, path: &str) -> serde_json::Value {
+    reqwest::Client::new()
+        .get(format!("{}{}", base, path))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn dice_roll_is_verifiable_after_rotation() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+    fund(&pool, &alice_id, 500).await;
+
+    let seed = get_json(&base, &alice_token, "/api/games/seed").await;
+    assert_eq!(seed["nonce"], 0);
+
+    let (status, game) = post(&base, &alice_token, "/api/games/dice", json!({"channelId": channel_id, "wager": 100, "target": 50})).await;
+    assert_eq!(status, 200);
+    assert_eq!(game["status"], "resolved");
+    assert_eq!(game["serverSeedHash"], seed["serverSeedHash"]);
+    assert_eq!(game["nonce"], 0);
+    assert!(game["serverSeed"].is_null(), "seed stays hidden until rotated");
+
+    let roll = game["roll"].as_i64().unwrap();
+    let expected_payout = if roll < 5000 { 100 * 9800 / 5000 } else { 0 };
+    assert_eq!(game["payout"], expected_payout);
+    assert_eq!(balance(&pool, &alice_id).await, 400 + expected_payout);
+
+    let (status, rotated) = post(&base, &alice_token, "/api/games/seed/rotate", json!({"clientSeed": "my-seed"})).await;
+    assert_eq!(status, 200);
+    assert_eq!(rotated["clientSeed"], "my-seed");
+    assert_ne!(rotated["serverSeedHash"], seed["serverSeedHash"]);
+    let revealed = rotated["previous"]["serverSeed"].as_str().unwrap();
+    assert_eq!(flux_server::routes::games::hash_seed(revealed), seed["serverSeedHash"].as_str().unwrap());
+    assert_eq!(
+        flux_server::routes::games::roll(revealed, seed["clientSeed"].as_str().unwrap(), 0),
+        roll
+    );
+
+    let game = get_json(&base, &alice_token, &format!("/api/games/{}", game["id"].as_str().unwrap())).await;
+    assert_eq!(game["serverSeed"], revealed);
+
+    let (status, _) = post(&base, &alice_token, "/api/games/dice", json!({"channelId": channel_id, "wager": 100, "target": 99})).await;
+    assert_eq!(status, 400);
+    let (status, _) = post(&base, &alice_token, "/api/games/dice", json!({"channelId": channel_id, "wager": 10_000, "target": 50})).await;
+    assert_eq!(status, 403, "over the default daily loss limit");
+}
+
+#[tokio::test]
+async fn coinflip_settles_between_players_and_broadcasts() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+    fund(&pool, &alice_id, 300).await;
+    fund(&pool, &bob_id, 300).await;
+
+    let mut ws = ws_connect(&base, &bob_token).await;
+    send_json(&mut ws, &json!({"type": "join_channel", "channelId": channel_id})).await;
+    drain_messages(&mut ws).await;
+
+    let (status, game) = post(&base, &alice_token, "/api/games/coinflip", json!({"channelId": channel_id, "wager": 100})).await;
+    assert_eq!(status, 201);
+    assert_eq!(game["status"], "open");
+    assert_eq!(balance(&pool, &alice_id).await, 200);
+    let game_id = game["id"].as_str().unwrap().to_string();
+
+    let (status, _) = post(&base, &alice_token, &format!("/api/games/coinflip/{}/accept", game_id), json!({})).await;
+    assert_eq!(status, 400);
+
+    let (status, game) = post(&base, &bob_token, &format!("/api/games/coinflip/{}/accept", game_id), json!({})).await;
+    assert_eq!(status, 200);
+    assert_eq!(game["status"], "resolved");
+    assert_eq!(game["opponentId"], bob_id.as_str());
+    assert_eq!(game["payout"], 196);
+    let creator_won = game["roll"].as_i64().unwrap() < 5000;
+    assert_eq!(game["winnerId"], if creator_won { alice_id.as_str() } else { bob_id.as_str() });
+    // Both staked 100, the winner took 196 (2% house edge)
+    assert_eq!(balance(&pool, &alice_id).await + balance(&pool, &bob_id).await, 596);
+
+    let (status, _) = post(&base, &bob_token, &format!("/api/games/coinflip/{}/accept", game_id), json!({})).await;
+    assert_eq!(status, 409);
+    let (status, _) = post(&base, &alice_token, &format!("/api/games/coinflip/{}/cancel", game_id), json!({})).await;
+    assert_eq!(status, 409);
+
+    let updates: Vec<_> = drain_messages(&mut ws)
+        .await
+        .into_iter()
+        .filter(|m| m["type"] == "game_update")
+        .collect();
+    assert_eq!(updates.len(), 2);
+    assert_eq!(updates[0]["game"]["status"], "open");
+    assert_eq!(updates[1]["game"]["status"], "resolved");
+}
+
+#[tokio::test]
+async fn owner_controls_edge_and_loss_limit() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+    fund(&pool, &bob_id, 1000).await;
+
+    let client = reqwest::Client::new();
+    let path = format!("{}/api/games/settings/{}", base, server_id);
+    let res = client
+        .patch(&path)
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .json(&json!({"dailyLossLimit": 0}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 403);
+    let res = client
+        .patch(&path)
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"houseEdgeBps": 5000}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 400);
+    let res = client
+        .patch(&path)
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"dailyLossLimit": 150, "houseEdgeBps": 500}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+
+    let settings = get_json(&base, &bob_token, &format!("/api/games/settings/{}", server_id)).await;
+    assert_eq!(settings["dailyLossLimit"], 150);
+    assert_eq!(settings["houseEdgeBps"], 500);
+    assert_eq!(settings["enabled"], 1);
+
+    // A cancelled flip is refunded and doesn't count as a loss
+    let (_, game) = post(&base, &bob_token, "/api/games/coinflip", json!({"channelId": channel_id, "wager": 100})).await;
+    let (status, _) = post(&base, &bob_token, &format!("/api/games/coinflip/{}/cancel", game["id"].as_str().unwrap()), json!({})).await;
+    assert_eq!(status, 200);
+    assert_eq!(balance(&pool, &bob_id).await, 1000);
+
+    let (status, _) = post(&base, &bob_token, "/api/games/coinflip", json!({"channelId": channel_id, "wager": 100})).await;
+    assert_eq!(status, 201);
+    let (status, body) = post(&base, &bob_token, "/api/games/coinflip", json!({"channelId": channel_id, "wager": 100})).await;
+    assert_eq!(status, 403);
+    assert_eq!(body["error"], "Daily loss limit reached");
+
+    // Alice has no coins to match the stake
+    let (status, body) = post(&base, &alice_token, "/api/games/coinflip", json!({"channelId": channel_id, "wager": 50})).await;
+    assert_eq!(status, 400);
+    assert_eq!(body["error"], "Insufficient balance");
+}
@@ -0,0 +1,101 @@
+use flux_server::ws::events::ServerEvent;
+use flux_server::ws::gateway::{client_channel, ClientReceiver, Delivery, GatewayState};
+use tokio::sync::mpsc::error::TryRecvError;
+
+fn typing(user_id: &str, active: bool) -> ServerEvent {
+    ServerEvent::Typing { channel_id: "c1".into(), user_id: user_id.into(), active }
+}
+
+fn presence(user_id: &str, status: &str) -> ServerEvent {
+    ServerEvent::Presence { user_id: user_id.into(), status: status.into() }
+}
+
+fn send(tx: &flux_server::ws::gateway::ClientSender, event: &ServerEvent) -> bool {
+    tx.send_with(serde_json::to_string(event).unwrap().into(), &Delivery::of(event))
+}
+
+fn drain(rx: &mut ClientReceiver) -> Vec<serde_json::Value> {
+    let mut out = Vec::new();
+    while let Ok(msg) = rx.try_recv() {
+        out.push(serde_json::from_str(&msg).unwrap());
+    }
+    out
+}
+
+#[tokio::test]
+async fn presence_and_typing_coalesce() {
+    let (tx, mut rx) = client_channel(16);
+
+    send(&tx, &presence("u1", "online"));
+    send(&tx, &typing("u1", true));
+    send(&tx, &presence("u1", "idle"));
+    send(&tx, &presence("u2", "online"));
+    send(&tx, &typing("u1", false));
+    assert_eq!(tx.depth(), 3);
+
+    let events = drain(&mut rx);
+    assert_eq!(events[0]["status"], "idle");
+    assert_eq!(events[1]["active"], false);
+    assert_eq!(events[2]["userId"], "u2");
+}
+
+#[tokio::test]
+async fn full_queue_drops_indicators_but_not_messages() {
+    let (tx, mut rx) = client_channel(3);
+
+    send(&tx, &typing("u1", true));
+    for i in 0..2 {
+        assert!(tx.send(format!(r#"{{"type":"message","n":{}}}"#, i)));
+    }
+    // Full: another indicator is dropped...
+    assert!(!send(&tx, &typing("u2", true)));
+    // ...and a message makes room by evicting the queued one
+    assert!(tx.send(r#"{"type":"message","n":2}"#.to_string()));
+    // Past capacity, messages still queue rather than being lost
+    assert!(tx.send(r#"{"type":"message","n":3}"#.to_string()));
+
+    let events = drain(&mut rx);
+    let numbers: Vec<i64> = events.iter().map(|e| e["n"].as_i64().unwrap()).collect();
+    assert_eq!(numbers, vec![0, 1, 2, 3]);
+}
+
+#[tokio::test]
+async fn client_too_far_behind_is_disconnected() {
+    let gw = GatewayState::new();
+    let (tx, mut rx) = client_channel(2);
+    let cid = gw.next_client_id().await;
+    gw.register(cid, "u1".into(), "alice".into(), tx, "online".into()).await;
+    let closed = gw.close_signal(cid).await.unwrap();
+
+    let event = ServerEvent::MessageDelete { message_id: "m".into(), channel_id: "c".into() };
+    for _ in 0..20 {
+        gw.send_to(cid, &event).await;
+    }
+
+    // The connection is told to close and nothing more is queued for it
+    tokio::time::timeout(std::time::Duration::from_secs(1), closed.notified())
+        .await
+        .expect("slow client should be closed");
+    assert!(matches!(rx.try_recv(), Err(TryRecvError::Disconnected)));
+    assert!(rx.recv().await.is_none());
+}
+
+#[tokio::test]
+async fn receiver_wakes_for_new_events_and_ends_when_dropped() {
+    let (tx, mut rx) = client_channel(4);
+    let reader = tokio::spawn(async move {
+        let mut got = Vec::new();
+        while let Some(msg) = rx.recv().await {
+            got.push(msg);
+        }
+        got
+    });
+
+    tokio::task::yield_now().await;
+    tx.send("a");
+    tx.send("b");
+    drop(tx);
+
+    let got = reader.await.unwrap();
+    assert_eq!(got.iter().map(|m| m.as_str()).collect::<Vec<_>>(), vec!["a", "b"]);
+}
@@ -1,9 +1,8 @@
-use flux_server::ws::events::{ActivityInfo, ServerEvent};
-use flux_server::ws::gateway::GatewayState;
-use tokio::sync::mpsc;
+use flux_server::ws::events::ServerEvent;
+use flux_server::ws::gateway::{client_channel, ClientReceiver, ClientSender, GatewayState, CLIENT_QUEUE_CAPACITY};
 
-fn make_tx() -> (mpsc::UnboundedSender<String>, mpsc::UnboundedReceiver<String>) {
-    mpsc::unbounded_channel()
+fn make_tx() -> (ClientSender, ClientReceiver) {
+    client_channel(CLIENT_QUEUE_CAPACITY)
 }
 
 #[tokio::test]
@@ -224,8 +223,11 @@ async fn broadcast_all_sends_to_everyone() {
     };
     gw.broadcast_all(&event, None).await;
 
-    assert!(rx1.try_recv().is_ok());
-    assert!(rx2.try_recv().is_ok());
+    let msg1 = rx1.try_recv().unwrap();
+    let msg2 = rx2.try_recv().unwrap();
+    assert_eq!(msg1, msg2);
+    // Serialized once, with both queues holding the same buffer
+    assert_eq!(msg1.as_ptr(), msg2.as_ptr());
 }
 
 #[tokio::test]
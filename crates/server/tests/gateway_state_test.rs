@@ -1,9 +1,8 @@
 use flux_server::ws::events::{ActivityInfo, ServerEvent};
-use flux_server::ws::gateway::GatewayState;
-use tokio::sync::mpsc;
+use flux_server::ws::gateway::{client_channel, ClientReceiver, ClientSender, GatewayState, CLIENT_QUEUE_CAPACITY};
 
-fn make_tx() -> (mpsc::UnboundedSender<String>, mpsc::UnboundedReceiver<String>) {
-    mpsc::unbounded_channel()
+fn make_tx() -> (ClientSender, ClientReceiver) {
+    client_channel(CLIENT_QUEUE_CAPACITY)
 }
 
 #[tokio::test]
@@ -237,3 +236,62 @@ async fn unregister_cleans_voice_and_subscriptions() {
     assert_eq!(gw.voice_channel_participants("vc1").await.len(), 0);
     assert!(gw.clients.read().await.is_empty());
 }
+
+#[tokio::test]
+async fn speaking_update_reaches_only_voice_participants() {
+    let gw = std::sync::Arc::new(GatewayState::new());
+    let (tx1, mut rx1) = make_tx();
+    let (tx2, mut rx2) = make_tx();
+    let (tx3, mut rx3) = make_tx();
+
+    let cid1 = gw.next_client_id().await;
+    let cid2 = gw.next_client_id().await;
+    let cid3 = gw.next_client_id().await;
+    gw.register(cid1, "u1".into(), "alice".into(), tx1, "online".into()).await;
+    gw.register(cid2, "u2".into(), "bob".into(), tx2, "online".into()).await;
+    gw.register(cid3, "u3".into(), "carol".into(), tx3, "online".into()).await;
+    gw.voice_join(cid1, "vc1").await;
+    gw.voice_join(cid2, "vc1").await;
+    gw.voice_join(cid3, "vc2").await;
+
+    gw.update_speaking(cid1, "vc1", true).await;
+
+    let msg: serde_json::Value = serde_json::from_str(&rx2.try_recv().unwrap()).unwrap();
+    assert_eq!(msg["type"], "speaking");
+    assert_eq!(msg["userId"], "u1");
+    assert_eq!(msg["speaking"], true);
+    assert!(rx1.try_recv().is_err());
+    assert!(rx3.try_recv().is_err());
+
+    // Not in vc2, so this is dropped
+    gw.update_speaking(cid1, "vc2", false).await;
+    assert!(rx3.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn speaking_update_is_debounced() {
+    let gw = std::sync::Arc::new(GatewayState::new());
+    let (tx1, _rx1) = make_tx();
+    let (tx2, mut rx2) = make_tx();
+
+    let cid1 = gw.next_client_id().await;
+    let cid2 = gw.next_client_id().await;
+    gw.register(cid1, "u1".into(), "alice".into(), tx1, "online".into()).await;
+    gw.register(cid2, "u2".into(), "bob".into(), tx2, "online".into()).await;
+    gw.voice_join(cid1, "vc1").await;
+    gw.voice_join(cid2, "vc1").await;
+
+    gw.update_speaking(cid1, "vc1", true).await;
+    // Rapid toggles inside the window collapse into one trailing event
+    gw.update_speaking(cid1, "vc1", false).await;
+    gw.update_speaking(cid1, "vc1", true).await;
+    gw.update_speaking(cid1, "vc1", false).await;
+
+    assert!(rx2.try_recv().is_ok());
+    assert!(rx2.try_recv().is_err());
+
+    tokio::time::sleep(flux_server::ws::gateway::SPEAKING_DEBOUNCE * 2).await;
+    let msg: serde_json::Value = serde_json::from_str(&rx2.try_recv().unwrap()).unwrap();
+    assert_eq!(msg["speaking"], false);
+    assert!(rx2.try_recv().is_err());
+}
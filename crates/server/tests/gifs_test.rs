@@ -0,0 +1,72 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn setup() -> (TestServer, sqlx::SqlitePool) {
+    let pool = common::setup_test_db().await;
+    let app = common::create_test_app(pool.clone());
+    let server = TestServer::new(app).unwrap();
+    (server, pool)
+}
+
+#[tokio::test]
+async fn gif_search_requires_auth() {
+    let (server, _pool) = setup().await;
+    let res = server.get("/api/gifs/search?q=cat").await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn gif_search_empty_query_returns_no_results() {
+    let (server, pool) = setup().await;
+    let (_user_id, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+
+    let (h, v) = auth_header(&token);
+    let res = server.get("/api/gifs/search?q=%20").add_header(h, v).await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["results"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn gif_search_without_api_key_is_unavailable() {
+    let (server, pool) = setup().await;
+    let (_user_id, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+
+    let (h, v) = auth_header(&token);
+    let res = server.get("/api/gifs/search?q=cat").add_header(h, v).await;
+    res.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn gif_search_is_rate_limited_per_user() {
+    let (server, pool) = setup().await;
+    let (_alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (_bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+
+    for _ in 0..30 {
+        let (h, v) = auth_header(&alice_token);
+        let res = server.get("/api/gifs/search?q=cat").add_header(h, v).await;
+        assert_ne!(res.status_code(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    let (h, v) = auth_header(&alice_token);
+    let res = server.get("/api/gifs/search?q=cat").add_header(h, v).await;
+    res.assert_status(StatusCode::TOO_MANY_REQUESTS);
+
+    let (h, v) = auth_header(&bob_token);
+    let res = server.get("/api/gifs/search?q=cat").add_header(h, v).await;
+    assert_ne!(res.status_code(), StatusCode::TOO_MANY_REQUESTS);
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use axum::http::StatusCode;
+use axum_test::TestServer;
+
+fn server_with(config: flux_server::config::Config, pool: sqlx::SqlitePool) -> TestServer {
+    TestServer::new(common::create_test_app_with_config(pool, config)).unwrap()
+}
+
+#[tokio::test]
+async fn healthz_is_always_ok() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool)).unwrap();
+
+    let res = server.get("/healthz").await;
+    res.assert_status_ok();
+    assert_eq!(res.json::<serde_json::Value>()["status"], "ok");
+}
+
+#[tokio::test]
+async fn readyz_reports_each_component() {
+    let pool = common::setup_test_db().await;
+    let config = common::test_config();
+    std::fs::create_dir_all(&config.upload_dir).unwrap();
+    let server = server_with(config, pool);
+
+    let res = server.get("/readyz").await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["checks"]["database"]["status"], "ok");
+    assert_eq!(body["checks"]["database"]["required"], true);
+    assert_eq!(body["checks"]["uploads"]["status"], "ok");
+    assert_eq!(body["checks"]["livekit"]["status"], "disabled");
+    // yt-dlp may or may not be installed where the tests run
+    let yt_dlp = body["checks"]["ytDlp"]["status"].as_str().unwrap();
+    assert!(yt_dlp == "ok" || yt_dlp == "error");
+    assert!(body["status"] == "ok" || body["status"] == "degraded");
+}
+
+#[tokio::test]
+async fn unwritable_uploads_make_the_server_unready() {
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    config.upload_dir = "/nonexistent/flux-uploads".into();
+    let server = server_with(config, pool);
+
+    let res = server.get("/readyz").await;
+    res.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["status"], "error");
+    assert_eq!(body["checks"]["uploads"]["status"], "error");
+    assert!(body["checks"]["uploads"]["detail"].as_str().unwrap().contains("/nonexistent/flux-uploads"));
+}
+
+#[tokio::test]
+async fn unreachable_livekit_only_degrades() {
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    std::fs::create_dir_all(&config.upload_dir).unwrap();
+    config.livekit_api_key = "key".into();
+    config.livekit_api_secret = "secret".into();
+    // Nothing listens on port 1
+    config.livekit_url = "ws://127.0.0.1:1".into();
+    let server = server_with(config, pool);
+
+    let res = server.get("/readyz").await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["status"], "degraded");
+    assert_eq!(body["checks"]["livekit"]["status"], "error");
+    assert_eq!(body["checks"]["livekit"]["required"], false);
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use flux_shared::i18n::{negotiate, translate, Locale};
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+fn accept_language(value: &'static str) -> (HeaderName, HeaderValue) {
+    (HeaderName::from_static("accept-language"), HeaderValue::from_static(value))
+}
+
+#[test]
+fn negotiate_honours_q_values_and_subtags() {
+    assert_eq!(negotiate("es-MX,es;q=0.9,en;q=0.8"), Locale::Es);
+    assert_eq!(negotiate("ja, de;q=0.5, fr;q=0.7"), Locale::Fr);
+    assert_eq!(negotiate("pt-BR"), Locale::Pt);
+    assert_eq!(negotiate("de;q=0, ja"), Locale::En);
+    assert_eq!(negotiate(""), Locale::En);
+    assert_eq!(Locale::from_tag("DE_at"), Some(Locale::De));
+    assert_eq!(Locale::from_tag("xx"), None);
+}
+
+#[test]
+fn translate_falls_back_to_english() {
+    assert_eq!(translate(Locale::De, "Channel not found"), "Kanal nicht gefunden");
+    assert_eq!(translate(Locale::En, "Channel not found"), "Channel not found");
+    assert_eq!(translate(Locale::Fr, "Something nobody translated"), "Something nobody translated");
+}
+
+#[tokio::test]
+async fn errors_follow_accept_language_then_user_preference() {
+    let pool = common::setup_test_db().await;
+    let state = common::create_test_state(pool.clone(), common::test_config());
+    let server = TestServer::new(flux_server::routes::build_router(state)).unwrap();
+    let (alice_id, alice_token) = common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+
+    // Signed out: the browser's language
+    let (h, v) = accept_language("es-ES,es;q=0.9");
+    let res = server.get("/api/users/me").add_header(h, v).await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(res.header("content-language"), "es");
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["error"], "No has iniciado sesión");
+    assert_eq!(body["code"], "unauthorized");
+
+    let missing = format!("/api/servers/{}/channels/nope", server_id);
+    let (h, v) = auth_header(&alice_token);
+    let (lh, lv) = accept_language("fr");
+    let res = server.patch(&missing).add_header(h, v).add_header(lh, lv).json(&json!({"name": "x"})).await;
+    res.assert_status(StatusCode::NOT_FOUND);
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["error"], "Salon introuvable");
+
+    // A saved preference wins over the browser
+    let (h, v) = auth_header(&alice_token);
+    let res = server.patch("/api/users/me").add_header(h, v).json(&json!({"locale": "de-DE"})).await;
+    res.assert_status(StatusCode::OK);
+    let profile: serde_json::Value = res.json();
+    assert_eq!(profile["locale"], "de");
+
+    let (h, v) = auth_header(&alice_token);
+    let (lh, lv) = accept_language("fr");
+    let res = server.patch(&missing).add_header(h, v).add_header(lh, lv).json(&json!({"name": "x"})).await;
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["error"], "Kanal nicht gefunden");
+    assert_eq!(res.header("content-language"), "de");
+
+    let (h, v) = auth_header(&alice_token);
+    let res = server.patch("/api/users/me").add_header(h, v).json(&json!({"locale": "klingon"})).await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    // Clearing it goes back to the browser's language, and to English without one
+    let (h, v) = auth_header(&alice_token);
+    let res = server.patch("/api/users/me").add_header(h, v).json(&json!({"locale": null})).await;
+    let profile: serde_json::Value = res.json();
+    assert!(profile["locale"].is_null());
+    let (h, v) = auth_header(&alice_token);
+    let res = server.patch(&missing).add_header(h, v).json(&json!({"name": "x"})).await;
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["error"], "Channel not found");
+}
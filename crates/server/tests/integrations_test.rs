@@ -0,0 +1,213 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+fn header(name: &'static str, value: &str) -> (HeaderName, HeaderValue) {
+    (HeaderName::from_static(name), value.parse().unwrap())
+}
+
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(body);
+    let hex: String = mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+async fn channel_messages(pool: &sqlx::SqlitePool, channel_id: &str) -> Vec<(String, Option<String>)> {
+    sqlx::query_as("SELECT content, integration_id FROM messages WHERE channel_id = ? ORDER BY created_at")
+        .bind(channel_id)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn github_deliveries_are_verified_and_posted() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (owner_id, owner_token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "dev").await;
+    let path = format!("/api/servers/{}/channels/{}/integrations", server_id, channel_id);
+
+    let (h, v) = auth_header(&bob_token);
+    server
+        .post(&path)
+        .add_header(h, v)
+        .json(&json!({ "provider": "github" }))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    let (h, v) = auth_header(&owner_token);
+    server
+        .post(&path)
+        .add_header(h, v)
+        .json(&json!({ "provider": "bitbucket" }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let (h, v) = auth_header(&owner_token);
+    let res = server.post(&path).add_header(h, v).json(&json!({ "provider": "github" })).await;
+    res.assert_status(StatusCode::CREATED);
+    let created: serde_json::Value = res.json();
+    assert_eq!(created["name"], "GitHub");
+    let token = created["token"].as_str().unwrap().to_string();
+    let secret = created["secret"].as_str().unwrap().to_string();
+    assert!(created["webhookUrl"].as_str().unwrap().ends_with(&format!("/api/integrations/github/{}", token)));
+    let hook = format!("/api/integrations/github/{}", token);
+
+    let payload = serde_json::to_vec(&json!({
+        "ref": "refs/heads/main",
+        "compare": "https://github.com/acme/app/compare/a...b",
+        "repository": { "full_name": "acme/app" },
+        "pusher": { "name": "alice" },
+        "sender": { "login": "alice" },
+        "commits": [{ "id": "0123456789abcdef", "message": "Fix @everyone ping\n\nDetails" }]
+    }))
+    .unwrap();
+
+    let (h, v) = header("x-github-event", "push");
+    let (sh, sv) = header("x-hub-signature-256", &sign("wrong", &payload));
+    server
+        .post(&hook)
+        .add_header(h, v)
+        .add_header(sh, sv)
+        .bytes(payload.clone().into())
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    server
+        .post("/api/integrations/github/nope")
+        .bytes(payload.clone().into())
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    let (h, v) = header("x-github-event", "push");
+    let (sh, sv) = header("x-hub-signature-256", &sign(&secret, &payload));
+    server
+        .post(&hook)
+        .add_header(h, v)
+        .add_header(sh, sv)
+        .bytes(payload.clone().into())
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    // Events we don't announce are accepted but post nothing
+    let ping = br#"{"zen":"Keep it logically awesome."}"#.to_vec();
+    let (h, v) = header("x-github-event", "ping");
+    let (sh, sv) = header("x-hub-signature-256", &sign(&secret, &ping));
+    server
+        .post(&hook)
+        .add_header(h, v)
+        .add_header(sh, sv)
+        .bytes(ping.into())
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let messages = channel_messages(&pool, &channel_id).await;
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].0.contains("acme/app"));
+    assert!(messages[0].0.contains("`0123456` Fix"));
+    assert!(!messages[0].0.contains("@everyone"));
+    assert_eq!(messages[0].1.as_deref(), created["id"].as_str());
+
+    // Rotating retires the old URL
+    let (h, v) = auth_header(&owner_token);
+    let rotated: serde_json::Value = server
+        .post(&format!("{}/{}/rotate", path, created["id"].as_str().unwrap()))
+        .add_header(h, v)
+        .await
+        .json();
+    assert_ne!(rotated["token"], created["token"]);
+    let (h, v) = header("x-github-event", "push");
+    let (sh, sv) = header("x-hub-signature-256", &sign(&secret, &payload));
+    server
+        .post(&hook)
+        .add_header(h, v)
+        .add_header(sh, sv)
+        .bytes(payload.into())
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    let (h, v) = auth_header(&owner_token);
+    let listed: serde_json::Value = server.get(&path).add_header(h, v).await.json();
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+    assert!(listed[0]["token"].is_null());
+    assert!(listed[0]["lastDeliveryAt"].is_string());
+}
+
+#[tokio::test]
+async fn gitlab_deliveries_use_the_secret_token() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (owner_id, owner_token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "dev").await;
+    let path = format!("/api/servers/{}/channels/{}/integrations", server_id, channel_id);
+
+    let (h, v) = auth_header(&owner_token);
+    let created: serde_json::Value = server
+        .post(&path)
+        .add_header(h, v)
+        .json(&json!({ "provider": "gitlab", "name": "Backend" }))
+        .await
+        .json();
+    let hook = format!("/api/integrations/gitlab/{}", created["token"].as_str().unwrap());
+    let payload = json!({
+        "object_kind": "merge_request",
+        "user": { "username": "carol" },
+        "project": { "path_with_namespace": "acme/api" },
+        "object_attributes": { "iid": 7, "title": "Add caching", "action": "merge", "url": "https://gitlab.test/mr/7" }
+    });
+
+    let (h, v) = header("x-gitlab-event", "Merge Request Hook");
+    let (th, tv) = header("x-gitlab-token", "guess");
+    server
+        .post(&hook)
+        .add_header(h, v)
+        .add_header(th, tv)
+        .json(&payload)
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    let (h, v) = header("x-gitlab-event", "Merge Request Hook");
+    let (th, tv) = header("x-gitlab-token", created["secret"].as_str().unwrap());
+    server
+        .post(&hook)
+        .add_header(h, v)
+        .add_header(th, tv)
+        .json(&payload)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let messages = channel_messages(&pool, &channel_id).await;
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].0.contains("carol merged merge request #7: Add caching"));
+
+    let (h, v) = auth_header(&owner_token);
+    server
+        .delete(&format!("{}/{}", path, created["id"].as_str().unwrap()))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let (h, v) = header("x-gitlab-event", "Merge Request Hook");
+    let (th, tv) = header("x-gitlab-token", created["secret"].as_str().unwrap());
+    server
+        .post(&hook)
+        .add_header(h, v)
+        .add_header(th, tv)
+        .json(&payload)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
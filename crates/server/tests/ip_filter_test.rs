@@ -0,0 +1,127 @@
+mod common;
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use flux_server::middleware::ip_filter::IpFilter;
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+fn from(ip: &'static str) -> (HeaderName, HeaderValue) {
+    (HeaderName::from_static("x-forwarded-for"), HeaderValue::from_static(ip))
+}
+
+async fn make_admin(pool: &sqlx::SqlitePool, email: &str, username: &str) -> String {
+    let (user_id, token) = common::create_test_user(pool, email, username, "password123").await;
+    sqlx::query(r#"UPDATE "user" SET is_instance_admin = 1 WHERE id = ?"#)
+        .bind(&user_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    token
+}
+
+#[test]
+fn forwarded_headers_only_count_from_trusted_proxies() {
+    let filter = IpFilter::new(&common::test_config());
+    let mut headers = HeaderMap::new();
+    headers.insert("x-forwarded-for", HeaderValue::from_static("203.0.113.7, 198.51.100.1, 10.0.0.2"));
+
+    // A client talking to us directly can't pick its own address
+    let direct = "198.51.100.9".parse().ok();
+    assert_eq!(filter.resolve(direct, &headers), direct);
+
+    // Through our proxies, the nearest hop that isn't one of them is the
+    // client; whatever it claimed further left is ignored
+    let proxy = "10.0.0.5".parse().ok();
+    assert_eq!(filter.resolve(proxy, &headers), "198.51.100.1".parse().ok());
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-real-ip", HeaderValue::from_static("203.0.113.7"));
+    assert_eq!(filter.resolve(None, &headers), "203.0.113.7".parse().ok());
+    assert_eq!(filter.resolve(proxy, &HeaderMap::new()), proxy);
+}
+
+#[tokio::test]
+async fn allowlisted_paths_refuse_other_networks() {
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    config.ip_allowlists = "/api/admin=192.168.0.0/16".into();
+    let server = TestServer::new(common::create_test_app_with_config(pool.clone(), config)).unwrap();
+    let token = make_admin(&pool, "admin@test.com", "admin").await;
+
+    let (h, v) = auth_header(&token);
+    let (fh, fv) = from("203.0.113.7");
+    server
+        .get("/api/admin/settings")
+        .add_header(h, v)
+        .add_header(fh, fv)
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let (h, v) = auth_header(&token);
+    let (fh, fv) = from("192.168.1.20");
+    server.get("/api/admin/settings").add_header(h, v).add_header(fh, fv).await.assert_status_ok();
+
+    // Other paths are open to everyone
+    let (fh, fv) = from("203.0.113.7");
+    server.get("/healthz").add_header(fh, fv).await.assert_status_ok();
+}
+
+#[tokio::test]
+async fn admins_ban_and_unban_addresses() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let token = make_admin(&pool, "admin@test.com", "admin").await;
+    let (_, member_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+
+    let (h, v) = auth_header(&member_token);
+    server
+        .post("/api/admin/ip-bans")
+        .add_header(h, v)
+        .json(&json!({ "network": "203.0.113.0/24" }))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let ban = |network: &str| {
+        let (h, v) = auth_header(&token);
+        let (fh, fv) = from("198.51.100.1");
+        server
+            .post("/api/admin/ip-bans")
+            .add_header(h, v)
+            .add_header(fh, fv)
+            .json(&json!({ "network": network, "reason": "spam" }))
+    };
+    ban("not an address").await.assert_status(StatusCode::BAD_REQUEST);
+    ban("198.51.100.0/24").await.assert_status(StatusCode::BAD_REQUEST);
+
+    let res = ban("203.0.113.9/24").await;
+    res.assert_status(StatusCode::CREATED);
+    let created: serde_json::Value = res.json();
+    assert_eq!(created["network"], "203.0.113.0/24");
+    ban("203.0.113.0/24").await.assert_status(StatusCode::CONFLICT);
+
+    let (fh, fv) = from("203.0.113.7");
+    server.get("/healthz").add_header(fh, fv).await.assert_status(StatusCode::FORBIDDEN);
+    let (fh, fv) = from("203.0.114.7");
+    server.get("/healthz").add_header(fh, fv).await.assert_status_ok();
+
+    let (h, v) = auth_header(&token);
+    let bans: serde_json::Value = server.get("/api/admin/ip-bans").add_header(h, v).await.json();
+    assert_eq!(bans.as_array().unwrap().len(), 1);
+    assert_eq!(bans[0]["reason"], "spam");
+
+    let (h, v) = auth_header(&token);
+    server
+        .delete(&format!("/api/admin/ip-bans/{}", created["id"].as_str().unwrap()))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let (fh, fv) = from("203.0.113.7");
+    server.get("/healthz").add_header(fh, fv).await.assert_status_ok();
+}
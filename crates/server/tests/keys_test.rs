@@ -208,3 +208,300 @@ async fn share_server_key_with_member() {
 
     res.assert_status(StatusCode::NO_CONTENT);
 }
+
+fn backup_body(wrapped: &str, expected_version: Option<i64>) -> serde_json::Value {
+    json!({
+        "wrappedKey": wrapped,
+        "salt": "c2FsdA==",
+        "kdf": "pbkdf2-sha256",
+        "kdfParams": "{\"iterations\":600000}",
+        "publicKey": "cHVibGljLWtleQ==",
+        "expectedVersion": expected_version,
+    })
+}
+
+#[tokio::test]
+async fn key_backup_versioning() {
+    let (server, pool) = setup().await;
+
+    let (_, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+
+    let (h, v) = auth_header(&token);
+    server
+        .get("/api/users/me/key-backup")
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    let (h, v) = auth_header(&token);
+    let res = server
+        .put("/api/users/me/key-backup")
+        .add_header(h, v)
+        .json(&backup_body("wrapped-v1", None))
+        .await;
+    res.assert_status_ok();
+    assert_eq!(res.json::<serde_json::Value>()["version"], 1);
+
+    // A second device that hasn't seen version 1 can't overwrite it
+    let (h, v) = auth_header(&token);
+    let res = server
+        .put("/api/users/me/key-backup")
+        .add_header(h, v)
+        .json(&backup_body("wrapped-other", None))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+    assert_eq!(res.json::<serde_json::Value>()["currentVersion"], 1);
+
+    let (h, v) = auth_header(&token);
+    let res = server
+        .put("/api/users/me/key-backup")
+        .add_header(h, v)
+        .json(&backup_body("wrapped-v2", Some(1)))
+        .await;
+    res.assert_status_ok();
+    assert_eq!(res.json::<serde_json::Value>()["version"], 2);
+
+    let (h, v) = auth_header(&token);
+    let body: serde_json::Value = server.get("/api/users/me/key-backup").add_header(h, v).await.json();
+    assert_eq!(body["version"], 2);
+    assert_eq!(body["wrappedKey"], "wrapped-v2");
+    assert_eq!(body["kdf"], "pbkdf2-sha256");
+
+    let (h, v) = auth_header(&token);
+    server
+        .delete("/api/users/me/key-backup")
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let (h, v) = auth_header(&token);
+    server
+        .get("/api/users/me/key-backup")
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn key_backup_restore_publishes_key() {
+    let (server, pool) = setup().await;
+
+    let (user_id, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+
+    let (h, v) = auth_header(&token);
+    server
+        .put("/api/users/me/key-backup")
+        .add_header(h, v)
+        .json(&backup_body("wrapped-v1", None))
+        .await
+        .assert_status_ok();
+
+    let (h, v) = auth_header(&token);
+    server
+        .post("/api/users/me/key-backup/restored")
+        .add_header(h, v)
+        .json(&json!({"version": 1, "publicKey": "c29tZS1vdGhlci1rZXk="}))
+        .await
+        .assert_status(StatusCode::CONFLICT);
+
+    let (h, v) = auth_header(&token);
+    server
+        .post("/api/users/me/key-backup/restored")
+        .add_header(h, v)
+        .json(&json!({"version": 1, "publicKey": "cHVibGljLWtleQ=="}))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let (h, v) = auth_header(&token);
+    let body: serde_json::Value = server
+        .get(&format!("/api/users/{}/public-key", user_id))
+        .add_header(h, v)
+        .await
+        .json();
+    assert_eq!(body["publicKey"], "cHVibGljLWtleQ==");
+}
+
+#[tokio::test]
+async fn devices_register_and_list() {
+    let (server, pool) = setup().await;
+
+    let (user_id, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+
+    for (device, key) in [("laptop", "a2V5LWxhcHRvcA=="), ("phone", "a2V5LXBob25l")] {
+        let (h, v) = auth_header(&token);
+        server
+            .put(&format!("/api/users/me/devices/{}", device))
+            .add_header(h, v)
+            .json(&json!({ "publicKey": key, "name": device }))
+            .await
+            .assert_status_ok();
+    }
+
+    let (h, v) = auth_header(&token);
+    server
+        .put("/api/users/me/devices/bad%20id")
+        .add_header(h, v)
+        .json(&json!({ "publicKey": "eA==" }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let (h, v) = auth_header(&token);
+    let devices: serde_json::Value = server
+        .get(&format!("/api/users/{}/devices", user_id))
+        .add_header(h, v)
+        .await
+        .json();
+    let devices = devices.as_array().unwrap();
+    assert_eq!(devices.len(), 2);
+    assert_eq!(devices[1]["deviceId"], "phone");
+    assert_eq!(devices[1]["name"], "phone");
+
+    // The latest device key is also the account key for older clients
+    let (h, v) = auth_header(&token);
+    let body: serde_json::Value = server
+        .get(&format!("/api/users/{}/public-key", user_id))
+        .add_header(h, v)
+        .await
+        .json();
+    assert_eq!(body["publicKey"], "a2V5LXBob25l");
+
+    let (h, v) = auth_header(&token);
+    server
+        .delete("/api/users/me/devices/laptop")
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let (h, v) = auth_header(&token);
+    let devices: serde_json::Value = server.get("/api/users/me/devices").add_header(h, v).await.json();
+    assert_eq!(devices.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn server_key_per_device_falls_back_to_account_key() {
+    let (server, pool) = setup().await;
+
+    let (user_id, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let server_id = common::create_test_server(&pool, &user_id, "TestServer").await;
+
+    for body in [
+        json!({ "encryptedKey": "account-wrapped", "senderId": user_id }),
+        json!({ "encryptedKey": "phone-wrapped", "senderId": user_id, "deviceId": "phone" }),
+    ] {
+        let (h, v) = auth_header(&token);
+        server
+            .post(&format!("/api/servers/{}/keys", server_id))
+            .add_header(h, v)
+            .json(&body)
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+    }
+
+    let (h, v) = auth_header(&token);
+    let body: serde_json::Value = server
+        .get(&format!("/api/servers/{}/keys/me?deviceId=phone", server_id))
+        .add_header(h, v)
+        .await
+        .json();
+    assert_eq!(body["encryptedKey"], "phone-wrapped");
+    assert_eq!(body["deviceId"], "phone");
+
+    let (h, v) = auth_header(&token);
+    let body: serde_json::Value = server
+        .get(&format!("/api/servers/{}/keys/me?deviceId=laptop", server_id))
+        .add_header(h, v)
+        .await
+        .json();
+    assert_eq!(body["encryptedKey"], "account-wrapped");
+    assert!(body["deviceId"].is_null());
+}
+
+#[tokio::test]
+async fn safety_number_verification_goes_stale_on_rotation() {
+    let (server, pool) = setup().await;
+
+    let (_, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let alice_id: String = sqlx::query_scalar(r#"SELECT id FROM "user" WHERE username = 'alice'"#)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    for (token, key) in [(&alice_token, "YWxpY2Uta2V5"), (&bob_token, "Ym9iLWtleQ==")] {
+        let (h, v) = auth_header(token);
+        server
+            .put("/api/users/me/public-key")
+            .add_header(h, v)
+            .json(&json!({ "publicKey": key }))
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+    }
+
+    let (h, v) = auth_header(&alice_token);
+    let from_alice: serde_json::Value = server
+        .get(&format!("/api/users/{}/safety-number", bob_id))
+        .add_header(h, v)
+        .await
+        .json();
+    let (h, v) = auth_header(&bob_token);
+    let from_bob: serde_json::Value = server
+        .get(&format!("/api/users/{}/safety-number", alice_id))
+        .add_header(h, v)
+        .await
+        .json();
+    let number = from_alice["safetyNumber"].as_str().unwrap().to_string();
+    assert_eq!(number, from_bob["safetyNumber"]);
+    assert_eq!(number.chars().filter(|c| c.is_ascii_digit()).count(), 60);
+    assert_eq!(from_alice["verified"], false);
+
+    let (h, v) = auth_header(&alice_token);
+    server
+        .put(&format!("/api/users/{}/verification", bob_id))
+        .add_header(h, v)
+        .json(&json!({ "safetyNumber": "12345" }))
+        .await
+        .assert_status(StatusCode::CONFLICT);
+    let (h, v) = auth_header(&alice_token);
+    server
+        .put(&format!("/api/users/{}/verification", bob_id))
+        .add_header(h, v)
+        .json(&json!({ "safetyNumber": number.replace(' ', "") }))
+        .await
+        .assert_status_ok();
+
+    let (h, v) = auth_header(&alice_token);
+    let status: serde_json::Value = server
+        .get(&format!("/api/users/{}/safety-number", bob_id))
+        .add_header(h, v)
+        .await
+        .json();
+    assert_eq!(status["verified"], true);
+
+    // Bob's key rotates: the old verification no longer holds
+    let (h, v) = auth_header(&bob_token);
+    server
+        .put("/api/users/me/public-key")
+        .add_header(h, v)
+        .json(&json!({ "publicKey": "Ym9iLW5ldy1rZXk=" }))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let (h, v) = auth_header(&alice_token);
+    let status: serde_json::Value = server
+        .get(&format!("/api/users/{}/safety-number", bob_id))
+        .add_header(h, v)
+        .await
+        .json();
+    assert_eq!(status["verified"], false);
+    assert_eq!(status["stale"], true);
+    assert_ne!(status["safetyNumber"], number.as_str());
+
+    let (h, v) = auth_header(&alice_token);
+    let list: serde_json::Value = server.get("/api/users/me/verifications").add_header(h, v).await.json();
+    assert_eq!(list[0]["userId"], bob_id.as_str());
+    assert_eq!(list[0]["stale"], true);
+}
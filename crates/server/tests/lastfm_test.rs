@@ -0,0 +1,121 @@
+mod common;
+
+use common::ws_helpers::{send_json, start_server, ws_connect};
+use serde_json::json;
+
+async fn link_lastfm(pool: &sqlx::SqlitePool, user_id: &str, username: &str) {
+    sqlx::query(
+        r#"INSERT INTO "lastfm_links" (user_id, username, session_key, scrobbling_enabled, created_at)
+           VALUES (?, ?, 'sk-test', 1, ?)"#,
+    )
+    .bind(user_id)
+    .bind(username)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn status_and_settings_follow_the_link() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let client = reqwest::Client::new();
+    let auth = format!("Bearer {}", alice_token);
+
+    let res = client.get(format!("{}/api/lastfm/status", base)).header("Authorization", &auth).send().await.unwrap();
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["linked"], false);
+
+    let res = client
+        .patch(format!("{}/api/lastfm/settings", base))
+        .header("Authorization", &auth)
+        .json(&json!({"scrobbling": false}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 404);
+
+    link_lastfm(&pool, &alice_id, "alice_fm").await;
+    let res = client
+        .patch(format!("{}/api/lastfm/settings", base))
+        .header("Authorization", &auth)
+        .json(&json!({"scrobbling": false}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+
+    let res = client.get(format!("{}/api/lastfm/status", base)).header("Authorization", &auth).send().await.unwrap();
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["linked"], true);
+    assert_eq!(body["username"], "alice_fm");
+    assert_eq!(body["scrobbling"], false);
+
+    client.post(format!("{}/api/lastfm/unlink", base)).header("Authorization", &auth).send().await.unwrap();
+    let res = client.get(format!("{}/api/lastfm/status", base)).header("Authorization", &auth).send().await.unwrap();
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["linked"], false);
+}
+
+#[tokio::test]
+async fn session_plays_queue_scrobbles_for_opted_in_participants() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let vc_id = common::create_voice_channel(&pool, &server_id, "voice-chat").await;
+    link_lastfm(&pool, &bob_id, "bob_fm").await;
+
+    let mut ws_alice = ws_connect(&base, &alice_token).await;
+    let mut ws_bob = ws_connect(&base, &bob_token).await;
+    for ws in [&mut ws_alice, &mut ws_bob] {
+        send_json(ws, &json!({"type": "voice_state_update", "channelId": vc_id, "action": "join"})).await;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(format!("{}/api/spotify/sessions", base))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"voiceChannelId": vc_id}))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = res.json().await.unwrap();
+    let session_id = body["sessionId"].as_str().unwrap().to_string();
+
+    for (uri, name, duration) in [("spotify:track:long", "Long Song", 200_000), ("spotify:track:short", "Jingle", 10_000)] {
+        client
+            .post(format!("{}/api/spotify/sessions/{}/queue", base, session_id))
+            .header("Authorization", format!("Bearer {}", alice_token))
+            .json(&json!({"trackUri": uri, "trackName": name, "trackArtist": "Band", "trackDurationMs": duration}))
+            .send()
+            .await
+            .unwrap();
+        send_json(
+            &mut ws_alice,
+            &json!({"type": "spotify_playback_control", "sessionId": session_id, "action": "skip", "trackUri": uri}),
+        )
+        .await;
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+
+    // Only bob opted in, and the 10s jingle is too short to count
+    let queued = sqlx::query_as::<_, (String, String, String, i64, i64)>(
+        r#"SELECT user_id, artist, track, played_at, ready_at FROM "scrobble_queue""#,
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(queued.len(), 1);
+    let (user_id, artist, track, played_at, ready_at) = &queued[0];
+    assert_eq!(user_id, &bob_id);
+    assert_eq!(artist, "Band");
+    assert_eq!(track, "Long Song");
+    assert_eq!(ready_at - played_at, 100);
+}
@@ -0,0 +1,100 @@
+#![cfg(unix)]
+
+mod common;
+
+use flux_server::config::Config;
+use flux_server::listen::{self, ServerListener};
+use flux_server::middleware::ip_filter::PeerAddr;
+use std::os::fd::IntoRawFd;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+fn socket_config(test: &str) -> (Config, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("flux-listen-{}-{}", test, uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut config = common::test_config();
+    config.unix_socket = dir.join("flux.sock").to_string_lossy().into_owned();
+    (config, dir)
+}
+
+async fn get_over_unix(path: &str, request_path: &str, extra_headers: &str) -> String {
+    let mut stream = tokio::net::UnixStream::connect(path).await.unwrap();
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: localhost\r\n{}Connection: close\r\n\r\n",
+        request_path, extra_headers
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test]
+async fn serves_on_a_unix_socket() {
+    let (config, dir) = socket_config("serve");
+    let pool = common::setup_test_db().await;
+    let state = common::create_test_state(pool, config.clone());
+
+    // A socket left behind by a previous run is replaced
+    drop(listen::bind(&config).await.unwrap());
+    let Ok(ServerListener::Unix(listener)) = listen::bind(&config).await else {
+        panic!("expected a Unix socket");
+    };
+    let mode = std::fs::metadata(&config.unix_socket).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o660);
+
+    let app = flux_server::routes::build_router(state.clone());
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<PeerAddr>()).await.unwrap();
+    });
+
+    let response = get_over_unix(&config.unix_socket, "/healthz", "").await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+
+    // Connections have no peer address, so the proxy's forwarding header
+    // is what bans go by
+    sqlx::query(r#"INSERT INTO "ip_bans" (id, network, created_at) VALUES ('b1', '203.0.113.7', '2026-01-01')"#)
+        .execute(&state.db)
+        .await
+        .unwrap();
+    state.ip_filter.reload_bans(&state.db).await.unwrap();
+    let response = get_over_unix(&config.unix_socket, "/healthz", "X-Forwarded-For: 203.0.113.7\r\n").await;
+    assert!(response.starts_with("HTTP/1.1 403"), "{}", response);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn leaves_other_files_at_the_socket_path_alone() {
+    let (config, dir) = socket_config("occupied");
+    std::fs::write(&config.unix_socket, "not a socket").unwrap();
+
+    assert!(listen::bind(&config).await.is_err());
+    assert_eq!(std::fs::read_to_string(&config.unix_socket).unwrap(), "not a socket");
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn adopts_inherited_sockets() {
+    // What systemd would pass: a listening TCP socket...
+    let tcp = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = tcp.local_addr().unwrap();
+    let Ok(ServerListener::Tcp(adopted)) = listen::adopt(tcp.into_raw_fd()) else {
+        panic!("expected a TCP socket");
+    };
+    assert_eq!(adopted.local_addr().unwrap(), addr);
+    let accepting = tokio::spawn(async move { adopted.accept().await.map(|_| ()) });
+    tokio::net::TcpStream::connect(addr).await.unwrap();
+    accepting.await.unwrap().unwrap();
+
+    // ...or a Unix one
+    let (config, dir) = socket_config("adopt");
+    let unix = std::os::unix::net::UnixListener::bind(&config.unix_socket).unwrap();
+    let adopted = listen::adopt(unix.into_raw_fd()).unwrap();
+    assert!(matches!(adopted, ServerListener::Unix(_)));
+    assert_eq!(adopted.describe(), format!("unix:{}", config.unix_socket));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
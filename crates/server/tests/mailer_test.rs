@@ -0,0 +1,118 @@
+mod common;
+
+use flux_server::mailer;
+use minijinja::context;
+use tokio::sync::mpsc;
+
+async fn next_email(mail: &mut mpsc::UnboundedReceiver<String>) -> String {
+    tokio::time::timeout(std::time::Duration::from_secs(5), mail.recv())
+        .await
+        .expect("email sent")
+        .unwrap()
+}
+
+async fn queued(pool: &sqlx::SqlitePool) -> Vec<(i64, Option<String>)> {
+    sqlx::query_as(r#"SELECT attempts, last_error FROM "mail_queue""#)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+#[test]
+fn templates_render_text_with_an_escaped_html_alternative() {
+    let email = mailer::render(
+        "login_locked",
+        "alice@test.com",
+        "Failed sign-in attempts",
+        context! { attempts => 5, address => "<script>", minutes => 15 },
+    )
+    .unwrap();
+    assert_eq!(email.to, "alice@test.com");
+    assert!(email.text.starts_with("There were 5 failed attempts"));
+    assert!(email.text.contains("Address: <script>\n"));
+    assert!(email.text.ends_with("changing your password.\n"));
+
+    let html = email.html.unwrap();
+    assert!(html.contains("<title>Failed sign-in attempts</title>"));
+    assert!(html.contains("&lt;script&gt;"));
+    assert!(!html.contains("<script>"));
+
+    assert!(mailer::render("no_such_email", "alice@test.com", "Hi", context! {}).is_err());
+}
+
+#[tokio::test]
+async fn failed_sends_are_queued_and_retried() {
+    let pool = common::setup_test_db().await;
+
+    // Nothing listening on this port
+    let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut config = common::test_config();
+    config.smtp_host = "127.0.0.1".into();
+    config.smtp_port = closed.local_addr().unwrap().port();
+    drop(closed);
+    let down = common::create_test_state(pool.clone(), config);
+
+    let email = mailer::render(
+        "access_approved",
+        "alice@test.com",
+        "Your access request was approved",
+        context! { url => "http://flux.test" },
+    )
+    .unwrap();
+    mailer::queue(&down, email).await;
+
+    let mut waited = 0;
+    while queued(&pool).await.first().map(|q| q.0) != Some(1) {
+        assert!(waited < 50, "send attempt failed and was rescheduled");
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        waited += 1;
+    }
+    assert!(queued(&pool).await[0].1.is_some());
+    // Backing off
+    assert_eq!(mailer::retry_queued(&down).await, 0);
+
+    let (port, mut mail) = common::smtp::start_smtp().await;
+    let mut config = common::test_config();
+    config.smtp_host = "127.0.0.1".into();
+    config.smtp_port = port;
+    let up = common::create_test_state(pool.clone(), config);
+    sqlx::query(r#"UPDATE "mail_queue" SET next_attempt_at = ?"#)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(mailer::retry_queued(&up).await, 1);
+    let sent = next_email(&mut mail).await;
+    assert!(sent.contains("To: alice@test.com"));
+    assert!(sent.contains("multipart/alternative"));
+    assert!(sent.contains("http://flux.test"));
+    assert!(queued(&pool).await.is_empty());
+}
+
+#[tokio::test]
+async fn log_only_mode_needs_no_relay() {
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    assert!(!mailer::enabled(&config));
+    config.mail_log_only = true;
+    assert!(mailer::enabled(&config));
+
+    let state = common::create_test_state(pool.clone(), config);
+    let email = mailer::render(
+        "password_reset",
+        "alice@test.com",
+        "Reset your Flux password",
+        context! { code => "abc", minutes => 30 },
+    )
+    .unwrap();
+    mailer::send(&state.config, &email).await.unwrap();
+
+    mailer::queue(&state, email).await;
+    let mut waited = 0;
+    while !queued(&pool).await.is_empty() {
+        assert!(waited < 50, "logged email left the queue");
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        waited += 1;
+    }
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use flux_shared::markdown::{parse, CodeBlock};
+use serde_json::json;
+
+#[test]
+fn parse_extracts_mentions_and_emojis() {
+    let parsed = parse("hey @Alice and @bob :wave: @everyone :wave: email@host.com");
+    assert_eq!(parsed.metadata.mentions, vec!["alice", "bob"]);
+    assert!(parsed.metadata.mentions_everyone);
+    assert!(!parsed.metadata.mentions_here);
+    assert_eq!(parsed.metadata.emojis, vec!["wave"]);
+}
+
+#[test]
+fn parse_ignores_markup_inside_code() {
+    let parsed = parse("`@alice :x:` ```rust\nlet a = \"@bob\";\n``` done");
+    assert!(parsed.metadata.mentions.is_empty());
+    assert!(parsed.metadata.emojis.is_empty());
+    assert_eq!(
+        parsed.metadata.code_blocks,
+        vec![CodeBlock { language: Some("rust".into()), code: "let a = \"@bob\";\n".into() }]
+    );
+}
+
+#[test]
+fn parse_normalizes_malformed_markup() {
+    let parsed = parse("look\r\n```\nunclosed");
+    assert_eq!(parsed.content, "look\n```\nunclosed\n```");
+    assert_eq!(parsed.metadata.code_blocks.len(), 1);
+
+    let parsed = parse("||secret|| and ||dangling\u{7}");
+    assert_eq!(parsed.metadata.spoilers, vec!["secret"]);
+    assert_eq!(parsed.content, "||secret|| and \\|\\|dangling");
+}
+
+#[tokio::test]
+async fn send_message_stores_metadata() {
+    let (base, pool) = start_server().await;
+    let (user_id, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let server_id = common::create_test_server(&pool, &user_id, "TestServer").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "test-channel").await;
+
+    let mut ws = ws_connect(&base, &token).await;
+    drain_messages(&mut ws).await;
+    send_json(&mut ws, &json!({"type": "join_channel", "channelId": channel_id})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    send_json(
+        &mut ws,
+        &json!({"type": "send_message", "channelId": channel_id, "content": "hi @bob ||spoiler||"}),
+    )
+    .await;
+
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let msgs = drain_messages(&mut ws).await;
+    let event = msgs.iter().find(|m| m["type"] == "message").expect("message event");
+    assert_eq!(event["message"]["metadata"]["mentions"], json!(["bob"]));
+    assert_eq!(event["message"]["metadata"]["spoilers"], json!(["spoiler"]));
+
+    let stored: Option<String> = sqlx::query_scalar("SELECT metadata FROM messages WHERE channel_id = ?")
+        .bind(&channel_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let stored: serde_json::Value = serde_json::from_str(&stored.unwrap()).unwrap();
+    assert_eq!(stored["mentions"], json!(["bob"]));
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn setup() -> (TestServer, String) {
+    let pool = common::setup_test_db().await;
+    let app = common::create_test_app(pool.clone());
+    let server = TestServer::new(app).unwrap();
+    let (_, token) = common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    (server, token)
+}
+
+#[tokio::test]
+async fn search_validates_source_and_query() {
+    let (server, token) = setup().await;
+
+    let (h, v) = auth_header(&token);
+    let res = server.get("/api/media/search?q=test&source=napster").add_header(h, v).await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    // Empty query short-circuits before yt-dlp, for every source
+    for source in ["youtube", "soundcloud", "bandcamp"] {
+        let (h, v) = auth_header(&token);
+        let res = server
+            .get(&format!("/api/media/search?q=%20&source={}", source))
+            .add_header(h, v)
+            .await;
+        res.assert_status_ok();
+        assert_eq!(res.json::<serde_json::Value>()["tracks"], serde_json::json!([]));
+    }
+
+    // Bandcamp has no search, only pasted track URLs
+    let (h, v) = auth_header(&token);
+    let res = server.get("/api/media/search?q=some%20album&source=bandcamp").add_header(h, v).await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert!(res.json::<serde_json::Value>()["error"].as_str().unwrap().contains("track URL"));
+
+    let res = server.get("/api/media/search?q=test").await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn audio_validates_source_and_id() {
+    let (server, token) = setup().await;
+
+    let res = server.get("/api/media/audio/soundcloud/artist/track").await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+
+    let (h, v) = auth_header(&token);
+    let res = server.get("/api/media/audio/napster/artist/track").add_header(h, v).await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    // SoundCloud and Bandcamp ids are exactly artist/track
+    for path in [
+        "/api/media/audio/soundcloud/just-an-artist",
+        "/api/media/audio/soundcloud/artist/track/extra",
+        "/api/media/audio/bandcamp/artist/bad%20slug",
+        "/api/media/audio/youtube/has/slash",
+    ] {
+        let (h, v) = auth_header(&token);
+        let res = server.get(path).add_header(h, v).await;
+        res.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    let (h, v) = auth_header(&token);
+    let res = server.get("/api/youtube/audio/bad%20id!").add_header(h, v).await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+}
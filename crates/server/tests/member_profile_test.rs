@@ -0,0 +1,148 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use common::ws_helpers::{drain_messages, start_server, ws_connect};
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn members(server: &TestServer, server_id: &str, token: &str, query: &str) -> Vec<serde_json::Value> {
+    let (h, v) = auth_header(token);
+    let body: serde_json::Value = server
+        .get(&format!("/api/servers/{}/members{}", server_id, query))
+        .add_header(h, v)
+        .await
+        .json();
+    body["items"].as_array().unwrap().clone()
+}
+
+fn member<'a>(members: &'a [serde_json::Value], user_id: &str) -> &'a serde_json::Value {
+    members.iter().find(|m| m["userId"] == user_id).unwrap()
+}
+
+#[tokio::test]
+async fn members_set_their_own_nickname_and_avatar() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (owner_id, owner_token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let path = format!("/api/servers/{}/members/me/profile", server_id);
+
+    // 1x1 GIF
+    let avatar = "data:image/gif;base64,R0lGODlhAQABAIAAAP///wAAACH5BAEAAAAALAAAAAABAAEAAAICRAEAOw==";
+    let (h, v) = auth_header(&bob_token);
+    server
+        .patch(&path)
+        .add_header(h, v)
+        .json(&json!({ "nickname": "  Bobby  ", "avatar": avatar }))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let list = members(&server, &server_id, &owner_token, "").await;
+    assert_eq!(member(&list, &bob_id)["nickname"], "Bobby");
+    assert_eq!(member(&list, &bob_id)["serverAvatar"], avatar);
+    assert_eq!(member(&list, &bob_id)["username"], "bob");
+    assert!(member(&list, &owner_id)["nickname"].is_null());
+
+    // Clearing one field leaves the other
+    let (h, v) = auth_header(&bob_token);
+    server
+        .patch(&path)
+        .add_header(h, v)
+        .json(&json!({ "nickname": null }))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let list = members(&server, &server_id, &owner_token, "").await;
+    assert!(member(&list, &bob_id)["nickname"].is_null());
+    assert_eq!(member(&list, &bob_id)["serverAvatar"], avatar);
+
+    for body in [json!({}), json!({ "nickname": "x".repeat(33) }), json!({ "avatar": "not an image" })] {
+        let (h, v) = auth_header(&bob_token);
+        server.patch(&path).add_header(h, v).json(&body).await.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    // Only members have a profile here
+    let (_, carol_token) = common::create_test_user(&pool, "carol@test.com", "carol", "password123").await;
+    let (h, v) = auth_header(&carol_token);
+    server
+        .patch(&path)
+        .add_header(h, v)
+        .json(&json!({ "nickname": "carol" }))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn admins_rename_members_but_not_other_admins() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (owner_id, owner_token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let (admin_id, admin_token) = common::create_test_user(&pool, "admin@test.com", "admin", "password123").await;
+    let (other_admin_id, _) = common::create_test_user(&pool, "admin2@test.com", "admin2", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    common::add_member(&pool, &admin_id, &server_id, "admin").await;
+    common::add_member(&pool, &other_admin_id, &server_id, "admin").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+
+    let rename = |target: &str, token: &str, nickname: serde_json::Value| {
+        let (h, v) = auth_header(token);
+        server
+            .patch(&format!("/api/servers/{}/members/{}/nickname", server_id, target))
+            .add_header(h, v)
+            .json(&json!({ "nickname": nickname }))
+    };
+
+    rename(&bob_id, &admin_token, json!("Bob the Builder")).await.assert_status(StatusCode::NO_CONTENT);
+    rename(&other_admin_id, &admin_token, json!("nope")).await.assert_status(StatusCode::FORBIDDEN);
+    rename(&owner_id, &admin_token, json!("nope")).await.assert_status(StatusCode::FORBIDDEN);
+    rename(&other_admin_id, &owner_token, json!("Deputy")).await.assert_status(StatusCode::NO_CONTENT);
+    rename(&admin_id, &bob_token, json!("nope")).await.assert_status(StatusCode::FORBIDDEN);
+    rename("nobody", &owner_token, json!("x")).await.assert_status(StatusCode::NOT_FOUND);
+
+    let list = members(&server, &server_id, &owner_token, "").await;
+    assert_eq!(member(&list, &bob_id)["nickname"], "Bob the Builder");
+    assert_eq!(member(&list, &other_admin_id)["nickname"], "Deputy");
+
+    // An empty nickname clears it
+    rename(&bob_id, &owner_token, json!("")).await.assert_status(StatusCode::NO_CONTENT);
+    let list = members(&server, &server_id, &owner_token, "").await;
+    assert!(member(&list, &bob_id)["nickname"].is_null());
+}
+
+#[tokio::test]
+async fn nickname_changes_are_broadcast() {
+    let (base, pool) = start_server().await;
+    let (owner_id, owner_token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let (bob_id, _) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+
+    let mut ws = ws_connect(&base, &owner_token).await;
+    drain_messages(&mut ws).await;
+
+    let res = reqwest::Client::new()
+        .patch(format!("{}/api/servers/{}/members/{}/nickname", base, server_id, bob_id))
+        .bearer_auth(&owner_token)
+        .json(&json!({ "nickname": "Bobby" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::NO_CONTENT);
+
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    let events = drain_messages(&mut ws).await;
+    let event = events.iter().find(|m| m["type"] == "member_profile_updated").unwrap();
+    assert_eq!(event["serverId"], server_id);
+    assert_eq!(event["userId"], bob_id);
+    assert_eq!(event["nickname"], "Bobby");
+    assert!(event["serverAvatar"].is_null());
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+/// A stand-in captcha provider that accepts the token "solved".
+async fn start_captcha_provider() -> String {
+    let app = axum::Router::new().route(
+        "/siteverify",
+        axum::routing::post(|body: String| async move {
+            let ok = body.contains("secret=shh") && body.contains("response=solved");
+            axum::Json(json!({ "success": ok }))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/siteverify", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+#[tokio::test]
+async fn new_accounts_and_unverified_emails_cannot_post() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+
+    let client = reqwest::Client::new();
+    let url = format!("{}/api/servers/{}/verification", base, server_id);
+    let res = client
+        .patch(&url)
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"minAccountAgeMinutes": 60, "requireVerifiedEmail": true}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+
+    // Challenges can't be turned on without a provider
+    let res = client
+        .patch(&url)
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"requireChallenge": true}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 503);
+
+    let res = client.get(&url).header("Authorization", format!("Bearer {}", bob_token)).send().await.unwrap();
+    let status: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(status["minAccountAgeMinutes"], 60);
+    assert!(status["challengeSiteKey"].is_null());
+    let unmet = status["unmet"].as_array().unwrap();
+    assert_eq!(unmet[0]["code"], "email_unverified");
+    assert_eq!(unmet[1]["code"], "account_too_new");
+    assert!(unmet[1]["availableAt"].is_string());
+
+    let mut ws = ws_connect(&base, &bob_token).await;
+    drain_messages(&mut ws).await;
+    send_json(&mut ws, &json!({"type": "send_message", "channelId": channel_id, "content": "hi"})).await;
+    let events = drain_messages(&mut ws).await;
+    let blocked = events.iter().find(|e| e["type"] == "verification_required").expect("blocked");
+    assert_eq!(blocked["serverId"], server_id);
+    assert_eq!(blocked["requirement"]["code"], "email_unverified");
+
+    // Owners aren't held to it
+    let mut alice_ws = ws_connect(&base, &alice_token).await;
+    drain_messages(&mut alice_ws).await;
+    send_json(&mut alice_ws, &json!({"type": "send_message", "channelId": channel_id, "content": "hi"})).await;
+
+    // Meeting both requirements unlocks posting
+    sqlx::query(r#"UPDATE "user" SET emailVerified = 1, createdAt = ? WHERE id = ?"#)
+        .bind((chrono::Utc::now() - chrono::Duration::hours(2)).to_rfc3339())
+        .bind(&bob_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    send_json(&mut ws, &json!({"type": "send_message", "channelId": channel_id, "content": "hi"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM messages WHERE channel_id = ?")
+        .bind(&channel_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+}
+
+#[tokio::test]
+async fn challenge_tokens_are_checked_with_the_provider() {
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    config.challenge_verify_url = start_captcha_provider().await;
+    config.challenge_site_key = "site-key".into();
+    config.challenge_secret = "shh".into();
+    let state = common::create_test_state(pool.clone(), config);
+    let server = TestServer::new(flux_server::routes::build_router(state)).unwrap();
+
+    let (alice_id, alice_token) = common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let url = format!("/api/servers/{}/verification", server_id);
+
+    let (h, v) = auth_header(&bob_token);
+    let res = server.patch(&url).add_header(h, v).json(&json!({"requireChallenge": true})).await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let (h, v) = auth_header(&alice_token);
+    let res = server.patch(&url).add_header(h, v).json(&json!({"requireChallenge": true})).await;
+    res.assert_status(StatusCode::OK);
+
+    let (h, v) = auth_header(&bob_token);
+    let status: serde_json::Value = server.get(&url).add_header(h, v).await.json();
+    assert_eq!(status["challengeSiteKey"], "site-key");
+    assert_eq!(status["unmet"], json!([{"code": "challenge_required"}]));
+
+    let (h, v) = auth_header(&bob_token);
+    let res = server
+        .post(&format!("{}/challenge", url))
+        .add_header(h, v)
+        .json(&json!({"token": "guess"}))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(res.json::<serde_json::Value>()["requirement"], "challenge_required");
+
+    let (h, v) = auth_header(&bob_token);
+    let res = server
+        .post(&format!("{}/challenge", url))
+        .add_header(h, v)
+        .json(&json!({"token": "solved"}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(res.json::<serde_json::Value>()["unmet"], json!([]));
+}
@@ -205,6 +205,39 @@ async fn search_server_messages_with_sender_filter() {
     assert_eq!(items[0]["senderId"], user2_id);
 }
 
+#[tokio::test]
+async fn mention_search_follows_renames() {
+    let (server, pool, user_id, token, server_id, channel_id) =
+        setup_with_channel().await;
+
+    insert_message(&pool, &channel_id, &user_id, "ping @alice").await;
+
+    let (h, v) = auth_header(&token);
+    server
+        .patch("/api/users/me/username")
+        .add_header(h, v)
+        .json(&serde_json::json!({ "username": "wren" }))
+        .await
+        .assert_status_ok();
+    insert_message(&pool, &channel_id, &user_id, "ping @wren").await;
+
+    // Either name finds both mentions
+    for name in ["wren", "alice"] {
+        let (h, v) = auth_header(&token);
+        let res = server
+            .get(&format!(
+                "/api/servers/{}/messages/search?mentions_username={}",
+                server_id, name
+            ))
+            .add_header(h, v)
+            .await;
+
+        res.assert_status_ok();
+        let body: serde_json::Value = res.json();
+        assert_eq!(body["items"].as_array().unwrap().len(), 2);
+    }
+}
+
 #[tokio::test]
 async fn get_reactions_for_messages() {
     let (server, pool, user_id, token, _server_id, channel_id) =
@@ -237,3 +270,188 @@ async fn get_reactions_for_messages() {
     assert_eq!(body[0]["emoji"], "👍");
     assert_eq!(body[0]["messageId"], msg_id);
 }
+
+#[tokio::test]
+async fn reaction_summaries_are_aggregated() {
+    let (server, pool, user_id, token, server_id, channel_id) =
+        setup_with_channel().await;
+
+    let msg_id = insert_message(&pool, &channel_id, &user_id, "popular").await;
+    let quiet_id = insert_message(&pool, &channel_id, &user_id, "quiet").await;
+    let mut reactors = vec![user_id.clone()];
+    for i in 0..4 {
+        let (id, _) = common::create_test_user(&pool, &format!("fan{}@test.com", i), &format!("fan{}", i), "pass123").await;
+        reactors.push(id);
+    }
+    for (i, reactor) in reactors.iter().enumerate() {
+        let emoji = if i == 4 { "🎉" } else { "👍" };
+        sqlx::query("INSERT INTO reactions (id, message_id, user_id, emoji, created_at) VALUES (?, ?, ?, ?, ?)")
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&msg_id)
+            .bind(reactor)
+            .bind(emoji)
+            .bind(format!("2026-01-01T00:00:0{}Z", i))
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let (h, v) = auth_header(&token);
+    let res = server
+        .get(&format!("/api/messages/reactions/summary?ids={},{}", msg_id, quiet_id))
+        .add_header(h, v)
+        .await;
+    res.assert_status_ok();
+    let body: Vec<serde_json::Value> = res.json();
+    assert_eq!(body.len(), 2);
+    assert_eq!(body[0]["emoji"], "👍");
+    assert_eq!(body[0]["count"], 4);
+    assert_eq!(body[0]["me"], true);
+    assert_eq!(body[0]["sampleUserIds"], serde_json::json!(&reactors[..3]));
+    assert_eq!(body[1]["emoji"], "🎉");
+    assert_eq!(body[1]["count"], 1);
+    assert_eq!(body[1]["me"], false);
+
+    // History carries the same counts, and leaves them off unreacted messages
+    let (h, v) = auth_header(&token);
+    let page: serde_json::Value = server
+        .get(&format!("/api/channels/{}/messages", channel_id))
+        .add_header(h, v)
+        .await
+        .json();
+    let items = page["items"].as_array().unwrap();
+    assert_eq!(items[0]["reactions"].as_array().unwrap().len(), 2);
+    assert!(items[1].get("reactions").is_none());
+
+    // Non-members see nothing, and huge batches are refused
+    let (_, outsider_token) =
+        common::create_test_user(&pool, "outsider@test.com", "outsider", "pass123").await;
+    let (h, v) = auth_header(&outsider_token);
+    let body: Vec<serde_json::Value> = server
+        .get(&format!("/api/messages/reactions/summary?ids={}", msg_id))
+        .add_header(h, v)
+        .await
+        .json();
+    assert!(body.is_empty());
+    let ids = vec![server_id.as_str(); 101].join(",");
+    let (h, v) = auth_header(&token);
+    server
+        .get(&format!("/api/messages/reactions/summary?ids={}", ids))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn get_message_with_context() {
+    let (server, pool, user_id, token, _server_id, channel_id) =
+        setup_with_channel().await;
+
+    let mut ids = Vec::new();
+    for i in 0..7 {
+        ids.push(insert_message(&pool, &channel_id, &user_id, &format!("msg {}", i)).await);
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+    }
+    sqlx::query("INSERT INTO reactions (id, message_id, user_id, emoji, created_at) VALUES ('r1', ?, ?, '👍', ?)")
+        .bind(&ids[3])
+        .bind(&user_id)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (h, v) = auth_header(&token);
+    let res = server
+        .get(&format!("/api/messages/{}?context=2", ids[3]))
+        .add_header(h, v)
+        .await;
+
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["message"]["id"], ids[3]);
+    let before: Vec<&str> = body["before"].as_array().unwrap().iter().map(|m| m["id"].as_str().unwrap()).collect();
+    let after: Vec<&str> = body["after"].as_array().unwrap().iter().map(|m| m["id"].as_str().unwrap()).collect();
+    assert_eq!(before, [ids[1].as_str(), ids[2].as_str()]);
+    assert_eq!(after, [ids[4].as_str(), ids[5].as_str()]);
+    assert_eq!(body["hasMoreBefore"], true);
+    assert_eq!(body["hasMoreAfter"], true);
+    assert_eq!(body["message"]["reactions"][0]["count"], 1);
+
+    // Outsiders can't tell the message exists
+    let (_, outsider_token) =
+        common::create_test_user(&pool, "outsider@test.com", "outsider", "pass123").await;
+    let (h, v) = auth_header(&outsider_token);
+    server
+        .get(&format!("/api/messages/{}", ids[3]))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn list_messages_around_after_and_date() {
+    let (server, pool, user_id, token, _server_id, channel_id) =
+        setup_with_channel().await;
+
+    let mut ids = Vec::new();
+    for i in 0..9 {
+        ids.push(insert_message(&pool, &channel_id, &user_id, &format!("msg {}", i)).await);
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+    }
+    let page_ids = |body: &serde_json::Value| -> Vec<String> {
+        body["items"].as_array().unwrap().iter().map(|m| m["id"].as_str().unwrap().to_string()).collect()
+    };
+
+    // Around: the message sits in the middle of the page
+    let (h, v) = auth_header(&token);
+    let body: serde_json::Value = server
+        .get(&format!("/api/channels/{}/messages?around={}&limit=4", channel_id, ids[4]))
+        .add_header(h, v)
+        .await
+        .json();
+    assert_eq!(page_ids(&body), &ids[2..6]);
+    assert_eq!(body["hasMore"], true);
+    assert_eq!(body["hasMoreAfter"], true);
+
+    // After: follow afterCursor forwards to the newest message
+    let after = body["afterCursor"].as_str().unwrap().to_string();
+    let (h, v) = auth_header(&token);
+    let body: serde_json::Value = server
+        .get(&format!(
+            "/api/channels/{}/messages?after={}&limit=4",
+            channel_id,
+            urlencoding::encode(&after)
+        ))
+        .add_header(h, v)
+        .await
+        .json();
+    assert_eq!(page_ids(&body), &ids[6..9]);
+    assert_eq!(body["hasMoreAfter"], false);
+
+    // Date: everything was sent today, so today's page starts at the oldest
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let (h, v) = auth_header(&token);
+    let body: serde_json::Value = server
+        .get(&format!("/api/channels/{}/messages?date={}&limit=4", channel_id, today))
+        .add_header(h, v)
+        .await
+        .json();
+    assert_eq!(page_ids(&body), &ids[0..4]);
+    assert_eq!(body["hasMore"], false);
+    assert_eq!(body["hasMoreAfter"], true);
+
+    for bad in ["date=yesterday", &format!("cursor=x&around={}", ids[0])] {
+        let (h, v) = auth_header(&token);
+        server
+            .get(&format!("/api/channels/{}/messages?{}", channel_id, bad))
+            .add_header(h, v)
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+    let (h, v) = auth_header(&token);
+    server
+        .get(&format!("/api/channels/{}/messages?around=nope", channel_id))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
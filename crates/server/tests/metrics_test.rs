@@ -0,0 +1,77 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue};
+use axum_test::TestServer;
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use serde_json::json;
+
+async fn scrape(base: &str) -> String {
+    reqwest::get(format!("{}/metrics", base)).await.unwrap().text().await.unwrap()
+}
+
+#[tokio::test]
+async fn metrics_cover_http_and_gateway() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "flux").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+    let room_id = common::create_room(&pool, &server_id, "Hang Out", &alice_id).await;
+
+    let mut alice = ws_connect(&base, &alice_token).await;
+    let mut bob = ws_connect(&base, &bob_token).await;
+    drain_messages(&mut alice).await;
+    drain_messages(&mut bob).await;
+    for ws in [&mut alice, &mut bob] {
+        send_json(ws, &json!({"type": "join_channel", "channelId": channel_id})).await;
+    }
+    send_json(&mut alice, &json!({"type": "voice_state_update", "channelId": room_id, "action": "join"})).await;
+    send_json(&mut alice, &json!({"type": "send_message", "channelId": channel_id, "content": "hi"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    reqwest::Client::new()
+        .get(format!("{}/api/servers/{}/channels", base, server_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+
+    let text = scrape(&base).await;
+    // Labelled by route template, not the raw path
+    assert!(text.contains(r#"route="/api/servers/{serverId}/channels""#), "{}", text);
+    assert!(!text.contains(&format!("/api/servers/{}/channels", server_id)));
+    assert!(text.contains("http_request_duration_seconds_bucket"));
+    assert!(text.contains(r#"gateway_events_received_total{type="send_message"}"#));
+    assert!(text.contains(r#"gateway_events_sent_total{type="message"}"#));
+    assert!(text.contains(r#"gateway_broadcast_recipients_bucket{scope="channel""#));
+    assert!(text.contains("gateway_connected_clients"));
+    assert!(text.contains("db_pool_connections"));
+    assert!(text.contains(&format!(r#"voice_participants{{server_id="{}"}} 1"#, server_id)));
+
+    // Leaving the room drops the server back to zero
+    send_json(&mut alice, &json!({"type": "voice_state_update", "channelId": room_id, "action": "leave"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    let text = scrape(&base).await;
+    assert!(text.contains(&format!(r#"voice_participants{{server_id="{}"}} 0"#, server_id)));
+}
+
+#[tokio::test]
+async fn metrics_token_is_enforced() {
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    config.metrics_token = "scrape-secret".into();
+    let server = TestServer::new(common::create_test_app_with_config(pool, config)).unwrap();
+
+    server.get("/metrics").await.assert_status_unauthorized();
+    server
+        .get("/metrics")
+        .add_header(HeaderName::from_static("authorization"), HeaderValue::from_static("Bearer wrong"))
+        .await
+        .assert_status_unauthorized();
+    server
+        .get("/metrics")
+        .add_header(HeaderName::from_static("authorization"), HeaderValue::from_static("Bearer scrape-secret"))
+        .await
+        .assert_status_ok();
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use axum::{routing::get, Router};
+use axum_test::TestServer;
+use sha2::{Digest, Sha256};
+
+const WASM: &[u8] = b"\0asm fake wasm module";
+const MODEL: &[u8] = b"fake model archive";
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// A stand-in for the model CDN.
+async fn start_upstream() -> String {
+    let app = Router::new()
+        .route("/v2/pkg/df_bg.wasm", get(|| async { WASM }))
+        .route("/v2/models/model.tar.gz", get(|| async { MODEL }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+fn temp_dir() -> String {
+    std::env::temp_dir()
+        .join(format!("flux-noise-models-{}", uuid::Uuid::new_v4()))
+        .display()
+        .to_string()
+}
+
+#[tokio::test]
+async fn models_are_cached_verified_and_served_by_version() {
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    config.noise_model_url = start_upstream().await;
+    config.noise_model_dir = temp_dir();
+    config.noise_model_files = format!("v2/pkg/df_bg.wasm={},v2/models/model.tar.gz", sha256_hex(WASM));
+    let state = common::create_test_state(pool, config.clone());
+
+    let server = TestServer::new(flux_server::routes::build_router(state.clone())).unwrap();
+    server.get("/api/noise-models/version").await.assert_status(axum::http::StatusCode::SERVICE_UNAVAILABLE);
+
+    flux_server::routes::noise_models::sync_noise_models(&state).await;
+    let res = server.get("/api/noise-models/version").await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    let version = body["version"].as_str().unwrap().to_string();
+    assert_eq!(body["baseUrl"], format!("/noise-models/{}", version));
+    assert_eq!(body["files"][0]["sha256"], sha256_hex(WASM));
+    assert_eq!(body["files"][1]["size"], MODEL.len());
+
+    let res = server.get(&format!("/noise-models/{}/v2/pkg/df_bg.wasm", version)).await;
+    res.assert_status_ok();
+    assert_eq!(res.as_bytes().as_ref(), WASM);
+    assert_eq!(res.header("content-type"), "application/wasm");
+    assert_eq!(res.header("cache-control"), "public, max-age=31536000, immutable");
+
+    // Old clients still find the files at the unversioned path
+    let res = server.get("/deepfilter-cdn/v2/models/model.tar.gz").await;
+    res.assert_status_ok();
+    assert_eq!(res.as_bytes().as_ref(), MODEL);
+
+    server.get("/noise-models/stale/v2/pkg/df_bg.wasm").await.assert_status_not_found();
+    server.get(&format!("/noise-models/{}/manifest.json", version)).await.assert_status_not_found();
+
+    // With no upstream whatever is on disk is served, changing the version;
+    // with one, the file is fetched again
+    std::fs::write(format!("{}/v2/models/model.tar.gz", config.noise_model_dir), b"corrupt").unwrap();
+    let mut offline = config.clone();
+    offline.noise_model_url = "".into();
+    let offline_state = common::create_test_state(common::setup_test_db().await, offline);
+    flux_server::routes::noise_models::sync_noise_models(&offline_state).await;
+    let changed = offline_state.noise_models.read().await.version.clone().unwrap();
+    assert_ne!(changed, version);
+
+    flux_server::routes::noise_models::sync_noise_models(&state).await;
+    assert_eq!(state.noise_models.read().await.version.as_deref(), Some(version.as_str()));
+}
+
+#[tokio::test]
+async fn downloads_failing_their_pinned_checksum_are_rejected() {
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    config.noise_model_url = start_upstream().await;
+    config.noise_model_dir = temp_dir();
+    config.noise_model_files = format!("v2/pkg/df_bg.wasm={}", sha256_hex(b"something else"));
+    let state = common::create_test_state(pool, config.clone());
+
+    flux_server::routes::noise_models::sync_noise_models(&state).await;
+    assert!(state.noise_models.read().await.version.is_none());
+    assert!(!std::path::Path::new(&format!("{}/v2/pkg/df_bg.wasm", config.noise_model_dir)).exists());
+
+    let server = TestServer::new(flux_server::routes::build_router(state)).unwrap();
+    server.get("/deepfilter-cdn/v2/pkg/df_bg.wasm").await.assert_status_not_found();
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue};
+use axum_test::TestServer;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+/// Follow `cursor` until `hasMore` is false, returning each page's items.
+async fn all_pages(server: &TestServer, path: &str, token: &str) -> Vec<Vec<serde_json::Value>> {
+    let sep = if path.contains('?') { '&' } else { '?' };
+    let mut pages = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let url = match &cursor {
+            Some(c) => format!("{}{}cursor={}", path, sep, urlencoding::encode(c)),
+            None => path.to_string(),
+        };
+        let (h, v) = auth_header(token);
+        let res = server.get(&url).add_header(h, v).await;
+        res.assert_status_ok();
+        let body: serde_json::Value = res.json();
+        pages.push(body["items"].as_array().unwrap().clone());
+        if body["hasMore"] == false {
+            assert!(body["cursor"].is_null());
+            return pages;
+        }
+        cursor = Some(body["cursor"].as_str().unwrap().to_string());
+    }
+}
+
+#[tokio::test]
+async fn members_page_through_ties_in_join_order() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (owner_id, token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+
+    // Everyone joins in the same instant, so only the id orders them
+    let joined_at = "2030-01-01T00:00:00+00:00";
+    for n in 0..5 {
+        let (id, _) =
+            common::create_test_user(&pool, &format!("u{}@test.com", n), &format!("user{}", n), "password123").await;
+        sqlx::query("INSERT INTO memberships (user_id, server_id, role, joined_at, role_updated_at) VALUES (?, ?, 'member', ?, ?)")
+            .bind(&id)
+            .bind(&server_id)
+            .bind(joined_at)
+            .bind(joined_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let pages = all_pages(&server, &format!("/api/servers/{}/members?limit=2", server_id), &token).await;
+    assert_eq!(pages.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 2, 2]);
+    let members: Vec<&serde_json::Value> = pages.iter().flatten().collect();
+    assert_eq!(members[0]["username"], "owner");
+
+    let tied: Vec<&str> = members[1..].iter().map(|m| m["userId"].as_str().unwrap()).collect();
+    let mut sorted = tied.clone();
+    sorted.sort();
+    sorted.dedup();
+    assert_eq!(tied, sorted);
+    assert_eq!(tied.len(), 5);
+}
+
+#[tokio::test]
+async fn emojis_and_whitelist_are_paged() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (owner_id, token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+
+    for n in 0..3 {
+        let attachment_id = common::create_test_attachment(&pool, &owner_id, &format!("e{}.png", n), "image/png").await;
+        sqlx::query(
+            "INSERT INTO custom_emojis (id, server_id, name, attachment_id, filename, uploader_id, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(format!("emoji{}", n))
+        .bind(&server_id)
+        .bind(format!("emoji_{}", n))
+        .bind(&attachment_id)
+        .bind(format!("e{}.png", n))
+        .bind(&owner_id)
+        .bind(format!("2030-01-0{}T00:00:00+00:00", n + 1))
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO email_whitelist (id, email, added_by, added_at) VALUES (?, ?, ?, ?)")
+            .bind(format!("w{}", n))
+            .bind(format!("w{}@test.com", n))
+            .bind(&owner_id)
+            .bind(format!("2030-01-0{}T00:00:00+00:00", n + 1))
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    // Emojis oldest first
+    let pages = all_pages(&server, &format!("/api/servers/{}/emojis?limit=2", server_id), &token).await;
+    let names: Vec<&str> = pages.iter().flatten().map(|e| e["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["emoji_0", "emoji_1", "emoji_2"]);
+    assert_eq!(pages.len(), 2);
+
+    // Whitelist newest first
+    let pages = all_pages(&server, "/api/whitelist?limit=2", &token).await;
+    let emails: Vec<&str> = pages.iter().flatten().map(|e| e["email"].as_str().unwrap()).collect();
+    assert_eq!(emails, vec!["w2@test.com", "w1@test.com", "w0@test.com"]);
+
+    let (h, v) = auth_header(&token);
+    server
+        .get("/api/whitelist?cursor=nonsense")
+        .add_header(h, v)
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn gallery_search_is_paged() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (user_id, token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+
+    for (n, name) in ["cats one", "dogs", "cats two", "cats three"].iter().enumerate() {
+        let at = format!("2030-01-0{}T00:00:00+00:00", n + 1);
+        sqlx::query("INSERT INTO gallery_sets (id, name, creator_id, created_at, updated_at) VALUES (?, ?, ?, ?, ?)")
+            .bind(format!("set{}", n))
+            .bind(name)
+            .bind(&user_id)
+            .bind(&at)
+            .bind(&at)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let pages = all_pages(&server, "/api/gallery?q=cats&limit=2", &token).await;
+    let names: Vec<&str> = pages.iter().flatten().map(|s| s["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["cats three", "cats two", "cats one"]);
+
+    let pages = all_pages(&server, "/api/gallery", &token).await;
+    assert_eq!(pages.len(), 1);
+    assert_eq!(pages[0].len(), 4);
+}
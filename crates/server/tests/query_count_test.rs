@@ -0,0 +1,153 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue};
+use axum_test::TestServer;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Once;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::Layer;
+
+static QUERIES: AtomicUsize = AtomicUsize::new(0);
+/// The counter is process-wide, so tests take turns.
+static SERIAL: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Counts the statements sqlx logs.
+struct QueryCounter;
+
+impl<S: tracing::Subscriber> Layer<S> for QueryCounter {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() == "sqlx::query" {
+            QUERIES.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+fn install_counter() {
+    static INSTALL: Once = Once::new();
+    // Global rather than scoped: sqlx runs queries on its own worker thread
+    INSTALL.call_once(|| {
+        tracing::subscriber::set_global_default(tracing_subscriber::registry().with(QueryCounter)).unwrap();
+    });
+}
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+/// Run a GET and return the body along with how many queries it took.
+async fn counted_get(server: &TestServer, path: &str, token: &str) -> (serde_json::Value, usize) {
+    let (h, v) = auth_header(token);
+    let before = QUERIES.load(Ordering::SeqCst);
+    let res = server.get(path).add_header(h, v).await;
+    let queries = QUERIES.load(Ordering::SeqCst) - before;
+    res.assert_status_ok();
+    (res.json(), queries)
+}
+
+async fn add_dm(pool: &sqlx::SqlitePool, user_id: &str, n: usize) {
+    let (other_id, _) =
+        common::create_test_user(pool, &format!("u{}@test.com", n), &format!("user{}", n), "password123").await;
+    let (a, b) = if user_id < other_id.as_str() { (user_id, other_id.as_str()) } else { (other_id.as_str(), user_id) };
+    sqlx::query("INSERT INTO dm_channels (id, user1_id, user2_id, created_at) VALUES (?, ?, ?, ?)")
+        .bind(format!("dm{}", n))
+        .bind(a)
+        .bind(b)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+async fn add_subscribed_set(pool: &sqlx::SqlitePool, user_id: &str, n: usize, images: usize) {
+    let now = chrono::Utc::now().to_rfc3339();
+    let set_id = format!("set{}", n);
+    sqlx::query("INSERT INTO gallery_sets (id, name, creator_id, created_at, updated_at) VALUES (?, ?, ?, ?, ?)")
+        .bind(&set_id)
+        .bind(format!("Set {}", n))
+        .bind(user_id)
+        .bind(&now)
+        .bind(&now)
+        .execute(pool)
+        .await
+        .unwrap();
+    // Inserted in reverse to check they come back in position order
+    for i in (0..images).rev() {
+        let attachment_id = common::create_test_attachment(pool, user_id, &format!("{}-{}.png", n, i), "image/png").await;
+        sqlx::query(
+            "INSERT INTO gallery_set_images (id, set_id, attachment_id, name, position, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(format!("{}-img{}", set_id, i))
+        .bind(&set_id)
+        .bind(&attachment_id)
+        .bind(format!("image {}", i))
+        .bind(i as i64)
+        .bind(&now)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+    sqlx::query("INSERT INTO gallery_subscriptions (user_id, set_id, created_at) VALUES (?, ?, ?)")
+        .bind(user_id)
+        .bind(&set_id)
+        .bind(&now)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn list_dms_query_count_is_flat() {
+    install_counter();
+    let _serial = SERIAL.lock().await;
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (user_id, token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+
+    add_dm(&pool, &user_id, 0).await;
+    let (one, one_queries) = counted_get(&server, "/api/dms", &token).await;
+    assert_eq!(one.as_array().unwrap().len(), 1);
+    assert_eq!(one[0]["otherUser"]["username"], "user0");
+
+    for n in 1..6 {
+        add_dm(&pool, &user_id, n).await;
+    }
+    let (many, many_queries) = counted_get(&server, "/api/dms", &token).await;
+    assert_eq!(many.as_array().unwrap().len(), 6);
+    assert!(many.as_array().unwrap().iter().all(|dm| dm["otherUser"]["id"] != user_id.as_str()));
+
+    assert!(one_queries > 0);
+    assert_eq!(one_queries, many_queries);
+}
+
+#[tokio::test]
+async fn list_subscribed_query_count_is_flat() {
+    install_counter();
+    let _serial = SERIAL.lock().await;
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (user_id, token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+
+    add_subscribed_set(&pool, &user_id, 0, 2).await;
+    let (one, one_queries) = counted_get(&server, "/api/gallery/subscribed", &token).await;
+    assert_eq!(one.as_array().unwrap().len(), 1);
+
+    for n in 1..5 {
+        add_subscribed_set(&pool, &user_id, n, n).await;
+    }
+    let (many, many_queries) = counted_get(&server, "/api/gallery/subscribed", &token).await;
+    let sets = many.as_array().unwrap();
+    assert_eq!(sets.len(), 5);
+    for set in sets {
+        let images = set["images"].as_array().unwrap();
+        assert_eq!(images.len() as i64, set["imageCount"].as_i64().unwrap());
+        assert!(images.iter().all(|i| i["setId"] == set["id"]));
+        let positions: Vec<i64> = images.iter().map(|i| i["position"].as_i64().unwrap()).collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    assert!(one_queries > 0);
+    assert_eq!(one_queries, many_queries);
+}
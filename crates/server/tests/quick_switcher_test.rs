@@ -0,0 +1,72 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue};
+use axum_test::TestServer;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn setup() -> (TestServer, sqlx::SqlitePool) {
+    let pool = common::setup_test_db().await;
+    let app = common::create_test_app(pool.clone());
+    let server = TestServer::new(app).unwrap();
+    (server, pool)
+}
+
+#[tokio::test]
+async fn ranks_channels_dms_and_people_together() {
+    let (server, pool) = setup().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, _) = common::create_test_user(&pool, "bob@test.com", "gene", "pass123").await;
+    let (carol_id, _) = common::create_test_user(&pool, "carol@test.com", "genevieve", "pass123").await;
+    let (_dave_id, _) = common::create_test_user(&pool, "dave@test.com", "genghis", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    common::add_member(&pool, &carol_id, &server_id, "member").await;
+    common::create_text_channel(&pool, &server_id, "off-topic").await;
+
+    // A server Alice isn't in stays out of her results
+    let other_server = common::create_test_server(&pool, &carol_id, "Elsewhere").await;
+    common::create_text_channel(&pool, &other_server, "gen").await;
+
+    let (h, v) = auth_header(&alice_token);
+    let res = server.post("/api/dms").add_header(h, v).json(&serde_json::json!({"userId": bob_id})).await;
+    res.assert_status_ok();
+    let dm_id = res.json::<serde_json::Value>()["id"].as_str().unwrap().to_string();
+
+    let (h, v) = auth_header(&alice_token);
+    let res = server.get("/api/quick-switcher?q=gen").add_header(h, v).await;
+    res.assert_status_ok();
+    let items: Vec<serde_json::Value> = res.json();
+
+    // Exact and prefix matches first; nobody outside Alice's servers
+    let summary: Vec<(&str, &str)> = items
+        .iter()
+        .map(|i| (i["kind"].as_str().unwrap(), i["name"].as_str().unwrap()))
+        .collect();
+    assert_eq!(summary, vec![("dm", "gene"), ("channel", "general"), ("user", "genevieve")]);
+    assert_eq!(items[0]["id"], dm_id);
+    assert_eq!(items[0]["userId"], bob_id);
+    assert_eq!(items[1]["serverName"], "TestServer");
+    assert_eq!(items[1]["channelType"], "text");
+    assert_eq!(items[2]["id"], carol_id);
+
+    // Scattered letters still match, and a leading # is ignored
+    let (h, v) = auth_header(&alice_token);
+    let items: Vec<serde_json::Value> = server.get("/api/quick-switcher?q=%23otp").add_header(h, v).await.json();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["name"], "off-topic");
+
+    let (h, v) = auth_header(&alice_token);
+    let items: Vec<serde_json::Value> = server.get("/api/quick-switcher?q=gen&limit=1").add_header(h, v).await.json();
+    assert_eq!(items.len(), 1);
+
+    let (h, v) = auth_header(&alice_token);
+    let items: Vec<serde_json::Value> = server.get("/api/quick-switcher?q=").add_header(h, v).await.json();
+    assert!(items.is_empty());
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use serde_json::json;
+
+#[tokio::test]
+async fn reacting_grants_and_unreacting_revokes_role() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "roles").await;
+    sqlx::query("INSERT INTO messages (id, channel_id, sender_id, content, created_at) VALUES ('m1', ?, ?, 'Pick your roles', ?)")
+        .bind(&channel_id)
+        .bind(&alice_id)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let client = reqwest::Client::new();
+    let api = format!("{}/api/servers/{}", base, server_id);
+
+    let res = client
+        .post(format!("{}/roles", api))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"name": "Gamers", "color": "#5865F2"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 201);
+    let role: serde_json::Value = res.json().await.unwrap();
+    let role_id = role["id"].as_str().unwrap().to_string();
+    assert_eq!(role["color"], "#5865f2");
+
+    let res = client
+        .post(format!("{}/roles", api))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"name": "admin"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 400);
+
+    // Members can't manage bindings
+    let res = client
+        .post(format!("{}/reaction-roles", api))
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .json(&json!({"messageId": "m1", "emoji": "🎮", "roleId": role_id}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 403);
+
+    let res = client
+        .post(format!("{}/reaction-roles", api))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"messageId": "m1", "emoji": "🎮", "roleId": role_id}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 201);
+    let binding: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(binding["channelId"], channel_id);
+    assert_eq!(binding["roleName"], "Gamers");
+
+    let res = client
+        .post(format!("{}/reaction-roles", api))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"messageId": "m1", "emoji": "🎮", "roleId": role_id}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 409);
+
+    let mut alice_ws = ws_connect(&base, &alice_token).await;
+    drain_messages(&mut alice_ws).await;
+    let mut bob_ws = ws_connect(&base, &bob_token).await;
+    drain_messages(&mut bob_ws).await;
+
+    send_json(&mut bob_ws, &json!({"type": "add_reaction", "messageId": "m1", "emoji": "🎮"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let events = drain_messages(&mut alice_ws).await;
+    let update = events
+        .iter()
+        .find(|e| e["type"] == "member_role_updated")
+        .expect("role update broadcast");
+    assert_eq!(update["userId"], bob_id);
+    assert_eq!(update["role"], "member");
+    assert_eq!(update["roles"], json!([role_id]));
+
+    let res = client
+        .get(format!("{}/roles", api))
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .send()
+        .await
+        .unwrap();
+    let roles: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(roles[0]["memberIds"], json!([bob_id]));
+
+    // A reaction with an unbound emoji changes nothing
+    send_json(&mut bob_ws, &json!({"type": "add_reaction", "messageId": "m1", "emoji": "👍"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let events = drain_messages(&mut alice_ws).await;
+    assert!(!events.iter().any(|e| e["type"] == "member_role_updated"));
+
+    send_json(&mut bob_ws, &json!({"type": "remove_reaction", "messageId": "m1", "emoji": "🎮"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let events = drain_messages(&mut alice_ws).await;
+    let update = events
+        .iter()
+        .find(|e| e["type"] == "member_role_updated")
+        .expect("role update broadcast");
+    assert_eq!(update["roles"], json!([]));
+
+    let held: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM member_roles WHERE user_id = ?")
+        .bind(&bob_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(held, 0);
+
+    // Deleting the role drops its bindings
+    let res = client
+        .delete(format!("{}/roles/{}", api, role_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 204);
+    let res = client
+        .get(format!("{}/reaction-roles", api))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+    let bindings: serde_json::Value = res.json().await.unwrap();
+    assert!(bindings.as_array().unwrap().is_empty());
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use flux_server::routes::messages::dispatch_due_reminders;
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn insert_message(pool: &sqlx::SqlitePool, channel_id: &str, sender_id: &str) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO messages (id, channel_id, sender_id, content, created_at) VALUES (?, ?, ?, 'hi', ?)")
+        .bind(&id)
+        .bind(channel_id)
+        .bind(sender_id)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(pool)
+        .await
+        .unwrap();
+    id
+}
+
+async fn insert_dm(pool: &sqlx::SqlitePool, user1: &str, user2: &str) -> (String, String) {
+    let now = chrono::Utc::now().to_rfc3339();
+    let dm_channel_id = uuid::Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO dm_channels (id, user1_id, user2_id, created_at) VALUES (?, ?, ?, ?)")
+        .bind(&dm_channel_id)
+        .bind(user1)
+        .bind(user2)
+        .bind(&now)
+        .execute(pool)
+        .await
+        .unwrap();
+    let message_id = uuid::Uuid::new_v4().to_string();
+    sqlx::query(
+        "INSERT INTO dm_messages (id, dm_channel_id, sender_id, ciphertext, mls_epoch, created_at) VALUES (?, ?, ?, 'x', 0, ?)",
+    )
+    .bind(&message_id)
+    .bind(&dm_channel_id)
+    .bind(user1)
+    .bind(&now)
+    .execute(pool)
+    .await
+    .unwrap();
+    (dm_channel_id, message_id)
+}
+
+#[tokio::test]
+async fn reminders_fire_once_and_can_be_cancelled() {
+    let pool = common::setup_test_db().await;
+    let state = common::create_test_state(pool.clone(), common::test_config());
+    let server = TestServer::new(flux_server::routes::build_router(state.clone())).unwrap();
+    let (alice_id, alice_token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let (_, eve_token) = common::create_test_user(&pool, "eve@test.com", "eve", "password123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "flux").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+    let message_id = insert_message(&pool, &channel_id, &alice_id).await;
+    let (dm_channel_id, dm_message_id) = insert_dm(&pool, &alice_id, &bob_id).await;
+    let remind = format!("/api/messages/{}/remind", message_id);
+
+    // Messages the caller can't see look missing
+    let (h, v) = auth_header(&eve_token);
+    server
+        .post(&remind)
+        .add_header(h, v)
+        .json(&json!({ "delaySeconds": 3600 }))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    let (h, v) = auth_header(&eve_token);
+    server
+        .post(&format!("/api/messages/{}/remind", dm_message_id))
+        .add_header(h, v)
+        .json(&json!({ "delaySeconds": 3600 }))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    for bad in [
+        json!({}),
+        json!({ "delaySeconds": 10 }),
+        json!({ "delaySeconds": 3600, "remindAt": "2030-01-01T00:00:00Z" }),
+        json!({ "remindAt": "next week" }),
+        json!({ "remindAt": "2000-01-01T00:00:00Z" }),
+    ] {
+        let (h, v) = auth_header(&alice_token);
+        server.post(&remind).add_header(h, v).json(&bad).await.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    let (h, v) = auth_header(&alice_token);
+    let res = server
+        .post(&remind)
+        .add_header(h, v)
+        .json(&json!({ "delaySeconds": 3600, "note": "reply to this" }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let channel_reminder: serde_json::Value = res.json();
+    assert_eq!(channel_reminder["messageId"], message_id);
+    assert_eq!(channel_reminder["serverId"], server_id);
+    assert_eq!(channel_reminder["channelId"], channel_id);
+    assert_eq!(channel_reminder["note"], "reply to this");
+    assert!(channel_reminder["firedAt"].is_null());
+
+    let (h, v) = auth_header(&bob_token);
+    let res = server
+        .post(&format!("/api/messages/{}/remind", dm_message_id))
+        .add_header(h, v)
+        .json(&json!({ "remindAt": (chrono::Utc::now() + chrono::Duration::days(1)).to_rfc3339() }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let dm_reminder: serde_json::Value = res.json();
+    assert_eq!(dm_reminder["dmChannelId"], dm_channel_id);
+    assert!(dm_reminder["channelId"].is_null());
+
+    // Nothing is due yet
+    assert_eq!(dispatch_due_reminders(&state).await, 0);
+
+    sqlx::query("UPDATE message_reminders SET remind_at = '2000-01-01T00:00:00Z' WHERE id = ?")
+        .bind(channel_reminder["id"].as_str().unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(dispatch_due_reminders(&state).await, 1);
+    assert_eq!(dispatch_due_reminders(&state).await, 0);
+
+    let (h, v) = auth_header(&alice_token);
+    let mine: serde_json::Value = server.get("/api/users/me/reminders").add_header(h, v).await.json();
+    assert_eq!(mine.as_array().unwrap().len(), 1);
+    assert!(mine[0]["firedAt"].is_string());
+
+    // Only the owner can cancel a reminder
+    let dm_path = format!("/api/users/me/reminders/{}", dm_reminder["id"].as_str().unwrap());
+    let (h, v) = auth_header(&alice_token);
+    server.delete(&dm_path).add_header(h, v).await.assert_status(StatusCode::NOT_FOUND);
+    let (h, v) = auth_header(&bob_token);
+    server.delete(&dm_path).add_header(h, v).await.assert_status(StatusCode::NO_CONTENT);
+    let (h, v) = auth_header(&bob_token);
+    let mine: serde_json::Value = server.get("/api/users/me/reminders").add_header(h, v).await.json();
+    assert!(mine.as_array().unwrap().is_empty());
+
+    // Deleting the message takes its reminders with it
+    sqlx::query("DELETE FROM messages WHERE id = ?").bind(&message_id).execute(&pool).await.unwrap();
+    let (h, v) = auth_header(&alice_token);
+    let mine: serde_json::Value = server.get("/api/users/me/reminders").add_header(h, v).await.json();
+    assert!(mine.as_array().unwrap().is_empty());
+}
@@ -0,0 +1,214 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use serde_json::json;
+use sqlx::SqlitePool;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn insert_message(pool: &SqlitePool, id: &str, channel_id: &str, sender_id: &str, days_ago: i64) {
+    let created_at = (chrono::Utc::now() - chrono::Duration::days(days_ago)).to_rfc3339();
+    sqlx::query("INSERT INTO messages (id, channel_id, sender_id, content, created_at) VALUES (?, ?, ?, 'hi', ?)")
+        .bind(id)
+        .bind(channel_id)
+        .bind(sender_id)
+        .bind(&created_at)
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO messages_fts (message_id, plaintext) VALUES (?, 'hi')")
+        .bind(id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+async fn message_exists(pool: &SqlitePool, id: &str) -> bool {
+    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM messages WHERE id = ?")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        > 0
+}
+
+#[tokio::test]
+async fn retention_is_set_by_admins_and_validated() {
+    let pool = common::setup_test_db().await;
+    let state = common::create_test_state(pool.clone(), common::test_config());
+    let server = TestServer::new(flux_server::routes::build_router(state)).unwrap();
+
+    let (alice_id, alice_token) = common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+
+    let (h, v) = auth_header(&alice_token);
+    let res = server
+        .patch(&format!("/api/servers/{}", server_id))
+        .add_header(h, v)
+        .json(&json!({"retentionDays": 90}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["retentionDays"], 90);
+    assert_eq!(body["name"], "TestServer");
+
+    // Servers can't use 0; channels use it to keep messages forever
+    let (h, v) = auth_header(&alice_token);
+    let res = server
+        .patch(&format!("/api/servers/{}", server_id))
+        .add_header(h, v)
+        .json(&json!({"retentionDays": 0}))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    let (h, v) = auth_header(&alice_token);
+    let res = server
+        .patch(&format!("/api/servers/{}/channels/{}", server_id, channel_id))
+        .add_header(h, v)
+        .json(&json!({"retentionDays": 0}))
+        .await;
+    res.assert_status(StatusCode::OK);
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["retentionDays"], 0);
+
+    // Retention shows up in channel listings, and renaming leaves it alone
+    let (h, v) = auth_header(&alice_token);
+    server
+        .patch(&format!("/api/servers/{}/channels/{}", server_id, channel_id))
+        .add_header(h, v)
+        .json(&json!({"name": "chat"}))
+        .await
+        .assert_status(StatusCode::OK);
+    let (h, v) = auth_header(&bob_token);
+    let res = server.get(&format!("/api/servers/{}/channels", server_id)).add_header(h, v).await;
+    let channels: Vec<serde_json::Value> = res.json();
+    let channel = channels.iter().find(|c| c["id"] == channel_id.as_str()).unwrap();
+    assert_eq!(channel["name"], "chat");
+    assert_eq!(channel["retentionDays"], 0);
+
+    let (h, v) = auth_header(&alice_token);
+    let res = server
+        .patch(&format!("/api/servers/{}", server_id))
+        .add_header(h, v)
+        .json(&json!({"retentionDays": null}))
+        .await;
+    let body: serde_json::Value = res.json();
+    assert!(body["retentionDays"].is_null());
+
+    let (h, v) = auth_header(&bob_token);
+    let res = server
+        .patch(&format!("/api/servers/{}/channels/{}", server_id, channel_id))
+        .add_header(h, v)
+        .json(&json!({"retentionDays": 7}))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn purge_deletes_expired_messages_and_their_files() {
+    let pool = common::setup_test_db().await;
+    let mut config = common::test_config();
+    config.dm_retention_days = 30;
+    let upload_dir = config.upload_dir.clone();
+    let state = common::create_test_state(pool.clone(), config);
+
+    let (alice_id, _) = common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, _) = common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    let general = common::create_text_channel(&pool, &server_id, "general").await;
+    let archive = common::create_text_channel(&pool, &server_id, "archive").await;
+    let short = common::create_text_channel(&pool, &server_id, "short").await;
+    sqlx::query("UPDATE servers SET retention_days = 90 WHERE id = ?")
+        .bind(&server_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE channels SET retention_days = 0 WHERE id = ?")
+        .bind(&archive)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE channels SET retention_days = 7 WHERE id = ?")
+        .bind(&short)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    insert_message(&pool, "old", &general, &alice_id, 100).await;
+    insert_message(&pool, "recent", &general, &alice_id, 10).await;
+    insert_message(&pool, "kept", &archive, &alice_id, 1000).await;
+    insert_message(&pool, "short-old", &short, &alice_id, 10).await;
+    insert_message(&pool, "short-new", &short, &alice_id, 1).await;
+
+    sqlx::query("INSERT INTO reactions (id, message_id, user_id, emoji, created_at) VALUES ('r1', 'old', ?, '👍', ?)")
+        .bind(&alice_id)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&pool)
+        .await
+        .unwrap();
+    let attachment_id = common::create_test_attachment(&pool, &alice_id, "photo.png", "image/png").await;
+    sqlx::query("UPDATE attachments SET message_id = 'old' WHERE id = ?")
+        .bind(&attachment_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    tokio::fs::create_dir_all(&upload_dir).await.unwrap();
+    let file = flux_server::routes::files::stored_file_path(&upload_dir, &attachment_id, "photo.png");
+    tokio::fs::write(&file, b"png").await.unwrap();
+
+    let now = chrono::Utc::now();
+    sqlx::query("INSERT INTO dm_channels (id, user1_id, user2_id, created_at) VALUES ('dm1', ?, ?, ?)")
+        .bind(&alice_id)
+        .bind(&bob_id)
+        .bind(now.to_rfc3339())
+        .execute(&pool)
+        .await
+        .unwrap();
+    for (id, days_ago) in [("dm-old", 40), ("dm-new", 5)] {
+        sqlx::query("INSERT INTO dm_messages (id, dm_channel_id, sender_id, ciphertext, created_at) VALUES (?, 'dm1', ?, 'x', ?)")
+            .bind(id)
+            .bind(&alice_id)
+            .bind((now - chrono::Duration::days(days_ago)).to_rfc3339())
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let (messages, dms) = flux_server::routes::messages::purge_expired_messages(&state).await;
+    assert_eq!((messages, dms), (2, 1));
+
+    assert!(!message_exists(&pool, "old").await);
+    assert!(!message_exists(&pool, "short-old").await);
+    assert!(message_exists(&pool, "recent").await);
+    assert!(message_exists(&pool, "kept").await);
+    assert!(message_exists(&pool, "short-new").await);
+
+    let fts: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM messages_fts WHERE message_id IN ('old', 'short-old')")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(fts, 0);
+    let leftovers: i64 = sqlx::query_scalar(
+        "SELECT (SELECT COUNT(*) FROM reactions) + (SELECT COUNT(*) FROM attachments)",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(leftovers, 0);
+    assert!(!file.exists());
+
+    let dms: Vec<String> = sqlx::query_scalar("SELECT id FROM dm_messages").fetch_all(&pool).await.unwrap();
+    assert_eq!(dms, vec!["dm-new".to_string()]);
+
+    // Nothing left to purge
+    assert_eq!(flux_server::routes::messages::purge_expired_messages(&state).await, (0, 0));
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use common::ws_helpers::{drain_messages, start_server, ws_connect};
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn create_item(server: &TestServer, server_id: &str, token: &str, title: &str) -> String {
+    let (h, v) = auth_header(token);
+    let res = server
+        .post(&format!("/api/servers/{}/roadmap", server_id))
+        .add_header(h, v)
+        .json(&json!({ "title": title }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    res.json::<serde_json::Value>()["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn items_are_sorted_by_votes() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (owner_id, owner_token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let (_, carol_token) = common::create_test_user(&pool, "carol@test.com", "carol", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let first = create_item(&server, &server_id, &owner_token, "Dark mode").await;
+    let second = create_item(&server, &server_id, &owner_token, "Threads").await;
+
+    let vote = |item: &str, token: &str| {
+        let (h, v) = auth_header(token);
+        server.post(&format!("/api/servers/{}/roadmap/{}/vote", server_id, item)).add_header(h, v)
+    };
+
+    // Voting twice counts once
+    vote(&second, &bob_token).await.assert_status_ok();
+    let tally: serde_json::Value = vote(&second, &bob_token).await.json();
+    assert_eq!(tally, json!({ "voteCount": 1, "voted": true }));
+    vote(&second, &carol_token).await.assert_status(StatusCode::FORBIDDEN);
+    vote("missing", &bob_token).await.assert_status(StatusCode::NOT_FOUND);
+
+    let (h, v) = auth_header(&bob_token);
+    let items: serde_json::Value = server.get(&format!("/api/servers/{}/roadmap", server_id)).add_header(h, v).await.json();
+    assert_eq!(items[0]["id"], second);
+    assert_eq!(items[0]["voteCount"], 1);
+    assert_eq!(items[0]["voted"], true);
+    assert_eq!(items[1]["id"], first);
+    assert_eq!(items[1]["voted"], false);
+
+    // Withdrawing the vote restores creation order
+    let (h, v) = auth_header(&bob_token);
+    let tally: serde_json::Value = server
+        .delete(&format!("/api/servers/{}/roadmap/{}/vote", server_id, second))
+        .add_header(h, v)
+        .await
+        .json();
+    assert_eq!(tally, json!({ "voteCount": 0, "voted": false }));
+    let (h, v) = auth_header(&owner_token);
+    let items: serde_json::Value = server.get(&format!("/api/servers/{}/roadmap", server_id)).add_header(h, v).await.json();
+    assert_eq!(items[0]["id"], first);
+}
+
+#[tokio::test]
+async fn comments_thread_and_cascade() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (owner_id, owner_token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let (carol_id, carol_token) = common::create_test_user(&pool, "carol@test.com", "carol", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    common::add_member(&pool, &carol_id, &server_id, "member").await;
+    let item = create_item(&server, &server_id, &owner_token, "Dark mode").await;
+    let other = create_item(&server, &server_id, &owner_token, "Threads").await;
+    let path = format!("/api/servers/{}/roadmap/{}/comments", server_id, item);
+
+    let comment = |token: &str, body: serde_json::Value| {
+        let (h, v) = auth_header(token);
+        server.post(&path).add_header(h, v).json(&body)
+    };
+
+    let res = comment(&bob_token, json!({ "content": " Please! " })).await;
+    res.assert_status(StatusCode::CREATED);
+    let root: serde_json::Value = res.json();
+    assert_eq!(root["content"], "Please!");
+    assert_eq!(root["authorUsername"], "bob");
+    let root_id = root["id"].as_str().unwrap();
+
+    comment(&carol_token, json!({ "content": "+1", "parentId": root_id })).await.assert_status(StatusCode::CREATED);
+    comment(&carol_token, json!({ "content": "  " })).await.assert_status(StatusCode::BAD_REQUEST);
+    comment(&carol_token, json!({ "content": "x".repeat(2001) })).await.assert_status(StatusCode::BAD_REQUEST);
+
+    // Replies stay on their own item
+    let (h, v) = auth_header(&carol_token);
+    server
+        .post(&format!("/api/servers/{}/roadmap/{}/comments", server_id, other))
+        .add_header(h, v)
+        .json(&json!({ "content": "hm", "parentId": root_id }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let (h, v) = auth_header(&owner_token);
+    let comments: serde_json::Value = server.get(&path).add_header(h, v).await.json();
+    assert_eq!(comments.as_array().unwrap().len(), 2);
+    assert_eq!(comments[1]["parentId"], root_id);
+    assert_eq!(comments[1]["authorUsername"], "carol");
+
+    let (h, v) = auth_header(&owner_token);
+    let items: serde_json::Value = server.get(&format!("/api/servers/{}/roadmap", server_id)).add_header(h, v).await.json();
+    let listed = items.as_array().unwrap().iter().find(|i| i["id"] == item).unwrap();
+    assert_eq!(listed["commentCount"], 2);
+
+    // Only the author or an admin may delete; the thread goes with its root
+    let (h, v) = auth_header(&carol_token);
+    server.delete(&format!("{}/{}", path, root_id)).add_header(h, v).await.assert_status(StatusCode::FORBIDDEN);
+    let (h, v) = auth_header(&owner_token);
+    server.delete(&format!("{}/{}", path, root_id)).add_header(h, v).await.assert_status(StatusCode::NO_CONTENT);
+    let (h, v) = auth_header(&bob_token);
+    let comments: serde_json::Value = server.get(&path).add_header(h, v).await.json();
+    assert!(comments.as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn board_changes_are_broadcast() {
+    let (base, pool) = start_server().await;
+    let (owner_id, owner_token) = common::create_test_user(&pool, "owner@test.com", "owner", "password123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "flux").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+
+    let client = reqwest::Client::new();
+    let item: serde_json::Value = client
+        .post(format!("{}/api/servers/{}/roadmap", base, server_id))
+        .bearer_auth(&owner_token)
+        .json(&json!({ "title": "Dark mode" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let mut ws = ws_connect(&base, &owner_token).await;
+    drain_messages(&mut ws).await;
+
+    let res = client
+        .post(format!("{}/api/servers/{}/roadmap/{}/vote", base, server_id, item["id"].as_str().unwrap()))
+        .bearer_auth(&bob_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    let events = drain_messages(&mut ws).await;
+    let event = events.iter().find(|m| m["type"] == "roadmap_updated").unwrap();
+    assert_eq!(event["serverId"], server_id);
+    assert_eq!(event["itemId"], item["id"]);
+}
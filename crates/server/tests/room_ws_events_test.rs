@@ -1,6 +1,6 @@
 mod common;
 
-use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use common::ws_helpers::{drain_messages, start_server, ws_connect};
 use serde_json::json;
 
 // ── Room Lifecycle Events (2 tests) ──
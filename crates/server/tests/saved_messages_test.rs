@@ -0,0 +1,153 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn insert_message(pool: &sqlx::SqlitePool, channel_id: &str, sender_id: &str, content: &str) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO messages (id, channel_id, sender_id, content, created_at) VALUES (?, ?, ?, ?, ?)")
+        .bind(&id)
+        .bind(channel_id)
+        .bind(sender_id)
+        .bind(content)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(pool)
+        .await
+        .unwrap();
+    id
+}
+
+async fn insert_dm(pool: &sqlx::SqlitePool, user1: &str, user2: &str) -> (String, String) {
+    let now = chrono::Utc::now().to_rfc3339();
+    let dm_channel_id = uuid::Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO dm_channels (id, user1_id, user2_id, created_at) VALUES (?, ?, ?, ?)")
+        .bind(&dm_channel_id)
+        .bind(user1)
+        .bind(user2)
+        .bind(&now)
+        .execute(pool)
+        .await
+        .unwrap();
+    let message_id = uuid::Uuid::new_v4().to_string();
+    sqlx::query(
+        "INSERT INTO dm_messages (id, dm_channel_id, sender_id, ciphertext, mls_epoch, created_at) VALUES (?, ?, ?, 'x', 3, ?)",
+    )
+    .bind(&message_id)
+    .bind(&dm_channel_id)
+    .bind(user1)
+    .bind(&now)
+    .execute(pool)
+    .await
+    .unwrap();
+    (dm_channel_id, message_id)
+}
+
+#[tokio::test]
+async fn saved_messages_are_per_user_and_paginated() {
+    let pool = common::setup_test_db().await;
+    let state = common::create_test_state(pool.clone(), common::test_config());
+    let server = TestServer::new(flux_server::routes::build_router(state)).unwrap();
+    let (alice_id, alice_token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let (bob_id, _) = common::create_test_user(&pool, "bob@test.com", "bob", "password123").await;
+    let (_, eve_token) = common::create_test_user(&pool, "eve@test.com", "eve", "password123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "flux").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+    let first = insert_message(&pool, &channel_id, &alice_id, "first").await;
+    let second = insert_message(&pool, &channel_id, &alice_id, "second").await;
+    let (dm_channel_id, dm_message_id) = insert_dm(&pool, &alice_id, &bob_id).await;
+
+    // Messages the caller can't see look missing
+    for id in [&first, &dm_message_id] {
+        let (h, v) = auth_header(&eve_token);
+        server
+            .put(&format!("/api/messages/{}/save", id))
+            .add_header(h, v)
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    let (h, v) = auth_header(&alice_token);
+    let res = server.put(&format!("/api/messages/{}/save", first)).add_header(h, v).await;
+    res.assert_status_ok();
+    let saved: serde_json::Value = res.json();
+    assert_eq!(saved["messageId"], first);
+    assert_eq!(saved["serverId"], server_id);
+    assert_eq!(saved["channelName"], "general");
+    assert_eq!(saved["content"], "first");
+
+    // Saving twice keeps a single entry
+    let (h, v) = auth_header(&alice_token);
+    let again: serde_json::Value = server.put(&format!("/api/messages/{}/save", first)).add_header(h, v).await.json();
+    assert_eq!(again["id"], saved["id"]);
+
+    for id in [&second, &dm_message_id] {
+        let (h, v) = auth_header(&alice_token);
+        server.put(&format!("/api/messages/{}/save", id)).add_header(h, v).await.assert_status_ok();
+    }
+
+    let (h, v) = auth_header(&alice_token);
+    let page: serde_json::Value = server.get("/api/users/me/saved?limit=2").add_header(h, v).await.json();
+    assert_eq!(page["items"].as_array().unwrap().len(), 2);
+    assert_eq!(page["hasMore"], true);
+    let cursor = page["cursor"].as_str().unwrap().to_string();
+    let (h, v) = auth_header(&alice_token);
+    let rest: serde_json::Value = server
+        .get(&format!("/api/users/me/saved?limit=2&cursor={}", urlencoding::encode(&cursor)))
+        .add_header(h, v)
+        .await
+        .json();
+    assert_eq!(rest["items"].as_array().unwrap().len(), 1);
+    assert_eq!(rest["hasMore"], false);
+
+    let mut ids: Vec<String> = page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .chain(rest["items"].as_array().unwrap())
+        .map(|s| s["messageId"].as_str().unwrap().to_string())
+        .collect();
+    ids.sort();
+    let mut expected = vec![first.clone(), second.clone(), dm_message_id.clone()];
+    expected.sort();
+    assert_eq!(ids, expected);
+
+    let dm = page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .chain(rest["items"].as_array().unwrap())
+        .find(|s| s["messageId"] == dm_message_id)
+        .unwrap();
+    assert_eq!(dm["dmChannelId"], dm_channel_id);
+    assert_eq!(dm["ciphertext"], "x");
+    assert_eq!(dm["mlsEpoch"], 3);
+    assert!(dm["content"].is_null());
+
+    // Other users don't see Alice's saved messages
+    let (h, v) = auth_header(&eve_token);
+    let eve: serde_json::Value = server.get("/api/users/me/saved").add_header(h, v).await.json();
+    assert!(eve["items"].as_array().unwrap().is_empty());
+
+    // Unsaving is idempotent, and deleted messages drop out
+    for _ in 0..2 {
+        let (h, v) = auth_header(&alice_token);
+        server
+            .delete(&format!("/api/messages/{}/save", second))
+            .add_header(h, v)
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+    }
+    sqlx::query("DELETE FROM messages WHERE id = ?").bind(&first).execute(&pool).await.unwrap();
+    let (h, v) = auth_header(&alice_token);
+    let mine: serde_json::Value = server.get("/api/users/me/saved").add_header(h, v).await.json();
+    let items = mine["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["messageId"], dm_message_id);
+}
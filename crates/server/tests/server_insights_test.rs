@@ -0,0 +1,113 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn insert_message(pool: &sqlx::SqlitePool, channel_id: &str, sender_id: &str, created_at: &str) {
+    sqlx::query("INSERT INTO messages (id, channel_id, sender_id, content, created_at) VALUES (?, ?, ?, 'hi', ?)")
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(channel_id)
+        .bind(sender_id)
+        .bind(created_at)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn insights_aggregate_activity_by_day() {
+    let pool = common::setup_test_db().await;
+    let state = common::create_test_state(pool.clone(), common::test_config());
+    let server = TestServer::new(flux_server::routes::build_router(state)).unwrap();
+
+    let (alice_id, alice_token) = common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) = common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let (carol_id, _) = common::create_test_user(&pool, "carol@test.com", "carol", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    common::add_member(&pool, &carol_id, &server_id, "member").await;
+    let general = common::create_text_channel(&pool, &server_id, "general").await;
+    let random = common::create_text_channel(&pool, &server_id, "random").await;
+    let voice = common::create_voice_channel(&pool, &server_id, "lounge").await;
+
+    let now = chrono::Utc::now();
+    let today = now.to_rfc3339();
+    insert_message(&pool, &general, &alice_id, &today).await;
+    insert_message(&pool, &general, &alice_id, &today).await;
+    insert_message(&pool, &random, &bob_id, &today).await;
+    // Outside a one-week window
+    insert_message(&pool, &random, &bob_id, &(now - chrono::Duration::days(20)).to_rfc3339()).await;
+    // Carol only talks in voice
+    sqlx::query(
+        r#"INSERT INTO voice_sessions (id, user_id, channel_id, channel_name, server_id, joined_at, last_seen_at, left_at, duration_secs)
+           VALUES ('s1', ?, ?, 'lounge', ?, ?, ?, ?, 600)"#,
+    )
+    .bind(&carol_id)
+    .bind(&voice)
+    .bind(&server_id)
+    .bind(&today)
+    .bind(&today)
+    .bind(&today)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (h, v) = auth_header(&alice_token);
+    let res = server
+        .get(&format!("/api/servers/{}/insights?days=7", server_id))
+        .add_header(h, v)
+        .await;
+    res.assert_status(StatusCode::OK);
+    let insights: serde_json::Value = res.json();
+    assert_eq!(insights["days"], 7);
+    assert_eq!(insights["totalMembers"], 3);
+    assert_eq!(insights["totals"]["messages"], 3);
+    assert_eq!(insights["totals"]["activeUsers"], 3);
+    assert_eq!(insights["totals"]["newMembers"], 3);
+    assert_eq!(insights["totals"]["voiceMinutes"], 10);
+
+    // One zero-filled entry per day, today last
+    let daily = insights["daily"].as_array().unwrap();
+    assert_eq!(daily.len(), 7);
+    assert_eq!(daily[6]["day"], now.date_naive().to_string());
+    assert_eq!(daily[6]["messages"], 3);
+    assert_eq!(daily[6]["activeUsers"], 3);
+    assert_eq!(daily[0]["messages"], 0);
+
+    assert_eq!(insights["topChannels"][0]["name"], "general");
+    assert_eq!(insights["topChannels"][0]["messages"], 2);
+    assert_eq!(insights["topChannels"][1]["name"], "random");
+    assert_eq!(insights["topChannels"][1]["messages"], 1);
+
+    // A repeat request within the TTL is served from the cache
+    insert_message(&pool, &general, &alice_id, &today).await;
+    let (h, v) = auth_header(&alice_token);
+    let res = server
+        .get(&format!("/api/servers/{}/insights?days=7", server_id))
+        .add_header(h, v)
+        .await;
+    let cached: serde_json::Value = res.json();
+    assert_eq!(cached["totals"]["messages"], 3);
+    assert_eq!(cached["generatedAt"], insights["generatedAt"]);
+
+    // The 30-day default window sees the older message
+    let (h, v) = auth_header(&alice_token);
+    let res = server.get(&format!("/api/servers/{}/insights", server_id)).add_header(h, v).await;
+    let month: serde_json::Value = res.json();
+    assert_eq!(month["daily"].as_array().unwrap().len(), 30);
+    assert_eq!(month["totals"]["messages"], 5);
+
+    let (h, v) = auth_header(&bob_token);
+    let res = server
+        .get(&format!("/api/servers/{}/insights", server_id))
+        .add_header(h, v)
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+}
@@ -97,7 +97,9 @@ async fn list_members_with_user_details() {
         .await;
 
     res.assert_status_ok();
-    let body: Vec<serde_json::Value> = res.json();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["hasMore"], false);
+    let body = body["items"].as_array().unwrap();
     assert_eq!(body.len(), 1);
     assert_eq!(body[0]["username"], "owner");
     assert_eq!(body[0]["role"], "owner");
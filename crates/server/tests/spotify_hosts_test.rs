@@ -0,0 +1,227 @@
+mod common;
+
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use serde_json::json;
+
+type Ws = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+async fn join_voice(ws: &mut Ws, vc_id: &str) {
+    send_json(ws, &json!({"type": "voice_state_update", "channelId": vc_id, "action": "join"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+async fn create_session(base: &str, token: &str, vc_id: &str) -> String {
+    let res = reqwest::Client::new()
+        .post(format!("{}/api/spotify/sessions", base))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({"voiceChannelId": vc_id}))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = res.json().await.unwrap();
+    body["sessionId"].as_str().unwrap().to_string()
+}
+
+async fn session_host(pool: &sqlx::SqlitePool, session_id: &str) -> String {
+    sqlx::query_scalar(r#"SELECT host_user_id FROM "listening_sessions" WHERE id = ?"#)
+        .bind(session_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn host_disconnect_hands_off_to_cohost() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let (carol_id, carol_token) =
+        common::create_test_user(&pool, "carol@test.com", "carol", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    common::add_member(&pool, &carol_id, &server_id, "member").await;
+    let vc_id = common::create_voice_channel(&pool, &server_id, "voice-chat").await;
+
+    let mut ws_alice = ws_connect(&base, &alice_token).await;
+    let mut ws_bob = ws_connect(&base, &bob_token).await;
+    let mut ws_carol = ws_connect(&base, &carol_token).await;
+    join_voice(&mut ws_alice, &vc_id).await;
+    join_voice(&mut ws_bob, &vc_id).await;
+    join_voice(&mut ws_carol, &vc_id).await;
+
+    let session_id = create_session(&base, &alice_token, &vc_id).await;
+    let res = reqwest::Client::new()
+        .put(format!("{}/api/spotify/sessions/{}/cohosts/{}", base, session_id, carol_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    drain_messages(&mut ws_bob).await;
+
+    drop(ws_alice);
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    // Carol is a co-host, so she's preferred over bob
+    assert_eq!(session_host(&pool, &session_id).await, carol_id);
+    let msgs = drain_messages(&mut ws_bob).await;
+    let roles = msgs
+        .iter()
+        .find(|m| m["type"] == "spotify_session_roles")
+        .expect("roles event");
+    assert_eq!(roles["hostUserId"], carol_id);
+    assert_eq!(roles["cohostIds"], json!([]));
+    drain_messages(&mut ws_carol).await;
+}
+
+#[tokio::test]
+async fn transfer_host_requires_host_and_participant() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let vc_id = common::create_voice_channel(&pool, &server_id, "voice-chat").await;
+
+    let mut ws_alice = ws_connect(&base, &alice_token).await;
+    join_voice(&mut ws_alice, &vc_id).await;
+    let session_id = create_session(&base, &alice_token, &vc_id).await;
+    let client = reqwest::Client::new();
+    let url = format!("{}/api/spotify/sessions/{}/host", base, session_id);
+
+    // Bob isn't in the voice channel yet
+    let res = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"userId": bob_id}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 400);
+
+    let mut ws_bob = ws_connect(&base, &bob_token).await;
+    join_voice(&mut ws_bob, &vc_id).await;
+
+    let res = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .json(&json!({"userId": bob_id}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 403);
+
+    let res = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"userId": bob_id}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    assert_eq!(session_host(&pool, &session_id).await, bob_id);
+}
+
+#[tokio::test]
+async fn playback_control_limited_to_host_and_cohosts() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let vc_id = common::create_voice_channel(&pool, &server_id, "voice-chat").await;
+
+    let mut ws_alice = ws_connect(&base, &alice_token).await;
+    let mut ws_bob = ws_connect(&base, &bob_token).await;
+    join_voice(&mut ws_alice, &vc_id).await;
+    join_voice(&mut ws_bob, &vc_id).await;
+    let session_id = create_session(&base, &alice_token, &vc_id).await;
+    drain_messages(&mut ws_alice).await;
+    drain_messages(&mut ws_bob).await;
+
+    let pause = json!({"type": "spotify_playback_control", "sessionId": session_id, "action": "pause", "positionMs": 0});
+    send_json(&mut ws_bob, &pause).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let msgs = drain_messages(&mut ws_bob).await;
+    assert!(msgs.iter().any(|m| m["type"] == "error"));
+    let msgs = drain_messages(&mut ws_alice).await;
+    assert!(!msgs.iter().any(|m| m["type"] == "spotify_playback_sync"));
+
+    reqwest::Client::new()
+        .put(format!("{}/api/spotify/sessions/{}/cohosts/{}", base, session_id, bob_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+
+    send_json(&mut ws_bob, &pause).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let msgs = drain_messages(&mut ws_alice).await;
+    assert!(msgs.iter().any(|m| m["type"] == "spotify_playback_sync"));
+}
+
+#[tokio::test]
+async fn played_tracks_recorded_and_requeued() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    let vc_id = common::create_voice_channel(&pool, &server_id, "voice-chat").await;
+
+    let mut ws = ws_connect(&base, &alice_token).await;
+    join_voice(&mut ws, &vc_id).await;
+    let session_id = create_session(&base, &alice_token, &vc_id).await;
+    let client = reqwest::Client::new();
+
+    client
+        .post(format!("{}/api/spotify/sessions/{}/queue", base, session_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({
+            "trackUri": "spotify:track:one", "trackName": "One", "trackArtist": "Band",
+            "trackDurationMs": 1000
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    send_json(&mut ws, &json!({"type": "spotify_playback_control", "sessionId": session_id, "action": "skip", "trackUri": "spotify:track:one"})).await;
+    // Resuming isn't a new play
+    send_json(&mut ws, &json!({"type": "spotify_playback_control", "sessionId": session_id, "action": "play", "positionMs": 500})).await;
+    send_json(&mut ws, &json!({"type": "spotify_playback_control", "sessionId": session_id, "action": "play", "trackUri": "spotify:track:two", "positionMs": 0})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let res = client
+        .get(format!("{}/api/spotify/sessions/{}/history", base, session_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = res.json().await.unwrap();
+    let history = body["history"].as_array().unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0]["trackUri"], "spotify:track:two");
+    assert_eq!(history[1]["trackName"], "One");
+    assert_eq!(history[1]["trackArtist"], "Band");
+
+    let history_id = history[1]["id"].as_str().unwrap();
+    let res = client
+        .post(format!("{}/api/spotify/sessions/{}/history/{}/requeue", base, session_id, history_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+
+    let queued: String = sqlx::query_scalar(r#"SELECT track_name FROM "session_queue" WHERE session_id = ?"#)
+        .bind(&session_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(queued, "One");
+}
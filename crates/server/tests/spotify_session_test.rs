@@ -1,6 +1,6 @@
 mod common;
 
-use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum::http::{HeaderName, HeaderValue};
 use axum_test::TestServer;
 use serde_json::json;
 
@@ -136,3 +136,57 @@ async fn get_session_not_found() {
     assert!(body["session"].is_null());
     assert!(body["queue"].as_array().unwrap().is_empty());
 }
+
+#[tokio::test]
+async fn lyrics_need_a_playing_track_and_a_provider() {
+    let (server, pool) = setup().await;
+
+    let (_user_id, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+
+    let (h, v) = auth_header(&token);
+    let res = server.get("/api/spotify/sessions/missing/lyrics").add_header(h, v).await;
+    res.assert_status_not_found();
+
+    let (h, v) = auth_header(&token);
+    let res = server
+        .post("/api/spotify/sessions")
+        .add_header(h, v)
+        .json(&json!({ "voiceChannelId": uuid::Uuid::new_v4().to_string() }))
+        .await;
+    let session_id = res.json::<serde_json::Value>()["sessionId"].as_str().unwrap().to_string();
+
+    let (h, v) = auth_header(&token);
+    let res = server
+        .get(&format!("/api/spotify/sessions/{}/lyrics", session_id))
+        .add_header(h, v)
+        .await;
+    res.assert_status_not_found();
+    assert_eq!(res.json::<serde_json::Value>()["error"], "Nothing is playing");
+
+    sqlx::query(r#"UPDATE "listening_sessions" SET current_track_uri = 'spotify:track:abc' WHERE id = ?"#)
+        .bind(&session_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // The test config has no lyrics provider
+    let (h, v) = auth_header(&token);
+    let res = server
+        .get(&format!("/api/spotify/sessions/{}/lyrics", session_id))
+        .add_header(h, v)
+        .await;
+    res.assert_status(axum::http::StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[test]
+fn lrc_parsing_handles_offsets_and_repeated_timestamps() {
+    let lines = flux_server::routes::spotify::parse_lrc(
+        "[ar:Someone]\n[offset:500]\n[00:12.34]First\n[00:05.00][01:00.5]Chorus\nno timestamp\n[00:20]\n",
+    );
+    let got: Vec<(i64, &str)> = lines.iter().map(|l| (l.time_ms, l.text.as_str())).collect();
+    assert_eq!(
+        got,
+        vec![(4500, "Chorus"), (11840, "First"), (19500, ""), (60000, "Chorus")]
+    );
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use base64::Engine;
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use serde_json::json;
+
+/// Whether a LiveKit token from /api/voice/token lets its holder publish.
+async fn can_publish(base: &str, token: &str, channel_id: &str) -> bool {
+    let res = reqwest::Client::new()
+        .post(format!("{}/api/voice/token", base))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({"channelId": channel_id}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    let body: serde_json::Value = res.json().await.unwrap();
+    let payload = body["token"].as_str().unwrap().split('.').nth(1).unwrap().to_string();
+    let claims: serde_json::Value =
+        serde_json::from_slice(&base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(payload).unwrap()).unwrap();
+    claims["video"]["canPublish"].as_bool().unwrap_or(false)
+}
+
+#[tokio::test]
+async fn listeners_raise_hands_and_moderators_bring_them_up() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    sqlx::query(
+        "INSERT INTO voice_nodes (id, region, url, api_key, api_secret, enabled, created_at) VALUES ('n1', 'local', 'ws://localhost:7880', 'devkey', 'secret-that-is-at-least-256-bits-long-for-hmac', 1, ?)",
+    )
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(format!("{}/api/servers/{}/channels", base, server_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"name": "town-hall", "type": "stage"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 201);
+    let stage: serde_json::Value = res.json().await.unwrap();
+    let stage_id = stage["id"].as_str().unwrap().to_string();
+
+    // The moderator can speak; the audience can't
+    assert!(can_publish(&base, &alice_token, &stage_id).await);
+    assert!(!can_publish(&base, &bob_token, &stage_id).await);
+
+    let mut alice_ws = ws_connect(&base, &alice_token).await;
+    let mut bob_ws = ws_connect(&base, &bob_token).await;
+    for ws in [&mut alice_ws, &mut bob_ws] {
+        drain_messages(ws).await;
+        send_json(ws, &json!({"type": "voice_state_update", "channelId": stage_id, "action": "join"})).await;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    drain_messages(&mut alice_ws).await;
+
+    send_json(&mut bob_ws, &json!({"type": "stage_raise_hand", "channelId": stage_id, "raised": true})).await;
+    let events = drain_messages(&mut alice_ws).await;
+    let hand = events.iter().find(|e| e["type"] == "stage_hand_update").expect("hand event");
+    assert_eq!(hand["userId"], bob_id);
+    assert_eq!(hand["raised"], true);
+
+    // Only moderators bring people up
+    send_json(&mut bob_ws, &json!({"type": "stage_set_speaker", "channelId": stage_id, "userId": bob_id, "speaker": true})).await;
+    let events = drain_messages(&mut bob_ws).await;
+    assert!(events.iter().any(|e| e["type"] == "error"));
+
+    send_json(&mut alice_ws, &json!({"type": "stage_set_speaker", "channelId": stage_id, "userId": bob_id, "speaker": true})).await;
+    let events = drain_messages(&mut bob_ws).await;
+    let promoted = events.iter().find(|e| e["type"] == "stage_speaker_update").expect("speaker event");
+    assert_eq!(promoted["speaker"], true);
+    assert!(events.iter().any(|e| e["type"] == "stage_hand_update" && e["raised"] == false));
+    assert!(can_publish(&base, &bob_token, &stage_id).await);
+
+    let res = client
+        .get(format!("{}/api/channels/{}/stage", base, stage_id))
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .send()
+        .await
+        .unwrap();
+    let state: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(state["speakers"], json!([bob_id]));
+    assert_eq!(state["raisedHands"], json!([]));
+    assert_eq!(state["canModerate"], false);
+
+    // Leaving the stage gives up the speaker slot
+    send_json(&mut bob_ws, &json!({"type": "voice_state_update", "channelId": stage_id, "action": "leave"})).await;
+    let events = drain_messages(&mut alice_ws).await;
+    assert!(events.iter().any(|e| e["type"] == "stage_speaker_update" && e["speaker"] == false));
+    assert!(!can_publish(&base, &bob_token, &stage_id).await);
+}
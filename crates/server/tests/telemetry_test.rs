@@ -0,0 +1,71 @@
+mod common;
+
+use axum::{body::Bytes, routing::post, Router};
+use axum::http::{HeaderName, HeaderValue};
+use axum_test::TestServer;
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// A stand-in OTLP collector that keeps every export body it's sent.
+async fn start_collector() -> (String, Arc<Mutex<Vec<Bytes>>>) {
+    let received: Arc<Mutex<Vec<Bytes>>> = Arc::default();
+    let store = received.clone();
+    let app = Router::new().route(
+        "/v1/traces",
+        post(move |body: Bytes| async move {
+            store.lock().unwrap().push(body);
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (format!("http://{}", addr), received)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+#[tokio::test]
+async fn request_spans_join_the_callers_trace() {
+    let (endpoint, received) = start_collector().await;
+    let provider = flux_server::telemetry::tracer_provider(&endpoint, "flux-test").unwrap();
+    let subscriber = tracing_subscriber::registry().with(flux_server::telemetry::layer(&provider));
+    // Global rather than scoped: sqlx runs queries on its own worker thread
+    tracing::subscriber::set_global_default(subscriber).unwrap();
+
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (user_id, token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let server_id = common::create_test_server(&pool, &user_id, "flux").await;
+
+    server
+        .get(&format!("/api/servers/{}/channels", server_id))
+        .add_header(
+            HeaderName::from_static("authorization"),
+            format!("Bearer {}", token).parse::<HeaderValue>().unwrap(),
+        )
+        .add_header(
+            HeaderName::from_static("traceparent"),
+            HeaderValue::from_static("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+        )
+        .await
+        .assert_status_ok();
+
+    let flusher = provider.clone();
+    tokio::task::spawn_blocking(move || flusher.force_flush()).await.unwrap().unwrap();
+
+    let bodies = received.lock().unwrap().clone();
+    let export: Vec<u8> = bodies.iter().flat_map(|b| b.iter().copied()).collect();
+    // Same trace id the caller sent, as raw bytes in the protobuf
+    let trace_id: Vec<u8> = (0..16)
+        .map(|i| u8::from_str_radix(&"4bf92f3577b34da6a3ce929d0e0e4736"[i * 2..i * 2 + 2], 16).unwrap())
+        .collect();
+    assert!(contains(&export, &trace_id));
+    assert!(contains(&export, b"flux-test"));
+    assert!(contains(&export, b"GET /api/servers/{serverId}/channels"));
+    // The queries the handler ran are recorded on its span
+    assert!(contains(&export, b"SELECT"));
+
+    tokio::task::spawn_blocking(move || provider.shutdown()).await.unwrap().unwrap();
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use axum::{extract::ConnectInfo, http::StatusCode, routing::get, Router};
+use axum_test::TestServer;
+use flux_server::middleware::ip_filter::PeerAddr;
+use flux_server::tls;
+use rustls::pki_types::{pem::PemObject, CertificateDer};
+use std::path::{Path, PathBuf};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tls").join(name)
+}
+
+/// Copies of the fixtures the test can swap out underneath the server.
+fn cert_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("flux-tls-{}-{}", test, uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(fixture("first.crt"), dir.join("server.crt")).unwrap();
+    std::fs::copy(fixture("first.key"), dir.join("server.key")).unwrap();
+    dir
+}
+
+fn tls_config(dir: &Path) -> flux_server::config::Config {
+    let mut config = common::test_config();
+    config.tls_cert_path = dir.join("server.crt").to_string_lossy().into_owned();
+    config.tls_key_path = dir.join("server.key").to_string_lossy().into_owned();
+    config
+}
+
+fn der(name: &str) -> Vec<u8> {
+    CertificateDer::from_pem_file(fixture(name)).unwrap().to_vec()
+}
+
+/// The certificate a fresh connection is shown, and what the server saw
+/// as the peer address.
+async fn handshake(port: u16) -> (Vec<u8>, String) {
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .tls_info(true)
+        .pool_max_idle_per_host(0)
+        .build()
+        .unwrap();
+    let res = client.get(format!("https://127.0.0.1:{}/peer", port)).send().await.unwrap();
+    let cert = res
+        .extensions()
+        .get::<reqwest::tls::TlsInfo>()
+        .and_then(|info| info.peer_certificate())
+        .unwrap()
+        .to_vec();
+    (cert, res.text().await.unwrap())
+}
+
+#[tokio::test]
+async fn serves_https_and_reloads_the_certificate() {
+    let dir = cert_dir("reload");
+    let (acceptor, certs) = tls::acceptor(&tls_config(&dir)).unwrap();
+
+    let tcp = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = tcp.local_addr().unwrap().port();
+    let listener = tls::TlsListener::new(tcp, acceptor).unwrap();
+    let app = Router::new().route(
+        "/peer",
+        get(|ConnectInfo(peer): ConnectInfo<PeerAddr>| async move { peer.0.unwrap().ip().to_string() }),
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<PeerAddr>()).await.unwrap();
+    });
+
+    let (cert, peer) = handshake(port).await;
+    assert_eq!(cert, der("first.crt"));
+    assert_eq!(peer, "127.0.0.1");
+
+    // A half-written renewal is rejected and the old certificate kept
+    std::fs::copy(fixture("second.crt"), dir.join("server.crt")).unwrap();
+    assert!(certs.reload().is_err());
+    assert_eq!(handshake(port).await.0, der("first.crt"));
+
+    std::fs::copy(fixture("second.key"), dir.join("server.key")).unwrap();
+    certs.reload().unwrap();
+    assert_eq!(handshake(port).await.0, der("second.crt"));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn refuses_a_key_that_does_not_match() {
+    let dir = cert_dir("mismatch");
+    std::fs::copy(fixture("second.key"), dir.join("server.key")).unwrap();
+    assert!(tls::acceptor(&tls_config(&dir)).is_err());
+
+    let mut config = tls_config(&dir);
+    config.tls_cert_path = dir.join("missing.crt").to_string_lossy().into_owned();
+    assert!(tls::acceptor(&config).is_err());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn redirects_plain_http_to_https() {
+    let server = TestServer::new(tls::redirect_router(8443, Default::default())).unwrap();
+
+    let res = server
+        .get("/channels/abc?page=2")
+        .add_header(axum::http::header::HOST, "flux.example.com:8080")
+        .await;
+    res.assert_status(StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(res.header("location"), "https://flux.example.com:8443/channels/abc?page=2");
+
+    let server = TestServer::new(tls::redirect_router(443, Default::default())).unwrap();
+    let res = server.get("/").add_header(axum::http::header::HOST, "[::1]:80").await;
+    assert_eq!(res.header("location"), "https://[::1]/");
+}
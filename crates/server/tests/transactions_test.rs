@@ -0,0 +1,139 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+/// Make the next matching write fail, part way through a flow.
+async fn fail_on(pool: &sqlx::SqlitePool, trigger: &str) {
+    sqlx::query(&format!(
+        "CREATE TRIGGER injected_failure {} BEGIN SELECT RAISE(ABORT, 'injected failure'); END",
+        trigger
+    ))
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn count(pool: &sqlx::SqlitePool, sql: &str) -> i64 {
+    sqlx::query_scalar(sql).fetch_one(pool).await.unwrap()
+}
+
+#[tokio::test]
+async fn sign_up_rolls_back_when_the_session_fails() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    fail_on(&pool, r#"BEFORE INSERT ON "session""#).await;
+
+    let sign_up = json!({
+        "email": "alice@test.com",
+        "password": "Tangerine-Lighthouse-42",
+        "username": "alice",
+        "name": "Alice",
+    });
+    server
+        .post("/api/auth/sign-up/email")
+        .json(&sign_up)
+        .await
+        .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+
+    // No user, account, server or membership left behind
+    assert_eq!(count(&pool, r#"SELECT COUNT(*) FROM "user""#).await, 0);
+    assert_eq!(count(&pool, r#"SELECT COUNT(*) FROM "account""#).await, 0);
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM servers").await, 0);
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM memberships").await, 0);
+
+    // The same details work once the failure clears
+    sqlx::query("DROP TRIGGER injected_failure").execute(&pool).await.unwrap();
+    server.post("/api/auth/sign-up/email").json(&sign_up).await.assert_status_ok();
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM memberships").await, 1);
+}
+
+#[tokio::test]
+async fn gallery_set_is_not_created_without_its_images() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (user_id, token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let first = common::create_test_attachment(&pool, &user_id, "a.png", "image/png").await;
+    let second = common::create_test_attachment(&pool, &user_id, "b.png", "image/png").await;
+    fail_on(&pool, "BEFORE INSERT ON gallery_set_images WHEN NEW.position = 1").await;
+
+    let (h, v) = auth_header(&token);
+    server
+        .post("/api/gallery")
+        .add_header(h, v)
+        .json(&json!({
+            "name": "Holiday",
+            "imageAttachmentIds": [first, second],
+            "imageNames": ["a", "b"],
+        }))
+        .await
+        .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM gallery_sets").await, 0);
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM gallery_set_images").await, 0);
+}
+
+#[tokio::test]
+async fn channel_reorder_is_all_or_nothing() {
+    let pool = common::setup_test_db().await;
+    let server = TestServer::new(common::create_test_app(pool.clone())).unwrap();
+    let (user_id, token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let server_id = common::create_test_server(&pool, &user_id, "flux").await;
+    let first = common::create_text_channel(&pool, &server_id, "one").await;
+    let second = common::create_text_channel(&pool, &server_id, "two").await;
+    fail_on(&pool, &format!("BEFORE UPDATE ON channels WHEN NEW.id = '{}'", second)).await;
+
+    let (h, v) = auth_header(&token);
+    server
+        .put(&format!("/api/servers/{}/channels/reorder", server_id))
+        .add_header(h, v)
+        .json(&json!({ "items": [
+            { "id": first, "parentId": null, "position": 0 },
+            { "id": second, "parentId": null, "position": 1 },
+        ]}))
+        .await
+        .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+
+    // The first update was rolled back with the second
+    let position: i64 = sqlx::query_scalar("SELECT position FROM channels WHERE id = ?")
+        .bind(&first)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(position, 99);
+}
+
+#[tokio::test]
+async fn message_is_not_saved_when_its_attachments_fail_to_link() {
+    let (base, pool) = start_server().await;
+    let (user_id, token) = common::create_test_user(&pool, "alice@test.com", "alice", "password123").await;
+    let server_id = common::create_test_server(&pool, &user_id, "flux").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "general").await;
+    let attachment = common::create_test_attachment(&pool, &user_id, "a.png", "image/png").await;
+    fail_on(&pool, "BEFORE UPDATE ON attachments").await;
+
+    let mut ws = ws_connect(&base, &token).await;
+    drain_messages(&mut ws).await;
+    send_json(&mut ws, &json!({"type": "join_channel", "channelId": channel_id})).await;
+    send_json(
+        &mut ws,
+        &json!({"type": "send_message", "channelId": channel_id, "content": "look", "attachmentIds": [attachment]}),
+    )
+    .await;
+
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let msgs = drain_messages(&mut ws).await;
+    assert!(msgs.iter().any(|m| m["type"] == "error"));
+    assert!(!msgs.iter().any(|m| m["type"] == "message"));
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM messages").await, 0);
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM messages_fts").await, 0);
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::ws_helpers::{drain_messages, start_server, ws_connect};
+use serde_json::json;
+
+#[tokio::test]
+async fn settings_sync_to_the_users_other_sessions() {
+    let (base, pool) = start_server().await;
+    let (_, token) = common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let client = reqwest::Client::new();
+    let url = format!("{}/api/users/me/settings", base);
+
+    let mut ws = ws_connect(&base, &token).await;
+    drain_messages(&mut ws).await;
+
+    let keybinds = json!([{"action": "push-to-talk", "key": "Mouse3", "label": "Mouse 4"}]);
+    let res = client
+        .put(format!("{}/keybinds", url))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({"value": keybinds}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+
+    let events = drain_messages(&mut ws).await;
+    let update = events.iter().find(|e| e["type"] == "user_setting_update").expect("setting event");
+    assert_eq!(update["key"], "keybinds");
+    assert_eq!(update["value"], keybinds);
+
+    let res = client
+        .put(format!("{}/Bad%20Key", url))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({"value": 1}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 400);
+
+    let settings: Vec<serde_json::Value> = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(settings.len(), 1);
+    assert_eq!(settings[0]["value"], keybinds);
+
+    let res = client
+        .delete(format!("{}/keybinds", url))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 204);
+    let events = drain_messages(&mut ws).await;
+    assert!(events.iter().any(|e| e["type"] == "user_setting_update" && e["value"].is_null()));
+}
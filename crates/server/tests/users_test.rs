@@ -202,3 +202,167 @@ async fn update_no_fields_returns_400() {
     let body: serde_json::Value = res.json();
     assert_eq!(body["error"], "No fields to update");
 }
+
+#[tokio::test]
+async fn update_profile_details() {
+    let (server, pool) = setup().await;
+
+    let (_, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+
+    let (h, v) = auth_header(&token);
+    let res = server
+        .patch("/api/users/me")
+        .add_header(h, v)
+        .json(&json!({
+            "bio": "  Plays bass badly  ",
+            "pronouns": "she/her",
+            "accentColor": "#5865F2",
+            "timezone": "America/Argentina/Buenos_Aires"
+        }))
+        .await;
+
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["bio"], "Plays bass badly");
+    assert_eq!(body["pronouns"], "she/her");
+    assert_eq!(body["accentColor"], "#5865f2");
+    assert_eq!(body["timezone"], "America/Argentina/Buenos_Aires");
+
+    // Null or blank clears a field, and the others are left alone
+    let (h, v) = auth_header(&token);
+    let res = server
+        .patch("/api/users/me")
+        .add_header(h, v)
+        .json(&json!({ "bio": null, "pronouns": "   " }))
+        .await;
+
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert!(body["bio"].is_null());
+    assert!(body["pronouns"].is_null());
+    assert_eq!(body["timezone"], "America/Argentina/Buenos_Aires");
+}
+
+#[tokio::test]
+async fn update_profile_details_invalid() {
+    let (server, pool) = setup().await;
+
+    let (_, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+
+    for body in [
+        json!({ "bio": "x".repeat(191) }),
+        json!({ "pronouns": 42 }),
+        json!({ "accentColor": "blue" }),
+        json!({ "timezone": "Not a timezone" }),
+        // Nothing is written when any field is invalid
+        json!({ "username": "alice2", "timezone": "Mars/Olympus Mons" }),
+    ] {
+        let (h, v) = auth_header(&token);
+        let res = server.patch("/api/users/me").add_header(h, v).json(&body).await;
+        res.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    let (h, v) = auth_header(&token);
+    let body: serde_json::Value = server.get("/api/users/me").add_header(h, v).await.json();
+    assert_eq!(body["username"], "alice");
+}
+
+#[tokio::test]
+async fn profile_shows_details_cosmetics_and_mutual_servers() {
+    let (server, pool) = setup().await;
+
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+
+    let shared = common::create_test_server(&pool, &alice_id, "Band Practice").await;
+    common::add_member(&pool, &bob_id, &shared, "member").await;
+    common::create_test_server(&pool, &alice_id, "Alice Only").await;
+
+    let (h, v) = auth_header(&alice_token);
+    server
+        .patch("/api/users/me")
+        .add_header(h, v)
+        .json(&json!({ "bio": "hi", "ringStyle": "galaxy" }))
+        .await
+        .assert_status_ok();
+
+    let (h, v) = auth_header(&bob_token);
+    let res = server
+        .get(&format!("/api/users/{}/profile", alice_id))
+        .add_header(h, v)
+        .await;
+
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["username"], "alice");
+    assert_eq!(body["bio"], "hi");
+    assert!(body.get("email").is_none());
+    assert_eq!(body["cosmetics"]["ringStyle"], "galaxy");
+    assert_eq!(body["mutualServers"], json!([{ "id": shared, "name": "Band Practice" }]));
+    // Not connected to the gateway
+    assert_eq!(body["status"], "offline");
+    assert!(body["activity"].is_null());
+}
+
+#[tokio::test]
+async fn profile_of_unknown_user_returns_404() {
+    let (server, pool) = setup().await;
+
+    let (_, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+
+    let (h, v) = auth_header(&token);
+    let res = server.get("/api/users/nobody/profile").add_header(h, v).await;
+
+    res.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn username_changes_are_rate_limited_and_old_names_held() {
+    let (server, pool) = setup().await;
+
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (_, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+
+    let rename = |token: &str, username: &str| {
+        let (h, v) = auth_header(token);
+        server
+            .patch("/api/users/me/username")
+            .add_header(h, v)
+            .json(&json!({ "username": username }))
+    };
+
+    let res = rename(&alice_token, "alicia").await;
+    res.assert_status_ok();
+    assert_eq!(res.json::<serde_json::Value>()["username"], "alicia");
+
+    let old: String = sqlx::query_scalar("SELECT old_username FROM username_history WHERE user_id = ?")
+        .bind(&alice_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(old, "alice");
+
+    // Keeping the current name is not a change
+    rename(&alice_token, "alicia").await.assert_status_ok();
+    let res = rename(&alice_token, "ally").await;
+    res.assert_status(StatusCode::TOO_MANY_REQUESTS);
+
+    // The name alice gave up isn't free for someone else yet
+    let res = rename(&bob_token, "alice").await;
+    res.assert_status(StatusCode::CONFLICT);
+
+    // Once the cooldown has passed alice may take it back
+    sqlx::query("UPDATE username_history SET changed_at = ?")
+        .bind((chrono::Utc::now() - chrono::Duration::days(8)).to_rfc3339())
+        .execute(&pool)
+        .await
+        .unwrap();
+    rename(&alice_token, "alice").await.assert_status_ok();
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use serde_json::json;
+
+const SECRET: &str = "secret-that-is-at-least-256-bits-long-for-hmac";
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn setup() -> (TestServer, sqlx::SqlitePool) {
+    let pool = common::setup_test_db().await;
+    let app = common::create_test_app(pool.clone());
+    let server = TestServer::new(app).unwrap();
+    (server, pool)
+}
+
+async fn add_node(server: &TestServer, token: &str, region: &str, url: &str) -> String {
+    let (h, v) = auth_header(token);
+    let res = server
+        .post("/api/admin/voice-nodes")
+        .add_header(h, v)
+        .json(&json!({"region": region, "url": url, "apiKey": "devkey", "apiSecret": SECRET}))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let node: serde_json::Value = res.json();
+    assert!(node.get("apiSecret").is_none());
+    node["id"].as_str().unwrap().to_string()
+}
+
+async fn voice_token(server: &TestServer, token: &str, channel_id: &str) -> serde_json::Value {
+    let (h, v) = auth_header(token);
+    let res = server.post("/api/voice/token").add_header(h, v).json(&json!({"channelId": channel_id})).await;
+    res.assert_status_ok();
+    res.json()
+}
+
+#[tokio::test]
+async fn tokens_follow_latency_and_channel_region() {
+    let (server, pool) = setup().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    sqlx::query(r#"UPDATE "user" SET is_instance_admin = 1 WHERE id = ?"#)
+        .bind(&alice_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    let channel_id = common::create_voice_channel(&pool, &server_id, "Voice").await;
+
+    let eu = add_node(&server, &alice_token, "EU-West", "wss://eu.example.com").await;
+    let us = add_node(&server, &alice_token, "us-east", "wss://us.example.com").await;
+
+    let (h, v) = auth_header(&alice_token);
+    let nodes: Vec<serde_json::Value> = server.get("/api/voice/nodes").add_header(h, v).await.json();
+    assert_eq!(nodes.len(), 2);
+    assert_eq!(nodes[0]["region"], "eu-west");
+    assert!(nodes[0]["rttMs"].is_null());
+
+    let (h, v) = auth_header(&alice_token);
+    server
+        .post("/api/voice/latency")
+        .add_header(h, v)
+        .json(&json!({"results": [{"nodeId": eu, "rttMs": 120}, {"nodeId": us, "rttMs": 30}]}))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    // Closest node when the channel has no region
+    let body = voice_token(&server, &alice_token, &channel_id).await;
+    assert_eq!(body["url"], "wss://us.example.com");
+    assert_eq!(body["nodeId"], us);
+    assert_eq!(body["region"], "us-east");
+
+    let url = format!("/api/servers/{}/channels/{}", server_id, channel_id);
+    let (h, v) = auth_header(&alice_token);
+    server.patch(&url).add_header(h, v).json(&json!({"voiceRegion": "mars"})).await.assert_status(StatusCode::BAD_REQUEST);
+    let (h, v) = auth_header(&alice_token);
+    let res = server.patch(&url).add_header(h, v).json(&json!({"voiceRegion": "eu-west"})).await;
+    res.assert_status_ok();
+    assert_eq!(res.json::<serde_json::Value>()["voiceRegion"], "eu-west");
+
+    let body = voice_token(&server, &alice_token, &channel_id).await;
+    assert_eq!(body["url"], "wss://eu.example.com");
+
+    // A region with no enabled nodes falls back to the rest
+    let (h, v) = auth_header(&alice_token);
+    server
+        .patch(&format!("/api/admin/voice-nodes/{}", eu))
+        .add_header(h, v)
+        .json(&json!({"enabled": false}))
+        .await
+        .assert_status_ok();
+    let body = voice_token(&server, &alice_token, &channel_id).await;
+    assert_eq!(body["url"], "wss://us.example.com");
+
+    // Removing the region's last node unpins the channel
+    let (h, v) = auth_header(&alice_token);
+    server
+        .delete(&format!("/api/admin/voice-nodes/{}", eu))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let region: Option<String> = sqlx::query_scalar("SELECT voice_region FROM channels WHERE id = ?")
+        .bind(&channel_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(region.is_none());
+}
+
+#[tokio::test]
+async fn only_instance_admins_manage_nodes() {
+    let (server, pool) = setup().await;
+    let (_, token) = common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+
+    let (h, v) = auth_header(&token);
+    server
+        .post("/api/admin/voice-nodes")
+        .add_header(h, v)
+        .json(&json!({"region": "eu", "url": "wss://eu.example.com"}))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let (h, v) = auth_header(&token);
+    server
+        .post("/api/voice/latency")
+        .add_header(h, v)
+        .json(&json!({"results": [{"nodeId": "nope", "rttMs": 10}]}))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use serde_json::json;
+
+async fn get_quality(base: &str, token: &str, server_id: &str) -> reqwest::Response {
+    reqwest::Client::new()
+        .get(format!("{}/api/servers/{}/voice/quality", base, server_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn samples_aggregate_per_channel_and_flag_degraded_calls() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let lounge = common::create_voice_channel(&pool, &server_id, "Lounge").await;
+    let gaming = common::create_voice_channel(&pool, &server_id, "Gaming").await;
+
+    let mut alice_ws = ws_connect(&base, &alice_token).await;
+    let mut bob_ws = ws_connect(&base, &bob_token).await;
+    drain_messages(&mut alice_ws).await;
+    drain_messages(&mut bob_ws).await;
+
+    // Ignored: not in a call yet
+    send_json(&mut alice_ws, &json!({"type": "voice_stats", "rtt": 40.0, "jitter": 2.0, "packetLoss": 0.0})).await;
+
+    send_json(&mut alice_ws, &json!({"type": "voice_state_update", "channelId": lounge, "action": "join"})).await;
+    send_json(&mut bob_ws, &json!({"type": "voice_state_update", "channelId": gaming, "action": "join"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+    send_json(&mut alice_ws, &json!({"type": "voice_stats", "rtt": 40.0, "jitter": 2.0, "packetLoss": 0.0})).await;
+    send_json(&mut alice_ws, &json!({"type": "voice_stats", "rtt": 60.0, "jitter": 4.0, "packetLoss": 1.0})).await;
+    send_json(&mut bob_ws, &json!({"type": "voice_stats", "rtt": 450.0, "jitter": 80.0, "packetLoss": 12.0})).await;
+    // Out of range samples are dropped
+    send_json(&mut bob_ws, &json!({"type": "voice_stats", "rtt": -1.0, "jitter": 0.0, "packetLoss": 250.0})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    // Server admins only
+    assert_eq!(get_quality(&base, &bob_token, &server_id).await.status(), 403);
+
+    let res = get_quality(&base, &alice_token, &server_id).await;
+    assert_eq!(res.status(), 200);
+    let body: serde_json::Value = res.json().await.unwrap();
+    let channels = body["channels"].as_array().unwrap();
+    assert_eq!(channels.len(), 2);
+
+    // Worst channel first
+    assert_eq!(channels[0]["channelId"], gaming);
+    assert_eq!(channels[0]["samples"], 1);
+    assert_eq!(channels[0]["degraded"], true);
+    assert_eq!(channels[0]["avgPacketLoss"], 12.0);
+
+    assert_eq!(channels[1]["channelId"], lounge);
+    assert_eq!(channels[1]["channelName"], "Lounge");
+    assert_eq!(channels[1]["samples"], 2);
+    assert_eq!(channels[1]["users"], 1);
+    assert_eq!(channels[1]["avgRttMs"], 50.0);
+    assert_eq!(channels[1]["p95RttMs"], 60.0);
+    assert_eq!(channels[1]["degraded"], false);
+}
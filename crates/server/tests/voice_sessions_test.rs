@@ -0,0 +1,100 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use serde_json::json;
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn get_json(base: &str, token: &str, path: &str) -> (u16, serde_json::Value) {
+    let res = reqwest::Client::new()
+        .get(format!("{}{}", base, path))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .unwrap();
+    let status = res.status().as_u16();
+    (status, res.json().await.unwrap_or_default())
+}
+
+#[tokio::test]
+async fn voice_joins_and_leaves_are_recorded() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let lounge = common::create_voice_channel(&pool, &server_id, "lounge").await;
+    let gaming = common::create_voice_channel(&pool, &server_id, "gaming").await;
+
+    let mut ws = ws_connect(&base, &alice_token).await;
+    drain_messages(&mut ws).await;
+
+    // Moving channels ends the first session; rejoining the same one doesn't
+    for channel_id in [&lounge, &lounge, &gaming] {
+        send_json(&mut ws, &json!({"type": "voice_state_update", "channelId": channel_id, "action": "join"})).await;
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+    let (_, history) = get_json(&base, &alice_token, "/api/users/me/voice-history").await;
+    let items = history["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["channelName"], "gaming");
+    assert!(items[0]["leftAt"].is_null());
+    assert_eq!(items[1]["channelName"], "lounge");
+    assert!(items[1]["leftAt"].is_string());
+    assert!(items[1]["durationSecs"].is_number());
+
+    // Disconnecting ends the open session
+    drop(ws);
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let (_, history) = get_json(&base, &alice_token, "/api/users/me/voice-history").await;
+    assert!(history["items"][0]["leftAt"].is_string());
+
+    let (status, stats) = get_json(&base, &alice_token, &format!("/api/servers/{}/voice/stats?days=7", server_id)).await;
+    assert_eq!(status, 200);
+    assert_eq!(stats["sessions"], 2);
+    assert_eq!(stats["uniqueUsers"], 1);
+    assert_eq!(stats["topUsers"][0]["username"], "alice");
+    assert_eq!(stats["topChannels"].as_array().unwrap().len(), 2);
+    assert_eq!(stats["perDay"].as_array().unwrap().len(), 1);
+
+    // Stats are for server admins, history is per user
+    let (status, _) = get_json(&base, &bob_token, &format!("/api/servers/{}/voice/stats", server_id)).await;
+    assert_eq!(status, 403);
+    let (_, history) = get_json(&base, &bob_token, "/api/users/me/voice-history").await;
+    assert!(history["items"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn sessions_open_at_startup_end_when_last_seen() {
+    let pool = common::setup_test_db().await;
+    let state = common::create_test_state(pool.clone(), common::test_config());
+    let server = TestServer::new(flux_server::routes::build_router(state.clone())).unwrap();
+    let (user_id, token) = common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    sqlx::query(
+        r#"INSERT INTO voice_sessions (id, user_id, channel_id, channel_name, joined_at, last_seen_at)
+           VALUES ('s1', ?, 'gone', 'old room', '2026-01-01T10:00:00Z', '2026-01-01T10:05:00Z')"#,
+    )
+    .bind(&user_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    assert_eq!(flux_server::routes::voice::close_orphaned_voice_sessions(&state).await, 1);
+    assert_eq!(flux_server::routes::voice::close_orphaned_voice_sessions(&state).await, 0);
+
+    let (h, v) = auth_header(&token);
+    let res = server.get("/api/users/me/voice-history").add_header(h, v).await;
+    res.assert_status(StatusCode::OK);
+    let history: serde_json::Value = res.json();
+    assert_eq!(history["items"][0]["leftAt"], "2026-01-01T10:05:00Z");
+    assert_eq!(history["items"][0]["durationSecs"], 300);
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use serde_json::json;
+
+type Ws = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+async fn join_voice(ws: &mut Ws, vc_id: &str) {
+    send_json(ws, &json!({"type": "voice_state_update", "channelId": vc_id, "action": "join"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+async fn settle() {
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+}
+
+#[tokio::test]
+async fn video_session_syncs_with_server_timestamps() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let vc_id = common::create_voice_channel(&pool, &server_id, "voice-chat").await;
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(format!("{}/api/spotify/sessions", base))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"voiceChannelId": vc_id, "sessionType": "theatre"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 400);
+
+    let res = client
+        .post(format!("{}/api/spotify/sessions", base))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"voiceChannelId": vc_id, "sessionType": "video"}))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = res.json().await.unwrap();
+    let session_id = body["sessionId"].as_str().unwrap().to_string();
+
+    let res = client
+        .get(format!("{}/api/spotify/sessions/channel/{}", base, vc_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["session"]["sessionType"], "video");
+
+    let mut ws_alice = ws_connect(&base, &alice_token).await;
+    let mut ws_bob = ws_connect(&base, &bob_token).await;
+    join_voice(&mut ws_alice, &vc_id).await;
+    join_voice(&mut ws_bob, &vc_id).await;
+    drain_messages(&mut ws_alice).await;
+    drain_messages(&mut ws_bob).await;
+
+    let before = chrono::Utc::now().timestamp_millis();
+    send_json(
+        &mut ws_alice,
+        &json!({"type": "spotify_playback_control", "sessionId": session_id, "action": "sync", "positionMs": 42_000, "source": "youtube"}),
+    )
+    .await;
+    settle().await;
+
+    let msgs = drain_messages(&mut ws_bob).await;
+    let sync = msgs
+        .iter()
+        .find(|m| m["type"] == "spotify_playback_sync")
+        .expect("bob should get the host heartbeat");
+    assert_eq!(sync["action"], "sync");
+    assert_eq!(sync["positionMs"], 42_000);
+    let server_time = sync["serverTimeMs"].as_i64().unwrap();
+    assert!(server_time >= before && server_time <= chrono::Utc::now().timestamp_millis());
+
+    let position: i64 =
+        sqlx::query_scalar(r#"SELECT current_track_position_ms FROM "listening_sessions" WHERE id = ?"#)
+            .bind(&session_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(position, 42_000);
+}
+
+#[tokio::test]
+async fn buffering_reports_reach_the_channel() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let (carol_id, carol_token) =
+        common::create_test_user(&pool, "carol@test.com", "carol", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    common::add_member(&pool, &carol_id, &server_id, "member").await;
+    let vc_id = common::create_voice_channel(&pool, &server_id, "voice-chat").await;
+
+    let res = reqwest::Client::new()
+        .post(format!("{}/api/spotify/sessions", base))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"voiceChannelId": vc_id, "sessionType": "video"}))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = res.json().await.unwrap();
+    let session_id = body["sessionId"].as_str().unwrap().to_string();
+
+    let mut ws_alice = ws_connect(&base, &alice_token).await;
+    let mut ws_bob = ws_connect(&base, &bob_token).await;
+    let mut ws_carol = ws_connect(&base, &carol_token).await;
+    join_voice(&mut ws_alice, &vc_id).await;
+    join_voice(&mut ws_bob, &vc_id).await;
+    drain_messages(&mut ws_alice).await;
+    drain_messages(&mut ws_bob).await;
+
+    // Not in the voice channel: ignored
+    send_json(&mut ws_carol, &json!({"type": "media_buffering_update", "sessionId": session_id, "buffering": true})).await;
+    settle().await;
+    let msgs = drain_messages(&mut ws_alice).await;
+    assert!(!msgs.iter().any(|m| m["type"] == "media_buffering"));
+
+    send_json(
+        &mut ws_bob,
+        &json!({"type": "media_buffering_update", "sessionId": session_id, "buffering": true, "positionMs": 1000}),
+    )
+    .await;
+    settle().await;
+    let msgs = drain_messages(&mut ws_alice).await;
+    let event = msgs.iter().find(|m| m["type"] == "media_buffering").unwrap();
+    assert_eq!(event["userId"], bob_id.as_str());
+    assert_eq!(event["buffering"], true);
+    assert_eq!(event["bufferingUserIds"], json!([bob_id]));
+
+    send_json(&mut ws_bob, &json!({"type": "media_buffering_update", "sessionId": session_id, "buffering": false})).await;
+    settle().await;
+    let msgs = drain_messages(&mut ws_alice).await;
+    let event = msgs.iter().find(|m| m["type"] == "media_buffering").unwrap();
+    assert_eq!(event["bufferingUserIds"], json!([]));
+}
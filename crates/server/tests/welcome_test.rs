@@ -0,0 +1,117 @@
+mod common;
+
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect};
+use serde_json::json;
+
+async fn message_count(pool: &sqlx::SqlitePool, channel_id: &str) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM messages WHERE channel_id = ?")
+        .bind(channel_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn members_must_accept_rules_before_posting() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+    let general_id = common::create_text_channel(&pool, &server_id, "general").await;
+    let intro_id = common::create_text_channel(&pool, &server_id, "introductions").await;
+
+    let client = reqwest::Client::new();
+    let url = format!("{}/api/servers/{}/welcome", base, server_id);
+
+    // Nothing configured yet
+    let res = client.get(&url).header("Authorization", format!("Bearer {}", bob_token)).send().await.unwrap();
+    assert_eq!(res.status(), 200);
+    let welcome: serde_json::Value = res.json().await.unwrap();
+    assert!(welcome["rules"].is_null());
+    assert_eq!(welcome["requiresAcceptance"], false);
+
+    let res = client
+        .put(&url)
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .json(&json!({"message": "Hi!", "rules": "Be nice"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 403);
+
+    let res = client
+        .put(&url)
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"message": "Hi!", "rules": "  Be nice  ", "suggestedChannelIds": [intro_id, general_id, intro_id]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    let welcome: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(welcome["rules"], "Be nice");
+    let suggested = welcome["suggestedChannels"].as_array().unwrap();
+    assert_eq!(suggested.len(), 2);
+    assert_eq!(suggested[0]["id"], intro_id);
+    assert_eq!(suggested[0]["type"], "text");
+    // Admins aren't gated
+    assert_eq!(welcome["requiresAcceptance"], false);
+
+    let res = client.get(&url).header("Authorization", format!("Bearer {}", bob_token)).send().await.unwrap();
+    let welcome: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(welcome["message"], "Hi!");
+    assert_eq!(welcome["requiresAcceptance"], true);
+
+    let mut ws = ws_connect(&base, &bob_token).await;
+    drain_messages(&mut ws).await;
+    send_json(&mut ws, &json!({"type": "send_message", "channelId": general_id, "content": "hello"})).await;
+    let events = drain_messages(&mut ws).await;
+    assert!(events.iter().any(|e| e["type"] == "error"));
+    assert_eq!(message_count(&pool, &general_id).await, 0);
+
+    let res = client
+        .post(format!("{}/accept", url))
+        .header("Authorization", format!("Bearer {}", bob_token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+
+    send_json(&mut ws, &json!({"type": "send_message", "channelId": general_id, "content": "hello"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    assert_eq!(message_count(&pool, &general_id).await, 1);
+
+    // Editing only the message keeps the acceptance; changing the rules resets it
+    let res = client
+        .put(&url)
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"message": "Welcome!", "rules": "Be nice"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    let res = client.get(&url).header("Authorization", format!("Bearer {}", bob_token)).send().await.unwrap();
+    let welcome: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(welcome["requiresAcceptance"], false);
+    assert!(welcome["suggestedChannels"].as_array().unwrap().is_empty());
+
+    client
+        .put(&url)
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"rules": "Be nice. No spam."}))
+        .send()
+        .await
+        .unwrap();
+    let res = client.get(&url).header("Authorization", format!("Bearer {}", bob_token)).send().await.unwrap();
+    let welcome: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(welcome["requiresAcceptance"], true);
+
+    // Removing the welcome screen lifts the gate
+    let res = client.delete(&url).header("Authorization", format!("Bearer {}", alice_token)).send().await.unwrap();
+    assert_eq!(res.status(), 204);
+    send_json(&mut ws, &json!({"type": "send_message", "channelId": general_id, "content": "again"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    assert_eq!(message_count(&pool, &general_id).await, 2);
+}
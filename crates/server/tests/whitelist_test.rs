@@ -30,8 +30,8 @@ async fn list_whitelist_as_admin() {
     let res = server.get("/api/whitelist").add_header(h, v).await;
 
     res.assert_status_ok();
-    let body: Vec<serde_json::Value> = res.json();
-    assert!(body.is_empty());
+    let body: serde_json::Value = res.json();
+    assert!(body["items"].as_array().unwrap().is_empty());
 }
 
 #[tokio::test]
@@ -143,8 +143,8 @@ async fn remove_from_whitelist() {
     let res = server.get("/api/whitelist").add_header(h, v).await;
 
     res.assert_status_ok();
-    let body: Vec<serde_json::Value> = res.json();
-    assert!(body.is_empty());
+    let body: serde_json::Value = res.json();
+    assert!(body["items"].as_array().unwrap().is_empty());
 }
 
 #[tokio::test]
@@ -183,3 +183,164 @@ async fn remove_forbidden_for_member() {
     let body: serde_json::Value = res.json();
     assert_eq!(body["error"], "Insufficient permissions");
 }
+
+async fn sign_up(server: &TestServer, email: &str, username: &str, invite: Option<&str>) -> axum_test::TestResponse {
+    server
+        .post("/api/auth/sign-up/email")
+        .json(&json!({
+            "email": email,
+            "password": "Tangerine-Lighthouse-42",
+            "name": username,
+            "username": username,
+            "inviteToken": invite,
+        }))
+        .await
+}
+
+#[tokio::test]
+async fn domain_wildcard_lets_in_the_whole_domain() {
+    let (server, pool) = setup().await;
+    let (owner_id, token) = common::create_test_user(&pool, "owner@test.com", "owner", "pass123").await;
+    common::create_test_server(&pool, &owner_id, "TestServer").await;
+
+    let (h, v) = auth_header(&token);
+    server
+        .post("/api/whitelist")
+        .add_header(h, v)
+        .json(&json!({ "emails": ["*@Company.com"] }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    for bad in ["*@*.com", "a*@company.com", "nobody", "*@localhost"] {
+        let (h, v) = auth_header(&token);
+        server
+            .post("/api/whitelist")
+            .add_header(h, v)
+            .json(&json!({ "emails": [bad] }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    sign_up(&server, "dana@company.com", "dana", None).await.assert_status_ok();
+    sign_up(&server, "eve@company.com.evil.io", "eve", None).await.assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn invite_tokens_bypass_the_whitelist_once() {
+    let (server, pool) = setup().await;
+    let (owner_id, token) = common::create_test_user(&pool, "owner@test.com", "owner", "pass123").await;
+    common::create_test_server(&pool, &owner_id, "TestServer").await;
+
+    let (h, v) = auth_header(&token);
+    let res = server
+        .post("/api/whitelist/invites")
+        .add_header(h, v)
+        .json(&json!({ "note": "for dana" }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let invite: serde_json::Value = res.json();
+    let invite_token = invite["token"].as_str().unwrap();
+
+    sign_up(&server, "dana@test.com", "dana", Some("not-a-real-token")).await.assert_status(StatusCode::FORBIDDEN);
+    sign_up(&server, "dana@test.com", "dana", Some(invite_token)).await.assert_status_ok();
+    sign_up(&server, "eve@test.com", "eve", Some(invite_token)).await.assert_status(StatusCode::FORBIDDEN);
+
+    // The list shows who used it, but never the token
+    let (h, v) = auth_header(&token);
+    let invites: serde_json::Value = server.get("/api/whitelist/invites").add_header(h, v).await.json();
+    assert_eq!(invites[0]["note"], "for dana");
+    assert!(invites[0]["usedBy"].is_string());
+    assert!(invites[0].get("token").is_none());
+
+    // Revoked invites can't be redeemed
+    let (h, v) = auth_header(&token);
+    let invite: serde_json::Value = server.post("/api/whitelist/invites").add_header(h, v).json(&json!({})).await.json();
+    let (h, v) = auth_header(&token);
+    server
+        .delete(&format!("/api/whitelist/invites/{}", invite["id"].as_str().unwrap()))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    sign_up(&server, "eve@test.com", "eve", invite["token"].as_str()).await.assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn access_requests_queue_for_approval() {
+    let (server, pool) = setup().await;
+    let (owner_id, token) = common::create_test_user(&pool, "owner@test.com", "owner", "pass123").await;
+    common::create_test_server(&pool, &owner_id, "TestServer").await;
+
+    for email in ["Dana@test.com", "dana@test.com", "eve@test.com", "owner@test.com"] {
+        server
+            .post("/api/auth/request-access")
+            .json(&json!({ "email": email, "reason": "friend of owner" }))
+            .await
+            .assert_status(StatusCode::ACCEPTED);
+    }
+    server
+        .post("/api/auth/request-access")
+        .json(&json!({ "email": "*@test.com" }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    // Duplicates and existing accounts aren't queued
+    let (h, v) = auth_header(&token);
+    let pending: Vec<serde_json::Value> = server.get("/api/whitelist/requests").add_header(h, v).await.json();
+    let emails: Vec<&str> = pending.iter().map(|r| r["email"].as_str().unwrap()).collect();
+    assert_eq!(emails, ["dana@test.com", "eve@test.com"]);
+    assert_eq!(pending[0]["reason"], "friend of owner");
+
+    let (h, v) = auth_header(&token);
+    server
+        .post(&format!("/api/whitelist/requests/{}/approve", pending[0]["id"].as_str().unwrap()))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let (h, v) = auth_header(&token);
+    server
+        .post(&format!("/api/whitelist/requests/{}/deny", pending[1]["id"].as_str().unwrap()))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let (h, v) = auth_header(&token);
+    server
+        .post(&format!("/api/whitelist/requests/{}/deny", pending[1]["id"].as_str().unwrap()))
+        .add_header(h, v)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    sign_up(&server, "dana@test.com", "dana", None).await.assert_status_ok();
+    sign_up(&server, "eve@test.com", "eve", None).await.assert_status(StatusCode::FORBIDDEN);
+
+    // A denied email can't queue itself again
+    server
+        .post("/api/auth/request-access")
+        .json(&json!({ "email": "eve@test.com" }))
+        .await
+        .assert_status(StatusCode::ACCEPTED);
+    let (h, v) = auth_header(&token);
+    let pending: Vec<serde_json::Value> = server.get("/api/whitelist/requests").add_header(h, v).await.json();
+    assert!(pending.is_empty());
+}
+
+#[tokio::test]
+async fn admins_hear_about_access_requests() {
+    let (base, pool) = common::ws_helpers::start_server().await;
+    let (owner_id, token) = common::create_test_user(&pool, "owner@test.com", "owner", "pass123").await;
+    common::create_test_server(&pool, &owner_id, "TestServer").await;
+    let mut ws = common::ws_helpers::ws_connect(&base, &token).await;
+    common::ws_helpers::drain_messages(&mut ws).await;
+
+    let res = reqwest::Client::new()
+        .post(format!("{}/api/auth/request-access", base))
+        .json(&json!({ "email": "dana@test.com" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::ACCEPTED);
+
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    let events = common::ws_helpers::drain_messages(&mut ws).await;
+    let event = events.iter().find(|m| m["type"] == "access_requested").unwrap();
+    assert_eq!(event["request"]["email"], "dana@test.com");
+}
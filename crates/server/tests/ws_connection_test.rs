@@ -140,3 +140,47 @@ async fn send_message_broadcasts_to_channel_subscribers() {
     let has_msg = msgs.iter().any(|m| m["type"] == "message");
     assert!(has_msg, "Bob should receive the message");
 }
+
+#[tokio::test]
+async fn revoked_session_is_disconnected() {
+    let (base, pool) = start_server().await;
+    let (_, token) = common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+
+    let client = reqwest::Client::new();
+    let other: serde_json::Value = client
+        .post(format!("{}/api/auth/sign-in/email", base))
+        .json(&json!({"email": "alice@test.com", "password": "pass123"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let other_token = other["token"].as_str().unwrap();
+
+    let mut kept = ws_connect(&base, &token).await;
+    let mut revoked = ws_connect(&base, other_token).await;
+    drain_messages(&mut kept).await;
+    drain_messages(&mut revoked).await;
+
+    let res = client
+        .post(format!("{}/api/auth/sessions/revoke-others", base))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+
+    let msgs = drain_messages(&mut revoked).await;
+    assert!(msgs.iter().any(|m| m["type"] == "session_revoked"));
+    let next = tokio::time::timeout(std::time::Duration::from_secs(2), revoked.next()).await;
+    assert!(
+        matches!(next, Ok(None) | Ok(Some(Ok(Message::Close(_)))) | Ok(Some(Err(_)))),
+        "revoked socket should close, got {:?}",
+        next
+    );
+
+    // The other session's socket stays up
+    send_json(&mut kept, &json!({"type": "update_status", "status": "idle"})).await;
+    assert!(drain_messages(&mut kept).await.iter().all(|m| m["type"] != "session_revoked"));
+}
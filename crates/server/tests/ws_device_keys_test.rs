@@ -0,0 +1,108 @@
+mod common;
+
+use common::ws_helpers::{drain_messages, send_json, start_server, ws_connect, ws_connect_device};
+use serde_json::json;
+
+#[tokio::test]
+async fn shared_key_goes_only_to_the_target_device() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &alice_id, "TestServer").await;
+    common::add_member(&pool, &bob_id, &server_id, "member").await;
+
+    let mut alice = ws_connect(&base, &alice_token).await;
+    let mut bob_laptop = ws_connect_device(&base, &bob_token, "laptop").await;
+    let mut bob_phone = ws_connect_device(&base, &bob_token, "phone").await;
+    drain_messages(&mut alice).await;
+    drain_messages(&mut bob_laptop).await;
+    drain_messages(&mut bob_phone).await;
+
+    // The phone asks for the key; members learn which device to wrap it for
+    send_json(&mut bob_phone, &json!({"type": "request_server_key", "serverId": server_id})).await;
+    let requests: Vec<_> = drain_messages(&mut alice)
+        .await
+        .into_iter()
+        .filter(|m| m["type"] == "server_key_requested")
+        .collect();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0]["deviceId"], "phone");
+
+    send_json(
+        &mut alice,
+        &json!({"type": "share_server_key", "serverId": server_id, "userId": bob_id, "encryptedKey": "for-phone", "deviceId": "phone"}),
+    )
+    .await;
+
+    let phone_msgs = drain_messages(&mut bob_phone).await;
+    let shared = phone_msgs.iter().find(|m| m["type"] == "server_key_shared").unwrap();
+    assert_eq!(shared["encryptedKey"], "for-phone");
+    assert_eq!(shared["deviceId"], "phone");
+
+    let laptop_msgs = drain_messages(&mut bob_laptop).await;
+    assert!(!laptop_msgs.iter().any(|m| m["type"] == "server_key_shared"));
+
+    let stored: String = sqlx::query_scalar(
+        "SELECT encrypted_key FROM server_keys WHERE server_id = ? AND user_id = ? AND device_id = 'phone'",
+    )
+    .bind(&server_id)
+    .bind(&bob_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(stored, "for-phone");
+}
+
+#[tokio::test]
+async fn key_rotation_warns_verifiers() {
+    let (base, pool) = start_server().await;
+    let (alice_id, alice_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (bob_id, bob_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let client = reqwest::Client::new();
+
+    let set_key = |token: &str, key: &str| {
+        client
+            .put(format!("{}/api/users/me/public-key", base))
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&json!({"publicKey": key}))
+            .send()
+    };
+    set_key(&alice_token, "YWxpY2Uta2V5").await.unwrap();
+    set_key(&bob_token, "Ym9iLWtleQ==").await.unwrap();
+
+    let number: serde_json::Value = client
+        .get(format!("{}/api/users/{}/safety-number", base, bob_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let res = client
+        .put(format!("{}/api/users/{}/verification", base, bob_id))
+        .header("Authorization", format!("Bearer {}", alice_token))
+        .json(&json!({"safetyNumber": number["safetyNumber"]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+
+    let mut alice = ws_connect(&base, &alice_token).await;
+    drain_messages(&mut alice).await;
+
+    set_key(&bob_token, "Ym9iLW5ldy1rZXk=").await.unwrap();
+    let changed: Vec<_> = drain_messages(&mut alice)
+        .await
+        .into_iter()
+        .filter(|m| m["type"] == "key_changed")
+        .collect();
+    assert_eq!(changed.len(), 1);
+    assert_eq!(changed[0]["userId"], bob_id.as_str());
+    assert_eq!(changed[0]["wasVerified"], true);
+    assert_ne!(changed[0]["userId"], alice_id.as_str());
+}
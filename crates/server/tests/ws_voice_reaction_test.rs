@@ -191,3 +191,117 @@ async fn duplicate_reaction_ignored() {
     .unwrap();
     assert_eq!(count, 1, "Duplicate reaction should be ignored");
 }
+
+#[tokio::test]
+async fn custom_emoji_reaction_includes_emoji_info() {
+    let (base, pool) = start_server().await;
+    let (user_id, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let server_id = common::create_test_server(&pool, &user_id, "TestServer").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "test-channel").await;
+    let attachment_id = common::create_test_attachment(&pool, &user_id, "party.png", "image/png").await;
+
+    let emoji_id = uuid::Uuid::new_v4().to_string();
+    let msg_id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query("INSERT INTO custom_emojis (id, server_id, name, attachment_id, filename, uploader_id, created_at) VALUES (?, ?, 'party', ?, 'party.png', ?, ?)")
+        .bind(&emoji_id).bind(&server_id).bind(&attachment_id).bind(&user_id).bind(&now)
+        .execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO messages (id, channel_id, sender_id, content, created_at) VALUES (?, ?, ?, 'hello', ?)")
+        .bind(&msg_id).bind(&channel_id).bind(&user_id).bind(&now)
+        .execute(&pool).await.unwrap();
+
+    let mut ws = ws_connect(&base, &token).await;
+    drain_messages(&mut ws).await;
+    send_json(&mut ws, &json!({"type": "join_channel", "channelId": channel_id})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let emoji = format!(":party:{emoji_id}");
+    send_json(&mut ws, &json!({"type": "add_reaction", "messageId": msg_id, "emoji": emoji})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let msgs = drain_messages(&mut ws).await;
+    let reaction = msgs.iter().find(|m| m["type"] == "reaction_add").expect("reaction_add");
+    assert_eq!(reaction["customEmoji"]["id"], emoji_id);
+    assert_eq!(reaction["customEmoji"]["attachmentId"], attachment_id);
+    assert_eq!(reaction["customEmoji"]["filename"], "party.png");
+}
+
+#[tokio::test]
+async fn unknown_custom_emoji_reaction_rejected() {
+    let (base, pool) = start_server().await;
+    let (user_id, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (other_id, _) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &user_id, "TestServer").await;
+    let other_server = common::create_test_server(&pool, &other_id, "OtherServer").await;
+    let channel_id = common::create_text_channel(&pool, &server_id, "test-channel").await;
+    let attachment_id = common::create_test_attachment(&pool, &other_id, "secret.png", "image/png").await;
+
+    // Emoji in a server Alice isn't a member of
+    let emoji_id = uuid::Uuid::new_v4().to_string();
+    let msg_id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query("INSERT INTO custom_emojis (id, server_id, name, attachment_id, filename, uploader_id, created_at) VALUES (?, ?, 'secret', ?, 'secret.png', ?, ?)")
+        .bind(&emoji_id).bind(&other_server).bind(&attachment_id).bind(&other_id).bind(&now)
+        .execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO messages (id, channel_id, sender_id, content, created_at) VALUES (?, ?, ?, 'hello', ?)")
+        .bind(&msg_id).bind(&channel_id).bind(&user_id).bind(&now)
+        .execute(&pool).await.unwrap();
+
+    let mut ws = ws_connect(&base, &token).await;
+    drain_messages(&mut ws).await;
+
+    for emoji in [format!(":secret:{emoji_id}"), ":party:does-not-exist".to_string()] {
+        send_json(&mut ws, &json!({"type": "add_reaction", "messageId": msg_id, "emoji": emoji})).await;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let msgs = drain_messages(&mut ws).await;
+    let errors = msgs.iter().filter(|m| m["type"] == "error").count();
+    assert_eq!(errors, 2);
+
+    let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM reactions WHERE message_id = ?")
+        .bind(&msg_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn speaking_update_relayed_to_voice_channel() {
+    let (base, pool) = start_server().await;
+    let (user1_id, token1) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (user2_id, token2) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &user1_id, "TestServer").await;
+    common::add_member(&pool, &user2_id, &server_id, "member").await;
+    let vc_id = common::create_voice_channel(&pool, &server_id, "voice-chat").await;
+
+    let mut ws1 = ws_connect(&base, &token1).await;
+    let mut ws2 = ws_connect(&base, &token2).await;
+    drain_messages(&mut ws1).await;
+    drain_messages(&mut ws2).await;
+
+    // Bob isn't in voice yet, so he shouldn't hear about it
+    send_json(&mut ws1, &json!({"type": "voice_state_update", "channelId": vc_id, "action": "join"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    send_json(&mut ws1, &json!({"type": "speaking_update", "channelId": vc_id, "speaking": true})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let msgs = drain_messages(&mut ws2).await;
+    assert!(!msgs.iter().any(|m| m["type"] == "speaking"));
+
+    send_json(&mut ws2, &json!({"type": "voice_state_update", "channelId": vc_id, "action": "join"})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    drain_messages(&mut ws2).await;
+    send_json(&mut ws1, &json!({"type": "speaking_update", "channelId": vc_id, "speaking": false})).await;
+
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let msgs = drain_messages(&mut ws2).await;
+    let speaking = msgs.iter().find(|m| m["type"] == "speaking").expect("speaking event");
+    assert_eq!(speaking["userId"], user1_id);
+    assert_eq!(speaking["speaking"], false);
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+
+const CACHE_DIR: &str = "/tmp/flux-test-youtube-cache";
+
+fn auth_header(token: &str) -> (HeaderName, HeaderValue) {
+    (
+        HeaderName::from_static("authorization"),
+        format!("Bearer {}", token).parse().unwrap(),
+    )
+}
+
+async fn setup() -> (TestServer, sqlx::SqlitePool) {
+    let pool = common::setup_test_db().await;
+    let app = common::create_test_app(pool.clone());
+    let server = TestServer::new(app).unwrap();
+    std::fs::create_dir_all(CACHE_DIR).ok();
+    (server, pool)
+}
+
+#[tokio::test]
+async fn cached_audio_served_from_disk_with_ranges_then_purged() {
+    let (server, pool) = setup().await;
+    let (user_id, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    common::create_test_server(&pool, &user_id, "TestServer").await;
+
+    let data: Vec<u8> = (0..100u8).collect();
+    std::fs::write(format!("{}/cachedVid01.webm", CACHE_DIR), &data).unwrap();
+
+    let (h, v) = auth_header(&token);
+    let res = server.get("/api/youtube/audio/cachedVid01").add_header(h, v).await;
+    res.assert_status_ok();
+    assert_eq!(res.as_bytes().as_ref(), data.as_slice());
+
+    let (h, v) = auth_header(&token);
+    let res = server
+        .get("/api/youtube/audio/cachedVid01")
+        .add_header(h, v)
+        .add_header(HeaderName::from_static("range"), HeaderValue::from_static("bytes=10-19"))
+        .await;
+    res.assert_status(StatusCode::PARTIAL_CONTENT);
+    assert_eq!(res.header("content-range"), "bytes 10-19/100");
+    assert_eq!(res.as_bytes().as_ref(), &data[10..20]);
+
+    let (h, v) = auth_header(&token);
+    let res = server
+        .get("/api/youtube/audio/cachedVid01")
+        .add_header(h, v)
+        .add_header(HeaderName::from_static("range"), HeaderValue::from_static("bytes=-5"))
+        .await;
+    res.assert_status(StatusCode::PARTIAL_CONTENT);
+    assert_eq!(res.as_bytes().as_ref(), &data[95..]);
+
+    let (h, v) = auth_header(&token);
+    let res = server
+        .get("/api/youtube/audio/cachedVid01")
+        .add_header(h, v)
+        .add_header(HeaderName::from_static("range"), HeaderValue::from_static("bytes=200-"))
+        .await;
+    res.assert_status(StatusCode::RANGE_NOT_SATISFIABLE);
+
+    let (h, v) = auth_header(&token);
+    let res = server.get("/api/youtube/cache").add_header(h, v).await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert!(body["files"].as_u64().unwrap() >= 1);
+    assert_eq!(body["maxBytes"], 10_485_760);
+
+    let (h, v) = auth_header(&token);
+    let res = server.delete("/api/youtube/cache").add_header(h, v).await;
+    res.assert_status_ok();
+    assert!(!std::path::Path::new(&format!("{}/cachedVid01.webm", CACHE_DIR)).exists());
+}
+
+#[tokio::test]
+async fn cache_admin_endpoints_require_admin() {
+    let (server, pool) = setup().await;
+    let (owner_id, _owner_token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+    let (member_id, member_token) =
+        common::create_test_user(&pool, "bob@test.com", "bob", "pass123").await;
+    let server_id = common::create_test_server(&pool, &owner_id, "TestServer").await;
+    common::add_member(&pool, &member_id, &server_id, "member").await;
+
+    let (h, v) = auth_header(&member_token);
+    let res = server.get("/api/youtube/cache").add_header(h, v).await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let (h, v) = auth_header(&member_token);
+    let res = server.delete("/api/youtube/cache").add_header(h, v).await;
+    res.assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn prefetch_rejects_invalid_video_id() {
+    let (server, pool) = setup().await;
+    let (_user_id, token) =
+        common::create_test_user(&pool, "alice@test.com", "alice", "pass123").await;
+
+    let (h, v) = auth_header(&token);
+    let res = server.post("/api/youtube/prefetch/bad%20id!").add_header(h, v).await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,183 @@
+//! Translations for server-generated text.
+//!
+//! Error messages are written in English where they're raised; the catalog
+//! maps those English strings to other languages, so translating one is a
+//! matter of adding a row here rather than touching the call site. Text
+//! without an entry (including messages built with `format!`) stays
+//! English.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+    Fr,
+    De,
+    Pt,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 5] = [Locale::En, Locale::Es, Locale::Fr, Locale::De, Locale::Pt];
+
+    /// The BCP 47 primary language subtag, e.g. `"es"`.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+            Locale::Fr => "fr",
+            Locale::De => "de",
+            Locale::Pt => "pt",
+        }
+    }
+
+    /// Match a language tag by its primary subtag, so `pt-BR` and `PT` both
+    /// give `Pt`.
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        Locale::ALL.into_iter().find(|l| l.tag() == primary)
+    }
+
+    /// Column in `CATALOG` rows; English is the key itself.
+    fn column(self) -> Option<usize> {
+        match self {
+            Locale::En => None,
+            Locale::Es => Some(0),
+            Locale::Fr => Some(1),
+            Locale::De => Some(2),
+            Locale::Pt => Some(3),
+        }
+    }
+}
+
+/// Pick the best supported locale from an `Accept-Language` header,
+/// honouring q-values. Falls back to English.
+pub fn negotiate(accept_language: &str) -> Locale {
+    let mut best: Option<(f32, Locale)> = None;
+    for range in accept_language.split(',') {
+        let mut parts = range.split(';');
+        let tag = parts.next().unwrap_or("").trim();
+        let q = parts
+            .find_map(|p| p.trim().strip_prefix("q="))
+            .and_then(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        if q <= 0.0 {
+            continue;
+        }
+        if let Some(locale) = Locale::from_tag(tag) {
+            // Earlier ranges win ties, as listed order is the client's preference
+            if best.is_none_or(|(best_q, _)| q > best_q) {
+                best = Some((q, locale));
+            }
+        }
+    }
+    best.map(|(_, l)| l).unwrap_or_default()
+}
+
+/// English message, then Spanish, French, German and Portuguese.
+const CATALOG: &[(&str, [&str; 4])] = &[
+    // Access
+    ("Not authenticated", ["No has iniciado sesión", "Non authentifié", "Nicht angemeldet", "Não autenticado"]),
+    ("Invalid session", ["Sesión no válida", "Session invalide", "Ungültige Sitzung", "Sessão inválida"]),
+    ("Session expired", ["La sesión ha caducado", "Session expirée", "Sitzung abgelaufen", "Sessão expirada"]),
+    ("Invalid credentials", ["Credenciales no válidas", "Identifiants invalides", "Ungültige Anmeldedaten", "Credenciais inválidas"]),
+    ("Insufficient permissions", ["Permisos insuficientes", "Autorisations insuffisantes", "Unzureichende Berechtigungen", "Permissões insuficientes"]),
+    ("Not a member of this server", [
+        "No eres miembro de este servidor",
+        "Vous n'êtes pas membre de ce serveur",
+        "Du bist kein Mitglied dieses Servers",
+        "Você não é membro deste servidor",
+    ]),
+    ("Not a member", ["No eres miembro", "Vous n'êtes pas membre", "Du bist kein Mitglied", "Você não é membro"]),
+    ("Verify your email to connect", [
+        "Verifica tu correo para conectarte",
+        "Vérifiez votre adresse e-mail pour vous connecter",
+        "Bestätige deine E-Mail-Adresse, um dich zu verbinden",
+        "Verifique seu e-mail para se conectar",
+    ]),
+    // Lookups
+    ("User not found", ["Usuario no encontrado", "Utilisateur introuvable", "Benutzer nicht gefunden", "Usuário não encontrado"]),
+    ("Server not found", ["Servidor no encontrado", "Serveur introuvable", "Server nicht gefunden", "Servidor não encontrado"]),
+    ("Channel not found", ["Canal no encontrado", "Salon introuvable", "Kanal nicht gefunden", "Canal não encontrado"]),
+    ("Message not found", ["Mensaje no encontrado", "Message introuvable", "Nachricht nicht gefunden", "Mensagem não encontrada"]),
+    ("Member not found", ["Miembro no encontrado", "Membre introuvable", "Mitglied nicht gefunden", "Membro não encontrado"]),
+    ("Event not found", ["Evento no encontrado", "Événement introuvable", "Veranstaltung nicht gefunden", "Evento não encontrado"]),
+    ("Session not found", ["Sesión no encontrada", "Session introuvable", "Sitzung nicht gefunden", "Sessão não encontrada"]),
+    ("File not found", ["Archivo no encontrado", "Fichier introuvable", "Datei nicht gefunden", "Arquivo não encontrado"]),
+    // Accounts
+    ("Username already taken", [
+        "El nombre de usuario ya está en uso",
+        "Ce nom d'utilisateur est déjà pris",
+        "Der Benutzername ist bereits vergeben",
+        "Nome de usuário já está em uso",
+    ]),
+    ("Email already registered", [
+        "El correo ya está registrado",
+        "Cette adresse e-mail est déjà enregistrée",
+        "Die E-Mail-Adresse ist bereits registriert",
+        "E-mail já cadastrado",
+    ]),
+    ("Username can only contain letters, numbers, hyphens, and underscores", [
+        "El nombre de usuario solo puede contener letras, números, guiones y guiones bajos",
+        "Le nom d'utilisateur ne peut contenir que des lettres, des chiffres, des tirets et des tirets bas",
+        "Der Benutzername darf nur Buchstaben, Ziffern, Binde- und Unterstriche enthalten",
+        "O nome de usuário só pode conter letras, números, hifens e sublinhados",
+    ]),
+    ("Invite is invalid or has expired", [
+        "La invitación no es válida o ha caducado",
+        "L'invitation est invalide ou a expiré",
+        "Die Einladung ist ungültig oder abgelaufen",
+        "O convite é inválido ou expirou",
+    ]),
+    ("Email is not configured on this server", [
+        "El correo no está configurado en este servidor",
+        "L'e-mail n'est pas configuré sur ce serveur",
+        "E-Mail ist auf diesem Server nicht eingerichtet",
+        "O e-mail não está configurado neste servidor",
+    ]),
+    // Input
+    ("Name is required", ["El nombre es obligatorio", "Le nom est obligatoire", "Name ist erforderlich", "O nome é obrigatório"]),
+    ("Server name is required", [
+        "El nombre del servidor es obligatorio",
+        "Le nom du serveur est obligatoire",
+        "Servername ist erforderlich",
+        "O nome do servidor é obrigatório",
+    ]),
+    ("Channel name is required", [
+        "El nombre del canal es obligatorio",
+        "Le nom du salon est obligatoire",
+        "Kanalname ist erforderlich",
+        "O nome do canal é obrigatório",
+    ]),
+    ("Message content is required", [
+        "El mensaje no puede estar vacío",
+        "Le message ne peut pas être vide",
+        "Die Nachricht darf nicht leer sein",
+        "A mensagem não pode estar vazia",
+    ]),
+    ("Message too long", ["Mensaje demasiado largo", "Message trop long", "Nachricht zu lang", "Mensagem muito longa"]),
+    ("No fields to update", [
+        "No hay campos para actualizar",
+        "Aucun champ à mettre à jour",
+        "Keine Felder zum Aktualisieren",
+        "Nenhum campo para atualizar",
+    ]),
+    ("No file provided", ["No se ha enviado ningún archivo", "Aucun fichier fourni", "Keine Datei angegeben", "Nenhum arquivo enviado"]),
+    // Server faults
+    ("Database error", ["Error de base de datos", "Erreur de base de données", "Datenbankfehler", "Erro no banco de dados"]),
+];
+
+/// Translate an English server string, or return it unchanged when the
+/// catalog has no entry for it.
+pub fn translate(locale: Locale, english: &str) -> &str {
+    let Some(column) = locale.column() else {
+        return english;
+    };
+    CATALOG
+        .iter()
+        .find(|(key, _)| *key == english)
+        .map(|(_, translations)| translations[column])
+        .unwrap_or(english)
+}
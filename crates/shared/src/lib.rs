@@ -1,2 +1,4 @@
 pub mod constants;
+pub mod i18n;
+pub mod markdown;
 pub mod validation;
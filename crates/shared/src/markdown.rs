@@ -0,0 +1,181 @@
+//! Message markup parsing.
+//!
+//! The server runs `parse` when a channel message is sent or edited. It
+//! normalizes malformed markup (stray control characters, unterminated code
+//! fences, an unmatched spoiler marker) and extracts the parts other features
+//! care about, so search and notifications can read the stored metadata
+//! instead of re-parsing raw text.
+
+use crate::constants::{MAX_USERNAME_LENGTH, MIN_USERNAME_LENGTH};
+use serde::{Deserialize, Serialize};
+
+/// Longest custom emoji name (matches the emoji upload validation).
+const MAX_EMOJI_NAME_LENGTH: usize = 32;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageMetadata {
+    /// Lowercased usernames from `@name`, excluding `@everyone`/`@here`.
+    pub mentions: Vec<String>,
+    pub mentions_everyone: bool,
+    pub mentions_here: bool,
+    /// Names from `:name:` shortcodes.
+    pub emojis: Vec<String>,
+    pub code_blocks: Vec<CodeBlock>,
+    /// Inner text of `||spoiler||` spans.
+    pub spoilers: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeBlock {
+    pub language: Option<String>,
+    pub code: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedMessage {
+    /// Normalized content; this is what gets stored and broadcast.
+    pub content: String,
+    pub metadata: MessageMetadata,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn push_unique(list: &mut Vec<String>, value: String) {
+    if !list.contains(&value) {
+        list.push(value);
+    }
+}
+
+/// `\r\n` -> `\n` and drop control characters other than newline and tab.
+fn strip_controls(raw: &str) -> String {
+    raw.replace("\r\n", "\n")
+        .chars()
+        .filter(|&c| !c.is_control() || c == '\n' || c == '\t')
+        .collect()
+}
+
+/// Split a fenced block body into an optional language tag and the code.
+fn code_block(body: &str) -> CodeBlock {
+    if let Some((first, rest)) = body.split_once('\n') {
+        let tag = first.trim();
+        if !tag.is_empty() && !tag.contains(char::is_whitespace) {
+            return CodeBlock { language: Some(tag.to_lowercase()), code: rest.to_string() };
+        }
+    }
+    CodeBlock { language: None, code: body.strip_prefix('\n').unwrap_or(body).to_string() }
+}
+
+pub fn parse(raw: &str) -> ParsedMessage {
+    let text = strip_controls(raw);
+    let mut meta = MessageMetadata::default();
+    let mut out = String::with_capacity(text.len() + 4);
+    // Byte offset in `out` just after the currently open `||`
+    let mut spoiler_start: Option<usize> = None;
+    let mut prev: Option<char> = None;
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+
+        if let Some(after) = rest.strip_prefix("```") {
+            match after.find("```") {
+                Some(end) => {
+                    meta.code_blocks.push(code_block(&after[..end]));
+                    out.push_str(&rest[..end + 6]);
+                    i += end + 6;
+                }
+                None => {
+                    // Unterminated fence: close it rather than letting it swallow later messages
+                    meta.code_blocks.push(code_block(after));
+                    out.push_str(rest);
+                    if !out.ends_with('\n') {
+                        out.push('\n');
+                    }
+                    out.push_str("```");
+                    i = text.len();
+                }
+            }
+            prev = Some('`');
+            continue;
+        }
+
+        if let Some(after) = rest.strip_prefix('`') {
+            // Inline code is opaque; a lone backtick is just a character
+            let len = after.find('`').map_or(1, |end| end + 2);
+            out.push_str(&rest[..len]);
+            i += len;
+            prev = Some('`');
+            continue;
+        }
+
+        if rest.starts_with("||") {
+            match spoiler_start.take() {
+                Some(start) => {
+                    let inner = out[start..].trim();
+                    if !inner.is_empty() {
+                        meta.spoilers.push(inner.to_string());
+                    }
+                }
+                None => spoiler_start = Some(out.len() + 2),
+            }
+            out.push_str("||");
+            i += 2;
+            prev = Some('|');
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap_or_default();
+
+        if c == '@' && !prev.is_some_and(is_word_char) {
+            let name: String = rest[1..]
+                .chars()
+                .take_while(|&c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                .collect();
+            let lower = name.to_lowercase();
+            match lower.as_str() {
+                "everyone" => meta.mentions_everyone = true,
+                "here" => meta.mentions_here = true,
+                _ if (MIN_USERNAME_LENGTH..=MAX_USERNAME_LENGTH).contains(&name.len()) => {
+                    push_unique(&mut meta.mentions, lower)
+                }
+                _ => {}
+            }
+            out.push('@');
+            out.push_str(&name);
+            i += 1 + name.len();
+            prev = name.chars().last().or(Some('@'));
+            continue;
+        }
+
+        if c == ':' {
+            let name: String = rest[1..].chars().take_while(|&c| is_word_char(c)).collect();
+            if !name.is_empty()
+                && name.len() <= MAX_EMOJI_NAME_LENGTH
+                && rest[1 + name.len()..].starts_with(':')
+            {
+                push_unique(&mut meta.emojis, name.clone());
+                out.push(':');
+                out.push_str(&name);
+                out.push(':');
+                i += name.len() + 2;
+                prev = Some(':');
+                continue;
+            }
+        }
+
+        out.push(c);
+        i += c.len_utf8();
+        prev = Some(c);
+    }
+
+    // A dangling opener would hide everything after it once more text is
+    // appended by an edit; escape it so it renders literally.
+    if let Some(start) = spoiler_start {
+        out.replace_range(start - 2..start, "\\|\\|");
+    }
+
+    ParsedMessage { content: out, metadata: meta }
+}
@@ -0,0 +1,402 @@
+//! "Share system audio": WASAPI loopback capture of an output device.
+//!
+//! Audio is captured as 48 kHz stereo f32 (WASAPI converts from the device mix
+//! format) and emitted in 20 ms `audio-loopback-data` chunks; the client feeds
+//! them into an AudioWorklet and publishes the resulting track to LiveKit next
+//! to the screen share. `audio-loopback-level` carries a meter reading every
+//! 50 ms and `audio-loopback-state` the lifecycle, like `capture-state`.
+
+use serde::Serialize;
+
+pub const SAMPLE_RATE: u32 = 48_000;
+pub const CHANNELS: usize = 2;
+/// 20 ms, one Opus frame.
+#[cfg_attr(not(windows), allow(dead_code))]
+const CHUNK_FRAMES: usize = SAMPLE_RATE as usize / 50;
+/// 50 ms between meter readings.
+#[cfg_attr(not(windows), allow(dead_code))]
+const LEVEL_FRAMES: usize = SAMPLE_RATE as usize / 20;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioDevice {
+    pub id: String,
+    pub name: String,
+    pub is_default: bool,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LoopbackState {
+    /// "started" | "stopped" | "error"
+    pub state: String,
+    pub device_id: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct AudioChunk {
+    pub sample_rate: u32,
+    pub channels: usize,
+    /// Interleaved little-endian f32 samples, base64-encoded.
+    pub data: String,
+}
+
+#[derive(Serialize, Clone)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct AudioLevel {
+    /// Linear 0.0–1.0 across all channels.
+    pub rms: f32,
+    pub peak: f32,
+}
+
+fn emit_state(app: &tauri::AppHandle, state: &str, device_id: Option<String>, error: Option<String>) {
+    use tauri::Emitter;
+    let _ = app.emit(
+        "audio-loopback-state",
+        LoopbackState { state: state.into(), device_id, error },
+    );
+}
+
+/// Accumulates samples and emits a reading every `LEVEL_FRAMES`.
+#[derive(Default)]
+#[cfg_attr(not(windows), allow(dead_code))]
+struct LevelMeter {
+    sum_squares: f64,
+    peak: f32,
+    samples: usize,
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+impl LevelMeter {
+    fn push(&mut self, app: &tauri::AppHandle, samples: &[f32]) {
+        for &s in samples {
+            self.sum_squares += (s as f64) * (s as f64);
+            self.peak = self.peak.max(s.abs());
+        }
+        self.samples += samples.len();
+        if self.samples >= LEVEL_FRAMES * CHANNELS {
+            let rms = (self.sum_squares / self.samples as f64).sqrt() as f32;
+            emit_level(app, rms.min(1.0), self.peak.min(1.0));
+            *self = Self::default();
+        }
+    }
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn emit_level(app: &tauri::AppHandle, rms: f32, peak: f32) {
+    use tauri::Emitter;
+    let _ = app.emit("audio-loopback-level", AudioLevel { rms, peak });
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn emit_chunk(app: &tauri::AppHandle, samples: &[f32]) {
+    use base64::Engine;
+    use tauri::Emitter;
+
+    let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    let _ = app.emit(
+        "audio-loopback-data",
+        AudioChunk {
+            sample_rate: SAMPLE_RATE,
+            channels: CHANNELS,
+            data: base64::engine::general_purpose::STANDARD.encode(bytes),
+        },
+    );
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{
+        emit_chunk, emit_level, emit_state, AudioDevice, LevelMeter, CHANNELS, CHUNK_FRAMES,
+        LEVEL_FRAMES, SAMPLE_RATE,
+    };
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{mpsc, Arc, Mutex};
+    use std::thread::JoinHandle;
+    use std::time::{Duration, Instant};
+    use windows::core::HSTRING;
+    use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+    use windows::Win32::Media::Audio::{
+        eConsole, eRender, IAudioCaptureClient, IAudioClient, IMMDevice, IMMDeviceEnumerator,
+        MMDeviceEnumerator, AUDCLNT_BUFFERFLAGS_SILENT, AUDCLNT_SHAREMODE_SHARED,
+        AUDCLNT_STREAMFLAGS_AUTOCONVERTPCM, AUDCLNT_STREAMFLAGS_LOOPBACK,
+        AUDCLNT_STREAMFLAGS_SRC_DEFAULT_QUALITY, DEVICE_STATE_ACTIVE, WAVEFORMATEX,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoTaskMemFree, CoUninitialize, CLSCTX_ALL,
+        COINIT_MULTITHREADED, STGM_READ,
+    };
+
+    const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+    /// Shared-mode buffer, in 100 ns units (100 ms).
+    const BUFFER_DURATION: i64 = 1_000_000;
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    /// Balances CoInitializeEx on the current thread. Tauri's main thread is
+    /// already STA, in which case the call fails and we just use that apartment.
+    struct ComGuard(bool);
+
+    impl ComGuard {
+        fn new() -> Self {
+            Self(unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) }.is_ok())
+        }
+    }
+
+    impl Drop for ComGuard {
+        fn drop(&mut self) {
+            if self.0 {
+                unsafe { CoUninitialize() };
+            }
+        }
+    }
+
+    struct ActiveCapture {
+        stop: Arc<AtomicBool>,
+        thread: JoinHandle<()>,
+    }
+
+    static ACTIVE: Mutex<Option<ActiveCapture>> = Mutex::new(None);
+
+    fn enumerator() -> windows::core::Result<IMMDeviceEnumerator> {
+        unsafe { CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) }
+    }
+
+    fn device_id(device: &IMMDevice) -> windows::core::Result<String> {
+        unsafe {
+            let id = device.GetId()?;
+            let s = id.to_string().unwrap_or_default();
+            CoTaskMemFree(Some(id.0 as *const _));
+            Ok(s)
+        }
+    }
+
+    fn device_name(device: &IMMDevice) -> windows::core::Result<String> {
+        unsafe {
+            let store = device.OpenPropertyStore(STGM_READ)?;
+            Ok(store.GetValue(&PKEY_Device_FriendlyName)?.to_string())
+        }
+    }
+
+    pub fn list_devices() -> Result<Vec<AudioDevice>, String> {
+        let _com = ComGuard::new();
+        let list = || -> windows::core::Result<Vec<AudioDevice>> {
+            let enumerator = enumerator()?;
+            let default_id = unsafe { enumerator.GetDefaultAudioEndpoint(eRender, eConsole) }
+                .and_then(|d| device_id(&d))
+                .ok();
+            let collection = unsafe { enumerator.EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)? };
+
+            let mut devices = Vec::new();
+            for i in 0..unsafe { collection.GetCount()? } {
+                let device = unsafe { collection.Item(i)? };
+                let id = device_id(&device)?;
+                devices.push(AudioDevice {
+                    name: device_name(&device).unwrap_or_else(|_| "Unknown device".into()),
+                    is_default: default_id.as_deref() == Some(id.as_str()),
+                    id,
+                });
+            }
+            Ok(devices)
+        };
+        list().map_err(|e| e.to_string())
+    }
+
+    fn open_client(
+        device_id: Option<&str>,
+    ) -> windows::core::Result<(IAudioClient, IAudioCaptureClient)> {
+        unsafe {
+            let enumerator = enumerator()?;
+            let device = match device_id {
+                Some(id) => enumerator.GetDevice(&HSTRING::from(id))?,
+                None => enumerator.GetDefaultAudioEndpoint(eRender, eConsole)?,
+            };
+            let client: IAudioClient = device.Activate(CLSCTX_ALL, None)?;
+
+            // Ask for our fixed format and let the audio engine resample/convert
+            let block_align = (CHANNELS * std::mem::size_of::<f32>()) as u16;
+            let format = WAVEFORMATEX {
+                wFormatTag: WAVE_FORMAT_IEEE_FLOAT,
+                nChannels: CHANNELS as u16,
+                nSamplesPerSec: SAMPLE_RATE,
+                nAvgBytesPerSec: SAMPLE_RATE * block_align as u32,
+                nBlockAlign: block_align,
+                wBitsPerSample: 32,
+                cbSize: 0,
+            };
+            client.Initialize(
+                AUDCLNT_SHAREMODE_SHARED,
+                AUDCLNT_STREAMFLAGS_LOOPBACK
+                    | AUDCLNT_STREAMFLAGS_AUTOCONVERTPCM
+                    | AUDCLNT_STREAMFLAGS_SRC_DEFAULT_QUALITY,
+                BUFFER_DURATION,
+                0,
+                &format,
+                None,
+            )?;
+            let capture: IAudioCaptureClient = client.GetService()?;
+            client.Start()?;
+            Ok((client, capture))
+        }
+    }
+
+    /// Drain available packets into `pending`. Returns whether anything arrived.
+    fn read_packets(
+        capture: &IAudioCaptureClient,
+        pending: &mut Vec<f32>,
+    ) -> windows::core::Result<bool> {
+        let mut received = false;
+        unsafe {
+            while capture.GetNextPacketSize()? > 0 {
+                let mut data = std::ptr::null_mut();
+                let mut frames = 0u32;
+                let mut flags = 0u32;
+                capture.GetBuffer(&mut data, &mut frames, &mut flags, None, None)?;
+
+                let samples = frames as usize * CHANNELS;
+                if data.is_null() || flags & AUDCLNT_BUFFERFLAGS_SILENT.0 as u32 != 0 {
+                    pending.resize(pending.len() + samples, 0.0);
+                } else {
+                    pending.extend_from_slice(std::slice::from_raw_parts(data as *const f32, samples));
+                }
+                capture.ReleaseBuffer(frames)?;
+                received |= frames > 0;
+            }
+        }
+        Ok(received)
+    }
+
+    fn run(
+        app: tauri::AppHandle,
+        device_id: Option<String>,
+        stop: Arc<AtomicBool>,
+        ready: mpsc::Sender<Result<(), String>>,
+    ) {
+        let _com = ComGuard::new();
+        let (client, capture) = match open_client(device_id.as_deref()) {
+            Ok(c) => c,
+            Err(e) => {
+                let _ = ready.send(Err(e.to_string()));
+                return;
+            }
+        };
+        let _ = ready.send(Ok(()));
+
+        let mut pending: Vec<f32> = Vec::with_capacity(CHUNK_FRAMES * CHANNELS * 4);
+        let mut meter = LevelMeter::default();
+        let mut last_audio = Instant::now();
+        let mut error = None;
+
+        while !stop.load(Ordering::SeqCst) {
+            std::thread::sleep(POLL_INTERVAL);
+            match read_packets(&capture, &mut pending) {
+                Ok(true) => last_audio = Instant::now(),
+                // Loopback delivers nothing at all while the device is silent
+                Ok(false) => {
+                    let level_interval =
+                        Duration::from_millis(LEVEL_FRAMES as u64 * 1000 / SAMPLE_RATE as u64);
+                    if last_audio.elapsed() >= level_interval {
+                        emit_level(&app, 0.0, 0.0);
+                        last_audio = Instant::now();
+                    }
+                }
+                // Device unplugged or the endpoint was invalidated
+                Err(e) => {
+                    error = Some(e.to_string());
+                    break;
+                }
+            }
+            while pending.len() >= CHUNK_FRAMES * CHANNELS {
+                let chunk: Vec<f32> = pending.drain(..CHUNK_FRAMES * CHANNELS).collect();
+                meter.push(&app, &chunk);
+                emit_chunk(&app, &chunk);
+            }
+        }
+
+        unsafe {
+            let _ = client.Stop();
+        }
+        match error {
+            Some(e) => emit_state(&app, "error", device_id, Some(e)),
+            None => emit_state(&app, "stopped", device_id, None),
+        }
+    }
+
+    pub fn start(app: tauri::AppHandle, device_id: Option<String>) -> Result<(), String> {
+        stop();
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let thread = {
+            let (app, device_id, stop_flag) = (app.clone(), device_id.clone(), stop_flag.clone());
+            std::thread::Builder::new()
+                .name("audio-loopback".into())
+                .spawn(move || run(app, device_id, stop_flag, ready_tx))
+                .map_err(|e| e.to_string())?
+        };
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                emit_state(&app, "error", device_id, Some(e.clone()));
+                return Err(e);
+            }
+            Err(_) => return Err("audio loopback thread exited".into()),
+        }
+
+        if let Ok(mut active) = ACTIVE.lock() {
+            *active = Some(ActiveCapture { stop: stop_flag, thread });
+        }
+        emit_state(&app, "started", device_id, None);
+        Ok(())
+    }
+
+    pub fn stop() {
+        let active = ACTIVE.lock().ok().and_then(|mut a| a.take());
+        if let Some(active) = active {
+            active.stop.store(true, Ordering::SeqCst);
+            let _ = active.thread.join();
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use super::AudioDevice;
+
+    pub fn list_devices() -> Result<Vec<AudioDevice>, String> {
+        Ok(Vec::new())
+    }
+
+    pub fn start(app: tauri::AppHandle, device_id: Option<String>) -> Result<(), String> {
+        let err = "System audio capture is not supported on this platform yet".to_string();
+        super::emit_state(&app, "error", device_id, Some(err.clone()));
+        Err(err)
+    }
+
+    pub fn stop() {}
+}
+
+// ── Tauri commands ──────────────────────────────────────────────────────────
+
+/// Output devices that can be captured. An empty list means loopback capture
+/// isn't available on this platform.
+#[tauri::command]
+pub fn get_loopback_devices() -> Result<Vec<AudioDevice>, String> {
+    platform::list_devices()
+}
+
+/// Start capturing what's playing on `device_id` (default output when omitted).
+/// Replaces any capture already running.
+#[tauri::command]
+pub fn start_audio_loopback(app: tauri::AppHandle, device_id: Option<String>) -> Result<(), String> {
+    platform::start(app, device_id)
+}
+
+#[tauri::command]
+pub fn stop_audio_loopback() {
+    platform::stop();
+}
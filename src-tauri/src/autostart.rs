@@ -0,0 +1,276 @@
+//! Launch Flux on OS login.
+//!
+//! Windows: HKCU Run key. macOS: a LaunchAgent plist. Linux: an XDG autostart
+//! .desktop entry. When started with `--minimized` the main window stays hidden
+//! in the tray.
+
+use serde::Serialize;
+
+pub const MINIMIZED_ARG: &str = "--minimized";
+
+#[derive(Serialize, Clone)]
+pub struct AutostartStatus {
+    pub enabled: bool,
+    pub minimized: bool,
+}
+
+/// True if this process was launched by the autostart entry in minimized mode.
+pub fn launched_minimized() -> bool {
+    std::env::args().any(|a| a == MINIMIZED_ARG)
+}
+
+/// Path to relaunch. AppImages run from a temporary mount, so prefer the image itself.
+fn launch_path() -> Result<String, String> {
+    if let Ok(appimage) = std::env::var("APPIMAGE") {
+        return Ok(appimage);
+    }
+    std::env::current_exe()
+        .map(|p| p.to_string_lossy().into_owned())
+        .map_err(|e| format!("current_exe: {e}"))
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{launch_path, AutostartStatus, MINIMIZED_ARG};
+    use windows::core::w;
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegCreateKeyW, RegDeleteValueW, RegQueryValueExW, RegSetValueExW, HKEY,
+        HKEY_CURRENT_USER, REG_SZ,
+    };
+
+    fn open_run_key() -> Result<HKEY, String> {
+        let mut hkey = HKEY::default();
+        let status = unsafe {
+            RegCreateKeyW(
+                HKEY_CURRENT_USER,
+                w!("Software\\Microsoft\\Windows\\CurrentVersion\\Run"),
+                &mut hkey,
+            )
+        };
+        if status == ERROR_SUCCESS {
+            Ok(hkey)
+        } else {
+            Err(format!("open Run key: {status:?}"))
+        }
+    }
+
+    pub fn enable(minimized: bool) -> Result<(), String> {
+        let mut command = format!("\"{}\"", launch_path()?);
+        if minimized {
+            command.push(' ');
+            command.push_str(MINIMIZED_ARG);
+        }
+        let value: Vec<u16> = command.encode_utf16().chain(std::iter::once(0)).collect();
+
+        let hkey = open_run_key()?;
+        let status = unsafe {
+            let status = RegSetValueExW(
+                hkey,
+                w!("Flux"),
+                0,
+                REG_SZ,
+                Some(std::slice::from_raw_parts(
+                    value.as_ptr() as *const u8,
+                    value.len() * 2,
+                )),
+            );
+            let _ = RegCloseKey(hkey);
+            status
+        };
+        if status == ERROR_SUCCESS {
+            Ok(())
+        } else {
+            Err(format!("write Run value: {status:?}"))
+        }
+    }
+
+    pub fn disable() -> Result<(), String> {
+        let hkey = open_run_key()?;
+        unsafe {
+            // Missing value is fine — already disabled
+            let _ = RegDeleteValueW(hkey, w!("Flux"));
+            let _ = RegCloseKey(hkey);
+        }
+        Ok(())
+    }
+
+    pub fn status() -> AutostartStatus {
+        let Ok(hkey) = open_run_key() else {
+            return AutostartStatus { enabled: false, minimized: false };
+        };
+
+        let mut buf = [0u16; 1024];
+        let mut len = (buf.len() * 2) as u32;
+        let status = unsafe {
+            let status = RegQueryValueExW(
+                hkey,
+                w!("Flux"),
+                None,
+                None,
+                Some(buf.as_mut_ptr() as *mut u8),
+                Some(&mut len as *mut u32),
+            );
+            let _ = RegCloseKey(hkey);
+            status
+        };
+        if status != ERROR_SUCCESS {
+            return AutostartStatus { enabled: false, minimized: false };
+        }
+
+        let chars = (len as usize / 2).min(buf.len());
+        let command = String::from_utf16_lossy(&buf[..chars]);
+        AutostartStatus { enabled: true, minimized: command.contains(MINIMIZED_ARG) }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{launch_path, AutostartStatus, MINIMIZED_ARG};
+    use std::path::PathBuf;
+
+    fn plist_path() -> Result<PathBuf, String> {
+        let home = std::env::var("HOME").map_err(|_| "HOME is not set".to_string())?;
+        Ok(PathBuf::from(home).join("Library/LaunchAgents/com.flux.app.plist"))
+    }
+
+    fn xml_escape(s: &str) -> String {
+        s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+    }
+
+    pub fn enable(minimized: bool) -> Result<(), String> {
+        let mut args = format!("        <string>{}</string>\n", xml_escape(&launch_path()?));
+        if minimized {
+            args.push_str(&format!("        <string>{MINIMIZED_ARG}</string>\n"));
+        }
+        let plist = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+             <plist version=\"1.0\">\n\
+             <dict>\n\
+             \x20   <key>Label</key>\n\
+             \x20   <string>com.flux.app</string>\n\
+             \x20   <key>ProgramArguments</key>\n\
+             \x20   <array>\n\
+             {args}\
+             \x20   </array>\n\
+             \x20   <key>RunAtLoad</key>\n\
+             \x20   <true/>\n\
+             </dict>\n\
+             </plist>\n"
+        );
+
+        let path = plist_path()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("create LaunchAgents: {e}"))?;
+        }
+        std::fs::write(&path, plist).map_err(|e| format!("write plist: {e}"))
+    }
+
+    pub fn disable() -> Result<(), String> {
+        match std::fs::remove_file(plist_path()?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("remove plist: {e}")),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn status() -> AutostartStatus {
+        match plist_path().and_then(|p| std::fs::read_to_string(p).map_err(|e| e.to_string())) {
+            Ok(plist) => AutostartStatus { enabled: true, minimized: plist.contains(MINIMIZED_ARG) },
+            Err(_) => AutostartStatus { enabled: false, minimized: false },
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{launch_path, AutostartStatus, MINIMIZED_ARG};
+    use std::path::PathBuf;
+
+    fn desktop_path() -> Result<PathBuf, String> {
+        let config = std::env::var("XDG_CONFIG_HOME")
+            .ok()
+            .filter(|d| !d.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var("HOME").ok().map(|h| PathBuf::from(h).join(".config")))
+            .ok_or_else(|| "HOME is not set".to_string())?;
+        Ok(config.join("autostart/flux.desktop"))
+    }
+
+    pub fn enable(minimized: bool) -> Result<(), String> {
+        // Desktop Entry Exec quoting: wrap in double quotes, escape \ " ` $
+        let exe = launch_path()?
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('`', "\\`")
+            .replace('$', "\\$");
+        let exec = if minimized {
+            format!("\"{exe}\" {MINIMIZED_ARG}")
+        } else {
+            format!("\"{exe}\"")
+        };
+        let entry = format!(
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name=Flux\n\
+             Exec={exec}\n\
+             Terminal=false\n\
+             X-GNOME-Autostart-enabled=true\n"
+        );
+
+        let path = desktop_path()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("create autostart dir: {e}"))?;
+        }
+        std::fs::write(&path, entry).map_err(|e| format!("write desktop entry: {e}"))
+    }
+
+    pub fn disable() -> Result<(), String> {
+        match std::fs::remove_file(desktop_path()?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("remove desktop entry: {e}"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn status() -> AutostartStatus {
+        match desktop_path().and_then(|p| std::fs::read_to_string(p).map_err(|e| e.to_string())) {
+            Ok(entry) => AutostartStatus { enabled: true, minimized: entry.contains(MINIMIZED_ARG) },
+            Err(_) => AutostartStatus { enabled: false, minimized: false },
+        }
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod platform {
+    use super::AutostartStatus;
+
+    pub fn enable(_minimized: bool) -> Result<(), String> {
+        Err("Autostart is not supported on this platform".into())
+    }
+
+    pub fn disable() -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn status() -> AutostartStatus {
+        AutostartStatus { enabled: false, minimized: false }
+    }
+}
+
+// ── Tauri commands ──────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn set_autostart(enabled: bool, minimized: bool) -> Result<(), String> {
+    if enabled {
+        platform::enable(minimized)
+    } else {
+        platform::disable()
+    }
+}
+
+#[tauri::command]
+pub fn get_autostart_status() -> AutostartStatus {
+    platform::status()
+}
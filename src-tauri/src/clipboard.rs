@@ -0,0 +1,236 @@
+//! Paste-to-upload: read an image from the OS clipboard, either a copied
+//! bitmap (screenshots) or an image file from a copied file list, and shrink
+//! it before it goes to the webview.
+//!
+//! WebView2 hands pasted screenshots to the page as multi-megabyte PNGs and
+//! doesn't expose copied files at all, so the webview asks here first.
+
+use serde::Serialize;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// Longest edge kept when transcoding; larger images are scaled down.
+const DEFAULT_MAX_DIMENSION: u32 = 2560;
+/// Image files at most this big (and within the dimension limit) are
+/// returned as they are.
+const PASSTHROUGH_BYTES: usize = 512 * 1024;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardImage {
+    pub name: String,
+    pub mime: String,
+    pub width: u32,
+    pub height: u32,
+    /// Encoded file bytes, base64.
+    pub data: String,
+}
+
+fn mime_for(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Paths from a copied file list (Explorer's CF_HDROP).
+#[cfg(windows)]
+fn clipboard_files(_clipboard: &mut arboard::Clipboard) -> Vec<PathBuf> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::DataExchange::{CloseClipboard, GetClipboardData, OpenClipboard};
+    use windows::Win32::System::Ole::CF_HDROP;
+    use windows::Win32::UI::Shell::{DragQueryFileW, HDROP};
+
+    let mut files = Vec::new();
+    unsafe {
+        if OpenClipboard(HWND::default()).is_err() {
+            return files;
+        }
+        if let Ok(handle) = GetClipboardData(CF_HDROP.0 as u32) {
+            let hdrop = HDROP(handle.0);
+            let count = DragQueryFileW(hdrop, u32::MAX, None);
+            for i in 0..count {
+                let len = DragQueryFileW(hdrop, i, None) as usize;
+                let mut buf = vec![0u16; len + 1];
+                DragQueryFileW(hdrop, i, Some(&mut buf));
+                files.push(PathBuf::from(String::from_utf16_lossy(&buf[..len])));
+            }
+        }
+        let _ = CloseClipboard();
+    }
+    files
+}
+
+/// Paths from a copied file list. File managers on Linux and macOS also put
+/// the files on the clipboard as `file://` URIs, one per line.
+#[cfg(not(windows))]
+fn clipboard_files(clipboard: &mut arboard::Clipboard) -> Vec<PathBuf> {
+    let Ok(text) = clipboard.get_text() else {
+        return Vec::new();
+    };
+    text.lines()
+        .filter_map(|line| url::Url::parse(line.trim()).ok())
+        .filter(|url| url.scheme() == "file")
+        .filter_map(|url| url.to_file_path().ok())
+        .collect()
+}
+
+/// Scale `img` so its longest edge is at most `max_dimension`.
+fn downscale(img: image::DynamicImage, max_dimension: u32) -> image::DynamicImage {
+    if img.width().max(img.height()) <= max_dimension {
+        return img;
+    }
+    img.resize(
+        max_dimension,
+        max_dimension,
+        image::imageops::FilterType::Lanczos3,
+    )
+}
+
+/// Encode as lossless WebP when transcoding (far smaller than PNG for
+/// screenshots), PNG otherwise.
+fn encode(img: &image::DynamicImage, transcode: bool) -> Result<(Vec<u8>, &'static str), String> {
+    let rgba = img.to_rgba8();
+    let mut buf = Cursor::new(Vec::new());
+    let mime = if transcode {
+        let encoder = image::codecs::webp::WebPEncoder::new_lossless(&mut buf);
+        image::ImageEncoder::write_image(
+            encoder,
+            rgba.as_raw(),
+            rgba.width(),
+            rgba.height(),
+            image::ExtendedColorType::Rgba8,
+        )
+        .map_err(|e| e.to_string())?;
+        "image/webp"
+    } else {
+        let encoder = image::codecs::png::PngEncoder::new(&mut buf);
+        image::ImageEncoder::write_image(
+            encoder,
+            rgba.as_raw(),
+            rgba.width(),
+            rgba.height(),
+            image::ExtendedColorType::Rgba8,
+        )
+        .map_err(|e| e.to_string())?;
+        "image/png"
+    };
+    Ok((buf.into_inner(), mime))
+}
+
+fn finish(stem: &str, bytes: Vec<u8>, mime: &str, width: u32, height: u32) -> ClipboardImage {
+    use base64::Engine;
+
+    let ext = match mime {
+        "image/jpeg" => "jpg",
+        other => other.trim_start_matches("image/"),
+    };
+    ClipboardImage {
+        name: format!("{stem}.{ext}"),
+        mime: mime.to_string(),
+        width,
+        height,
+        data: base64::engine::general_purpose::STANDARD.encode(bytes),
+    }
+}
+
+/// The first image file in a copied file list.
+fn read_file(path: &Path, max_dimension: u32, transcode: bool) -> Result<ClipboardImage, String> {
+    let mime = mime_for(path).ok_or("Not an image")?;
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("image")
+        .to_string();
+
+    let (width, height) = image::ImageReader::new(Cursor::new(&bytes))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?
+        .into_dimensions()
+        .map_err(|e| e.to_string())?;
+
+    // GIFs may be animated; re-encoding would keep only the first frame
+    let small = bytes.len() <= PASSTHROUGH_BYTES && width.max(height) <= max_dimension;
+    if !transcode || small || mime == "image/gif" {
+        return Ok(finish(&stem, bytes, mime, width, height));
+    }
+
+    let img = image::load_from_memory(&bytes).map_err(|e| e.to_string())?;
+    let img = downscale(img, max_dimension);
+    let (encoded, encoded_mime) = encode(&img, true)?;
+    // Keep the original if re-encoding didn't help
+    if encoded.len() >= bytes.len() && img.width() == width {
+        return Ok(finish(&stem, bytes, mime, width, height));
+    }
+    Ok(finish(
+        &stem,
+        encoded,
+        encoded_mime,
+        img.width(),
+        img.height(),
+    ))
+}
+
+/// A copied bitmap, e.g. from a screenshot tool.
+fn read_bitmap(
+    bitmap: arboard::ImageData<'_>,
+    max_dimension: u32,
+    transcode: bool,
+) -> Result<ClipboardImage, String> {
+    let (width, height) = (bitmap.width as u32, bitmap.height as u32);
+    let rgba = image::RgbaImage::from_raw(width, height, bitmap.bytes.into_owned())
+        .ok_or("Clipboard image has an unexpected size")?;
+    let img = image::DynamicImage::ImageRgba8(rgba);
+    let img = if transcode {
+        downscale(img, max_dimension)
+    } else {
+        img
+    };
+    let (encoded, mime) = encode(&img, transcode)?;
+    Ok(finish(
+        "pasted-image",
+        encoded,
+        mime,
+        img.width(),
+        img.height(),
+    ))
+}
+
+fn read(max_dimension: u32, transcode: bool) -> Result<Option<ClipboardImage>, String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+
+    if let Some(path) = clipboard_files(&mut clipboard)
+        .into_iter()
+        .find(|p| mime_for(p).is_some())
+    {
+        return read_file(&path, max_dimension, transcode).map(Some);
+    }
+
+    match clipboard.get_image() {
+        Ok(bitmap) => read_bitmap(bitmap, max_dimension, transcode).map(Some),
+        Err(arboard::Error::ContentNotAvailable) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// ── Tauri commands ──────────────────────────────────────────────────────────
+
+/// Read an image from the clipboard, or `None` if there isn't one. With
+/// `transcode` (the default), images over `max_dimension` pixels on their
+/// longest edge are scaled down and large ones re-encoded as WebP.
+#[tauri::command]
+pub async fn get_clipboard_image(
+    max_dimension: Option<u32>,
+    transcode: Option<bool>,
+) -> Result<Option<ClipboardImage>, String> {
+    let max_dimension = max_dimension.unwrap_or(DEFAULT_MAX_DIMENSION).max(1);
+    let transcode = transcode.unwrap_or(true);
+    tauri::async_runtime::spawn_blocking(move || read(max_dimension, transcode))
+        .await
+        .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,102 @@
+//! `flux://` URL scheme handling.
+//!
+//! Supported links:
+//!   flux://invite/<code>
+//!   flux://channels/<serverId>/<channelId>[/<messageId>]
+//!   flux://dm/<dmChannelId>[/<messageId>]
+//!
+//! Parsed links are emitted to the webview as `deep-link` events. A link that
+//! launched the app arrives before the frontend is listening, so the most
+//! recent one is also kept until `take_pending_deep_link` collects it.
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DeepLink {
+    Invite {
+        code: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Channel {
+        server_id: String,
+        channel_id: String,
+        message_id: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Dm {
+        dm_channel_id: String,
+        message_id: Option<String>,
+    },
+}
+
+static PENDING: Mutex<Option<DeepLink>> = Mutex::new(None);
+
+/// Parse a flux:// URL into a navigation target. Unknown shapes return None.
+fn parse(url: &url::Url) -> Option<DeepLink> {
+    if url.scheme() != "flux" {
+        return None;
+    }
+    // flux://invite/CODE parses with "invite" as the host
+    let segments: Vec<&str> = url
+        .host_str()
+        .into_iter()
+        .chain(url.path_segments().into_iter().flatten())
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    let link = match segments.as_slice() {
+        ["invite", code] => DeepLink::Invite { code: code.to_string() },
+        ["channels", server_id, channel_id, rest @ ..] if rest.len() <= 1 => DeepLink::Channel {
+            server_id: server_id.to_string(),
+            channel_id: channel_id.to_string(),
+            message_id: rest.first().map(|m| m.to_string()),
+        },
+        ["dm", dm_channel_id, rest @ ..] if rest.len() <= 1 => DeepLink::Dm {
+            dm_channel_id: dm_channel_id.to_string(),
+            message_id: rest.first().map(|m| m.to_string()),
+        },
+        _ => return None,
+    };
+    Some(link)
+}
+
+fn dispatch(app: &AppHandle, urls: Vec<url::Url>) {
+    for link in urls.iter().filter_map(parse) {
+        if let Ok(mut pending) = PENDING.lock() {
+            *pending = Some(link.clone());
+        }
+        let _ = app.emit("deep-link", link);
+        crate::tray::show_main_window(app);
+    }
+}
+
+pub fn init(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    // macOS registers schemes from Info.plist at bundle time; Windows and Linux
+    // need the registry / .desktop entry written at runtime (also covers dev builds).
+    #[cfg(any(windows, target_os = "linux"))]
+    app.deep_link().register_all()?;
+
+    // Cold start: the URL is in our own argv
+    if let Some(urls) = app.deep_link().get_current()? {
+        dispatch(app, urls);
+    }
+
+    // Warm start: forwarded by the single-instance plugin or the OS (macOS)
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        dispatch(&handle, event.urls());
+    });
+    Ok(())
+}
+
+// ── Tauri commands ──────────────────────────────────────────────────────────
+
+/// Returns (and clears) the link the app was launched or last focused with.
+#[tauri::command]
+pub fn take_pending_deep_link() -> Option<DeepLink> {
+    PENDING.lock().ok().and_then(|mut p| p.take())
+}
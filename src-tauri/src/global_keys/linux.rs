@@ -0,0 +1,201 @@
+use evdev::{Device, InputEventKind, Key};
+use std::os::fd::AsRawFd;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use super::{clear_pressed, code_to_mouse, set_pressed, KeyBinding};
+
+/// Bumped on every start/stop; reader threads exit once their generation is stale.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// How long a reader thread blocks in poll() before re-checking its generation.
+const POLL_TIMEOUT_MS: i32 = 100;
+
+/// Convert a KeyboardEvent.code string to a Linux input event key code.
+fn code_to_key(code: &str) -> Option<Key> {
+    const LETTERS: [Key; 26] = [
+        Key::KEY_A, Key::KEY_B, Key::KEY_C, Key::KEY_D, Key::KEY_E, Key::KEY_F,
+        Key::KEY_G, Key::KEY_H, Key::KEY_I, Key::KEY_J, Key::KEY_K, Key::KEY_L,
+        Key::KEY_M, Key::KEY_N, Key::KEY_O, Key::KEY_P, Key::KEY_Q, Key::KEY_R,
+        Key::KEY_S, Key::KEY_T, Key::KEY_U, Key::KEY_V, Key::KEY_W, Key::KEY_X,
+        Key::KEY_Y, Key::KEY_Z,
+    ];
+    const DIGITS: [Key; 10] = [
+        Key::KEY_0, Key::KEY_1, Key::KEY_2, Key::KEY_3, Key::KEY_4,
+        Key::KEY_5, Key::KEY_6, Key::KEY_7, Key::KEY_8, Key::KEY_9,
+    ];
+    const FKEYS: [Key; 24] = [
+        Key::KEY_F1, Key::KEY_F2, Key::KEY_F3, Key::KEY_F4, Key::KEY_F5, Key::KEY_F6,
+        Key::KEY_F7, Key::KEY_F8, Key::KEY_F9, Key::KEY_F10, Key::KEY_F11, Key::KEY_F12,
+        Key::KEY_F13, Key::KEY_F14, Key::KEY_F15, Key::KEY_F16, Key::KEY_F17, Key::KEY_F18,
+        Key::KEY_F19, Key::KEY_F20, Key::KEY_F21, Key::KEY_F22, Key::KEY_F23, Key::KEY_F24,
+    ];
+
+    // Letters: "KeyA" .. "KeyZ"
+    if code.starts_with("Key") && code.len() == 4 {
+        let ch = code.as_bytes()[3];
+        if ch.is_ascii_uppercase() {
+            return Some(LETTERS[(ch - b'A') as usize]);
+        }
+    }
+    // Digits: "Digit0" .. "Digit9"
+    if code.starts_with("Digit") && code.len() == 6 {
+        let ch = code.as_bytes()[5];
+        if ch.is_ascii_digit() {
+            return Some(DIGITS[(ch - b'0') as usize]);
+        }
+    }
+    // Function keys: "F1" .. "F24"
+    if code.starts_with('F') && code.len() >= 2 {
+        if let Ok(n) = code[1..].parse::<usize>() {
+            if (1..=24).contains(&n) {
+                return Some(FKEYS[n - 1]);
+            }
+        }
+    }
+    // Named keys (left/right modifiers are distinct keys on evdev)
+    match code {
+        "Space" => Some(Key::KEY_SPACE),
+        "Enter" => Some(Key::KEY_ENTER),
+        "Tab" => Some(Key::KEY_TAB),
+        "CapsLock" => Some(Key::KEY_CAPSLOCK),
+        "ShiftLeft" => Some(Key::KEY_LEFTSHIFT),
+        "ShiftRight" => Some(Key::KEY_RIGHTSHIFT),
+        "ControlLeft" => Some(Key::KEY_LEFTCTRL),
+        "ControlRight" => Some(Key::KEY_RIGHTCTRL),
+        "AltLeft" => Some(Key::KEY_LEFTALT),
+        "AltRight" => Some(Key::KEY_RIGHTALT),
+        "Backquote" => Some(Key::KEY_GRAVE),
+        "Minus" => Some(Key::KEY_MINUS),
+        "Equal" => Some(Key::KEY_EQUAL),
+        "BracketLeft" => Some(Key::KEY_LEFTBRACE),
+        "BracketRight" => Some(Key::KEY_RIGHTBRACE),
+        "Backslash" => Some(Key::KEY_BACKSLASH),
+        "Semicolon" => Some(Key::KEY_SEMICOLON),
+        "Quote" => Some(Key::KEY_APOSTROPHE),
+        "Comma" => Some(Key::KEY_COMMA),
+        "Period" => Some(Key::KEY_DOT),
+        "Slash" => Some(Key::KEY_SLASH),
+        "Insert" => Some(Key::KEY_INSERT),
+        "Delete" => Some(Key::KEY_DELETE),
+        "Home" => Some(Key::KEY_HOME),
+        "End" => Some(Key::KEY_END),
+        "PageUp" => Some(Key::KEY_PAGEUP),
+        "PageDown" => Some(Key::KEY_PAGEDOWN),
+        "ArrowUp" => Some(Key::KEY_UP),
+        "ArrowDown" => Some(Key::KEY_DOWN),
+        "ArrowLeft" => Some(Key::KEY_LEFT),
+        "ArrowRight" => Some(Key::KEY_RIGHT),
+        "NumpadMultiply" => Some(Key::KEY_KPASTERISK),
+        "NumpadAdd" => Some(Key::KEY_KPPLUS),
+        "NumpadSubtract" => Some(Key::KEY_KPMINUS),
+        "NumpadDecimal" => Some(Key::KEY_KPDOT),
+        "NumpadDivide" => Some(Key::KEY_KPSLASH),
+        "Numpad0" => Some(Key::KEY_KP0),
+        "Numpad1" => Some(Key::KEY_KP1),
+        "Numpad2" => Some(Key::KEY_KP2),
+        "Numpad3" => Some(Key::KEY_KP3),
+        "Numpad4" => Some(Key::KEY_KP4),
+        "Numpad5" => Some(Key::KEY_KP5),
+        "Numpad6" => Some(Key::KEY_KP6),
+        "Numpad7" => Some(Key::KEY_KP7),
+        "Numpad8" => Some(Key::KEY_KP8),
+        "Numpad9" => Some(Key::KEY_KP9),
+        "NumLock" => Some(Key::KEY_NUMLOCK),
+        "ScrollLock" => Some(Key::KEY_SCROLLLOCK),
+        _ => None,
+    }
+}
+
+/// Map our internal mouse button id (1-5) to the evdev button code.
+fn mouse_to_key(button: u32) -> Option<Key> {
+    match button {
+        1 => Some(Key::BTN_LEFT),
+        2 => Some(Key::BTN_MIDDLE),
+        3 => Some(Key::BTN_RIGHT),
+        4 => Some(Key::BTN_SIDE),
+        5 => Some(Key::BTN_EXTRA),
+        _ => None,
+    }
+}
+
+// ── Device readers ──────────────────────────────────────────────────────────
+
+/// Read events from one device until the listener generation changes.
+/// Works the same under X11 and Wayland since it reads /dev/input directly.
+fn read_device(mut device: Device, targets: Arc<Vec<(Key, String)>>, generation: u64) {
+    let fd = device.as_raw_fd();
+    while GENERATION.load(Ordering::Relaxed) == generation {
+        let mut pfd = libc::pollfd { fd, events: libc::POLLIN, revents: 0 };
+        let ready = unsafe { libc::poll(&mut pfd, 1, POLL_TIMEOUT_MS) };
+        if ready <= 0 {
+            continue;
+        }
+
+        let events = match device.fetch_events() {
+            Ok(events) => events,
+            // Device unplugged or permission revoked
+            Err(_) => return,
+        };
+        for ev in events {
+            let InputEventKind::Key(key) = ev.kind() else { continue };
+            // value: 1 = press, 0 = release, 2 = autorepeat
+            let is_down = match ev.value() {
+                1 => true,
+                0 => false,
+                _ => continue,
+            };
+            for (_, id) in targets.iter().filter(|(target, _)| *target == key) {
+                set_pressed(id, is_down);
+            }
+        }
+    }
+}
+
+pub(super) fn stop_hook() {
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+    clear_pressed();
+}
+
+// ── Entry point ─────────────────────────────────────────────────────────────
+
+pub(super) fn start(bindings: &[KeyBinding]) -> Result<(), String> {
+    let targets = bindings
+        .iter()
+        .map(|b| {
+            let key = code_to_key(&b.code)
+                .or_else(|| code_to_mouse(&b.code).and_then(mouse_to_key))
+                .ok_or_else(|| format!("Unknown key code: {}", b.code))?;
+            Ok((key, b.id.clone()))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let targets = Arc::new(targets);
+
+    // Stop any existing readers first
+    stop_hook();
+    let generation = GENERATION.load(Ordering::Relaxed);
+
+    // Only devices that can actually produce one of the target keys; enumerate()
+    // silently skips nodes we cannot open, which is every device unless the user
+    // is in `input`.
+    let devices: Vec<Device> = evdev::enumerate()
+        .map(|(_, device)| device)
+        .filter(|device| {
+            device
+                .supported_keys()
+                .is_some_and(|keys| targets.iter().any(|(key, _)| keys.contains(*key)))
+        })
+        .collect();
+
+    if devices.is_empty() {
+        return Err(
+            "No readable input devices found (add your user to the 'input' group)".to_string(),
+        );
+    }
+
+    for device in devices {
+        let targets = targets.clone();
+        std::thread::spawn(move || read_device(device, targets, generation));
+    }
+    Ok(())
+}
@@ -0,0 +1,267 @@
+use core_foundation::base::TCFType;
+use core_foundation::runloop::{kCFRunLoopCommonModes, CFRunLoop, CFRunLoopRef};
+use core_graphics::event::{
+    CGEventFlags, CGEventTap, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement,
+    CGEventType, EventField,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+
+use super::{clear_pressed, code_to_mouse, set_pressed, KeyBinding};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Target {
+    /// A keyboard key, by virtual key code.
+    Key(i64),
+    /// A mouse button (1=left, 2=middle, 3=right, 4=back, 5=forward).
+    Mouse(u32),
+}
+
+/// What each registered binding listens for.
+static TARGETS: Mutex<Vec<(Target, String)>> = Mutex::new(Vec::new());
+/// CFRunLoopRef of the tap thread, so stop_hook() can wake it from another thread.
+static RUN_LOOP: AtomicUsize = AtomicUsize::new(0);
+
+/// Convert a KeyboardEvent.code string to a macOS virtual key code (kVK_*).
+fn code_to_keycode(code: &str) -> Option<i64> {
+    // kVK_ANSI_* codes follow the physical ANSI layout, not the alphabet
+    const LETTERS: [i64; 26] = [
+        0x00, 0x0B, 0x08, 0x02, 0x0E, 0x03, 0x05, 0x04, 0x22, 0x26, 0x28, 0x25, 0x2E,
+        0x2D, 0x1F, 0x23, 0x0C, 0x0F, 0x01, 0x11, 0x20, 0x09, 0x0D, 0x07, 0x10, 0x06,
+    ];
+    const DIGITS: [i64; 10] = [0x1D, 0x12, 0x13, 0x14, 0x15, 0x17, 0x16, 0x1A, 0x1C, 0x19];
+    const FKEYS: [i64; 20] = [
+        0x7A, 0x78, 0x63, 0x76, 0x60, 0x61, 0x62, 0x64, 0x65, 0x6D,
+        0x67, 0x6F, 0x69, 0x6B, 0x71, 0x6A, 0x40, 0x4F, 0x50, 0x5A,
+    ];
+
+    // Letters: "KeyA" .. "KeyZ"
+    if code.starts_with("Key") && code.len() == 4 {
+        let ch = code.as_bytes()[3];
+        if ch.is_ascii_uppercase() {
+            return Some(LETTERS[(ch - b'A') as usize]);
+        }
+    }
+    // Digits: "Digit0" .. "Digit9"
+    if code.starts_with("Digit") && code.len() == 6 {
+        let ch = code.as_bytes()[5];
+        if ch.is_ascii_digit() {
+            return Some(DIGITS[(ch - b'0') as usize]);
+        }
+    }
+    // Function keys: "F1" .. "F20" (macOS has no F21-F24)
+    if code.starts_with('F') && code.len() >= 2 {
+        if let Ok(n) = code[1..].parse::<usize>() {
+            if (1..=20).contains(&n) {
+                return Some(FKEYS[n - 1]);
+            }
+        }
+    }
+    // Named keys
+    match code {
+        "Space" => Some(0x31),
+        "Enter" => Some(0x24),
+        "Tab" => Some(0x30),
+        "CapsLock" => Some(0x39),
+        "ShiftLeft" => Some(0x38),
+        "ShiftRight" => Some(0x3C),
+        "ControlLeft" => Some(0x3B),
+        "ControlRight" => Some(0x3E),
+        "AltLeft" => Some(0x3A),
+        "AltRight" => Some(0x3D),
+        "Backquote" => Some(0x32),
+        "Minus" => Some(0x1B),
+        "Equal" => Some(0x18),
+        "BracketLeft" => Some(0x21),
+        "BracketRight" => Some(0x1E),
+        "Backslash" => Some(0x2A),
+        "Semicolon" => Some(0x29),
+        "Quote" => Some(0x27),
+        "Comma" => Some(0x2B),
+        "Period" => Some(0x2F),
+        "Slash" => Some(0x2C),
+        "Insert" => Some(0x72), // Help key on Apple keyboards
+        "Delete" => Some(0x75), // forward delete
+        "Home" => Some(0x73),
+        "End" => Some(0x77),
+        "PageUp" => Some(0x74),
+        "PageDown" => Some(0x79),
+        "ArrowUp" => Some(0x7E),
+        "ArrowDown" => Some(0x7D),
+        "ArrowLeft" => Some(0x7B),
+        "ArrowRight" => Some(0x7C),
+        "NumpadMultiply" => Some(0x43),
+        "NumpadAdd" => Some(0x45),
+        "NumpadSubtract" => Some(0x4E),
+        "NumpadDecimal" => Some(0x41),
+        "NumpadDivide" => Some(0x4B),
+        "Numpad0" => Some(0x52),
+        "Numpad1" => Some(0x53),
+        "Numpad2" => Some(0x54),
+        "Numpad3" => Some(0x55),
+        "Numpad4" => Some(0x56),
+        "Numpad5" => Some(0x57),
+        "Numpad6" => Some(0x58),
+        "Numpad7" => Some(0x59),
+        "Numpad8" => Some(0x5B),
+        "Numpad9" => Some(0x5C),
+        "NumLock" => Some(0x47), // keypad Clear
+        _ => None,
+    }
+}
+
+/// Modifier keys arrive as FlagsChanged events; this is the flag that tells
+/// whether the key went down or up.
+fn modifier_flag(keycode: i64) -> Option<CGEventFlags> {
+    match keycode {
+        0x38 | 0x3C => Some(CGEventFlags::CGEventFlagShift),
+        0x3B | 0x3E => Some(CGEventFlags::CGEventFlagControl),
+        0x3A | 0x3D => Some(CGEventFlags::CGEventFlagAlternate),
+        0x39 => Some(CGEventFlags::CGEventFlagAlphaShift),
+        _ => None,
+    }
+}
+
+/// Press or release every binding on `target`.
+fn dispatch(target: Target, is_down: bool) {
+    let ids: Vec<String> = TARGETS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(t, _)| *t == target)
+        .map(|(_, id)| id.clone())
+        .collect();
+    for id in ids {
+        set_pressed(&id, is_down);
+    }
+}
+
+// ── Event tap ───────────────────────────────────────────────────────────────
+
+fn handle_event(etype: CGEventType, event: &core_graphics::event::CGEvent) {
+    match etype {
+        CGEventType::KeyDown | CGEventType::KeyUp => {
+            let keycode = event.get_integer_value_field(EventField::KEYBOARD_EVENT_KEYCODE);
+            let is_repeat =
+                event.get_integer_value_field(EventField::KEYBOARD_EVENT_AUTOREPEAT) != 0;
+            if !is_repeat {
+                dispatch(Target::Key(keycode), matches!(etype, CGEventType::KeyDown));
+            }
+        }
+        CGEventType::FlagsChanged => {
+            let keycode = event.get_integer_value_field(EventField::KEYBOARD_EVENT_KEYCODE);
+            if let Some(flag) = modifier_flag(keycode) {
+                dispatch(Target::Key(keycode), event.get_flags().contains(flag));
+            }
+        }
+        CGEventType::LeftMouseDown | CGEventType::LeftMouseUp => {
+            dispatch(Target::Mouse(1), matches!(etype, CGEventType::LeftMouseDown));
+        }
+        CGEventType::RightMouseDown | CGEventType::RightMouseUp => {
+            dispatch(Target::Mouse(3), matches!(etype, CGEventType::RightMouseDown));
+        }
+        CGEventType::OtherMouseDown | CGEventType::OtherMouseUp => {
+            // Button numbers: 2 = middle, 3 = back, 4 = forward
+            let button = event.get_integer_value_field(EventField::MOUSE_EVENT_BUTTON_NUMBER);
+            let id = match button {
+                2 => 2,
+                3 => 4,
+                4 => 5,
+                _ => return,
+            };
+            dispatch(Target::Mouse(id), matches!(etype, CGEventType::OtherMouseDown));
+        }
+        _ => {}
+    }
+}
+
+fn start_hook() -> Result<(), String> {
+    let (ready_tx, ready_rx) = mpsc::channel::<Result<(), String>>();
+
+    std::thread::spawn(move || {
+        let tap = CGEventTap::new(
+            CGEventTapLocation::HID,
+            CGEventTapPlacement::HeadInsertEventTap,
+            CGEventTapOptions::ListenOnly,
+            vec![
+                CGEventType::KeyDown,
+                CGEventType::KeyUp,
+                CGEventType::FlagsChanged,
+                CGEventType::LeftMouseDown,
+                CGEventType::LeftMouseUp,
+                CGEventType::RightMouseDown,
+                CGEventType::RightMouseUp,
+                CGEventType::OtherMouseDown,
+                CGEventType::OtherMouseUp,
+            ],
+            |_proxy, etype, event| {
+                handle_event(etype, event);
+                None
+            },
+        );
+
+        // Tap creation fails when the app hasn't been granted Input Monitoring
+        let tap = match tap {
+            Ok(tap) => tap,
+            Err(()) => {
+                let _ = ready_tx.send(Err(
+                    "Input Monitoring permission is required for push-to-talk".to_string(),
+                ));
+                return;
+            }
+        };
+
+        let Ok(source) = tap.mach_port.create_runloop_source(0) else {
+            let _ = ready_tx.send(Err("Failed to create event tap run loop source".to_string()));
+            return;
+        };
+
+        let run_loop = CFRunLoop::get_current();
+        unsafe {
+            run_loop.add_source(&source, kCFRunLoopCommonModes);
+        }
+        tap.enable();
+        RUN_LOOP.store(run_loop.as_concrete_TypeRef() as usize, Ordering::Relaxed);
+        let _ = ready_tx.send(Ok(()));
+
+        // Blocks until stop_hook() stops this run loop
+        CFRunLoop::run_current();
+    });
+
+    ready_rx
+        .recv()
+        .map_err(|_| "event tap thread exited".to_string())?
+}
+
+pub(super) fn stop_hook() {
+    let run_loop = RUN_LOOP.swap(0, Ordering::Relaxed);
+    if run_loop != 0 {
+        unsafe {
+            CFRunLoop::wrap_under_get_rule(run_loop as CFRunLoopRef).stop();
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    TARGETS.lock().unwrap().clear();
+    clear_pressed();
+}
+
+// ── Entry point ─────────────────────────────────────────────────────────────
+
+pub(super) fn start(bindings: &[KeyBinding]) -> Result<(), String> {
+    // Each binding is either a keyboard key or a mouse button
+    let targets = bindings
+        .iter()
+        .map(|b| {
+            let target = code_to_keycode(&b.code)
+                .map(Target::Key)
+                .or_else(|| code_to_mouse(&b.code).map(Target::Mouse))
+                .ok_or_else(|| format!("Unknown key code: {}", b.code))?;
+            Ok((target, b.id.clone()))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    // Stop any existing tap first so its teardown can't clobber the new targets
+    stop_hook();
+    *TARGETS.lock().unwrap() = targets;
+    start_hook()
+}
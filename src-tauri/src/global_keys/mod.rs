@@ -0,0 +1,105 @@
+//! System-wide key binding listener.
+//!
+//! The frontend registers its bindings (push-to-talk, mute/deafen toggles,
+//! overlay) as binding id + key code pairs. Each platform backend watches for
+//! those keys or mouse buttons, side buttons included, and emits
+//! `global-key` with the binding id and a `down` / `up` phase to the
+//! frontend, even while the window is unfocused.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+use tauri::Emitter;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(windows)]
+mod win;
+
+#[cfg(target_os = "linux")]
+use linux as platform;
+#[cfg(target_os = "macos")]
+use macos as platform;
+#[cfg(windows)]
+use win as platform;
+
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+/// Ids of the bindings currently held down, so autorepeat and duplicate
+/// releases don't emit twice.
+static PRESSED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// One binding from the frontend: what it's for, and the
+/// `KeyboardEvent.code` (or "Mouse0".."Mouse4") that triggers it.
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyBinding {
+    pub id: String,
+    pub code: String,
+}
+
+#[derive(Clone, Serialize)]
+struct GlobalKeyEvent<'a> {
+    binding: &'a str,
+    phase: &'static str,
+}
+
+/// Store the AppHandle so the backend callbacks can emit events.
+pub fn init(app: &tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app.clone());
+}
+
+/// Record a binding's key going down or up, emitting only on a change.
+fn set_pressed(id: &str, is_down: bool) {
+    let changed = {
+        let mut pressed = PRESSED.lock().unwrap();
+        let pressed = pressed.get_or_insert_with(HashSet::new);
+        if is_down {
+            pressed.insert(id.to_string())
+        } else {
+            pressed.remove(id)
+        }
+    };
+    if !changed {
+        return;
+    }
+    if let Some(app) = APP_HANDLE.get() {
+        let phase = if is_down { "down" } else { "up" };
+        let _ = app.emit("global-key", GlobalKeyEvent { binding: id, phase });
+    }
+}
+
+fn clear_pressed() {
+    *PRESSED.lock().unwrap() = None;
+}
+
+/// Convert a "Mouse0".."Mouse4" code to our internal mouse button id (1-5).
+fn code_to_mouse(code: &str) -> Option<u32> {
+    match code {
+        "Mouse0" => Some(1), // left
+        "Mouse1" => Some(2), // middle
+        "Mouse2" => Some(3), // right
+        "Mouse3" => Some(4), // X1 (back / thumb)
+        "Mouse4" => Some(5), // X2 (forward / thumb)
+        _ => None,
+    }
+}
+
+// ── Tauri commands ──────────────────────────────────────────────────────────
+
+/// Replace the set of bindings being listened for. An empty list stops
+/// listening.
+#[tauri::command]
+pub fn set_global_key_bindings(bindings: Vec<KeyBinding>) -> Result<(), String> {
+    if bindings.is_empty() {
+        platform::stop_hook();
+        return Ok(());
+    }
+    platform::start(&bindings)
+}
+
+#[tauri::command]
+pub fn stop_global_key_listen() {
+    platform::stop_hook();
+}
@@ -1,23 +1,23 @@
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
-use std::sync::OnceLock;
-use tauri::Emitter;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
 use windows::Win32::Foundation::*;
 use windows::Win32::System::LibraryLoader::GetModuleHandleW;
 use windows::Win32::UI::WindowsAndMessaging::*;
 
-static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
-/// Non-zero when the target is a keyboard key (stores the VK code).
-static TARGET_VK: AtomicU32 = AtomicU32::new(0);
-/// Non-zero when the target is a mouse button (1=left, 2=middle, 3=right, 4=X1, 5=X2).
-static TARGET_MOUSE: AtomicU32 = AtomicU32::new(0);
-static IS_PRESSED: AtomicBool = AtomicBool::new(false);
-static HOOK_THREAD_ID: AtomicU32 = AtomicU32::new(0);
+use super::{clear_pressed, code_to_mouse, set_pressed, KeyBinding};
 
-/// Store the AppHandle so the hook callbacks can emit events.
-pub fn init(app: &tauri::AppHandle) {
-    let _ = APP_HANDLE.set(app.clone());
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Target {
+    /// A keyboard key, by VK code.
+    Key(u32),
+    /// A mouse button (1=left, 2=middle, 3=right, 4=X1, 5=X2).
+    Mouse(u32),
 }
 
+/// What each registered binding listens for.
+static TARGETS: Mutex<Vec<(Target, String)>> = Mutex::new(Vec::new());
+static HOOK_THREAD_ID: AtomicU32 = AtomicU32::new(0);
+
 /// Convert a KeyboardEvent.code string to a Windows virtual key code.
 fn code_to_vk(code: &str) -> Option<u32> {
     // Letters: "KeyA" .. "KeyZ"
@@ -93,41 +93,30 @@ fn code_to_vk(code: &str) -> Option<u32> {
     }
 }
 
-/// Convert a "Mouse0".."Mouse4" code to our internal mouse button id (1-5).
-fn code_to_mouse(code: &str) -> Option<u32> {
-    match code {
-        "Mouse0" => Some(1), // left
-        "Mouse1" => Some(2), // middle
-        "Mouse2" => Some(3), // right
-        "Mouse3" => Some(4), // X1 (back / thumb)
-        "Mouse4" => Some(5), // X2 (forward / thumb)
-        _ => None,
-    }
-}
+// ── Keyboard hook ───────────────────────────────────────────────────────────
 
-fn emit(event: &str) {
-    if let Some(app) = APP_HANDLE.get() {
-        let _ = app.emit(event, ());
+/// Press or release every binding on `target`.
+fn dispatch(target: Target, is_down: bool) {
+    let ids: Vec<String> = TARGETS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(t, _)| *t == target)
+        .map(|(_, id)| id.clone())
+        .collect();
+    for id in ids {
+        set_pressed(&id, is_down);
     }
 }
 
-// ── Keyboard hook ───────────────────────────────────────────────────────────
-
 unsafe extern "system" fn keyboard_hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
     if code >= 0 {
         let kb = unsafe { &*(lparam.0 as *const KBDLLHOOKSTRUCT) };
-        let target = TARGET_VK.load(Ordering::Relaxed);
-
-        if target != 0 && kb.vkCode == target {
-            let msg = wparam.0 as u32;
-            if msg == WM_KEYDOWN || msg == WM_SYSKEYDOWN {
-                if !IS_PRESSED.swap(true, Ordering::Relaxed) {
-                    emit("global-key-down");
-                }
-            } else if msg == WM_KEYUP || msg == WM_SYSKEYUP {
-                IS_PRESSED.store(false, Ordering::Relaxed);
-                emit("global-key-up");
-            }
+        let msg = wparam.0 as u32;
+        if msg == WM_KEYDOWN || msg == WM_SYSKEYDOWN {
+            dispatch(Target::Key(kb.vkCode), true);
+        } else if msg == WM_KEYUP || msg == WM_SYSKEYUP {
+            dispatch(Target::Key(kb.vkCode), false);
         }
     }
     unsafe { CallNextHookEx(HHOOK::default(), code, wparam, lparam) }
@@ -159,21 +148,9 @@ fn classify_mouse_msg(msg: u32, mouse_data: u32) -> Option<(u32, bool)> {
 
 unsafe extern "system" fn mouse_hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
     if code >= 0 {
-        let target = TARGET_MOUSE.load(Ordering::Relaxed);
-        if target != 0 {
-            let ms = unsafe { &*(lparam.0 as *const MSLLHOOKSTRUCT) };
-            if let Some((btn, is_down)) = classify_mouse_msg(wparam.0 as u32, ms.mouseData) {
-                if btn == target {
-                    if is_down {
-                        if !IS_PRESSED.swap(true, Ordering::Relaxed) {
-                            emit("global-key-down");
-                        }
-                    } else {
-                        IS_PRESSED.store(false, Ordering::Relaxed);
-                        emit("global-key-up");
-                    }
-                }
-            }
+        let ms = unsafe { &*(lparam.0 as *const MSLLHOOKSTRUCT) };
+        if let Some((btn, is_down)) = classify_mouse_msg(wparam.0 as u32, ms.mouseData) {
+            dispatch(Target::Mouse(btn), is_down);
         }
     }
     unsafe { CallNextHookEx(HHOOK::default(), code, wparam, lparam) }
@@ -182,13 +159,13 @@ unsafe extern "system" fn mouse_hook_proc(code: i32, wparam: WPARAM, lparam: LPA
 // ── Hook lifecycle ──────────────────────────────────────────────────────────
 
 fn start_hook() {
-    // Stop any existing hook first
-    stop_hook();
-
-    IS_PRESSED.store(false, Ordering::Relaxed);
-
-    let need_keyboard = TARGET_VK.load(Ordering::Relaxed) != 0;
-    let need_mouse = TARGET_MOUSE.load(Ordering::Relaxed) != 0;
+    let (need_keyboard, need_mouse) = {
+        let targets = TARGETS.lock().unwrap();
+        (
+            targets.iter().any(|(t, _)| matches!(t, Target::Key(_))),
+            targets.iter().any(|(t, _)| matches!(t, Target::Mouse(_))),
+        )
+    };
     if !need_keyboard && !need_mouse {
         return;
     }
@@ -220,44 +197,40 @@ fn start_hook() {
 
             if let Some(h) = kb_hook { let _ = UnhookWindowsHookEx(h); }
             if let Some(h) = mouse_hook { let _ = UnhookWindowsHookEx(h); }
-
-            HOOK_THREAD_ID.store(0, Ordering::Relaxed);
-            TARGET_VK.store(0, Ordering::Relaxed);
-            TARGET_MOUSE.store(0, Ordering::Relaxed);
-            IS_PRESSED.store(false, Ordering::Relaxed);
         }
     });
 }
 
-fn stop_hook() {
-    let tid = HOOK_THREAD_ID.load(Ordering::Relaxed);
+pub(super) fn stop_hook() {
+    let tid = HOOK_THREAD_ID.swap(0, Ordering::Relaxed);
     if tid != 0 {
         unsafe {
             windows::Win32::UI::WindowsAndMessaging::PostThreadMessageW(tid, WM_QUIT, WPARAM(0), LPARAM(0)).ok();
         }
         std::thread::sleep(std::time::Duration::from_millis(50));
     }
+    TARGETS.lock().unwrap().clear();
+    clear_pressed();
 }
 
-// ── Tauri commands ──────────────────────────────────────────────────────────
-
-#[tauri::command]
-pub fn start_global_key_listen(key_code: String) -> Result<(), String> {
-    // Determine if this is a keyboard key or mouse button
-    if let Some(vk) = code_to_vk(&key_code) {
-        TARGET_VK.store(vk, Ordering::Relaxed);
-        TARGET_MOUSE.store(0, Ordering::Relaxed);
-    } else if let Some(mb) = code_to_mouse(&key_code) {
-        TARGET_VK.store(0, Ordering::Relaxed);
-        TARGET_MOUSE.store(mb, Ordering::Relaxed);
-    } else {
-        return Err(format!("Unknown key code: {key_code}"));
-    }
+// ── Entry point ─────────────────────────────────────────────────────────────
+
+pub(super) fn start(bindings: &[KeyBinding]) -> Result<(), String> {
+    // Each binding is either a keyboard key or a mouse button
+    let targets = bindings
+        .iter()
+        .map(|b| {
+            let target = code_to_vk(&b.code)
+                .map(Target::Key)
+                .or_else(|| code_to_mouse(&b.code).map(Target::Mouse))
+                .ok_or_else(|| format!("Unknown key code: {}", b.code))?;
+            Ok((target, b.id.clone()))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    // Stop any existing hook first so its teardown can't clobber the new targets
+    stop_hook();
+    *TARGETS.lock().unwrap() = targets;
     start_hook();
     Ok(())
 }
-
-#[tauri::command]
-pub fn stop_global_key_listen() {
-    stop_hook();
-}
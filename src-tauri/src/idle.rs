@@ -0,0 +1,130 @@
+//! Milliseconds since the last keyboard/mouse input anywhere on the system,
+//! used to flip the user to "idle" status automatically.
+
+#[cfg(windows)]
+pub fn system_idle_ms() -> u64 {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    unsafe {
+        let mut lii = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        let _ = GetLastInputInfo(&mut lii);
+        let now = GetTickCount();
+        now.wrapping_sub(lii.dwTime) as u64
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn system_idle_ms() -> u64 {
+    /// kCGEventSourceStateHIDSystemState
+    const HID_SYSTEM_STATE: i32 = 1;
+    /// kCGAnyInputEventType
+    const ANY_INPUT_EVENT: u32 = !0;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state_id: i32, event_type: u32) -> f64;
+    }
+
+    let secs = unsafe { CGEventSourceSecondsSinceLastEventType(HID_SYSTEM_STATE, ANY_INPUT_EVENT) };
+    (secs * 1000.0) as u64
+}
+
+#[cfg(target_os = "linux")]
+pub fn system_idle_ms() -> u64 {
+    linux::x11_idle_ms().unwrap_or_else(linux::evdev_idle_ms)
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+pub fn system_idle_ms() -> u64 {
+    0
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::os::fd::AsRawFd;
+    use std::sync::{Mutex, OnceLock};
+    use std::time::Instant;
+    use x11_dl::{xlib::Xlib, xss::Xss};
+
+    /// Ask the X server via the MIT-SCREEN-SAVER extension. Libraries are loaded
+    /// at runtime, so this returns None on Wayland sessions or when libXss is absent.
+    pub fn x11_idle_ms() -> Option<u64> {
+        std::env::var_os("DISPLAY")?;
+        let xlib = Xlib::open().ok()?;
+        let xss = Xss::open().ok()?;
+        unsafe {
+            let display = (xlib.XOpenDisplay)(std::ptr::null());
+            if display.is_null() {
+                return None;
+            }
+            let info = (xss.XScreenSaverAllocInfo)();
+            let root = (xlib.XDefaultRootWindow)(display);
+            let status = (xss.XScreenSaverQueryInfo)(display, root, info);
+            let idle = u64::from((*info).idle);
+            (xlib.XFree)(info as *mut _);
+            (xlib.XCloseDisplay)(display);
+            (status != 0).then_some(idle)
+        }
+    }
+
+    static LAST_INPUT: OnceLock<Mutex<Instant>> = OnceLock::new();
+
+    /// Wayland has no portable idle query, so watch /dev/input ourselves. The
+    /// watcher starts on first call; until input arrives we count from then.
+    /// Without access to the input devices this always reads as active.
+    pub fn evdev_idle_ms() -> u64 {
+        let last = LAST_INPUT.get_or_init(|| {
+            std::thread::spawn(watch_input_devices);
+            Mutex::new(Instant::now())
+        });
+        last.lock()
+            .map(|t| t.elapsed().as_millis() as u64)
+            .unwrap_or(0)
+    }
+
+    fn watch_input_devices() {
+        let mut devices: Vec<evdev::Device> = evdev::enumerate()
+            .map(|(_, device)| device)
+            .filter(|device| device.supported_keys().is_some())
+            .collect();
+        if devices.is_empty() {
+            return;
+        }
+
+        loop {
+            let mut fds: Vec<libc::pollfd> = devices
+                .iter()
+                .map(|d| libc::pollfd { fd: d.as_raw_fd(), events: libc::POLLIN, revents: 0 })
+                .collect();
+            let ready = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1) };
+            if ready <= 0 {
+                continue;
+            }
+
+            let mut saw_input = false;
+            for (device, pfd) in devices.iter_mut().zip(&fds) {
+                if pfd.revents & libc::POLLIN != 0 {
+                    if let Ok(events) = device.fetch_events() {
+                        saw_input |= events.count() > 0;
+                    }
+                }
+            }
+            // Drop unplugged devices so poll() doesn't spin on them
+            let mut revents = fds.iter().map(|pfd| pfd.revents);
+            devices.retain(|_| revents.next().unwrap_or(0) & (libc::POLLHUP | libc::POLLERR) == 0);
+            if devices.is_empty() {
+                return;
+            }
+            if saw_input {
+                if let Some(last) = LAST_INPUT.get() {
+                    if let Ok(mut t) = last.lock() {
+                        *t = Instant::now();
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,82 +1,25 @@
 mod activity;
+mod audio_loopback;
+mod autostart;
 mod capture;
-#[cfg(windows)]
+mod clipboard;
+mod deep_link;
+#[cfg(any(windows, target_os = "linux", target_os = "macos"))]
 mod global_keys;
-
-use std::io::{Read, Write};
-use std::net::TcpListener;
-use tauri::{Emitter, Manager};
-use tauri_plugin_updater::UpdaterExt;
-
-/// Check for updates from a specific endpoint URL.
-/// Returns update metadata if available, or null if already up to date.
-#[tauri::command]
-async fn check_for_update(
-    app: tauri::AppHandle,
-    endpoint: String,
-) -> Result<Option<serde_json::Value>, String> {
-    let url: url::Url = endpoint.parse().map_err(|e| format!("invalid endpoint: {e}"))?;
-    let updater = app
-        .updater_builder()
-        .endpoints(vec![url])
-        .map_err(|e| e.to_string())?
-        .build()
-        .map_err(|e| e.to_string())?;
-
-    match updater.check().await {
-        Ok(Some(update)) => Ok(Some(serde_json::json!({
-            "version": update.version,
-            "body": update.body,
-        }))),
-        Ok(None) => Ok(None),
-        Err(e) => Err(e.to_string()),
-    }
-}
-
-/// Download and install an update from a specific endpoint URL.
-/// Emits `update-progress` events with { chunk, total } during download.
-#[tauri::command]
-async fn download_and_install_update(
-    app: tauri::AppHandle,
-    endpoint: String,
-) -> Result<(), String> {
-    let url: url::Url = endpoint.parse().map_err(|e| format!("invalid endpoint: {e}"))?;
-    let updater = app
-        .updater_builder()
-        .endpoints(vec![url])
-        .map_err(|e| e.to_string())?
-        .build()
-        .map_err(|e| e.to_string())?;
-
-    let update = updater
-        .check()
-        .await
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "No update available".to_string())?;
-
-    let handle = app.clone();
-    let mut downloaded: usize = 0;
-
-    update
-        .download_and_install(
-            move |chunk_len, total| {
-                downloaded += chunk_len;
-                let _ = handle.emit(
-                    "update-progress",
-                    serde_json::json!({ "downloaded": downloaded, "total": total }),
-                );
-            },
-            || {},
-        )
-        .await
-        .map_err(|e| e.to_string())
-}
-
-// TODO: implement
-#[tauri::command]
-fn set_titlebar_color(window: tauri::Window, color: String) {
-    let _ = (window, color);
-}
+mod idle;
+mod logging;
+mod message_cache;
+mod notifications;
+mod oauth;
+mod overlay;
+mod screen_share;
+mod spellcheck;
+mod taskbar;
+mod tray;
+mod updater;
+mod window_theme;
+
+use tauri::Manager;
 
 #[tauri::command]
 async fn open_popout_window(app: tauri::AppHandle, window_type: String) -> Result<(), String> {
@@ -111,128 +54,36 @@ fn get_capture_sources() -> Vec<capture::CaptureSource> {
     capture::get_sources()
 }
 
-#[cfg(windows)]
+/// Start streaming frames from a source returned by `get_capture_sources`.
+/// Frames arrive as `capture-frame` events; lifecycle as `capture-state`.
 #[tauri::command]
-fn get_system_idle_ms() -> u64 {
-    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
-    use windows::Win32::System::SystemInformation::GetTickCount;
-    unsafe {
-        let mut lii = LASTINPUTINFO {
-            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
-            dwTime: 0,
-        };
-        let _ = GetLastInputInfo(&mut lii);
-        let now = GetTickCount();
-        now.wrapping_sub(lii.dwTime) as u64
-    }
+fn start_capture(
+    app: tauri::AppHandle,
+    source_id: String,
+    fps: Option<u32>,
+    max_width: Option<u32>,
+) -> Result<(), String> {
+    screen_share::start(
+        app,
+        source_id,
+        fps.unwrap_or(screen_share::DEFAULT_FPS),
+        max_width.unwrap_or(screen_share::DEFAULT_MAX_WIDTH),
+    )
 }
 
-#[cfg(not(windows))]
 #[tauri::command]
-fn get_system_idle_ms() -> u64 {
-    0
+fn stop_capture(app: tauri::AppHandle) {
+    screen_share::stop(&app);
 }
 
 #[tauri::command]
-fn detect_activity() -> Option<activity::DetectedActivity> {
-    activity::detect_activity()
+fn get_system_idle_ms() -> u64 {
+    idle::system_idle_ms()
 }
 
-/// One-shot HTTP server on 127.0.0.1:29170 that catches an OAuth redirect.
-/// Spotify redirects the browser here; we extract code+state, then redirect
-/// the browser to the backend's GET /api/spotify/callback for token exchange.
 #[tauri::command]
-async fn start_oauth_listener(server_url: String) -> Result<serde_json::Value, String> {
-    let handle = std::thread::spawn(move || -> Result<serde_json::Value, String> {
-        let listener = TcpListener::bind("127.0.0.1:29170").map_err(|e| format!("bind: {e}"))?;
-
-        // Block until Spotify redirects here (frontend has its own 5-min timeout)
-        let (mut stream, _) = listener.accept().map_err(|e| format!("accept: {e}"))?;
-
-        let mut buf = [0u8; 8192];
-        let n = stream.read(&mut buf).map_err(|e| format!("read: {e}"))?;
-        let request = String::from_utf8_lossy(&buf[..n]);
-
-        // Parse "GET /callback?code=...&state=... HTTP/1.1"
-        let path = request.lines().next().unwrap_or("")
-            .split_whitespace().nth(1).unwrap_or("");
-        let query = path.split_once('?').map(|(_, q)| q).unwrap_or("");
-
-        let mut code = String::new();
-        let mut state = String::new();
-        let mut error = String::new();
-        for pair in query.split('&') {
-            if let Some((k, v)) = pair.split_once('=') {
-                match k {
-                    "code" => code = urldecode(v),
-                    "state" => state = urldecode(v),
-                    "error" => error = urldecode(v),
-                    _ => {}
-                }
-            }
-        }
-
-        // Redirect browser to backend's GET callback (which does the token exchange)
-        let response = if !error.is_empty() {
-            format!(
-                "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nConnection: close\r\n\r\n\
-                <html><body style=\"background:#1a1a2e;color:#fff;font-family:system-ui;\
-                display:flex;align-items:center;justify-content:center;height:100vh;margin:0\">\
-                <div style=\"text-align:center\"><h2>Spotify Authorization Failed</h2>\
-                <p>{error}</p><p>You can close this tab.</p></div></body></html>"
-            )
-        } else {
-            let backend_url = format!(
-                "{}/api/spotify/callback?code={}&state={}",
-                server_url.trim_end_matches('/'),
-                urlencode(&code),
-                urlencode(&state),
-            );
-            format!("HTTP/1.1 302 Found\r\nLocation: {backend_url}\r\nConnection: close\r\n\r\n")
-        };
-
-        let _ = stream.write_all(response.as_bytes());
-        let _ = stream.flush();
-
-        Ok(serde_json::json!({ "code": code, "state": state, "error": error }))
-    });
-
-    // Await the thread on the async runtime without blocking it
-    tauri::async_runtime::spawn_blocking(move || {
-        handle.join().map_err(|_| "listener thread panicked".to_string())?
-    })
-    .await
-    .map_err(|e| format!("spawn: {e}"))?
-}
-
-fn urldecode(s: &str) -> String {
-    let mut out = String::with_capacity(s.len());
-    let mut bytes = s.bytes();
-    while let Some(b) = bytes.next() {
-        match b {
-            b'%' => {
-                let hi = bytes.next().unwrap_or(b'0');
-                let lo = bytes.next().unwrap_or(b'0');
-                if let Ok(val) = u8::from_str_radix(&format!("{}{}", hi as char, lo as char), 16) {
-                    out.push(val as char);
-                }
-            }
-            b'+' => out.push(' '),
-            _ => out.push(b as char),
-        }
-    }
-    out
-}
-
-fn urlencode(s: &str) -> String {
-    let mut out = String::with_capacity(s.len());
-    for b in s.bytes() {
-        match b {
-            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(b as char),
-            _ => { out.push_str(&format!("%{:02X}", b)); }
-        }
-    }
-    out
+fn detect_activity() -> Option<activity::DetectedActivity> {
+    activity::detect_activity()
 }
 
 #[cfg(windows)]
@@ -280,39 +131,96 @@ pub fn run() {
             let _ = SetCurrentProcessExplicitAppUserModelID(w!("com.flux.app"));
         }
         register_aumid_in_registry();
+        spellcheck::apply_before_launch("com.flux.app");
     }
 
     tauri::Builder::default()
+        // Must be registered first: a second launch (e.g. clicking a flux:// link)
+        // forwards its argv here and exits; the deep-link feature re-emits the URL.
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            tray::show_main_window(app);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_notification::init())
         .invoke_handler(tauri::generate_handler![
-            check_for_update,
-            download_and_install_update,
-            set_titlebar_color,
+            updater::check_for_update,
+            updater::download_and_install_update,
+            updater::confirm_update_launch,
+            updater::start_update_poll,
+            updater::stop_update_poll,
+            window_theme::set_titlebar_color,
+            window_theme::set_window_effects,
             open_popout_window,
             close_popout_window,
+            overlay::toggle_overlay,
+            overlay::set_overlay_corner,
+            overlay::update_overlay,
+            overlay::get_overlay_state,
             get_capture_sources,
+            clipboard::get_clipboard_image,
+            start_capture,
+            stop_capture,
+            audio_loopback::get_loopback_devices,
+            audio_loopback::start_audio_loopback,
+            audio_loopback::stop_audio_loopback,
             detect_activity,
             get_system_idle_ms,
-            start_oauth_listener,
-            #[cfg(windows)]
-            global_keys::start_global_key_listen,
-            #[cfg(windows)]
+            oauth::start_oauth_listener,
+            oauth::cancel_oauth_listener,
+            tray::set_tray_badge,
+            tray::set_minimize_to_tray,
+            taskbar::set_taskbar_progress,
+            taskbar::set_taskbar_badge,
+            taskbar::flash_window,
+            deep_link::take_pending_deep_link,
+            autostart::set_autostart,
+            autostart::get_autostart_status,
+            notifications::show_message_notification,
+            message_cache::cache_messages,
+            message_cache::get_cached_messages,
+            message_cache::clear_cache,
+            logging::get_recent_logs,
+            logging::submit_crash_report,
+            spellcheck::get_spellcheck_settings,
+            spellcheck::set_spellcheck_settings,
+            #[cfg(any(windows, target_os = "linux", target_os = "macos"))]
+            global_keys::set_global_key_bindings,
+            #[cfg(any(windows, target_os = "linux", target_os = "macos"))]
             global_keys::stop_global_key_listen,
         ])
         .setup(|_app| {
-            #[cfg(windows)]
+            // First, so everything after (including setup failures) is captured
+            logging::init(_app.handle())?;
+            #[cfg(any(windows, target_os = "linux", target_os = "macos"))]
             {
                 global_keys::init(_app.handle());
             }
+            tray::init(_app.handle())?;
+            deep_link::init(_app.handle())?;
+            message_cache::init(_app.handle())?;
+            updater::init(_app.handle());
+            spellcheck::init(_app.handle());
             // Open devtools (F12 / Ctrl+Shift+I) — enabled in all builds via "devtools" feature
             if let Some(window) = _app.get_webview_window("main") {
                 window.open_devtools();
+                // Launched at login with "start minimized": live in the tray until opened
+                if autostart::launched_minimized() {
+                    let _ = window.hide();
+                }
             }
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                if window.label() == "main" && tray::should_hide_on_close() {
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+            }
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
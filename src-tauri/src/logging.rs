@@ -0,0 +1,204 @@
+//! File logging, panic capture and crash report submission.
+//!
+//! Logs go to `<app log dir>/flux.YYYY-MM-DD.log`, rotated daily with a week
+//! kept. A panic additionally writes `crash-<unix ts>.log` next to them so the
+//! report survives the process dying before the log writer flushes.
+
+use regex_lite::Regex;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+const LOG_PREFIX: &str = "flux";
+const LOG_SUFFIX: &str = "log";
+const MAX_LOG_FILES: usize = 7;
+const MAX_CRASH_FILES: usize = 5;
+const DEFAULT_LOG_LINES: usize = 500;
+
+static LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
+/// Keeps the non-blocking writer alive for the life of the process.
+static LOG_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+pub fn init(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = app.path().app_log_dir()?;
+    std::fs::create_dir_all(&dir)?;
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_PREFIX)
+        .filename_suffix(LOG_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir)?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    let _ = LOG_GUARD.set(guard);
+
+    tracing_subscriber::fmt()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "info,flux_tauri_lib=debug".into()),
+        )
+        .try_init()
+        .map_err(|e| e.to_string())?;
+
+    let _ = LOG_DIR.set(dir);
+    install_panic_hook();
+
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), os = std::env::consts::OS, "Flux started");
+    Ok(())
+}
+
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let backtrace = std::backtrace::Backtrace::force_capture();
+        let thread = std::thread::current();
+        let report = format!(
+            "version: {}\nos: {} {}\nthread: {}\n{}\n\nbacktrace:\n{}\n",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            thread.name().unwrap_or("<unnamed>"),
+            info,
+            backtrace,
+        );
+        tracing::error!("panic: {info}");
+
+        if let Some(dir) = LOG_DIR.get() {
+            let ts = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let _ = std::fs::write(dir.join(format!("crash-{ts}.log")), report);
+            prune_crash_files(dir);
+        }
+        default_hook(info);
+    }));
+}
+
+/// Files in `dir` whose name starts with `prefix` and ends with `suffix`, oldest first.
+/// Both log and crash names embed a sortable date/timestamp.
+fn list_files(dir: &Path, prefix: &str, suffix: &str) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(prefix) && n.ends_with(suffix))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn prune_crash_files(dir: &Path) {
+    let crashes = list_files(dir, "crash-", ".log");
+    let excess = crashes.len().saturating_sub(MAX_CRASH_FILES);
+    for path in &crashes[..excess] {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Last `lines` lines across the newest log files.
+fn tail_logs(dir: &Path, lines: usize) -> String {
+    let mut collected: Vec<String> = Vec::new();
+    for path in list_files(dir, &format!("{LOG_PREFIX}."), &format!(".{LOG_SUFFIX}"))
+        .iter()
+        .rev()
+    {
+        let Ok(content) = std::fs::read_to_string(path) else { continue };
+        let mut file_lines: Vec<String> = content.lines().map(str::to_string).collect();
+        file_lines.append(&mut collected);
+        collected = file_lines;
+        if collected.len() >= lines {
+            break;
+        }
+    }
+    let start = collected.len().saturating_sub(lines);
+    collected[start..].join("\n")
+}
+
+/// Strip credentials and personal details before anything leaves the machine.
+fn redact(text: &str) -> String {
+    static PATTERNS: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(|| {
+        vec![
+            (Regex::new(r"(?i)(bearer\s+)[A-Za-z0-9._~+/=-]+").unwrap(), "${1}[redacted]"),
+            (
+                Regex::new(r#"(?i)((?:token|session_token|access_token|refresh_token|code|state|secret|password)["']?\s*[=:]\s*["']?)[^&\s"',}]+"#).unwrap(),
+                "${1}[redacted]",
+            ),
+            (Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap(), "[email]"),
+        ]
+    });
+
+    let mut out = text.to_string();
+    for (re, replacement) in patterns {
+        out = re.replace_all(&out, *replacement).into_owned();
+    }
+    // Home directory paths carry the OS username
+    if let Some(home) = std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" }) {
+        let home = home.to_string_lossy();
+        if !home.is_empty() {
+            out = out.replace(home.as_ref(), "~");
+        }
+    }
+    out
+}
+
+// ── Tauri commands ──────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_recent_logs(lines: Option<usize>) -> String {
+    LOG_DIR
+        .get()
+        .map(|dir| tail_logs(dir, lines.unwrap_or(DEFAULT_LOG_LINES)))
+        .unwrap_or_default()
+}
+
+/// POST a redacted bundle of recent logs and crash dumps to `endpoint`.
+/// Crash files are removed once the server accepts them.
+#[tauri::command]
+pub async fn submit_crash_report(
+    endpoint: String,
+    description: Option<String>,
+) -> Result<(), String> {
+    let dir = LOG_DIR.get().ok_or("logging is not initialized")?.clone();
+
+    let crash_files = list_files(&dir, "crash-", ".log");
+    let crashes: Vec<String> = crash_files
+        .iter()
+        .filter_map(|p| std::fs::read_to_string(p).ok())
+        .map(|c| redact(&c))
+        .collect();
+
+    let bundle = serde_json::json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "description": description.map(|d| redact(&d)),
+        "logs": redact(&tail_logs(&dir, DEFAULT_LOG_LINES)),
+        "crashes": crashes,
+    });
+
+    let resp = reqwest::Client::new()
+        .post(&endpoint)
+        .json(&bundle)
+        .timeout(std::time::Duration::from_secs(30))
+        .send()
+        .await
+        .map_err(|e| format!("submit: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!("submit: server returned {}", resp.status()));
+    }
+
+    for path in crash_files {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(())
+}
@@ -0,0 +1,150 @@
+//! Local SQLite cache of recent channel messages.
+//!
+//! Lets the client paint the last-seen history instantly on startup and scroll
+//! back while offline. Messages are stored as the JSON the server returned;
+//! once the gateway resumes the client re-fetches and calls `cache_messages`
+//! with `replace: true`, making the server authoritative for that time window.
+
+use rusqlite::{params, Connection};
+use serde_json::Value;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+/// Messages kept per channel; older ones are trimmed on every write.
+const MAX_PER_CHANNEL: i64 = 500;
+const DEFAULT_LIMIT: i64 = 50;
+
+pub struct MessageCache(Mutex<Connection>);
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS messages (
+    id TEXT PRIMARY KEY,
+    channel_id TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_messages_channel_time ON messages(channel_id, created_at);
+";
+
+pub fn init(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = app.path().app_data_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    let conn = Connection::open(dir.join("message_cache.db"))?;
+    conn.execute_batch("PRAGMA journal_mode = WAL;")?;
+    conn.execute_batch(SCHEMA)?;
+    app.manage(MessageCache(Mutex::new(conn)));
+    Ok(())
+}
+
+fn store(
+    conn: &mut Connection,
+    channel_id: &str,
+    messages: &[Value],
+    replace: bool,
+) -> rusqlite::Result<()> {
+    // Messages without an id/createdAt can't be ordered or deduplicated
+    let rows: Vec<(&str, &str, String)> = messages
+        .iter()
+        .filter_map(|m| {
+            let id = m.get("id")?.as_str()?;
+            let created_at = m.get("createdAt")?.as_str()?;
+            Some((id, created_at, m.to_string()))
+        })
+        .collect();
+
+    let tx = conn.transaction()?;
+
+    // The batch is a complete server page: anything cached inside its time
+    // window that isn't in it was deleted server-side while we were away.
+    if replace {
+        if let (Some(oldest), Some(newest)) = (
+            rows.iter().map(|r| r.1).min(),
+            rows.iter().map(|r| r.1).max(),
+        ) {
+            let ids = serde_json::to_string(&rows.iter().map(|r| r.0).collect::<Vec<_>>())
+                .unwrap_or_else(|_| "[]".into());
+            tx.execute(
+                "DELETE FROM messages
+                 WHERE channel_id = ?1 AND created_at >= ?2 AND created_at <= ?3
+                   AND id NOT IN (SELECT value FROM json_each(?4))",
+                params![channel_id, oldest, newest, ids],
+            )?;
+        }
+    }
+
+    for (id, created_at, data) in &rows {
+        tx.execute(
+            "INSERT INTO messages (id, channel_id, created_at, data) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(id) DO UPDATE SET data = excluded.data",
+            params![id, channel_id, created_at, data],
+        )?;
+    }
+
+    tx.execute(
+        "DELETE FROM messages WHERE channel_id = ?1 AND id NOT IN (
+             SELECT id FROM messages WHERE channel_id = ?1
+             ORDER BY created_at DESC LIMIT ?2
+         )",
+        params![channel_id, MAX_PER_CHANNEL],
+    )?;
+
+    tx.commit()
+}
+
+fn load(
+    conn: &Connection,
+    channel_id: &str,
+    limit: i64,
+    before: Option<&str>,
+) -> rusqlite::Result<Vec<Value>> {
+    let mut stmt = conn.prepare(
+        "SELECT data FROM messages
+         WHERE channel_id = ?1 AND (?2 IS NULL OR created_at < ?2)
+         ORDER BY created_at DESC LIMIT ?3",
+    )?;
+    let mut messages: Vec<Value> = stmt
+        .query_map(params![channel_id, before, limit], |row| row.get::<_, String>(0))?
+        .filter_map(|data| data.ok().and_then(|d| serde_json::from_str(&d).ok()))
+        .collect();
+    // Oldest first, matching the server's list order
+    messages.reverse();
+    Ok(messages)
+}
+
+// ── Tauri commands ──────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn cache_messages(
+    cache: State<'_, MessageCache>,
+    channel_id: String,
+    messages: Vec<Value>,
+    replace: Option<bool>,
+) -> Result<(), String> {
+    let mut conn = cache.0.lock().map_err(|_| "cache lock poisoned".to_string())?;
+    store(&mut conn, &channel_id, &messages, replace.unwrap_or(false)).map_err(|e| e.to_string())
+}
+
+/// Cached messages for a channel, oldest first. `before` is a createdAt cursor.
+#[tauri::command]
+pub fn get_cached_messages(
+    cache: State<'_, MessageCache>,
+    channel_id: String,
+    limit: Option<i64>,
+    before: Option<String>,
+) -> Result<Vec<Value>, String> {
+    let conn = cache.0.lock().map_err(|_| "cache lock poisoned".to_string())?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_PER_CHANNEL);
+    load(&conn, &channel_id, limit, before.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Drop one channel's cache, or everything (e.g. on sign-out) when omitted.
+#[tauri::command]
+pub fn clear_cache(cache: State<'_, MessageCache>, channel_id: Option<String>) -> Result<(), String> {
+    let conn = cache.0.lock().map_err(|_| "cache lock poisoned".to_string())?;
+    match channel_id {
+        Some(id) => conn.execute("DELETE FROM messages WHERE channel_id = ?1", params![id]),
+        None => conn.execute("DELETE FROM messages", []),
+    }
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,182 @@
+//! Actionable message notifications (inline reply + mark as read).
+//!
+//! The notification plugin only does plain toasts; these are shown natively so
+//! the user can answer a DM without focusing the app. Interactions come back to
+//! the webview as `notification_action` events:
+//! `{ action: "open" | "reply" | "read", tag, text? }` where `tag` is whatever
+//! the frontend passed in (typically the DM channel id).
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+#[derive(Serialize, Clone)]
+#[cfg_attr(not(any(windows, target_os = "macos")), allow(dead_code))]
+pub struct NotificationAction {
+    pub action: String,
+    pub tag: String,
+    pub text: Option<String>,
+}
+
+#[cfg_attr(not(any(windows, target_os = "macos")), allow(dead_code))]
+fn emit_action(app: &AppHandle, action: &str, tag: &str, text: Option<String>) {
+    let _ = app.emit(
+        "notification_action",
+        NotificationAction { action: action.into(), tag: tag.into(), text },
+    );
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::emit_action;
+    use tauri::AppHandle;
+    use windows::core::{IInspectable, Interface, HSTRING};
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::Foundation::{IPropertyValue, TypedEventHandler};
+    use windows::UI::Notifications::{
+        ToastActivatedEventArgs, ToastNotification, ToastNotificationManager,
+    };
+
+    fn xml_escape(s: &str) -> String {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;")
+    }
+
+    /// Parse the `action=...` out of a toast/button arguments string.
+    fn action_from_args(args: &str) -> &str {
+        args.split('&')
+            .find_map(|kv| kv.strip_prefix("action="))
+            .unwrap_or("open")
+    }
+
+    pub fn show(app: AppHandle, tag: String, title: String, body: String) -> Result<(), String> {
+        let toast_xml = format!(
+            r#"<toast launch="action=open" activationType="foreground">
+  <visual>
+    <binding template="ToastGeneric">
+      <text>{title}</text>
+      <text>{body}</text>
+    </binding>
+  </visual>
+  <actions>
+    <input id="reply" type="text" placeHolderContent="Reply"/>
+    <action content="Send" arguments="action=reply" hint-inputId="reply" activationType="foreground"/>
+    <action content="Mark as read" arguments="action=read" activationType="foreground"/>
+  </actions>
+</toast>"#,
+            title = xml_escape(&title),
+            body = xml_escape(&body),
+        );
+
+        let build = || -> windows::core::Result<()> {
+            let xml = XmlDocument::new()?;
+            xml.LoadXml(&HSTRING::from(toast_xml))?;
+            let toast = ToastNotification::CreateToastNotification(&xml)?;
+            toast.SetTag(&HSTRING::from(tag.as_str()))?;
+
+            let handle = app.clone();
+            let toast_tag = tag.clone();
+            toast.Activated(&TypedEventHandler::<ToastNotification, IInspectable>::new(
+                move |_, args| {
+                    let Some(args) = args.as_ref() else { return Ok(()) };
+                    let args: ToastActivatedEventArgs = args.cast()?;
+                    let arguments = args.Arguments()?.to_string();
+                    let action = action_from_args(&arguments);
+
+                    let text = if action == "reply" {
+                        args.UserInput()
+                            .and_then(|input| input.Lookup(&HSTRING::from("reply")))
+                            .and_then(|value| value.cast::<IPropertyValue>())
+                            .and_then(|value| value.GetString())
+                            .map(|s| s.to_string())
+                            .ok()
+                            .filter(|s| !s.trim().is_empty())
+                    } else {
+                        None
+                    };
+                    // An empty inline reply is just a click
+                    let action = if action == "reply" && text.is_none() { "open" } else { action };
+
+                    emit_action(&handle, action, &toast_tag, text);
+                    if action == "open" {
+                        crate::tray::show_main_window(&handle);
+                    }
+                    Ok(())
+                },
+            ))?;
+
+            // Same AUMID that run() registers, so toasts are attributed to Flux
+            let notifier =
+                ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from("com.flux.app"))?;
+            notifier.Show(&toast)
+        };
+        build().map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::emit_action;
+    use mac_notification_sys::{MainButton, Notification, NotificationResponse};
+    use std::sync::Once;
+    use tauri::AppHandle;
+
+    static SET_APPLICATION: Once = Once::new();
+
+    pub fn show(app: AppHandle, tag: String, title: String, body: String) -> Result<(), String> {
+        SET_APPLICATION.call_once(|| {
+            // Fails for unbundled dev builds; notifications then show as Terminal
+            let _ = mac_notification_sys::set_application("com.flux.app");
+        });
+
+        // send() blocks until the user interacts or the banner is dismissed
+        std::thread::spawn(move || {
+            let response = Notification::new()
+                .title(&title)
+                .message(&body)
+                .main_button(MainButton::Response("Reply"))
+                .close_button("Mark as read")
+                .wait_for_click(true)
+                .send();
+
+            match response {
+                Ok(NotificationResponse::Reply(text)) if !text.trim().is_empty() => {
+                    emit_action(&app, "reply", &tag, Some(text));
+                }
+                Ok(NotificationResponse::CloseButton(_)) => emit_action(&app, "read", &tag, None),
+                Ok(NotificationResponse::Click) | Ok(NotificationResponse::Reply(_)) => {
+                    emit_action(&app, "open", &tag, None);
+                    crate::tray::show_main_window(&app);
+                }
+                _ => {}
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod platform {
+    use tauri::AppHandle;
+
+    pub fn show(_app: AppHandle, _tag: String, _title: String, _body: String) -> Result<(), String> {
+        Err("Actionable notifications are not supported on this platform".into())
+    }
+}
+
+// ── Tauri commands ──────────────────────────────────────────────────────────
+
+/// Show a message notification with inline reply and mark-as-read actions.
+/// On unsupported platforms this errors and the frontend falls back to the
+/// plain notification plugin.
+#[tauri::command]
+pub fn show_message_notification(
+    app: AppHandle,
+    tag: String,
+    title: String,
+    body: String,
+) -> Result<(), String> {
+    platform::show(app, tag, title, body)
+}
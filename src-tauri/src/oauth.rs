@@ -0,0 +1,304 @@
+//! Loopback HTTP listener that catches OAuth redirects.
+//!
+//! The provider redirects the browser to `http://127.0.0.1:<port>/callback`;
+//! once the `state` checks out we bounce the browser on to the backend's
+//! `GET /api/<provider>/callback`, which does the token exchange. Ports are
+//! tried from `BASE_PORT` upwards, so every port in the range has to be
+//! registered as a redirect URI with the provider.
+//!
+//! One flow runs per provider; starting another for the same provider
+//! cancels the first. The outcome is emitted as an `oauth-callback` event:
+//! `{ provider, code, state, error }`.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+const BASE_PORT: u16 = 29170;
+const PORT_COUNT: u16 = 10;
+const DEFAULT_TIMEOUT_SECS: u64 = 300;
+const ACCEPT_POLL: Duration = Duration::from_millis(100);
+
+/// Cancel flags of the running flows, keyed by provider.
+static FLOWS: Mutex<Option<HashMap<String, Arc<AtomicBool>>>> = Mutex::new(None);
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthListener {
+    pub provider: String,
+    pub port: u16,
+    pub redirect_uri: String,
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct OAuthCallback {
+    pub provider: String,
+    pub code: String,
+    pub state: String,
+    pub error: String,
+}
+
+struct Flow {
+    app: AppHandle,
+    provider: String,
+    server_url: String,
+    expected_state: Option<String>,
+    deadline: Instant,
+    cancelled: Arc<AtomicBool>,
+}
+
+fn bind_in_range() -> Result<TcpListener, String> {
+    (BASE_PORT..BASE_PORT + PORT_COUNT)
+        .find_map(|port| TcpListener::bind(("127.0.0.1", port)).ok())
+        .ok_or_else(|| {
+            format!("bind: ports {BASE_PORT}-{} are all in use", BASE_PORT + PORT_COUNT - 1)
+        })
+}
+
+/// Replace the provider's running flow (if any) with `cancelled`.
+fn register_flow(provider: &str, cancelled: Arc<AtomicBool>) {
+    let mut flows = FLOWS.lock().unwrap_or_else(|e| e.into_inner());
+    let flows = flows.get_or_insert_with(HashMap::new);
+    if let Some(previous) = flows.insert(provider.to_string(), cancelled) {
+        previous.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Forget the provider's flow, unless it has already been replaced by a newer one.
+fn finish_flow(provider: &str, cancelled: &Arc<AtomicBool>) {
+    let mut flows = FLOWS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(flows) = flows.as_mut() {
+        if flows.get(provider).is_some_and(|c| Arc::ptr_eq(c, cancelled)) {
+            flows.remove(provider);
+        }
+    }
+}
+
+fn display_name(provider: &str) -> String {
+    let mut chars = provider.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn html_page(status: &str, heading: &str, detail: &str) -> String {
+    format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/html\r\nConnection: close\r\n\r\n\
+        <html><body style=\"background:#1a1a2e;color:#fff;font-family:system-ui;\
+        display:flex;align-items:center;justify-content:center;height:100vh;margin:0\">\
+        <div style=\"text-align:center\"><h2>{heading}</h2>\
+        <p>{detail}</p><p>You can close this tab.</p></div></body></html>",
+        heading = html_escape(heading),
+        detail = html_escape(detail),
+    )
+}
+
+/// Read up to the end of the request headers.
+fn read_request(stream: &mut TcpStream) -> Result<String, String> {
+    stream.set_nonblocking(false).map_err(|e| format!("read: {e}"))?;
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .map_err(|e| format!("read: {e}"))?;
+
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    while buf.len() < 8192 && !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut chunk).map_err(|e| format!("read: {e}"))?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+/// Handle one connection. Returns the callback once a request with the
+/// expected state arrives; stray requests (favicon, bad state) are answered
+/// and the flow keeps waiting.
+fn handle_connection(flow: &Flow, mut stream: TcpStream) -> Option<OAuthCallback> {
+    let request = read_request(&mut stream).ok()?;
+
+    // "GET /callback?code=...&state=... HTTP/1.1"
+    let target = request.lines().next().unwrap_or("").split_whitespace().nth(1).unwrap_or("");
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    if path != "/callback" {
+        let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nConnection: close\r\n\r\n");
+        return None;
+    }
+
+    let mut callback = OAuthCallback { provider: flow.provider.clone(), ..Default::default() };
+    for pair in query.split('&') {
+        if let Some((k, v)) = pair.split_once('=') {
+            match k {
+                "code" => callback.code = urldecode(v),
+                "state" => callback.state = urldecode(v),
+                "error" => callback.error = urldecode(v),
+                _ => {}
+            }
+        }
+    }
+
+    let name = display_name(&flow.provider);
+    if flow.expected_state.as_ref().is_some_and(|s| *s != callback.state) {
+        let page = html_page(
+            "400 Bad Request",
+            &format!("{name} Authorization Failed"),
+            "The login response didn't match this request. Please try again from the app.",
+        );
+        let _ = stream.write_all(page.as_bytes());
+        return None;
+    }
+
+    let response = if !callback.error.is_empty() {
+        html_page("200 OK", &format!("{name} Authorization Failed"), &callback.error)
+    } else {
+        // Redirect browser to backend's GET callback (which does the token exchange)
+        let backend_url = format!(
+            "{}/api/{}/callback?code={}&state={}",
+            flow.server_url.trim_end_matches('/'),
+            flow.provider,
+            urlencode(&callback.code),
+            urlencode(&callback.state),
+        );
+        format!("HTTP/1.1 302 Found\r\nLocation: {backend_url}\r\nConnection: close\r\n\r\n")
+    };
+    let _ = stream.write_all(response.as_bytes());
+    let _ = stream.flush();
+    Some(callback)
+}
+
+fn run(flow: Flow, listener: TcpListener) {
+    let outcome = loop {
+        if flow.cancelled.load(Ordering::SeqCst) {
+            break None;
+        }
+        if Instant::now() >= flow.deadline {
+            break Some(OAuthCallback {
+                provider: flow.provider.clone(),
+                error: "timeout".into(),
+                ..Default::default()
+            });
+        }
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if let Some(callback) = handle_connection(&flow, stream) {
+                    break Some(callback);
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => std::thread::sleep(ACCEPT_POLL),
+            Err(e) => {
+                break Some(OAuthCallback {
+                    provider: flow.provider.clone(),
+                    error: format!("accept: {e}"),
+                    ..Default::default()
+                })
+            }
+        }
+    };
+    // Free the port before anyone hears about the result
+    drop(listener);
+    finish_flow(&flow.provider, &flow.cancelled);
+
+    if let Some(callback) = outcome {
+        let _ = flow.app.emit("oauth-callback", callback);
+    }
+}
+
+fn urldecode(s: &str) -> String {
+    let mut out = Vec::with_capacity(s.len());
+    let mut bytes = s.bytes();
+    while let Some(b) = bytes.next() {
+        match b {
+            b'%' => {
+                let hi = bytes.next().unwrap_or(b'0');
+                let lo = bytes.next().unwrap_or(b'0');
+                if let Ok(val) = u8::from_str_radix(&format!("{}{}", hi as char, lo as char), 16) {
+                    out.push(val);
+                }
+            }
+            b'+' => out.push(b' '),
+            _ => out.push(b),
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn urlencode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(b as char),
+            _ => { out.push_str(&format!("%{:02X}", b)); }
+        }
+    }
+    out
+}
+
+// ── Tauri commands ──────────────────────────────────────────────────────────
+
+/// Bind a loopback listener for `provider` (default "spotify") and return the
+/// redirect URI to hand to the provider. The listener closes after the first
+/// valid callback, on timeout, or when cancelled.
+#[tauri::command]
+pub fn start_oauth_listener(
+    app: AppHandle,
+    server_url: String,
+    provider: Option<String>,
+    expected_state: Option<String>,
+    timeout_secs: Option<u64>,
+) -> Result<OAuthListener, String> {
+    let provider = provider.unwrap_or_else(|| "spotify".into());
+    // Goes into the backend URL path
+    if provider.is_empty()
+        || !provider.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("invalid provider: {provider}"));
+    }
+
+    // Cancel our own previous flow first so its port can be reused
+    let cancelled = Arc::new(AtomicBool::new(false));
+    register_flow(&provider, cancelled.clone());
+
+    let listener = bind_in_range().inspect_err(|_| finish_flow(&provider, &cancelled))?;
+    listener.set_nonblocking(true).map_err(|e| format!("bind: {e}"))?;
+    let port = listener.local_addr().map_err(|e| format!("bind: {e}"))?.port();
+
+    let flow = Flow {
+        app,
+        provider: provider.clone(),
+        server_url,
+        expected_state,
+        deadline: Instant::now()
+            + Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS)),
+        cancelled,
+    };
+    std::thread::Builder::new()
+        .name(format!("oauth-{provider}"))
+        .spawn(move || run(flow, listener))
+        .map_err(|e| format!("spawn: {e}"))?;
+
+    Ok(OAuthListener {
+        redirect_uri: format!("http://127.0.0.1:{port}/callback"),
+        provider,
+        port,
+    })
+}
+
+/// Stop waiting for `provider`'s redirect and free its port.
+#[tauri::command]
+pub fn cancel_oauth_listener(provider: String) {
+    let flows = FLOWS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(cancelled) = flows.as_ref().and_then(|f| f.get(&provider)) {
+        cancelled.store(true, Ordering::SeqCst);
+    }
+}
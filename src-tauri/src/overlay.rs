@@ -0,0 +1,150 @@
+//! Voice overlay: a small always-on-top, click-through window listing the
+//! current voice participants and who's speaking.
+//!
+//! The main webview owns voice state and pushes snapshots with
+//! `update_overlay`. The latest one is kept here and forwarded to the overlay
+//! window as `overlay-state`, so a freshly opened overlay can pull it with
+//! `get_overlay_state` instead of waiting for the next change.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+const LABEL: &str = "overlay";
+/// Logical size of the overlay window; content is anchored to its corner.
+const WIDTH: f64 = 260.0;
+const HEIGHT: f64 = 360.0;
+/// Logical gap between the overlay and the screen edges.
+const MARGIN: f64 = 16.0;
+
+#[derive(Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    fn is_left(self) -> bool {
+        matches!(self, Corner::TopLeft | Corner::BottomLeft)
+    }
+
+    fn is_top(self) -> bool {
+        matches!(self, Corner::TopLeft | Corner::TopRight)
+    }
+}
+
+static CORNER: Mutex<Corner> = Mutex::new(Corner::TopLeft);
+static LAST_STATE: Mutex<Option<serde_json::Value>> = Mutex::new(None);
+
+#[derive(Serialize)]
+pub struct OverlaySnapshot {
+    corner: Corner,
+    state: Option<serde_json::Value>,
+}
+
+/// Move the overlay to `corner` of the monitor the main window is on.
+fn place(app: &AppHandle, window: &WebviewWindow, corner: Corner) -> tauri::Result<()> {
+    let monitor = match app
+        .get_webview_window("main")
+        .and_then(|main| main.current_monitor().ok().flatten())
+    {
+        Some(monitor) => monitor,
+        None => match window.primary_monitor()? {
+            Some(monitor) => monitor,
+            None => return Ok(()),
+        },
+    };
+
+    let scale = monitor.scale_factor();
+    let origin = monitor.position();
+    let size = monitor.size();
+    let (width, height, margin) = (WIDTH * scale, HEIGHT * scale, MARGIN * scale);
+
+    let x = if corner.is_left() {
+        origin.x as f64 + margin
+    } else {
+        origin.x as f64 + size.width as f64 - width - margin
+    };
+    let y = if corner.is_top() {
+        origin.y as f64 + margin
+    } else {
+        origin.y as f64 + size.height as f64 - height - margin
+    };
+    window.set_position(tauri::PhysicalPosition::new(
+        x.round() as i32,
+        y.round() as i32,
+    ))
+}
+
+fn open(app: &AppHandle, corner: Corner) -> tauri::Result<()> {
+    let builder = WebviewWindowBuilder::new(app, LABEL, WebviewUrl::App("/?overlay".into()))
+        .title("Flux Overlay")
+        .inner_size(WIDTH, HEIGHT)
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .shadow(false)
+        .focused(false)
+        .visible(false);
+    // macOS only supports transparent webviews through private APIs
+    #[cfg(not(target_os = "macos"))]
+    let builder = builder.transparent(true);
+
+    let window = builder.build()?;
+    // Clicks fall through to whatever is underneath (usually a game)
+    window.set_ignore_cursor_events(true)?;
+    place(app, &window, corner)?;
+    window.show()
+}
+
+// ── Tauri commands ──────────────────────────────────────────────────────────
+
+/// Open the overlay in `corner`, or close it if it's already open.
+/// Returns whether the overlay is now open.
+#[tauri::command]
+pub async fn toggle_overlay(app: AppHandle, corner: Corner) -> Result<bool, String> {
+    *CORNER.lock().unwrap() = corner;
+
+    if let Some(window) = app.get_webview_window(LABEL) {
+        window.close().map_err(|e| e.to_string())?;
+        return Ok(false);
+    }
+
+    open(&app, corner).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// Remember the user's preferred corner, moving the overlay if it's open.
+#[tauri::command]
+pub fn set_overlay_corner(app: AppHandle, corner: Corner) -> Result<(), String> {
+    *CORNER.lock().unwrap() = corner;
+
+    if let Some(window) = app.get_webview_window(LABEL) {
+        place(&app, &window, corner).map_err(|e| e.to_string())?;
+        let _ = app.emit_to(LABEL, "overlay-corner", corner);
+    }
+    Ok(())
+}
+
+/// Called by the main webview whenever the voice state shown in the overlay
+/// changes.
+#[tauri::command]
+pub fn update_overlay(app: AppHandle, state: serde_json::Value) {
+    *LAST_STATE.lock().unwrap() = Some(state.clone());
+    if app.get_webview_window(LABEL).is_some() {
+        let _ = app.emit_to(LABEL, "overlay-state", state);
+    }
+}
+
+/// Called by the overlay window on load.
+#[tauri::command]
+pub fn get_overlay_state() -> OverlaySnapshot {
+    OverlaySnapshot {
+        corner: *CORNER.lock().unwrap(),
+        state: LAST_STATE.lock().unwrap().clone(),
+    }
+}
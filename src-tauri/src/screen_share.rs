@@ -0,0 +1,229 @@
+//! Live capture of a source picked from `capture::get_sources()`.
+//!
+//! Frames are downscaled, JPEG-encoded and emitted to the webview as
+//! `capture-frame` events; the client paints them onto a canvas and publishes
+//! `canvas.captureStream()` to LiveKit. Lifecycle changes are emitted as
+//! `capture-state` events.
+
+use serde::Serialize;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureState {
+    /// "started" | "stopped" | "error"
+    pub state: String,
+    pub source_id: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct CaptureFrame {
+    pub width: u32,
+    pub height: u32,
+    /// data:image/jpeg;base64,… so the client can assign it straight to an <img>.
+    pub data: String,
+}
+
+pub const DEFAULT_FPS: u32 = 30;
+pub const DEFAULT_MAX_WIDTH: u32 = 1920;
+const JPEG_QUALITY: u8 = 75;
+
+/// Scale an RGBA frame down to `max_width` (keeping aspect) and JPEG-encode it.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn encode_frame(rgba: &[u8], width: u32, height: u32, max_width: u32) -> Option<CaptureFrame> {
+    use base64::Engine;
+
+    let img = image::RgbaImage::from_raw(width, height, rgba.to_vec())?;
+    let img = if width > max_width {
+        let scaled_height = (height as u64 * max_width as u64 / width as u64).max(1) as u32;
+        image::imageops::resize(&img, max_width, scaled_height, image::imageops::FilterType::Triangle)
+    } else {
+        img
+    };
+    // JPEG has no alpha channel
+    let rgb = image::DynamicImage::ImageRgba8(img).to_rgb8();
+
+    let mut buf = std::io::Cursor::new(Vec::new());
+    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, JPEG_QUALITY);
+    image::ImageEncoder::write_image(
+        encoder,
+        rgb.as_raw(),
+        rgb.width(),
+        rgb.height(),
+        image::ExtendedColorType::Rgb8,
+    )
+    .ok()?;
+
+    let b64 = base64::engine::general_purpose::STANDARD.encode(buf.into_inner());
+    Some(CaptureFrame {
+        width: rgb.width(),
+        height: rgb.height(),
+        data: format!("data:image/jpeg;base64,{}", b64),
+    })
+}
+
+fn emit_state(app: &tauri::AppHandle, state: &str, source_id: Option<String>, error: Option<String>) {
+    use tauri::Emitter;
+    let _ = app.emit(
+        "capture-state",
+        CaptureState { state: state.into(), source_id, error },
+    );
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{emit_state, encode_frame};
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+    use tauri::Emitter;
+    use windows_capture::capture::{CaptureControl, Context, GraphicsCaptureApiHandler};
+    use windows_capture::frame::Frame;
+    use windows_capture::graphics_capture_api::InternalCaptureControl;
+    use windows_capture::monitor::Monitor;
+    use windows_capture::settings::{ColorFormat, CursorCaptureSettings, DrawBorderSettings, Settings};
+    use windows_capture::window::Window;
+
+    pub struct ForwarderFlags {
+        app: tauri::AppHandle,
+        source_id: String,
+        frame_interval: Duration,
+        max_width: u32,
+    }
+
+    /// Windows Graphics Capture handler that forwards throttled frames to the webview.
+    pub struct FrameForwarder {
+        flags: ForwarderFlags,
+        last_frame: Option<Instant>,
+    }
+
+    impl GraphicsCaptureApiHandler for FrameForwarder {
+        type Flags = ForwarderFlags;
+        type Error = String;
+
+        fn new(ctx: Context<Self::Flags>) -> Result<Self, Self::Error> {
+            Ok(Self { flags: ctx.flags, last_frame: None })
+        }
+
+        fn on_frame_arrived(
+            &mut self,
+            frame: &mut Frame,
+            _capture_control: InternalCaptureControl,
+        ) -> Result<(), Self::Error> {
+            // WGC delivers at display refresh rate; drop frames above the target fps
+            if self
+                .last_frame
+                .is_some_and(|t| t.elapsed() < self.flags.frame_interval)
+            {
+                return Ok(());
+            }
+            self.last_frame = Some(Instant::now());
+
+            let mut buffer = frame.buffer().map_err(|e| e.to_string())?;
+            let (width, height) = (buffer.width(), buffer.height());
+            let pixels = buffer.as_nopadding_buffer().map_err(|e| e.to_string())?;
+
+            if let Some(encoded) = encode_frame(pixels, width, height, self.flags.max_width) {
+                let _ = self.flags.app.emit("capture-frame", encoded);
+            }
+            Ok(())
+        }
+
+        fn on_closed(&mut self) -> Result<(), Self::Error> {
+            // The captured window was closed
+            ACTIVE.lock().map(|mut a| a.take()).ok();
+            emit_state(&self.flags.app, "stopped", Some(self.flags.source_id.clone()), None);
+            Ok(())
+        }
+    }
+
+    static ACTIVE: Mutex<Option<CaptureControl<FrameForwarder, String>>> = Mutex::new(None);
+
+    pub fn start(
+        app: tauri::AppHandle,
+        source_id: String,
+        fps: u32,
+        max_width: u32,
+    ) -> Result<(), String> {
+        stop(&app);
+
+        let flags = ForwarderFlags {
+            app: app.clone(),
+            source_id: source_id.clone(),
+            frame_interval: Duration::from_millis(1000 / fps.clamp(1, 60) as u64),
+            max_width,
+        };
+
+        // Source ids come from capture::get_sources(): "screen:<index>" / "window:<hwnd>"
+        let control = match source_id.split_once(':') {
+            Some(("screen", index)) => {
+                let index: usize = index.parse().map_err(|_| "invalid screen id".to_string())?;
+                // Both enumerate via EnumDisplayMonitors, but Monitor indices start at 1
+                let monitor = Monitor::from_index(index + 1).map_err(|e| e.to_string())?;
+                FrameForwarder::start_free_threaded(Settings::new(
+                    monitor,
+                    CursorCaptureSettings::WithCursor,
+                    DrawBorderSettings::WithoutBorder,
+                    ColorFormat::Rgba8,
+                    flags,
+                ))
+            }
+            Some(("window", hwnd)) => {
+                let hwnd: usize = hwnd.parse().map_err(|_| "invalid window id".to_string())?;
+                let window = Window::from_raw_hwnd(hwnd as *mut std::ffi::c_void);
+                if !window.is_valid() {
+                    return Err("Window is no longer available".into());
+                }
+                FrameForwarder::start_free_threaded(Settings::new(
+                    window,
+                    CursorCaptureSettings::WithCursor,
+                    DrawBorderSettings::WithoutBorder,
+                    ColorFormat::Rgba8,
+                    flags,
+                ))
+            }
+            _ => return Err(format!("Unknown capture source: {source_id}")),
+        }
+        .map_err(|e| e.to_string());
+
+        let control = match control {
+            Ok(c) => c,
+            Err(e) => {
+                emit_state(&app, "error", Some(source_id), Some(e.clone()));
+                return Err(e);
+            }
+        };
+
+        if let Ok(mut active) = ACTIVE.lock() {
+            *active = Some(control);
+        }
+        emit_state(&app, "started", Some(source_id), None);
+        Ok(())
+    }
+
+    pub fn stop(app: &tauri::AppHandle) {
+        let control = ACTIVE.lock().ok().and_then(|mut a| a.take());
+        if let Some(control) = control {
+            let _ = control.stop();
+            emit_state(app, "stopped", None, None);
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    pub fn start(
+        app: tauri::AppHandle,
+        source_id: String,
+        _fps: u32,
+        _max_width: u32,
+    ) -> Result<(), String> {
+        let err = "Screen capture is not supported on this platform yet".to_string();
+        super::emit_state(&app, "error", Some(source_id), Some(err.clone()));
+        Err(err)
+    }
+
+    pub fn stop(_app: &tauri::AppHandle) {}
+}
+
+pub use platform::{start, stop};
@@ -0,0 +1,165 @@
+//! Spellcheck languages and autocorrect for the webview, persisted as
+//! `spellcheck.json` in the app config dir.
+//!
+//! Windows: WebView2 takes its languages from browser arguments, which only
+//! apply when the webview is created, so changes take effect on the next
+//! launch. Linux: WebKitGTK's spell checker is updated immediately. macOS:
+//! WKWebView detects the language by itself. Autocorrect is saved alongside;
+//! the page applies it through the `autocorrect` attribute, which WebKit
+//! honours.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+const SETTINGS_FILE: &str = "spellcheck.json";
+/// More than a few dictionaries slows checking down noticeably.
+const MAX_LANGUAGES: usize = 4;
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpellcheckSettings {
+    /// BCP 47 tags, e.g. "en-US", "de-DE". Empty means the system language.
+    pub languages: Vec<String>,
+    pub autocorrect: bool,
+}
+
+impl Default for SpellcheckSettings {
+    fn default() -> Self {
+        Self {
+            languages: Vec::new(),
+            autocorrect: true,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpellcheckStatus {
+    #[serde(flatten)]
+    pub settings: SpellcheckSettings,
+    /// Whether the change only applies after restarting the app.
+    pub restart_required: bool,
+}
+
+fn is_language_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag.len() <= 16
+        && tag.split('-').all(|part| {
+            (1..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+fn settings_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_config_dir()
+        .ok()
+        .map(|d| d.join(SETTINGS_FILE))
+}
+
+fn read_settings(path: &std::path::Path) -> SpellcheckSettings {
+    std::fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+/// Pass the saved languages to WebView2. Must run before any webview is
+/// created; the app handle doesn't exist yet, so the config dir is resolved
+/// the way Tauri does it on Windows.
+#[cfg(windows)]
+pub fn apply_before_launch(identifier: &str) {
+    let Some(appdata) = std::env::var_os("APPDATA") else {
+        return;
+    };
+    let settings = read_settings(&PathBuf::from(appdata).join(identifier).join(SETTINGS_FILE));
+    if settings.languages.is_empty()
+        || std::env::var_os("WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS").is_some()
+    {
+        return;
+    }
+    // The variable replaces Tauri's default arguments, so repeat them
+    let args = format!(
+        "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection --accept-lang={}",
+        settings.languages.join(",")
+    );
+    std::env::set_var("WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS", args);
+}
+
+/// Point WebKitGTK's spell checker at the saved languages.
+#[cfg(target_os = "linux")]
+fn apply_languages(app: &AppHandle, languages: &[String]) {
+    let languages = languages.to_vec();
+    for window in app.webview_windows().into_values() {
+        let languages = languages.clone();
+        let _ = window.with_webview(move |webview| {
+            use webkit2gtk::{WebContextExt, WebViewExt};
+            let Some(context) = webview.inner().context() else {
+                return;
+            };
+            context.set_spell_checking_enabled(true);
+            if !languages.is_empty() {
+                let refs: Vec<&str> = languages.iter().map(String::as_str).collect();
+                context.set_spell_checking_languages(&refs);
+            }
+        });
+    }
+}
+
+/// Apply the saved settings to the webviews that already exist.
+pub fn init(app: &AppHandle) {
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(path) = settings_path(app) {
+            apply_languages(app, &read_settings(&path).languages);
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = app;
+}
+
+// ── Tauri commands ──────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_spellcheck_settings(app: AppHandle) -> SpellcheckSettings {
+    settings_path(&app)
+        .map(|path| read_settings(&path))
+        .unwrap_or_default()
+}
+
+/// Save spellcheck languages and the autocorrect toggle, applying what can
+/// be applied without a restart.
+#[tauri::command]
+pub fn set_spellcheck_settings(
+    app: AppHandle,
+    languages: Vec<String>,
+    autocorrect: bool,
+) -> Result<SpellcheckStatus, String> {
+    if languages.len() > MAX_LANGUAGES {
+        return Err(format!("At most {MAX_LANGUAGES} spellcheck languages"));
+    }
+    if let Some(bad) = languages.iter().find(|tag| !is_language_tag(tag)) {
+        return Err(format!("Invalid language tag: {bad}"));
+    }
+
+    let path = settings_path(&app).ok_or("No app config directory")?;
+    let previous = read_settings(&path);
+    let settings = SpellcheckSettings {
+        languages,
+        autocorrect,
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_vec_pretty(&settings).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+
+    #[cfg(target_os = "linux")]
+    apply_languages(&app, &settings.languages);
+
+    let restart_required = cfg!(windows) && previous.languages != settings.languages;
+    Ok(SpellcheckStatus {
+        settings,
+        restart_required,
+    })
+}
@@ -0,0 +1,111 @@
+//! Taskbar / dock integration for the main window: a progress bar for
+//! updates and uploads, an unread badge, and attention flashing.
+//!
+//! Progress goes through ITaskbarList3 on Windows, the dock tile on macOS and
+//! the Unity launcher API on Linux. Windows has no badge count, so the
+//! taskbar button gets an overlay dot instead.
+
+use serde::Deserialize;
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{AppHandle, Manager, UserAttentionType};
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressState {
+    #[default]
+    Normal,
+    Indeterminate,
+    Paused,
+    Error,
+}
+
+/// Set the main window's taskbar progress (0.0-1.0), or clear it with `None`.
+pub fn set_progress(app: &AppHandle, progress: Option<f64>, state: ProgressState) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let bar = match progress {
+        None => ProgressBarState {
+            status: Some(ProgressBarStatus::None),
+            progress: None,
+        },
+        Some(fraction) => ProgressBarState {
+            status: Some(match state {
+                ProgressState::Normal => ProgressBarStatus::Normal,
+                ProgressState::Indeterminate => ProgressBarStatus::Indeterminate,
+                ProgressState::Paused => ProgressBarStatus::Paused,
+                ProgressState::Error => ProgressBarStatus::Error,
+            }),
+            progress: Some((fraction.clamp(0.0, 1.0) * 100.0).round() as u64),
+        },
+    };
+    if let Err(e) = window.set_progress_bar(bar) {
+        tracing::debug!("taskbar progress: {e}");
+    }
+}
+
+/// A filled red dot, used as the Windows taskbar overlay icon.
+#[cfg(windows)]
+fn badge_overlay() -> tauri::image::Image<'static> {
+    const SIZE: u32 = 16;
+    let radius = SIZE as f32 / 2.0 - 0.5;
+    let center = SIZE as f32 / 2.0;
+    let mut rgba = vec![0u8; (SIZE * SIZE * 4) as usize];
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let (dx, dy) = (x as f32 + 0.5 - center, y as f32 + 0.5 - center);
+            if dx * dx + dy * dy <= radius * radius {
+                let i = ((y * SIZE + x) * 4) as usize;
+                rgba[i..i + 4].copy_from_slice(&[0xED, 0x42, 0x45, 0xFF]);
+            }
+        }
+    }
+    tauri::image::Image::new_owned(rgba, SIZE, SIZE)
+}
+
+// ── Tauri commands ──────────────────────────────────────────────────────────
+
+/// Show upload (or other) progress on the taskbar button; `None` clears it.
+#[tauri::command]
+pub fn set_taskbar_progress(app: AppHandle, progress: Option<f64>, state: Option<ProgressState>) {
+    set_progress(&app, progress, state.unwrap_or_default());
+}
+
+/// Show the unread mention count on the dock / taskbar icon. Zero clears it.
+#[tauri::command]
+pub fn set_taskbar_badge(app: AppHandle, count: u32) -> Result<(), String> {
+    let Some(window) = app.get_webview_window("main") else {
+        return Ok(());
+    };
+
+    #[cfg(windows)]
+    {
+        let icon = (count > 0).then(badge_overlay);
+        window.set_overlay_icon(icon).map_err(|e| e.to_string())
+    }
+    #[cfg(not(windows))]
+    {
+        let count = (count > 0).then_some(count as i64);
+        window.set_badge_count(count).map_err(|e| e.to_string())
+    }
+}
+
+/// Flash the taskbar button / bounce the dock icon if the main window isn't
+/// focused. `critical` keeps it going until the window is focused.
+#[tauri::command]
+pub fn flash_window(app: AppHandle, critical: bool) -> Result<(), String> {
+    let Some(window) = app.get_webview_window("main") else {
+        return Ok(());
+    };
+    if window.is_focused().unwrap_or(false) {
+        return Ok(());
+    }
+    let attention = if critical {
+        UserAttentionType::Critical
+    } else {
+        UserAttentionType::Informational
+    };
+    window
+        .request_user_attention(Some(attention))
+        .map_err(|e| e.to_string())
+}
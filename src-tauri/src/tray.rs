@@ -0,0 +1,164 @@
+//! System tray icon: unread badge, quick voice/status actions, minimize-to-tray.
+//!
+//! Menu clicks are forwarded to the webview as `tray-action` events with
+//! `{ action, status? }`; the frontend owns the actual mute/deafen/status state.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::image::Image;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Manager};
+
+const TRAY_ID: &str = "main";
+
+/// When set, closing the main window hides it instead of quitting.
+static MINIMIZE_TO_TRAY: AtomicBool = AtomicBool::new(true);
+
+/// (menu id, label, status value sent to the webview)
+const STATUSES: &[(&str, &str, &str)] = &[
+    ("status-online", "Online", "online"),
+    ("status-idle", "Idle", "idle"),
+    ("status-dnd", "Do Not Disturb", "dnd"),
+    ("status-invisible", "Invisible", "invisible"),
+];
+
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let show = MenuItem::with_id(app, "show", "Open Flux", true, None::<&str>)?;
+    let mute = MenuItem::with_id(app, "mute", "Toggle Mute", true, None::<&str>)?;
+    let deafen = MenuItem::with_id(app, "deafen", "Toggle Deafen", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", "Quit Flux", true, None::<&str>)?;
+
+    let status_items = STATUSES
+        .iter()
+        .map(|(id, label, _)| MenuItem::with_id(app, *id, *label, true, None::<&str>))
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let status_refs: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> = status_items
+        .iter()
+        .map(|i| i as &dyn tauri::menu::IsMenuItem<tauri::Wry>)
+        .collect();
+    let status = Submenu::with_items(app, "Set Status", true, &status_refs)?;
+
+    let menu = Menu::with_items(
+        app,
+        &[
+            &show,
+            &PredefinedMenuItem::separator(app)?,
+            &mute,
+            &deafen,
+            &status,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Flux")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            "show" => show_main_window(app),
+            "quit" => app.exit(0),
+            "mute" | "deafen" => {
+                let _ = app.emit("tray-action", serde_json::json!({ "action": event.id.as_ref() }));
+            }
+            id => {
+                if let Some((_, _, status)) = STATUSES.iter().find(|(sid, _, _)| *sid == id) {
+                    let _ = app.emit(
+                        "tray-action",
+                        serde_json::json!({ "action": "set-status", "status": status }),
+                    );
+                }
+            }
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(tray.app_handle());
+            }
+        });
+
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Whether closing the main window should hide it to the tray instead.
+pub fn should_hide_on_close() -> bool {
+    MINIMIZE_TO_TRAY.load(Ordering::Relaxed)
+}
+
+/// Draw a filled dot in the top-right corner of the app icon.
+fn badge_icon(base: &Image<'_>, color: [u8; 4]) -> Image<'static> {
+    let (w, h) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+    let radius = (w.min(h) as f32) * 0.22;
+    let (cx, cy) = (w as f32 - radius - 1.0, radius + 1.0);
+
+    for y in 0..h {
+        for x in 0..w {
+            let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+            if dx * dx + dy * dy <= radius * radius {
+                let i = ((y * w + x) * 4) as usize;
+                rgba[i..i + 4].copy_from_slice(&color);
+            }
+        }
+    }
+    Image::new_owned(rgba, w, h)
+}
+
+// ── Tauri commands ──────────────────────────────────────────────────────────
+
+/// Update the tray tooltip/icon with unread and mention counts.
+/// Mentions get a red dot; plain unreads a grey one.
+#[tauri::command]
+pub fn set_tray_badge(app: AppHandle, unread: u32, mentions: u32) -> Result<(), String> {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+
+    let tooltip = match (mentions, unread) {
+        (0, 0) => "Flux".to_string(),
+        (0, u) => format!("Flux — {u} unread"),
+        (m, 0) => format!("Flux — {m} mention{}", if m == 1 { "" } else { "s" }),
+        (m, u) => format!("Flux — {m} mention{}, {u} unread", if m == 1 { "" } else { "s" }),
+    };
+    tray.set_tooltip(Some(tooltip)).map_err(|e| e.to_string())?;
+
+    // Menu bar text next to the icon (macOS / some Linux panels)
+    #[cfg(not(windows))]
+    {
+        let title = if mentions > 0 { Some(mentions.to_string()) } else { None };
+        tray.set_title(title).map_err(|e| e.to_string())?;
+    }
+
+    if let Some(base) = app.default_window_icon() {
+        let icon = if mentions > 0 {
+            badge_icon(base, [0xED, 0x42, 0x45, 0xFF])
+        } else if unread > 0 {
+            badge_icon(base, [0xB9, 0xBB, 0xBE, 0xFF])
+        } else {
+            base.clone().to_owned()
+        };
+        tray.set_icon(Some(icon)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_minimize_to_tray(enabled: bool) {
+    MINIMIZE_TO_TRAY.store(enabled, Ordering::Relaxed);
+}
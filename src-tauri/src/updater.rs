@@ -0,0 +1,321 @@
+//! Release channels, staged installs, launch rollback and background polling
+//! on top of the updater plugin.
+//!
+//! Installing an update writes `update_state.json` to the app data dir. The
+//! new version must call `confirm_update_launch` once the UI is up; if it
+//! starts `MAX_UNCONFIRMED_LAUNCHES` times without doing so, the previous
+//! version is reinstalled from its release.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::taskbar::{self, ProgressState};
+
+const RELEASES_URL: &str = "https://github.com/NoahSmiley/fluxchat/releases";
+const STATE_FILE: &str = "update_state.json";
+const MAX_UNCONFIRMED_LAUNCHES: u32 = 2;
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 6 * 60 * 60;
+const MIN_POLL_INTERVAL_SECS: u64 = 5 * 60;
+
+/// Bumped to stop the running poll loop.
+static POLL_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    #[default]
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl Channel {
+    fn endpoint(self) -> String {
+        match self {
+            Channel::Stable => format!("{RELEASES_URL}/latest/download/latest.json"),
+            Channel::Beta => format!("{RELEASES_URL}/download/latest-beta/latest.json"),
+            Channel::Nightly => format!("{RELEASES_URL}/download/latest-nightly/latest.json"),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub body: Option<String>,
+    pub channel: Channel,
+}
+
+/// Installed-but-unconfirmed update, persisted across the restart.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PendingUpdate {
+    previous_version: String,
+    target_version: String,
+    launches: u32,
+}
+
+fn state_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|d| d.join(STATE_FILE))
+}
+
+fn read_state(app: &AppHandle) -> Option<PendingUpdate> {
+    let data = std::fs::read(state_path(app)?).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+fn write_state(app: &AppHandle, state: &PendingUpdate) -> Result<(), String> {
+    let path = state_path(app).ok_or("no app data dir")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_vec(state).map_err(|e| e.to_string())?;
+    std::fs::write(path, data).map_err(|e| e.to_string())
+}
+
+fn clear_state(app: &AppHandle) {
+    if let Some(path) = state_path(app) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// An explicit endpoint wins over the channel, for self-hosted update feeds.
+fn resolve_endpoint(endpoint: Option<String>, channel: Channel) -> Result<url::Url, String> {
+    endpoint
+        .unwrap_or_else(|| channel.endpoint())
+        .parse()
+        .map_err(|e| format!("invalid endpoint: {e}"))
+}
+
+async fn find_update(app: &AppHandle, url: url::Url) -> Result<Option<Update>, String> {
+    app.updater_builder()
+        .endpoints(vec![url])
+        .map_err(|e| e.to_string())?
+        .build()
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+fn is_signature_error(e: &tauri_plugin_updater::Error) -> bool {
+    use tauri_plugin_updater::Error;
+    matches!(e, Error::Minisign(_) | Error::Base64(_) | Error::SignatureUtf8(_))
+}
+
+/// Download (which verifies the signature against the configured pubkey),
+/// then install. Emits `update-progress` and `update-status` events.
+async fn stage_and_install(app: &AppHandle, update: &Update) -> Result<(), String> {
+    let handle = app.clone();
+    let mut downloaded: usize = 0;
+    let status = |stage: &str, error: Option<String>| {
+        let _ = app.emit(
+            "update-status",
+            serde_json::json!({ "stage": stage, "version": update.version, "error": error }),
+        );
+    };
+
+    status("downloading", None);
+    let bytes = match update
+        .download(
+            move |chunk_len, total| {
+                downloaded += chunk_len;
+                let _ = handle.emit(
+                    "update-progress",
+                    serde_json::json!({ "downloaded": downloaded, "total": total }),
+                );
+                let fraction = total.map(|total| downloaded as f64 / total.max(1) as f64);
+                let state = if fraction.is_some() {
+                    ProgressState::Normal
+                } else {
+                    ProgressState::Indeterminate
+                };
+                taskbar::set_progress(&handle, Some(fraction.unwrap_or(0.0)), state);
+            },
+            || {},
+        )
+        .await
+    {
+        Ok(bytes) => bytes,
+        Err(e) if is_signature_error(&e) => {
+            let msg = format!("signature verification failed: {e}");
+            tracing::error!(version = %update.version, "{msg}");
+            taskbar::set_progress(app, None, ProgressState::Normal);
+            status("signature-invalid", Some(msg.clone()));
+            return Err(msg);
+        }
+        Err(e) => {
+            taskbar::set_progress(app, None, ProgressState::Normal);
+            status("failed", Some(e.to_string()));
+            return Err(e.to_string());
+        }
+    };
+    taskbar::set_progress(app, None, ProgressState::Normal);
+    status("verified", None);
+
+    status("installing", None);
+    update.install(bytes).map_err(|e| {
+        status("failed", Some(e.to_string()));
+        e.to_string()
+    })?;
+    status("installed", None);
+    Ok(())
+}
+
+/// Reinstall `version` from its own release. The comparator accepts the
+/// exact version only, which lets the updater go backwards.
+async fn rollback(app: AppHandle, version: String) {
+    let url = format!("{RELEASES_URL}/download/v{version}/latest.json");
+    let result = async {
+        let url: url::Url = url.parse().map_err(|e| format!("invalid endpoint: {e}"))?;
+        let target = version.clone();
+        let update = app
+            .updater_builder()
+            .endpoints(vec![url])
+            .map_err(|e| e.to_string())?
+            .version_comparator(move |_current, remote| remote.version.to_string() == target)
+            .build()
+            .map_err(|e| e.to_string())?
+            .check()
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("release {version} not found"))?;
+        stage_and_install(&app, &update).await
+    }
+    .await;
+
+    match result {
+        Ok(()) => {
+            tracing::warn!(%version, "rolled back after failed launches");
+            clear_state(&app);
+            let _ = app.emit("update-rolled-back", serde_json::json!({ "version": version }));
+            app.restart();
+        }
+        Err(e) => tracing::error!(%version, "rollback failed: {e}"),
+    }
+}
+
+/// Count this launch against a pending update and roll back if it keeps
+/// failing to come up.
+pub fn init(app: &AppHandle) {
+    let Some(mut pending) = read_state(app) else { return };
+    let current = app.package_info().version.to_string();
+
+    if current != pending.target_version {
+        // Install never took effect (or we already rolled back)
+        clear_state(app);
+        return;
+    }
+
+    pending.launches += 1;
+    if pending.launches > MAX_UNCONFIRMED_LAUNCHES {
+        tracing::warn!(
+            from = %pending.target_version,
+            to = %pending.previous_version,
+            "update failed to launch, rolling back"
+        );
+        tauri::async_runtime::spawn(rollback(app.clone(), pending.previous_version));
+        return;
+    }
+    if let Err(e) = write_state(app, &pending) {
+        tracing::warn!("update state: {e}");
+    }
+}
+
+// ── Tauri commands ──────────────────────────────────────────────────────────
+
+/// Check for updates on a release channel (default stable), or a specific
+/// endpoint URL. Returns update metadata if available, or null if up to date.
+#[tauri::command]
+pub async fn check_for_update(
+    app: AppHandle,
+    endpoint: Option<String>,
+    channel: Option<Channel>,
+) -> Result<Option<UpdateInfo>, String> {
+    let channel = channel.unwrap_or_default();
+    let url = resolve_endpoint(endpoint, channel)?;
+    Ok(find_update(&app, url).await?.map(|update| UpdateInfo {
+        version: update.version,
+        body: update.body,
+        channel,
+    }))
+}
+
+/// Download, verify and install an update. Emits `update-progress` events
+/// with { downloaded, total } and `update-status` events with { stage, version,
+/// error } where a bad signature is reported as `signature-invalid`.
+#[tauri::command]
+pub async fn download_and_install_update(
+    app: AppHandle,
+    endpoint: Option<String>,
+    channel: Option<Channel>,
+) -> Result<(), String> {
+    let url = resolve_endpoint(endpoint, channel.unwrap_or_default())?;
+    let update = find_update(&app, url)
+        .await?
+        .ok_or_else(|| "No update available".to_string())?;
+
+    // Written before installing: on Windows the installer may kill us mid-way
+    write_state(
+        &app,
+        &PendingUpdate {
+            previous_version: update.current_version.clone(),
+            target_version: update.version.clone(),
+            launches: 0,
+        },
+    )?;
+    stage_and_install(&app, &update).await.inspect_err(|_| clear_state(&app))
+}
+
+/// Called by the frontend once it has rendered: the installed update works.
+#[tauri::command]
+pub fn confirm_update_launch(app: AppHandle) {
+    clear_state(&app);
+}
+
+/// Poll the channel in the background, emitting `update-available` with
+/// { version, body, channel } whenever a newer version is found. Replaces
+/// any poll already running.
+#[tauri::command]
+pub fn start_update_poll(
+    app: AppHandle,
+    channel: Option<Channel>,
+    interval_secs: Option<u64>,
+) -> Result<(), String> {
+    let channel = channel.unwrap_or_default();
+    let url = resolve_endpoint(None, channel)?;
+    let interval = Duration::from_secs(
+        interval_secs
+            .unwrap_or(DEFAULT_POLL_INTERVAL_SECS)
+            .max(MIN_POLL_INTERVAL_SECS),
+    );
+    let generation = POLL_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    tauri::async_runtime::spawn(async move {
+        let mut last_notified: Option<String> = None;
+        while POLL_GENERATION.load(Ordering::SeqCst) == generation {
+            match find_update(&app, url.clone()).await {
+                Ok(Some(update)) if last_notified.as_deref() != Some(update.version.as_str()) => {
+                    last_notified = Some(update.version.clone());
+                    let _ = app.emit(
+                        "update-available",
+                        UpdateInfo { version: update.version, body: update.body, channel },
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => tracing::debug!("update poll: {e}"),
+            }
+            tokio::time::sleep(interval).await;
+        }
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_update_poll() {
+    POLL_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
@@ -0,0 +1,117 @@
+//! Native window chrome that follows the selected app theme: titlebar and
+//! border colors, light/dark appearance, and the Windows 11 / macOS backdrop
+//! materials.
+
+use serde::Deserialize;
+use tauri::window::{Effect, EffectsBuilder};
+use tauri::Theme;
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowEffect {
+    None,
+    Mica,
+    Acrylic,
+}
+
+/// Parse a `#rrggbb` color.
+fn parse_hex(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Whether light text reads better on this background.
+fn is_dark((r, g, b): (u8, u8, u8)) -> bool {
+    let luminance = 0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64;
+    luminance < 128.0
+}
+
+#[cfg(windows)]
+fn set_caption_colors(
+    window: &tauri::Window,
+    (r, g, b): (u8, u8, u8),
+    dark: bool,
+) -> Result<(), String> {
+    use windows::Win32::Foundation::{BOOL, COLORREF, HWND};
+    use windows::Win32::Graphics::Dwm::{
+        DwmSetWindowAttribute, DWMWA_BORDER_COLOR, DWMWA_CAPTION_COLOR, DWMWA_TEXT_COLOR,
+        DWMWA_USE_IMMERSIVE_DARK_MODE,
+    };
+
+    let hwnd = HWND(window.hwnd().map_err(|e| e.to_string())?.0 as _);
+    let caption = COLORREF(r as u32 | (g as u32) << 8 | (b as u32) << 16);
+    let text = COLORREF(if dark { 0x00FF_FFFF } else { 0 });
+    let dark_mode = BOOL::from(dark);
+
+    unsafe {
+        let _ = DwmSetWindowAttribute(
+            hwnd,
+            DWMWA_USE_IMMERSIVE_DARK_MODE,
+            &dark_mode as *const BOOL as *const _,
+            std::mem::size_of::<BOOL>() as u32,
+        );
+        // Caption, border and text colors are Windows 11 only; older builds
+        // reject them and keep the system colors.
+        for (attribute, color) in [
+            (DWMWA_CAPTION_COLOR, caption),
+            (DWMWA_BORDER_COLOR, caption),
+            (DWMWA_TEXT_COLOR, text),
+        ] {
+            let _ = DwmSetWindowAttribute(
+                hwnd,
+                attribute,
+                &color as *const COLORREF as *const _,
+                std::mem::size_of::<COLORREF>() as u32,
+            );
+        }
+    }
+    Ok(())
+}
+
+// ── Tauri commands ──────────────────────────────────────────────────────────
+
+/// Match the native titlebar to the app's background color (`#rrggbb`).
+/// Light or dark window appearance is picked from its luminance; on macOS
+/// that's the NSWindow appearance, on Windows the immersive dark mode flag
+/// plus the DWM caption colors.
+#[tauri::command]
+pub fn set_titlebar_color(window: tauri::Window, color: String) -> Result<(), String> {
+    let rgb = parse_hex(&color).ok_or_else(|| format!("Invalid color: {}", color))?;
+    let dark = is_dark(rgb);
+
+    window
+        .set_theme(Some(if dark { Theme::Dark } else { Theme::Light }))
+        .map_err(|e| e.to_string())?;
+
+    #[cfg(windows)]
+    set_caption_colors(&window, rgb, dark)?;
+
+    Ok(())
+}
+
+/// Put a translucent backdrop material behind the webview, or remove it.
+/// It shows through wherever the page leaves its background transparent.
+/// Linux has no equivalent, so it's ignored there.
+#[tauri::command]
+pub fn set_window_effects(window: tauri::Window, effect: WindowEffect) -> Result<(), String> {
+    let native: Option<Effect> = match effect {
+        WindowEffect::None => None,
+        #[cfg(windows)]
+        WindowEffect::Mica => Some(Effect::Mica),
+        #[cfg(windows)]
+        WindowEffect::Acrylic => Some(Effect::Acrylic),
+        #[cfg(target_os = "macos")]
+        WindowEffect::Mica => Some(Effect::UnderWindowBackground),
+        #[cfg(target_os = "macos")]
+        WindowEffect::Acrylic => Some(Effect::HudWindow),
+        #[cfg(not(any(windows, target_os = "macos")))]
+        WindowEffect::Mica | WindowEffect::Acrylic => None,
+    };
+
+    let config = native.map(|effect| EffectsBuilder::new().effect(effect).build());
+    window.set_effects(config).map_err(|e| e.to_string())
+}
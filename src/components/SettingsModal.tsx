@@ -1,6 +1,6 @@
 import { useEffect, useState } from "react";
 import { useShallow } from "zustand/react/shallow";
-import { useUIStore } from "@/stores/ui.js";
+import { useUIStore, type OverlayCorner } from "@/stores/ui.js";
 import { useKeybindsStore, type KeybindAction, type KeybindEntry } from "@/stores/keybinds.js";
 import { useSpotifyStore } from "@/stores/spotify/index.js";
 import { useUpdater } from "@/hooks/useUpdater.js";
@@ -184,6 +184,7 @@ const ACTION_LABELS: Record<KeybindAction, string> = {
   "push-to-mute": "Push to Mute",
   "toggle-mute": "Toggle Mute",
   "toggle-deafen": "Toggle Deafen",
+  "toggle-overlay": "Toggle Overlay",
 };
 
 const ACTION_DESCRIPTIONS: Record<KeybindAction, string> = {
@@ -191,8 +192,16 @@ const ACTION_DESCRIPTIONS: Record<KeybindAction, string> = {
   "push-to-mute": "Hold key to mute, release to unmute",
   "toggle-mute": "Press to toggle microphone mute",
   "toggle-deafen": "Press to toggle deafen (mutes all audio)",
+  "toggle-overlay": "Press to show or hide the in-game voice overlay",
 };
 
+const OVERLAY_CORNERS: { value: OverlayCorner; label: string }[] = [
+  { value: "top-left", label: "Top Left" },
+  { value: "top-right", label: "Top Right" },
+  { value: "bottom-left", label: "Bottom Left" },
+  { value: "bottom-right", label: "Bottom Right" },
+];
+
 function KeybindButton({ entry }: { entry: KeybindEntry }) {
   const { recording, startRecording, stopRecording, clearKeybind } = useKeybindsStore(useShallow((s) => ({
     recording: s.recording, startRecording: s.startRecording, stopRecording: s.stopRecording, clearKeybind: s.clearKeybind,
@@ -246,8 +255,9 @@ export function SettingsModal() {
     account: s.account, startOAuthFlow: s.startOAuthFlow, unlinkAccount: s.unlinkAccount,
     polling: s.polling, oauthError: s.oauthError,
   })));
-  const { betaUpdates, setBetaUpdates } = useUIStore(useShallow((s) => ({
+  const { betaUpdates, setBetaUpdates, overlayCorner, setOverlayCorner } = useUIStore(useShallow((s) => ({
     betaUpdates: s.betaUpdates, setBetaUpdates: s.setBetaUpdates,
+    overlayCorner: s.overlayCorner, setOverlayCorner: s.setOverlayCorner,
   })));
   const updater = useUpdater(betaUpdates);
 
@@ -319,6 +329,21 @@ export function SettingsModal() {
                 <KeybindButton entry={entry} />
               </div>
             ))}
+            <div className="settings-row">
+              <div className="settings-row-info">
+                <span className="settings-row-label">Overlay Position</span>
+                <span className="settings-row-desc">Screen corner the voice overlay appears in (desktop app only)</span>
+              </div>
+              <select
+                className="settings-select"
+                value={overlayCorner}
+                onChange={(e) => setOverlayCorner(e.target.value as OverlayCorner)}
+              >
+                {OVERLAY_CORNERS.map((c) => (
+                  <option key={c.value} value={c.value}>{c.label}</option>
+                ))}
+              </select>
+            </div>
           </div>
         )}
 
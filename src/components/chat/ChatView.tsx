@@ -48,7 +48,7 @@ export function ChatView() {
     typingUsers: s.typingUsers, customEmojis: s.customEmojis,
   })));
   const { user } = useAuthStore();
-  const { highlightOwnMessages, spellcheck, showSendButton, setSpellcheck, setShowSendButton } = useUIStore();
+  const { highlightOwnMessages, spellcheck, autocorrect, showSendButton, setSpellcheck, setShowSendButton } = useUIStore();
 
   const inputValueRef = useRef("");
   const [hasContent, setHasContent] = useState(false);
@@ -63,8 +63,8 @@ export function ChatView() {
     const roleMap: Record<string, string> = {};
     const ringMap: Record<string, { ringStyle: string; ringSpin: boolean; ringPatternSeed: number | null }> = {};
     for (const m of members) {
-      usernameMap[m.userId] = m.username;
-      imageMap[m.userId] = m.image;
+      usernameMap[m.userId] = m.nickname ?? m.username;
+      imageMap[m.userId] = m.serverAvatar ?? m.image;
       roleMap[m.userId] = m.role;
       ringMap[m.userId] = { ringStyle: m.ringStyle ?? "default", ringSpin: m.ringSpin ?? false, ringPatternSeed: m.ringPatternSeed ?? null };
     }
@@ -183,6 +183,7 @@ export function ChatView() {
         uploadFile={uploadFile}
         spellcheck={spellcheck}
         setSpellcheck={setSpellcheck}
+        autocorrect={autocorrect}
         showSendButton={showSendButton}
         setShowSendButton={setShowSendButton}
         inputRef={inputRef}
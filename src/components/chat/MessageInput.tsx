@@ -8,6 +8,7 @@ import { lazy, Suspense } from "react";
 const EmojiPicker = lazy(() => import("@/components/EmojiPicker.js"));
 import ContextMenu from "@/components/ContextMenu.js";
 import { getCharOffset, setCursorAtOffset, getDivPlainText, getTextBeforeCursor } from "@/lib/contentEditable.js";
+import { hasNativeClipboard, readClipboardImage } from "@/lib/clipboard.js";
 import type { MemberWithUser, Attachment } from "@/types/shared.js";
 
 type MentionEntry =
@@ -28,6 +29,7 @@ interface MessageInputProps {
   uploadFile: (file: File) => Promise<void>;
   spellcheck: boolean;
   setSpellcheck: (v: boolean) => void;
+  autocorrect: boolean;
   showSendButton: boolean;
   setShowSendButton: (v: boolean) => void;
   inputRef: RefObject<HTMLDivElement | null>;
@@ -56,7 +58,7 @@ function statusLabel(s: string) {
 export function MessageInput({
   activeChannelId, activeServerId, members, onlineUsers, userStatuses, userId,
   pendingAttachments, uploadProgress, removePendingAttachment, sendMessage,
-  uploadFile, spellcheck, setSpellcheck, showSendButton, setShowSendButton,
+  uploadFile, spellcheck, setSpellcheck, autocorrect, showSendButton, setShowSendButton,
   inputRef, inputValueRef, hasContent, setHasContent,
 }: MessageInputProps) {
   const [inputEmojiOpen, setInputEmojiOpen] = useState(false);
@@ -106,8 +108,15 @@ export function MessageInput({
   }
 
   function handleDivPaste(e: React.ClipboardEvent<HTMLDivElement>) {
-    const files = e.clipboardData?.files;
-    if (files && files.length > 0) { e.preventDefault(); handleFiles(files); return; }
+    const files = Array.from(e.clipboardData?.files ?? []);
+    const text = e.clipboardData?.getData("text/plain") ?? "";
+    // Desktop app: let the backend read (and shrink) clipboard images and copied image files
+    if (hasNativeClipboard && (files.some((f) => f.type.startsWith("image/")) || !text)) {
+      e.preventDefault();
+      readClipboardImage().then((image) => handleFiles(image ? [image] : files));
+      return;
+    }
+    if (files.length > 0) { e.preventDefault(); handleFiles(files); return; }
     e.preventDefault();
     document.execCommand("insertText", false, e.clipboardData?.getData("text/plain") ?? "");
     handleDivInput();
@@ -242,7 +251,7 @@ export function MessageInput({
           <button type="button" className="btn-attach" onClick={() => fileInputRef.current?.click()} title="Attach file"><Paperclip size={18} /></button>
           <input ref={fileInputRef} type="file" multiple className="file-input-hidden" onChange={(e) => { if (e.target.files) handleFiles(e.target.files); e.target.value = ""; }} />
           <div
-            ref={inputRef} contentEditable suppressContentEditableWarning spellCheck={spellcheck}
+            ref={inputRef} contentEditable suppressContentEditableWarning spellCheck={spellcheck} autoCorrect={autocorrect ? "on" : "off"}
             className="message-input" data-testid="message-input" data-placeholder="Type a message..."
             onInput={handleDivInput} onKeyDown={handleDivKeyDown} onPaste={handleDivPaste}
             onContextMenu={(e) => { e.preventDefault(); setChatboxMenu({ x: e.clientX, y: e.clientY }); }}
@@ -1,11 +1,13 @@
 import { useState, useEffect, useCallback } from "react";
-import { ChevronRight, Plus, Pencil, Trash2 } from "lucide-react";
+import { ChevronRight, ChevronUp, Plus, Pencil, Trash2 } from "lucide-react";
 import { useChatStore } from "@/stores/chat/index.js";
+import { gateway } from "@/lib/ws.js";
 import {
   getRoadmapItems,
   createRoadmapItem,
   updateRoadmapItem,
   deleteRoadmapItem,
+  voteRoadmapItem,
 } from "@/lib/api/index.js";
 import type { RoadmapItem } from "@/types/shared.js";
 
@@ -85,6 +87,29 @@ export function RoadmapView() {
     fetchItems();
   }, [fetchItems]);
 
+  // Live-update the board when anyone changes, votes on or discusses an item
+  useEffect(() => {
+    if (!activeServerId) return;
+    return gateway.on((event) => {
+      if (event.type !== "roadmap_updated" || event.serverId !== activeServerId) return;
+      getRoadmapItems(activeServerId)
+        .then(setItems)
+        .catch(() => {});
+    });
+  }, [activeServerId]);
+
+  const handleVote = async (item: RoadmapItem) => {
+    if (!activeServerId) return;
+    try {
+      const tally = await voteRoadmapItem(activeServerId, item.id, !item.voted);
+      setItems((prev) =>
+        prev.map((i) => (i.id === item.id ? { ...i, ...tally } : i)),
+      );
+    } catch {
+      // silently fail
+    }
+  };
+
   // Delete handler
   const handleDelete = async (itemId: string) => {
     if (!activeServerId) return;
@@ -194,6 +219,7 @@ export function RoadmapView() {
                     setEditingId(null);
                   }}
                   onDelete={() => handleDelete(item.id)}
+                  onVote={() => handleVote(item)}
                 />
               );
             })}
@@ -232,6 +258,7 @@ interface RoadmapCardProps {
     category?: string;
   }) => Promise<void>;
   onDelete: () => void;
+  onVote: () => void;
 }
 
 function RoadmapCard({
@@ -244,6 +271,7 @@ function RoadmapCard({
   onCancelEdit,
   onSave,
   onDelete,
+  onVote,
 }: RoadmapCardProps) {
   const [editTitle, setEditTitle] = useState(item.title);
   const [editDescription, setEditDescription] = useState(item.description);
@@ -352,6 +380,17 @@ function RoadmapCard({
         {item.category && (
           <span className="roadmap-category-tag">{item.category}</span>
         )}
+        <button
+          className={`roadmap-vote-button${item.voted ? " voted" : ""}`}
+          title={item.voted ? "Remove upvote" : "Upvote"}
+          onClick={(e) => {
+            e.stopPropagation();
+            onVote();
+          }}
+        >
+          <ChevronUp size={12} />
+          {item.voteCount}
+        </button>
       </div>
       {isExpanded && (
         <>
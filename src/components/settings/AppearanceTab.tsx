@@ -1,12 +1,13 @@
 import { useRef, useState, useEffect } from "react";
 import { useShallow } from "zustand/react/shallow";
 import { Upload, X, Compass } from "lucide-react";
-import { useUIStore, type SidebarPosition, type AppBorderStyle } from "@/stores/ui.js";
+import { useUIStore, type SidebarPosition, type AppBorderStyle, type WindowEffect } from "@/stores/ui.js";
 import { useGalleryStore, type GalleryImage, type GalleryMediaType, type GalleryMode, type RotationMode } from "@/stores/gallery.js";
 import { ToggleSwitch } from "@/components/SettingsModal.js";
 import { PRESET_THEMES, LIMINAL_THEME, THEME_COLOR_LABELS, type CustomTheme, type ThemeColors, type ActiveTheme } from "@/lib/themes.js";
 import { ART_SETS } from "@/lib/galleryPresets.js";
 import { getFileUrl, uploadFile } from "@/lib/api/messages.js";
+import { parseLanguageList } from "@/lib/spellcheck.js";
 import { GalleryPublishModal } from "./GalleryPublishModal.js";
 
 const SIDEBAR_POSITIONS: { value: SidebarPosition; label: string }[] = [
@@ -16,6 +17,12 @@ const SIDEBAR_POSITIONS: { value: SidebarPosition; label: string }[] = [
   { value: "bottom", label: "Bottom" },
 ];
 
+const WINDOW_EFFECTS: { value: WindowEffect; label: string }[] = [
+  { value: "none", label: "Solid" },
+  { value: "mica", label: "Mica" },
+  { value: "acrylic", label: "Acrylic" },
+];
+
 const APP_BORDER_STYLES: { value: AppBorderStyle; label: string }[] = [
   { value: "none", label: "None" },
   { value: "chroma", label: "Chroma" },
@@ -438,11 +445,51 @@ function GallerySettings() {
   );
 }
 
+function SpellingSettings() {
+  const { spellcheckLanguages, setSpellcheckLanguages, autocorrect, setAutocorrect } = useUIStore(useShallow((s) => ({
+    spellcheckLanguages: s.spellcheckLanguages, setSpellcheckLanguages: s.setSpellcheckLanguages,
+    autocorrect: s.autocorrect, setAutocorrect: s.setAutocorrect,
+  })));
+  const [draft, setDraft] = useState(spellcheckLanguages.join(", "));
+
+  const commit = () => {
+    const languages = parseLanguageList(draft).slice(0, 4);
+    setSpellcheckLanguages(languages);
+    setDraft(languages.join(", "));
+  };
+
+  return (
+    <div className="settings-card">
+      <h3 className="settings-card-title">Spelling</h3>
+      <p className="settings-card-desc">
+        Spellcheck languages for the desktop app, e.g. "en-US, de-DE" (up to 4). Leave empty to use the system language. On Windows, changes apply after a restart.
+      </p>
+      <input
+        type="text"
+        className="settings-input"
+        value={draft}
+        onChange={(e) => setDraft(e.target.value)}
+        onBlur={commit}
+        onKeyDown={(e) => { if (e.key === "Enter") commit(); }}
+        placeholder="System language"
+      />
+      <div className="settings-row">
+        <div className="settings-row-info">
+          <span className="settings-row-label">Autocorrect</span>
+          <span className="settings-row-desc">Let the system fix typos as you type, where supported.</span>
+        </div>
+        <ToggleSwitch checked={autocorrect} onChange={setAutocorrect} />
+      </div>
+    </div>
+  );
+}
+
 export function AppearanceTab() {
-  const { sidebarPosition, setSidebarPosition, appBorderStyle, setAppBorderStyle, highlightOwnMessages, setHighlightOwnMessages } = useUIStore(useShallow((s) => ({
+  const { sidebarPosition, setSidebarPosition, appBorderStyle, setAppBorderStyle, highlightOwnMessages, setHighlightOwnMessages, windowEffect, setWindowEffect } = useUIStore(useShallow((s) => ({
     sidebarPosition: s.sidebarPosition, setSidebarPosition: s.setSidebarPosition,
     appBorderStyle: s.appBorderStyle, setAppBorderStyle: s.setAppBorderStyle,
     highlightOwnMessages: s.highlightOwnMessages, setHighlightOwnMessages: s.setHighlightOwnMessages,
+    windowEffect: s.windowEffect, setWindowEffect: s.setWindowEffect,
   })));
 
   return (
@@ -470,6 +517,22 @@ export function AppearanceTab() {
         </div>
       </div>
 
+      <div className="settings-card">
+        <h3 className="settings-card-title">Window Material</h3>
+        <p className="settings-card-desc">Let the desktop show through the window background (Windows 11 and macOS desktop app).</p>
+        <div className="ring-style-picker">
+          {WINDOW_EFFECTS.map((we) => (
+            <button
+              key={we.value}
+              className={`ring-style-option ${windowEffect === we.value ? "active" : ""}`}
+              onClick={() => setWindowEffect(we.value)}
+            >
+              <span className="ring-style-label">{we.label}</span>
+            </button>
+          ))}
+        </div>
+      </div>
+
       <div className="settings-card">
         <h3 className="settings-card-title">Messages</h3>
         <div className="settings-row">
@@ -481,6 +544,8 @@ export function AppearanceTab() {
         </div>
       </div>
 
+      <SpellingSettings />
+
       <div className="settings-card">
         <h3 className="settings-card-title">App Border</h3>
         <p className="settings-card-desc">Add an animated ring border around the app window.</p>
@@ -1,5 +1,54 @@
+import { useEffect, useState } from "react";
 import { useShallow } from "zustand/react/shallow";
 import { useNotifStore, type GlobalNotifSetting } from "@/stores/notifications.js";
+import { getDigestSettings, setDigestFrequency, type DigestFrequency, type DigestSettings } from "@/lib/api/index.js";
+
+const DIGEST_OPTIONS: { value: DigestFrequency; label: string; desc: string }[] = [
+  { value: "off", label: "Off", desc: "Don't send digest emails" },
+  { value: "daily", label: "Daily", desc: "Once a day, if you missed anything" },
+  { value: "weekly", label: "Weekly", desc: "Once a week, if you missed anything" },
+];
+
+function EmailDigestSettings() {
+  const [settings, setSettings] = useState<DigestSettings | null>(null);
+  const [error, setError] = useState("");
+
+  useEffect(() => {
+    getDigestSettings().then(setSettings).catch(() => {});
+  }, []);
+
+  if (!settings?.available) return null;
+
+  const choose = (frequency: DigestFrequency) => {
+    setError("");
+    setDigestFrequency(frequency)
+      .then(setSettings)
+      .catch((e) => setError(e instanceof Error ? e.message : "Couldn't update digest emails"));
+  };
+
+  return (
+    <div className="settings-card">
+      <h3 className="settings-card-title">Email Digest</h3>
+      <p className="settings-card-desc">Get an email summarizing unread @mentions and direct messages while you're away. Needs a verified email address.</p>
+      {DIGEST_OPTIONS.map((opt) => (
+        <label key={opt.value} className="settings-radio-row">
+          <input
+            type="radio"
+            name="email-digest"
+            checked={settings.frequency === opt.value}
+            onChange={() => choose(opt.value)}
+            className="settings-radio"
+          />
+          <div className="settings-row-info">
+            <span className="settings-row-label">{opt.label}</span>
+            <span className="settings-row-desc">{opt.desc}</span>
+          </div>
+        </label>
+      ))}
+      {error && <span className="settings-row-error">{error}</span>}
+    </div>
+  );
+}
 
 export function NotificationsTab() {
   const { defaultChannelSetting, setDefaultChannelSetting } = useNotifStore(useShallow((s) => ({
@@ -40,6 +89,8 @@ export function NotificationsTab() {
           </label>
         ))}
       </div>
+
+      <EmailDigestSettings />
     </>
   );
 }
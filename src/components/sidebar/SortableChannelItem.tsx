@@ -1,7 +1,7 @@
 import type { Channel, ChannelType } from "@/types/shared.js";
 import { useChatStore } from "@/stores/chat/index.js";
 import { useVoiceStore } from "@/stores/voice/index.js";
-import { MessageSquareText, Volume2, Settings, ChevronRight, Folder, Radio, Lock, LockOpen } from "lucide-react";
+import { MessageSquareText, Volume2, Megaphone, Settings, ChevronRight, Folder, Radio, Lock, LockOpen } from "lucide-react";
 import { useSortable } from "@dnd-kit/sortable";
 import { CSS } from "@dnd-kit/utilities";
 import type { TreeNode } from "@/lib/channel-tree.js";
@@ -12,6 +12,7 @@ export function getChannelIcon(type: ChannelType, size = 14) {
   switch (type) {
     case "text": return <MessageSquareText size={size} className="channel-type-icon" />;
     case "voice": return <Volume2 size={size} className="channel-type-icon" />;
+    case "stage": return <Megaphone size={size} className="channel-type-icon" />;
     case "category": return <Folder size={size} className="channel-type-icon" />;
   }
 }
@@ -0,0 +1,63 @@
+import { useEffect, useState } from "react";
+import { Mic, MicOff, HeadphoneOff } from "lucide-react";
+import type { OverlayState } from "@/lib/overlay.js";
+import type { OverlayCorner } from "@/stores/ui.js";
+import { avatarColor } from "@/lib/avatarColor.js";
+
+interface OverlaySnapshot {
+  corner: OverlayCorner;
+  state: OverlayState | null;
+}
+
+/** Contents of the click-through overlay window. State comes from the main window via Tauri. */
+export function VoiceOverlay() {
+  const [corner, setCorner] = useState<OverlayCorner>("top-left");
+  const [state, setState] = useState<OverlayState | null>(null);
+
+  useEffect(() => {
+    document.documentElement.classList.add("overlay-window");
+    const unlisteners: (() => void)[] = [];
+    let cancelled = false;
+
+    (async () => {
+      const { invoke } = await import("@tauri-apps/api/core");
+      const { listen } = await import("@tauri-apps/api/event");
+
+      unlisteners.push(await listen<OverlayState>("overlay-state", ({ payload }) => setState(payload)));
+      unlisteners.push(await listen<OverlayCorner>("overlay-corner", ({ payload }) => setCorner(payload)));
+
+      const snapshot = await invoke<OverlaySnapshot>("get_overlay_state");
+      if (cancelled) return;
+      setCorner(snapshot.corner);
+      if (snapshot.state) setState(snapshot.state);
+    })().catch(() => {});
+
+    return () => {
+      cancelled = true;
+      unlisteners.forEach((unlisten) => unlisten());
+    };
+  }, []);
+
+  if (!state || state.participants.length === 0) return null;
+
+  return (
+    <div className={`voice-overlay voice-overlay-${corner}`}>
+      {state.channelName && <div className="voice-overlay-channel">{state.channelName}</div>}
+      {state.participants.map((p) => (
+        <div key={p.userId} className={`voice-overlay-user ${p.speaking ? "speaking" : ""}`}>
+          <span className="voice-overlay-avatar" style={{ background: avatarColor(p.username) }}>
+            {p.username.charAt(0).toUpperCase()}
+          </span>
+          <span className="voice-overlay-name">{p.username}</span>
+          {p.deafened ? (
+            <HeadphoneOff size={12} className="voice-overlay-icon" />
+          ) : p.muted ? (
+            <MicOff size={12} className="voice-overlay-icon" />
+          ) : (
+            <Mic size={12} className={`voice-overlay-icon ${p.speaking ? "active" : ""}`} />
+          )}
+        </div>
+      ))}
+    </div>
+  );
+}
@@ -53,7 +53,7 @@ export function getHoldKeybind() {
   );
 }
 
-// ── Global Key Hook Management (Tauri / Windows) ─────────────────────────────
+// ── Global Key Hook Management (Tauri) ───────────────────────────────────────
 
 let globalHookActive = false;
 
@@ -61,11 +61,20 @@ export function isGlobalHookActive(): boolean {
   return globalHookActive;
 }
 
-export async function startGlobalHook(keyCode: string) {
+/** Register every bound action with the native hook; none stops it. */
+export async function syncGlobalHook() {
   if (!isTauri) return;
+  const bindings = useKeybindsStore
+    .getState()
+    .keybinds.filter((kb) => kb.key !== null)
+    .map((kb) => ({ id: kb.action, code: kb.key }));
+  if (bindings.length === 0) {
+    await stopGlobalHook();
+    return;
+  }
   try {
     const { invoke } = await import("@tauri-apps/api/core");
-    await invoke("start_global_key_listen", { keyCode });
+    await invoke("set_global_key_bindings", { bindings });
     globalHookActive = true;
   } catch {
     globalHookActive = false;
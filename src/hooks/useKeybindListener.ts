@@ -7,57 +7,59 @@ import {
   formatKeyLabel,
   mouseCode,
   mouseLabel,
-  getHoldKeybind,
   isGlobalHookActive,
-  startGlobalHook,
+  syncGlobalHook,
   stopGlobalHook,
 } from "./keybind-config.js";
+import { startKeybindSync } from "@/lib/keybindSync.js";
+import { toggleOverlay } from "@/lib/overlay.js";
+
+interface GlobalKeyPayload {
+  binding: string;
+  phase: "down" | "up";
+}
 
 export function useKeybindListener() {
   const heldKeysRef = useRef<Set<string>>(new Set());
 
   useEffect(() => {
+    // ── Synced bindings — pulled from and pushed to the server ────────────
+    const stopSync = startKeybindSync();
+
     // ── Global key events (Tauri) — fire even when app window is NOT focused
-    let unlistenDown: (() => void) | null = null;
-    let unlistenUp: (() => void) | null = null;
+    let unlistenGlobal: (() => void) | null = null;
 
     if (isTauri) {
       (async () => {
         const { listen } = await import("@tauri-apps/api/event");
 
-        unlistenDown = await listen("global-key-down", () => {
-          const { room } = useVoiceStore.getState();
-          if (!room) return;
-
-          const kb = getHoldKeybind();
-          if (!kb) return;
-
-          if (kb.action === "push-to-talk") {
-            useVoiceStore.getState().setMuted(false);
-          } else if (kb.action === "push-to-mute") {
-            useVoiceStore.getState().setMuted(true);
-          }
-        });
-
-        unlistenUp = await listen("global-key-up", () => {
+        unlistenGlobal = await listen<GlobalKeyPayload>("global-key", ({ payload }) => {
           const { room } = useVoiceStore.getState();
           if (!room) return;
 
-          const kb = getHoldKeybind();
-          if (!kb) return;
-
-          if (kb.action === "push-to-talk") {
-            useVoiceStore.getState().setMuted(true);
-          } else if (kb.action === "push-to-mute") {
-            useVoiceStore.getState().setMuted(false);
+          const down = payload.phase === "down";
+          switch (payload.binding) {
+            case "push-to-talk":
+              useVoiceStore.getState().setMuted(!down);
+              break;
+            case "push-to-mute":
+              useVoiceStore.getState().setMuted(down);
+              break;
+            case "toggle-mute":
+              if (down) useVoiceStore.getState().toggleMute();
+              break;
+            case "toggle-deafen":
+              if (down) useVoiceStore.getState().toggleDeafen();
+              break;
+            case "toggle-overlay":
+              if (down) toggleOverlay();
+              break;
           }
         });
 
-        // Start the global hook if already connected to voice with a hold keybind
-        const { room } = useVoiceStore.getState();
-        const kb = getHoldKeybind();
-        if (room && kb?.key) {
-          startGlobalHook(kb.key);
+        // Start the global hook if already connected to voice
+        if (useVoiceStore.getState().room) {
+          syncGlobalHook();
         }
       })();
     }
@@ -68,9 +70,8 @@ export function useKeybindListener() {
       if (state.room === prevRoom) return;
       prevRoom = state.room;
 
-      const kb = getHoldKeybind();
-      if (state.room && kb?.key) {
-        startGlobalHook(kb.key);
+      if (state.room) {
+        syncGlobalHook();
       } else {
         stopGlobalHook();
       }
@@ -81,12 +82,8 @@ export function useKeybindListener() {
       if (state.keybinds === prevKeybinds) return;
       prevKeybinds = state.keybinds;
 
-      const { room } = useVoiceStore.getState();
-      const kb = getHoldKeybind();
-      if (room && kb?.key) {
-        startGlobalHook(kb.key);
-      } else {
-        stopGlobalHook();
+      if (useVoiceStore.getState().room) {
+        syncGlobalHook();
       }
     });
 
@@ -115,13 +112,8 @@ export function useKeybindListener() {
       for (const kb of keybinds) {
         if (!kb.key || kb.key !== e.code) continue;
 
-        // Skip PTT/PTM at window level when global hook handles it
-        if (
-          isGlobalHookActive() &&
-          (kb.action === "push-to-talk" || kb.action === "push-to-mute")
-        ) {
-          continue;
-        }
+        // Skip at window level when the global hook handles it
+        if (isGlobalHookActive()) continue;
 
         e.preventDefault();
 
@@ -140,6 +132,9 @@ export function useKeybindListener() {
           case "toggle-deafen":
             useVoiceStore.getState().toggleDeafen();
             break;
+          case "toggle-overlay":
+            toggleOverlay();
+            break;
         }
       }
     }
@@ -190,12 +185,7 @@ export function useKeybindListener() {
       for (const kb of keybinds) {
         if (!kb.key || kb.key !== code) continue;
 
-        if (
-          isGlobalHookActive() &&
-          (kb.action === "push-to-talk" || kb.action === "push-to-mute")
-        ) {
-          continue;
-        }
+        if (isGlobalHookActive()) continue;
 
         e.preventDefault();
 
@@ -214,6 +204,9 @@ export function useKeybindListener() {
           case "toggle-deafen":
             useVoiceStore.getState().toggleDeafen();
             break;
+          case "toggle-overlay":
+            toggleOverlay();
+            break;
         }
       }
     }
@@ -254,7 +247,7 @@ export function useKeybindListener() {
 
     // ── Blur handler ──────────────────────────────────────────────────────
     function handleBlur() {
-      // When the global hook is active, PTT/PTM works across focus — no blur reset needed.
+      // When the global hook is active, bindings work across focus — no blur reset needed.
       if (isGlobalHookActive()) return;
 
       if (heldKeysRef.current.size === 0) return;
@@ -288,8 +281,8 @@ export function useKeybindListener() {
       window.removeEventListener("mouseup", handleMouseUp, true);
       window.removeEventListener("contextmenu", handleContextMenu, true);
       window.removeEventListener("blur", handleBlur);
-      unlistenDown?.();
-      unlistenUp?.();
+      unlistenGlobal?.();
+      stopSync();
       unsubVoice();
       unsubKeybinds();
       stopGlobalHook();
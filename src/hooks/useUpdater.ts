@@ -1,7 +1,5 @@
 import { useState, useCallback, useEffect, useRef } from "react";
 
-const STABLE_ENDPOINT = "https://github.com/NoahSmiley/fluxchat/releases/latest/download/latest.json";
-const BETA_ENDPOINT = "https://github.com/NoahSmiley/fluxchat/releases/download/latest-beta/latest.json";
 
 type UpdateStatus = "idle" | "checking" | "available" | "downloading" | "ready" | "error" | "up-to-date";
 
@@ -22,9 +20,16 @@ export function useUpdater(beta = false) {
     progress: 0,
   });
 
-  const endpoint = beta ? BETA_ENDPOINT : STABLE_ENDPOINT;
+  const channel = beta ? "beta" : "stable";
   const unlistenRef = useRef<(() => void) | null>(null);
 
+  // The app rendered, so a freshly installed update works — cancel rollback
+  useEffect(() => {
+    import("@tauri-apps/api/core")
+      .then(({ invoke }) => invoke("confirm_update_launch"))
+      .catch(() => {});
+  }, []);
+
   // Clean up event listener on unmount
   useEffect(() => {
     return () => {
@@ -38,7 +43,7 @@ export function useUpdater(beta = false) {
       const { invoke } = await import("@tauri-apps/api/core");
       const result = await invoke<{ version: string; body?: string } | null>(
         "check_for_update",
-        { endpoint }
+        { channel }
       );
       if (result) {
         setState((s) => ({
@@ -57,7 +62,7 @@ export function useUpdater(beta = false) {
         error: e?.message || String(e),
       }));
     }
-  }, [endpoint]);
+  }, [channel]);
 
   const downloadAndInstall = useCallback(async () => {
     setState((s) => ({ ...s, status: "downloading", progress: 0 }));
@@ -77,7 +82,7 @@ export function useUpdater(beta = false) {
       );
       unlistenRef.current = unlisten;
 
-      await invoke("download_and_install_update", { endpoint });
+      await invoke("download_and_install_update", { channel });
 
       unlistenRef.current?.();
       unlistenRef.current = null;
@@ -91,7 +96,7 @@ export function useUpdater(beta = false) {
         error: e?.message || String(e),
       }));
     }
-  }, [endpoint]);
+  }, [channel]);
 
   const relaunch = useCallback(async () => {
     try {
@@ -15,6 +15,9 @@ import { RoomToasts } from "@/components/voice/RoomToasts.js";
 import { useKeybindListener } from "@/hooks/useKeybindListener.js";
 import { useIdleDetection } from "@/hooks/useIdleDetection.js";
 import { useUIStore } from "@/stores/ui.js";
+import { startOverlaySync } from "@/lib/overlay.js";
+import { startTaskbarSync } from "@/lib/taskbar.js";
+import { startSpellcheckSync } from "@/lib/spellcheck.js";
 
 const SettingsModal = lazy(() => import("@/components/SettingsModal.js").then(m => ({ default: m.SettingsModal })));
 const RoadmapView = lazy(() => import("@/components/roadmap/RoadmapView.js").then(m => ({ default: m.RoadmapView })));
@@ -71,6 +74,10 @@ export function MainLayout() {
     return () => { teardownChatEvents(); gateway.disconnect(); };
   }, [loadServers]);
 
+  useEffect(() => startOverlaySync(), []);
+  useEffect(() => startTaskbarSync(), []);
+  useEffect(() => startSpellcheckSync(), []);
+
   // Auto-select the single server when available
   useEffect(() => {
     if (servers.length === 1 && !activeServerId && !showingDMs) {
@@ -25,6 +25,7 @@ import {
   onCommand,
   getPopoutType,
   isPopout,
+  isOverlay,
 } from "@/lib/broadcast.js";
 
 describe("broadcast", () => {
@@ -155,4 +156,15 @@ describe("broadcast", () => {
 
     expect(isPopout()).toBe(true);
   });
+
+  it("isOverlay is only true in the overlay window", () => {
+    Object.defineProperty(window, "location", {
+      value: { search: "?overlay", protocol: "https:", host: "localhost" },
+      writable: true,
+      configurable: true,
+    });
+
+    expect(isOverlay()).toBe(true);
+    expect(isPopout()).toBe(false);
+  });
 });
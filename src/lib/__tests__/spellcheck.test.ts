@@ -0,0 +1,16 @@
+import { describe, it, expect } from "vitest";
+import { parseLanguageList } from "../spellcheck.js";
+
+describe("parseLanguageList", () => {
+  it("splits on commas and whitespace", () => {
+    expect(parseLanguageList("en-US, de-DE fr")).toEqual(["en-US", "de-DE", "fr"]);
+  });
+
+  it("drops invalid tags and duplicates", () => {
+    expect(parseLanguageList("en-US,,en-US, not_a_tag, -x")).toEqual(["en-US"]);
+  });
+
+  it("returns an empty list for blank input", () => {
+    expect(parseLanguageList("  ")).toEqual([]);
+  });
+});
@@ -1,14 +1,14 @@
-import type { RingStyle } from "@/types/shared.js";
+import type { BlockedUser, RingStyle, SyncedSetting, UserProfile } from "@/types/shared.js";
 
 import { API_BASE, request, getStoredToken, setStoredToken } from "./base.js";
 import type { AuthResponse } from "./base.js";
 
 // ── Auth ──
 
-export async function signUp(email: string, password: string, username: string) {
+export async function signUp(email: string, password: string, username: string, inviteToken?: string) {
   const data = await request<AuthResponse>("/auth/sign-up/email", {
     method: "POST",
-    body: JSON.stringify({ email, password, name: username, username }),
+    body: JSON.stringify({ email, password, name: username, username, inviteToken }),
   });
   if (data.token) setStoredToken(data.token);
   return data;
@@ -29,7 +29,7 @@ export async function signOut() {
   return result;
 }
 
-export async function getSession(): Promise<{ user: { id: string; email: string; username: string; image?: string | null; ringStyle: RingStyle; ringSpin: boolean; steamId?: string | null; ringPatternSeed?: number | null; bannerCss?: string | null; bannerPatternSeed?: number | null; status?: string } } | null> {
+export async function getSession(): Promise<{ user: { id: string; email: string; username: string; image?: string | null; ringStyle: RingStyle; ringSpin: boolean; steamId?: string | null; ringPatternSeed?: number | null; bannerCss?: string | null; bannerPatternSeed?: number | null; status?: string; emailVerified?: boolean; pendingEmail?: string | null } } | null> {
   const headers: Record<string, string> = {};
   const token = getStoredToken();
   if (token) {
@@ -44,15 +44,190 @@ export async function getSession(): Promise<{ user: { id: string; email: string;
   return data ?? null;
 }
 
+// ── Password reset ──
+
+/** Emails a reset code if the address has an account. */
+export async function forgotPassword(email: string) {
+  return request<void>("/auth/forgot-password", {
+    method: "POST",
+    body: JSON.stringify({ email }),
+  });
+}
+
+/** Signs the account out everywhere; sign in again with the new password. */
+export async function resetPassword(token: string, newPassword: string) {
+  return request<void>("/auth/reset-password", {
+    method: "POST",
+    body: JSON.stringify({ token, newPassword }),
+  });
+}
+
+// ── Email verification ──
+
+/** 429 with `retryAfter` seconds if one was sent recently. */
+export async function resendVerification() {
+  return request<void>("/auth/verify/resend", { method: "POST" });
+}
+
+/** Sends confirmation links to both addresses; the change applies once both are opened. */
+export async function changeEmail(newEmail: string, password?: string) {
+  return request<{ pendingEmail: string }>("/auth/change-email", {
+    method: "POST",
+    body: JSON.stringify({ newEmail, password }),
+  });
+}
+
+export async function cancelEmailChange() {
+  return request<void>("/auth/change-email", { method: "DELETE" });
+}
+
+// ── OIDC ──
+
+/** Opens at the identity provider. With a session, links instead of signing in. */
+export async function startOidc() {
+  return request<{ url: string; pollToken: string; linking: boolean }>("/auth/oidc/start");
+}
+
+/** `null` while the user is still at the provider. */
+export async function completeOidc(pollToken: string) {
+  const res = await fetch(`${API_BASE}/auth/oidc/complete`, {
+    method: "POST",
+    credentials: "include",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify({ pollToken }),
+  });
+  if (res.status === 202) return null;
+  const data = await res.json();
+  if (!res.ok) throw new Error(data.error ?? "Sign-in failed");
+  if (data.token) setStoredToken(data.token);
+  return data as AuthResponse | { linked: true };
+}
+
+export async function unlinkOidc() {
+  return request<void>("/auth/oidc", { method: "DELETE" });
+}
+
+// ── Sessions ──
+
+export interface SessionInfo {
+  id: string;
+  userAgent: string | null;
+  ipAddress: string | null;
+  createdAt: string;
+  lastSeenAt: string;
+  expiresAt: string;
+  /** The session this client is signed in with. */
+  current: boolean;
+}
+
+export async function getSessions() {
+  return request<SessionInfo[]>("/auth/sessions");
+}
+
+export async function revokeSession(sessionId: string) {
+  return request<void>(`/auth/sessions/${sessionId}`, { method: "DELETE" });
+}
+
+export async function revokeOtherSessions() {
+  return request<{ revoked: number }>("/auth/sessions/revoke-others", { method: "POST" });
+}
+
+// ── Sign-in attempts ──
+
+export interface LoginAttempt {
+  ip: string | null;
+  success: boolean;
+  createdAt: string;
+}
+
+/** Your own with "me"; anyone's for admins. `lockedFor` is seconds left, if locked. */
+export async function getLoginAttempts(userId = "me") {
+  return request<{ lockedFor: number | null; attempts: LoginAttempt[] }>(`/users/${userId}/login-attempts`);
+}
+
+/** Admin only. */
+export async function unlockAccount(userId: string) {
+  return request<void>(`/users/${userId}/lockout`, { method: "DELETE" });
+}
+
 // ── User Profile ──
 
-export async function updateUserProfile(data: { username?: string; image?: string | null; ringStyle?: RingStyle; ringSpin?: boolean; steamId?: string | null }) {
-  return request<{ id: string; username: string; email: string; image: string | null; ringStyle: RingStyle; ringSpin: boolean; steamId: string | null; ringPatternSeed: number | null; bannerCss: string | null; bannerPatternSeed: number | null }>("/users/me", {
+/** `locale` sets the language of server messages ("en", "es", "fr", "de" or "pt"); null follows the browser. */
+export async function updateUserProfile(data: { username?: string; image?: string | null; ringStyle?: RingStyle; ringSpin?: boolean; steamId?: string | null; bio?: string | null; pronouns?: string | null; accentColor?: string | null; timezone?: string | null; locale?: string | null }) {
+  return request<{ id: string; username: string; email: string; image: string | null; imageStatic: string | null; ringStyle: RingStyle; ringSpin: boolean; steamId: string | null; ringPatternSeed: number | null; bannerCss: string | null; bannerPatternSeed: number | null; bio: string | null; pronouns: string | null; accentColor: string | null; timezone: string | null; locale: string | null }>("/users/me", {
     method: "PATCH",
     body: JSON.stringify(data),
   });
 }
 
+/** Change username; rejected with 429 while the cooldown since the last change runs */
+export async function updateUsername(username: string) {
+  return request<{ id: string; username: string }>("/users/me/username", {
+    method: "PATCH",
+    body: JSON.stringify({ username }),
+  });
+}
+
+export async function getUserProfile(userId: string) {
+  return request<UserProfile>(`/users/${userId}/profile`);
+}
+
+// ── Blocking ──
+
+export async function getBlockedUsers() {
+  return request<BlockedUser[]>("/users/me/blocks");
+}
+
+/** Block a user; also declines any message request between the two of you */
+export async function blockUser(userId: string) {
+  return request<void>(`/users/me/blocks/${userId}`, { method: "PUT" });
+}
+
+export async function unblockUser(userId: string) {
+  return request<void>(`/users/me/blocks/${userId}`, { method: "DELETE" });
+}
+
+// ── Synced settings ──
+
+export async function getSyncedSettings() {
+  return request<SyncedSetting[]>("/users/me/settings");
+}
+
+/** Store a setting; the user's other sessions get it over the gateway */
+export async function putSyncedSetting(key: string, value: unknown) {
+  return request<SyncedSetting>(`/users/me/settings/${encodeURIComponent(key)}`, {
+    method: "PUT",
+    body: JSON.stringify({ value }),
+  });
+}
+
+export async function deleteSyncedSetting(key: string) {
+  return request<void>(`/users/me/settings/${encodeURIComponent(key)}`, { method: "DELETE" });
+}
+
+// ── Email digests ──
+
+export type DigestFrequency = "off" | "daily" | "weekly";
+
+export interface DigestSettings {
+  frequency: DigestFrequency;
+  lastSentAt: string | null;
+  /** Whether this server can send email at all */
+  available: boolean;
+}
+
+export async function getDigestSettings() {
+  return request<DigestSettings>("/users/me/digest");
+}
+
+/** Turning digests on needs a verified email address */
+export async function setDigestFrequency(frequency: DigestFrequency) {
+  return request<DigestSettings>("/users/me/digest", {
+    method: "PUT",
+    body: JSON.stringify({ frequency }),
+  });
+}
+
 // ── E2EE Keys ──
 
 export async function setPublicKey(publicKey: string) {
@@ -66,20 +241,126 @@ export async function getPublicKey(userId: string) {
   return request<{ publicKey: string | null }>(`/users/${userId}/public-key`);
 }
 
-export async function storeServerKey(serverId: string, encryptedKey: string, senderId: string) {
+export async function storeServerKey(serverId: string, encryptedKey: string, senderId: string, deviceId?: string) {
   return request<void>(`/servers/${serverId}/keys`, {
     method: "POST",
-    body: JSON.stringify({ encryptedKey, senderId }),
+    body: JSON.stringify({ encryptedKey, senderId, deviceId }),
   });
 }
 
-export async function getMyServerKey(serverId: string) {
-  return request<{ encryptedKey: string; senderId: string } | null>(`/servers/${serverId}/keys/me`);
+export async function getMyServerKey(serverId: string, deviceId?: string) {
+  const query = deviceId ? `?deviceId=${encodeURIComponent(deviceId)}` : "";
+  return request<{ encryptedKey: string; senderId: string; deviceId: string | null } | null>(
+    `/servers/${serverId}/keys/me${query}`,
+  );
 }
 
-export async function shareServerKeyWith(serverId: string, userId: string, encryptedKey: string, senderId: string) {
+export async function shareServerKeyWith(
+  serverId: string,
+  userId: string,
+  encryptedKey: string,
+  senderId: string,
+  deviceId?: string,
+) {
   return request<void>(`/servers/${serverId}/keys/${userId}`, {
     method: "POST",
-    body: JSON.stringify({ encryptedKey, senderId }),
+    body: JSON.stringify({ encryptedKey, senderId, deviceId }),
+  });
+}
+
+export interface DeviceKey {
+  deviceId: string;
+  publicKey: string;
+  name: string | null;
+  createdAt: string;
+  lastSeenAt: string;
+}
+
+export async function registerDevice(deviceId: string, publicKey: string, name?: string) {
+  return request<DeviceKey>(`/users/me/devices/${encodeURIComponent(deviceId)}`, {
+    method: "PUT",
+    body: JSON.stringify({ publicKey, name }),
+  });
+}
+
+export async function removeDevice(deviceId: string) {
+  return request<void>(`/users/me/devices/${encodeURIComponent(deviceId)}`, { method: "DELETE" });
+}
+
+export async function getUserDevices(userId: string) {
+  return request<DeviceKey[]>(`/users/${userId}/devices`);
+}
+
+export interface KeyBackup {
+  version: number;
+  wrappedKey: string;
+  salt: string;
+  kdf: string;
+  kdfParams: string;
+  publicKey: string;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export async function getKeyBackup() {
+  return request<KeyBackup>("/users/me/key-backup");
+}
+
+/** `expectedVersion` is the version being replaced; omit for the first backup. */
+export async function putKeyBackup(
+  backup: Pick<KeyBackup, "wrappedKey" | "salt" | "kdf" | "kdfParams" | "publicKey">,
+  expectedVersion?: number,
+) {
+  return request<{ version: number }>("/users/me/key-backup", {
+    method: "PUT",
+    body: JSON.stringify({ ...backup, expectedVersion }),
+  });
+}
+
+export async function deleteKeyBackup() {
+  return request<void>("/users/me/key-backup", { method: "DELETE" });
+}
+
+export async function confirmKeyBackupRestored(version: number, publicKey: string) {
+  return request<void>("/users/me/key-backup/restored", {
+    method: "POST",
+    body: JSON.stringify({ version, publicKey }),
   });
 }
+
+export interface SafetyNumber {
+  userId: string;
+  deviceId?: string;
+  safetyNumber: string;
+  verified: boolean;
+  /** Verified once, but the peer's key has changed since. */
+  stale: boolean;
+}
+
+export interface KeyVerification {
+  userId: string;
+  deviceId?: string;
+  verifiedAt: string;
+  stale: boolean;
+}
+
+export async function getSafetyNumber(userId: string, deviceId?: string) {
+  const query = deviceId ? `?deviceId=${encodeURIComponent(deviceId)}` : "";
+  return request<SafetyNumber>(`/users/${userId}/safety-number${query}`);
+}
+
+export async function verifyUserKey(userId: string, safetyNumber: string, deviceId?: string) {
+  return request<{ userId: string; verified: boolean; verifiedAt: string }>(`/users/${userId}/verification`, {
+    method: "PUT",
+    body: JSON.stringify({ safetyNumber, deviceId }),
+  });
+}
+
+export async function unverifyUserKey(userId: string, deviceId?: string) {
+  const query = deviceId ? `?deviceId=${encodeURIComponent(deviceId)}` : "";
+  return request<void>(`/users/${userId}/verification${query}`, { method: "DELETE" });
+}
+
+export async function getKeyVerifications() {
+  return request<KeyVerification[]>("/users/me/verifications");
+}
@@ -1,4 +1,4 @@
-import type { RingStyle } from "@/types/shared.js";
+import type { PaginatedResponse, RingStyle } from "@/types/shared.js";
 
 import { API_BASE } from "@/lib/serverUrl.js";
 
@@ -23,6 +23,20 @@ export function setStoredToken(token: string | null) {
   }
 }
 
+/** An error response from the server. `code` is stable to branch on; the message is for display. */
+export class ApiError extends Error {
+  constructor(message: string, public status: number, public code?: string, public retryAfter?: number) {
+    super(message);
+    this.name = "ApiError";
+  }
+}
+
+/** The double-submit CSRF token the server sets alongside the session cookie. */
+function csrfToken(): string | null {
+  const match = document.cookie.match(/(?:^|;\s*)flux\.csrf_token=([^;]*)/);
+  return match ? decodeURIComponent(match[1]) : null;
+}
+
 export async function request<T>(path: string, options?: RequestInit): Promise<T> {
   const headers: Record<string, string> = { ...options?.headers as Record<string, string> };
   if (options?.body) {
@@ -32,6 +46,10 @@ export async function request<T>(path: string, options?: RequestInit): Promise<T
   const token = getStoredToken();
   if (token) {
     headers["Authorization"] = `Bearer ${token}`;
+  } else {
+    // Cookie sessions must echo the CSRF token on state-changing requests
+    const csrf = csrfToken();
+    if (csrf) headers["X-CSRF-Token"] = csrf;
   }
   const res = await fetch(`${API_BASE}${path}`, {
     credentials: "include",
@@ -40,11 +58,25 @@ export async function request<T>(path: string, options?: RequestInit): Promise<T
   });
 
   if (!res.ok) {
-    // TODO: improve error handling — catch returns {} so body.error is undefined (not a crash), but a typed error response would be better
     const body = await res.json().catch(() => ({}));
-    throw new Error(body.error ?? `Request failed: ${res.status}`);
+    throw new ApiError(body.error ?? `Request failed: ${res.status}`, res.status, body.code, body.retryAfter);
   }
 
   if (res.status === 204) return undefined as T;
   return res.json();
 }
+
+/** Fetch every page of a cursor-paginated list endpoint. */
+export async function requestAll<T>(path: string): Promise<T[]> {
+  const sep = path.includes("?") ? "&" : "?";
+  const items: T[] = [];
+  let cursor: string | null = null;
+  do {
+    const page: PaginatedResponse<T> = await request<PaginatedResponse<T>>(
+      cursor ? `${path}${sep}cursor=${encodeURIComponent(cursor)}` : path,
+    );
+    items.push(...page.items);
+    cursor = page.hasMore ? page.cursor : null;
+  } while (cursor);
+  return items;
+}
@@ -0,0 +1,89 @@
+import { request } from "./base.js";
+
+// ── Economy ──
+
+export interface Wallet {
+  balance: number;
+  streak: number;
+  dailyClaimed: boolean;
+  today: { total: number; byReason: Record<string, number> };
+}
+
+export interface RewardRule {
+  rule: string;
+  amount: number;
+  dailyCap: number;
+  cooldownSecs: number;
+  enabled: number;
+}
+
+export async function getWallet() {
+  return request<Wallet>("/economy/wallet");
+}
+
+export async function claimDailyReward() {
+  return request<{ amount: number; streak: number; balance: number }>("/economy/daily", {
+    method: "POST",
+  });
+}
+
+export async function getRewardRules() {
+  return request<RewardRule[]>("/economy/rules");
+}
+
+export async function updateRewardRule(
+  rule: string,
+  data: Partial<{ amount: number; dailyCap: number; cooldownSecs: number; enabled: boolean }>,
+) {
+  return request<RewardRule>(`/economy/rules/${rule}`, {
+    method: "PATCH",
+    body: JSON.stringify(data),
+  });
+}
+
+export interface CoinLogEntry {
+  id: string;
+  amount: number;
+  reason: string;
+  serverId: string | null;
+  note: string | null;
+  actorId: string | null;
+  createdAt: string;
+}
+
+export interface ServerEconomyStatus {
+  serverId: string;
+  paused: boolean;
+  pausedUntil: string | null;
+  reason: string | null;
+}
+
+/** Admin: positive amounts grant, negative revoke. */
+export async function adjustCoins(userId: string, amount: number, reason: string, serverId?: string) {
+  return request<{ userId: string; balance: number }>("/economy/admin/coins", {
+    method: "POST",
+    body: JSON.stringify({ userId, amount, reason, serverId }),
+  });
+}
+
+export async function getCoinLog(userId: string) {
+  return request<CoinLogEntry[]>(`/economy/admin/log/${userId}`);
+}
+
+export async function getServerEconomy(serverId: string) {
+  return request<ServerEconomyStatus>(`/economy/servers/${serverId}`);
+}
+
+/** Admin: pause for `minutes`, or until resumed if omitted. */
+export async function pauseServerEconomy(serverId: string, minutes?: number, reason?: string) {
+  return request<ServerEconomyStatus>(`/economy/servers/${serverId}/pause`, {
+    method: "PUT",
+    body: JSON.stringify({ minutes, reason }),
+  });
+}
+
+export async function resumeServerEconomy(serverId: string) {
+  return request<{ serverId: string; paused: boolean }>(`/economy/servers/${serverId}/pause`, {
+    method: "DELETE",
+  });
+}
@@ -0,0 +1,82 @@
+import type { EventRecurrence, EventRsvp, RsvpStatus, ScheduledEvent } from "@/types/shared.js";
+
+import { API_BASE, ApiError, getStoredToken, request } from "./base.js";
+
+// ── Scheduled events ──
+
+export async function getServerEvents(serverId: string) {
+  return request<ScheduledEvent[]>(`/servers/${serverId}/events`);
+}
+
+export async function getServerEvent(serverId: string, eventId: string) {
+  return request<ScheduledEvent>(`/servers/${serverId}/events/${eventId}`);
+}
+
+export async function createServerEvent(
+  serverId: string,
+  data: {
+    title: string;
+    description?: string;
+    startAt: string;
+    endAt?: string;
+    channelId?: string;
+    recurrence?: EventRecurrence;
+  },
+) {
+  return request<ScheduledEvent>(`/servers/${serverId}/events`, {
+    method: "POST",
+    body: JSON.stringify(data),
+  });
+}
+
+/** An empty string for endAt, channelId or recurrence clears it. */
+export async function updateServerEvent(
+  serverId: string,
+  eventId: string,
+  data: {
+    title?: string;
+    description?: string;
+    startAt?: string;
+    endAt?: string;
+    channelId?: string;
+    recurrence?: EventRecurrence | "";
+  },
+) {
+  return request<ScheduledEvent>(`/servers/${serverId}/events/${eventId}`, {
+    method: "PATCH",
+    body: JSON.stringify(data),
+  });
+}
+
+export async function deleteServerEvent(serverId: string, eventId: string) {
+  return request<void>(`/servers/${serverId}/events/${eventId}`, { method: "DELETE" });
+}
+
+export async function setEventRsvp(serverId: string, eventId: string, status: RsvpStatus) {
+  return request<ScheduledEvent>(`/servers/${serverId}/events/${eventId}/rsvp`, {
+    method: "PUT",
+    body: JSON.stringify({ status }),
+  });
+}
+
+export async function clearEventRsvp(serverId: string, eventId: string) {
+  return request<ScheduledEvent>(`/servers/${serverId}/events/${eventId}/rsvp`, { method: "DELETE" });
+}
+
+export async function getEventRsvps(serverId: string, eventId: string) {
+  return request<EventRsvp[]>(`/servers/${serverId}/events/${eventId}/rsvps`);
+}
+
+/** The server's events as an iCalendar (.ics) document. */
+export async function exportServerEventsIcs(serverId: string): Promise<string> {
+  const token = getStoredToken();
+  const res = await fetch(`${API_BASE}/servers/${serverId}/events.ics`, {
+    credentials: "include",
+    headers: token ? { Authorization: `Bearer ${token}` } : {},
+  });
+  if (!res.ok) {
+    const body = await res.json().catch(() => ({}));
+    throw new ApiError(body.error ?? `Request failed: ${res.status}`, res.status, body.code);
+  }
+  return res.text();
+}
@@ -1,12 +1,12 @@
 import type { GallerySet, GallerySetDetail } from "@/types/shared.js";
 
-import { request } from "./base.js";
+import { request, requestAll } from "./base.js";
 
 // ── Gallery ──
 
 export async function getGallerySets(query?: string) {
   const params = query ? `?q=${encodeURIComponent(query)}` : "";
-  return request<GallerySet[]>(`/gallery${params}`);
+  return requestAll<GallerySet>(`/gallery${params}`);
 }
 
 export async function getSubscribedSets() {
@@ -70,3 +70,23 @@ export async function removeImageFromSet(setId: string, imageId: string) {
     method: "DELETE",
   });
 }
+
+export async function reorderSetImages(setId: string, items: { id: string; name?: string }[]) {
+  return request<void>(`/gallery/${setId}/images/order`, {
+    method: "PUT",
+    body: JSON.stringify({ items }),
+  });
+}
+
+export async function addSetCollaborator(setId: string, userId: string) {
+  return request<void>(`/gallery/${setId}/collaborators`, {
+    method: "POST",
+    body: JSON.stringify({ userId }),
+  });
+}
+
+export async function removeSetCollaborator(setId: string, userId: string) {
+  return request<void>(`/gallery/${setId}/collaborators/${userId}`, {
+    method: "DELETE",
+  });
+}
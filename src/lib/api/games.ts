@@ -0,0 +1,62 @@
+import type { Game, GameSettings } from "@/types/shared.js";
+import { request } from "./base.js";
+
+// ── Games ──
+
+export interface GameSeed {
+  serverSeedHash: string;
+  clientSeed: string;
+  nonce: number;
+}
+
+export async function getGameSeed() {
+  return request<GameSeed>("/games/seed");
+}
+
+/** Start a new seed pair; the response reveals the previous server seed. */
+export async function rotateGameSeed(clientSeed?: string) {
+  return request<GameSeed & { previous: (GameSeed & { serverSeed: string }) | null }>("/games/seed/rotate", {
+    method: "POST",
+    body: JSON.stringify({ clientSeed }),
+  });
+}
+
+export async function playDice(channelId: string, wager: number, target: number) {
+  return request<Game>("/games/dice", {
+    method: "POST",
+    body: JSON.stringify({ channelId, wager, target }),
+  });
+}
+
+export async function createCoinflip(channelId: string, wager: number) {
+  return request<Game>("/games/coinflip", {
+    method: "POST",
+    body: JSON.stringify({ channelId, wager }),
+  });
+}
+
+export async function acceptCoinflip(gameId: string) {
+  return request<Game>(`/games/coinflip/${gameId}/accept`, { method: "POST" });
+}
+
+export async function cancelCoinflip(gameId: string) {
+  return request<Game>(`/games/coinflip/${gameId}/cancel`, { method: "POST" });
+}
+
+export async function getGame(gameId: string) {
+  return request<Game>(`/games/${gameId}`);
+}
+
+export async function getGameSettings(serverId: string) {
+  return request<GameSettings>(`/games/settings/${serverId}`);
+}
+
+export async function updateGameSettings(
+  serverId: string,
+  data: Partial<{ enabled: boolean; houseEdgeBps: number; dailyLossLimit: number }>,
+) {
+  return request<GameSettings>(`/games/settings/${serverId}`, {
+    method: "PATCH",
+    body: JSON.stringify(data),
+  });
+}
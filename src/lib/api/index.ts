@@ -1,5 +1,5 @@
 // ── Base helpers (kept here for backwards compatibility) ──
-export { getStoredToken } from "./base.js";
+export { ApiError, getStoredToken } from "./base.js";
 
 // ── Domain re-exports ──
 export {
@@ -7,23 +7,78 @@ export {
   signIn,
   signOut,
   getSession,
+  forgotPassword,
+  resetPassword,
+  resendVerification,
+  changeEmail,
+  cancelEmailChange,
+  startOidc,
+  completeOidc,
+  unlinkOidc,
+  getSessions,
+  revokeSession,
+  revokeOtherSessions,
+  getLoginAttempts,
+  unlockAccount,
   updateUserProfile,
+  updateUsername,
+  getBlockedUsers,
+  getSyncedSettings,
+  putSyncedSetting,
+  deleteSyncedSetting,
+  getDigestSettings,
+  setDigestFrequency,
+  blockUser,
+  unblockUser,
   setPublicKey,
   getPublicKey,
   storeServerKey,
   getMyServerKey,
   shareServerKeyWith,
+  getKeyBackup,
+  putKeyBackup,
+  deleteKeyBackup,
+  confirmKeyBackupRestored,
+  registerDevice,
+  removeDevice,
+  getUserDevices,
+  getSafetyNumber,
+  verifyUserKey,
+  unverifyUserKey,
+  getKeyVerifications,
 } from "./auth.js";
+export type { SessionInfo, LoginAttempt, KeyBackup, DeviceKey, SafetyNumber, KeyVerification, DigestFrequency, DigestSettings } from "./auth.js";
 
 export {
   getServers,
   updateServer,
+  getServerInsights,
+  getServerRoles,
+  createServerRole,
+  deleteServerRole,
+  getReactionRoles,
+  createReactionRole,
+  deleteReactionRole,
+  getWelcomeScreen,
+  updateWelcomeScreen,
+  deleteWelcomeScreen,
+  acceptServerRules,
+  getServerVerification,
+  updateServerVerification,
+  submitVerificationChallenge,
   leaveServer,
   getServerMembers,
   updateMemberRole,
   getWhitelist,
   addToWhitelist,
   removeFromWhitelist,
+  getSignupInvites,
+  createSignupInvite,
+  revokeSignupInvite,
+  requestAccess,
+  getAccessRequests,
+  approveAccessRequest,
+  denyAccessRequest,
   getChannels,
   createChannel,
   updateChannel,
@@ -33,9 +88,17 @@ export {
   inviteToRoom,
   moveUserToRoom,
   reorderChannels,
+  getChannelFeeds,
+  addChannelFeed,
+  removeChannelFeed,
+  getChannelIntegrations,
+  createChannelIntegration,
+  rotateChannelIntegration,
+  revokeChannelIntegration,
   getCustomEmojis,
   createCustomEmoji,
   deleteCustomEmoji,
+  getEmojiStats,
   getEmojiFavorites,
   addStandardFavorite,
   removeStandardFavorite,
@@ -45,12 +108,28 @@ export {
 
 export {
   getMessages,
+  getMessage,
+  getMessagesAt,
+  ackChannel,
+  exportChannelTranscript,
   searchServerMessages,
   getReactions,
+  getReactionSummaries,
+  remindAboutMessage,
+  getMyReminders,
+  deleteReminder,
+  saveMessage,
+  unsaveMessage,
+  getSavedMessages,
   getDMChannels,
   createDM,
+  getDMRequests,
+  acceptDMRequest,
+  declineDMRequest,
   getDMMessages,
+  ackDM,
   searchDMMessages,
+  quickSwitcher,
   uploadFile,
   getFileUrl,
   getLinkPreview,
@@ -58,6 +137,13 @@ export {
 
 export {
   getVoiceToken,
+  getVoiceNodes,
+  reportVoiceLatency,
+  getStage,
+  getVoiceHistory,
+  getServerVoiceStats,
+  getServerVoiceQuality,
+  getNoiseModelVersion,
 } from "./voice.js";
 
 export {
@@ -70,9 +156,19 @@ export {
   getListeningSession,
   addToQueue,
   removeFromQueue,
+  getSessionHistory,
+  requeueFromHistory,
+  getSessionLyrics,
   deleteListeningSession,
+  initLastfmAuth,
+  getLastfmStatus,
+  setLastfmScrobbling,
+  unlinkLastfm,
   searchYouTubeTracks,
   getYouTubeAudioUrl,
+  prefetchYouTubeAudio,
+  searchMediaTracks,
+  getMediaAudioUrl,
 } from "./spotify.js";
 
 export {
@@ -89,4 +185,46 @@ export {
   createRoadmapItem,
   updateRoadmapItem,
   deleteRoadmapItem,
+  voteRoadmapItem,
+  getRoadmapComments,
+  createRoadmapComment,
+  deleteRoadmapComment,
 } from "./roadmap.js";
+
+export {
+  getServerEvents,
+  getServerEvent,
+  createServerEvent,
+  updateServerEvent,
+  deleteServerEvent,
+  setEventRsvp,
+  clearEventRsvp,
+  getEventRsvps,
+  exportServerEventsIcs,
+} from "./events.js";
+
+export {
+  getWallet,
+  claimDailyReward,
+  getRewardRules,
+  updateRewardRule,
+  adjustCoins,
+  getCoinLog,
+  getServerEconomy,
+  pauseServerEconomy,
+  resumeServerEconomy,
+} from "./economy.js";
+export type { Wallet, RewardRule, CoinLogEntry, ServerEconomyStatus } from "./economy.js";
+
+export {
+  getGameSeed,
+  rotateGameSeed,
+  playDice,
+  createCoinflip,
+  acceptCoinflip,
+  cancelCoinflip,
+  getGame,
+  getGameSettings,
+  updateGameSettings,
+} from "./games.js";
+export type { GameSeed } from "./games.js";
@@ -1,13 +1,20 @@
 import type {
   Message,
+  MessageContext,
+  MessagePage,
   PaginatedResponse,
   Reaction,
+  ReactionSummary,
+  MessageReminder,
+  SavedMessage,
   DMMessage,
+  DMChannel,
   Attachment,
   LinkPreview,
+  QuickSwitcherItem,
 } from "@/types/shared.js";
 
-import { API_BASE, request, getStoredToken } from "./base.js";
+import { API_BASE, ApiError, request, getStoredToken } from "./base.js";
 
 // ── Messages ──
 
@@ -16,6 +23,23 @@ export async function getMessages(channelId: string, cursor?: string) {
   return request<PaginatedResponse<Message>>(`/channels/${channelId}/messages${params}`);
 }
 
+/** Fetch a page newer than `after`, centred on a message, or starting at a date (YYYY-MM-DD). */
+export async function getMessagesAt(
+  channelId: string,
+  at: { after: string } | { around: string } | { date: string },
+  limit?: number,
+) {
+  const params = new URLSearchParams(at);
+  if (limit !== undefined) params.set("limit", String(limit));
+  return request<MessagePage>(`/channels/${channelId}/messages?${params}`);
+}
+
+/** Fetch one message with up to `context` messages either side of it. */
+export async function getMessage(messageId: string, context?: number) {
+  const params = context !== undefined ? `?context=${context}` : "";
+  return request<MessageContext>(`/messages/${messageId}${params}`);
+}
+
 // ── Search ──
 
 interface ServerSearchOptions {
@@ -48,30 +72,118 @@ export async function getReactions(messageIds: string[]) {
   return request<Reaction[]>(`/messages/reactions?ids=${messageIds.join(",")}`);
 }
 
+export async function getReactionSummaries(messageIds: string[]) {
+  return request<ReactionSummary[]>(`/messages/reactions/summary?ids=${messageIds.join(",")}`);
+}
+
+// ── Reminders ──
+
+/** Remind me about a channel message or DM, after `delaySeconds` or at `remindAt`. */
+export async function remindAboutMessage(
+  messageId: string,
+  when: { delaySeconds: number } | { remindAt: string },
+  note?: string,
+) {
+  return request<MessageReminder>(`/messages/${messageId}/remind`, {
+    method: "POST",
+    body: JSON.stringify({ ...when, note }),
+  });
+}
+
+export async function getMyReminders() {
+  return request<MessageReminder[]>("/users/me/reminders");
+}
+
+export async function deleteReminder(reminderId: string) {
+  return request<void>(`/users/me/reminders/${reminderId}`, { method: "DELETE" });
+}
+
+// ── Saved messages ──
+
+export async function saveMessage(messageId: string) {
+  return request<SavedMessage>(`/messages/${messageId}/save`, { method: "PUT" });
+}
+
+export async function unsaveMessage(messageId: string) {
+  return request<void>(`/messages/${messageId}/save`, { method: "DELETE" });
+}
+
+export async function getSavedMessages(cursor?: string) {
+  const params = cursor ? `?cursor=${encodeURIComponent(cursor)}` : "";
+  return request<PaginatedResponse<SavedMessage>>(`/users/me/saved${params}`);
+}
+
+/** Record that everything in the channel so far has been read; email digests skip it */
+export async function ackChannel(channelId: string) {
+  return request<void>(`/channels/${channelId}/read`, { method: "PUT" });
+}
+
+/** A text channel as a standalone HTML transcript (admins only). `embedImages` inlines images so the file works offline. */
+export async function exportChannelTranscript(channelId: string, embedImages = false): Promise<Blob> {
+  const token = getStoredToken();
+  const params = embedImages ? "?embedImages=true" : "";
+  const res = await fetch(`${API_BASE}/channels/${channelId}/export${params}`, {
+    method: "POST",
+    credentials: "include",
+    headers: token ? { Authorization: `Bearer ${token}` } : {},
+  });
+  if (!res.ok) {
+    const body = await res.json().catch(() => ({}));
+    throw new ApiError(body.error ?? `Request failed: ${res.status}`, res.status, body.code);
+  }
+  return res.blob();
+}
+
 // ── Direct Messages ──
 
 export async function getDMChannels() {
-  return request<{ id: string; otherUser: { id: string; username: string; image: string | null }; createdAt: string }[]>("/dms");
+  return request<DMChannel[]>("/dms");
 }
 
+/** Opens a message request for someone new; opening one you were sent accepts it. */
 export async function createDM(userId: string) {
-  return request<{ id: string; otherUser: { id: string; username: string; image: string | null }; createdAt: string }>("/dms", {
+  return request<DMChannel>("/dms", {
     method: "POST",
     body: JSON.stringify({ userId }),
   });
 }
 
+/** Message requests waiting on you, each with the message that came with it. */
+export async function getDMRequests() {
+  return request<{ channel: DMChannel; message: DMMessage | null }[]>("/dms/requests");
+}
+
+export async function acceptDMRequest(dmChannelId: string) {
+  return request<void>(`/dms/${dmChannelId}/accept`, { method: "POST" });
+}
+
+/** Drops the request's message; the sender isn't told. */
+export async function declineDMRequest(dmChannelId: string) {
+  return request<void>(`/dms/${dmChannelId}/decline`, { method: "POST" });
+}
+
 export async function getDMMessages(dmChannelId: string, cursor?: string) {
   const params = cursor ? `?cursor=${encodeURIComponent(cursor)}` : "";
   return request<PaginatedResponse<DMMessage>>(`/dms/${dmChannelId}/messages${params}`);
 }
 
+export async function ackDM(dmChannelId: string) {
+  return request<void>(`/dms/${dmChannelId}/read`, { method: "PUT" });
+}
+
 export async function searchDMMessages(dmChannelId: string, query: string) {
   return request<{ items: DMMessage[] }>(
     `/dms/${dmChannelId}/messages/search?q=${encodeURIComponent(query)}`
   );
 }
 
+/** Channels, DMs and people matching `query`, best match first */
+export async function quickSwitcher(query: string, limit?: number) {
+  const params = new URLSearchParams({ q: query });
+  if (limit) params.set("limit", String(limit));
+  return request<QuickSwitcherItem[]>(`/quick-switcher?${params}`);
+}
+
 // ── Files ──
 
 export function uploadFile(file: File, onProgress?: (pct: number) => void): Promise<Attachment> {
@@ -1,4 +1,4 @@
-import type { RoadmapItem } from "@/types/shared.js";
+import type { RoadmapComment, RoadmapItem } from "@/types/shared.js";
 
 import { request } from "./base.js";
 
@@ -44,3 +44,30 @@ export async function deleteRoadmapItem(serverId: string, itemId: string) {
     method: "DELETE",
   });
 }
+
+export async function voteRoadmapItem(serverId: string, itemId: string, voted: boolean) {
+  return request<{ voteCount: number; voted: boolean }>(`/servers/${serverId}/roadmap/${itemId}/vote`, {
+    method: voted ? "POST" : "DELETE",
+  });
+}
+
+export async function getRoadmapComments(serverId: string, itemId: string) {
+  return request<RoadmapComment[]>(`/servers/${serverId}/roadmap/${itemId}/comments`);
+}
+
+export async function createRoadmapComment(
+  serverId: string,
+  itemId: string,
+  data: { content: string; parentId?: string },
+) {
+  return request<RoadmapComment>(`/servers/${serverId}/roadmap/${itemId}/comments`, {
+    method: "POST",
+    body: JSON.stringify(data),
+  });
+}
+
+export async function deleteRoadmapComment(serverId: string, itemId: string, commentId: string) {
+  return request<void>(`/servers/${serverId}/roadmap/${itemId}/comments/${commentId}`, {
+    method: "DELETE",
+  });
+}
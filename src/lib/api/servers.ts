@@ -2,16 +2,30 @@ import type {
   Server,
   Channel,
   UpdateServerRequest,
+  ServerInsights,
+  ServerRole,
+  ReactionRole,
+  WelcomeScreen,
+  VerificationSettings,
+  VerificationStatus,
+  UnmetRequirement,
   CreateChannelRequest,
   UpdateChannelRequest,
   MemberWithUser,
   ReorderItem,
+  ChannelFeed,
+  ChannelIntegration,
+  IntegrationProvider,
+  IssuedIntegration,
   WhitelistEntry,
+  SignupInvite,
+  AccessRequest,
   CustomEmoji,
+  EmojiUsageStats,
   EmojiFavorites,
 } from "@/types/shared.js";
 
-import { request } from "./base.js";
+import { request, requestAll } from "./base.js";
 
 // ── Servers ──
 
@@ -26,6 +40,100 @@ export async function updateServer(serverId: string, data: UpdateServerRequest)
   });
 }
 
+/** Activity dashboard for the last `days` days (admins only). Cached server-side for a few minutes. */
+export async function getServerInsights(serverId: string, days?: number) {
+  const params = days !== undefined ? `?days=${days}` : "";
+  return request<ServerInsights>(`/servers/${serverId}/insights${params}`);
+}
+
+// ── Roles ──
+
+export async function getServerRoles(serverId: string) {
+  return request<ServerRole[]>(`/servers/${serverId}/roles`);
+}
+
+export async function createServerRole(serverId: string, data: { name: string; color?: string | null }) {
+  return request<ServerRole>(`/servers/${serverId}/roles`, {
+    method: "POST",
+    body: JSON.stringify(data),
+  });
+}
+
+export async function deleteServerRole(serverId: string, roleId: string) {
+  return request<void>(`/servers/${serverId}/roles/${roleId}`, {
+    method: "DELETE",
+  });
+}
+
+/** Owners and admins. */
+export async function getReactionRoles(serverId: string) {
+  return request<ReactionRole[]>(`/servers/${serverId}/reaction-roles`);
+}
+
+export async function createReactionRole(serverId: string, data: { messageId: string; emoji: string; roleId: string }) {
+  return request<ReactionRole>(`/servers/${serverId}/reaction-roles`, {
+    method: "POST",
+    body: JSON.stringify(data),
+  });
+}
+
+export async function deleteReactionRole(serverId: string, bindingId: string) {
+  return request<void>(`/servers/${serverId}/reaction-roles/${bindingId}`, {
+    method: "DELETE",
+  });
+}
+
+// ── Welcome screen ──
+
+export async function getWelcomeScreen(serverId: string) {
+  return request<WelcomeScreen>(`/servers/${serverId}/welcome`);
+}
+
+/** Replaces the whole screen (admins only). Changing the rules asks members to accept them again. */
+export async function updateWelcomeScreen(
+  serverId: string,
+  data: { message?: string | null; rules?: string | null; suggestedChannelIds?: string[] },
+) {
+  return request<WelcomeScreen>(`/servers/${serverId}/welcome`, {
+    method: "PUT",
+    body: JSON.stringify(data),
+  });
+}
+
+export async function deleteWelcomeScreen(serverId: string) {
+  return request<void>(`/servers/${serverId}/welcome`, {
+    method: "DELETE",
+  });
+}
+
+export async function acceptServerRules(serverId: string) {
+  return request<{ rulesAcceptedAt: string }>(`/servers/${serverId}/welcome/accept`, {
+    method: "POST",
+  });
+}
+
+// ── New-member verification ──
+
+export async function getServerVerification(serverId: string) {
+  return request<VerificationStatus>(`/servers/${serverId}/verification`);
+}
+
+/** Owners and admins. */
+export async function updateServerVerification(serverId: string, data: Partial<VerificationSettings>) {
+  return request<VerificationSettings>(`/servers/${serverId}/verification`, {
+    method: "PATCH",
+    body: JSON.stringify(data),
+  });
+}
+
+/** Submit the token from the captcha widget rendered with `challengeSiteKey`. */
+export async function submitVerificationChallenge(serverId: string, token: string) {
+  return request<{ unmet: UnmetRequirement[] }>(`/servers/${serverId}/verification/challenge`, {
+    method: "POST",
+    body: JSON.stringify({ token }),
+  });
+}
+
 export async function leaveServer(serverId: string) {
   return request<void>(`/servers/${serverId}/members/me`, {
     method: "DELETE",
@@ -33,7 +141,22 @@ export async function leaveServer(serverId: string) {
 }
 
 export async function getServerMembers(serverId: string) {
-  return request<MemberWithUser[]>(`/servers/${serverId}/members`);
+  return requestAll<MemberWithUser>(`/servers/${serverId}/members`);
+}
+
+export async function updateMyMemberProfile(serverId: string, data: { nickname?: string | null; avatar?: string | null }) {
+  return request<void>(`/servers/${serverId}/members/me/profile`, {
+    method: "PATCH",
+    body: JSON.stringify(data),
+  });
+}
+
+/** Owners and admins. */
+export async function setMemberNickname(serverId: string, userId: string, nickname: string | null) {
+  return request<void>(`/servers/${serverId}/members/${userId}/nickname`, {
+    method: "PATCH",
+    body: JSON.stringify({ nickname }),
+  });
 }
 
 export async function updateMemberRole(userId: string, role: string) {
@@ -46,7 +169,7 @@ export async function updateMemberRole(userId: string, role: string) {
 // ── Whitelist ──
 
 export async function getWhitelist() {
-  return request<WhitelistEntry[]>("/whitelist");
+  return requestAll<WhitelistEntry>("/whitelist");
 }
 
 export async function addToWhitelist(emails: string[]) {
@@ -60,6 +183,41 @@ export async function removeFromWhitelist(id: string) {
   return request<void>(`/whitelist/${id}`, { method: "DELETE" });
 }
 
+export async function getSignupInvites() {
+  return request<SignupInvite[]>("/whitelist/invites");
+}
+
+/** The returned token is only ever shown here. */
+export async function createSignupInvite(data: { note?: string; expiresInHours?: number } = {}) {
+  return request<SignupInvite & { token: string }>("/whitelist/invites", {
+    method: "POST",
+    body: JSON.stringify(data),
+  });
+}
+
+export async function revokeSignupInvite(id: string) {
+  return request<void>(`/whitelist/invites/${id}`, { method: "DELETE" });
+}
+
+export async function requestAccess(email: string, reason?: string) {
+  return request<void>("/auth/request-access", {
+    method: "POST",
+    body: JSON.stringify({ email, reason }),
+  });
+}
+
+export async function getAccessRequests() {
+  return request<AccessRequest[]>("/whitelist/requests");
+}
+
+export async function approveAccessRequest(id: string) {
+  return request<void>(`/whitelist/requests/${id}/approve`, { method: "POST" });
+}
+
+export async function denyAccessRequest(id: string) {
+  return request<void>(`/whitelist/requests/${id}/deny`, { method: "POST" });
+}
+
 // ── Channels ──
 
 export async function getChannels(serverId: string) {
@@ -118,10 +276,60 @@ export async function reorderChannels(serverId: string, items: ReorderItem[]) {
   });
 }
 
+// ── Channel Feeds ──
+
+export async function getChannelFeeds(serverId: string, channelId: string) {
+  return request<ChannelFeed[]>(`/servers/${serverId}/channels/${channelId}/feeds`);
+}
+
+export async function addChannelFeed(serverId: string, channelId: string, url: string) {
+  return request<ChannelFeed>(`/servers/${serverId}/channels/${channelId}/feeds`, {
+    method: "POST",
+    body: JSON.stringify({ url }),
+  });
+}
+
+export async function removeChannelFeed(serverId: string, channelId: string, feedId: string) {
+  return request<void>(`/servers/${serverId}/channels/${channelId}/feeds/${feedId}`, {
+    method: "DELETE",
+  });
+}
+
+// ── Channel Integrations ──
+
+export async function getChannelIntegrations(serverId: string, channelId: string) {
+  return request<ChannelIntegration[]>(`/servers/${serverId}/channels/${channelId}/integrations`);
+}
+
+export async function createChannelIntegration(
+  serverId: string,
+  channelId: string,
+  provider: IntegrationProvider,
+  name?: string,
+) {
+  return request<IssuedIntegration>(`/servers/${serverId}/channels/${channelId}/integrations`, {
+    method: "POST",
+    body: JSON.stringify({ provider, name }),
+  });
+}
+
+export async function rotateChannelIntegration(serverId: string, channelId: string, integrationId: string) {
+  return request<IssuedIntegration>(
+    `/servers/${serverId}/channels/${channelId}/integrations/${integrationId}/rotate`,
+    { method: "POST" },
+  );
+}
+
+export async function revokeChannelIntegration(serverId: string, channelId: string, integrationId: string) {
+  return request<void>(`/servers/${serverId}/channels/${channelId}/integrations/${integrationId}`, {
+    method: "DELETE",
+  });
+}
+
 // ── Custom Emoji ──
 
 export async function getCustomEmojis(serverId: string) {
-  return request<CustomEmoji[]>(`/servers/${serverId}/emojis`);
+  return requestAll<CustomEmoji>(`/servers/${serverId}/emojis`);
 }
 
 export async function createCustomEmoji(
@@ -140,6 +348,12 @@ export async function deleteCustomEmoji(serverId: string, emojiId: string) {
   });
 }
 
+/** Usage per custom emoji over the last `days` days, least recently used first (admins only). */
+export async function getEmojiStats(serverId: string, days?: number) {
+  const params = days !== undefined ? `?days=${days}` : "";
+  return request<{ days: number; emojis: EmojiUsageStats[] }>(`/servers/${serverId}/emojis/stats${params}`);
+}
+
 export async function getEmojiFavorites() {
   return request<EmojiFavorites>("/me/emoji-favorites");
 }
@@ -3,6 +3,8 @@ import type {
   SpotifyTrack,
   ListeningSession,
   QueueItem,
+  HistoryItem,
+  LyricLine,
 } from "@/types/shared.js";
 
 import { API_BASE, request, getStoredToken } from "./base.js";
@@ -38,15 +40,15 @@ export async function searchSpotifyTracks(q: string) {
   return request<SpotifySearchResponse>(`/spotify/search?q=${encodeURIComponent(q)}`);
 }
 
-export async function createListeningSession(voiceChannelId: string) {
+export async function createListeningSession(voiceChannelId: string, sessionType: "audio" | "video" = "audio") {
   return request<{ sessionId: string; existing?: boolean }>("/spotify/sessions", {
     method: "POST",
-    body: JSON.stringify({ voiceChannelId }),
+    body: JSON.stringify({ voiceChannelId, sessionType }),
   });
 }
 
 export async function getListeningSession(voiceChannelId: string) {
-  return request<{ session: ListeningSession | null; queue: QueueItem[] }>(
+  return request<{ session: ListeningSession | null; queue: QueueItem[]; cohostIds: string[] }>(
     `/spotify/sessions/channel/${voiceChannelId}`
   );
 }
@@ -68,19 +70,80 @@ export async function removeFromQueue(sessionId: string, itemId: string) {
   });
 }
 
+export async function getSessionHistory(sessionId: string, limit?: number) {
+  const qs = limit ? `?limit=${limit}` : "";
+  return request<{ history: HistoryItem[] }>(`/spotify/sessions/${sessionId}/history${qs}`);
+}
+
+export async function requeueFromHistory(sessionId: string, historyId: string) {
+  return request<{ id: string }>(`/spotify/sessions/${sessionId}/history/${historyId}/requeue`, {
+    method: "POST",
+  });
+}
+
+/** Fetches lyrics and also broadcasts them as `lyrics_sync` to the voice channel. */
+export async function getSessionLyrics(sessionId: string) {
+  return request<{ trackUri: string; lines: LyricLine[]; positionMs: number; isPlaying: boolean }>(
+    `/spotify/sessions/${sessionId}/lyrics`,
+  );
+}
+
 export async function deleteListeningSession(sessionId: string) {
   return request<{ success: boolean }>(`/spotify/sessions/${sessionId}/end`, {
     method: "DELETE",
   });
 }
 
+// ── Last.fm ──
+
+export async function initLastfmAuth() {
+  return request<{ url: string; state: string }>("/lastfm/init-auth", { method: "POST" });
+}
+
+export async function getLastfmStatus() {
+  return request<{ linked: boolean; username: string | null; scrobbling: boolean; pendingScrobbles: number }>(
+    "/lastfm/status",
+  );
+}
+
+export async function setLastfmScrobbling(scrobbling: boolean) {
+  return request<{ scrobbling: boolean }>("/lastfm/settings", {
+    method: "PATCH",
+    body: JSON.stringify({ scrobbling }),
+  });
+}
+
+export async function unlinkLastfm() {
+  return request<{ success: boolean }>("/lastfm/unlink", { method: "POST" });
+}
+
 // ── YouTube ──
 
 export async function searchYouTubeTracks(q: string) {
   return request<{ tracks: import("@/types/shared.js").YouTubeTrack[] }>(`/youtube/search?q=${encodeURIComponent(q)}`);
 }
 
+export async function prefetchYouTubeAudio(videoId: string) {
+  return request<{ cached: boolean; downloading: boolean }>(`/youtube/prefetch/${videoId}`, {
+    method: "POST",
+  });
+}
+
 export function getYouTubeAudioUrl(videoId: string): string {
   const token = getStoredToken();
   return `${API_BASE}/youtube/audio/${videoId}${token ? `?token=${token}` : ""}`;
 }
+
+// ── Other media sources (SoundCloud, Bandcamp) ──
+
+export async function searchMediaTracks(q: string, source: import("@/types/shared.js").MediaSource) {
+  return request<{ tracks: import("@/types/shared.js").MediaTrack[] }>(
+    `/media/search?q=${encodeURIComponent(q)}&source=${source}`,
+  );
+}
+
+/** SoundCloud and Bandcamp ids are `artist/track` paths, so the slash is kept. */
+export function getMediaAudioUrl(source: import("@/types/shared.js").MediaSource, id: string): string {
+  const token = getStoredToken();
+  return `${API_BASE}/media/audio/${source}/${id}${token ? `?token=${token}` : ""}`;
+}
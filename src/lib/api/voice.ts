@@ -1,10 +1,52 @@
+import type { NoiseModelVersion, PaginatedResponse, StageState, VoiceNode, VoiceQuality, VoiceSession, VoiceStats } from "@/types/shared.js";
+
 import { request } from "./base.js";
 
 // ── Voice ──
 
 export async function getVoiceToken(channelId: string) {
-  return request<{ token: string; url: string }>("/voice/token", {
+  return request<{ token: string; url: string; nodeId: string | null; region: string | null }>("/voice/token", {
     method: "POST",
     body: JSON.stringify({ channelId }),
   });
 }
+
+/** Voice nodes to measure latency to before joining */
+export async function getVoiceNodes() {
+  return request<VoiceNode[]>("/voice/nodes");
+}
+
+/** Report probe round trips; token requests route to the closest node */
+export async function reportVoiceLatency(results: { nodeId: string; rttMs: number }[]) {
+  return request<void>("/voice/latency", {
+    method: "POST",
+    body: JSON.stringify({ results }),
+  });
+}
+
+/** Speakers and raised hands on a stage channel */
+export async function getStage(channelId: string) {
+  return request<StageState>(`/channels/${channelId}/stage`);
+}
+
+export async function getVoiceHistory(cursor?: string) {
+  const params = cursor ? `?cursor=${encodeURIComponent(cursor)}` : "";
+  return request<PaginatedResponse<VoiceSession>>(`/users/me/voice-history${params}`);
+}
+
+/** Voice activity on a server over the last `days` days (admins only). */
+export async function getServerVoiceStats(serverId: string, days?: number) {
+  const params = days !== undefined ? `?days=${days}` : "";
+  return request<VoiceStats>(`/servers/${serverId}/voice/stats${params}`);
+}
+
+/** Call quality per voice channel over the last `hours` hours (admins only). */
+export async function getServerVoiceQuality(serverId: string, hours?: number) {
+  const params = hours !== undefined ? `?hours=${hours}` : "";
+  return request<VoiceQuality>(`/servers/${serverId}/voice/quality${params}`);
+}
+
+/** The hosted noise suppression model's version and where its files are. */
+export async function getNoiseModelVersion() {
+  return request<NoiseModelVersion>("/noise-models/version");
+}
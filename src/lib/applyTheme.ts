@@ -1,4 +1,4 @@
-import { useUIStore } from "@/stores/ui.js";
+import { useUIStore, type WindowEffect } from "@/stores/ui.js";
 import { resolveThemeColors, type ThemeColors } from "@/lib/themes.js";
 
 const CSS_VAR_KEYS: (keyof ThemeColors)[] = [
@@ -18,8 +18,33 @@ const CSS_VAR_KEYS: (keyof ThemeColors)[] = [
   "--radius-lg",
 ];
 
+const isTauri = typeof window !== "undefined" && "__TAURI_INTERNALS__" in window;
+
+// Last values sent to the native window, so unrelated store updates don't re-invoke
+let nativeTitlebarColor: string | null = null;
+let nativeWindowEffect: WindowEffect | null = null;
+
+/** Match the native window chrome (titlebar, backdrop material) to the theme. */
+function applyNativeChrome(effect: WindowEffect) {
+  if (!isTauri) return;
+  const bg = getComputedStyle(document.documentElement).getPropertyValue("--bg-primary").trim();
+  const titlebarColor = /^#[0-9a-f]{6}$/i.test(bg) ? bg : null;
+  const changedColor = titlebarColor !== null && titlebarColor !== nativeTitlebarColor;
+  const changedEffect = effect !== nativeWindowEffect;
+  if (!changedColor && !changedEffect) return;
+  if (changedColor) nativeTitlebarColor = titlebarColor;
+  nativeWindowEffect = effect;
+
+  import("@tauri-apps/api/core")
+    .then(({ invoke }) => {
+      if (changedColor) invoke("set_titlebar_color", { color: titlebarColor }).catch(() => {});
+      if (changedEffect) invoke("set_window_effects", { effect }).catch(() => {});
+    })
+    .catch(() => {});
+}
+
 function applyTheme() {
-  const { activeTheme, customThemes } = useUIStore.getState();
+  const { activeTheme, customThemes, windowEffect } = useUIStore.getState();
   const root = document.documentElement;
 
   // Set data-theme attribute
@@ -48,6 +73,13 @@ function applyTheme() {
     root.style.setProperty("--bg-modifier-hover", hexToRgba(accent, 0.06));
     root.style.setProperty("--bg-modifier-active", hexToRgba(accent, 0.1));
   }
+
+  if (windowEffect === "none") {
+    root.removeAttribute("data-window-effect");
+  } else {
+    root.setAttribute("data-window-effect", windowEffect);
+  }
+  applyNativeChrome(windowEffect);
 }
 
 function hexToRgba(hex: string, alpha: number): string {
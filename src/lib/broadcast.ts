@@ -94,3 +94,8 @@ export function getPopoutType(): "chat" | "screenshare" | null {
 export function isPopout(): boolean {
   return getPopoutType() !== null;
 }
+
+/** True inside the voice overlay window. */
+export function isOverlay(): boolean {
+  return new URLSearchParams(window.location.search).has("overlay");
+}
@@ -0,0 +1,33 @@
+// ═══════════════════════════════════════════════════════════════════
+// Native clipboard images (Tauri)
+//
+// The webview only sees pasted screenshots as full-size PNGs and can't
+// see copied files at all; the backend reads the clipboard itself and
+// downscales / re-encodes big images to WebP before handing them over.
+// ═══════════════════════════════════════════════════════════════════
+
+/** Whether clipboard images can be read natively (desktop app only). */
+export const hasNativeClipboard = typeof window !== "undefined" && "__TAURI_INTERNALS__" in window;
+
+interface ClipboardImage {
+  name: string;
+  mime: string;
+  width: number;
+  height: number;
+  /** Encoded file bytes, base64. */
+  data: string;
+}
+
+/** Image on the OS clipboard as an upload-ready File, or null (also outside Tauri). */
+export async function readClipboardImage(): Promise<File | null> {
+  if (!hasNativeClipboard) return null;
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    const image = await invoke<ClipboardImage | null>("get_clipboard_image", { transcode: true });
+    if (!image) return null;
+    const bytes = Uint8Array.from(atob(image.data), (c) => c.charCodeAt(0));
+    return new File([bytes], image.name, { type: image.mime });
+  } catch {
+    return null;
+  }
+}
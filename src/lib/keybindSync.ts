@@ -0,0 +1,65 @@
+import { useKeybindsStore, normalizeKeybinds, type KeybindEntry } from "@/stores/keybinds.js";
+import { getSyncedSettings, putSyncedSetting } from "@/lib/api/index.js";
+import { gateway } from "@/lib/ws.js";
+import { dbg } from "@/lib/debug.js";
+
+// ═══════════════════════════════════════════════════════════════════
+// Keybind Sync
+//
+// Bindings live in the synced settings store under one key, so they
+// follow the user to every device. Local edits are pushed; edits from
+// other devices arrive as `user_setting_update`.
+// ═══════════════════════════════════════════════════════════════════
+
+const SETTINGS_KEY = "keybinds";
+
+let applyingRemote = false;
+
+function applyRemote(value: unknown) {
+  const next = normalizeKeybinds(value);
+  if (JSON.stringify(next) === JSON.stringify(useKeybindsStore.getState().keybinds)) return;
+  applyingRemote = true;
+  useKeybindsStore.getState().replaceKeybinds(next);
+  applyingRemote = false;
+}
+
+function push(keybinds: KeybindEntry[]) {
+  putSyncedSetting(SETTINGS_KEY, keybinds).catch((e) => dbg("keybinds", "sync push failed", e));
+}
+
+/** Pull the user's synced bindings and keep them in sync. Returns a cleanup. */
+export function startKeybindSync(): () => void {
+  let stopped = false;
+
+  getSyncedSettings()
+    .then((settings) => {
+      if (stopped) return;
+      const synced = settings.find((s) => s.key === SETTINGS_KEY);
+      if (synced) {
+        applyRemote(synced.value);
+      } else if (useKeybindsStore.getState().keybinds.some((kb) => kb.key)) {
+        // First device to sync: upload what's bound here
+        push(useKeybindsStore.getState().keybinds);
+      }
+    })
+    .catch((e) => dbg("keybinds", "sync pull failed", e));
+
+  let prev = useKeybindsStore.getState().keybinds;
+  const unsubStore = useKeybindsStore.subscribe((state) => {
+    if (state.keybinds === prev) return;
+    prev = state.keybinds;
+    if (!applyingRemote) push(state.keybinds);
+  });
+
+  const unsubGateway = gateway.on((event) => {
+    if (event.type === "user_setting_update" && event.key === SETTINGS_KEY && event.value !== null) {
+      applyRemote(event.value);
+    }
+  });
+
+  return () => {
+    stopped = true;
+    unsubStore();
+    unsubGateway();
+  };
+}
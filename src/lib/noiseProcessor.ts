@@ -1,4 +1,6 @@
 import { dbg } from "@/lib/debug.js";
+import { API_BASE } from "@/lib/serverUrl.js";
+import { getNoiseModelVersion } from "@/lib/api/voice.js";
 
 // ═══════════════════════════════════════════════════════════════════
 // Noise Suppression Processors
@@ -60,6 +62,21 @@ export class RnnoiseProcessor {
   }
 }
 
+/**
+ * Where to load the DeepFilterNet3 model from: the server's current
+ * versioned copy, or its unversioned path if the version can't be fetched.
+ */
+async function deepFilterCdnUrl(): Promise<string> {
+  const origin = API_BASE.replace(/\/api$/, "");
+  try {
+    const { baseUrl } = await getNoiseModelVersion();
+    return `${origin}${baseUrl}`;
+  } catch (e) {
+    dbg("voice", "noise model version unavailable, using unversioned path", e);
+    return `${origin}/deepfilter-cdn`;
+  }
+}
+
 /**
  * DeepFilterNet3 processor using LiveKit's TrackProcessor interface.
  * Attaches directly to the LocalTrackPublication via setProcessor().
@@ -74,6 +91,7 @@ export class DeepFilterProcessor {
     this.processor = new DeepFilterNoiseFilterProcessor({
       sampleRate: 48000,
       noiseReductionLevel: 50, // Conservative — avoids speech distortion
+      assetConfig: { cdnUrl: await deepFilterCdnUrl() },
     });
 
     await micPublication.track.setProcessor(this.processor);
@@ -77,6 +77,14 @@ export function showDesktopNotification(senderName: string, text: string) {
   new Notification(senderName, { body, silent: true });
 }
 
+/** Flash the taskbar button (or bounce the dock icon) unless the window is focused. */
+export function flashForMention() {
+  if (!isTauri || document.hasFocus()) return;
+  import("@tauri-apps/api/core")
+    .then(({ invoke }) => invoke("flash_window", { critical: false }))
+    .catch(() => {});
+}
+
 export function requestNotificationPermission() {
   if (isTauri) {
     void (async () => {
@@ -0,0 +1,97 @@
+import { useVoiceStore } from "@/stores/voice/index.js";
+import { useChatStore } from "@/stores/chat/index.js";
+import { useUIStore, type OverlayCorner } from "@/stores/ui.js";
+
+// ═══════════════════════════════════════════════════════════════════
+// Voice Overlay (Tauri)
+//
+// The overlay is a separate click-through window. It can't read our
+// stores, so the main window pushes a snapshot through the Tauri
+// backend (`update_overlay`) whenever what it shows changes.
+// ═══════════════════════════════════════════════════════════════════
+
+export interface OverlayParticipant {
+  userId: string;
+  username: string;
+  speaking: boolean;
+  muted: boolean;
+  deafened: boolean;
+}
+
+export interface OverlayState {
+  channelName: string | null;
+  participants: OverlayParticipant[];
+}
+
+const isTauri = typeof window !== "undefined" && "__TAURI_INTERNALS__" in window;
+
+export async function toggleOverlay() {
+  if (!isTauri) return;
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke<boolean>("toggle_overlay", { corner: useUIStore.getState().overlayCorner });
+  } catch {
+    // ignore
+  }
+}
+
+export async function setOverlayCorner(corner: OverlayCorner) {
+  if (!isTauri) return;
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("set_overlay_corner", { corner });
+  } catch {
+    // ignore
+  }
+}
+
+function snapshot(): OverlayState {
+  const { connectedChannelId, participants, speakingUserIds } = useVoiceStore.getState();
+  if (!connectedChannelId) return { channelName: null, participants: [] };
+  const channel = useChatStore.getState().channels.find((c) => c.id === connectedChannelId);
+  return {
+    channelName: channel?.name ?? null,
+    participants: participants.map((p) => ({
+      userId: p.userId,
+      username: p.username,
+      speaking: speakingUserIds.has(p.userId),
+      muted: p.isMuted,
+      deafened: p.isDeafened,
+    })),
+  };
+}
+
+/** Keep the overlay fed from the main window. Returns a cleanup. */
+export function startOverlaySync(): () => void {
+  if (!isTauri) return () => {};
+
+  let last = "";
+  function push() {
+    const state = snapshot();
+    const json = JSON.stringify(state);
+    if (json === last) return;
+    last = json;
+    import("@tauri-apps/api/core")
+      .then(({ invoke }) => invoke("update_overlay", { state }))
+      .catch(() => {});
+  }
+
+  push();
+  const unsubVoice = useVoiceStore.subscribe(push);
+  const unsubChat = useChatStore.subscribe(push);
+
+  // The backend forgets the corner on restart; tell it the saved one
+  setOverlayCorner(useUIStore.getState().overlayCorner);
+  let prevCorner = useUIStore.getState().overlayCorner;
+  const unsubUI = useUIStore.subscribe((s) => {
+    if (s.overlayCorner === prevCorner) return;
+    prevCorner = s.overlayCorner;
+    setOverlayCorner(s.overlayCorner);
+  });
+
+  return () => {
+    unsubVoice();
+    unsubChat();
+    unsubUI();
+  };
+}
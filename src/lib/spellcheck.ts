@@ -0,0 +1,41 @@
+import { useUIStore } from "@/stores/ui.js";
+
+// ═══════════════════════════════════════════════════════════════════
+// Spellcheck languages (Tauri)
+//
+// The languages and autocorrect toggle live in the UI store; the
+// backend keeps a copy so WebView2 can be given the languages at
+// startup, before any page has loaded.
+// ═══════════════════════════════════════════════════════════════════
+
+const isTauri = typeof window !== "undefined" && "__TAURI_INTERNALS__" in window;
+
+/** Split user input like "en-US, de" into language tags. */
+export function parseLanguageList(input: string): string[] {
+  const tags = input
+    .split(/[\s,]+/)
+    .map((tag) => tag.trim())
+    .filter((tag) => /^[a-zA-Z0-9]{1,8}(-[a-zA-Z0-9]{1,8})*$/.test(tag));
+  return [...new Set(tags)];
+}
+
+function push(languages: string[], autocorrect: boolean) {
+  import("@tauri-apps/api/core")
+    .then(({ invoke }) => invoke("set_spellcheck_settings", { languages, autocorrect }))
+    .catch(() => {});
+}
+
+/** Mirror spellcheck settings to the backend. Returns a cleanup. */
+export function startSpellcheckSync(): () => void {
+  if (!isTauri) return () => {};
+
+  let { spellcheckLanguages: prevLanguages, autocorrect: prevAutocorrect } = useUIStore.getState();
+  push(prevLanguages, prevAutocorrect);
+
+  return useUIStore.subscribe((s) => {
+    if (s.spellcheckLanguages === prevLanguages && s.autocorrect === prevAutocorrect) return;
+    prevLanguages = s.spellcheckLanguages;
+    prevAutocorrect = s.autocorrect;
+    push(s.spellcheckLanguages, s.autocorrect);
+  });
+}
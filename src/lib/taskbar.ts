@@ -0,0 +1,52 @@
+import { useChatStore } from "@/stores/chat/index.js";
+
+// ═══════════════════════════════════════════════════════════════════
+// Taskbar / Dock (Tauri)
+//
+// Mirrors unread mentions and in-flight uploads onto the app's taskbar
+// button or dock icon. Mention flashing lives with the notifications.
+// ═══════════════════════════════════════════════════════════════════
+
+const isTauri = typeof window !== "undefined" && "__TAURI_INTERNALS__" in window;
+
+function invokeQuietly(cmd: string, args: Record<string, unknown>) {
+  import("@tauri-apps/api/core")
+    .then(({ invoke }) => invoke(cmd, args))
+    .catch(() => {});
+}
+
+/** Fraction done across uploads still in flight, or null when there are none. */
+export function uploadFraction(uploadProgress: Record<string, number>): number | null {
+  const inFlight = Object.values(uploadProgress).filter((pct) => pct < 100);
+  if (inFlight.length === 0) return null;
+  return inFlight.reduce((sum, pct) => sum + pct, 0) / inFlight.length / 100;
+}
+
+/** Keep the badge and progress bar in sync with the chat store. Returns a cleanup. */
+export function startTaskbarSync(): () => void {
+  if (!isTauri) return () => {};
+
+  let lastBadge = -1;
+  let lastProgress: number | null = null;
+
+  function sync() {
+    const { mentionCounts, uploadProgress } = useChatStore.getState();
+
+    const badge = Object.values(mentionCounts).reduce((sum, n) => sum + n, 0);
+    if (badge !== lastBadge) {
+      lastBadge = badge;
+      invokeQuietly("set_taskbar_badge", { count: badge });
+    }
+
+    const fraction = uploadFraction(uploadProgress);
+    // Whole percents only; the taskbar can't show finer steps anyway
+    const progress = fraction === null ? null : Math.round(fraction * 100) / 100;
+    if (progress !== lastProgress) {
+      lastProgress = progress;
+      invokeQuietly("set_taskbar_progress", { progress });
+    }
+  }
+
+  sync();
+  return useChatStore.subscribe(sync);
+}
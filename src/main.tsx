@@ -3,7 +3,8 @@ import { createRoot } from "react-dom/client";
 import { BrowserRouter } from "react-router-dom";
 import { App } from "./App.js";
 import { PopoutApp } from "./PopoutApp.js";
-import { isPopout } from "./lib/broadcast.js";
+import { VoiceOverlay } from "./components/voice/VoiceOverlay.js";
+import { isPopout, isOverlay } from "./lib/broadcast.js";
 import { initThemeApplicator } from "./lib/applyTheme.js";
 // Global styles
 import "./styles/base.css";
@@ -27,6 +28,7 @@ import "./components/voice/styles/voice-channel.css";
 import "./components/voice/styles/voice-controls.css";
 import "./components/voice/styles/voice-sidebar.css";
 import "./components/voice/styles/voice-rooms.css";
+import "./components/voice/styles/voice-overlay.css";
 import "./components/voice/styles/screen-share-viewer.css";
 import "./components/voice/styles/screen-share-controls.css";
 import "./components/settings/styles/settings-layout.css";
@@ -48,7 +50,9 @@ initThemeApplicator();
 
 createRoot(document.getElementById("root")!).render(
   <StrictMode>
-    {isPopout() ? (
+    {isOverlay() ? (
+      <VoiceOverlay />
+    ) : isPopout() ? (
       <PopoutApp />
     ) : (
       <BrowserRouter>
@@ -45,6 +45,7 @@ vi.mock("../../lib/broadcast.js", () => ({
 vi.mock("../../lib/notifications.js", () => ({
   playMessageSound: vi.fn(),
   showDesktopNotification: vi.fn(),
+  flashForMention: vi.fn(),
 }));
 
 vi.mock("../crypto.js", () => ({
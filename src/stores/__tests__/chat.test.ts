@@ -8,10 +8,12 @@ vi.mock("../../lib/api/index.js", () => ({
   getChannels: vi.fn(),
   getServerMembers: vi.fn(),
   getMessages: vi.fn(),
+  ackChannel: vi.fn(() => Promise.resolve()),
   getReactions: vi.fn(),
   searchServerMessages: vi.fn(),
   getDMChannels: vi.fn(),
   getDMMessages: vi.fn(),
+  ackDM: vi.fn(() => Promise.resolve()),
   createDM: vi.fn(),
   searchDMMessages: vi.fn(),
   updateServer: vi.fn(),
@@ -44,6 +46,7 @@ vi.mock("../../lib/broadcast.js", () => ({
 vi.mock("../../lib/notifications.js", () => ({
   playMessageSound: vi.fn(),
   showDesktopNotification: vi.fn(),
+  flashForMention: vi.fn(),
 }));
 
 vi.mock("../crypto.js", () => ({
@@ -234,8 +237,8 @@ describe("useChatStore", () => {
 
 describe("chat store helper functions", () => {
   const members = [
-    { userId: "u1", serverId: "s1", username: "alice", image: "a.png", role: "owner" as const, joinedAt: "2024-01-01", ringStyle: "default" as const, ringSpin: false, steamId: null, ringPatternSeed: null, bannerCss: null, bannerPatternSeed: null },
-    { userId: "u2", serverId: "s1", username: "bob", image: null, role: "member" as const, joinedAt: "2024-01-01", ringStyle: "default" as const, ringSpin: false, steamId: null, ringPatternSeed: null, bannerCss: null, bannerPatternSeed: null },
+    { userId: "u1", serverId: "s1", username: "alice", image: "a.png", role: "owner" as const, joinedAt: "2024-01-01", ringStyle: "default" as const, ringSpin: false, steamId: null, ringPatternSeed: null, bannerCss: null, bannerPatternSeed: null, nickname: null, serverAvatar: null },
+    { userId: "u2", serverId: "s1", username: "bob", image: null, role: "member" as const, joinedAt: "2024-01-01", ringStyle: "default" as const, ringSpin: false, steamId: null, ringPatternSeed: null, bannerCss: null, bannerPatternSeed: null, nickname: null, serverAvatar: null },
   ];
 
   it("getUsernameMap returns userId->username mapping", () => {
@@ -243,4 +246,9 @@ describe("chat store helper functions", () => {
     expect(map).toEqual({ u1: "alice", u2: "bob" });
   });
 
+  it("getUsernameMap prefers a server nickname", () => {
+    const map = getUsernameMap([members[0], { ...members[1], nickname: "Bobby" }]);
+    expect(map).toEqual({ u1: "alice", u2: "Bobby" });
+  });
+
 });
@@ -9,14 +9,15 @@ describe("useKeybindsStore", () => {
         { action: "push-to-mute", key: null, label: null },
         { action: "toggle-mute", key: null, label: null },
         { action: "toggle-deafen", key: null, label: null },
+        { action: "toggle-overlay", key: null, label: null },
       ],
       recording: null,
     });
   });
 
-  it("initial state has 4 default keybinds with null keys and labels", () => {
+  it("initial state has 5 default keybinds with null keys and labels", () => {
     const state = useKeybindsStore.getState();
-    expect(state.keybinds).toHaveLength(4);
+    expect(state.keybinds).toHaveLength(5);
     expect(state.recording).toBeNull();
     for (const kb of state.keybinds) {
       expect(kb.key).toBeNull();
@@ -27,6 +28,7 @@ describe("useKeybindsStore", () => {
       "push-to-mute",
       "toggle-mute",
       "toggle-deafen",
+      "toggle-overlay",
     ]);
   });
 
@@ -94,4 +96,20 @@ describe("useKeybindsStore", () => {
     expect(mute?.key).toBe("KeyM");
     expect(mute?.label).toBe("M");
   });
+
+  it("replaceKeybinds keeps every action and drops unknown ones", () => {
+    useKeybindsStore.getState().replaceKeybinds([
+      { action: "toggle-deafen", key: "Mouse4", label: "Mouse 5" },
+      { action: "bogus", key: "KeyB", label: "B" } as never,
+    ]);
+
+    const state = useKeybindsStore.getState();
+    expect(state.keybinds).toHaveLength(5);
+    expect(state.keybinds.map((kb) => kb.action)).not.toContain("bogus");
+    const deafen = state.keybinds.find((kb) => kb.action === "toggle-deafen");
+    expect(deafen?.key).toBe("Mouse4");
+    expect(deafen?.label).toBe("Mouse 5");
+    const ptt = state.keybinds.find((kb) => kb.action === "push-to-talk");
+    expect(ptt?.key).toBeNull();
+  });
 });
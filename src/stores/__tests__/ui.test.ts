@@ -8,6 +8,7 @@ describe("useUIStore", () => {
       serverSettingsOpen: false,
       sidebarPosition: "left",
       appBorderStyle: "none",
+      windowEffect: "none",
     });
   });
 
@@ -60,4 +61,12 @@ describe("useUIStore", () => {
     expect(useUIStore.getState().appBorderStyle).toBe("neon");
   });
 
+  it("setWindowEffect changes the window material", () => {
+    useUIStore.getState().setWindowEffect("mica");
+    expect(useUIStore.getState().windowEffect).toBe("mica");
+
+    useUIStore.getState().setWindowEffect("none");
+    expect(useUIStore.getState().windowEffect).toBe("none");
+  });
+
 });
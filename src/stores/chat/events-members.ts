@@ -73,6 +73,8 @@ export function handleMemberJoined(
           ringPatternSeed: event.ringPatternSeed ?? null,
           bannerCss: event.bannerCss ?? null,
           bannerPatternSeed: event.bannerPatternSeed ?? null,
+          nickname: null,
+          serverAvatar: null,
         }],
       }));
     }
@@ -143,6 +145,19 @@ export function handleMemberRoleUpdated(
   }));
 }
 
+export function handleMemberProfileUpdated(
+  event: any,
+  useChatStore: UseBoundStore<StoreApi<ChatState>>,
+) {
+  useChatStore.setState((s) => ({
+    members: s.members.map((m) =>
+      m.userId === event.userId && m.serverId === event.serverId
+        ? { ...m, nickname: event.nickname, serverAvatar: event.serverAvatar }
+        : m
+    ),
+  }));
+}
+
 export function handleProfileUpdate(
   event: any,
   useChatStore: UseBoundStore<StoreApi<ChatState>>,
@@ -6,7 +6,7 @@ import {
   getUsernameMap,
 } from "./types.js";
 import * as api from "@/lib/api/index.js";
-import { playMessageSound, showDesktopNotification, shouldNotifyChannel } from "@/lib/notifications.js";
+import { playMessageSound, showDesktopNotification, shouldNotifyChannel, flashForMention } from "@/lib/notifications.js";
 import { isUserMentioned } from "@/lib/mention.js";
 import { useCryptoStore } from "@/stores/crypto.js";
 import type {
@@ -89,6 +89,7 @@ export function handleMessage(
       const usernameMap = getUsernameMap(state.members);
       playMessageSound();
       showDesktopNotification(usernameMap[msg.senderId] ?? "Someone", msg.content);
+      if (authUser && isUserMentioned(msg.content, authUser.username)) flashForMention();
     }
   }
 }
@@ -4,6 +4,7 @@ import { gateway } from "@/lib/ws.js";
 import { broadcastState, onCommand, isPopout } from "@/lib/broadcast.js";
 import { useCryptoStore } from "@/stores/crypto.js";
 import { dbg } from "@/lib/debug.js";
+import { showDesktopNotification } from "@/lib/notifications.js";
 import type { ChatState } from "./types.js";
 
 // ── Message handlers ──
@@ -28,6 +29,7 @@ import {
   handleServerUpdated,
   handleServerDeleted,
   handleMemberRoleUpdated,
+  handleMemberProfileUpdated,
   handleProfileUpdate,
 } from "./events-members.js";
 
@@ -105,6 +107,32 @@ function handleGallerySetUpdated(event: { setId: string }) {
   }
 }
 
+// ── Event reminder handler ──
+
+function handleEventReminder(
+  event: { serverId: string; title: string; startAt: string },
+  useChatStore: UseBoundStore<StoreApi<ChatState>>,
+) {
+  const serverName = useChatStore.getState().servers.find((s) => s.id === event.serverId)?.name ?? "Event";
+  const minutes = Math.round((Date.parse(event.startAt) - Date.now()) / 60_000);
+  const when = minutes > 0 ? `starts in ${minutes} min` : "is starting";
+  showDesktopNotification(serverName, `${event.title} ${when}`);
+}
+
+// ── Message reminder handler ──
+
+function handleReminderDue(
+  event: { reminder: { note: string | null; serverId: string | null; channelId: string | null } },
+  useChatStore: UseBoundStore<StoreApi<ChatState>>,
+) {
+  const { reminder } = event;
+  const channelName = reminder.channelId
+    ? useChatStore.getState().channels.find((c) => c.id === reminder.channelId)?.name
+    : undefined;
+  const where = channelName ? ` in #${channelName}` : reminder.channelId ? "" : " in your DMs";
+  showDesktopNotification("Reminder", reminder.note ?? `You asked to be reminded about a message${where}`);
+}
+
 // ── Activity polling state ──
 
 let activityPollInterval: ReturnType<typeof setInterval> | null = null;
@@ -229,7 +257,11 @@ gateway.on((event) => {
     case "member_role_updated":
       handleMemberRoleUpdated(event, useChatStore, authStoreRef);
       break;
+    case "member_profile_updated":
+      handleMemberProfileUpdated(event, useChatStore);
+      break;
     case "profile_update":
+    case "user_renamed":
       handleProfileUpdate(event, useChatStore);
       break;
 
@@ -269,6 +301,14 @@ gateway.on((event) => {
       handleGallerySetUpdated(event);
       break;
 
+    // Reminders
+    case "event_reminder":
+      handleEventReminder(event, useChatStore);
+      break;
+    case "reminder_due":
+      handleReminderDue(event, useChatStore);
+      break;
+
     // Server errors
     case "error":
       dbg("chat", `Server error: ${event.message}`);
@@ -208,6 +208,7 @@ export const useChatStore = create<ChatState>((set, get) => ({
       delete newMentions[channelId];
       return { unreadChannels: newUnread, mentionCounts: newMentions };
     });
+    api.ackChannel(channelId).catch(() => {});
   },
 }));
 
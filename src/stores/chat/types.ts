@@ -136,11 +136,11 @@ export function saveDMCache(dmChannelId: string, state: { dmMessages: DMMessage[
   });
 }
 
-// Helper to get username map
+// Helper to get the names members go by in their server
 export function getUsernameMap(members: MemberWithUser[]): Record<string, string> {
   const map: Record<string, string> = {};
   for (const m of members) {
-    map[m.userId] = m.username;
+    map[m.userId] = m.nickname ?? m.username;
   }
   return map;
 }
@@ -101,6 +101,7 @@ export const useDMStore = create<DMState>((set, get) => ({
     });
 
     gateway.send({ type: "join_dm", dmChannelId });
+    api.ackDM(dmChannelId).catch(() => {});
 
     // Fetch fresh data in background (non-blocking if cache exists)
     try {
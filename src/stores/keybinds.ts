@@ -5,7 +5,8 @@ export type KeybindAction =
   | "push-to-talk"
   | "push-to-mute"
   | "toggle-mute"
-  | "toggle-deafen";
+  | "toggle-deafen"
+  | "toggle-overlay";
 
 export interface KeybindEntry {
   action: KeybindAction;
@@ -24,6 +25,8 @@ interface KeybindsState {
   clearKeybind: (action: KeybindAction) => void;
   startRecording: (action: KeybindAction) => void;
   stopRecording: () => void;
+  /** Replace every binding, e.g. with ones synced from another device */
+  replaceKeybinds: (keybinds: KeybindEntry[]) => void;
 }
 
 const DEFAULT_KEYBINDS: KeybindEntry[] = [
@@ -31,8 +34,20 @@ const DEFAULT_KEYBINDS: KeybindEntry[] = [
   { action: "push-to-mute", key: null, label: null },
   { action: "toggle-mute", key: null, label: null },
   { action: "toggle-deafen", key: null, label: null },
+  { action: "toggle-overlay", key: null, label: null },
 ];
 
+/** One entry per known action, in default order, taking keys from `entries`. */
+export function normalizeKeybinds(entries: unknown): KeybindEntry[] {
+  const list = Array.isArray(entries) ? (entries as Partial<KeybindEntry>[]) : [];
+  return DEFAULT_KEYBINDS.map((def) => {
+    const found = list.find((e) => e && e.action === def.action);
+    return typeof found?.key === "string"
+      ? { action: def.action, key: found.key, label: typeof found.label === "string" ? found.label : found.key }
+      : { ...def };
+  });
+}
+
 export const useKeybindsStore = create<KeybindsState>()(
   persist(
     (set) => ({
@@ -56,10 +71,17 @@ export const useKeybindsStore = create<KeybindsState>()(
 
       startRecording: (action) => set({ recording: action }),
       stopRecording: () => set({ recording: null }),
+
+      replaceKeybinds: (keybinds) => set({ keybinds: normalizeKeybinds(keybinds) }),
     }),
     {
       name: "flux-keybinds",
       partialize: (state) => ({ keybinds: state.keybinds }),
+      // Bindings saved before an action existed get its default
+      merge: (persisted, current) => ({
+        ...current,
+        keybinds: normalizeKeybinds((persisted as Partial<KeybindsState> | undefined)?.keybinds),
+      }),
     }
   )
 );
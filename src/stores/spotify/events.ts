@@ -112,6 +112,20 @@ export function createHandleWSEvent(store: StoreApi<SpotifyState>) {
         }
         break;
       }
+      case "spotify_session_roles": {
+        const { session } = store.getState();
+        dbg("spotify", `WS spotify_session_roles sessionId=${event.sessionId} host=${event.hostUserId}`);
+        if (session && session.id === event.sessionId) {
+          import("@/stores/auth.js").then(({ useAuthStore }) => {
+            const userId = useAuthStore.getState().user?.id;
+            store.setState((s) => ({
+              session: s.session ? { ...s.session, hostUserId: event.hostUserId } : s.session,
+              isHost: !!userId && (event.hostUserId === userId || event.cohostIds.includes(userId)),
+            }));
+          });
+        }
+        break;
+      }
       case "spotify_session_ended": {
         const { session, player } = store.getState();
         dbg("spotify", `WS spotify_session_ended sessionId=${event.sessionId}`, { currentSession: session?.id });
@@ -35,8 +35,12 @@ export function createStartOAuthFlow(storeApi: StoreApi<SpotifyState>) {
       try {
         const { invoke } = await import("@tauri-apps/api/core");
         const serverUrl = API_BASE.startsWith("/") ? "http://127.0.0.1:3001" : API_BASE.replace(/\/api$/, "");
-        invoke("start_oauth_listener", { serverUrl });
-        redirectUri = "http://127.0.0.1:29170/callback";
+        const listener = await invoke<{ redirectUri: string }>("start_oauth_listener", {
+          serverUrl,
+          provider: "spotify",
+          expectedState: state,
+        });
+        redirectUri = listener.redirectUri;
         dbg("spotify", "OAuth using local listener", { redirectUri });
       } catch {
         dbg("spotify", "OAuth using backend redirect", { redirectUri });
@@ -118,13 +118,8 @@ export function createPlay(store: StoreApi<SpotifyState>) {
     // Default source to spotify if still unknown
     if (!effectiveSource) effectiveSource = "spotify";
 
-    const queueItem = queue.find((item) => item.trackUri === trackUri);
-    if (queueItem) {
-      store.setState((s) => ({ queue: s.queue.filter((item) => item.trackUri !== trackUri) }));
-      api.removeFromQueue(session.id, queueItem.id);
-    }
-
-    // Broadcast to other session members
+    // Broadcast to other session members (before dequeuing, so the server
+    // can still read the queue entry's metadata for the session history)
     gateway.send({
       type: "spotify_playback_control",
       sessionId: session.id,
@@ -134,6 +129,12 @@ export function createPlay(store: StoreApi<SpotifyState>) {
       source: effectiveSource,
     });
 
+    const queueItem = queue.find((item) => item.trackUri === trackUri);
+    if (queueItem) {
+      store.setState((s) => ({ queue: s.queue.filter((item) => item.trackUri !== trackUri) }));
+      api.removeFromQueue(session.id, queueItem.id);
+    }
+
     if (effectiveSource === "youtube") {
       // Build track info from queue or search results
       const searchItem = yt().youtubeSearchResults.find(t => t.id === trackUri);
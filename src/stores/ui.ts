@@ -3,6 +3,8 @@ import { persist } from "zustand/middleware";
 import type { ActiveTheme, CustomTheme } from "@/lib/themes.js";
 
 export type SidebarPosition = "left" | "top" | "right" | "bottom";
+export type WindowEffect = "none" | "mica" | "acrylic";
+export type OverlayCorner = "top-left" | "top-right" | "bottom-left" | "bottom-right";
 export type AppBorderStyle = "none" | "chroma" | "pulse" | "wave" | "ember" | "frost" | "neon" | "galaxy";
 
 interface UIState {
@@ -14,8 +16,12 @@ interface UIState {
   appBorderStyle: AppBorderStyle;
   highlightOwnMessages: boolean;
   spellcheck: boolean;
+  spellcheckLanguages: string[];
+  autocorrect: boolean;
   showSendButton: boolean;
   betaUpdates: boolean;
+  overlayCorner: OverlayCorner;
+  windowEffect: WindowEffect;
   activeTheme: ActiveTheme;
   customThemes: CustomTheme[];
   openSettings: () => void;
@@ -29,8 +35,12 @@ interface UIState {
   setAppBorderStyle: (style: AppBorderStyle) => void;
   setHighlightOwnMessages: (val: boolean) => void;
   setSpellcheck: (val: boolean) => void;
+  setSpellcheckLanguages: (languages: string[]) => void;
+  setAutocorrect: (val: boolean) => void;
   setShowSendButton: (val: boolean) => void;
   setBetaUpdates: (val: boolean) => void;
+  setOverlayCorner: (corner: OverlayCorner) => void;
+  setWindowEffect: (effect: WindowEffect) => void;
   setActiveTheme: (theme: ActiveTheme) => void;
   addCustomTheme: (theme: CustomTheme) => void;
   updateCustomTheme: (id: string, updates: Partial<Pick<CustomTheme, "name" | "colors">>) => void;
@@ -48,8 +58,12 @@ export const useUIStore = create<UIState>()(
       appBorderStyle: "none",
       highlightOwnMessages: true,
       spellcheck: true,
+      spellcheckLanguages: [] as string[],
+      autocorrect: true,
       showSendButton: true,
       betaUpdates: false,
+      overlayCorner: "top-left",
+      windowEffect: "none",
       activeTheme: { type: "preset", id: "liminal" } as ActiveTheme,
       customThemes: [] as CustomTheme[],
       openSettings: () => set({ settingsOpen: true, settingsTab: null }),
@@ -63,8 +77,12 @@ export const useUIStore = create<UIState>()(
       setAppBorderStyle: (style) => set({ appBorderStyle: style }),
       setHighlightOwnMessages: (val) => set({ highlightOwnMessages: val }),
       setSpellcheck: (val) => set({ spellcheck: val }),
+      setSpellcheckLanguages: (languages) => set({ spellcheckLanguages: languages }),
+      setAutocorrect: (val) => set({ autocorrect: val }),
       setShowSendButton: (val) => set({ showSendButton: val }),
       setBetaUpdates: (val) => set({ betaUpdates: val }),
+      setOverlayCorner: (corner) => set({ overlayCorner: corner }),
+      setWindowEffect: (effect) => set({ windowEffect: effect }),
       setActiveTheme: (theme) => set({ activeTheme: theme }),
       addCustomTheme: (theme) =>
         set((s) => ({ customThemes: [...s.customThemes, theme] })),
@@ -92,8 +110,12 @@ export const useUIStore = create<UIState>()(
         appBorderStyle: state.appBorderStyle,
         highlightOwnMessages: state.highlightOwnMessages,
         spellcheck: state.spellcheck,
+        spellcheckLanguages: state.spellcheckLanguages,
+        autocorrect: state.autocorrect,
         showSendButton: state.showSendButton,
         betaUpdates: state.betaUpdates,
+        overlayCorner: state.overlayCorner,
+        windowEffect: state.windowEffect,
         activeTheme: state.activeTheme,
         customThemes: state.customThemes,
       }),
@@ -1,6 +1,7 @@
 import { dbg } from "@/lib/debug.js";
 import { collectWebRTCStats, resetStatsDelta } from "@/lib/webrtcStats.js";
 import { tickAdaptiveBitrate } from "@/lib/adaptiveBitrate.js";
+import { gateway } from "@/lib/ws.js";
 import type { StoreApi } from "zustand";
 import type { VoiceState } from "./types.js";
 
@@ -10,6 +11,10 @@ import type { VoiceState } from "./types.js";
 
 let statsInterval: ReturnType<typeof setInterval> | null = null;
 let storeRef: StoreApi<VoiceState> | null = null;
+let statsTicks = 0;
+
+// Report a call quality sample to the server every 15th poll (30s)
+const REPORT_EVERY_TICKS = 15;
 
 export function initStatsPolling(store: StoreApi<VoiceState>) {
   storeRef = store;
@@ -18,6 +23,7 @@ export function initStatsPolling(store: StoreApi<VoiceState>) {
 export function startStatsPolling() {
   stopStatsPolling();
   resetStatsDelta();
+  statsTicks = 0;
   statsInterval = setInterval(async () => {
     const { room, audioSettings } = storeRef!.getState();
     if (!room) return;
@@ -27,6 +33,15 @@ export function startStatsPolling() {
       if (audioSettings.adaptiveBitrate) {
         tickAdaptiveBitrate(stats.audioPacketLoss);
       }
+      statsTicks++;
+      if (statsTicks % REPORT_EVERY_TICKS === 0) {
+        gateway.send({
+          type: "voice_stats",
+          rtt: stats.rtt,
+          jitter: stats.audioJitter * 1000,
+          packetLoss: stats.audioPacketLoss,
+        });
+      }
     } catch (e) {
       dbg("voice", "stats polling error", e);
     }
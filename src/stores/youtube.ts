@@ -62,6 +62,8 @@ export const useYouTubeStore = create<YouTubeState>((set, get) => ({
       trackDurationMs: track.durationMs,
       source: "youtube",
     });
+    // Warm the server's audio cache so the track starts instantly when it comes up
+    api.prefetchYouTubeAudio(track.id).catch(() => {});
   },
 
   playYouTube: (videoId, trackInfo) => {
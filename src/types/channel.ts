@@ -12,15 +12,82 @@ export interface Channel {
   creatorId: string | null;
   isLocked: boolean;
   createdAt: string;
+  /** Overrides the server's retention: null follows it, 0 keeps messages forever */
+  retentionDays?: number | null;
+  /** Voice channels: region calls run in; null picks the node closest to whoever joins first */
+  voiceRegion?: string | null;
 }
 
-export type ChannelType = "text" | "voice" | "category";
+export type ChannelType = "text" | "voice" | "stage" | "category";
 
 export interface VoiceParticipant {
   userId: string;
   username: string;
 }
 
+/** One stretch a user spent in a voice channel; `leftAt` is null while connected */
+export interface VoiceSession {
+  id: string;
+  channelId: string;
+  channelName: string;
+  serverId: string | null;
+  joinedAt: string;
+  leftAt: string | null;
+  durationSecs: number | null;
+  coinsEarned: number;
+}
+
+export interface VoiceStats {
+  days: number;
+  sessions: number;
+  totalSecs: number;
+  uniqueUsers: number;
+  perDay: { day: string; sessions: number; secs: number }[];
+  topUsers: { userId: string; username: string | null; sessions: number; secs: number }[];
+  topChannels: { channelId: string; channelName: string; sessions: number; secs: number }[];
+}
+
+/** Call quality per voice channel, worst first (server admins only) */
+export interface VoiceQuality {
+  hours: number;
+  thresholds: { rttMs: number; jitterMs: number; packetLoss: number };
+  channels: {
+    channelId: string;
+    channelName: string;
+    samples: number;
+    users: number;
+    avgRttMs: number;
+    p95RttMs: number;
+    avgJitterMs: number;
+    avgPacketLoss: number;
+    degraded: boolean;
+  }[];
+}
+
+/** The noise suppression model files the server hosts */
+export interface NoiseModelVersion {
+  version: string;
+  /** Path the files are served under; changes with the version */
+  baseUrl: string;
+  files: { path: string; sha256: string; size: number }[];
+}
+
+/** A LiveKit node to probe, with the user's last round trip to it */
+export interface VoiceNode {
+  id: string;
+  region: string;
+  url: string;
+  rttMs: number | null;
+  measuredAt: string | null;
+}
+
+/** Who's speaking on a stage channel and who's asked to */
+export interface StageState {
+  speakers: string[];
+  raisedHands: { userId: string; raisedAt: string }[];
+  canModerate: boolean;
+}
+
 export interface CreateChannelRequest {
   name: string;
   type: ChannelType;
@@ -39,4 +106,54 @@ export interface UpdateChannelRequest {
   name?: string;
   bitrate?: number | null;
   isLocked?: boolean;
+  /** Admins only; 0-3650 days, or null to follow the server */
+  retentionDays?: number | null;
+  /** Admins only, voice channels; a region some voice node serves, or null for automatic */
+  voiceRegion?: string | null;
+}
+
+/** An RSS/Atom feed that posts new entries into a text channel */
+export interface ChannelFeed {
+  id: string;
+  channelId: string;
+  url: string;
+  title: string;
+  createdBy: string;
+  createdAt: string;
+  lastPolledAt: string | null;
+  /** Why the last poll failed, if it did */
+  lastError: string | null;
+}
+
+export type IntegrationProvider = "github" | "gitlab";
+
+/** A GitHub/GitLab webhook that posts repository activity into a text channel */
+export interface ChannelIntegration {
+  id: string;
+  channelId: string;
+  provider: IntegrationProvider;
+  name: string;
+  createdBy: string;
+  createdAt: string;
+  lastDeliveryAt: string | null;
+}
+
+/** Returned on create and rotate only; the token and secret can't be fetched again */
+export interface IssuedIntegration extends ChannelIntegration {
+  token: string;
+  secret: string;
+  webhookUrl: string;
+}
+
+/** A quick switcher (Ctrl+K) result; `id` is the channel, DM channel or user to open */
+export interface QuickSwitcherItem {
+  kind: "channel" | "dm" | "user";
+  id: string;
+  name: string;
+  image: string | null;
+  channelType?: ChannelType;
+  serverId?: string;
+  serverName?: string;
+  /** The other person, for DMs and users */
+  userId?: string;
 }
@@ -23,6 +23,12 @@ export interface Message {
   createdAt: string;
   editedAt?: string;
   attachments?: Attachment[];
+  /** Set when a channel feed posted this message */
+  feedId?: string;
+  /** Set when a GitHub/GitLab integration posted this message */
+  integrationId?: string;
+  /** Per-emoji counts; absent when nobody has reacted */
+  reactions?: ReactionSummary[];
 }
 
 export interface Reaction {
@@ -33,6 +39,45 @@ export interface Reaction {
   createdAt: string;
 }
 
+/** One emoji's reactions on a message; `sampleUserIds` holds the first few reactors */
+export interface ReactionSummary {
+  messageId: string;
+  emoji: string;
+  count: number;
+  me: boolean;
+  sampleUserIds: string[];
+}
+
+/** A "remind me about this message" reminder; the location fields point back to the message */
+export interface MessageReminder {
+  id: string;
+  messageId: string;
+  serverId: string | null;
+  channelId: string | null;
+  dmChannelId: string | null;
+  note: string | null;
+  remindAt: string;
+  createdAt: string;
+  /** Set once the reminder has gone off */
+  firedAt: string | null;
+}
+
+/** A saved message with its context; DMs carry ciphertext instead of content */
+export interface SavedMessage {
+  id: string;
+  messageId: string;
+  senderId: string;
+  serverId: string | null;
+  channelId: string | null;
+  channelName: string | null;
+  content: string | null;
+  dmChannelId: string | null;
+  ciphertext: string | null;
+  mlsEpoch: number | null;
+  messageCreatedAt: string;
+  savedAt: string;
+}
+
 export interface DMMessage {
   id: string;
   dmChannelId: string;
@@ -42,6 +87,31 @@ export interface DMMessage {
   createdAt: string;
 }
 
+/** A DM conversation; `pending` and `declined` ones are message requests opened by `requestedBy` */
+export interface DMChannel {
+  id: string;
+  otherUser: { id: string; username: string; image: string | null };
+  createdAt: string;
+  status: "accepted" | "pending" | "declined";
+  requestedBy: string | null;
+}
+
+/** A page of channel history that can be extended in both directions */
+export interface MessagePage extends PaginatedResponse<Message> {
+  afterCursor: string | null;
+  hasMoreAfter: boolean;
+}
+
+/** A single message with the messages around it, for permalinks */
+export interface MessageContext {
+  message: Message;
+  before: Message[];
+  after: Message[];
+  reactions: Reaction[];
+  hasMoreBefore: boolean;
+  hasMoreAfter: boolean;
+}
+
 export interface PaginatedResponse<T> {
   items: T[];
   cursor: string | null;
@@ -6,6 +6,8 @@ export interface Server {
   ownerId: string;
   inviteCode: string;
   createdAt: string;
+  /** Days before channel messages are deleted; null keeps them forever */
+  retentionDays?: number | null;
 }
 
 export type RingStyle = "default" | "chroma" | "pulse" | "wave" | "ember" | "frost" | "neon" | "galaxy" | "none"
@@ -24,12 +26,17 @@ export interface MemberWithUser {
   ringPatternSeed: number | null;
   bannerCss: string | null;
   bannerPatternSeed: number | null;
+  /** Per-server overrides of username and image. */
+  nickname: string | null;
+  serverAvatar: string | null;
 }
 
 export type MemberRole = "owner" | "admin" | "member";
 
 export interface UpdateServerRequest {
   name?: string;
+  /** 1-3650 days, or null to keep messages forever */
+  retentionDays?: number | null;
 }
 
 export interface WhitelistEntry {
@@ -39,6 +46,23 @@ export interface WhitelistEntry {
   addedAt: string;
 }
 
+export interface SignupInvite {
+  id: string;
+  note: string | null;
+  createdBy: string;
+  createdAt: string;
+  expiresAt: string;
+  usedBy: string | null;
+  usedAt: string | null;
+}
+
+export interface AccessRequest {
+  id: string;
+  email: string;
+  reason: string;
+  createdAt: string;
+}
+
 export interface SoundboardSound {
   id: string;
   serverId: string;
@@ -65,6 +89,80 @@ export interface CustomEmoji {
   createdAt: string;
 }
 
+/** Custom emoji usage over a window of days; `lastUsedAt` is all-time */
+export interface EmojiUsageStats {
+  id: string;
+  name: string;
+  filename: string;
+  createdAt: string;
+  lastUsedAt: string | null;
+  reactions: number;
+  messages: number;
+  usage: { day: string; reactions: number; messages: number }[];
+}
+
+/** Server activity over a window of days; `daily` is oldest first, with empty days included */
+export interface ServerInsights {
+  days: number;
+  generatedAt: string;
+  totalMembers: number;
+  totals: { messages: number; activeUsers: number; newMembers: number; voiceMinutes: number };
+  daily: { day: string; messages: number; activeUsers: number; newMembers: number; voiceMinutes: number }[];
+  topChannels: { channelId: string; name: string; messages: number }[];
+}
+
+/** A custom role admins create, on top of owner/admin/member */
+export interface ServerRole {
+  id: string;
+  serverId: string;
+  name: string;
+  color: string | null;
+  createdAt: string;
+  memberIds: string[];
+}
+
+/** Reacting to `messageId` with `emoji` grants `roleId`; unreacting revokes it */
+export interface ReactionRole {
+  id: string;
+  messageId: string;
+  channelId: string;
+  emoji: string;
+  roleId: string;
+  roleName: string;
+  createdBy: string;
+  createdAt: string;
+}
+
+/** Shown on first join; while `requiresAcceptance` is set the member can't post until they accept the rules */
+export interface WelcomeScreen {
+  serverId: string;
+  message: string | null;
+  rules: string | null;
+  suggestedChannels: { id: string; name: string; type: string }[];
+  rulesUpdatedAt: string | null;
+  updatedAt: string | null;
+  rulesAcceptedAt: string | null;
+  requiresAcceptance: boolean;
+}
+
+/** A new-member requirement not met yet; `availableAt` is when a too-new account becomes old enough */
+export interface UnmetRequirement {
+  code: "email_unverified" | "account_too_new" | "challenge_required";
+  availableAt?: string;
+}
+
+/** What a server asks of members before they can post (owners and admins are exempt) */
+export interface VerificationSettings {
+  minAccountAgeMinutes: number;
+  requireVerifiedEmail: boolean;
+  requireChallenge: boolean;
+}
+
+export interface VerificationStatus extends VerificationSettings {
+  challengeSiteKey: string | null;
+  unmet: UnmetRequirement[];
+}
+
 export interface EmojiFavorites {
   standard: string[];   // Unicode chars
   customIds: string[];  // custom_emoji ids
@@ -80,6 +178,51 @@ export interface RoadmapItem {
   createdBy: string;
   createdAt: string;
   updatedAt: string;
+  voteCount: number;
+  commentCount: number;
+  voted: boolean;
+}
+
+export interface RoadmapComment {
+  id: string;
+  itemId: string;
+  parentId: string | null;
+  authorId: string;
+  authorUsername: string;
+  authorImage: string | null;
+  content: string;
+  createdAt: string;
+}
+
+export type EventRecurrence = "daily" | "weekly" | "monthly";
+export type RsvpStatus = "going" | "interested" | "not_going";
+
+/** A scheduled server event */
+export interface ScheduledEvent {
+  id: string;
+  serverId: string;
+  channelId: string | null;
+  title: string;
+  description: string;
+  startAt: string;
+  endAt: string | null;
+  recurrence: EventRecurrence | null;
+  createdBy: string;
+  createdAt: string;
+  updatedAt: string;
+  goingCount: number;
+  interestedCount: number;
+  myRsvp: RsvpStatus | null;
+  /** Start of the next occurrence; equals startAt once a one-off event has begun */
+  nextStartAt: string;
+}
+
+export interface EventRsvp {
+  userId: string;
+  username: string;
+  image: string | null;
+  status: RsvpStatus;
+  updatedAt: string;
 }
 
 export interface GallerySet {
@@ -107,6 +250,45 @@ export interface GallerySetImage {
   createdAt: string;
 }
 
+export interface GalleryCollaborator {
+  userId: string;
+  username: string;
+  createdAt: string;
+}
+
 export interface GallerySetDetail extends GallerySet {
   images: GallerySetImage[];
+  // Only returned by the single-set endpoint
+  collaborators?: GalleryCollaborator[];
+}
+
+export interface Game {
+  id: string;
+  kind: "coinflip" | "dice";
+  serverId: string;
+  channelId: string;
+  creatorId: string;
+  opponentId: string | null;
+  wager: number;
+  /** Dice only: win chance in percent. */
+  target: number | null;
+  status: "open" | "resolved" | "cancelled";
+  seedUserId: string | null;
+  /** Revealed once the roller rotates their seed pair. */
+  serverSeed: string | null;
+  serverSeedHash: string | null;
+  clientSeed: string | null;
+  nonce: number | null;
+  roll: number | null;
+  winnerId: string | null;
+  payout: number;
+  createdAt: string;
+  resolvedAt: string | null;
+}
+
+export interface GameSettings {
+  serverId: string;
+  enabled: number;
+  houseEdgeBps: number;
+  dailyLossLimit: number;
 }
@@ -5,52 +5,94 @@ export type {
   MemberWithUser,
   MemberRole,
   UpdateServerRequest,
+  ServerInsights,
+  ServerRole,
+  ReactionRole,
+  WelcomeScreen,
+  UnmetRequirement,
+  VerificationSettings,
+  VerificationStatus,
   WhitelistEntry,
+  SignupInvite,
+  AccessRequest,
   SoundboardSound,
   CustomEmoji,
+  EmojiUsageStats,
   EmojiFavorites,
   RoadmapItem,
+  RoadmapComment,
+  EventRecurrence,
+  RsvpStatus,
+  ScheduledEvent,
+  EventRsvp,
   GallerySet,
   GallerySetImage,
   GallerySetDetail,
+  GalleryCollaborator,
+  Game,
+  GameSettings,
 } from "./server.js";
 
 export type {
   Channel,
   ChannelType,
   VoiceParticipant,
+  VoiceSession,
+  VoiceStats,
+  VoiceQuality,
+  NoiseModelVersion,
+  VoiceNode,
+  StageState,
   CreateChannelRequest,
   ReorderItem,
   UpdateChannelRequest,
+  ChannelFeed,
+  IntegrationProvider,
+  ChannelIntegration,
+  IssuedIntegration,
+  QuickSwitcherItem,
 } from "./channel.js";
 
 export type {
   Attachment,
   LinkPreview,
   Message,
+  MessageContext,
+  MessagePage,
   Reaction,
+  ReactionSummary,
+  MessageReminder,
+  SavedMessage,
   DMMessage,
+  DMChannel,
   PaginatedResponse,
 } from "./message.js";
 
 export type {
   ActivityInfo,
   PresenceStatus,
+  UserProfile,
   SpotifyAccount,
   ListeningSession,
   QueueItem,
+  HistoryItem,
   SpotifyTrack,
   YouTubeTrack,
+  MediaSource,
+  MediaTrack,
+  LyricLine,
+  BlockedUser,
+  SyncedSetting,
 } from "./user.js";
 
 // --- WebSocket event types (cross-domain, kept here) ---
 
 import type { Message, Attachment } from "./message.js";
 import type { Channel } from "./channel.js";
-import type { RingStyle } from "./server.js";
-import type { ActivityInfo, PresenceStatus, QueueItem } from "./user.js";
+import type { RingStyle, Game, UnmetRequirement } from "./server.js";
+import type { ActivityInfo, PresenceStatus, QueueItem, LyricLine } from "./user.js";
 import type { VoiceParticipant } from "./channel.js";
-import type { DMMessage } from "./message.js";
+import type { DMMessage, DMChannel } from "./message.js";
 
 export type WSClientEvent =
   | { type: "send_message"; channelId: string; content: string; attachmentIds?: string[] }
@@ -59,6 +101,8 @@ export type WSClientEvent =
   | { type: "join_channel"; channelId: string }
   | { type: "leave_channel"; channelId: string }
   | { type: "voice_state_update"; channelId: string; action: "join" | "leave" }
+  | { type: "speaking_update"; channelId: string; speaking: boolean }
+  | { type: "media_buffering_update"; sessionId: string; buffering: boolean; positionMs?: number }
   | { type: "add_reaction"; messageId: string; emoji: string }
   | { type: "remove_reaction"; messageId: string; emoji: string }
   | { type: "edit_message"; messageId: string; content: string }
@@ -67,12 +111,15 @@ export type WSClientEvent =
   | { type: "join_dm"; dmChannelId: string }
   | { type: "leave_dm"; dmChannelId: string }
   | { type: "update_activity"; activity: ActivityInfo | null }
-  | { type: "share_server_key"; serverId: string; userId: string; encryptedKey: string }
+  | { type: "share_server_key"; serverId: string; userId: string; encryptedKey: string; deviceId?: string }
   | { type: "request_server_key"; serverId: string }
   | { type: "spotify_playback_control"; sessionId: string; action: string; trackUri?: string; positionMs?: number; source?: string }
   | { type: "update_status"; status: string }
   | { type: "play_sound"; channelId: string; soundId: string }
   | { type: "room_knock"; channelId: string }
+  | { type: "stage_raise_hand"; channelId: string; raised: boolean }
+  | { type: "stage_set_speaker"; channelId: string; userId: string; speaker: boolean }
+  | { type: "voice_stats"; rtt: number; jitter: number; packetLoss: number }
   | { type: "ping" };
 
 export type WSServerEvent =
@@ -83,21 +130,35 @@ export type WSServerEvent =
   | { type: "member_left"; serverId: string; userId: string }
   | { type: "server_updated"; serverId: string; name: string }
   | { type: "server_deleted"; serverId: string }
-  | { type: "member_role_updated"; serverId: string; userId: string; role: string }
+  | { type: "member_role_updated"; serverId: string; userId: string; role: string; roles?: string[] }
+  | { type: "verification_required"; serverId: string; channelId: string; requirement: UnmetRequirement }
+  | { type: "member_profile_updated"; serverId: string; userId: string; nickname: string | null; serverAvatar: string | null }
   | { type: "channel_update"; channelId: string; name?: string; bitrate: number | null }
+  | { type: "user_renamed"; userId: string; oldUsername: string; username: string }
   | { type: "profile_update"; userId: string; username?: string; image?: string | null; ringStyle?: RingStyle; ringSpin?: boolean; ringPatternSeed?: number | null; bannerCss?: string | null; bannerPatternSeed?: number | null }
   | { type: "voice_state"; channelId: string; participants: VoiceParticipant[] }
+  | { type: "speaking"; channelId: string; userId: string; speaking: boolean }
   | { type: "reaction_add"; messageId: string; userId: string; emoji: string }
   | { type: "reaction_remove"; messageId: string; userId: string; emoji: string }
   | { type: "message_edit"; messageId: string; content: string; editedAt: string }
   | { type: "message_delete"; messageId: string; channelId: string }
   | { type: "dm_message"; message: DMMessage }
+  | { type: "dm_request"; channel: DMChannel; message: DMMessage }
+  | { type: "dm_request_accepted"; dmChannelId: string; userId: string }
   | { type: "activity_update"; userId: string; activity: ActivityInfo | null }
-  | { type: "server_key_shared"; serverId: string; encryptedKey: string; senderId: string }
-  | { type: "server_key_requested"; serverId: string; userId: string }
+  | { type: "server_key_shared"; serverId: string; encryptedKey: string; senderId: string; deviceId?: string }
+  | { type: "server_key_requested"; serverId: string; userId: string; deviceId?: string }
+  | { type: "key_backup_updated"; version: number }
+  | { type: "key_backup_restored"; version: number; publicKey: string }
+  | { type: "session_revoked"; sessionId: string }
+  | { type: "key_changed"; userId: string; deviceId?: string; wasVerified: boolean }
   | { type: "spotify_queue_update"; sessionId: string; voiceChannelId: string; queueItem: QueueItem }
   | { type: "spotify_queue_remove"; sessionId: string; voiceChannelId: string; itemId: string }
-  | { type: "spotify_playback_sync"; sessionId: string; voiceChannelId: string; action: string; trackUri?: string; positionMs?: number; source?: string }
+  | { type: "spotify_playback_sync"; sessionId: string; voiceChannelId: string; action: string; trackUri?: string; positionMs?: number; source?: string; serverTimeMs: number }
+  | { type: "media_buffering"; sessionId: string; voiceChannelId: string; userId: string; buffering: boolean; positionMs?: number; bufferingUserIds: string[] }
+  | { type: "spotify_session_roles"; sessionId: string; voiceChannelId: string; hostUserId: string; cohostIds: string[] }
+  | { type: "lyrics_sync"; sessionId: string; voiceChannelId: string; trackUri: string; lines: LyricLine[]; positionMs: number; isPlaying: boolean }
+  | { type: "game_update"; game: Game }
   | { type: "spotify_session_ended"; sessionId: string; voiceChannelId: string }
   | { type: "soundboard_play"; channelId: string; soundId: string; audioAttachmentId: string; audioFilename: string; volume: number; username: string }
   | { type: "room_created"; channel: Channel }
@@ -105,9 +166,18 @@ export type WSServerEvent =
   | { type: "room_lock_toggled"; channelId: string; serverId: string; isLocked: boolean }
   | { type: "room_knock"; channelId: string; userId: string; username: string }
   | { type: "room_knock_accepted"; channelId: string }
+  | { type: "stage_hand_update"; channelId: string; userId: string; raised: boolean }
+  | { type: "stage_speaker_update"; channelId: string; userId: string; speaker: boolean }
+  | { type: "user_setting_update"; key: string; value: unknown | null; updatedAt: string }
   | { type: "room_invite"; channelId: string; channelName: string; inviterUsername: string; serverId: string }
   | { type: "room_force_move"; targetChannelId: string; targetChannelName: string }
   | { type: "gallery_set_updated"; setId: string }
+  | { type: "roadmap_updated"; serverId: string; itemId: string }
+  | { type: "scheduled_event_updated"; serverId: string; eventId: string; deleted: boolean }
+  | { type: "reminder_due"; reminder: MessageReminder }
+  | { type: "event_reminder"; serverId: string; eventId: string; channelId: string | null; title: string; startAt: string }
+  | { type: "access_requested"; request: AccessRequest }
+  | { type: "access_request_resolved"; requestId: string; approved: boolean }
   | { type: "error"; message: string };
 
 // --- Constants ---
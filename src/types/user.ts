@@ -1,4 +1,6 @@
-// User-related types: activity, presence, Spotify, YouTube
+// User-related types: activity, presence, Spotify, YouTube and other media
+
+import type { RingStyle } from "./server.js";
 
 export interface ActivityInfo {
   name: string;
@@ -11,6 +13,42 @@ export interface ActivityInfo {
 
 export type PresenceStatus = "online" | "idle" | "dnd" | "invisible" | "offline";
 
+/** Another user's profile card, from GET /users/:id/profile. */
+export interface UserProfile {
+  id: string;
+  username: string;
+  image: string | null;
+  status: PresenceStatus;
+  bio: string | null;
+  pronouns: string | null;
+  accentColor: string | null;
+  timezone: string | null;
+  cosmetics: {
+    ringStyle: RingStyle;
+    ringSpin: boolean;
+    ringPatternSeed: number | null;
+    bannerCss: string | null;
+    bannerPatternSeed: number | null;
+  };
+  mutualServers: { id: string; name: string }[];
+  activity: ActivityInfo | null;
+}
+
+/** Someone the user has blocked, from GET /users/me/blocks. */
+export interface BlockedUser {
+  userId: string;
+  username: string;
+  image: string | null;
+  createdAt: string;
+}
+
+/** A client setting synced between the user's devices */
+export interface SyncedSetting {
+  key: string;
+  value: unknown;
+  updatedAt: string;
+}
+
 // Spotify types
 export interface SpotifyAccount {
   linked: boolean;
@@ -26,6 +64,8 @@ export interface ListeningSession {
   isPlaying: number;
   createdAt: string;
   updatedAt: string;
+  /** "video" sessions are watch-together. */
+  sessionType?: "audio" | "video";
 }
 
 export interface QueueItem {
@@ -43,6 +83,20 @@ export interface QueueItem {
   source: string;
 }
 
+export interface HistoryItem {
+  id: string;
+  sessionId: string;
+  trackUri: string;
+  trackName: string;
+  trackArtist: string;
+  trackAlbum?: string;
+  trackImageUrl?: string;
+  trackDurationMs: number;
+  source: string;
+  playedByUserId: string;
+  playedAt: string;
+}
+
 export interface SpotifyTrack {
   uri: string;
   name: string;
@@ -51,10 +105,20 @@ export interface SpotifyTrack {
   duration_ms: number;
 }
 
-export interface YouTubeTrack {
+export interface LyricLine {
+  timeMs: number;
+  text: string;
+}
+
+export type MediaSource = "youtube" | "soundcloud" | "bandcamp";
+
+export interface MediaTrack {
   id: string;
   title: string;
   channel: string;
   thumbnail: string;
   durationMs: number;
+  source: MediaSource;
 }
+
+export type YouTubeTrack = MediaTrack;
@@ -22,12 +22,8 @@ export default defineConfig({
         target: `http://localhost:${process.env.API_PORT || "3001"}`,
         ws: true,
       },
-      "/deepfilter-cdn": {
-        target: "https://cdn.mezon.ai/AI/models/datas/noise_suppression/deepfilternet3",
-        changeOrigin: true,
-        rewrite: (path) => path.replace(/^\/deepfilter-cdn/, ""),
-        secure: true,
-      },
+      "/noise-models": `http://localhost:${process.env.API_PORT || "3001"}`,
+      "/deepfilter-cdn": `http://localhost:${process.env.API_PORT || "3001"}`,
     },
   },
   define: {